@@ -0,0 +1,153 @@
+//! Named access patterns saved per table in `access_patterns.json` in the
+//! data directory: queries like "orders by customer" with `:name` parameters
+//! filled in when they run, so the questions a single-table design answers
+//! are a keystroke away in the query view.
+//!
+//! Patterns are kept by table name alone, so the same table in another
+//! profile or on a local endpoint shares them.
+
+use std::path::PathBuf;
+
+use serde_json::{Map, Value as Json};
+
+use crate::logging;
+
+const FILE: &str = "access_patterns.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPattern {
+    pub name: String,
+    pub query: String,
+}
+
+impl AccessPattern {
+    fn to_json(&self) -> Json {
+        let mut map = Map::new();
+        map.insert("name".to_string(), Json::from(self.name.clone()));
+        map.insert("query".to_string(), Json::from(self.query.clone()));
+        Json::Object(map)
+    }
+
+    fn from_json(value: &Json) -> Option<Self> {
+        Some(Self {
+            name: value.get("name")?.as_str()?.to_string(),
+            query: value.get("query")?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// The patterns saved for `table`, in the order they were added.
+pub fn list(table: &str) -> Vec<AccessPattern> {
+    patterns(&read(), table)
+}
+
+/// Save `pattern` for `table`, replacing the one with the same name.
+pub fn save(table: &str, pattern: &AccessPattern) {
+    update(|root| {
+        let mut saved = patterns(root, table);
+        match saved.iter_mut().find(|saved| saved.name == pattern.name) {
+            Some(saved) => saved.query.clone_from(&pattern.query),
+            None => saved.push(pattern.clone()),
+        }
+        set(root, table, &saved);
+    });
+}
+
+/// Forget the pattern called `name` on `table`.
+pub fn remove(table: &str, name: &str) {
+    update(|root| {
+        let mut saved = patterns(root, table);
+        saved.retain(|saved| saved.name != name);
+        set(root, table, &saved);
+    });
+}
+
+/// Names can't be blank; they're what the picker lists.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        Err("Enter a name".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn patterns(root: &Map<String, Json>, table: &str) -> Vec<AccessPattern> {
+    root.get(table)
+        .and_then(Json::as_array)
+        .map(|saved| saved.iter().filter_map(AccessPattern::from_json).collect())
+        .unwrap_or_default()
+}
+
+fn set(root: &mut Map<String, Json>, table: &str, patterns: &[AccessPattern]) {
+    if patterns.is_empty() {
+        root.remove(table);
+    } else {
+        root.insert(
+            table.to_string(),
+            Json::Array(patterns.iter().map(AccessPattern::to_json).collect()),
+        );
+    }
+}
+
+fn update(change: impl FnOnce(&mut Map<String, Json>)) {
+    let mut root = read();
+    change(&mut root);
+    let path = path();
+    let temp = path.with_extension("json.tmp");
+    let text = serde_json::to_string_pretty(&Json::Object(root)).unwrap_or_default();
+    let result = std::fs::write(&temp, text).and_then(|()| std::fs::rename(&temp, &path));
+    if let Err(err) = result {
+        tracing::warn!(path = %path.display(), error = %err, "access_patterns_save_failed");
+    }
+}
+
+fn read() -> Map<String, Json> {
+    std::fs::read_to_string(path())
+        .ok()
+        .and_then(|text| serde_json::from_str::<Json>(&text).ok())
+        .and_then(|value| match value {
+            Json::Object(root) => Some(root),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn path() -> PathBuf {
+    logging::get_data_dir().join(FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Map, json};
+
+    use super::{AccessPattern, patterns, set};
+
+    #[test]
+    fn keeps_patterns_per_table_and_skips_malformed_ones() {
+        let mut root = Map::new();
+        let by_customer = AccessPattern {
+            name: "orders by customer".to_string(),
+            query: "pk = :customer".to_string(),
+        };
+        let in_range = AccessPattern {
+            name: "orders in range".to_string(),
+            query: "pk = :customer and sk between :from and :to".to_string(),
+        };
+        set(&mut root, "app", &[by_customer.clone(), in_range.clone()]);
+
+        assert_eq!(patterns(&root, "app"), [by_customer.clone(), in_range]);
+        assert!(patterns(&root, "other").is_empty());
+
+        root.insert(
+            "events".to_string(),
+            json!([{"name": "recent"}, {"name": "by type", "query": "type = :type"}]),
+        );
+        let events = patterns(&root, "events");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "by type");
+
+        set(&mut root, "app", &[]);
+        assert!(!root.contains_key("app"));
+        assert!(root.contains_key("events"));
+    }
+}
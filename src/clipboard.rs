@@ -0,0 +1,57 @@
+//! Copying text to the clipboard.
+//!
+//! Over SSH the native clipboard belongs to the remote machine, so the text is
+//! handed to the local terminal with an OSC 52 escape sequence instead. Locally
+//! the native clipboard is used, with OSC 52 as the fallback when there is none
+//! (e.g. no display server).
+
+use std::io::Write;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+
+/// Copy `text` to the clipboard.
+pub fn copy(text: &str) -> Result<(), String> {
+    if is_remote_session() {
+        return copy_osc52(text);
+    }
+    copy_native(text).or_else(|native_err| {
+        copy_osc52(text).map_err(|err| format!("{native_err}; OSC 52: {err}"))
+    })
+}
+
+fn is_remote_session() -> bool {
+    ["SSH_TTY", "SSH_CONNECTION", "SSH_CLIENT"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some())
+}
+
+fn copy_native(text: &str) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|err| err.to_string())?;
+    clipboard
+        .set_text(text.to_string())
+        .map_err(|err| err.to_string())
+}
+
+/// Ask the terminal to set its clipboard. The terminal doesn't acknowledge
+/// the request, so this only fails when stdout can't be written.
+fn copy_osc52(text: &str) -> Result<(), String> {
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(osc52_sequence(text).as_bytes())
+        .and_then(|()| stdout.flush())
+        .map_err(|err| err.to_string())
+}
+
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", BASE64.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::osc52_sequence;
+
+    #[test]
+    fn osc52_sequence_encodes_text_as_base64() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
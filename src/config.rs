@@ -0,0 +1,665 @@
+//! The optional JSON config file, read from `--config` or from `config.json`
+//! in the platform config directory (e.g. `~/.config/dynamate` on Linux).
+//!
+//! ```json
+//! { "max_rcu": 100, "theme": "solarized", "local": { "port": 8001 } }
+//! ```
+//!
+//! `confirm` sets how destructive actions are confirmed, per action: `none`,
+//! `confirm`, or `type_name`, e.g. `{ "confirm": { "export": "none" } }`.
+//! `environments` tags profiles and endpoint URLs as `prod`, `staging`, or
+//! `dev`, e.g. `{ "environments": { "prod-admin": "prod" } }`.
+//! `editor` is `builtin` for the editor inside the TUI, or a command to edit
+//! items in instead of `$EDITOR`.
+//! `templates` saves items to start new ones from, per table, e.g.
+//! `{ "templates": { "orders": { "open order": { "status": "OPEN" } } } }`.
+//! `entities` names the kinds of item a single-table design stores, per table,
+//! by the prefixes of their attributes, e.g.
+//! `{ "entities": { "app": { "User": { "PK": "USER#" } } } }`.
+//! `follow` names the item an attribute refers to, per table: the table it is
+//! in and its key, written with `{value}` for the attribute's value, e.g.
+//! `{ "follow": { "orders": { "customerId": { "table": "customers",
+//! "key": { "PK": "CUSTOMER#{value}" } } } } }`.
+
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use color_eyre::eyre::{Result, eyre};
+use dynamate::dynamodb::json;
+use dynamate::dynamodb::retry::RetryPolicy;
+use dynamate::throttle::Budget;
+
+use crate::environment::{Environment, Tags};
+use crate::safety::{Action, Confirmation, SafetyPolicy};
+
+const FILE_NAME: &str = "config.json";
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Config {
+    /// Read capacity units per second that scans, exports, and purges may use.
+    pub max_rcu: Option<f64>,
+    /// Items per second that scans, exports, and purges may read.
+    pub max_items_per_second: Option<f64>,
+    /// A built-in theme name, `auto`, or the path of a palette file.
+    pub theme: Option<String>,
+    /// Capture the mouse in the TUI.
+    pub mouse: bool,
+    /// Where the write audit log goes.
+    pub audit_log: AuditLogSetting,
+    /// Regions the table list can show at once (`R`).
+    pub regions: Vec<String>,
+    /// Stop loading a query's results after this many items.
+    pub max_items: Option<usize>,
+    /// Stop loading a query's results after this many pages.
+    pub max_pages: Option<usize>,
+    /// How throttled requests are retried.
+    pub retry: RetryPolicy,
+    /// How destructive actions are confirmed.
+    pub safety: SafetyPolicy,
+    /// The environment of each tagged profile and endpoint URL.
+    pub environments: Tags,
+    /// `builtin`, or the command items are edited with.
+    pub editor: Option<String>,
+    /// Items new ones can start from (`n` in a query view).
+    pub templates: Vec<SavedTemplate>,
+    /// Which kind of item each row is, by key prefix (`V` in a query view).
+    pub entities: Vec<EntityRule>,
+    /// The items attributes refer to (`⏎` in an item's tree).
+    pub follow: Vec<FollowRule>,
+    /// Settings for `dynamate local`.
+    pub local: LocalConfig,
+}
+
+/// `audit_log` in the config file: `false` turns the log off and a path moves
+/// it out of the data directory.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum AuditLogSetting {
+    #[default]
+    Default,
+    Off,
+    Path(PathBuf),
+}
+
+/// An entity under `entities`: items whose string attributes begin with
+/// every one of the prefixes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityRule {
+    pub table: String,
+    pub name: String,
+    /// Attribute names and the prefix each value begins with.
+    pub prefixes: Vec<(String, String)>,
+}
+
+/// A reference under `follow`: `attribute` of items in `table` holds the key
+/// of an item in `target`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowRule {
+    pub table: String,
+    /// The attribute's document path, e.g. `customerId` or `order.customer`.
+    pub attribute: String,
+    pub target: String,
+    /// The target's key attributes and their values, with `{value}` for the
+    /// followed attribute's value and `{name}` for another attribute's.
+    pub key: Vec<(String, String)>,
+}
+
+/// A named item under `templates`, in plain JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedTemplate {
+    pub table: String,
+    pub name: String,
+    pub item: HashMap<String, AttributeValue>,
+}
+
+/// How `dynamate local` reaches DynamoDB Local. Unset fields fall back to the
+/// command's defaults.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LocalConfig {
+    /// An already running DynamoDB Local; no container is managed.
+    pub endpoint_url: Option<String>,
+    /// Host port the managed container listens on.
+    pub port: Option<u16>,
+    pub image: Option<String>,
+    pub container: Option<String>,
+}
+
+impl Config {
+    /// The read budget; a capacity limit wins over an item limit.
+    pub fn budget(&self) -> Option<Budget> {
+        self.max_rcu
+            .map(Budget::CapacityUnits)
+            .or(self.max_items_per_second.map(Budget::Items))
+    }
+}
+
+/// Load the config file. A missing default file is an empty config; a missing
+/// `--config` file is an error.
+pub fn load(path: Option<&str>) -> Result<Config> {
+    let (path, explicit) = match path {
+        Some(path) => (PathBuf::from(path), true),
+        None => match crate::logging::project_directory() {
+            Some(dirs) => (dirs.config_dir().join(FILE_NAME), false),
+            None => return Ok(Config::default()),
+        },
+    };
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if !explicit && err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Config::default());
+        }
+        Err(err) => return Err(eyre!("Failed to read {}: {err}", path.display())),
+    };
+    parse(&text).map_err(|err| eyre!("Invalid config file {}: {err}", path.display()))
+}
+
+fn parse(text: &str) -> Result<Config, String> {
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| "expected a JSON object".to_string())?;
+    Ok(Config {
+        max_rcu: positive_number(object, "max_rcu")?,
+        max_items_per_second: positive_number(object, "max_items_per_second")?,
+        theme: match object.get("theme") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(theme)) if !theme.trim().is_empty() => {
+                Some(theme.trim().to_string())
+            }
+            Some(_) => return Err("theme must be a non-empty string".to_string()),
+        },
+        mouse: match object.get("mouse") {
+            None | Some(serde_json::Value::Null) => false,
+            Some(serde_json::Value::Bool(mouse)) => *mouse,
+            Some(_) => return Err("mouse must be true or false".to_string()),
+        },
+        audit_log: match object.get("audit_log") {
+            None | Some(serde_json::Value::Null | serde_json::Value::Bool(true)) => {
+                AuditLogSetting::Default
+            }
+            Some(serde_json::Value::Bool(false)) => AuditLogSetting::Off,
+            Some(serde_json::Value::String(path)) if !path.trim().is_empty() => {
+                AuditLogSetting::Path(PathBuf::from(path.trim()))
+            }
+            Some(_) => return Err("audit_log must be a path or false".to_string()),
+        },
+        regions: regions(object.get("regions"))?,
+        max_items: positive_integer(object, "max_items")?,
+        max_pages: positive_integer(object, "max_pages")?,
+        retry: retry_policy(object.get("retry"))?,
+        safety: safety_policy(object.get("confirm"))?,
+        environments: environments(object.get("environments"))?,
+        editor: match object.get("editor") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(editor)) if !editor.trim().is_empty() => {
+                Some(editor.trim().to_string())
+            }
+            Some(_) => return Err("editor must be a non-empty string".to_string()),
+        },
+        templates: templates(object.get("templates"))?,
+        entities: entities(object.get("entities"))?,
+        follow: follow(object.get("follow"))?,
+        local: local_config(object.get("local"))?,
+    })
+}
+
+fn regions(value: Option<&serde_json::Value>) -> Result<Vec<String>, String> {
+    let error = || "regions must be a list of region names".to_string();
+    match value {
+        None | Some(serde_json::Value::Null) => Ok(Vec::new()),
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .map(|value| match value {
+                serde_json::Value::String(region) if !region.trim().is_empty() => {
+                    Ok(region.trim().to_string())
+                }
+                _ => Err(error()),
+            })
+            .collect(),
+        Some(_) => Err(error()),
+    }
+}
+
+/// `retry`: `max_attempts`, and `base_delay_ms` and `max_delay_ms` for the
+/// backoff. Unset fields keep the defaults.
+fn retry_policy(value: Option<&serde_json::Value>) -> Result<RetryPolicy, String> {
+    let defaults = RetryPolicy::default();
+    let object = match value {
+        None | Some(serde_json::Value::Null) => return Ok(defaults),
+        Some(serde_json::Value::Object(object)) => object,
+        Some(_) => return Err("retry must be an object".to_string()),
+    };
+    let number = |key: &str| {
+        positive_integer(object, key).map_err(|_| format!("retry.{key} must be a positive integer"))
+    };
+    let millis = |key: &str| number(key).map(|ms| ms.map(|ms| Duration::from_millis(ms as u64)));
+    let policy = RetryPolicy {
+        max_attempts: match number("max_attempts")? {
+            Some(attempts) => u32::try_from(attempts)
+                .map_err(|_| "retry.max_attempts is too large".to_string())?,
+            None => defaults.max_attempts,
+        },
+        base_delay: millis("base_delay_ms")?.unwrap_or(defaults.base_delay),
+        max_delay: millis("max_delay_ms")?.unwrap_or(defaults.max_delay),
+    };
+    if policy.max_delay < policy.base_delay {
+        return Err("retry.max_delay_ms must be at least retry.base_delay_ms".to_string());
+    }
+    Ok(policy)
+}
+
+/// `confirm`: a tier for each action to change from its default.
+fn safety_policy(value: Option<&serde_json::Value>) -> Result<SafetyPolicy, String> {
+    let object = match value {
+        None | Some(serde_json::Value::Null) => return Ok(SafetyPolicy::default()),
+        Some(serde_json::Value::Object(object)) => object,
+        Some(_) => return Err("confirm must be an object".to_string()),
+    };
+    object
+        .iter()
+        .try_fold(SafetyPolicy::default(), |policy, (key, value)| {
+            let action = Action::ALL
+                .into_iter()
+                .find(|action| action.key() == key)
+                .ok_or_else(|| format!("confirm.{key} is not a known action"))?;
+            let confirmation = value
+                .as_str()
+                .and_then(Confirmation::parse)
+                .ok_or_else(|| format!("confirm.{key} must be none, confirm, or type_name"))?;
+            Ok(policy.with(action, confirmation))
+        })
+}
+
+fn environments(value: Option<&serde_json::Value>) -> Result<Tags, String> {
+    let object = match value {
+        None | Some(serde_json::Value::Null) => return Ok(Tags::default()),
+        Some(serde_json::Value::Object(object)) => object,
+        Some(_) => return Err("environments must be an object".to_string()),
+    };
+    object
+        .iter()
+        .map(|(name, value)| {
+            value
+                .as_str()
+                .and_then(Environment::parse)
+                .map(|environment| (name.clone(), environment))
+                .ok_or_else(|| format!("environments.{name} must be prod, staging, or dev"))
+        })
+        .collect::<Result<_, _>>()
+        .map(Tags::new)
+}
+
+/// `templates`: named items for each table.
+fn templates(value: Option<&serde_json::Value>) -> Result<Vec<SavedTemplate>, String> {
+    let tables = match value {
+        None | Some(serde_json::Value::Null) => return Ok(Vec::new()),
+        Some(serde_json::Value::Object(tables)) => tables,
+        Some(_) => return Err("templates must be an object".to_string()),
+    };
+    let mut templates = Vec::new();
+    for (table, named) in tables {
+        let named = named
+            .as_object()
+            .ok_or_else(|| format!("templates.{table} must be an object of named items"))?;
+        for (name, item) in named {
+            if !item.is_object() {
+                return Err(format!("templates.{table}.{name} must be an object"));
+            }
+            let item =
+                json::from_json(item).map_err(|err| format!("templates.{table}.{name}: {err}"))?;
+            templates.push(SavedTemplate {
+                table: table.clone(),
+                name: name.clone(),
+                item,
+            });
+        }
+    }
+    Ok(templates)
+}
+
+/// `entities`: for each table, entity names and the attribute prefixes that
+/// make an item one.
+fn entities(value: Option<&serde_json::Value>) -> Result<Vec<EntityRule>, String> {
+    let tables = match value {
+        None | Some(serde_json::Value::Null) => return Ok(Vec::new()),
+        Some(serde_json::Value::Object(tables)) => tables,
+        Some(_) => return Err("entities must be an object".to_string()),
+    };
+    let mut rules = Vec::new();
+    for (table, named) in tables {
+        let named = named
+            .as_object()
+            .ok_or_else(|| format!("entities.{table} must be an object of named entities"))?;
+        for (name, prefixes) in named {
+            let error = || format!("entities.{table}.{name} must map attribute names to prefixes");
+            let prefixes = prefixes
+                .as_object()
+                .filter(|prefixes| !prefixes.is_empty())
+                .ok_or_else(error)?
+                .iter()
+                .map(|(attribute, prefix)| {
+                    prefix
+                        .as_str()
+                        .map(|prefix| (attribute.clone(), prefix.to_string()))
+                        .ok_or_else(error)
+                })
+                .collect::<Result<_, _>>()?;
+            rules.push(EntityRule {
+                table: table.clone(),
+                name: name.clone(),
+                prefixes,
+            });
+        }
+    }
+    Ok(rules)
+}
+
+/// `follow`: for each table, the attributes that refer to other items, with
+/// the table those are in and a template of their key.
+fn follow(value: Option<&serde_json::Value>) -> Result<Vec<FollowRule>, String> {
+    let tables = match value {
+        None | Some(serde_json::Value::Null) => return Ok(Vec::new()),
+        Some(serde_json::Value::Object(tables)) => tables,
+        Some(_) => return Err("follow must be an object".to_string()),
+    };
+    let mut rules = Vec::new();
+    for (table, attributes) in tables {
+        let attributes = attributes
+            .as_object()
+            .ok_or_else(|| format!("follow.{table} must be an object of attributes"))?;
+        for (attribute, rule) in attributes {
+            let error = || {
+                format!(
+                    "follow.{table}.{attribute} must have a \"table\" and a \"key\" of attribute templates"
+                )
+            };
+            let target = rule
+                .get("table")
+                .and_then(serde_json::Value::as_str)
+                .filter(|target| !target.trim().is_empty())
+                .ok_or_else(error)?;
+            let key = rule
+                .get("key")
+                .and_then(serde_json::Value::as_object)
+                .filter(|key| !key.is_empty())
+                .ok_or_else(error)?
+                .iter()
+                .map(|(name, template)| {
+                    template
+                        .as_str()
+                        .map(|template| (name.clone(), template.to_string()))
+                        .ok_or_else(error)
+                })
+                .collect::<Result<_, _>>()?;
+            rules.push(FollowRule {
+                table: table.clone(),
+                attribute: attribute.clone(),
+                target: target.trim().to_string(),
+                key,
+            });
+        }
+    }
+    Ok(rules)
+}
+
+fn local_config(value: Option<&serde_json::Value>) -> Result<LocalConfig, String> {
+    let object = match value {
+        None | Some(serde_json::Value::Null) => return Ok(LocalConfig::default()),
+        Some(serde_json::Value::Object(object)) => object,
+        Some(_) => return Err("local must be an object".to_string()),
+    };
+    let string = |key: &str| match object.get(key) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(text)) if !text.trim().is_empty() => {
+            Ok(Some(text.trim().to_string()))
+        }
+        Some(_) => Err(format!("local.{key} must be a non-empty string")),
+    };
+    let port = match object.get("port") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(value) => Some(
+            value
+                .as_u64()
+                .and_then(|port| u16::try_from(port).ok())
+                .filter(|port| *port > 0)
+                .ok_or_else(|| "local.port must be a port number".to_string())?,
+        ),
+    };
+    Ok(LocalConfig {
+        endpoint_url: string("endpoint_url")?,
+        port,
+        image: string("image")?,
+        container: string("container")?,
+    })
+}
+
+fn positive_number(
+    object: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Result<Option<f64>, String> {
+    match object.get(key) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => match value.as_f64() {
+            Some(number) if number > 0.0 => Ok(Some(number)),
+            _ => Err(format!("{key} must be a positive number")),
+        },
+    }
+}
+
+fn positive_integer(
+    object: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Result<Option<usize>, String> {
+    match object.get(key) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .and_then(|number| usize::try_from(number).ok())
+            .filter(|number| *number > 0)
+            .map(Some)
+            .ok_or_else(|| format!("{key} must be a positive integer")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::types::AttributeValue;
+    use dynamate::dynamodb::retry::RetryPolicy;
+    use dynamate::throttle::Budget;
+
+    use std::{path::PathBuf, time::Duration};
+
+    use super::{AuditLogSetting, Config, LocalConfig, parse};
+    use crate::environment::Environment;
+    use crate::safety::{Action, Confirmation};
+
+    #[test]
+    fn parses_limits_and_prefers_capacity() {
+        let config = parse(r#"{"max_rcu": 50, "max_items_per_second": 200}"#).unwrap();
+        assert_eq!(config.budget(), Some(Budget::CapacityUnits(50.0)));
+        let config = parse(r#"{"max_items_per_second": 200}"#).unwrap();
+        assert_eq!(config.budget(), Some(Budget::Items(200.0)));
+        assert_eq!(parse("{}").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn rejects_bad_limits() {
+        assert!(parse(r#"{"max_rcu": 0}"#).is_err());
+        assert!(parse(r#"{"max_rcu": "fast"}"#).is_err());
+        assert!(parse("[]").is_err());
+    }
+
+    #[test]
+    fn parses_query_limits() {
+        let config = parse(r#"{"max_items": 1000, "max_pages": 20}"#).unwrap();
+        assert_eq!(config.max_items, Some(1000));
+        assert_eq!(config.max_pages, Some(20));
+        assert!(parse(r#"{"max_items": 0}"#).is_err());
+        assert!(parse(r#"{"max_pages": 2.5}"#).is_err());
+    }
+
+    #[test]
+    fn parses_local_settings() {
+        let config = parse(r#"{"local": {"port": 8001, "container": "ddb"}}"#).unwrap();
+        assert_eq!(
+            config.local,
+            LocalConfig {
+                port: Some(8001),
+                container: Some("ddb".to_string()),
+                ..LocalConfig::default()
+            }
+        );
+        assert!(parse(r#"{"local": {"port": 70000}}"#).is_err());
+        assert!(parse(r#"{"local": {"endpoint_url": ""}}"#).is_err());
+        assert!(parse(r#"{"local": true}"#).is_err());
+    }
+
+    #[test]
+    fn parses_retry_policy() {
+        let config = parse(r#"{"retry": {"max_attempts": 3, "base_delay_ms": 50}}"#).unwrap();
+        assert_eq!(config.retry.max_attempts, 3);
+        assert_eq!(config.retry.base_delay, Duration::from_millis(50));
+        assert_eq!(config.retry.max_delay, RetryPolicy::default().max_delay);
+        assert!(parse(r#"{"retry": {"max_attempts": 0}}"#).is_err());
+        assert!(parse(r#"{"retry": {"base_delay_ms": 900, "max_delay_ms": 100}}"#).is_err());
+        assert!(parse(r#"{"retry": 3}"#).is_err());
+    }
+
+    #[test]
+    fn parses_theme() {
+        let config = parse(r#"{"theme": " light "}"#).unwrap();
+        assert_eq!(config.theme.as_deref(), Some("light"));
+        assert!(parse(r#"{"theme": 1}"#).is_err());
+    }
+
+    #[test]
+    fn parses_mouse() {
+        assert!(parse(r#"{"mouse": true}"#).unwrap().mouse);
+        assert!(!parse("{}").unwrap().mouse);
+        assert!(parse(r#"{"mouse": "yes"}"#).is_err());
+    }
+
+    #[test]
+    fn parses_regions() {
+        assert_eq!(
+            parse(r#"{"regions": ["us-east-1", " eu-west-1 "]}"#)
+                .unwrap()
+                .regions,
+            ["us-east-1", "eu-west-1"]
+        );
+        assert!(parse("{}").unwrap().regions.is_empty());
+        assert!(parse(r#"{"regions": "us-east-1"}"#).is_err());
+        assert!(parse(r#"{"regions": [""]}"#).is_err());
+    }
+
+    #[test]
+    fn parses_audit_log() {
+        assert_eq!(parse("{}").unwrap().audit_log, AuditLogSetting::Default);
+        assert_eq!(
+            parse(r#"{"audit_log": false}"#).unwrap().audit_log,
+            AuditLogSetting::Off
+        );
+        assert_eq!(
+            parse(r#"{"audit_log": "/tmp/audit.ndjson"}"#)
+                .unwrap()
+                .audit_log,
+            AuditLogSetting::Path(PathBuf::from("/tmp/audit.ndjson"))
+        );
+        assert!(parse(r#"{"audit_log": 1}"#).is_err());
+    }
+
+    #[test]
+    fn parses_confirmation_tiers() {
+        let config =
+            parse(r#"{"confirm": {"export": "none", "delete_items": "type_name"}}"#).unwrap();
+        assert_eq!(
+            config.safety.confirmation(Action::Export),
+            Confirmation::None
+        );
+        assert_eq!(
+            config.safety.confirmation(Action::DeleteItems),
+            Confirmation::TypeName
+        );
+        assert_eq!(
+            config.safety.confirmation(Action::DeleteTable),
+            Confirmation::TypeName
+        );
+        assert!(parse(r#"{"confirm": {"truncate": "none"}}"#).is_err());
+        assert!(parse(r#"{"confirm": {"purge": "never"}}"#).is_err());
+        assert!(parse(r#"{"confirm": "none"}"#).is_err());
+    }
+
+    #[test]
+    fn parses_environment_tags() {
+        let config = parse(r#"{"environments": {"prod-admin": "prod"}}"#).unwrap();
+        assert_eq!(
+            config.environments.lookup(None, "prod-admin"),
+            Some(Environment::Prod)
+        );
+        assert!(parse(r#"{"environments": {"prod-admin": "live"}}"#).is_err());
+        assert!(parse(r#"{"environments": ["prod"]}"#).is_err());
+    }
+
+    #[test]
+    fn parses_item_templates() {
+        let config =
+            parse(r#"{"templates": {"orders": {"open order": {"status": "OPEN", "total": 0}}}}"#)
+                .unwrap();
+        let [template] = config.templates.as_slice() else {
+            panic!("expected one template, got {:?}", config.templates);
+        };
+        assert_eq!(template.table, "orders");
+        assert_eq!(template.name, "open order");
+        assert_eq!(
+            template.item.get("status"),
+            Some(&AttributeValue::S("OPEN".to_string()))
+        );
+        assert!(parse(r#"{"templates": {"orders": {"open order": []}}}"#).is_err());
+        assert!(parse(r#"{"templates": {"orders": []}}"#).is_err());
+    }
+
+    #[test]
+    fn parses_entity_rules() {
+        let config = parse(
+            r#"{"entities": {"app": {"User": {"PK": "USER#"}, "Order": {"PK": "USER#", "SK": "ORDER#"}}}}"#,
+        )
+        .unwrap();
+        let names: Vec<_> = config
+            .entities
+            .iter()
+            .map(|rule| rule.name.as_str())
+            .collect();
+        assert_eq!(names, ["User", "Order"]);
+        assert_eq!(config.entities[1].table, "app");
+        assert_eq!(config.entities[1].prefixes.len(), 2);
+        assert!(parse(r#"{"entities": {"app": {"User": {}}}}"#).is_err());
+        assert!(parse(r#"{"entities": {"app": {"User": {"PK": 1}}}}"#).is_err());
+        assert!(parse(r#"{"entities": {"app": ["User"]}}"#).is_err());
+    }
+
+    #[test]
+    fn parses_follow_rules() {
+        let config = parse(
+            r#"{"follow": {"orders": {"customerId": {"table": "customers", "key": {"PK": "CUSTOMER#{value}", "SK": "PROFILE"}}}}}"#,
+        )
+        .unwrap();
+        let [rule] = config.follow.as_slice() else {
+            panic!("expected one rule, got {:?}", config.follow);
+        };
+        assert_eq!(rule.table, "orders");
+        assert_eq!(rule.attribute, "customerId");
+        assert_eq!(rule.target, "customers");
+        assert_eq!(
+            rule.key,
+            [
+                ("PK".to_string(), "CUSTOMER#{value}".to_string()),
+                ("SK".to_string(), "PROFILE".to_string())
+            ]
+        );
+        assert!(
+            parse(r#"{"follow": {"orders": {"customerId": {"table": "customers"}}}}"#).is_err()
+        );
+        assert!(
+            parse(r#"{"follow": {"orders": {"customerId": {"key": {"PK": "{value}"}}}}}"#).is_err()
+        );
+    }
+}
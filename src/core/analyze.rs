@@ -0,0 +1,801 @@
+//! Profiling a collection's items, for the table picker's analyze view.
+//!
+//! [`analyze_items`] reads a sample (or all) of a collection and feeds each
+//! item to an [`Analyzer`], which tallies how often every top-level attribute
+//! appears and with which types, item sizes, how many distinct values each
+//! key field takes and which of them (and which prefixes, e.g. `user#`) hold
+//! most of the items. [`Analyzer::finish`] turns the tallies into a
+//! [`TableReport`] with the heuristics the view shows: key cardinality, hot
+//! keys and prefixes, and attributes that would make sparse indexes. It also
+//! breaks the items down by partition key value and prefix, with how many
+//! items and bytes each partition holds, to find hot partitions.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use serde_json::{Value as Json, json};
+
+use super::datastore::Datastore;
+use super::error::Result;
+use super::query::{Cursor, Page, QueryPlan};
+use super::schema::{KeyRole, KeySchema};
+use super::size::{MAX_ITEM_SIZE_BYTES, estimate_item_size_bytes};
+use super::value::{Item, Value};
+
+/// Items fetched per page.
+const PAGE_SIZE: u32 = 100;
+/// Distinct values remembered per key field; past this, counts are lower
+/// bounds and new values are no longer tracked.
+const MAX_TRACKED_VALUES: usize = 100_000;
+/// Entries listed per hot-key and hot-prefix ranking.
+const TOP_ENTRIES: usize = 5;
+/// Share of the items above which one key value or prefix counts as hot.
+const HOT_SHARE: f64 = 0.1;
+/// Attributes present in fewer items than this share are sparse.
+const SPARSE_SHARE: f64 = 0.5;
+/// Characters that end a key prefix such as `user#` or `order:`.
+const PREFIX_SEPARATORS: [char; 4] = ['#', ':', '|', '/'];
+
+/// How much of a collection to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalyzeOptions {
+    /// Stop after this many items; `None` reads everything.
+    pub sample: Option<u64>,
+}
+
+/// How one top-level attribute shows up across the items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeStats {
+    pub name: String,
+    /// Items that have the attribute.
+    pub count: u64,
+    /// `count` as a share of the items analyzed.
+    pub frequency: f64,
+    /// Type tags (`S`, `N`, `M`, …) and how many items had each, most
+    /// common first.
+    pub types: Vec<(String, u64)>,
+}
+
+/// Percentiles of the estimated item sizes, in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SizeStats {
+    pub average: f64,
+    pub p50: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub max: usize,
+    /// Items within 10% of the item size limit.
+    pub near_limit: u64,
+}
+
+/// A value or prefix and how many items carry it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    pub value: String,
+    pub count: u64,
+    /// `count` as a share of the items analyzed.
+    pub share: f64,
+    /// Above [`HOT_SHARE`] of the items.
+    pub hot: bool,
+}
+
+/// How spread out a key field's values are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinality {
+    /// Nearly every item has its own value.
+    High,
+    Medium,
+    /// A few values cover most items; a partition key like this concentrates
+    /// traffic.
+    Low,
+}
+
+impl Cardinality {
+    fn from_ratio(ratio: f64) -> Self {
+        if ratio >= 0.5 {
+            Cardinality::High
+        } else if ratio >= 0.05 {
+            Cardinality::Medium
+        } else {
+            Cardinality::Low
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Cardinality::High => "high",
+            Cardinality::Medium => "medium",
+            Cardinality::Low => "low",
+        }
+    }
+}
+
+/// Distribution of one key field's values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyStats {
+    pub name: String,
+    pub role: KeyRole,
+    /// Distinct values seen; a lower bound when `capped`.
+    pub distinct: u64,
+    pub capped: bool,
+    pub cardinality: Cardinality,
+    /// The values most items share, most common first.
+    pub top_values: Vec<Share>,
+    /// The most common prefixes, most common first.
+    pub top_prefixes: Vec<Share>,
+}
+
+/// An attribute that only some items have, which a secondary index could be
+/// keyed on to index just those items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseCandidate {
+    pub name: String,
+    /// The attribute's only type tag.
+    pub ty: String,
+    pub count: u64,
+    pub frequency: f64,
+}
+
+/// Everything an analysis found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableReport {
+    pub items: u64,
+    /// Whether the analysis read a sample rather than every item.
+    pub sampled: bool,
+    /// Stopped through the cancel flag before the sample was complete.
+    pub cancelled: bool,
+    pub elapsed: Duration,
+    /// Most frequent first.
+    pub attributes: Vec<AttributeStats>,
+    pub sizes: SizeStats,
+    pub keys: Vec<KeyStats>,
+    /// Most frequent first.
+    pub sparse_candidates: Vec<SparseCandidate>,
+    /// `None` when the collection has no partition key.
+    pub partitions: Option<PartitionReport>,
+}
+
+/// How many items, and how much data, one partition holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionStats {
+    /// The partition key value, or its prefix.
+    pub value: String,
+    pub items: u64,
+    pub bytes: u64,
+    /// Of the items with a partition key.
+    pub item_share: f64,
+    pub byte_share: f64,
+}
+
+impl PartitionStats {
+    /// Whether the partition holds an outsized share of the items or bytes.
+    pub fn is_hot(&self) -> bool {
+        self.items > 1 && (self.item_share > HOT_SHARE || self.byte_share > HOT_SHARE)
+    }
+}
+
+/// The items grouped by partition key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionReport {
+    pub key: String,
+    /// Every partition key value seen, most items first.
+    pub values: Vec<PartitionStats>,
+    /// Every prefix seen (see [`KeyStats::top_prefixes`]), most items first.
+    pub prefixes: Vec<PartitionStats>,
+    /// More values were seen than are tracked, so some are missing.
+    pub capped: bool,
+}
+
+impl PartitionReport {
+    /// How many times more items the busiest partition holds than the mean
+    /// partition; 1 when evenly spread.
+    pub fn item_skew(&self) -> f64 {
+        skew(self.values.iter().map(|partition| partition.items))
+    }
+
+    /// Like [`Self::item_skew`], for bytes.
+    pub fn size_skew(&self) -> f64 {
+        skew(self.values.iter().map(|partition| partition.bytes))
+    }
+}
+
+impl TableReport {
+    /// The report as JSON, for export.
+    pub fn to_json(&self, name: &str) -> Json {
+        let shares = |shares: &[Share]| -> Vec<Json> {
+            shares
+                .iter()
+                .map(|share| {
+                    json!({
+                        "value": share.value,
+                        "count": share.count,
+                        "share": share.share,
+                        "hot": share.hot,
+                    })
+                })
+                .collect()
+        };
+        json!({
+            "table": name,
+            "items": self.items,
+            "sampled": self.sampled,
+            "cancelled": self.cancelled,
+            "elapsed_ms": u64::try_from(self.elapsed.as_millis()).unwrap_or(u64::MAX),
+            "attributes": self.attributes.iter().map(|attribute| json!({
+                "name": attribute.name,
+                "count": attribute.count,
+                "frequency": attribute.frequency,
+                "types": attribute
+                    .types
+                    .iter()
+                    .map(|(ty, count)| (ty.clone(), json!(count)))
+                    .collect::<serde_json::Map<_, _>>(),
+            })).collect::<Vec<_>>(),
+            "item_size_bytes": {
+                "average": self.sizes.average,
+                "p50": self.sizes.p50,
+                "p90": self.sizes.p90,
+                "p99": self.sizes.p99,
+                "max": self.sizes.max,
+                "near_limit": self.sizes.near_limit,
+            },
+            "keys": self.keys.iter().map(|key| json!({
+                "name": key.name,
+                "role": match key.role {
+                    KeyRole::Partition => "partition",
+                    KeyRole::Sort => "sort",
+                },
+                "distinct": key.distinct,
+                "capped": key.capped,
+                "cardinality": key.cardinality.label(),
+                "top_values": shares(&key.top_values),
+                "top_prefixes": shares(&key.top_prefixes),
+            })).collect::<Vec<_>>(),
+            "sparse_index_candidates": self.sparse_candidates.iter().map(|candidate| json!({
+                "name": candidate.name,
+                "type": candidate.ty,
+                "count": candidate.count,
+                "frequency": candidate.frequency,
+            })).collect::<Vec<_>>(),
+            "partitions": self.partitions.as_ref().map(|partitions| json!({
+                "key": partitions.key,
+                "distinct": partitions.values.len(),
+                "capped": partitions.capped,
+                "item_skew": partitions.item_skew(),
+                "size_skew": partitions.size_skew(),
+                "top_values": partitions
+                    .values
+                    .iter()
+                    .take(TOP_ENTRIES)
+                    .map(partition_json)
+                    .collect::<Vec<_>>(),
+            })),
+        })
+    }
+}
+
+fn partition_json(partition: &PartitionStats) -> Json {
+    json!({
+        "value": partition.value,
+        "items": partition.items,
+        "bytes": partition.bytes,
+        "item_share": partition.item_share,
+        "byte_share": partition.byte_share,
+    })
+}
+
+/// The largest of `values` over their mean.
+fn skew(values: impl Iterator<Item = u64> + Clone) -> f64 {
+    let count = values.clone().count();
+    let total: u64 = values.clone().sum();
+    let max = values.max().unwrap_or(0);
+    if total == 0 {
+        1.0
+    } else {
+        max as f64 * count as f64 / total as f64
+    }
+}
+
+/// Items and bytes per partition key value and prefix.
+#[derive(Debug, Default)]
+struct PartitionTally {
+    key: String,
+    values: HashMap<String, (u64, u64)>,
+    prefixes: HashMap<String, (u64, u64)>,
+    capped: bool,
+}
+
+impl PartitionTally {
+    fn add(&mut self, text: &str, bytes: u64) {
+        if let Some(prefix) = key_prefix(text) {
+            let entry = self.prefixes.entry(prefix.to_string()).or_default();
+            entry.0 += 1;
+            entry.1 += bytes;
+        }
+        if let Some(entry) = self.values.get_mut(text) {
+            entry.0 += 1;
+            entry.1 += bytes;
+        } else if self.values.len() < MAX_TRACKED_VALUES {
+            self.values.insert(text.to_string(), (1, bytes));
+        } else {
+            self.capped = true;
+        }
+    }
+
+    fn finish(self) -> PartitionReport {
+        let ranked = |tallies: HashMap<String, (u64, u64)>| {
+            let items: u64 = tallies.values().map(|(items, _)| items).sum();
+            let bytes: u64 = tallies.values().map(|(_, bytes)| bytes).sum();
+            let share = |part: u64, total: u64| {
+                if total == 0 {
+                    0.0
+                } else {
+                    part as f64 / total as f64
+                }
+            };
+            let mut partitions: Vec<PartitionStats> = tallies
+                .into_iter()
+                .map(|(value, (count, size))| PartitionStats {
+                    value,
+                    items: count,
+                    bytes: size,
+                    item_share: share(count, items),
+                    byte_share: share(size, bytes),
+                })
+                .collect();
+            partitions.sort_by(|a, b| b.items.cmp(&a.items).then_with(|| a.value.cmp(&b.value)));
+            partitions
+        };
+        PartitionReport {
+            key: self.key,
+            values: ranked(self.values),
+            prefixes: ranked(self.prefixes),
+            capped: self.capped,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct AttributeTally {
+    count: u64,
+    types: BTreeMap<&'static str, u64>,
+}
+
+#[derive(Debug)]
+struct KeyTally {
+    name: String,
+    role: KeyRole,
+    values: HashMap<String, u64>,
+    prefixes: HashMap<String, u64>,
+    capped: bool,
+}
+
+/// Accumulates item statistics one item at a time.
+#[derive(Debug)]
+pub struct Analyzer {
+    items: u64,
+    attributes: HashMap<String, AttributeTally>,
+    sizes: Vec<usize>,
+    keys: Vec<KeyTally>,
+    partitions: Option<PartitionTally>,
+}
+
+impl Analyzer {
+    pub fn new(key: &KeySchema) -> Self {
+        Self {
+            items: 0,
+            attributes: HashMap::new(),
+            sizes: Vec::new(),
+            keys: key
+                .fields
+                .iter()
+                .map(|field| KeyTally {
+                    name: field.name.clone(),
+                    role: field.role,
+                    values: HashMap::new(),
+                    prefixes: HashMap::new(),
+                    capped: false,
+                })
+                .collect(),
+            partitions: key
+                .fields
+                .iter()
+                .find(|field| field.role == KeyRole::Partition)
+                .map(|field| PartitionTally {
+                    key: field.name.clone(),
+                    ..PartitionTally::default()
+                }),
+        }
+    }
+
+    pub fn items(&self) -> u64 {
+        self.items
+    }
+
+    pub fn add(&mut self, item: &Item) {
+        self.items += 1;
+        for (name, value) in item {
+            let tally = self.attributes.entry(name.clone()).or_default();
+            tally.count += 1;
+            *tally.types.entry(type_tag(value)).or_default() += 1;
+        }
+        let size = estimate_item_size_bytes(item);
+        self.sizes.push(size);
+        if let Some(partitions) = self.partitions.as_mut()
+            && let Some(text) = item.get(&partitions.key).and_then(key_text)
+        {
+            partitions.add(&text, size as u64);
+        }
+        for key in &mut self.keys {
+            let Some(text) = item.get(&key.name).and_then(key_text) else {
+                continue;
+            };
+            if let Some(prefix) = key_prefix(&text) {
+                *key.prefixes.entry(prefix.to_string()).or_default() += 1;
+            }
+            if let Some(count) = key.values.get_mut(&text) {
+                *count += 1;
+            } else if key.values.len() < MAX_TRACKED_VALUES {
+                key.values.insert(text, 1);
+            } else {
+                key.capped = true;
+            }
+        }
+    }
+
+    pub fn finish(self, sampled: bool, cancelled: bool, elapsed: Duration) -> TableReport {
+        let items = self.items;
+        let share_of = |count: u64| {
+            if items == 0 {
+                0.0
+            } else {
+                count as f64 / items as f64
+            }
+        };
+
+        let key_names: Vec<&str> = self.keys.iter().map(|key| key.name.as_str()).collect();
+        let mut attributes: Vec<AttributeStats> = self
+            .attributes
+            .into_iter()
+            .map(|(name, tally)| {
+                let mut types: Vec<(String, u64)> = tally
+                    .types
+                    .into_iter()
+                    .map(|(ty, count)| (ty.to_string(), count))
+                    .collect();
+                types.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                AttributeStats {
+                    frequency: share_of(tally.count),
+                    name,
+                    count: tally.count,
+                    types,
+                }
+            })
+            .collect();
+        attributes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+        let sparse_candidates = attributes
+            .iter()
+            .filter(|attribute| {
+                attribute.frequency < SPARSE_SHARE
+                    && !key_names.contains(&attribute.name.as_str())
+                    && matches!(attribute.types.as_slice(), [(ty, _)] if is_scalar_tag(ty))
+            })
+            .map(|attribute| SparseCandidate {
+                name: attribute.name.clone(),
+                ty: attribute.types[0].0.clone(),
+                count: attribute.count,
+                frequency: attribute.frequency,
+            })
+            .collect();
+
+        let keys = self
+            .keys
+            .into_iter()
+            .map(|key| {
+                let present: u64 = key.values.values().sum();
+                let distinct = key.values.len() as u64;
+                let ratio = if present == 0 {
+                    1.0
+                } else {
+                    distinct as f64 / present as f64
+                };
+                KeyStats {
+                    top_values: top_shares(key.values, items),
+                    top_prefixes: top_shares(key.prefixes, items),
+                    name: key.name,
+                    role: key.role,
+                    distinct,
+                    capped: key.capped,
+                    cardinality: if key.capped {
+                        Cardinality::High
+                    } else {
+                        Cardinality::from_ratio(ratio)
+                    },
+                }
+            })
+            .collect();
+
+        TableReport {
+            items,
+            sampled,
+            cancelled,
+            elapsed,
+            attributes,
+            sizes: size_stats(self.sizes),
+            keys,
+            sparse_candidates,
+            partitions: self.partitions.map(PartitionTally::finish),
+        }
+    }
+}
+
+/// The most common entries of `counts`, as shares of `items`.
+fn top_shares(counts: HashMap<String, u64>, items: u64) -> Vec<Share> {
+    let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+        .into_iter()
+        .take(TOP_ENTRIES)
+        .map(|(value, count)| {
+            let share = if items == 0 {
+                0.0
+            } else {
+                count as f64 / items as f64
+            };
+            Share {
+                value,
+                count,
+                share,
+                // A single item is never a hotspot, however small the sample.
+                hot: count > 1 && share > HOT_SHARE,
+            }
+        })
+        .collect()
+}
+
+fn size_stats(mut sizes: Vec<usize>) -> SizeStats {
+    if sizes.is_empty() {
+        return SizeStats::default();
+    }
+    sizes.sort_unstable();
+    let total: usize = sizes.iter().sum();
+    let near_limit = sizes
+        .iter()
+        .filter(|size| **size * 10 >= MAX_ITEM_SIZE_BYTES * 9)
+        .count() as u64;
+    SizeStats {
+        average: total as f64 / sizes.len() as f64,
+        p50: percentile(&sizes, 50),
+        p90: percentile(&sizes, 90),
+        p99: percentile(&sizes, 99),
+        max: sizes[sizes.len() - 1],
+        near_limit,
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty `values`.
+fn percentile(values: &[usize], percent: usize) -> usize {
+    let rank = (values.len() * percent).div_ceil(100).max(1);
+    values[rank - 1]
+}
+
+/// The part of a key value before its first separator, separator included:
+/// `user#` for `user#42`. Values without one have no prefix.
+fn key_prefix(value: &str) -> Option<&str> {
+    let end = value.find(PREFIX_SEPARATORS)?;
+    (end > 0).then(|| &value[..=end])
+}
+
+/// A key value as text, for counting distinct values.
+fn key_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Str(text) => Some(text.clone()),
+        Value::Num(number) => Some(number.as_str().to_string()),
+        Value::Bytes(bytes) => Some(BASE64.encode(bytes)),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// The typed-JSON tag for a value's type.
+fn type_tag(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "NULL",
+        Value::Bool(_) => "BOOL",
+        Value::Str(_) => "S",
+        Value::Num(_) => "N",
+        Value::Bytes(_) => "B",
+        Value::List(_) => "L",
+        Value::Map(_) => "M",
+        Value::StringSet(_) => "SS",
+        Value::NumberSet(_) => "NS",
+        Value::BytesSet(_) => "BS",
+    }
+}
+
+/// Whether an index can be keyed on values of this type.
+fn is_scalar_tag(tag: &str) -> bool {
+    matches!(tag, "S" | "N" | "B")
+}
+
+/// Read `options.sample` items of `name` (all of them when unset) and report
+/// on them. `cancel` stops before the next page with the items read so far;
+/// `on_progress` sees the number of items read after every page.
+pub async fn analyze_items(
+    db: &dyn Datastore,
+    name: &str,
+    options: AnalyzeOptions,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(u64) + Sync),
+) -> Result<TableReport> {
+    let schema = db.describe_collection(name).await?;
+    let started = Instant::now();
+    let plan = QueryPlan::new(None, None);
+    let mut analyzer = Analyzer::new(&schema.key);
+    let mut cursor: Option<Cursor> = None;
+    let mut sampled = false;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let remaining = options
+            .sample
+            .map(|sample| sample.saturating_sub(analyzer.items()));
+        if remaining == Some(0) {
+            sampled = true;
+            break;
+        }
+        let limit = remaining.map_or(PAGE_SIZE, |remaining| {
+            u32::try_from(remaining).map_or(PAGE_SIZE, |remaining| remaining.min(PAGE_SIZE))
+        });
+        let page = Page {
+            cursor: cursor.take(),
+            limit: Some(limit),
+        };
+        crate::throttle::acquire().await;
+        let result = db.query(name, &plan, page).await?;
+        crate::throttle::charge(&result);
+        for item in &result.items {
+            analyzer.add(item);
+        }
+        on_progress(analyzer.items());
+        match result.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(analyzer.finish(sampled, cancel.load(Ordering::Relaxed), started.elapsed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Analyzer, Cardinality, key_prefix, percentile};
+    use crate::core::schema::{KeyField, KeyRole, KeySchema, ScalarType};
+    use crate::core::value::{Item, Number, Value};
+
+    fn schema() -> KeySchema {
+        KeySchema {
+            fields: vec![
+                KeyField {
+                    name: "pk".to_string(),
+                    role: KeyRole::Partition,
+                    ty: ScalarType::String,
+                },
+                KeyField {
+                    name: "sk".to_string(),
+                    role: KeyRole::Sort,
+                    ty: ScalarType::Number,
+                },
+            ],
+        }
+    }
+
+    fn item(pk: &str, sk: i64, extra: Option<(&str, Value)>) -> Item {
+        let mut item = Item::new();
+        item.insert("pk".to_string(), Value::Str(pk.to_string()));
+        item.insert("sk".to_string(), Value::Num(Number::new(sk.to_string())));
+        if let Some((name, value)) = extra {
+            item.insert(name.to_string(), value);
+        }
+        item
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let values: Vec<usize> = (1..=100).collect();
+        assert_eq!(percentile(&values, 50), 50);
+        assert_eq!(percentile(&values, 99), 99);
+        assert_eq!(percentile(&[7], 90), 7);
+    }
+
+    #[test]
+    fn key_prefix_stops_at_the_first_separator() {
+        assert_eq!(key_prefix("user#42#profile"), Some("user#"));
+        assert_eq!(key_prefix("order:7"), Some("order:"));
+        assert_eq!(key_prefix("#leading"), None);
+        assert_eq!(key_prefix("plain"), None);
+    }
+
+    #[test]
+    fn reports_frequency_keys_and_sparse_candidates() {
+        let mut analyzer = Analyzer::new(&schema());
+        for sk in 0..8 {
+            analyzer.add(&item("tenant#hot", sk, None));
+        }
+        analyzer.add(&item(
+            "tenant#a",
+            1,
+            Some(("flagged", Value::Str("yes".to_string()))),
+        ));
+        analyzer.add(&item(
+            "user#b",
+            1,
+            Some(("tags", Value::List(vec![Value::Bool(true)]))),
+        ));
+        let report = analyzer.finish(false, false, Duration::ZERO);
+
+        assert_eq!(report.items, 10);
+        assert_eq!(report.attributes[0].name, "pk");
+        assert_eq!(report.attributes[0].count, 10);
+
+        let pk = &report.keys[0];
+        assert_eq!(pk.distinct, 3);
+        assert_eq!(pk.cardinality, Cardinality::Medium);
+        assert_eq!(pk.top_values[0].value, "tenant#hot");
+        assert!(pk.top_values[0].hot);
+        assert!(!pk.top_values[1].hot);
+        assert_eq!(pk.top_prefixes[0].value, "tenant#");
+        assert_eq!(pk.top_prefixes[0].count, 9);
+
+        // Lists can't key an index; a sparse scalar can.
+        let candidates: Vec<&str> = report
+            .sparse_candidates
+            .iter()
+            .map(|candidate| candidate.name.as_str())
+            .collect();
+        assert_eq!(candidates, vec!["flagged"]);
+        assert!(report.sizes.p50 <= report.sizes.p99);
+        assert!(report.sizes.p99 <= report.sizes.max);
+    }
+
+    #[test]
+    fn groups_items_and_bytes_by_partition() {
+        let mut analyzer = Analyzer::new(&schema());
+        for sk in 0..6 {
+            analyzer.add(&item("tenant#hot", sk, None));
+        }
+        analyzer.add(&item(
+            "tenant#big",
+            1,
+            Some(("blob", Value::Str("x".repeat(1_000)))),
+        ));
+        analyzer.add(&item("user#a", 1, None));
+        let partitions = analyzer
+            .finish(false, false, Duration::ZERO)
+            .partitions
+            .unwrap();
+
+        assert_eq!(partitions.key, "pk");
+        let values: Vec<(&str, u64)> = partitions
+            .values
+            .iter()
+            .map(|partition| (partition.value.as_str(), partition.items))
+            .collect();
+        assert_eq!(
+            values,
+            vec![("tenant#hot", 6), ("tenant#big", 1), ("user#a", 1)]
+        );
+        assert!((partitions.values[0].item_share - 0.75).abs() < 1e-9);
+        // One large item makes tenant#big the biggest partition by size.
+        assert!(partitions.values[1].byte_share > partitions.values[0].byte_share);
+        assert!((partitions.item_skew() - 2.25).abs() < 1e-9);
+        assert!(partitions.size_skew() > partitions.item_skew());
+        assert_eq!(partitions.prefixes[0].value, "tenant#");
+        assert_eq!(partitions.prefixes[0].items, 7);
+    }
+}
@@ -0,0 +1,692 @@
+//! The write audit log: an append-only NDJSON file with one line per write
+//! dynamate makes — puts, updates, deletes (purges included), mutating
+//! statements, and table, index, capacity, TTL, and backup changes.
+//!
+//! Logging happens in [`Audited`], a [`Datastore`] wrapper that every opened
+//! backend goes through once [`set`] has named a file. Single-item writes
+//! record the item as it was beforehand when the backend can look it up by
+//! key, which is what lets a delete be undone. A failure to write the log is
+//! traced but never fails the write itself, which has already happened.
+
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use async_trait::async_trait;
+use serde_json::{Map, Value as Json};
+
+use super::capabilities::Capabilities;
+use super::connect::{ProfileInfo, ProfileSelection};
+use super::datastore::Datastore;
+use super::error::Result;
+use super::json::{item_from_typed_json, item_to_typed_json};
+use super::language::QueryLanguage;
+use super::metrics::{CollectionMetrics, MetricsWindow};
+use super::query::{
+    BatchDeleteOutcome, BatchPutOutcome, CapacitySpec, CollectionPage, CreateCollectionSpec,
+    ImportSource, ItemUpdate, Key, Page, PlanExplanation, QueryPlan, QueryResult, TransactOutcome,
+    TransactWrite,
+};
+use super::schema::{
+    BackupInfo, CollectionDetails, CollectionSchema, ImportInfo, IndexSchema, KeySchema,
+    SchemaHints,
+};
+use super::value::Item;
+
+static LOG_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Turn the audit log on, writing to `path`, or leave it off with `None`.
+/// Only the first call has an effect.
+pub fn set(path: Option<PathBuf>) {
+    let _ = LOG_PATH.set(path);
+}
+
+/// The audit log file, when the log is on.
+pub fn path() -> Option<&'static Path> {
+    LOG_PATH.get().and_then(Option::as_deref)
+}
+
+/// Wrap `db` so its writes are logged, when the audit log is on.
+pub fn wrap(db: Arc<dyn Datastore>) -> Arc<dyn Datastore> {
+    match path() {
+        Some(path) => Arc::new(Audited {
+            inner: db,
+            path: path.to_path_buf(),
+        }),
+        None => db,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOp {
+    Put,
+    Update,
+    Delete,
+    Statement,
+    CreateTable,
+    DropTable,
+    CreateIndex,
+    DropIndex,
+    UpdateCapacity,
+    EnableTtl,
+    CreateBackup,
+    DeleteBackup,
+    RestoreBackup,
+    AddReplica,
+    RemoveReplica,
+    ImportTable,
+}
+
+impl AuditOp {
+    const ALL: [AuditOp; 16] = [
+        AuditOp::Put,
+        AuditOp::Update,
+        AuditOp::Delete,
+        AuditOp::Statement,
+        AuditOp::CreateTable,
+        AuditOp::DropTable,
+        AuditOp::CreateIndex,
+        AuditOp::DropIndex,
+        AuditOp::UpdateCapacity,
+        AuditOp::EnableTtl,
+        AuditOp::CreateBackup,
+        AuditOp::DeleteBackup,
+        AuditOp::RestoreBackup,
+        AuditOp::AddReplica,
+        AuditOp::RemoveReplica,
+        AuditOp::ImportTable,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditOp::Put => "put",
+            AuditOp::Update => "update",
+            AuditOp::Delete => "delete",
+            AuditOp::Statement => "statement",
+            AuditOp::CreateTable => "create_table",
+            AuditOp::DropTable => "drop_table",
+            AuditOp::CreateIndex => "create_index",
+            AuditOp::DropIndex => "drop_index",
+            AuditOp::UpdateCapacity => "update_capacity",
+            AuditOp::EnableTtl => "enable_ttl",
+            AuditOp::CreateBackup => "create_backup",
+            AuditOp::DeleteBackup => "delete_backup",
+            AuditOp::RestoreBackup => "restore_backup",
+            AuditOp::AddReplica => "add_replica",
+            AuditOp::RemoveReplica => "remove_replica",
+            AuditOp::ImportTable => "import_table",
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.as_str() == text)
+    }
+}
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// When the write finished, as RFC 3339 in UTC.
+    pub at: String,
+    /// The backend label (e.g. "DynamoDB").
+    pub backend: String,
+    pub op: AuditOp,
+    /// The table written to; empty for statements, which name their own.
+    pub table: String,
+    pub key: Option<Item>,
+    /// The item as it was before the write, when it could be read.
+    pub before: Option<Item>,
+    /// Anything else worth keeping: the statement text, the index or backup
+    /// name, or how the write was made (`batch`, `transaction`).
+    pub detail: Option<String>,
+}
+
+impl AuditEntry {
+    fn new(backend: &str, op: AuditOp, table: &str) -> Self {
+        Self {
+            at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            backend: backend.to_string(),
+            op,
+            table: table.to_string(),
+            key: None,
+            before: None,
+            detail: None,
+        }
+    }
+
+    fn with_key(mut self, key: Key) -> Self {
+        self.key = Some(key.0);
+        self
+    }
+
+    fn with_before(mut self, before: Option<Item>) -> Self {
+        self.before = before;
+        self
+    }
+
+    fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// The entry as one JSON object. Items use typed JSON so a restore puts
+    /// back exactly what was there.
+    pub fn to_json(&self) -> Json {
+        let mut object = Map::new();
+        object.insert("at".to_string(), Json::from(self.at.clone()));
+        object.insert("backend".to_string(), Json::from(self.backend.clone()));
+        object.insert("op".to_string(), Json::from(self.op.as_str()));
+        object.insert("table".to_string(), Json::from(self.table.clone()));
+        for (name, item) in [("key", &self.key), ("before", &self.before)] {
+            if let Some(json) = item.as_ref().and_then(|item| item_to_typed_json(item).ok()) {
+                object.insert(name.to_string(), json);
+            }
+        }
+        if let Some(detail) = &self.detail {
+            object.insert("detail".to_string(), Json::from(detail.clone()));
+        }
+        Json::Object(object)
+    }
+
+    /// Read back an entry written by [`to_json`](Self::to_json).
+    pub fn from_json(value: &Json) -> Option<Self> {
+        let text = |name: &str| value.get(name).and_then(Json::as_str).map(str::to_string);
+        let item = |name: &str| {
+            value
+                .get(name)
+                .and_then(|json| item_from_typed_json(json).ok())
+        };
+        Some(Self {
+            at: text("at")?,
+            backend: text("backend").unwrap_or_default(),
+            op: AuditOp::parse(&text("op")?)?,
+            table: text("table").unwrap_or_default(),
+            key: item("key"),
+            before: item("before"),
+            detail: text("detail"),
+        })
+    }
+}
+
+/// Append `entries` to the log at `path`, creating it (and its directory)
+/// when missing.
+pub fn append(path: &Path, entries: &[AuditEntry]) -> std::io::Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&entry.to_json().to_string());
+        text.push('\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(text.as_bytes())
+}
+
+/// Every entry in the log at `path`, oldest first. A missing file is an empty
+/// log; lines that don't parse (say, one cut short by a crash) are skipped.
+pub fn read(path: &Path) -> std::io::Result<Vec<AuditEntry>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Json>(line).ok())
+        .filter_map(|json| AuditEntry::from_json(&json))
+        .collect())
+}
+
+/// The index of the newest delete on `backend` that can still be undone: it
+/// kept the deleted item, and nothing has been written to that key since.
+pub fn last_undoable_delete(entries: &[AuditEntry], backend: &str) -> Option<usize> {
+    entries.iter().enumerate().rev().find_map(|(idx, entry)| {
+        let undoable = entry.op == AuditOp::Delete
+            && entry.backend == backend
+            && entry.before.is_some()
+            && !entries[idx + 1..].iter().any(|later| {
+                later.backend == entry.backend
+                    && later.table == entry.table
+                    && later.key.is_some()
+                    && later.key == entry.key
+            });
+        undoable.then_some(idx)
+    })
+}
+
+/// Whether a statement only reads. Anything that isn't plainly a read counts
+/// as a write.
+pub fn is_read_statement(statement: &str) -> bool {
+    let keyword = statement
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    matches!(
+        keyword.as_str(),
+        "select" | "with" | "explain" | "show" | "describe"
+    )
+}
+
+fn capacity_detail(spec: &CapacitySpec) -> String {
+    match spec {
+        CapacitySpec::OnDemand => "on-demand".to_string(),
+        CapacitySpec::Provisioned { table, .. } => {
+            format!("provisioned {} RCU / {} WCU", table.read, table.write)
+        }
+    }
+}
+
+/// The key attributes of `item`, or `None` when one is missing.
+fn item_key(item: &Item, schema: &KeySchema) -> Option<Key> {
+    let mut key = Item::new();
+    for field in &schema.fields {
+        key.insert(field.name.clone(), item.get(&field.name)?.clone());
+    }
+    Some(Key(key))
+}
+
+/// A [`Datastore`] that logs every successful write of the store it wraps.
+pub struct Audited {
+    inner: Arc<dyn Datastore>,
+    path: PathBuf,
+}
+
+impl Audited {
+    fn entry(&self, op: AuditOp, table: &str) -> AuditEntry {
+        AuditEntry::new(self.inner.label(), op, table)
+    }
+
+    fn record(&self, entries: &[AuditEntry]) {
+        if let Err(err) = append(&self.path, entries) {
+            tracing::warn!(path = %self.path.display(), error = %err, "audit_log_write_failed");
+        }
+    }
+
+    /// The item stored under `key`, when the backend can look it up.
+    async fn before_image(&self, name: &str, key: &Key) -> Option<Item> {
+        if !self.inner.capabilities().batch_get || self.inner.is_read_only() {
+            return None;
+        }
+        let items = self
+            .inner
+            .batch_get(name, vec![key.clone()], true)
+            .await
+            .ok()?;
+        items.into_iter().next()
+    }
+
+    async fn key_schema(&self, name: &str) -> Option<KeySchema> {
+        self.inner
+            .describe_collection(name)
+            .await
+            .ok()
+            .map(|schema| schema.key)
+    }
+}
+
+#[async_trait]
+impl Datastore for Audited {
+    fn capabilities(&self) -> &Capabilities {
+        self.inner.capabilities()
+    }
+
+    fn query_language(&self) -> &dyn QueryLanguage {
+        self.inner.query_language()
+    }
+
+    fn label(&self) -> &str {
+        self.inner.label()
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+
+    async fn validate(&self) -> Result<()> {
+        self.inner.validate().await
+    }
+
+    async fn list_collections(&self) -> Result<Vec<String>> {
+        self.inner.list_collections().await
+    }
+
+    async fn list_collections_page(&self, start: Option<&str>) -> Result<CollectionPage> {
+        self.inner.list_collections_page(start).await
+    }
+
+    async fn describe_collection(&self, name: &str) -> Result<CollectionSchema> {
+        self.inner.describe_collection(name).await
+    }
+
+    async fn describe_details(&self, name: &str) -> Result<CollectionDetails> {
+        self.inner.describe_details(name).await
+    }
+
+    async fn query(&self, name: &str, plan: &QueryPlan, page: Page) -> Result<QueryResult> {
+        self.inner.query(name, plan, page).await
+    }
+
+    async fn put_item(&self, name: &str, item: Item) -> Result<()> {
+        let key = match self.key_schema(name).await {
+            Some(schema) => item_key(&item, &schema),
+            None => None,
+        };
+        let before = match &key {
+            Some(key) => self.before_image(name, key).await,
+            None => None,
+        };
+        self.inner.put_item(name, item).await?;
+        let mut entry = self.entry(AuditOp::Put, name).with_before(before);
+        entry.key = key.map(|key| key.0);
+        self.record(&[entry]);
+        Ok(())
+    }
+
+    async fn update_item(&self, name: &str, key: Key, update: &ItemUpdate) -> Result<()> {
+        let before = self.before_image(name, &key).await;
+        self.inner.update_item(name, key.clone(), update).await?;
+        self.record(&[self
+            .entry(AuditOp::Update, name)
+            .with_key(key)
+            .with_before(before)]);
+        Ok(())
+    }
+
+    async fn delete_item(&self, name: &str, key: Key) -> Result<()> {
+        let before = self.before_image(name, &key).await;
+        self.inner.delete_item(name, key.clone()).await?;
+        self.record(&[self
+            .entry(AuditOp::Delete, name)
+            .with_key(key)
+            .with_before(before)]);
+        Ok(())
+    }
+
+    async fn batch_delete(&self, name: &str, keys: Vec<Key>) -> Result<BatchDeleteOutcome> {
+        let outcome = self.inner.batch_delete(name, keys.clone()).await?;
+        let entries: Vec<AuditEntry> = keys
+            .into_iter()
+            .map(|key| {
+                self.entry(AuditOp::Delete, name)
+                    .with_key(key)
+                    .with_detail("batch")
+            })
+            .collect();
+        self.record(&entries);
+        Ok(outcome)
+    }
+
+    async fn batch_put(&self, name: &str, items: Vec<Item>) -> Result<BatchPutOutcome> {
+        let schema = self.key_schema(name).await;
+        let keys: Vec<Option<Key>> = items
+            .iter()
+            .map(|item| schema.as_ref().and_then(|schema| item_key(item, schema)))
+            .collect();
+        let outcome = self.inner.batch_put(name, items).await?;
+        let entries: Vec<AuditEntry> = keys
+            .into_iter()
+            .map(|key| {
+                let mut entry = self.entry(AuditOp::Put, name).with_detail("batch");
+                entry.key = key.map(|key| key.0);
+                entry
+            })
+            .collect();
+        self.record(&entries);
+        Ok(outcome)
+    }
+
+    async fn batch_get(
+        &self,
+        name: &str,
+        keys: Vec<Key>,
+        consistent_read: bool,
+    ) -> Result<Vec<Item>> {
+        self.inner.batch_get(name, keys, consistent_read).await
+    }
+
+    async fn transact_write(&self, writes: &[TransactWrite]) -> Result<TransactOutcome> {
+        let outcome = self.inner.transact_write(writes).await?;
+        if matches!(outcome, TransactOutcome::Committed) {
+            let mut entries = Vec::with_capacity(writes.len());
+            for write in writes {
+                let entry = match write {
+                    TransactWrite::Put { collection, item } => {
+                        let key = match self.key_schema(collection).await {
+                            Some(schema) => item_key(item, &schema),
+                            None => None,
+                        };
+                        let mut entry = self.entry(AuditOp::Put, collection);
+                        entry.key = key.map(|key| key.0);
+                        entry
+                    }
+                    TransactWrite::Update {
+                        collection, key, ..
+                    } => self
+                        .entry(AuditOp::Update, collection)
+                        .with_key(key.clone()),
+                    TransactWrite::Delete { collection, key } => self
+                        .entry(AuditOp::Delete, collection)
+                        .with_key(key.clone()),
+                };
+                entries.push(entry.with_detail("transaction"));
+            }
+            self.record(&entries);
+        }
+        Ok(outcome)
+    }
+
+    async fn create_collection(&self, spec: &CreateCollectionSpec) -> Result<()> {
+        self.inner.create_collection(spec).await?;
+        self.record(&[self.entry(AuditOp::CreateTable, &spec.name)]);
+        Ok(())
+    }
+
+    async fn drop_collection(&self, name: &str) -> Result<()> {
+        self.inner.drop_collection(name).await?;
+        self.record(&[self.entry(AuditOp::DropTable, name)]);
+        Ok(())
+    }
+
+    async fn create_index(&self, name: &str, index: &IndexSchema) -> Result<()> {
+        self.inner.create_index(name, index).await?;
+        self.record(&[self
+            .entry(AuditOp::CreateIndex, name)
+            .with_detail(index.name.clone())]);
+        Ok(())
+    }
+
+    async fn drop_index(&self, name: &str, index_name: &str) -> Result<()> {
+        self.inner.drop_index(name, index_name).await?;
+        self.record(&[self.entry(AuditOp::DropIndex, name).with_detail(index_name)]);
+        Ok(())
+    }
+
+    async fn update_capacity(&self, name: &str, spec: &CapacitySpec) -> Result<()> {
+        self.inner.update_capacity(name, spec).await?;
+        self.record(&[self
+            .entry(AuditOp::UpdateCapacity, name)
+            .with_detail(capacity_detail(spec))]);
+        Ok(())
+    }
+
+    async fn enable_ttl(&self, name: &str, attribute: &str) -> Result<()> {
+        self.inner.enable_ttl(name, attribute).await?;
+        self.record(&[self.entry(AuditOp::EnableTtl, name).with_detail(attribute)]);
+        Ok(())
+    }
+
+    async fn add_replica(&self, name: &str, region: &str) -> Result<()> {
+        self.inner.add_replica(name, region).await?;
+        self.record(&[self.entry(AuditOp::AddReplica, name).with_detail(region)]);
+        Ok(())
+    }
+
+    async fn remove_replica(&self, name: &str, region: &str) -> Result<()> {
+        self.inner.remove_replica(name, region).await?;
+        self.record(&[self.entry(AuditOp::RemoveReplica, name).with_detail(region)]);
+        Ok(())
+    }
+
+    async fn collection_metrics(
+        &self,
+        name: &str,
+        window: MetricsWindow,
+    ) -> Result<CollectionMetrics> {
+        self.inner.collection_metrics(name, window).await
+    }
+
+    async fn list_backups(&self, name: &str) -> Result<Vec<BackupInfo>> {
+        self.inner.list_backups(name).await
+    }
+
+    async fn create_backup(&self, name: &str, backup_name: &str) -> Result<()> {
+        self.inner.create_backup(name, backup_name).await?;
+        self.record(&[self
+            .entry(AuditOp::CreateBackup, name)
+            .with_detail(backup_name)]);
+        Ok(())
+    }
+
+    async fn delete_backup(&self, backup_id: &str) -> Result<()> {
+        self.inner.delete_backup(backup_id).await?;
+        self.record(&[self.entry(AuditOp::DeleteBackup, "").with_detail(backup_id)]);
+        Ok(())
+    }
+
+    async fn restore_backup(&self, backup_id: &str, target: &str) -> Result<()> {
+        self.inner.restore_backup(backup_id, target).await?;
+        self.record(&[self
+            .entry(AuditOp::RestoreBackup, target)
+            .with_detail(backup_id)]);
+        Ok(())
+    }
+
+    async fn import_collection(
+        &self,
+        spec: &CreateCollectionSpec,
+        source: &ImportSource,
+    ) -> Result<String> {
+        let id = self.inner.import_collection(spec, source).await?;
+        let detail = format!("s3://{}/{}", source.bucket, source.key_prefix);
+        self.record(&[self
+            .entry(AuditOp::ImportTable, &spec.name)
+            .with_detail(detail)]);
+        Ok(id)
+    }
+
+    async fn list_imports(&self) -> Result<Vec<ImportInfo>> {
+        self.inner.list_imports().await
+    }
+
+    async fn describe_ttl(&self, name: &str) -> Result<Option<String>> {
+        self.inner.describe_ttl(name).await
+    }
+
+    async fn explain(&self, name: &str, plan: &QueryPlan) -> PlanExplanation {
+        self.inner.explain(name, plan).await
+    }
+
+    async fn raw_query(&self, query: &str, page: Page) -> Result<QueryResult> {
+        self.inner.raw_query(query, page).await
+    }
+
+    async fn execute_statement(&self, statement: &str, page: Page) -> Result<QueryResult> {
+        let result = self.inner.execute_statement(statement, page).await?;
+        if !is_read_statement(statement) {
+            self.record(&[self
+                .entry(AuditOp::Statement, "")
+                .with_detail(statement.trim())]);
+        }
+        Ok(result)
+    }
+
+    async fn scan_segment(
+        &self,
+        name: &str,
+        plan: &QueryPlan,
+        segment: u32,
+        total_segments: u32,
+        page: Page,
+    ) -> Result<QueryResult> {
+        self.inner
+            .scan_segment(name, plan, segment, total_segments, page)
+            .await
+    }
+
+    fn raw_query_language(&self) -> Option<&dyn QueryLanguage> {
+        self.inner.raw_query_language()
+    }
+
+    async fn schema_hints(&self) -> Result<SchemaHints> {
+        self.inner.schema_hints().await
+    }
+
+    async fn list_profiles(&self) -> Result<Vec<ProfileInfo>> {
+        self.inner.list_profiles().await
+    }
+
+    fn active_profile(&self) -> Option<ProfileSelection> {
+        self.inner.active_profile()
+    }
+
+    async fn for_region(&self, region: &str) -> Result<Arc<dyn Datastore>> {
+        self.inner.for_region(region).await.map(wrap)
+    }
+
+    async fn switch_profile(&self, selection: &ProfileSelection) -> Result<()> {
+        self.inner.switch_profile(selection).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditEntry, AuditOp, last_undoable_delete};
+    use crate::core::value::{Item, Value};
+
+    fn entry(op: AuditOp, id: &str, before: bool) -> AuditEntry {
+        let key = Item::from([("id".to_string(), Value::from(id))]);
+        let mut item = key.clone();
+        item.insert("name".to_string(), Value::from("x"));
+        AuditEntry {
+            at: "2026-10-16T12:00:00.000Z".to_string(),
+            backend: "DynamoDB".to_string(),
+            op,
+            table: "users".to_string(),
+            key: Some(key),
+            before: before.then_some(item),
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn entries_round_trip_through_json() {
+        let mut original = entry(AuditOp::Delete, "a", true);
+        original.detail = Some("batch".to_string());
+        let line = original.to_json().to_string();
+        let parsed = AuditEntry::from_json(&serde_json::from_str(&line).unwrap()).unwrap();
+        assert_eq!(parsed, original);
+        assert!(AuditEntry::from_json(&serde_json::json!({"at": "now", "op": "nope"})).is_none());
+    }
+
+    #[test]
+    fn undo_skips_deletes_already_written_over() {
+        let entries = vec![
+            entry(AuditOp::Delete, "a", true),
+            entry(AuditOp::Delete, "b", true),
+            entry(AuditOp::Put, "b", false),
+            entry(AuditOp::Delete, "c", false),
+        ];
+        assert_eq!(last_undoable_delete(&entries, "DynamoDB"), Some(0));
+        assert_eq!(last_undoable_delete(&entries, "MongoDB"), None);
+        assert_eq!(last_undoable_delete(&entries[1..], "DynamoDB"), None);
+    }
+}
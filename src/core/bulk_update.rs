@@ -0,0 +1,193 @@
+//! Applying one update to every item a plan matches, for the query view's
+//! update-matching action.
+//!
+//! The matching keys are gathered first and updated afterwards, a bounded
+//! number at a time. Gathering first gives the progress a total, and keeps
+//! paging stable on backends that page by offset, where an update that makes
+//! an item stop matching would otherwise shift every later page. An item
+//! that fails doesn't stop the others; its error ends up in the
+//! [`UpdateSummary`].
+
+use std::future::ready;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+
+use super::datastore::Datastore;
+use super::error::{DbError, Result};
+use super::json::item_to_json_string;
+use super::purge::item_key;
+use super::query::{Cursor, ItemUpdate, Key, Page, QueryPlan};
+use super::value::Item;
+
+/// Items fetched per page while gathering keys.
+const PAGE_SIZE: u32 = 100;
+/// Per-item errors kept in the summary; the rest are only counted.
+const MAX_ERRORS: usize = 50;
+
+/// How far a bulk update has got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpdateProgress {
+    /// Items read while gathering keys, matching the plan or not.
+    pub scanned: u64,
+    /// Items the plan matched; the total to update.
+    pub matched: u64,
+    pub updated: u64,
+    pub failed: u64,
+}
+
+/// How a bulk update ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateSummary {
+    pub progress: UpdateProgress,
+    /// Stopped through the cancel flag before every item was updated.
+    pub cancelled: bool,
+    /// The first failures, each naming the item's key.
+    pub errors: Vec<String>,
+    pub elapsed: Duration,
+}
+
+/// Apply `update` to every item of `name` that `plan` matches, with at most
+/// `concurrency` updates in flight. `cancel` stops gathering before the next
+/// page and updating before the next item; `on_progress` sees the totals
+/// after every page and item.
+pub async fn update_items(
+    db: &dyn Datastore,
+    name: &str,
+    plan: &QueryPlan,
+    update: &ItemUpdate,
+    concurrency: usize,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(UpdateProgress) + Sync),
+) -> Result<UpdateSummary> {
+    if db.is_read_only() {
+        return Err(DbError::ReadOnly);
+    }
+    if !db.capabilities().partial_update {
+        return Err(DbError::Unsupported(
+            "this backend cannot update individual attributes",
+        ));
+    }
+    let key_fields = key_fields(db, name).await?;
+    let updated = db.query_language().updated_attributes(update);
+    if let Some(field) = key_fields.iter().find(|field| updated.contains(field)) {
+        return Err(DbError::Backend(format!(
+            "{field} is a key attribute and can't be updated"
+        )));
+    }
+
+    let started = Instant::now();
+    let progress = Mutex::new(UpdateProgress::default());
+    let record = |change: &dyn Fn(&mut UpdateProgress)| {
+        let mut progress = progress.lock().unwrap_or_else(PoisonError::into_inner);
+        change(&mut progress);
+        on_progress(*progress);
+    };
+
+    // Only the keys are needed to update an item.
+    let mut plan = plan.clone();
+    plan.projection.clone_from(&key_fields);
+    let mut keys = Vec::new();
+    let mut cursor: Option<Cursor> = None;
+    while !cancel.load(Ordering::Relaxed) {
+        let page = Page {
+            cursor: cursor.take(),
+            limit: Some(PAGE_SIZE),
+        };
+        crate::throttle::acquire().await;
+        let result = db.query(name, &plan, page).await?;
+        crate::throttle::charge(&result);
+        for item in &result.items {
+            keys.push(item_key(item, &key_fields)?);
+        }
+        record(&|progress| {
+            progress.scanned += result.scanned_count.unwrap_or(result.count);
+            progress.matched += result.items.len() as u64;
+        });
+        match result.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut updates = stream::iter(keys)
+        .take_while(|_| ready(!cancel.load(Ordering::Relaxed)))
+        .map(|key: Key| async move {
+            crate::throttle::acquire().await;
+            let label = item_to_json_string(&key.0).unwrap_or_default();
+            (label, db.update_item(name, key, update).await)
+        })
+        .buffer_unordered(concurrency.max(1));
+    while let Some((label, result)) = updates.next().await {
+        match result {
+            Ok(()) => record(&|progress| progress.updated += 1),
+            Err(err) => {
+                if errors.len() < MAX_ERRORS {
+                    errors.push(format!("{label}: {err}"));
+                }
+                record(&|progress| progress.failed += 1);
+            }
+        }
+    }
+
+    Ok(UpdateSummary {
+        progress: progress
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner),
+        cancelled: cancel.load(Ordering::Relaxed),
+        errors,
+        elapsed: started.elapsed(),
+    })
+}
+
+/// Up to `limit` items `plan` matches, each with how it would read after
+/// `update`. Writes nothing.
+pub async fn preview_update(
+    db: &dyn Datastore,
+    name: &str,
+    plan: &QueryPlan,
+    update: &ItemUpdate,
+    limit: usize,
+) -> Result<Vec<(Item, Item)>> {
+    let mut preview = Vec::new();
+    let mut cursor: Option<Cursor> = None;
+    while preview.len() < limit {
+        let page = Page {
+            cursor: cursor.take(),
+            limit: Some(PAGE_SIZE),
+        };
+        crate::throttle::acquire().await;
+        let result = db.query(name, plan, page).await?;
+        crate::throttle::charge(&result);
+        let language = db.query_language();
+        for item in result.items.into_iter().take(limit - preview.len()) {
+            let updated = language.apply_update(update, &item).map_err(|err| {
+                let key = item_to_json_string(&item).unwrap_or_default();
+                DbError::Backend(format!("The update fails on {key}: {err}"))
+            })?;
+            preview.push((item, updated));
+        }
+        match result.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(preview)
+}
+
+async fn key_fields(db: &dyn Datastore, name: &str) -> Result<Vec<String>> {
+    let schema = db.describe_collection(name).await?;
+    let key_fields: Vec<String> = schema
+        .key
+        .fields
+        .iter()
+        .map(|field| field.name.clone())
+        .collect();
+    if key_fields.is_empty() {
+        return Err(DbError::Backend(format!("{name} has no key attributes")));
+    }
+    Ok(key_fields)
+}
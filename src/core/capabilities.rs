@@ -43,4 +43,61 @@ pub struct Capabilities {
     /// Supports a free-form database-level query (SQL `SELECT …` across tables).
     /// Drives the table picker's query view.
     pub raw_query: bool,
+    /// Accepts statements (DynamoDB PartiQL) in the per-table query box,
+    /// alongside the expression syntax.
+    pub statements: bool,
+    /// Supports switching credentials profile/region at runtime (the profile
+    /// picker).
+    pub profiles: bool,
+    /// Supports splitting a full scan into independently paged segments
+    /// (DynamoDB parallel scan).
+    pub parallel_scan: bool,
+    /// Supports adding and dropping secondary indexes on an existing
+    /// collection (DynamoDB GSIs via `UpdateTable`).
+    pub index_management: bool,
+    /// Supports switching between on-demand and provisioned billing and
+    /// adjusting provisioned capacity (the capacity editor).
+    pub capacity: bool,
+    /// Supports changing individual attributes of an existing item
+    /// ([`update_item`](super::datastore::Datastore::update_item)), so edits
+    /// don't overwrite attributes they didn't touch.
+    pub partial_update: bool,
+    /// Supports per-query read options: strongly consistent reads, fetching
+    /// only selected attributes, and descending sort-key order (the query
+    /// options popup).
+    pub read_options: bool,
+    /// Supports on-demand backups: listing, creating, and deleting them, and
+    /// restoring one to a new collection (the backups view).
+    pub backups: bool,
+    /// Supports fetching many items by primary key in one call
+    /// ([`batch_get`](super::datastore::Datastore::batch_get); DynamoDB
+    /// `BatchGetItem`).
+    pub batch_get: bool,
+    /// Supports applying several writes atomically
+    /// ([`transact_write`](super::datastore::Datastore::transact_write);
+    /// DynamoDB `TransactWriteItems`).
+    pub transactions: bool,
+    /// Supports opening a client for another region of the same account
+    /// ([`for_region`](super::datastore::Datastore::for_region)), for the
+    /// table list across regions.
+    pub regions: bool,
+    /// Supports adding and removing replica regions of a global table
+    /// ([`add_replica`](super::datastore::Datastore::add_replica); DynamoDB
+    /// `UpdateTable` with `ReplicaUpdates`).
+    pub replicas: bool,
+    /// Supports creating a collection from files in S3 and following the
+    /// import
+    /// ([`import_collection`](super::datastore::Datastore::import_collection);
+    /// DynamoDB `ImportTable`).
+    pub imports: bool,
+    /// Reports recent capacity, throttling, and latency metrics of a
+    /// collection
+    /// ([`collection_metrics`](super::datastore::Datastore::collection_metrics);
+    /// CloudWatch `GetMetricData`), for the metrics view.
+    pub metrics: bool,
+    /// Rejects items that lack a key attribute or hold one with another type
+    /// than the schema declares, so items are checked before they're written
+    /// (DynamoDB key schemas). Backends that fill in or coerce keys leave
+    /// this off.
+    pub typed_keys: bool,
 }
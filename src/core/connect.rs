@@ -24,9 +24,74 @@ pub enum BackendKind {
 /// Per-backend connection parameters.
 #[derive(Debug, Clone)]
 pub enum ConnOptions {
-    Dynamo { endpoint_url: Option<String> },
-    Mongo { uri: String },
-    Sql { url: String },
+    Dynamo {
+        endpoint_url: Option<String>,
+        /// A role to assume on top of the environment's credentials.
+        role: Option<RoleSelection>,
+    },
+    Mongo {
+        uri: String,
+    },
+    Sql {
+        url: String,
+    },
+}
+
+/// A named credentials profile the backend can switch to (e.g. an AWS profile
+/// from `~/.aws/config`), with the region it is configured for, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub region: Option<String>,
+}
+
+/// The profile and region a backend is connected with. `profile: None` means
+/// credentials come from the environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileSelection {
+    pub profile: Option<String>,
+    pub region: Option<String>,
+    /// A role assumed with the profile's credentials, e.g. to reach tables in
+    /// another account.
+    pub role: Option<RoleSelection>,
+}
+
+impl ProfileSelection {
+    /// A short label for the title bar / picker, e.g. `prod · eu-west-1`, or
+    /// `prod · eu-west-1 · as Auditor` with a role assumed.
+    pub fn label(&self) -> String {
+        let profile = self.profile.as_deref().unwrap_or("env");
+        let mut label = match self.region.as_deref() {
+            Some(region) => format!("{profile} · {region}"),
+            None => profile.to_string(),
+        };
+        if let Some(role) = self.role.as_ref() {
+            label.push_str(" · as ");
+            label.push_str(role.name());
+        }
+        label
+    }
+}
+
+/// An IAM role to assume, with the MFA device to prove when its trust policy
+/// asks for one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoleSelection {
+    pub role_arn: String,
+    /// The MFA device's serial number or ARN.
+    pub mfa_serial: Option<String>,
+    /// The device's current code. It is only good once, so credentials
+    /// assumed with it can't be refreshed when they expire.
+    pub mfa_code: Option<String>,
+}
+
+impl RoleSelection {
+    /// The role's name, the last part of its ARN.
+    pub fn name(&self) -> &str {
+        self.role_arn
+            .rsplit_once('/')
+            .map_or(self.role_arn.as_str(), |(_, name)| name)
+    }
 }
 
 /// Choose a backend from the connection arguments by URI scheme: a
@@ -49,20 +114,38 @@ pub fn detect_backend(target: Option<&str>, endpoint_url: Option<&str>) -> Backe
     BackendKind::Dynamodb
 }
 
-/// Open a datastore for the given backend.
+/// Open a datastore for the given backend, logging its writes when the audit
+/// log is on.
 pub async fn open(
     kind: BackendKind,
     options: &ConnOptions,
     read_only: bool,
+) -> Result<Arc<dyn Datastore>> {
+    open_backend(kind, options, read_only)
+        .await
+        .map(super::audit::wrap)
+}
+
+async fn open_backend(
+    kind: BackendKind,
+    options: &ConnOptions,
+    read_only: bool,
 ) -> Result<Arc<dyn Datastore>> {
     match (kind, options) {
-        (BackendKind::Dynamodb, ConnOptions::Dynamo { endpoint_url }) => {
-            let client = crate::dynamodb::connect::new_client(endpoint_url.as_deref())
-                .await
-                .map_err(DbError::Backend)?;
-            Ok(Arc::new(crate::dynamodb::DynamoBackend::new(
-                client, read_only,
-            )))
+        (BackendKind::Dynamodb, ConnOptions::Dynamo { endpoint_url, role }) => {
+            let selection = ProfileSelection {
+                role: role.clone(),
+                ..ProfileSelection::default()
+            };
+            let client =
+                crate::dynamodb::connect::new_client_for(&selection, endpoint_url.as_deref())
+                    .await
+                    .map_err(DbError::Backend)?;
+            Ok(Arc::new(
+                crate::dynamodb::DynamoBackend::new(client, read_only)
+                    .with_endpoint_url(endpoint_url.clone())
+                    .with_role(role.clone()),
+            ))
         }
         (BackendKind::Mongodb, ConnOptions::Mongo { uri }) => {
             let backend = crate::mongo::connect::connect(uri, read_only)
@@ -0,0 +1,470 @@
+//! Copying every item of one collection into another, for the `copy-table`
+//! command and the table picker's copy action, and checking hand-picked items
+//! against the collection they're copied to, for the query view's copy to
+//! table action.
+//!
+//! Copies are resumable: a [`CopyCheckpoint`] records how far each scan
+//! segment has got and is handed to the progress callback after every page is
+//! written. Passing a saved checkpoint back to [`copy_items`] carries on from
+//! there. The page in flight when a copy stops is written again on resume,
+//! which is harmless since puts replace whole items. Setting the cancel flag
+//! stops a copy between pages, leaving the checkpoint to resume from.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use futures::future::try_join_all;
+use serde_json::{Map, Value as Json};
+
+use super::datastore::Datastore;
+use super::error::{DbError, Result};
+use super::json::{item_from_typed_json, item_to_typed_json};
+use super::query::{CreateCollectionSpec, Cursor, Page, QueryPlan};
+use super::schema::{CollectionSchema, KeyRole, ScalarType};
+use super::value::{Item, Value};
+
+/// Items sent per `batch_put` call.
+const BATCH_SIZE: usize = 25;
+/// How often to re-describe a new collection while it is being created.
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long a new collection may take to become usable.
+const READY_TIMEOUT: Duration = Duration::from_mins(10);
+
+/// How far one scan segment of a copy has got.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentState {
+    /// Items remain after `cursor` (from the start when `None`).
+    Pending(Option<Cursor>),
+    Done,
+}
+
+/// The progress of a copy, enough to resume it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyCheckpoint {
+    pub source: String,
+    pub destination: String,
+    /// Items written so far.
+    pub copied: u64,
+    /// One entry per scan segment; a single segment pages sequentially.
+    pub segments: Vec<SegmentState>,
+}
+
+impl CopyCheckpoint {
+    pub fn new(source: impl Into<String>, destination: impl Into<String>, segments: u32) -> Self {
+        Self {
+            source: source.into(),
+            destination: destination.into(),
+            copied: 0,
+            segments: vec![SegmentState::Pending(None); segments.max(1) as usize],
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.segments
+            .iter()
+            .all(|segment| *segment == SegmentState::Done)
+    }
+
+    pub fn to_json(&self) -> Json {
+        let segments = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                SegmentState::Done => Json::Bool(true),
+                SegmentState::Pending(None) => Json::Null,
+                SegmentState::Pending(Some(cursor)) => {
+                    item_to_typed_json(&cursor.0).unwrap_or(Json::Null)
+                }
+            })
+            .collect();
+        let mut map = Map::new();
+        map.insert("source".to_string(), Json::from(self.source.clone()));
+        map.insert(
+            "destination".to_string(),
+            Json::from(self.destination.clone()),
+        );
+        map.insert("copied".to_string(), Json::from(self.copied));
+        map.insert("segments".to_string(), Json::Array(segments));
+        Json::Object(map)
+    }
+
+    pub fn from_json(value: &Json) -> Result<Self> {
+        let invalid = |what: &str| DbError::Backend(format!("Invalid copy checkpoint: {what}"));
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(Json::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| invalid(&format!("missing {key}")))
+        };
+        let segments = value
+            .get("segments")
+            .and_then(Json::as_array)
+            .filter(|segments| !segments.is_empty())
+            .ok_or_else(|| invalid("missing segments"))?
+            .iter()
+            .map(|segment| match segment {
+                Json::Bool(true) => Ok(SegmentState::Done),
+                Json::Null => Ok(SegmentState::Pending(None)),
+                cursor => item_from_typed_json(cursor)
+                    .map(|item| SegmentState::Pending(Some(Cursor(item))))
+                    .map_err(|err| invalid(&err.to_string())),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            source: text("source")?,
+            destination: text("destination")?,
+            copied: value.get("copied").and_then(Json::as_u64).unwrap_or(0),
+            segments,
+        })
+    }
+}
+
+/// A spec for a collection with the keys and indexes of `schema`.
+pub fn copy_spec(schema: &CollectionSchema, destination: &str) -> CreateCollectionSpec {
+    CreateCollectionSpec {
+        name: destination.to_string(),
+        key: schema.key.clone(),
+        indexes: schema.indexes.clone(),
+    }
+}
+
+/// Wait for a newly created collection to become active. Backends that
+/// report no status are ready straight away.
+pub async fn wait_until_active(db: &dyn Datastore, name: &str) -> Result<()> {
+    let started = std::time::Instant::now();
+    loop {
+        let schema = db.describe_collection(name).await?;
+        match schema.status.as_deref() {
+            None => return Ok(()),
+            Some(status) if status.eq_ignore_ascii_case("active") => return Ok(()),
+            Some(status) if started.elapsed() > READY_TIMEOUT => {
+                return Err(DbError::Backend(format!(
+                    "{name} is still {status} after {} minutes",
+                    READY_TIMEOUT.as_secs() / 60
+                )));
+            }
+            Some(_) => tokio::time::sleep(READY_POLL_INTERVAL).await,
+        }
+    }
+}
+
+/// Copy the items still pending in `checkpoint` from its source to its
+/// destination, which must already exist. Segments run concurrently when
+/// there are several. `on_progress` sees the checkpoint after every page.
+/// When `cancel` is set the copy stops and the checkpoint returned isn't
+/// complete.
+pub async fn copy_items(
+    db: &dyn Datastore,
+    checkpoint: CopyCheckpoint,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(&CopyCheckpoint) + Sync),
+) -> Result<CopyCheckpoint> {
+    let total_segments = checkpoint.segments.len();
+    if total_segments > 1 && !db.capabilities().parallel_scan {
+        return Err(DbError::Unsupported("this backend has no parallel scan"));
+    }
+    let pending: Vec<(usize, Option<Cursor>)> = checkpoint
+        .segments
+        .iter()
+        .enumerate()
+        .filter_map(|(segment, state)| match state {
+            SegmentState::Pending(cursor) => Some((segment, cursor.clone())),
+            SegmentState::Done => None,
+        })
+        .collect();
+    let shared = Mutex::new(checkpoint);
+    let copy = SegmentCopy {
+        db,
+        shared: &shared,
+        total_segments,
+        cancel,
+        on_progress,
+    };
+    try_join_all(
+        pending
+            .into_iter()
+            .map(|(segment, cursor)| copy.run(segment, cursor)),
+    )
+    .await?;
+    Ok(shared.into_inner().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// What writing some items into a collection would run into, from
+/// [`check_items`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemsCheck {
+    /// Key attributes some items lack. Those items can't be written.
+    pub missing: Vec<String>,
+    /// Key and index attributes some items hold with another type than the
+    /// collection defines. Writing those items is likely rejected.
+    pub mismatches: Vec<String>,
+}
+
+impl ItemsCheck {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.mismatches.is_empty()
+    }
+}
+
+/// Check `items` against the key and index attributes `schema` defines.
+/// Items may leave out index attributes, since indexes are sparse.
+pub fn check_items(items: &[Item], schema: &CollectionSchema) -> ItemsCheck {
+    let mut check = ItemsCheck::default();
+    for field in &schema.key.fields {
+        let missing = items
+            .iter()
+            .filter(|item| !item.contains_key(&field.name))
+            .count();
+        if missing > 0 {
+            check.missing.push(format!(
+                "{missing} of {} items have no {}",
+                items.len(),
+                field.name
+            ));
+        }
+    }
+    let key_fields = schema
+        .key
+        .fields
+        .iter()
+        .map(|field| (field, "the key".to_string()));
+    let index_fields = schema.indexes.iter().flat_map(|index| {
+        index
+            .key
+            .fields
+            .iter()
+            .map(|field| (field, format!("index {}", index.name)))
+    });
+    let mut checked = Vec::new();
+    for (field, target) in key_fields.chain(index_fields) {
+        if checked.contains(&&field.name) {
+            continue;
+        }
+        checked.push(&field.name);
+        let mismatched = items
+            .iter()
+            .filter(|item| {
+                item.get(&field.name)
+                    .is_some_and(|value| scalar_type(value) != Some(field.ty))
+            })
+            .count();
+        if mismatched > 0 {
+            check.mismatches.push(format!(
+                "{} is not a {} in {mismatched} items, as {target} expects",
+                field.name,
+                scalar_label(field.ty)
+            ));
+        }
+    }
+    check
+}
+
+/// Check one item against the key `schema` defines, naming the first key
+/// attribute it lacks or holds with the wrong type.
+pub fn check_key(item: &Item, schema: &CollectionSchema) -> std::result::Result<(), String> {
+    for field in &schema.key.fields {
+        let role = match field.role {
+            KeyRole::Partition => "partition",
+            KeyRole::Sort => "sort",
+        };
+        let expected = scalar_label(field.ty);
+        match item.get(&field.name) {
+            None => {
+                return Err(format!(
+                    "The item has no {role} key {} (a {expected})",
+                    field.name
+                ));
+            }
+            Some(value) if scalar_type(value) != Some(field.ty) => {
+                return Err(format!(
+                    "The {role} key {} is a {}, but {} expects a {expected}",
+                    field.name,
+                    value_label(value),
+                    schema.name
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn value_label(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Str(_) => "string",
+        Value::Num(_) => "number",
+        Value::Bytes(_) => "binary",
+        Value::List(_) => "list",
+        Value::Map(_) => "map",
+        Value::StringSet(_) => "string set",
+        Value::NumberSet(_) => "number set",
+        Value::BytesSet(_) => "binary set",
+    }
+}
+
+fn scalar_type(value: &Value) -> Option<ScalarType> {
+    match value {
+        Value::Str(_) => Some(ScalarType::String),
+        Value::Num(_) => Some(ScalarType::Number),
+        Value::Bytes(_) => Some(ScalarType::Binary),
+        _ => None,
+    }
+}
+
+fn scalar_label(ty: ScalarType) -> &'static str {
+    match ty {
+        ScalarType::String => "string",
+        ScalarType::Number => "number",
+        ScalarType::Binary => "binary",
+    }
+}
+
+struct SegmentCopy<'a> {
+    db: &'a dyn Datastore,
+    shared: &'a Mutex<CopyCheckpoint>,
+    total_segments: usize,
+    cancel: &'a AtomicBool,
+    on_progress: &'a (dyn Fn(&CopyCheckpoint) + Sync),
+}
+
+impl SegmentCopy<'_> {
+    async fn run(&self, segment: usize, mut cursor: Option<Cursor>) -> Result<()> {
+        let (source, destination) = {
+            let checkpoint = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+            (checkpoint.source.clone(), checkpoint.destination.clone())
+        };
+        let plan = QueryPlan::default();
+        loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            let page = Page {
+                cursor: cursor.take(),
+                limit: None,
+            };
+            crate::throttle::acquire().await;
+            let result = if self.total_segments > 1 {
+                let (segment, total) = (segment as u32, self.total_segments as u32);
+                self.db
+                    .scan_segment(&source, &plan, segment, total, page)
+                    .await?
+            } else {
+                self.db.query(&source, &plan, page).await?
+            };
+            crate::throttle::charge(&result);
+            let mut written = 0;
+            for batch in result.items.chunks(BATCH_SIZE) {
+                written += self
+                    .db
+                    .batch_put(&destination, batch.to_vec())
+                    .await?
+                    .written;
+            }
+            cursor = result.next;
+            let mut checkpoint = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+            checkpoint.copied += written;
+            checkpoint.segments[segment] = match cursor.clone() {
+                Some(next) => SegmentState::Pending(Some(next)),
+                None => SegmentState::Done,
+            };
+            (self.on_progress)(&checkpoint);
+            if cursor.is_none() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CopyCheckpoint, SegmentState, check_items, check_key};
+    use crate::core::query::Cursor;
+    use crate::core::schema::{
+        CollectionSchema, IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, Projection,
+        ScalarType,
+    };
+    use crate::core::value::{Item, Value};
+
+    #[test]
+    fn checkpoint_round_trips_through_json() {
+        let mut checkpoint = CopyCheckpoint::new("orders", "orders-copy", 3);
+        let mut key = Item::new();
+        key.insert("PK".to_string(), Value::from("USER#1"));
+        checkpoint.copied = 42;
+        checkpoint.segments[0] = SegmentState::Done;
+        checkpoint.segments[2] = SegmentState::Pending(Some(Cursor(key)));
+        assert!(!checkpoint.is_complete());
+
+        let restored = CopyCheckpoint::from_json(&checkpoint.to_json()).expect("checkpoint");
+        assert_eq!(restored, checkpoint);
+        assert!(CopyCheckpoint::from_json(&serde_json::json!({"source": "a"})).is_err());
+    }
+
+    #[test]
+    fn check_items_reports_missing_keys_and_type_mismatches() {
+        let field = |name: &str, role, ty| KeyField {
+            name: name.to_string(),
+            role,
+            ty,
+        };
+        let schema = CollectionSchema {
+            name: "orders".to_string(),
+            key: KeySchema {
+                fields: vec![
+                    field("PK", KeyRole::Partition, ScalarType::String),
+                    field("SK", KeyRole::Sort, ScalarType::Number),
+                ],
+            },
+            indexes: vec![IndexSchema {
+                name: "byStatus".to_string(),
+                kind: IndexKind::GlobalSecondary,
+                key: KeySchema {
+                    fields: vec![field("status", KeyRole::Partition, ScalarType::String)],
+                },
+                projection: Projection::All,
+            }],
+            ..CollectionSchema::default()
+        };
+        let item = |pairs: &[(&str, Value)]| -> Item {
+            pairs
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), value.clone()))
+                .collect()
+        };
+        let good = item(&[("PK", Value::from("a")), ("SK", Value::from(1_i64))]);
+        assert!(check_items(std::slice::from_ref(&good), &schema).is_ok());
+
+        let items = [
+            good,
+            item(&[("PK", Value::from("b"))]),
+            item(&[
+                ("PK", Value::from("c")),
+                ("SK", Value::from("1")),
+                ("status", Value::from(3_i64)),
+            ]),
+        ];
+        let check = check_items(&items, &schema);
+        assert_eq!(check.missing, ["1 of 3 items have no SK"]);
+        assert_eq!(
+            check.mismatches,
+            [
+                "SK is not a number in 1 items, as the key expects",
+                "status is not a string in 1 items, as index byStatus expects"
+            ]
+        );
+
+        assert_eq!(check_key(&items[0], &schema), Ok(()));
+        assert_eq!(
+            check_key(&items[1], &schema),
+            Err("The item has no sort key SK (a number)".to_string())
+        );
+        assert_eq!(
+            check_key(&items[2], &schema),
+            Err("The sort key SK is a string, but orders expects a number".to_string())
+        );
+    }
+}
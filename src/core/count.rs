@@ -0,0 +1,119 @@
+//! Counting the items a plan matches without fetching them, for the query
+//! view's count-only mode.
+//!
+//! Every page is read with [`QueryPlan::count_only`] set, so DynamoDB only
+//! sends the counts back; the read capacity is the same as fetching the items,
+//! but nothing is transferred or kept. Backends without a count-only read
+//! return the items, which are counted and dropped page by page.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use super::datastore::Datastore;
+use super::error::Result;
+use super::query::{Cursor, Page, QueryPlan, QueryResult};
+
+/// How far a count has got, and how it ended.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CountSummary {
+    /// Items the plan matched.
+    pub matched: u64,
+    /// Items read to find them, matching or not.
+    pub scanned: u64,
+    pub pages: u64,
+    /// Read capacity consumed, when the backend reports it.
+    pub capacity_units: Option<f64>,
+    /// Stopped through the cancel flag before the last page.
+    pub cancelled: bool,
+    pub elapsed: Duration,
+}
+
+impl CountSummary {
+    fn add(&mut self, result: &QueryResult) {
+        let matched = result.count.max(result.items.len() as u64);
+        self.matched += matched;
+        self.scanned += result.scanned_count.unwrap_or(matched);
+        self.pages += 1;
+        if let Some(units) = result.cost.as_ref().and_then(|cost| cost.capacity_units) {
+            *self.capacity_units.get_or_insert(0.0) += units;
+        }
+    }
+}
+
+/// Count the items of `name` that `plan` matches, across every page.
+/// `cancel` stops before the next page; `on_progress` sees the totals after
+/// every page.
+pub async fn count_matches(
+    db: &dyn Datastore,
+    name: &str,
+    plan: &QueryPlan,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(CountSummary) + Sync),
+) -> Result<CountSummary> {
+    let started = Instant::now();
+    let mut plan = plan.clone();
+    plan.count_only = true;
+    plan.projection.clear();
+    let mut summary = CountSummary::default();
+    let mut cursor: Option<Cursor> = None;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            summary.cancelled = true;
+            break;
+        }
+        let page = Page {
+            cursor: cursor.take(),
+            limit: None,
+        };
+        crate::throttle::acquire().await;
+        let result = db.query(name, &plan, page).await?;
+        crate::throttle::charge(&result);
+        summary.add(&result);
+        summary.elapsed = started.elapsed();
+        on_progress(summary);
+        match result.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    summary.elapsed = started.elapsed();
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CountSummary;
+    use crate::core::query::{PlanKind, QueryCost, QueryResult};
+    use crate::core::value::Item;
+
+    fn page(items: usize, count: u64, scanned: Option<u64>, units: Option<f64>) -> QueryResult {
+        QueryResult {
+            items: vec![Item::default(); items],
+            count,
+            scanned_count: scanned,
+            next: None,
+            plan_kind: PlanKind::Scan,
+            cost: units.map(|units| QueryCost {
+                capacity_units: Some(units),
+            }),
+        }
+    }
+
+    #[test]
+    fn adds_up_counts_whether_or_not_items_came_back() {
+        let mut summary = CountSummary::default();
+        summary.add(&page(0, 40, Some(1000), Some(12.5)));
+        summary.add(&page(0, 2, Some(90), Some(1.5)));
+        assert_eq!(summary.matched, 42);
+        assert_eq!(summary.scanned, 1090);
+        assert_eq!(summary.pages, 2);
+        assert_eq!(summary.capacity_units, Some(14.0));
+
+        // A backend that ignores count-only sends the items and no scanned
+        // count.
+        let mut summary = CountSummary::default();
+        summary.add(&page(3, 3, None, None));
+        assert_eq!((summary.matched, summary.scanned), (3, 3));
+        assert_eq!(summary.capacity_units, None);
+    }
+}
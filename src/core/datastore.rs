@@ -5,15 +5,21 @@
 //! module at its own boundary, compiles the [`QueryPlan`] to its own query
 //! language, and enforces read-only mode inside its mutating methods.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
 use super::capabilities::Capabilities;
+use super::connect::{ProfileInfo, ProfileSelection};
 use super::error::Result;
 use super::language::QueryLanguage;
+use super::metrics::{CollectionMetrics, MetricsWindow};
 use super::query::{
-    BatchDeleteOutcome, CreateCollectionSpec, Key, Page, PlanExplanation, QueryPlan, QueryResult,
+    BatchDeleteOutcome, BatchPutOutcome, CapacitySpec, CollectionPage, CreateCollectionSpec,
+    ImportSource, ItemUpdate, Key, Page, PlanExplanation, QueryPlan, QueryResult, TransactOutcome,
+    TransactWrite,
 };
-use super::schema::CollectionSchema;
+use super::schema::{BackupInfo, CollectionDetails, CollectionSchema, ImportInfo, IndexSchema};
 use super::value::Item;
 
 #[async_trait]
@@ -43,9 +49,31 @@ pub trait Datastore: Send + Sync {
     /// List the collection (table) names.
     async fn list_collections(&self) -> Result<Vec<String>>;
 
+    /// List collection names a page at a time, in name order, starting after
+    /// `start`. Backends with a paged listing (DynamoDB `ListTables`)
+    /// override this; the default returns every name in one page.
+    async fn list_collections_page(&self, start: Option<&str>) -> Result<CollectionPage> {
+        let mut names = self.list_collections().await?;
+        names.sort();
+        if let Some(start) = start {
+            names.retain(|name| name.as_str() > start);
+        }
+        Ok(CollectionPage { names, next: None })
+    }
+
     /// Describe a collection's neutral schema.
     async fn describe_collection(&self, name: &str) -> Result<CollectionSchema>;
 
+    /// Describe a collection in full for the details view. Backends with more
+    /// to report than the schema (capacity, streams, backups) override this.
+    async fn describe_details(&self, name: &str) -> Result<CollectionDetails> {
+        let mut schema = self.describe_collection(name).await?;
+        if schema.ttl_attribute.is_none() && self.capabilities().ttl {
+            schema.ttl_attribute = self.describe_ttl(name).await?;
+        }
+        Ok(CollectionDetails::from(schema))
+    }
+
     /// Run a query, returning one page of results. The backend compiles the
     /// plan to its dialect and paginates itself.
     async fn query(&self, name: &str, plan: &QueryPlan, page: Page) -> Result<QueryResult>;
@@ -53,18 +81,195 @@ pub trait Datastore: Send + Sync {
     /// Create or replace a single item.
     async fn put_item(&self, name: &str, item: Item) -> Result<()>;
 
+    /// Apply attribute-level changes to the item with `key`, leaving other
+    /// attributes alone. Only supported when
+    /// [`Capabilities::partial_update`](super::capabilities::Capabilities::partial_update)
+    /// is set; otherwise callers write the whole item with
+    /// [`put_item`](Self::put_item).
+    async fn update_item(&self, _name: &str, _key: Key, _update: &ItemUpdate) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend cannot update individual attributes",
+        ))
+    }
+
     /// Delete a single item by key.
     async fn delete_item(&self, name: &str, key: Key) -> Result<()>;
 
     /// Delete many items by key.
     async fn batch_delete(&self, name: &str, keys: Vec<Key>) -> Result<BatchDeleteOutcome>;
 
+    /// Create or replace many items. Backends with a native bulk write
+    /// override this; the default writes the items one at a time and stops at
+    /// the first error.
+    async fn batch_put(&self, name: &str, items: Vec<Item>) -> Result<BatchPutOutcome> {
+        let mut written = 0_u64;
+        for item in items {
+            self.put_item(name, item).await?;
+            written += 1;
+        }
+        Ok(BatchPutOutcome { written })
+    }
+
+    /// Fetch the items with the given primary keys, in the order of `keys`.
+    /// Keys with no item are skipped. Only supported when
+    /// [`Capabilities::batch_get`](super::capabilities::Capabilities::batch_get)
+    /// is set.
+    async fn batch_get(
+        &self,
+        _name: &str,
+        _keys: Vec<Key>,
+        _consistent_read: bool,
+    ) -> Result<Vec<Item>> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no batch key lookup",
+        ))
+    }
+
+    /// Apply `writes` atomically: all of them or none. A rejected transaction
+    /// is an `Ok` [`TransactOutcome::Canceled`] carrying the reason for each
+    /// write. Only supported when
+    /// [`Capabilities::transactions`](super::capabilities::Capabilities::transactions)
+    /// is set.
+    async fn transact_write(&self, _writes: &[TransactWrite]) -> Result<TransactOutcome> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no transactions",
+        ))
+    }
+
     /// Create a collection.
     async fn create_collection(&self, spec: &CreateCollectionSpec) -> Result<()>;
 
     /// Drop a collection.
     async fn drop_collection(&self, name: &str) -> Result<()>;
 
+    /// Add a secondary index to an existing collection. The index may take a
+    /// while to become usable; [`describe_details`](Self::describe_details)
+    /// reports its progress. Only supported when
+    /// [`Capabilities::index_management`](super::capabilities::Capabilities::index_management)
+    /// is set.
+    async fn create_index(&self, _name: &str, _index: &IndexSchema) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend cannot add indexes to an existing collection",
+        ))
+    }
+
+    /// Drop a secondary index from a collection. Only supported when
+    /// [`Capabilities::index_management`](super::capabilities::Capabilities::index_management)
+    /// is set.
+    async fn drop_index(&self, _name: &str, _index_name: &str) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend cannot drop indexes",
+        ))
+    }
+
+    /// Switch a collection's billing mode and/or change its provisioned
+    /// capacity. Only supported when
+    /// [`Capabilities::capacity`](super::capabilities::Capabilities::capacity)
+    /// is set.
+    async fn update_capacity(&self, _name: &str, _spec: &CapacitySpec) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no capacity settings",
+        ))
+    }
+
+    /// Expire a collection's items by the epoch seconds in `attribute`. Only
+    /// supported when
+    /// [`Capabilities::ttl`](super::capabilities::Capabilities::ttl) is set.
+    async fn enable_ttl(&self, _name: &str, _attribute: &str) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no TTL",
+        ))
+    }
+
+    /// Replicate a collection to another region, making it a global table.
+    /// The replica stays in a creating state while it fills. Only supported
+    /// when
+    /// [`Capabilities::replicas`](super::capabilities::Capabilities::replicas)
+    /// is set.
+    async fn add_replica(&self, _name: &str, _region: &str) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no replicas",
+        ))
+    }
+
+    /// Stop replicating a collection to `region` and delete the replica there.
+    async fn remove_replica(&self, _name: &str, _region: &str) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no replicas",
+        ))
+    }
+
+    /// The on-demand backups of a collection, newest first. Only supported
+    /// when
+    /// [`Capabilities::backups`](super::capabilities::Capabilities::backups)
+    /// is set.
+    async fn list_backups(&self, _name: &str) -> Result<Vec<BackupInfo>> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no backups",
+        ))
+    }
+
+    /// Start an on-demand backup of a collection. The backup may stay in a
+    /// creating state for a while after this returns.
+    async fn create_backup(&self, _name: &str, _backup_name: &str) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no backups",
+        ))
+    }
+
+    /// Delete a backup by [`BackupInfo::id`].
+    async fn delete_backup(&self, _backup_id: &str) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no backups",
+        ))
+    }
+
+    /// Start restoring a backup into a new collection named `target`. The
+    /// collection is created right away and becomes usable once the restore
+    /// finishes; its [`CollectionSchema::status`] tracks progress.
+    async fn restore_backup(&self, _backup_id: &str, _target: &str) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no backups",
+        ))
+    }
+
+    /// Capacity, throttling, and latency metrics of a collection over
+    /// `window`. Only supported when
+    /// [`Capabilities::metrics`](super::capabilities::Capabilities::metrics)
+    /// is set.
+    async fn collection_metrics(
+        &self,
+        _name: &str,
+        _window: MetricsWindow,
+    ) -> Result<CollectionMetrics> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no metrics",
+        ))
+    }
+
+    /// Start creating the collection `spec` from the files in `source`,
+    /// returning the import's id. The collection exists as soon as this
+    /// returns and becomes usable once the import finishes; follow it with
+    /// [`list_imports`](Self::list_imports). Only supported when
+    /// [`Capabilities::imports`](super::capabilities::Capabilities::imports)
+    /// is set.
+    async fn import_collection(
+        &self,
+        _spec: &CreateCollectionSpec,
+        _source: &ImportSource,
+    ) -> Result<String> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no imports",
+        ))
+    }
+
+    /// Recent imports, newest first.
+    async fn list_imports(&self) -> Result<Vec<ImportInfo>> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no imports",
+        ))
+    }
+
     /// The TTL attribute for a collection, if TTL is configured.
     async fn describe_ttl(&self, _name: &str) -> Result<Option<String>> {
         Ok(None)
@@ -85,6 +290,35 @@ pub trait Datastore: Send + Sync {
         ))
     }
 
+    /// Run one page of a statement typed into a collection's query box (e.g. a
+    /// DynamoDB PartiQL `SELECT`/`UPDATE`/`DELETE`). Only supported when
+    /// [`Capabilities::statements`](super::capabilities::Capabilities::statements)
+    /// is set; mutating statements are rejected in read-only mode.
+    async fn execute_statement(&self, _statement: &str, _page: Page) -> Result<QueryResult> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no statement language",
+        ))
+    }
+
+    /// Fetch one page of segment `segment` (zero-based) of a scan split into
+    /// `total_segments`, applying the plan's text filter. Segments page
+    /// independently, so callers can run them concurrently. Only supported
+    /// when
+    /// [`Capabilities::parallel_scan`](super::capabilities::Capabilities::parallel_scan)
+    /// is set.
+    async fn scan_segment(
+        &self,
+        _name: &str,
+        _plan: &QueryPlan,
+        _segment: u32,
+        _total_segments: u32,
+        _page: Page,
+    ) -> Result<QueryResult> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no parallel scan",
+        ))
+    }
+
     /// The query language for the free-form query view, when supported.
     fn raw_query_language(&self) -> Option<&dyn QueryLanguage> {
         None
@@ -95,4 +329,38 @@ pub trait Datastore: Send + Sync {
     async fn schema_hints(&self) -> Result<super::schema::SchemaHints> {
         Ok(super::schema::SchemaHints::default())
     }
+
+    /// The named credentials profiles this backend can switch to. Only
+    /// supported when
+    /// [`Capabilities::profiles`](super::capabilities::Capabilities::profiles)
+    /// is set.
+    async fn list_profiles(&self) -> Result<Vec<ProfileInfo>> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no credentials profiles",
+        ))
+    }
+
+    /// The profile and region the backend is currently connected with.
+    fn active_profile(&self) -> Option<ProfileSelection> {
+        None
+    }
+
+    /// A separate connection to `region`, with the same profile, for listing
+    /// and browsing tables across regions. Only supported when
+    /// [`Capabilities::regions`](super::capabilities::Capabilities::regions)
+    /// is set.
+    async fn for_region(&self, _region: &str) -> Result<Arc<dyn Datastore>> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no regions",
+        ))
+    }
+
+    /// Reconnect with a different profile and/or region. On success every
+    /// holder of this datastore sees the new connection; on failure the
+    /// current connection is kept.
+    async fn switch_profile(&self, _selection: &ProfileSelection) -> Result<()> {
+        Err(super::error::DbError::Unsupported(
+            "this backend has no credentials profiles",
+        ))
+    }
 }
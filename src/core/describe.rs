@@ -0,0 +1,394 @@
+//! A collection's description as text, shared by the table details view and
+//! `dynamate describe`: labelled rows grouped into sections (overview, keys,
+//! capacity, settings, then one per index and replica), and the same details
+//! as JSON for scripts.
+
+use humansize::{BINARY, format_size};
+use serde_json::{Value as JsonValue, json};
+
+use super::schema::{
+    CollectionDetails, IndexDetails, IndexKind, KeyRole, KeySchema, Projection, ReplicaDetails,
+    Throughput,
+};
+
+/// Width of the label column when sections are laid out as text.
+pub const LABEL_WIDTH: usize = 22;
+
+/// What a section describes, for views that select indexes or replicas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionTarget {
+    Index(String),
+    Replica(String),
+}
+
+/// A titled group of label/value rows.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Section {
+    pub title: String,
+    /// The index or replica this section describes, if any.
+    pub target: Option<SectionTarget>,
+    pub rows: Vec<(String, String)>,
+}
+
+/// Group the details into display sections, skipping what the backend didn't
+/// report.
+pub fn sections(details: &CollectionDetails) -> Vec<Section> {
+    let schema = &details.schema;
+    let mut sections = Vec::new();
+
+    let mut overview = Vec::new();
+    push_row(&mut overview, "Status", schema.status.clone());
+    push_row(
+        &mut overview,
+        "Items (approx.)",
+        schema.item_count.map(|n| n.to_string()),
+    );
+    push_row(
+        &mut overview,
+        "Size (approx.)",
+        schema.size_bytes.map(format_bytes),
+    );
+    push_row(&mut overview, "Created", details.created_at.clone());
+    push_row(&mut overview, "ARN", details.resource_id.clone());
+    sections.push(Section {
+        title: "Overview".to_string(),
+        target: None,
+        rows: overview,
+    });
+
+    sections.push(Section {
+        title: "Key schema".to_string(),
+        target: None,
+        rows: key_rows(&schema.key),
+    });
+
+    let mut capacity = Vec::new();
+    push_row(&mut capacity, "Billing mode", details.billing_mode.clone());
+    push_row(
+        &mut capacity,
+        "Provisioned capacity",
+        details.throughput.map(format_throughput),
+    );
+    if !capacity.is_empty() {
+        sections.push(Section {
+            title: "Capacity".to_string(),
+            target: None,
+            rows: capacity,
+        });
+    }
+
+    let mut settings = Vec::new();
+    push_row(&mut settings, "TTL attribute", schema.ttl_attribute.clone());
+    push_row(&mut settings, "Stream", details.stream.clone());
+    push_row(
+        &mut settings,
+        "Point-in-time recovery",
+        details.point_in_time_recovery.map(enabled_label),
+    );
+    push_row(
+        &mut settings,
+        "Deletion protection",
+        details.deletion_protection.map(enabled_label),
+    );
+    if !settings.is_empty() {
+        sections.push(Section {
+            title: "Settings".to_string(),
+            target: None,
+            rows: settings,
+        });
+    }
+
+    for index in &details.indexes {
+        sections.push(Section {
+            title: format!(
+                "{} {}",
+                index_kind_label(index.schema.kind),
+                index.schema.name
+            ),
+            target: Some(SectionTarget::Index(index.schema.name.clone())),
+            rows: index_rows(index),
+        });
+    }
+
+    for replica in &details.replicas {
+        sections.push(Section {
+            title: format!("Replica {}", replica.region),
+            target: Some(SectionTarget::Replica(replica.region.clone())),
+            rows: replica_rows(replica),
+        });
+    }
+    sections
+}
+
+fn replica_rows(replica: &ReplicaDetails) -> Vec<(String, String)> {
+    let status = replica
+        .status
+        .clone()
+        .map(|status| match &replica.progress {
+            Some(progress) => format!("{status} ({progress})"),
+            None => status,
+        });
+    let mut rows = Vec::new();
+    push_row(&mut rows, "Status", status);
+    rows
+}
+
+fn index_rows(index: &IndexDetails) -> Vec<(String, String)> {
+    let mut rows = key_rows(&index.schema.key);
+    rows.push((
+        "Projection".to_string(),
+        match &index.schema.projection {
+            Projection::All => "all".to_string(),
+            Projection::KeysOnly => "keys only".to_string(),
+            Projection::Include(attrs) => format!("include {}", attrs.join(", ")),
+        },
+    ));
+    let status = index.status.clone().map(|status| {
+        if index.backfilling {
+            format!("{status} (backfilling)")
+        } else {
+            status
+        }
+    });
+    push_row(&mut rows, "Status", status);
+    push_row(
+        &mut rows,
+        "Items (approx.)",
+        index.item_count.map(|n| n.to_string()),
+    );
+    push_row(
+        &mut rows,
+        "Size (approx.)",
+        index.size_bytes.map(format_bytes),
+    );
+    push_row(
+        &mut rows,
+        "Provisioned capacity",
+        index.throughput.map(format_throughput),
+    );
+    rows
+}
+
+fn key_rows(key: &KeySchema) -> Vec<(String, String)> {
+    key.fields
+        .iter()
+        .map(|field| {
+            let role = match field.role {
+                KeyRole::Partition => "Partition key",
+                KeyRole::Sort => "Sort key",
+            };
+            let ty = format!("{:?}", field.ty).to_lowercase();
+            (role.to_string(), format!("{} ({ty})", field.name))
+        })
+        .collect()
+}
+
+fn push_row(rows: &mut Vec<(String, String)>, label: &str, value: Option<String>) {
+    if let Some(value) = value {
+        rows.push((label.to_string(), value));
+    }
+}
+
+fn index_kind_label(kind: IndexKind) -> &'static str {
+    match kind {
+        IndexKind::GlobalSecondary => "GSI",
+        IndexKind::LocalSecondary => "LSI",
+        IndexKind::Secondary | IndexKind::Composite => "Index",
+    }
+}
+
+fn format_throughput(throughput: Throughput) -> String {
+    format!("{} RCU / {} WCU", throughput.read, throughput.write)
+}
+
+fn format_bytes(size: i64) -> String {
+    u64::try_from(size).map_or_else(|_| "—".to_string(), |value| format_size(value, BINARY))
+}
+
+fn enabled_label(enabled: bool) -> String {
+    if enabled { "enabled" } else { "disabled" }.to_string()
+}
+
+/// The sections as plain text: each title followed by its indented rows,
+/// with a blank line between sections.
+pub fn text(sections: &[Section]) -> String {
+    let mut out = String::new();
+    for (idx, section) in sections.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        out.push_str(&section.title);
+        out.push('\n');
+        for (label, value) in &section.rows {
+            out.push_str(&format!("   {label:<LABEL_WIDTH$}{value}\n"));
+        }
+    }
+    out
+}
+
+/// The details as a JSON object. Values the backend didn't report are `null`.
+pub fn to_json(details: &CollectionDetails) -> JsonValue {
+    let schema = &details.schema;
+    json!({
+        "name": schema.name,
+        "status": schema.status,
+        "item_count": schema.item_count,
+        "size_bytes": schema.size_bytes,
+        "created_at": details.created_at,
+        "resource_id": details.resource_id,
+        "key_schema": key_json(&schema.key),
+        "billing_mode": details.billing_mode,
+        "provisioned_throughput": details.throughput.map(throughput_json),
+        "ttl_attribute": schema.ttl_attribute,
+        "stream": details.stream,
+        "point_in_time_recovery": details.point_in_time_recovery,
+        "deletion_protection": details.deletion_protection,
+        "indexes": details.indexes.iter().map(index_json).collect::<Vec<_>>(),
+        "replicas": details
+            .replicas
+            .iter()
+            .map(|replica| json!({
+                "region": replica.region,
+                "status": replica.status,
+                "progress": replica.progress,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn index_json(index: &IndexDetails) -> JsonValue {
+    let projection = match &index.schema.projection {
+        Projection::All => json!("all"),
+        Projection::KeysOnly => json!("keys_only"),
+        Projection::Include(attrs) => json!({ "include": attrs }),
+    };
+    let kind = match index.schema.kind {
+        IndexKind::GlobalSecondary => "global_secondary",
+        IndexKind::LocalSecondary => "local_secondary",
+        IndexKind::Secondary => "secondary",
+        IndexKind::Composite => "composite",
+    };
+    json!({
+        "name": index.schema.name,
+        "kind": kind,
+        "key_schema": key_json(&index.schema.key),
+        "projection": projection,
+        "status": index.status,
+        "backfilling": index.backfilling,
+        "item_count": index.item_count,
+        "size_bytes": index.size_bytes,
+        "provisioned_throughput": index.throughput.map(throughput_json),
+    })
+}
+
+fn key_json(key: &KeySchema) -> JsonValue {
+    key.fields
+        .iter()
+        .map(|field| {
+            let role = match field.role {
+                KeyRole::Partition => "partition",
+                KeyRole::Sort => "sort",
+            };
+            json!({
+                "name": field.name,
+                "role": role,
+                "type": format!("{:?}", field.ty).to_lowercase(),
+            })
+        })
+        .collect()
+}
+
+fn throughput_json(throughput: Throughput) -> JsonValue {
+    json!({ "read": throughput.read, "write": throughput.write })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::schema::{
+        CollectionDetails, CollectionSchema, IndexKind, IndexSchema, KeyField, KeyRole, KeySchema,
+        Projection, ScalarType, Throughput,
+    };
+
+    use super::{sections, text, to_json};
+
+    fn key(name: &str, role: KeyRole) -> KeyField {
+        KeyField {
+            name: name.to_string(),
+            role,
+            ty: ScalarType::String,
+        }
+    }
+
+    fn details() -> CollectionDetails {
+        let schema = CollectionSchema {
+            name: "orders".to_string(),
+            key: KeySchema {
+                fields: vec![key("PK", KeyRole::Partition), key("SK", KeyRole::Sort)],
+            },
+            indexes: vec![IndexSchema {
+                name: "byStatus".to_string(),
+                kind: IndexKind::GlobalSecondary,
+                key: KeySchema {
+                    fields: vec![key("status", KeyRole::Partition)],
+                },
+                projection: Projection::Include(vec!["total".to_string()]),
+            }],
+            status: Some("ACTIVE".to_string()),
+            item_count: Some(12),
+            ..CollectionSchema::default()
+        };
+        let mut details = CollectionDetails::from(schema);
+        details.billing_mode = Some("PROVISIONED".to_string());
+        details.throughput = Some(Throughput { read: 5, write: 2 });
+        details.point_in_time_recovery = Some(true);
+        details.indexes[0].status = Some("CREATING".to_string());
+        details.indexes[0].backfilling = true;
+        details
+    }
+
+    #[test]
+    fn sections_cover_keys_capacity_and_indexes() {
+        let sections = sections(&details());
+        let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Overview",
+                "Key schema",
+                "Capacity",
+                "Settings",
+                "GSI byStatus"
+            ]
+        );
+        assert_eq!(
+            sections[1].rows,
+            vec![
+                ("Partition key".to_string(), "PK (string)".to_string()),
+                ("Sort key".to_string(), "SK (string)".to_string()),
+            ]
+        );
+        assert!(sections[2].rows.contains(&(
+            "Provisioned capacity".to_string(),
+            "5 RCU / 2 WCU".to_string()
+        )));
+        let index_rows = &sections[4].rows;
+        assert!(index_rows.contains(&("Projection".to_string(), "include total".to_string())));
+        assert!(index_rows.contains(&("Status".to_string(), "CREATING (backfilling)".to_string())));
+
+        let text = text(&sections);
+        assert!(text.starts_with("Overview\n   Status                ACTIVE\n"));
+        assert!(text.contains("\n\nKey schema\n   Partition key         PK (string)\n"));
+    }
+
+    #[test]
+    fn json_reports_unknown_values_as_null() {
+        let json = to_json(&details());
+        assert_eq!(json["name"], "orders");
+        assert_eq!(json["key_schema"][1]["role"], "sort");
+        assert_eq!(json["provisioned_throughput"]["read"], 5);
+        assert_eq!(json["point_in_time_recovery"], true);
+        assert!(json["ttl_attribute"].is_null());
+        assert_eq!(json["indexes"][0]["kind"], "global_secondary");
+        assert_eq!(json["indexes"][0]["projection"]["include"][0], "total");
+    }
+}
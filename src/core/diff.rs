@@ -0,0 +1,284 @@
+//! Comparing two sets of items by primary key, for the query view's diff of
+//! its results against a saved export or another table.
+//!
+//! Items are matched on their key attributes. Each one then lands in exactly
+//! one bucket: added (only in the newer set), removed (only in the older
+//! set), changed (in both, with different attributes), or unchanged.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value as Json};
+
+use super::json::{item_from_json, item_from_typed_json, item_to_typed_json, value_to_typed_json};
+use super::key_list::item_key_text;
+use super::query::Key;
+use super::schema::KeySchema;
+use super::value::{Item, Value};
+
+/// One attribute that differs between the two versions of an item. A `None`
+/// side means the attribute is missing there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeChange {
+    pub name: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedItem {
+    pub key: Key,
+    pub before: Item,
+    pub after: Item,
+    pub changes: Vec<AttributeChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemDiff {
+    pub added: Vec<Item>,
+    pub removed: Vec<Item>,
+    pub changed: Vec<ChangedItem>,
+    pub unchanged: usize,
+}
+
+impl ItemDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// The diff as one JSON document, with items in DynamoDB JSON so every
+    /// value type survives.
+    pub fn to_json(&self) -> Json {
+        let items = |items: &[Item]| {
+            Json::Array(
+                items
+                    .iter()
+                    .filter_map(|item| item_to_typed_json(item).ok())
+                    .collect(),
+            )
+        };
+        let value = |value: Option<&Value>| value.map_or(Json::Null, value_to_typed_json);
+        let changed = self
+            .changed
+            .iter()
+            .map(|changed| {
+                let changes = changed
+                    .changes
+                    .iter()
+                    .map(|change| {
+                        let mut object = Map::new();
+                        object.insert("attribute".to_string(), Json::from(change.name.clone()));
+                        object.insert("before".to_string(), value(change.before.as_ref()));
+                        object.insert("after".to_string(), value(change.after.as_ref()));
+                        Json::Object(object)
+                    })
+                    .collect();
+                let mut object = Map::new();
+                object.insert(
+                    "key".to_string(),
+                    item_to_typed_json(&changed.key.0).unwrap_or(Json::Null),
+                );
+                object.insert("changes".to_string(), Json::Array(changes));
+                Json::Object(object)
+            })
+            .collect();
+        let mut object = Map::new();
+        object.insert("added".to_string(), items(&self.added));
+        object.insert("removed".to_string(), items(&self.removed));
+        object.insert("changed".to_string(), Json::Array(changed));
+        object.insert("unchanged".to_string(), Json::from(self.unchanged));
+        Json::Object(object)
+    }
+}
+
+/// Compare `before` (the older set) with `after`. Added and changed items
+/// follow the order of `after`; removed items the order of `before`. Fails
+/// when an item lacks a key attribute.
+pub fn diff_items(
+    before: Vec<Item>,
+    after: Vec<Item>,
+    schema: &KeySchema,
+) -> Result<ItemDiff, String> {
+    if schema.fields.is_empty() {
+        return Err("The table has no key attributes to match items on".to_string());
+    }
+    let mut old: HashMap<String, Item> = HashMap::with_capacity(before.len());
+    let mut old_order = Vec::with_capacity(before.len());
+    for item in before {
+        let text = checked_key_text(&item, schema)?;
+        old_order.push(text.clone());
+        old.insert(text, item);
+    }
+    let mut diff = ItemDiff::default();
+    for item in after {
+        let text = checked_key_text(&item, schema)?;
+        match old.remove(&text) {
+            None => diff.added.push(item),
+            Some(previous) => {
+                let changes = attribute_changes(&previous, &item);
+                if changes.is_empty() {
+                    diff.unchanged += 1;
+                } else {
+                    diff.changed.push(ChangedItem {
+                        key: key_of(&item, schema),
+                        before: previous,
+                        after: item,
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    diff.removed = old_order
+        .iter()
+        .filter_map(|text| old.remove(text))
+        .collect();
+    Ok(diff)
+}
+
+/// The attributes that differ between two versions of an item: those in
+/// `before` in their order, then the ones only `after` has.
+pub fn attribute_changes(before: &Item, after: &Item) -> Vec<AttributeChange> {
+    let mut changes: Vec<AttributeChange> = before
+        .iter()
+        .filter(|(name, value)| after.get(*name) != Some(*value))
+        .map(|(name, value)| AttributeChange {
+            name: name.clone(),
+            before: Some(value.clone()),
+            after: after.get(name).cloned(),
+        })
+        .collect();
+    changes.extend(
+        after
+            .iter()
+            .filter(|(name, _)| !before.contains_key(*name))
+            .map(|(name, value)| AttributeChange {
+                name: name.clone(),
+                before: None,
+                after: Some(value.clone()),
+            }),
+    );
+    changes
+}
+
+/// The items of a saved export: a JSON array or NDJSON, each item in plain
+/// JSON or DynamoDB JSON.
+pub fn parse_snapshot(text: &str) -> Result<Vec<Item>, String> {
+    let trimmed = text.trim();
+    let values = match serde_json::from_str::<Json>(trimmed) {
+        Ok(Json::Array(values)) => values,
+        Ok(value) => vec![value],
+        Err(_) => trimmed
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str(line).map_err(|err| format!("line {}: {err}", idx + 1))
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    values
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            item_from_typed_json(value)
+                .or_else(|_| item_from_json(value))
+                .map_err(|err| format!("item {}: {err}", idx + 1))
+        })
+        .collect()
+}
+
+fn checked_key_text(item: &Item, schema: &KeySchema) -> Result<String, String> {
+    if let Some(field) = schema
+        .fields
+        .iter()
+        .find(|field| !item.contains_key(&field.name))
+    {
+        return Err(format!("An item is missing key attribute {}", field.name));
+    }
+    Ok(item_key_text(item, schema))
+}
+
+fn key_of(item: &Item, schema: &KeySchema) -> Key {
+    Key(schema
+        .fields
+        .iter()
+        .filter_map(|field| {
+            item.get(&field.name)
+                .map(|value| (field.name.clone(), value.clone()))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AttributeChange, diff_items, parse_snapshot};
+    use crate::core::schema::{KeyField, KeyRole, KeySchema, ScalarType};
+    use crate::core::value::{Item, Number, Value};
+
+    fn item(id: &str, pairs: &[(&str, Value)]) -> Item {
+        let mut item = Item::from([("id".to_string(), Value::from(id))]);
+        for (name, value) in pairs {
+            item.insert((*name).to_string(), value.clone());
+        }
+        item
+    }
+
+    #[test]
+    fn sorts_items_into_added_removed_and_changed() {
+        let schema = KeySchema {
+            fields: vec![KeyField {
+                name: "id".to_string(),
+                role: KeyRole::Partition,
+                ty: ScalarType::String,
+            }],
+        };
+        let before = vec![
+            item("a", &[("n", Value::Num(Number::new("1")))]),
+            item("b", &[("s", Value::from("x"))]),
+            item("c", &[]),
+        ];
+        let after = vec![
+            item("c", &[]),
+            item("b", &[("t", Value::from("y"))]),
+            item("d", &[]),
+        ];
+        let diff = diff_items(before, after, &schema).unwrap();
+        assert_eq!(diff.added, vec![item("d", &[])]);
+        assert_eq!(
+            diff.removed,
+            vec![item("a", &[("n", Value::Num(Number::new("1")))])]
+        );
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![
+                AttributeChange {
+                    name: "s".to_string(),
+                    before: Some(Value::from("x")),
+                    after: None,
+                },
+                AttributeChange {
+                    name: "t".to_string(),
+                    before: None,
+                    after: Some(Value::from("y")),
+                },
+            ]
+        );
+        assert!(diff_items(vec![Item::new()], Vec::new(), &schema).is_err());
+    }
+
+    #[test]
+    fn snapshots_are_json_arrays_or_ndjson() {
+        let expected = vec![item("a", &[]), item("b", &[])];
+        assert_eq!(
+            parse_snapshot(r#"[{"id": "a"}, {"id": {"S": "b"}}]"#).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_snapshot("{\"id\": \"a\"}\n\n{\"id\": \"b\"}\n").unwrap(),
+            expected
+        );
+        assert!(parse_snapshot("{\"id\": \"a\"}\nnot json").is_err());
+    }
+}
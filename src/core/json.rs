@@ -83,7 +83,8 @@ pub fn item_from_json_string(input: &str) -> Result<Item> {
     item_from_json(&value)
 }
 
-fn value_to_json(value: &Value) -> Result<Json> {
+/// One value in standard JSON.
+pub fn value_to_json(value: &Value) -> Result<Json> {
     match value {
         Value::Bool(b) => Ok(Json::Bool(*b)),
         Value::Str(s) => Ok(Json::String(s.clone())),
@@ -165,7 +166,8 @@ pub fn item_from_typed_json_string(input: &str) -> Result<Item> {
     item_from_typed_json(&value)
 }
 
-fn value_to_typed_json(value: &Value) -> Json {
+/// One value in the typed encoding, e.g. `{"N": "1"}`.
+pub fn value_to_typed_json(value: &Value) -> Json {
     match value {
         Value::Str(s) => tagged("S", Json::String(s.clone())),
         Value::Num(n) => tagged("N", Json::String(n.as_str().to_string())),
@@ -0,0 +1,212 @@
+//! Parsing lists of primary keys, for batch lookups (`dynamate get` and the
+//! query view's get-by-keys popup).
+//!
+//! A list is a JSON array, or one entry per line (NDJSON). Each entry is a key
+//! object in plain or DynamoDB JSON; attributes outside the key are dropped,
+//! so exported items work as keys too. Collections keyed by a single
+//! attribute also take bare values: JSON scalars, or plain text with one value
+//! per line (or comma-separated on a single line). Repeated keys are kept once.
+
+use std::collections::HashSet;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use serde_json::Value as Json;
+
+use super::json::{item_from_json, item_from_typed_json};
+use super::query::Key;
+use super::schema::{KeyField, KeySchema, ScalarType};
+use super::value::{Item, Number, Value};
+
+/// The distinct keys in `input`, in the order they first appear.
+pub fn parse_key_list(input: &str, schema: &KeySchema) -> Result<Vec<Key>, String> {
+    if schema.fields.is_empty() {
+        return Err("The table has no key attributes".to_string());
+    }
+    let mut keys = Vec::new();
+    let mut seen = HashSet::new();
+    for (position, entry) in entries(input).into_iter().enumerate() {
+        let key =
+            entry_key(entry, schema).map_err(|err| format!("entry {}: {err}", position + 1))?;
+        if seen.insert(key_text(&key, schema)) {
+            keys.push(key);
+        }
+    }
+    if keys.is_empty() {
+        return Err("No keys given".to_string());
+    }
+    Ok(keys)
+}
+
+/// A key's values in schema order, for matching items back to the keys that
+/// fetched them.
+pub fn key_text(key: &Key, schema: &KeySchema) -> String {
+    item_key_text(&key.0, schema)
+}
+
+/// [`key_text`] for the key attributes of a full item.
+pub fn item_key_text(item: &Item, schema: &KeySchema) -> String {
+    schema
+        .fields
+        .iter()
+        .map(|field| match item.get(&field.name) {
+            // DynamoDB normalizes numbers, so `1.50` comes back as `1.5`.
+            Some(Value::Num(number)) => match number.as_f64() {
+                Some(parsed) => format!("N:{parsed}"),
+                None => format!("N:{number}"),
+            },
+            other => format!("{other:?}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\u{1f}")
+}
+
+enum Entry {
+    Json(Json),
+    Text(String),
+}
+
+fn entries(input: &str) -> Vec<Entry> {
+    let trimmed = input.trim();
+    if let Ok(value) = serde_json::from_str::<Json>(trimmed) {
+        return match value {
+            Json::Array(values) => values.into_iter().map(Entry::Json).collect(),
+            other => vec![Entry::Json(other)],
+        };
+    }
+    let lines: Vec<&str> = trimmed
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let lines = match lines.as_slice() {
+        [line] if serde_json::from_str::<Json>(line).is_err() => line.split(',').collect(),
+        _ => lines,
+    };
+    lines
+        .into_iter()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match serde_json::from_str::<Json>(line) {
+            Ok(value) => Entry::Json(value),
+            Err(_) => Entry::Text(line.to_string()),
+        })
+        .collect()
+}
+
+fn entry_key(entry: Entry, schema: &KeySchema) -> Result<Key, String> {
+    let text = match entry {
+        Entry::Json(value @ Json::Object(_)) => return object_key(&value, schema),
+        Entry::Json(Json::String(text)) | Entry::Text(text) => text,
+        Entry::Json(Json::Number(number)) => number.to_string(),
+        Entry::Json(other) => return Err(format!("expected a key, got {other}")),
+    };
+    let [field] = schema.fields.as_slice() else {
+        let names: Vec<&str> = schema.fields.iter().map(|f| f.name.as_str()).collect();
+        return Err(format!(
+            "a bare value can't be a key; give an object with {}",
+            names.join(" and ")
+        ));
+    };
+    let value = scalar_value(&text, field)?;
+    Ok(Key(Item::from([(field.name.clone(), value)])))
+}
+
+/// The key attributes of a JSON object, read as DynamoDB JSON when every
+/// value is type-tagged and as plain JSON otherwise.
+fn object_key(value: &Json, schema: &KeySchema) -> Result<Key, String> {
+    let item = item_from_typed_json(value)
+        .or_else(|_| item_from_json(value))
+        .map_err(|err| err.to_string())?;
+    let mut key = Item::new();
+    for field in &schema.fields {
+        let value = item
+            .get(&field.name)
+            .ok_or_else(|| format!("missing key attribute {}", field.name))?;
+        key.insert(field.name.clone(), value.clone());
+    }
+    Ok(Key(key))
+}
+
+fn scalar_value(text: &str, field: &KeyField) -> Result<Value, String> {
+    match field.ty {
+        ScalarType::String => Ok(Value::Str(text.to_string())),
+        ScalarType::Number => text
+            .parse::<f64>()
+            .map(|_| Value::Num(Number::new(text)))
+            .map_err(|_| format!("{} is a number key; got {text}", field.name)),
+        ScalarType::Binary => BASE64
+            .decode(text)
+            .map(Value::Bytes)
+            .map_err(|_| format!("{} is a binary key; expected base64", field.name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_key_list;
+    use crate::core::query::Key;
+    use crate::core::schema::{KeyField, KeyRole, KeySchema, ScalarType};
+    use crate::core::value::{Item, Number, Value};
+
+    fn schema(fields: &[(&str, KeyRole, ScalarType)]) -> KeySchema {
+        KeySchema {
+            fields: fields
+                .iter()
+                .map(|(name, role, ty)| KeyField {
+                    name: (*name).to_string(),
+                    role: *role,
+                    ty: *ty,
+                })
+                .collect(),
+        }
+    }
+
+    fn key(pairs: &[(&str, Value)]) -> Key {
+        Key(pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), value.clone()))
+            .collect::<Item>())
+    }
+
+    #[test]
+    fn reads_json_arrays_and_ndjson_of_plain_or_typed_keys() {
+        let schema = schema(&[
+            ("pk", KeyRole::Partition, ScalarType::String),
+            ("sk", KeyRole::Sort, ScalarType::Number),
+        ]);
+        let expected = vec![
+            key(&[
+                ("pk", Value::from("a")),
+                ("sk", Value::Num(Number::new("1"))),
+            ]),
+            key(&[
+                ("pk", Value::from("b")),
+                ("sk", Value::Num(Number::new("2"))),
+            ]),
+        ];
+        let array =
+            r#"[{"pk": "a", "sk": 1, "extra": true}, {"pk": {"S": "b"}, "sk": {"N": "2"}}]"#;
+        assert_eq!(parse_key_list(array, &schema).unwrap(), expected);
+        let ndjson =
+            "{\"pk\": \"a\", \"sk\": 1}\n\n{\"pk\": \"b\", \"sk\": 2}\n{\"pk\": \"a\", \"sk\": 1}";
+        assert_eq!(parse_key_list(ndjson, &schema).unwrap(), expected);
+
+        let err = parse_key_list(r#"[{"pk": "a"}]"#, &schema).unwrap_err();
+        assert_eq!(err, "entry 1: missing key attribute sk");
+        assert!(parse_key_list("a\nb", &schema).is_err());
+    }
+
+    #[test]
+    fn single_attribute_keys_take_bare_values() {
+        let schema = schema(&[("id", KeyRole::Partition, ScalarType::Number)]);
+        let expected = vec![
+            key(&[("id", Value::Num(Number::new("1")))]),
+            key(&[("id", Value::Num(Number::new("22")))]),
+        ];
+        assert_eq!(parse_key_list("1\n22\n", &schema).unwrap(), expected);
+        assert_eq!(parse_key_list("1, 22, 1", &schema).unwrap(), expected);
+        assert_eq!(parse_key_list("[1, \"22\"]", &schema).unwrap(), expected);
+        assert!(parse_key_list("x", &schema).is_err());
+        assert!(parse_key_list("  ", &schema).is_err());
+    }
+}
@@ -11,8 +11,9 @@
 //!
 //! [`Datastore::query_language`]: super::datastore::Datastore::query_language
 
-use super::query::PlanKind;
+use super::query::{ItemUpdate, PlanKind};
 use super::schema::{CollectionSchema, SchemaHints};
+use super::value::Item;
 
 /// Byte offsets into the input string delimiting the token under the cursor
 /// (the span a chosen suggestion replaces).
@@ -88,6 +89,97 @@ pub struct ReferenceSection {
     pub entries: Vec<(String, String)>,
 }
 
+/// A comparison offered by the query builder popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionOp {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    BeginsWith,
+    Contains,
+    Exists,
+    NotExists,
+}
+
+impl ConditionOp {
+    /// Every operator, in the order the builder cycles through them.
+    pub const ALL: [ConditionOp; 10] = [
+        ConditionOp::Equal,
+        ConditionOp::NotEqual,
+        ConditionOp::Less,
+        ConditionOp::LessOrEqual,
+        ConditionOp::Greater,
+        ConditionOp::GreaterOrEqual,
+        ConditionOp::BeginsWith,
+        ConditionOp::Contains,
+        ConditionOp::Exists,
+        ConditionOp::NotExists,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConditionOp::Equal => "=",
+            ConditionOp::NotEqual => "≠",
+            ConditionOp::Less => "<",
+            ConditionOp::LessOrEqual => "≤",
+            ConditionOp::Greater => ">",
+            ConditionOp::GreaterOrEqual => "≥",
+            ConditionOp::BeginsWith => "begins with",
+            ConditionOp::Contains => "contains",
+            ConditionOp::Exists => "exists",
+            ConditionOp::NotExists => "does not exist",
+        }
+    }
+
+    /// Whether the operator compares against a value.
+    pub fn takes_value(self) -> bool {
+        !matches!(self, ConditionOp::Exists | ConditionOp::NotExists)
+    }
+}
+
+/// A value typed into the query builder, with its type inferred the way the
+/// languages' own literals read: numbers and `true`/`false` unquoted,
+/// anything else (or anything in double quotes) a string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionValue {
+    String(String),
+    /// The number as typed, so languages can write it back verbatim.
+    Number(String),
+    Boolean(bool),
+}
+
+impl ConditionValue {
+    pub fn infer(text: &str) -> Self {
+        let text = text.trim();
+        if let Some(quoted) = text
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+        {
+            return ConditionValue::String(quoted.to_string());
+        }
+        match text {
+            "true" => ConditionValue::Boolean(true),
+            "false" => ConditionValue::Boolean(false),
+            _ if !text.is_empty() && text.parse::<f64>().is_ok_and(f64::is_finite) => {
+                ConditionValue::Number(text.to_string())
+            }
+            _ => ConditionValue::String(text.to_string()),
+        }
+    }
+}
+
+/// One row of the query builder: `attribute op value`, with `value` ignored
+/// by operators that take none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub attribute: String,
+    pub op: ConditionOp,
+    pub value: ConditionValue,
+}
+
 /// A backend's query language: parsing/validation, autocompletion, summarizing,
 /// and reference docs. All methods are synchronous and pure (no I/O) so the UI
 /// can call them on the render/keystroke path.
@@ -106,4 +198,51 @@ pub trait QueryLanguage: Send + Sync {
 
     /// Content for the in-app reference popup.
     fn reference(&self) -> Vec<ReferenceSection>;
+
+    /// The `:name` parameters in the query text that need values (passed in
+    /// [`QueryPlan::parameters`](super::query::QueryPlan::parameters)) before
+    /// it can run. Languages without parameters return nothing.
+    fn parameters(&self, _text: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Write the query builder's conditions, all of which must match, as query
+    /// text. No conditions match everything.
+    fn build_query(&self, _conditions: &[Condition]) -> Result<String, String> {
+        Err("this query language has no query builder".to_string())
+    }
+
+    /// `text` narrowed to the rows that also match all of `conditions`, to run
+    /// the local row filter on the server instead. Languages that can't
+    /// combine queries only narrow an empty one.
+    fn narrow_query(&self, text: &str, conditions: &[Condition]) -> Result<String, String> {
+        if text.trim().is_empty() {
+            self.build_query(conditions)
+        } else {
+            Err("this query language can't add conditions to a query".to_string())
+        }
+    }
+
+    /// An example update for the bulk-update prompt, or `None` when the
+    /// language can't express updates.
+    fn update_placeholder(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Parse the bulk-update prompt's text into attribute changes.
+    fn parse_update(&self, _text: &str) -> Result<ItemUpdate, String> {
+        Err("this query language has no update expressions".to_string())
+    }
+
+    /// `item` as it reads after `update`, for previews. Languages whose
+    /// updates have an `expression` work it out the way their backend would.
+    fn apply_update(&self, update: &ItemUpdate, item: &Item) -> Result<Item, String> {
+        Ok(update.apply(item))
+    }
+
+    /// The top-level attributes `update` writes or removes, so key
+    /// attributes can be refused up front.
+    fn updated_attributes(&self, update: &ItemUpdate) -> Vec<String> {
+        update.set.keys().chain(&update.remove).cloned().collect()
+    }
 }
@@ -0,0 +1,195 @@
+//! Capacity and request metrics of one collection over a recent window, for
+//! the metrics view. Backends fetch the samples; [`MetricSeries::from_samples`]
+//! lines them up one value per period so every series shares a time axis.
+
+use std::time::Duration;
+
+/// How far back the metrics view looks, and how coarse its periods are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetricsWindow {
+    Hour,
+    #[default]
+    ThreeHours,
+    Day,
+}
+
+impl MetricsWindow {
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::Hour => Duration::from_hours(1),
+            Self::ThreeHours => Duration::from_hours(3),
+            Self::Day => Duration::from_hours(24),
+        }
+    }
+
+    /// The length of one point in the series.
+    pub fn period(self) -> Duration {
+        match self {
+            Self::Hour => Duration::from_mins(1),
+            Self::ThreeHours => Duration::from_mins(5),
+            Self::Day => Duration::from_mins(15),
+        }
+    }
+
+    /// Points in a series covering the window.
+    pub fn points(self) -> usize {
+        (self.duration().as_secs() / self.period().as_secs()) as usize
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Hour => "1h",
+            Self::ThreeHours => "3h",
+            Self::Day => "24h",
+        }
+    }
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Hour => Self::ThreeHours,
+            Self::ThreeHours => Self::Day,
+            Self::Day => Self::Hour,
+        }
+    }
+}
+
+/// The metrics the view shows, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Read capacity units consumed per period.
+    ConsumedRead,
+    /// Write capacity units consumed per period.
+    ConsumedWrite,
+    /// Requests rejected for exceeding throughput, across operations.
+    Throttled,
+    /// Average latency of successful requests, in milliseconds.
+    Latency,
+}
+
+impl MetricKind {
+    pub const ALL: [Self; 4] = [
+        Self::ConsumedRead,
+        Self::ConsumedWrite,
+        Self::Throttled,
+        Self::Latency,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ConsumedRead => "Consumed read capacity",
+            Self::ConsumedWrite => "Consumed write capacity",
+            Self::Throttled => "Throttled requests",
+            Self::Latency => "Request latency",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::ConsumedRead => "RCU",
+            Self::ConsumedWrite => "WCU",
+            Self::Throttled => "requests",
+            Self::Latency => "ms",
+        }
+    }
+
+    /// Whether a period with nothing reported means none happened (a count),
+    /// rather than there being nothing to measure (an average).
+    pub fn is_count(self) -> bool {
+        !matches!(self, Self::Latency)
+    }
+}
+
+/// One metric over the window, oldest period first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSeries {
+    pub kind: MetricKind,
+    /// `None` where an average had nothing to measure.
+    pub points: Vec<Option<f64>>,
+}
+
+impl MetricSeries {
+    /// Line up `samples` (period start in Unix seconds, value) on the
+    /// `window`'s periods counted from `start`. Samples outside the window
+    /// are dropped; periods without one are zero for counts and `None`
+    /// otherwise.
+    pub fn from_samples(
+        kind: MetricKind,
+        window: MetricsWindow,
+        start: i64,
+        samples: impl IntoIterator<Item = (i64, f64)>,
+    ) -> Self {
+        let empty = kind.is_count().then_some(0.0);
+        let mut points = vec![empty; window.points()];
+        let period = window.period().as_secs() as i64;
+        for (at, value) in samples {
+            let offset = at - start;
+            if offset < 0 {
+                continue;
+            }
+            if let Some(point) = points.get_mut((offset / period) as usize) {
+                *point = Some(value);
+            }
+        }
+        Self { kind, points }
+    }
+
+    /// The most recent reported value.
+    pub fn latest(&self) -> Option<f64> {
+        self.points.iter().rev().find_map(|point| *point)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.points.iter().flatten().copied().reduce(f64::max)
+    }
+
+    /// The average over the periods that reported a value.
+    pub fn average(&self) -> Option<f64> {
+        let values: Vec<f64> = self.points.iter().flatten().copied().collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// The metrics of one collection, as the metrics view shows them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionMetrics {
+    pub window: MetricsWindow,
+    /// Unix seconds at the start of the first period.
+    pub start: i64,
+    /// One series per [`MetricKind::ALL`], in that order.
+    pub series: Vec<MetricSeries>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MetricKind, MetricSeries, MetricsWindow};
+
+    #[test]
+    fn samples_land_on_their_period() {
+        let window = MetricsWindow::Hour;
+        assert_eq!(window.points(), 60);
+        let series = MetricSeries::from_samples(
+            MetricKind::ConsumedRead,
+            window,
+            1_000,
+            [(1_000, 4.0), (1_120, 2.0), (900, 9.0), (1_000 + 3_600, 9.0)],
+        );
+        assert_eq!(series.points.len(), 60);
+        assert_eq!(&series.points[..3], &[Some(4.0), Some(0.0), Some(2.0)]);
+        assert_eq!(series.latest(), Some(0.0));
+        assert_eq!(series.max(), Some(4.0));
+
+        let series = MetricSeries::from_samples(MetricKind::Latency, window, 1_000, [(1_060, 3.0)]);
+        assert_eq!(&series.points[..2], &[None, Some(3.0)]);
+        assert_eq!(series.latest(), Some(3.0));
+        assert_eq!(series.average(), Some(3.0));
+        assert_eq!(
+            MetricSeries {
+                points: vec![None],
+                ..series
+            }
+            .average(),
+            None
+        );
+    }
+}
@@ -10,13 +10,27 @@
 //! - `datastore`, `schema`, `query`, `dialect`, `capabilities`, `connect`
 //!   are added in later phases.
 
+pub mod analyze;
+pub mod audit;
+pub mod bulk_update;
 pub mod capabilities;
 pub mod connect;
+pub mod copy;
+pub mod count;
 pub mod datastore;
+pub mod describe;
+pub mod diff;
 pub mod error;
 pub mod json;
+pub mod key_list;
 pub mod language;
+pub mod metrics;
+pub mod purge;
 pub mod query;
+pub mod rename;
+pub mod scan_cost;
 pub mod schema;
+pub mod seed;
 pub mod size;
+pub mod table_spec;
 pub mod value;
@@ -0,0 +1,210 @@
+//! Deleting every item a plan matches, for the table picker's purge action
+//! and the query view's delete-matching action.
+//!
+//! The scan is split into segments that run concurrently when the backend
+//! supports parallel scans; each segment deletes its pages in batches before
+//! fetching the next, so at most one batch per segment is in flight. A
+//! segment that fails stops on its own while the others carry on, and its
+//! error ends up in the [`PurgeSummary`]. Running a purge again picks up
+//! whatever is left.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+
+use super::datastore::Datastore;
+use super::error::{DbError, Result};
+use super::query::{Cursor, Key, Page, QueryPlan};
+use super::value::Item;
+
+/// Keys sent per `batch_delete` call.
+const BATCH_SIZE: usize = 25;
+/// Items fetched per page. A page is deleted before the next is fetched.
+const PAGE_SIZE: u32 = 100;
+
+/// How a purge runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PurgeOptions {
+    /// Parallel scan segments; 1 pages through the plan sequentially.
+    pub segments: u32,
+    /// Count the matching items without deleting them.
+    pub dry_run: bool,
+}
+
+/// How far a purge has got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PurgeProgress {
+    /// Items deleted, or in a dry run the items that would be.
+    pub deleted: u64,
+    /// Items read, matching the plan or not.
+    pub scanned: u64,
+}
+
+/// How a purge ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurgeSummary {
+    pub progress: PurgeProgress,
+    pub dry_run: bool,
+    /// Stopped through the cancel flag before every segment finished.
+    pub cancelled: bool,
+    /// One message per segment that failed.
+    pub errors: Vec<String>,
+    pub elapsed: Duration,
+}
+
+/// Delete every item of `name` that `plan` matches. `cancel` stops each
+/// segment before its next page; `on_progress` sees the totals after every
+/// batch.
+pub async fn purge_items(
+    db: &dyn Datastore,
+    name: &str,
+    plan: &QueryPlan,
+    options: PurgeOptions,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(PurgeProgress) + Sync),
+) -> Result<PurgeSummary> {
+    let segments = options.segments.max(1);
+    if segments > 1 && !db.capabilities().parallel_scan {
+        return Err(DbError::Unsupported("this backend has no parallel scan"));
+    }
+    let schema = db.describe_collection(name).await?;
+    let key_fields: Vec<String> = schema
+        .key
+        .fields
+        .iter()
+        .map(|field| field.name.clone())
+        .collect();
+    if key_fields.is_empty() {
+        return Err(DbError::Backend(format!("{name} has no key attributes")));
+    }
+    // Only the keys are needed to delete an item.
+    let mut plan = plan.clone();
+    plan.projection.clone_from(&key_fields);
+
+    let started = Instant::now();
+    let progress = Mutex::new(PurgeProgress::default());
+    let purge = SegmentPurge {
+        db,
+        name,
+        plan: &plan,
+        key_fields: &key_fields,
+        total_segments: segments,
+        dry_run: options.dry_run,
+        progress: &progress,
+        cancel,
+        on_progress,
+    };
+    let results = join_all((0..segments).map(|segment| purge.run(segment))).await;
+    let errors = results
+        .into_iter()
+        .enumerate()
+        .filter_map(|(segment, result)| {
+            let err = result.err()?;
+            Some(if segments > 1 {
+                format!("segment {}: {err}", segment + 1)
+            } else {
+                err.to_string()
+            })
+        })
+        .collect();
+    Ok(PurgeSummary {
+        progress: progress
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner),
+        dry_run: options.dry_run,
+        cancelled: cancel.load(Ordering::Relaxed),
+        errors,
+        elapsed: started.elapsed(),
+    })
+}
+
+/// The key attributes of `item`.
+pub fn item_key(item: &Item, key_fields: &[String]) -> Result<Key> {
+    let mut key = Item::new();
+    for field in key_fields {
+        let value = item
+            .get(field)
+            .ok_or_else(|| DbError::Backend(format!("Missing {field} in item")))?;
+        key.insert(field.clone(), value.clone());
+    }
+    Ok(Key(key))
+}
+
+struct SegmentPurge<'a> {
+    db: &'a dyn Datastore,
+    name: &'a str,
+    plan: &'a QueryPlan,
+    key_fields: &'a [String],
+    total_segments: u32,
+    dry_run: bool,
+    progress: &'a Mutex<PurgeProgress>,
+    cancel: &'a AtomicBool,
+    on_progress: &'a (dyn Fn(PurgeProgress) + Sync),
+}
+
+impl SegmentPurge<'_> {
+    async fn run(&self, segment: u32) -> Result<()> {
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            let page = Page {
+                cursor: cursor.clone(),
+                limit: Some(PAGE_SIZE),
+            };
+            crate::throttle::acquire().await;
+            let result = if self.total_segments > 1 {
+                self.db
+                    .scan_segment(self.name, self.plan, segment, self.total_segments, page)
+                    .await?
+            } else {
+                self.db.query(self.name, self.plan, page).await?
+            };
+            crate::throttle::charge(&result);
+            self.record(0, result.scanned_count.unwrap_or(result.count));
+
+            if self.dry_run {
+                self.record(result.items.len() as u64, 0);
+                match result.next {
+                    Some(next) => {
+                        cursor = Some(next);
+                        continue;
+                    }
+                    None => return Ok(()),
+                }
+            }
+            let keys = result
+                .items
+                .iter()
+                .map(|item| item_key(item, self.key_fields))
+                .collect::<Result<Vec<_>>>()?;
+            let mut deleted = 0;
+            for batch in keys.chunks(BATCH_SIZE) {
+                let outcome = self.db.batch_delete(self.name, batch.to_vec()).await?;
+                deleted += outcome.deleted;
+                self.record(outcome.deleted, 0);
+            }
+            match result.next {
+                None => return Ok(()),
+                // Backends without a scanned count page over matching items
+                // only, possibly by offset. With the whole page deleted, the
+                // same cursor leads to the items after it.
+                Some(_)
+                    if result.scanned_count.is_none()
+                        && !keys.is_empty()
+                        && deleted == keys.len() as u64 => {}
+                next => cursor = next,
+            }
+        }
+    }
+
+    fn record(&self, deleted: u64, scanned: u64) {
+        let mut progress = self.progress.lock().unwrap_or_else(PoisonError::into_inner);
+        progress.deleted += deleted;
+        progress.scanned += scanned;
+        (self.on_progress)(*progress);
+    }
+}
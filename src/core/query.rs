@@ -1,6 +1,6 @@
 //! Backend-neutral query plan, pagination, and result types.
 
-use super::schema::{IndexSchema, KeySchema};
+use super::schema::{IndexSchema, KeySchema, Throughput};
 use super::value::{Item, Value};
 
 /// Which index, if any, the user explicitly asked to run against.
@@ -34,6 +34,23 @@ pub struct QueryPlan {
     pub filter: Option<String>,
     pub index_hint: Option<IndexHint>,
     pub key_equals: Option<KeyEquals>,
+    /// Ask for strongly consistent reads where the backend distinguishes them
+    /// (DynamoDB); ignored elsewhere.
+    pub consistent_read: bool,
+    /// Only fetch these top-level attributes; empty fetches whole items.
+    /// Ignored by backends without `read_options`.
+    pub projection: Vec<String>,
+    /// Return key-condition queries in descending sort-key order. Scans have no
+    /// order and ignore it, as do backends without `read_options`.
+    pub descending: bool,
+    /// Values for the `:name` parameters in `filter`, as `(name, value)` with
+    /// the value written like a literal in the query language (`"abc"`, `42`).
+    /// Names the filter doesn't use are ignored.
+    pub parameters: Vec<(String, String)>,
+    /// Only count the matches (DynamoDB `Select=COUNT`): pages carry `count`
+    /// and `scanned_count` but no items, and `projection` is ignored. Other
+    /// backends still return the items.
+    pub count_only: bool,
 }
 
 impl QueryPlan {
@@ -43,6 +60,11 @@ impl QueryPlan {
             filter,
             index_hint,
             key_equals: None,
+            consistent_read: false,
+            projection: Vec::new(),
+            descending: false,
+            parameters: Vec::new(),
+            count_only: false,
         }
     }
 
@@ -52,6 +74,11 @@ impl QueryPlan {
             filter: None,
             index_hint: Some(index_hint),
             key_equals: Some(KeyEquals { attribute, value }),
+            consistent_read: false,
+            projection: Vec::new(),
+            descending: false,
+            parameters: Vec::new(),
+            count_only: false,
         }
     }
 }
@@ -68,6 +95,15 @@ pub struct Page {
     pub limit: Option<u32>,
 }
 
+/// One page of collection names from
+/// [`list_collections_page`](super::datastore::Datastore::list_collections_page).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectionPage {
+    pub names: Vec<String>,
+    /// The name to resume after; `None` once every name has been listed.
+    pub next: Option<String>,
+}
+
 /// How a query was actually served (reported after execution).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PlanKind {
@@ -75,6 +111,15 @@ pub enum PlanKind {
     Scan,
     /// An indexed query; `index` is `None` for the primary key.
     IndexedQuery { index: Option<String> },
+    /// A fetch of at most one item by its full primary key (DynamoDB
+    /// `GetItem`).
+    Lookup,
+    /// A fetch of the items for a list of primary keys (DynamoDB
+    /// `BatchGetItem`).
+    BatchLookup,
+    /// A free-form statement (e.g. DynamoDB PartiQL) whose plan the backend
+    /// chose on its own.
+    Statement,
 }
 
 /// Backend-neutral cost accounting (DynamoDB consumed-capacity, etc.).
@@ -101,12 +146,130 @@ pub struct QueryResult {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Key(pub Item);
 
+/// Attribute-level changes to an existing item, applied by
+/// [`update_item`](super::datastore::Datastore::update_item) without touching
+/// attributes that aren't mentioned.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemUpdate {
+    /// Attributes to add or overwrite.
+    pub set: Item,
+    /// Attributes to remove.
+    pub remove: Vec<String>,
+    /// An update in the backend's own update language, for changes `set` and
+    /// `remove` can't describe: paths into nested values, and values worked
+    /// out from the item, such as DynamoDB's `ADD` or `n = n + 1`. The
+    /// backend compiles it, and its query language previews it (see
+    /// [`QueryLanguage::apply_update`](super::language::QueryLanguage::apply_update)).
+    pub expression: Option<String>,
+}
+
+impl ItemUpdate {
+    /// The changes that turn `original` into `edited`. Returns `None` when a
+    /// key field differs, since keys can't change in place (the edit then
+    /// describes a different item).
+    pub fn between(original: &Item, edited: &Item, key: &KeySchema) -> Option<Self> {
+        let is_key = |name: &str| key.fields.iter().any(|field| field.name == name);
+        if key
+            .fields
+            .iter()
+            .any(|field| original.get(&field.name) != edited.get(&field.name))
+        {
+            return None;
+        }
+        let set = edited
+            .iter()
+            .filter(|(name, value)| !is_key(name) && original.get(*name) != Some(*value))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let remove = original
+            .keys()
+            .filter(|name| !is_key(name) && !edited.contains_key(*name))
+            .cloned()
+            .collect();
+        Some(Self {
+            set,
+            remove,
+            expression: None,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.remove.is_empty() && self.expression.is_none()
+    }
+
+    /// `item` as it reads after `set` and `remove`, for previews. The
+    /// `expression` is left to the backend's query language.
+    pub fn apply(&self, item: &Item) -> Item {
+        let mut updated = item.clone();
+        for name in &self.remove {
+            updated.shift_remove(name);
+        }
+        for (name, value) in &self.set {
+            updated.insert(name.clone(), value.clone());
+        }
+        updated
+    }
+}
+
 /// The outcome of a [`batch_delete`](super::datastore::Datastore::batch_delete).
 #[derive(Debug, Clone, Default)]
 pub struct BatchDeleteOutcome {
     pub deleted: u64,
 }
 
+/// The outcome of a [`batch_put`](super::datastore::Datastore::batch_put).
+#[derive(Debug, Clone, Default)]
+pub struct BatchPutOutcome {
+    pub written: u64,
+}
+
+/// One write in a [`transact_write`](super::datastore::Datastore::transact_write).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactWrite {
+    Put {
+        collection: String,
+        item: Item,
+    },
+    Update {
+        collection: String,
+        key: Key,
+        update: ItemUpdate,
+    },
+    Delete {
+        collection: String,
+        key: Key,
+    },
+}
+
+impl TransactWrite {
+    pub fn collection(&self) -> &str {
+        match self {
+            TransactWrite::Put { collection, .. }
+            | TransactWrite::Update { collection, .. }
+            | TransactWrite::Delete { collection, .. } => collection,
+        }
+    }
+
+    /// A short verb for the write, e.g. for a review list.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransactWrite::Put { .. } => "put",
+            TransactWrite::Update { .. } => "update",
+            TransactWrite::Delete { .. } => "delete",
+        }
+    }
+}
+
+/// The outcome of a [`transact_write`](super::datastore::Datastore::transact_write).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactOutcome {
+    /// Every write was applied.
+    Committed,
+    /// Nothing was applied. `reasons` lines up with the writes: why each one
+    /// was rejected, or `None` for writes that were fine on their own.
+    Canceled { reasons: Vec<Option<String>> },
+}
+
 /// A pre-flight estimate of how a query would run, when a backend can provide
 /// one. Lets the UI warn before an expensive full scan.
 #[derive(Debug, Clone)]
@@ -118,9 +281,73 @@ pub enum PlanExplanation {
 }
 
 /// A backend-neutral request to create a collection.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CreateCollectionSpec {
     pub name: String,
     pub key: KeySchema,
     pub indexes: Vec<IndexSchema>,
 }
+
+/// Files in S3 to create a collection from (DynamoDB `ImportTable`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportSource {
+    pub bucket: String,
+    /// Only objects under this prefix are imported; the whole bucket when
+    /// empty.
+    pub key_prefix: String,
+    pub format: ImportFormat,
+    pub compression: ImportCompression,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// DynamoDB JSON, one `{"Item": …}` per line, as written by an S3 export.
+    DynamoJson,
+    /// Amazon Ion, as written by an S3 export.
+    Ion,
+    /// CSV with a header row naming the attributes.
+    Csv,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] = [Self::DynamoJson, Self::Ion, Self::Csv];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::DynamoJson => "DynamoDB JSON",
+            Self::Ion => "ION",
+            Self::Csv => "CSV",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl ImportCompression {
+    pub const ALL: [ImportCompression; 3] = [Self::None, Self::Gzip, Self::Zstd];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip => "GZIP",
+            Self::Zstd => "ZSTD",
+        }
+    }
+}
+
+/// A backend-neutral request to change how a collection is billed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapacitySpec {
+    /// Pay per request; there is no capacity to manage.
+    OnDemand,
+    /// Fixed capacity for the collection and for each named index.
+    Provisioned {
+        table: Throughput,
+        indexes: Vec<(String, Throughput)>,
+    },
+}
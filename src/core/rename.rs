@@ -0,0 +1,266 @@
+//! Renaming a collection, which DynamoDB can't do in place, for the table
+//! picker's rename action. The new collection is created from the old one's
+//! definition, the items are copied across, both collections are counted to
+//! check nothing was missed, and the old one is dropped if asked.
+//!
+//! A [`RenameJob`] records the step it has reached, and the copy's
+//! checkpoint while copying, so running a job that stopped part way carries
+//! on from that step rather than starting over. Canceling stops a job the
+//! same way, between steps or between pages of the copy. Jobs convert to and
+//! from JSON, so one can be saved and resumed after a restart.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use serde_json::{Map, Value as Json};
+
+use super::copy::{CopyCheckpoint, copy_items, wait_until_active};
+use super::datastore::Datastore;
+use super::error::{DbError, Result};
+use super::query::{Page, QueryPlan};
+use super::table_spec::{self, TableSpec};
+
+/// Where a rename has got to. Each step is only run once the previous one
+/// has finished.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameStep {
+    /// The new collection doesn't exist yet.
+    Create,
+    /// Copying the items, resuming from the checkpoint.
+    Copy(CopyCheckpoint),
+    /// Counting the items of both collections.
+    Verify,
+    /// Dropping the old collection.
+    DropSource,
+    Done,
+}
+
+impl RenameStep {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Create => "creating",
+            Self::Copy(_) => "copying",
+            Self::Verify => "verifying",
+            Self::DropSource => "deleting the old table",
+            Self::Done => "done",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameJob {
+    pub source: String,
+    pub destination: String,
+    /// Drop the source once the counts match.
+    pub drop_source: bool,
+    /// Scan segments to copy with.
+    pub segments: u32,
+    pub step: RenameStep,
+    /// Items in the new collection, once verified.
+    pub items: Option<u64>,
+}
+
+impl RenameJob {
+    pub fn new(
+        source: impl Into<String>,
+        destination: impl Into<String>,
+        drop_source: bool,
+        segments: u32,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            destination: destination.into(),
+            drop_source,
+            segments,
+            step: RenameStep::Create,
+            items: None,
+        }
+    }
+
+    pub fn to_json(&self) -> Json {
+        let step = match &self.step {
+            RenameStep::Create => "create",
+            RenameStep::Copy(_) => "copy",
+            RenameStep::Verify => "verify",
+            RenameStep::DropSource => "drop_source",
+            RenameStep::Done => "done",
+        };
+        let mut map = Map::new();
+        map.insert("source".to_string(), Json::from(self.source.clone()));
+        map.insert(
+            "destination".to_string(),
+            Json::from(self.destination.clone()),
+        );
+        map.insert("drop_source".to_string(), Json::from(self.drop_source));
+        map.insert("segments".to_string(), Json::from(self.segments));
+        map.insert("step".to_string(), Json::from(step));
+        if let RenameStep::Copy(checkpoint) = &self.step {
+            map.insert("checkpoint".to_string(), checkpoint.to_json());
+        }
+        if let Some(items) = self.items {
+            map.insert("items".to_string(), Json::from(items));
+        }
+        Json::Object(map)
+    }
+
+    pub fn from_json(value: &Json) -> Result<Self> {
+        let invalid = |what: &str| DbError::Backend(format!("Invalid rename job: {what}"));
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(Json::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| invalid(&format!("missing {key}")))
+        };
+        let step = match text("step")?.as_str() {
+            "create" => RenameStep::Create,
+            "copy" => RenameStep::Copy(CopyCheckpoint::from_json(
+                value
+                    .get("checkpoint")
+                    .ok_or_else(|| invalid("missing checkpoint"))?,
+            )?),
+            "verify" => RenameStep::Verify,
+            "drop_source" => RenameStep::DropSource,
+            "done" => RenameStep::Done,
+            other => return Err(invalid(&format!("unknown step {other}"))),
+        };
+        Ok(Self {
+            source: text("source")?,
+            destination: text("destination")?,
+            drop_source: value
+                .get("drop_source")
+                .and_then(Json::as_bool)
+                .unwrap_or(false),
+            segments: value
+                .get("segments")
+                .and_then(Json::as_u64)
+                .and_then(|segments| u32::try_from(segments).ok())
+                .unwrap_or(1),
+            step,
+            items: value.get("items").and_then(Json::as_u64),
+        })
+    }
+
+    /// Check the counts of both collections agree. On a mismatch the source
+    /// is kept, whether or not it was to be dropped.
+    fn check_counts(&self, source: u64, destination: u64) -> Result<()> {
+        if source == destination {
+            return Ok(());
+        }
+        Err(DbError::Backend(format!(
+            "{} has {source} items but {} has {destination}; {} was kept",
+            self.source, self.destination, self.source
+        )))
+    }
+}
+
+/// What [`run`] fails with when it is canceled.
+pub const CANCELED: &str = "Rename canceled";
+
+/// Run `job` from the step it is at to the end. `on_progress` sees every step
+/// as it starts, and each page of the copy. On failure, including when
+/// `cancel` is set, `job` is left at the step that stopped, ready to be run
+/// again.
+pub async fn run(
+    db: &dyn Datastore,
+    job: &mut RenameJob,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(&RenameStep) + Sync),
+) -> Result<()> {
+    loop {
+        if job.step != RenameStep::Done && cancel.load(Ordering::Relaxed) {
+            return Err(DbError::Backend(CANCELED.to_string()));
+        }
+        on_progress(&job.step);
+        job.step = match &job.step {
+            RenameStep::Create => {
+                let details = db.describe_details(&job.source).await?;
+                let spec = TableSpec::from_details(&details).renamed(&job.destination);
+                table_spec::create(db, &spec).await?;
+                RenameStep::Copy(CopyCheckpoint::new(
+                    &job.source,
+                    &job.destination,
+                    job.segments,
+                ))
+            }
+            RenameStep::Copy(checkpoint) => {
+                wait_until_active(db, &job.destination).await?;
+                let latest = Mutex::new(checkpoint.clone());
+                let on_page = |checkpoint: &CopyCheckpoint| {
+                    latest
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .clone_from(checkpoint);
+                    on_progress(&RenameStep::Copy(checkpoint.clone()));
+                };
+                match copy_items(db, checkpoint.clone(), cancel, &on_page).await {
+                    Ok(checkpoint) if checkpoint.is_complete() => RenameStep::Verify,
+                    Ok(checkpoint) => RenameStep::Copy(checkpoint),
+                    Err(err) => {
+                        job.step = RenameStep::Copy(
+                            latest.into_inner().unwrap_or_else(PoisonError::into_inner),
+                        );
+                        return Err(err);
+                    }
+                }
+            }
+            RenameStep::Verify => {
+                let source = count_items(db, &job.source).await?;
+                let destination = count_items(db, &job.destination).await?;
+                job.check_counts(source, destination)?;
+                job.items = Some(destination);
+                if job.drop_source {
+                    RenameStep::DropSource
+                } else {
+                    RenameStep::Done
+                }
+            }
+            RenameStep::DropSource => {
+                db.drop_collection(&job.source).await?;
+                RenameStep::Done
+            }
+            RenameStep::Done => return Ok(()),
+        };
+    }
+}
+
+/// Count a collection's items by scanning all of it.
+pub async fn count_items(db: &dyn Datastore, name: &str) -> Result<u64> {
+    let plan = QueryPlan::default();
+    let mut count = 0;
+    let mut cursor = None;
+    loop {
+        crate::throttle::acquire().await;
+        let result = db
+            .query(
+                name,
+                &plan,
+                Page {
+                    cursor: cursor.take(),
+                    limit: None,
+                },
+            )
+            .await?;
+        crate::throttle::charge(&result);
+        count += result.items.len() as u64;
+        cursor = result.next;
+        if cursor.is_none() {
+            return Ok(count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RenameJob, RenameStep};
+
+    #[test]
+    fn keeps_the_source_when_counts_differ() {
+        let job = RenameJob::new("orders", "orders-v2", true, 1);
+        assert_eq!(job.step, RenameStep::Create);
+        assert!(job.check_counts(12, 12).is_ok());
+        let err = job.check_counts(12, 11).unwrap_err().to_string();
+        assert!(err.contains("orders has 12 items but orders-v2 has 11"));
+        assert!(err.ends_with("orders was kept"));
+    }
+}
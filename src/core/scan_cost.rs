@@ -0,0 +1,95 @@
+//! What a full scan is likely to cost, worked out before it runs from the
+//! item count and size the backend reports for the collection, for the query
+//! view's confirmation before a filtered scan of a large table.
+//!
+//! A scan is billed on the bytes it reads, matching or not: one read unit per
+//! 4 KB, half that for eventually consistent reads. The counts DynamoDB
+//! reports are refreshed about every six hours, so this is a rough guide.
+
+use std::time::Duration;
+
+use crate::throttle::Budget;
+
+/// Bytes per read capacity unit.
+const READ_UNIT_BYTES: u64 = 4 * 1024;
+/// The most one page of a scan returns, whatever its limit.
+const MAX_PAGE_BYTES: u64 = 1024 * 1024;
+/// Roughly how long one page takes to come back.
+const PAGE_LATENCY: Duration = Duration::from_millis(150);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanEstimate {
+    pub items: u64,
+    pub bytes: u64,
+    /// Read capacity units the whole scan consumes.
+    pub capacity_units: f64,
+    pub pages: u64,
+    /// How long reading every page takes, one after another, within the
+    /// throttle budget if one is set.
+    pub duration: Duration,
+}
+
+impl ScanEstimate {
+    /// The estimate for scanning `items` items of `bytes` in all, `page_size`
+    /// items a page.
+    pub fn new(
+        items: u64,
+        bytes: u64,
+        page_size: u32,
+        consistent_read: bool,
+        budget: Option<Budget>,
+    ) -> Self {
+        let units = bytes.div_ceil(READ_UNIT_BYTES) as f64;
+        let capacity_units = if consistent_read { units } else { units / 2.0 };
+        let pages = items
+            .div_ceil(u64::from(page_size.max(1)))
+            .max(bytes.div_ceil(MAX_PAGE_BYTES))
+            .max(1);
+        let paging = PAGE_LATENCY.saturating_mul(u32::try_from(pages).unwrap_or(u32::MAX));
+        let throttled = match budget {
+            Some(Budget::CapacityUnits(rate)) if rate > 0.0 => {
+                Duration::from_secs_f64(capacity_units / rate)
+            }
+            Some(Budget::Items(rate)) if rate > 0.0 => Duration::from_secs_f64(items as f64 / rate),
+            _ => Duration::ZERO,
+        };
+        Self {
+            items,
+            bytes,
+            capacity_units,
+            pages,
+            duration: paging.max(throttled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ScanEstimate;
+    use crate::throttle::Budget;
+
+    #[test]
+    fn charges_by_size_and_pages_by_the_larger_limit() {
+        // 100k items of 2 KB: 50k read units, or 25k eventually consistent.
+        let estimate = ScanEstimate::new(100_000, 200 * 1024 * 1024, 100, false, None);
+        assert!((estimate.capacity_units - 25_600.0).abs() < f64::EPSILON);
+        assert_eq!(estimate.pages, 1_000);
+        assert_eq!(estimate.duration, Duration::from_secs(150));
+
+        // Large items fill the 1 MB page limit before the item limit.
+        let estimate = ScanEstimate::new(1_000, 100 * 1024 * 1024, 1_000, true, None);
+        assert!((estimate.capacity_units - 25_600.0).abs() < f64::EPSILON);
+        assert_eq!(estimate.pages, 100);
+
+        let throttled = ScanEstimate::new(
+            1_000,
+            100 * 1024 * 1024,
+            1_000,
+            true,
+            Some(Budget::CapacityUnits(100.0)),
+        );
+        assert_eq!(throttled.duration, Duration::from_secs(256));
+    }
+}
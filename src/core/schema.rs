@@ -176,3 +176,127 @@ impl CollectionSchema {
             .count()
     }
 }
+
+/// Provisioned read/write capacity units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throughput {
+    pub read: i64,
+    pub write: i64,
+}
+
+/// Runtime details of one secondary index, for the table details view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexDetails {
+    pub schema: IndexSchema,
+    /// Backend status string (e.g. DynamoDB "ACTIVE" / "CREATING"), if any.
+    pub status: Option<String>,
+    /// Whether the index is still being populated from existing items.
+    pub backfilling: bool,
+    pub item_count: Option<i64>,
+    pub size_bytes: Option<i64>,
+    /// Provisioned capacity, for indexes billed separately from the table.
+    pub throughput: Option<Throughput>,
+}
+
+impl IndexDetails {
+    pub fn from_schema(schema: IndexSchema) -> Self {
+        Self {
+            schema,
+            status: None,
+            backfilling: false,
+            item_count: None,
+            size_bytes: None,
+            throughput: None,
+        }
+    }
+}
+
+/// Everything the table details view shows about a collection: the schema plus
+/// operational settings. Backends fill in what they expose and leave the rest
+/// `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectionDetails {
+    pub schema: CollectionSchema,
+    pub indexes: Vec<IndexDetails>,
+    /// Billing mode, e.g. DynamoDB "PAY_PER_REQUEST" / "PROVISIONED".
+    pub billing_mode: Option<String>,
+    pub throughput: Option<Throughput>,
+    /// Change stream view type (e.g. "NEW_AND_OLD_IMAGES"); `Some("disabled")`
+    /// when the backend supports streams but none is enabled.
+    pub stream: Option<String>,
+    pub point_in_time_recovery: Option<bool>,
+    pub deletion_protection: Option<bool>,
+    /// Creation time, formatted for display.
+    pub created_at: Option<String>,
+    /// Backend resource identifier (e.g. the table ARN).
+    pub resource_id: Option<String>,
+    /// The other regions a global table is replicated to.
+    pub replicas: Vec<ReplicaDetails>,
+}
+
+/// One region of a global table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplicaDetails {
+    pub region: String,
+    /// Backend status string (e.g. DynamoDB "CREATING" / "ACTIVE").
+    pub status: Option<String>,
+    /// How far a new replica has been filled, e.g. "42%".
+    pub progress: Option<String>,
+}
+
+impl From<CollectionSchema> for CollectionDetails {
+    fn from(schema: CollectionSchema) -> Self {
+        Self {
+            indexes: schema
+                .indexes
+                .iter()
+                .cloned()
+                .map(IndexDetails::from_schema)
+                .collect(),
+            schema,
+            ..Self::default()
+        }
+    }
+}
+
+/// An import of files from S3 into a new collection, for the imports view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportInfo {
+    /// Backend identifier for the import (e.g. the import ARN).
+    pub id: String,
+    /// The collection being created.
+    pub table: String,
+    /// Backend status string (e.g. DynamoDB "IN_PROGRESS" / "COMPLETED").
+    pub status: String,
+    /// Where the files come from, as an `s3://` URI.
+    pub source: String,
+    pub format: Option<String>,
+    /// Start and end times, formatted for display.
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub processed_items: i64,
+    pub imported_items: i64,
+    /// Items that could not be imported (e.g. missing key attributes).
+    pub errors: i64,
+    pub failure: Option<String>,
+}
+
+impl ImportInfo {
+    pub fn is_running(&self) -> bool {
+        matches!(self.status.as_str(), "IN_PROGRESS" | "CANCELLING")
+    }
+}
+
+/// An on-demand backup of a collection, for the backups view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupInfo {
+    /// Backend identifier used to delete or restore the backup (e.g. the
+    /// backup ARN).
+    pub id: String,
+    pub name: String,
+    /// Backend status string (e.g. DynamoDB "CREATING" / "AVAILABLE").
+    pub status: Option<String>,
+    /// Creation time, formatted for display.
+    pub created_at: Option<String>,
+    pub size_bytes: Option<i64>,
+}
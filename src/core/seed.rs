@@ -0,0 +1,566 @@
+//! Synthetic items for load tests and demos.
+//!
+//! Every attribute is generated from a template: literal text with
+//! placeholders in braces, e.g. `user#{uuid}` or `{int:1..500}`. Key
+//! attributes without a template get one that suits their type, so a
+//! [`SeedSpec`] always produces writable items.
+//!
+//! | Placeholder         | Produces                                        |
+//! |---------------------|-------------------------------------------------|
+//! | `{uuid}`            | a random version 4 UUID                         |
+//! | `{seq}`             | the item's position, starting at 1              |
+//! | `{int:MIN..MAX}`    | a whole number in the range (default `0..1000`) |
+//! | `{float:MIN..MAX}`  | a number with two decimals (default `0..1`)     |
+//! | `{lorem:N}`         | N lorem ipsum words (default 3)                 |
+//! | `{bool}`            | `true` or `false`                               |
+//! | `{pick:a\|b\|c}`    | one of the options                              |
+//! | `{timestamp}`       | an RFC 3339 time within the past year           |
+//!
+//! A template that is a single `{seq}`, `{int}`, or `{float}` yields a
+//! number and a single `{bool}` a boolean; anything else yields a string.
+//! `{{` and `}}` stand for literal braces.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::datastore::Datastore;
+use super::error::{DbError, Result};
+use super::schema::{KeySchema, ScalarType};
+use super::value::{Item, Number, Value};
+
+/// Attributes the TUI and the `seed` command generate when none are given.
+pub const DEFAULT_TEMPLATES: &str =
+    "name={lorem:2}, score={int:0..100}, active={bool}, created_at={timestamp}";
+
+/// Items sent per `batch_put` call.
+const BATCH_SIZE: usize = 25;
+/// Per-batch errors kept in the summary; the rest are only counted.
+const MAX_ERRORS: usize = 10;
+
+const LOREM: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Uuid,
+    Seq,
+    Int { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    Lorem(usize),
+    Bool,
+    Pick(Vec<String>),
+    Timestamp,
+}
+
+/// A parsed attribute template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template(Vec<Part>);
+
+impl Template {
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut body = String::new();
+                    let mut closed = false;
+                    for ch in chars.by_ref() {
+                        if ch == '}' {
+                            closed = true;
+                            break;
+                        }
+                        body.push(ch);
+                    }
+                    if !closed {
+                        return Err(format!("unclosed placeholder {{{body}"));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut literal)));
+                    }
+                    parts.push(placeholder(body.trim())?);
+                }
+                '}' => return Err("unmatched } (write }} for a literal brace)".to_string()),
+                ch => literal.push(ch),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Text(literal));
+        }
+        if parts.is_empty() {
+            return Err("template is empty".to_string());
+        }
+        Ok(Self(parts))
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self.0.as_slice(),
+            [Part::Seq | Part::Int { .. } | Part::Float { .. }]
+        )
+    }
+
+    fn generate(&self, seq: u64, rng: &mut impl Rng) -> Value {
+        match self.0.as_slice() {
+            [Part::Seq] => Value::Num(Number::from(seq as i64)),
+            [Part::Int { min, max }] => Value::Num(Number::from(rng.gen_range(*min..=*max))),
+            [Part::Float { .. }] => Value::Num(Number::new(self.text(seq, rng))),
+            [Part::Bool] => Value::Bool(rng.gen_bool(0.5)),
+            _ => Value::Str(self.text(seq, rng)),
+        }
+    }
+
+    fn text(&self, seq: u64, rng: &mut impl Rng) -> String {
+        let mut text = String::new();
+        for part in &self.0 {
+            match part {
+                Part::Text(literal) => text.push_str(literal),
+                Part::Uuid => text.push_str(&uuid(rng)),
+                Part::Seq => {
+                    let _ = write!(text, "{seq}");
+                }
+                Part::Int { min, max } => {
+                    let _ = write!(text, "{}", rng.gen_range(*min..=*max));
+                }
+                Part::Float { min, max } => {
+                    let _ = write!(text, "{:.2}", rng.gen_range(*min..=*max));
+                }
+                Part::Lorem(words) => {
+                    let words: Vec<&str> = (0..*words)
+                        .map(|_| LOREM[rng.gen_range(0..LOREM.len())])
+                        .collect();
+                    text.push_str(&words.join(" "));
+                }
+                Part::Bool => text.push_str(if rng.gen_bool(0.5) { "true" } else { "false" }),
+                Part::Pick(options) => text.push_str(&options[rng.gen_range(0..options.len())]),
+                Part::Timestamp => {
+                    let ago = chrono::Duration::seconds(rng.gen_range(0..365 * 24 * 60 * 60));
+                    let time = chrono::Utc::now() - ago;
+                    text.push_str(&time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+                }
+            }
+        }
+        text
+    }
+}
+
+fn placeholder(body: &str) -> std::result::Result<Part, String> {
+    let (name, arg) = match body.split_once(':') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (body, None),
+    };
+    let no_arg = |part: Part| match arg {
+        None => Ok(part),
+        Some(_) => Err(format!("{{{name}}} takes no argument")),
+    };
+    match name {
+        "uuid" => no_arg(Part::Uuid),
+        "seq" => no_arg(Part::Seq),
+        "bool" => no_arg(Part::Bool),
+        "timestamp" => no_arg(Part::Timestamp),
+        "int" => {
+            let (min, max) = match arg {
+                Some(arg) => range(arg, name)?,
+                None => (0, 1000),
+            };
+            Ok(Part::Int { min, max })
+        }
+        "float" => {
+            let (min, max) = match arg {
+                Some(arg) => range(arg, name)?,
+                None => (0.0, 1.0),
+            };
+            Ok(Part::Float { min, max })
+        }
+        "lorem" => {
+            let words = match arg {
+                Some(arg) => arg
+                    .parse()
+                    .ok()
+                    .filter(|words| (1..=100).contains(words))
+                    .ok_or_else(|| "{lorem:N} needs a word count from 1 to 100".to_string())?,
+                None => 3,
+            };
+            Ok(Part::Lorem(words))
+        }
+        "pick" => {
+            let options: Vec<String> = arg
+                .unwrap_or_default()
+                .split('|')
+                .map(str::trim)
+                .filter(|option| !option.is_empty())
+                .map(str::to_string)
+                .collect();
+            if options.is_empty() {
+                return Err("{pick:a|b} needs at least one option".to_string());
+            }
+            Ok(Part::Pick(options))
+        }
+        "" => Err("empty placeholder {}".to_string()),
+        other => Err(format!("unknown placeholder {{{other}}}")),
+    }
+}
+
+fn range<T: std::str::FromStr + PartialOrd>(
+    arg: &str,
+    name: &str,
+) -> std::result::Result<(T, T), String> {
+    let invalid = || format!("{{{name}:MIN..MAX}} needs a range like 1..100");
+    let (min, max) = arg.split_once("..").ok_or_else(invalid)?;
+    let min: T = min.trim().parse().map_err(|_| invalid())?;
+    let max: T = max.trim().parse().map_err(|_| invalid())?;
+    if min > max {
+        return Err(format!("{{{name}:{arg}}} has its bounds reversed"));
+    }
+    Ok((min, max))
+}
+
+fn uuid(rng: &mut impl Rng) -> String {
+    let mut bytes = [0_u8; 16];
+    rng.fill(&mut bytes);
+    // Version 4, RFC 4122 variant.
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let mut hex = String::with_capacity(32);
+    for byte in bytes {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Parse `name=template` assignments separated by commas. Commas inside
+/// placeholders don't separate.
+pub fn parse_templates(text: &str) -> std::result::Result<Vec<(String, Template)>, String> {
+    let mut assignments = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+    for (idx, ch) in text.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                assignments.push(&text[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    assignments.push(&text[start..]);
+    assignments
+        .into_iter()
+        .filter(|assignment| !assignment.trim().is_empty())
+        .map(parse_template_assignment)
+        .collect()
+}
+
+/// Parse one `name=template` assignment.
+pub fn parse_template_assignment(text: &str) -> std::result::Result<(String, Template), String> {
+    let (name, template) = text
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=TEMPLATE, got {:?}", text.trim()))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("missing attribute name in {:?}", text.trim()));
+    }
+    let template = Template::parse(template.trim()).map_err(|err| format!("{name}: {err}"))?;
+    Ok((name.to_string(), template))
+}
+
+#[derive(Debug, Clone)]
+struct Attribute {
+    name: String,
+    template: Template,
+    binary: bool,
+}
+
+/// What a generated item looks like: every key attribute, then the
+/// templated ones in the order given.
+#[derive(Debug, Clone)]
+pub struct SeedSpec {
+    attributes: Vec<Attribute>,
+}
+
+impl SeedSpec {
+    pub fn new(
+        key: &KeySchema,
+        templates: Vec<(String, Template)>,
+    ) -> std::result::Result<Self, String> {
+        for (idx, (name, _)) in templates.iter().enumerate() {
+            if templates[..idx].iter().any(|(other, _)| other == name) {
+                return Err(format!("{name} has more than one template"));
+            }
+        }
+        let mut templates = templates;
+        let mut attributes = Vec::new();
+        for field in &key.fields {
+            let template = match templates.iter().position(|(name, _)| *name == field.name) {
+                Some(idx) => templates.remove(idx).1,
+                None => Template(vec![match field.ty {
+                    ScalarType::Number => Part::Seq,
+                    ScalarType::String | ScalarType::Binary => Part::Uuid,
+                }]),
+            };
+            if field.ty == ScalarType::Number && !template.is_numeric() {
+                return Err(format!(
+                    "{} is a number key; use {{seq}}, {{int}}, or {{float}}",
+                    field.name
+                ));
+            }
+            attributes.push(Attribute {
+                name: field.name.clone(),
+                template,
+                binary: field.ty == ScalarType::Binary,
+            });
+        }
+        attributes.extend(templates.into_iter().map(|(name, template)| Attribute {
+            name,
+            template,
+            binary: false,
+        }));
+        Ok(Self { attributes })
+    }
+
+    /// The item at position `seq` (1-based), drawing randomness from `rng`.
+    pub fn item(&self, seq: u64, rng: &mut impl Rng) -> Item {
+        self.attributes
+            .iter()
+            .map(|attribute| {
+                let value = match attribute.template.generate(seq, rng) {
+                    Value::Str(text) if attribute.binary => Value::Bytes(text.into_bytes()),
+                    value => value,
+                };
+                (attribute.name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+/// How many items to write and how.
+#[derive(Debug, Clone, Copy)]
+pub struct SeedOptions {
+    pub count: u64,
+    /// Batches written at once.
+    pub concurrency: usize,
+    /// Makes the generated values repeatable; random when unset.
+    pub seed: Option<u64>,
+}
+
+/// The random source for a [`SeedOptions::seed`].
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// How far seeding has got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeedProgress {
+    pub written: u64,
+    pub failed: u64,
+}
+
+/// How seeding ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedSummary {
+    pub progress: SeedProgress,
+    /// Stopped through the cancel flag before every item was written.
+    pub cancelled: bool,
+    /// The first failed batches and why.
+    pub errors: Vec<String>,
+    pub elapsed: Duration,
+}
+
+/// Write `options.count` items generated from `spec` into `name`. `cancel`
+/// stops before the next batch; `on_progress` sees the totals after every
+/// batch.
+pub async fn seed_items(
+    db: &dyn Datastore,
+    name: &str,
+    spec: &SeedSpec,
+    options: SeedOptions,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(SeedProgress) + Sync),
+) -> Result<SeedSummary> {
+    if db.is_read_only() {
+        return Err(DbError::ReadOnly);
+    }
+    let started = Instant::now();
+    let count = options.count;
+    let mut rng = seeded_rng(options.seed);
+    let mut next = 1_u64;
+    // Batches are generated as they're sent so a large count stays cheap.
+    let batches = std::iter::from_fn(move || {
+        if next > count {
+            return None;
+        }
+        let end = count.min(next + BATCH_SIZE as u64 - 1);
+        let batch: Vec<Item> = (next..=end).map(|seq| spec.item(seq, &mut rng)).collect();
+        let first = next;
+        next = end + 1;
+        Some((first, batch))
+    });
+    let mut results = stream::iter(batches)
+        .take_while(|_| std::future::ready(!cancel.load(Ordering::Relaxed)))
+        .map(|(first, items)| async move {
+            let size = items.len() as u64;
+            (first, size, db.batch_put(name, items).await)
+        })
+        .buffer_unordered(options.concurrency.max(1));
+
+    let mut progress = SeedProgress::default();
+    let mut errors = Vec::new();
+    while let Some((first, size, result)) = results.next().await {
+        match result {
+            Ok(outcome) => {
+                progress.written += outcome.written;
+                progress.failed += size.saturating_sub(outcome.written);
+            }
+            Err(err) => {
+                if errors.len() < MAX_ERRORS {
+                    errors.push(format!("items {first}–{}: {err}", first + size - 1));
+                }
+                progress.failed += size;
+            }
+        }
+        on_progress(progress);
+    }
+
+    Ok(SeedSummary {
+        progress,
+        cancelled: cancel.load(Ordering::Relaxed),
+        errors,
+        elapsed: started.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::{SeedSpec, Template, parse_templates};
+    use crate::core::schema::{KeyField, KeyRole, KeySchema, ScalarType};
+    use crate::core::value::Value;
+
+    fn key_schema() -> KeySchema {
+        KeySchema {
+            fields: vec![
+                KeyField {
+                    name: "pk".to_string(),
+                    role: KeyRole::Partition,
+                    ty: ScalarType::String,
+                },
+                KeyField {
+                    name: "sk".to_string(),
+                    role: KeyRole::Sort,
+                    ty: ScalarType::Number,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn templates_fill_placeholders() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let template = Template::parse("user#{uuid}").unwrap();
+        let Value::Str(text) = template.generate(1, &mut rng) else {
+            panic!("expected a string");
+        };
+        let uuid = text.strip_prefix("user#").unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+
+        let template = Template::parse("{int:5..5}").unwrap();
+        assert_eq!(template.generate(1, &mut rng), Value::from(5));
+        let template = Template::parse("order-{seq} {{x}}").unwrap();
+        assert_eq!(template.generate(12, &mut rng), Value::from("order-12 {x}"));
+        let template = Template::parse("{pick:red}").unwrap();
+        assert_eq!(template.generate(1, &mut rng), Value::from("red"));
+        let template = Template::parse("{lorem:4}").unwrap();
+        let Value::Str(words) = template.generate(1, &mut rng) else {
+            panic!("expected a string");
+        };
+        assert_eq!(words.split(' ').count(), 4);
+    }
+
+    #[test]
+    fn template_errors() {
+        assert!(Template::parse("").is_err());
+        assert!(Template::parse("{nope}").is_err());
+        assert!(Template::parse("{int:9..1}").is_err());
+        assert!(Template::parse("{uuid:1}").is_err());
+        assert!(Template::parse("{uuid").is_err());
+        assert!(Template::parse("a}").is_err());
+        assert!(parse_templates("status").is_err());
+    }
+
+    #[test]
+    fn spec_covers_the_key_schema() {
+        let templates = parse_templates("pk=user#{seq}, tier={pick:a|b}, n={int:0..9}").unwrap();
+        let spec = SeedSpec::new(&key_schema(), templates).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let item = spec.item(3, &mut rng);
+        let names: Vec<&str> = item.keys().map(String::as_str).collect();
+        assert_eq!(names, ["pk", "sk", "tier", "n"]);
+        assert_eq!(item["pk"], Value::from("user#3"));
+        assert_eq!(item["sk"], Value::from(3));
+
+        let templates = parse_templates("sk=item-{seq}").unwrap();
+        assert!(SeedSpec::new(&key_schema(), templates).is_err());
+        let templates = parse_templates("a={seq}, a={int}").unwrap();
+        assert!(SeedSpec::new(&key_schema(), templates).is_err());
+    }
+}
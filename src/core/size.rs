@@ -5,6 +5,9 @@
 
 use super::value::{Item, Value};
 
+/// DynamoDB's limit on the size of one item.
+pub const MAX_ITEM_SIZE_BYTES: usize = 400 * 1024;
+
 /// Estimate item size in bytes using DynamoDB item-size rules.
 pub fn estimate_item_size_bytes(item: &Item) -> usize {
     item.iter()
@@ -12,6 +15,17 @@ pub fn estimate_item_size_bytes(item: &Item) -> usize {
         .sum()
 }
 
+/// Each top-level attribute's share of [`estimate_item_size_bytes`] (its name
+/// plus its value), largest first. The shares add up to the item's size.
+pub fn attribute_sizes(item: &Item) -> Vec<(String, usize)> {
+    let mut sizes: Vec<(String, usize)> = item
+        .iter()
+        .map(|(name, value)| (name.clone(), name.len() + estimate_value_size_bytes(value)))
+        .collect();
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sizes
+}
+
 fn estimate_value_size_bytes(value: &Value) -> usize {
     match value {
         Value::Str(text) => text.len(),
@@ -70,3 +84,31 @@ fn number_size_bytes(num: &str) -> usize {
     let count = if digits.is_empty() { 1 } else { digits.len() };
     count.div_ceil(2) + 1
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Item, Value, attribute_sizes, estimate_item_size_bytes};
+
+    #[test]
+    fn attribute_sizes_sum_to_the_item_size_largest_first() {
+        let item: Item = [
+            ("pk".to_string(), Value::Str("user#1".to_string())),
+            ("n".to_string(), Value::from(12345_i64)),
+            ("body".to_string(), Value::Str("x".repeat(100))),
+        ]
+        .into_iter()
+        .collect();
+
+        let sizes = attribute_sizes(&item);
+        assert_eq!(
+            sizes,
+            vec![
+                ("body".to_string(), 104),
+                ("pk".to_string(), 8),
+                ("n".to_string(), 5),
+            ]
+        );
+        let total: usize = sizes.iter().map(|(_, size)| size).sum();
+        assert_eq!(total, estimate_item_size_bytes(&item));
+    }
+}
@@ -0,0 +1,591 @@
+//! Portable table definitions. A [`TableSpec`] holds a table's keys, indexes,
+//! TTL attribute, and billing; `dynamate describe --spec` dumps one as JSON or
+//! TOML, and `dynamate create-table --spec` (or "create like" in the table
+//! picker) creates a table from it, so an environment can be rebuilt
+//! elsewhere.
+//!
+//! The layout, with everything but the name and the partition key optional:
+//!
+//! ```json
+//! {
+//!   "table_name": "orders",
+//!   "hash_key": { "name": "PK", "type": "S" },
+//!   "sort_key": { "name": "SK", "type": "S" },
+//!   "gsis": [{ "name": "byStatus", "hash_key": { "name": "status", "type": "S" },
+//!              "projection": { "include": ["total"] } }],
+//!   "lsis": [{ "name": "byDate", "sort_key": { "name": "date", "type": "S" },
+//!              "projection": "keys_only" }],
+//!   "ttl_attribute": "expiresAt",
+//!   "billing": { "read": 5, "write": 5, "indexes": { "byStatus": { "read": 1, "write": 1 } } }
+//! }
+//! ```
+//!
+//! `billing` is `"on_demand"` or the provisioned capacity; a `projection` is
+//! `all` when left out.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Value as Json, json};
+
+use super::copy::wait_until_active;
+use super::datastore::Datastore;
+use super::error::{DbError, Result};
+use super::query::{CapacitySpec, CreateCollectionSpec};
+use super::schema::{
+    CollectionDetails, IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, Projection,
+    ScalarType, Throughput,
+};
+
+/// Everything needed to recreate a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSpec {
+    pub collection: CreateCollectionSpec,
+    pub ttl_attribute: Option<String>,
+    /// `None` leaves the backend's default, on demand for DynamoDB.
+    pub capacity: Option<CapacitySpec>,
+}
+
+impl TableSpec {
+    /// The spec of an existing table.
+    pub fn from_details(details: &CollectionDetails) -> Self {
+        let schema = &details.schema;
+        let provisioned = || {
+            details
+                .throughput
+                .filter(|throughput| throughput.read > 0 || throughput.write > 0)
+                .map(|table| CapacitySpec::Provisioned {
+                    table,
+                    indexes: details
+                        .indexes
+                        .iter()
+                        .filter_map(|index| {
+                            index
+                                .throughput
+                                .map(|throughput| (index.schema.name.clone(), throughput))
+                        })
+                        .collect(),
+                })
+        };
+        let capacity = match details.billing_mode.as_deref() {
+            Some("PAY_PER_REQUEST") => Some(CapacitySpec::OnDemand),
+            _ => provisioned(),
+        };
+        Self {
+            collection: CreateCollectionSpec {
+                name: schema.name.clone(),
+                key: schema.key.clone(),
+                indexes: schema.indexes.clone(),
+            },
+            ttl_attribute: schema.ttl_attribute.clone(),
+            capacity,
+        }
+    }
+
+    /// The same spec for a table called `name`.
+    #[must_use]
+    pub fn renamed(mut self, name: &str) -> Self {
+        self.collection.name = name.to_string();
+        self
+    }
+
+    pub fn to_json(&self) -> Json {
+        let mut object = Map::new();
+        object.insert("table_name".to_string(), json!(self.collection.name));
+        if let Some(field) = key_field(&self.collection.key, KeyRole::Partition) {
+            object.insert("hash_key".to_string(), field);
+        }
+        if let Some(field) = key_field(&self.collection.key, KeyRole::Sort) {
+            object.insert("sort_key".to_string(), field);
+        }
+        let (lsis, gsis): (Vec<_>, Vec<_>) = self
+            .collection
+            .indexes
+            .iter()
+            .partition(|index| index.kind == IndexKind::LocalSecondary);
+        if !gsis.is_empty() {
+            object.insert(
+                "gsis".to_string(),
+                gsis.into_iter().map(index_json).collect(),
+            );
+        }
+        if !lsis.is_empty() {
+            object.insert(
+                "lsis".to_string(),
+                lsis.into_iter().map(index_json).collect(),
+            );
+        }
+        if let Some(attribute) = self.ttl_attribute.as_ref() {
+            object.insert("ttl_attribute".to_string(), json!(attribute));
+        }
+        match self.capacity.as_ref() {
+            Some(CapacitySpec::OnDemand) => {
+                object.insert("billing".to_string(), json!("on_demand"));
+            }
+            Some(CapacitySpec::Provisioned { table, indexes }) => {
+                let mut billing = throughput_json(*table);
+                if !indexes.is_empty() {
+                    billing["indexes"] = indexes
+                        .iter()
+                        .map(|(name, throughput)| (name.clone(), throughput_json(*throughput)))
+                        .collect::<Map<_, _>>()
+                        .into();
+                }
+                object.insert("billing".to_string(), billing);
+            }
+            None => {}
+        }
+        Json::Object(object)
+    }
+
+    /// Read a spec in the layout of the module docs. The spec is validated
+    /// too.
+    pub fn from_json(value: &Json) -> std::result::Result<Self, String> {
+        let object = json_object(value, "spec")?;
+        let mut key = KeySchema {
+            fields: vec![json_key(
+                object.get("hash_key"),
+                "hash_key",
+                KeyRole::Partition,
+            )?],
+        };
+        key.fields.extend(json_optional_key(
+            object.get("sort_key"),
+            "sort_key",
+            KeyRole::Sort,
+        )?);
+
+        let mut indexes = Vec::new();
+        for (idx, gsi) in json_array(object, "gsis")?.iter().enumerate() {
+            let field = format!("gsis[{idx}]");
+            let gsi = json_object(gsi, &field)?;
+            let mut fields = vec![json_key(
+                gsi.get("hash_key"),
+                &format!("{field}.hash_key"),
+                KeyRole::Partition,
+            )?];
+            fields.extend(json_optional_key(
+                gsi.get("sort_key"),
+                &format!("{field}.sort_key"),
+                KeyRole::Sort,
+            )?);
+            indexes.push(IndexSchema {
+                name: json_string(gsi, "name", &field)?,
+                kind: IndexKind::GlobalSecondary,
+                key: KeySchema { fields },
+                projection: json_projection(gsi.get("projection"), &field)?,
+            });
+        }
+        for (idx, lsi) in json_array(object, "lsis")?.iter().enumerate() {
+            let field = format!("lsis[{idx}]");
+            let lsi = json_object(lsi, &field)?;
+            indexes.push(IndexSchema {
+                name: json_string(lsi, "name", &field)?,
+                kind: IndexKind::LocalSecondary,
+                key: KeySchema {
+                    fields: vec![json_key(
+                        lsi.get("sort_key"),
+                        &format!("{field}.sort_key"),
+                        KeyRole::Sort,
+                    )?],
+                },
+                projection: json_projection(lsi.get("projection"), &field)?,
+            });
+        }
+
+        let ttl_attribute = match object.get("ttl_attribute") {
+            None | Some(Json::Null) => None,
+            Some(_) => Some(json_string(object, "ttl_attribute", "spec")?),
+        };
+        let spec = Self {
+            collection: CreateCollectionSpec {
+                name: json_string(object, "table_name", "spec")?,
+                key,
+                indexes,
+            },
+            ttl_attribute,
+            capacity: json_billing(object.get("billing"))?,
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Read a spec from TOML with the same layout as the JSON.
+    pub fn from_toml(text: &str) -> std::result::Result<Self, String> {
+        let value: Json = toml::from_str(text).map_err(|err| err.to_string())?;
+        Self::from_json(&value)
+    }
+
+    pub fn to_toml(&self) -> std::result::Result<String, String> {
+        toml::to_string_pretty(&self.to_json()).map_err(|err| err.to_string())
+    }
+
+    /// The checks DynamoDB would otherwise fail the create with.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let has_sort_key = self.collection.key.sort_key().is_some();
+        let mut types = HashMap::new();
+        let mut names = HashSet::new();
+        let fields = self.collection.key.fields.iter().chain(
+            self.collection
+                .indexes
+                .iter()
+                .flat_map(|index| &index.key.fields),
+        );
+        for field in fields {
+            if let Some(existing) = types.insert(field.name.as_str(), field.ty)
+                && existing != field.ty
+            {
+                return Err(format!(
+                    "Attribute {} has conflicting types ({} vs {})",
+                    field.name,
+                    type_label(existing),
+                    type_label(field.ty)
+                ));
+            }
+        }
+        for index in &self.collection.indexes {
+            if index.kind == IndexKind::LocalSecondary && !has_sort_key {
+                return Err("LSI requires a table sort key".to_string());
+            }
+            if !names.insert(index.name.as_str()) {
+                return Err(format!("Duplicate index name: {}", index.name));
+            }
+            if matches!(&index.projection, Projection::Include(attrs) if attrs.is_empty()) {
+                return Err(format!(
+                    "Index {} includes no attributes in its projection",
+                    index.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Create the table `spec` describes. Its TTL and provisioned capacity are
+/// set once it is active, since they can't be part of the create itself.
+pub async fn create(db: &dyn Datastore, spec: &TableSpec) -> Result<()> {
+    let capabilities = db.capabilities();
+    let provisioned = matches!(spec.capacity, Some(CapacitySpec::Provisioned { .. }));
+    if provisioned && !capabilities.capacity {
+        return Err(DbError::Backend(format!(
+            "{} has no provisioned capacity; drop billing from the spec",
+            capabilities.backend_label
+        )));
+    }
+    if spec.ttl_attribute.is_some() && !capabilities.ttl {
+        return Err(DbError::Backend(format!(
+            "{} has no TTL; drop ttl_attribute from the spec",
+            capabilities.backend_label
+        )));
+    }
+    let name = spec.collection.name.as_str();
+    db.create_collection(&spec.collection).await?;
+    if !provisioned && spec.ttl_attribute.is_none() {
+        return Ok(());
+    }
+    wait_until_active(db, name).await?;
+    if let Some(attribute) = spec.ttl_attribute.as_deref() {
+        db.enable_ttl(name, attribute).await?;
+    }
+    if let Some(capacity) = spec.capacity.as_ref().filter(|_| provisioned) {
+        db.update_capacity(name, capacity).await?;
+    }
+    Ok(())
+}
+
+fn key_field(key: &KeySchema, role: KeyRole) -> Option<Json> {
+    key.fields
+        .iter()
+        .find(|field| field.role == role)
+        .map(|field| json!({ "name": field.name, "type": type_label(field.ty) }))
+}
+
+fn index_json(index: &IndexSchema) -> Json {
+    let mut object = Map::new();
+    object.insert("name".to_string(), json!(index.name));
+    if index.kind != IndexKind::LocalSecondary
+        && let Some(field) = key_field(&index.key, KeyRole::Partition)
+    {
+        object.insert("hash_key".to_string(), field);
+    }
+    if let Some(field) = key_field(&index.key, KeyRole::Sort) {
+        object.insert("sort_key".to_string(), field);
+    }
+    let projection = match &index.projection {
+        Projection::All => json!("all"),
+        Projection::KeysOnly => json!("keys_only"),
+        Projection::Include(attrs) => json!({ "include": attrs }),
+    };
+    object.insert("projection".to_string(), projection);
+    Json::Object(object)
+}
+
+fn throughput_json(throughput: Throughput) -> Json {
+    json!({ "read": throughput.read, "write": throughput.write })
+}
+
+fn type_label(ty: ScalarType) -> &'static str {
+    match ty {
+        ScalarType::String => "S",
+        ScalarType::Number => "N",
+        ScalarType::Binary => "B",
+    }
+}
+
+fn json_object<'a>(
+    value: &'a Json,
+    field: &str,
+) -> std::result::Result<&'a Map<String, Json>, String> {
+    value
+        .as_object()
+        .ok_or_else(|| format!("{field} must be an object"))
+}
+
+fn json_string(
+    object: &Map<String, Json>,
+    key: &str,
+    parent: &str,
+) -> std::result::Result<String, String> {
+    match object.get(key) {
+        Some(Json::String(text)) if !text.trim().is_empty() => Ok(text.trim().to_string()),
+        _ => Err(format!("{parent}.{key} must be a non-empty string")),
+    }
+}
+
+fn json_array<'a>(
+    object: &'a Map<String, Json>,
+    key: &str,
+) -> std::result::Result<&'a [Json], String> {
+    match object.get(key) {
+        None | Some(Json::Null) => Ok(&[]),
+        Some(Json::Array(values)) => Ok(values),
+        Some(_) => Err(format!("{key} must be a list")),
+    }
+}
+
+/// `{ "name": "PK", "type": "S" }`; the type may also be spelled out, like
+/// `number`.
+fn json_key(
+    value: Option<&Json>,
+    field: &str,
+    role: KeyRole,
+) -> std::result::Result<KeyField, String> {
+    let object = json_object(value.unwrap_or(&Json::Null), field)?;
+    let ty = match object
+        .get("type")
+        .and_then(Json::as_str)
+        .map(|ty| ty.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("s" | "string") => ScalarType::String,
+        Some("n" | "number") => ScalarType::Number,
+        Some("b" | "binary") => ScalarType::Binary,
+        _ => return Err(format!("{field}.type must be S, N, or B")),
+    };
+    Ok(KeyField {
+        name: json_string(object, "name", field)?,
+        role,
+        ty,
+    })
+}
+
+fn json_optional_key(
+    value: Option<&Json>,
+    field: &str,
+    role: KeyRole,
+) -> std::result::Result<Option<KeyField>, String> {
+    match value {
+        None | Some(Json::Null) => Ok(None),
+        Some(value) => json_key(Some(value), field, role).map(Some),
+    }
+}
+
+/// `"all"`, `"keys_only"`, `{ "include": [...] }`, or `"include=a,b"` like
+/// the `--gsi` flag takes.
+fn json_projection(value: Option<&Json>, parent: &str) -> std::result::Result<Projection, String> {
+    let field = format!("{parent}.projection");
+    match value {
+        None | Some(Json::Null) => Ok(Projection::All),
+        Some(Json::String(token)) => {
+            let token = token.trim();
+            match token.to_ascii_lowercase().as_str() {
+                "all" => Ok(Projection::All),
+                "keys_only" | "keys-only" | "keys" => Ok(Projection::KeysOnly),
+                lower if lower.starts_with("include=") => Ok(Projection::Include(
+                    token["include=".len()..]
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|attr| !attr.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                )),
+                _ => Err(format!("{field}: Unknown projection: {token}")),
+            }
+        }
+        Some(Json::Object(object)) => match object.get("include") {
+            Some(Json::Array(attrs)) => attrs
+                .iter()
+                .map(|attr| attr.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .map(Projection::Include),
+            _ => None,
+        }
+        .ok_or_else(|| format!("{field}.include must be a list of attribute names")),
+        Some(_) => Err(format!("{field} must be a string or an object")),
+    }
+}
+
+/// `"on_demand"`, or `{ "read": 5, "write": 5 }` with optional `indexes`.
+fn json_billing(value: Option<&Json>) -> std::result::Result<Option<CapacitySpec>, String> {
+    let throughput = |value: &Json, field: &str| {
+        let units = |key: &str| {
+            value
+                .get(key)
+                .and_then(Json::as_i64)
+                .filter(|units| *units > 0)
+                .ok_or_else(|| format!("{field}.{key} must be a positive number"))
+        };
+        Ok::<_, String>(Throughput {
+            read: units("read")?,
+            write: units("write")?,
+        })
+    };
+    match value {
+        None | Some(Json::Null) => Ok(None),
+        Some(Json::String(mode)) if mode == "on_demand" => Ok(Some(CapacitySpec::OnDemand)),
+        Some(value @ Json::Object(object)) => {
+            let indexes = match object.get("indexes") {
+                None | Some(Json::Null) => Vec::new(),
+                Some(Json::Object(indexes)) => indexes
+                    .iter()
+                    .map(|(name, value)| {
+                        throughput(value, &format!("billing.indexes.{name}"))
+                            .map(|throughput| (name.clone(), throughput))
+                    })
+                    .collect::<std::result::Result<_, _>>()?,
+                Some(_) => return Err("billing.indexes must be an object".to_string()),
+            };
+            Ok(Some(CapacitySpec::Provisioned {
+                table: throughput(value, "billing")?,
+                indexes,
+            }))
+        }
+        Some(_) => Err(
+            "billing must be \"on_demand\" or an object with read and write capacity".to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::query::CapacitySpec;
+    use crate::core::schema::{
+        CollectionDetails, CollectionSchema, IndexDetails, IndexKind, Projection, ScalarType,
+        Throughput,
+    };
+
+    use super::TableSpec;
+
+    #[test]
+    fn reads_and_writes_specs() {
+        let spec = TableSpec::from_json(&serde_json::json!({
+            "table_name": "orders",
+            "hash_key": { "name": "PK", "type": "S" },
+            "sort_key": { "name": "SK", "type": "number" },
+            "gsis": [{
+                "name": "byStatus",
+                "hash_key": { "name": "status", "type": "S" },
+                "projection": { "include": ["total"] }
+            }],
+            "lsis": [{
+                "name": "byDate",
+                "sort_key": { "name": "date", "type": "S" },
+                "projection": "keys_only"
+            }],
+            "ttl_attribute": "expiresAt",
+            "billing": { "read": 5, "write": 2, "indexes": { "byStatus": { "read": 1, "write": 1 } } }
+        }))
+        .unwrap();
+        assert_eq!(spec.collection.name, "orders");
+        assert_eq!(spec.collection.key.fields[1].ty, ScalarType::Number);
+        assert_eq!(
+            spec.collection.indexes[0].projection,
+            Projection::Include(vec!["total".to_string()])
+        );
+        assert_eq!(spec.collection.indexes[1].kind, IndexKind::LocalSecondary);
+        assert_eq!(spec.ttl_attribute.as_deref(), Some("expiresAt"));
+        assert_eq!(
+            spec.capacity,
+            Some(CapacitySpec::Provisioned {
+                table: Throughput { read: 5, write: 2 },
+                indexes: vec![("byStatus".to_string(), Throughput { read: 1, write: 1 })],
+            })
+        );
+        assert_eq!(TableSpec::from_json(&spec.to_json()).unwrap(), spec);
+        assert_eq!(
+            TableSpec::from_toml(&spec.to_toml().unwrap()).unwrap(),
+            spec
+        );
+
+        let err = TableSpec::from_json(&serde_json::json!({
+            "table_name": "orders",
+            "hash_key": { "name": "PK", "type": "X" }
+        }))
+        .unwrap_err();
+        assert!(err.contains("hash_key.type"), "{err}");
+        let err = TableSpec::from_json(&serde_json::json!({
+            "table_name": "orders",
+            "hash_key": { "name": "PK", "type": "S" },
+            "lsis": [{ "name": "byDate", "sort_key": { "name": "date", "type": "S" } }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("LSI requires a table sort key"));
+    }
+
+    #[test]
+    fn dumps_an_existing_table() {
+        let spec = TableSpec::from_json(&serde_json::json!({
+            "table_name": "orders",
+            "hash_key": { "name": "PK", "type": "S" },
+            "gsis": [{ "name": "byStatus", "hash_key": { "name": "status", "type": "S" } }]
+        }))
+        .unwrap();
+        let details = CollectionDetails {
+            schema: CollectionSchema {
+                name: "orders".to_string(),
+                key: spec.collection.key.clone(),
+                indexes: spec.collection.indexes.clone(),
+                ttl_attribute: Some("expiresAt".to_string()),
+                item_count: Some(42),
+                ..CollectionSchema::default()
+            },
+            indexes: vec![IndexDetails {
+                throughput: Some(Throughput { read: 1, write: 1 }),
+                ..IndexDetails::from_schema(spec.collection.indexes[0].clone())
+            }],
+            billing_mode: Some("PROVISIONED".to_string()),
+            throughput: Some(Throughput { read: 5, write: 5 }),
+            ..CollectionDetails::default()
+        };
+        let dumped = TableSpec::from_details(&details).renamed("orders-staging");
+        assert_eq!(dumped.collection.name, "orders-staging");
+        assert_eq!(dumped.collection.indexes, spec.collection.indexes);
+        assert_eq!(dumped.ttl_attribute.as_deref(), Some("expiresAt"));
+        assert_eq!(
+            dumped.to_json()["billing"],
+            serde_json::json!({
+                "read": 5,
+                "write": 5,
+                "indexes": { "byStatus": { "read": 1, "write": 1 } }
+            })
+        );
+
+        let on_demand = CollectionDetails {
+            billing_mode: Some("PAY_PER_REQUEST".to_string()),
+            ..details
+        };
+        assert_eq!(
+            TableSpec::from_details(&on_demand).capacity,
+            Some(CapacitySpec::OnDemand)
+        );
+    }
+}
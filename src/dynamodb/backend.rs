@@ -8,35 +8,47 @@
 //! [`Value`]: crate::core::value::Value
 
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use aws_sdk_dynamodb::Client;
 use aws_sdk_dynamodb::types::{
-    DeleteRequest, KeyType, ScalarAttributeType, TableDescription, TimeToLiveStatus, WriteRequest,
+    DeleteRequest, KeyType, KeysAndAttributes, PointInTimeRecoveryStatus, PutRequest,
+    ScalarAttributeType, TableDescription, TimeToLiveStatus, WriteRequest,
 };
 
 use crate::core::capabilities::{Capabilities, SecondaryIndexSupport};
+use crate::core::connect::{ProfileInfo, ProfileSelection, RoleSelection};
 use crate::core::datastore::Datastore;
 use crate::core::error::{DbError, Result};
+use crate::core::key_list::{item_key_text, key_text};
+use crate::core::metrics::{CollectionMetrics, MetricsWindow};
 use crate::core::query::{
-    BatchDeleteOutcome, CreateCollectionSpec, IndexHint, Key, Page, PlanExplanation, PlanKind,
-    QueryCost, QueryPlan, QueryResult,
+    BatchDeleteOutcome, BatchPutOutcome, CapacitySpec, CollectionPage, CreateCollectionSpec,
+    ImportSource, IndexHint, ItemUpdate, Key, Page, PlanExplanation, PlanKind, QueryCost,
+    QueryPlan, QueryResult, TransactOutcome, TransactWrite,
 };
 use crate::core::schema::{
-    CollectionSchema, IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, Projection, ScalarType,
+    BackupInfo, CollectionDetails, CollectionSchema, ImportInfo, IndexKind, IndexSchema, KeyField,
+    KeyRole, KeySchema, Projection, ReplicaDetails, ScalarType, Throughput,
 };
 use crate::core::value::Item;
+use crate::expr::{SortOrder, split_order_clause};
 
 use super::convert::{attribute_map_from_item, item_from_attribute_map, value_to_attribute_value};
 use super::create_table::{
     AttributeType, CreateTableSpec, GsiSpec, IndexProjection, KeySpec, LsiSpec, create_table,
 };
 use super::executor::{self, Kind, Output};
-use super::language::parse_query_text;
+use super::language::parse_query_with_parameters;
+use super::partiql;
 use super::request_builder::DynamoDbRequest;
-use super::table_analyzer::{KeyCondition, KeyConditionType, QueryType, TableInfo};
+use super::table_analyzer::{
+    KeyCondition, KeyConditionType, QueryType, TableInfo, coerce_key_values, key_attribute_types,
+};
 use super::{QueryBuilder, ScanBuilder, format_sdk_error, send_dynamo_request};
+use super::{backups, imports, metrics, transact, update_item, update_table};
 
 use crate::core::query::KeyEquals;
 
@@ -54,28 +66,95 @@ const CAPABILITIES: Capabilities = Capabilities {
     scanned_count: true,
     consumed_capacity: true,
     raw_query: false,
+    statements: true,
+    profiles: true,
+    parallel_scan: true,
+    index_management: true,
+    capacity: true,
+    partial_update: true,
+    read_options: true,
+    backups: true,
+    batch_get: true,
+    transactions: true,
+    regions: true,
+    replicas: true,
+    imports: true,
+    metrics: true,
+    typed_keys: true,
 };
 
-/// Maximum number of delete requests per `BatchWriteItem` call.
+/// Maximum number of write requests per `BatchWriteItem` call.
 const BATCH_WRITE_CHUNK: usize = 25;
+/// Maximum number of keys per `BatchGetItem` call.
+const BATCH_GET_CHUNK: usize = 100;
+/// Wait before re-sending unprocessed requests, doubled per retry.
+const UNPROCESSED_RETRY_DELAY: Duration = Duration::from_millis(50);
+/// Retries in a row that make no progress before a batch request gives up.
+const UNPROCESSED_MAX_STALLS: u32 = 8;
 
 pub struct DynamoBackend {
-    client: Client,
+    /// Swapped wholesale when the user switches profile/region; callers clone
+    /// the (cheap, `Arc`-backed) client per request.
+    client: RwLock<Client>,
     read_only: bool,
+    /// Kept so a profile/region switch rebuilds the client against the same
+    /// endpoint.
+    endpoint_url: Option<String>,
+    selection: RwLock<ProfileSelection>,
     /// Cache of table descriptions, used to route queries without an extra
     /// `DescribeTable` per page. Invalidated on create/drop.
     schema_cache: Mutex<HashMap<String, TableDescription>>,
+    /// Built on first use by the metrics view, for the current selection.
+    cloudwatch: Mutex<Option<aws_sdk_cloudwatch::Client>>,
 }
 
 impl DynamoBackend {
     pub fn new(client: Client, read_only: bool) -> Self {
+        let selection = ProfileSelection {
+            region: client.config().region().map(ToString::to_string),
+            ..ProfileSelection::default()
+        };
         Self {
-            client,
+            client: RwLock::new(client),
             read_only,
+            endpoint_url: None,
+            selection: RwLock::new(selection),
             schema_cache: Mutex::new(HashMap::new()),
+            cloudwatch: Mutex::new(None),
         }
     }
 
+    /// Record the custom endpoint the client was built for, so profile
+    /// switches keep targeting it.
+    #[must_use]
+    pub fn with_endpoint_url(mut self, endpoint_url: Option<String>) -> Self {
+        self.endpoint_url = endpoint_url;
+        self
+    }
+
+    /// Record the role the client was built with, for the title bar.
+    #[must_use]
+    pub fn with_role(self, role: Option<RoleSelection>) -> Self {
+        self.selection.write().unwrap().role = role;
+        self
+    }
+
+    fn client(&self) -> Client {
+        self.client.read().unwrap().clone()
+    }
+
+    async fn cloudwatch(&self) -> Result<aws_sdk_cloudwatch::Client> {
+        if let Some(client) = self.cloudwatch.lock().unwrap().clone() {
+            return Ok(client);
+        }
+        let selection = self.selection.read().unwrap().clone();
+        let client = metrics::connect(&selection)
+            .await
+            .map_err(DbError::Backend)?;
+        *self.cloudwatch.lock().unwrap() = Some(client.clone());
+        Ok(client)
+    }
+
     fn cached_description(&self, name: &str) -> Option<TableDescription> {
         self.schema_cache.lock().unwrap().get(name).cloned()
     }
@@ -104,7 +183,7 @@ impl DynamoBackend {
         let span = tracing::trace_span!("DescribeTable", table = %name);
         let result = send_dynamo_request(
             span,
-            || self.client.describe_table().table_name(name).send(),
+            || self.client().describe_table().table_name(name).send(),
             format_sdk_error,
         )
         .await;
@@ -115,11 +194,39 @@ impl DynamoBackend {
             .ok_or_else(|| DbError::NotFound(name.to_string()))
     }
 
+    /// Whether point-in-time recovery is enabled; `None` when the status can't
+    /// be read (e.g. DynamoDB Local, or missing permissions).
+    async fn fetch_point_in_time_recovery(&self, name: &str) -> Option<bool> {
+        let span = tracing::trace_span!("DescribeContinuousBackups", table = %name);
+        let output = send_dynamo_request(
+            span,
+            || {
+                self.client()
+                    .describe_continuous_backups()
+                    .table_name(name)
+                    .send()
+            },
+            std::string::ToString::to_string,
+        )
+        .await
+        .ok()?;
+        let status = output
+            .continuous_backups_description()?
+            .point_in_time_recovery_description()?
+            .point_in_time_recovery_status()?;
+        Some(matches!(status, PointInTimeRecoveryStatus::Enabled))
+    }
+
     async fn fetch_ttl_attribute(&self, name: &str) -> Option<String> {
         let span = tracing::trace_span!("DescribeTimeToLive", table = %name);
         let output = send_dynamo_request(
             span,
-            || self.client.describe_time_to_live().table_name(name).send(),
+            || {
+                self.client()
+                    .describe_time_to_live()
+                    .table_name(name)
+                    .send()
+            },
             std::string::ToString::to_string,
         )
         .await
@@ -136,8 +243,126 @@ impl DynamoBackend {
         }
     }
 
+    /// Send write requests through `BatchWriteItem` in chunks, returning how
+    /// many were applied. Unprocessed requests, which DynamoDB hands back when
+    /// the table is throttled, are re-sent with exponential backoff.
+    async fn batch_write(&self, name: &str, requests: Vec<WriteRequest>) -> Result<u64> {
+        let mut applied = 0_u64;
+        for chunk in requests.chunks(BATCH_WRITE_CHUNK) {
+            let mut requests = chunk.to_vec();
+            let mut retries = 0_u32;
+            let mut stalls = 0_u32;
+
+            // Retry unprocessed items until the batch drains.
+            loop {
+                let batch = HashMap::from([(name.to_string(), requests.clone())]);
+                let span = tracing::trace_span!("BatchWriteItem", table = %name);
+                let output = send_dynamo_request(
+                    span,
+                    || {
+                        self.client()
+                            .batch_write_item()
+                            .set_request_items(Some(batch.clone()))
+                            .send()
+                    },
+                    format_sdk_error,
+                )
+                .await
+                .map_err(|err| DbError::Backend(format_sdk_error(&err)))?;
+
+                let unprocessed = output
+                    .unprocessed_items()
+                    .and_then(|items| items.get(name))
+                    .cloned()
+                    .unwrap_or_default();
+                let processed = requests.len() - unprocessed.len();
+                applied += processed as u64;
+                requests = unprocessed;
+                if requests.is_empty() {
+                    break;
+                }
+                stalls = if processed == 0 { stalls + 1 } else { 0 };
+                if stalls > UNPROCESSED_MAX_STALLS {
+                    return Err(DbError::Backend(format!(
+                        "{} writes to {name} still unprocessed after {UNPROCESSED_MAX_STALLS} retries",
+                        requests.len()
+                    )));
+                }
+                tokio::time::sleep(UNPROCESSED_RETRY_DELAY * 2_u32.pow(retries.min(6))).await;
+                retries += 1;
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Fetch keys through `BatchGetItem` in chunks. Unprocessed keys are
+    /// re-sent with exponential backoff, like unprocessed writes.
+    async fn batch_get_items(
+        &self,
+        name: &str,
+        keys: &[Key],
+        consistent_read: bool,
+    ) -> Result<Vec<Item>> {
+        let mut items = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(BATCH_GET_CHUNK) {
+            let mut pending: Vec<_> = chunk
+                .iter()
+                .map(|key| attribute_map_from_item(&key.0))
+                .collect();
+            let mut retries = 0_u32;
+            let mut stalls = 0_u32;
+
+            loop {
+                let request = KeysAndAttributes::builder()
+                    .set_keys(Some(pending.clone()))
+                    .consistent_read(consistent_read)
+                    .build()
+                    .map_err(|err| DbError::Backend(err.to_string()))?;
+                let batch = HashMap::from([(name.to_string(), request)]);
+                let span = tracing::trace_span!("BatchGetItem", table = %name);
+                let output = send_dynamo_request(
+                    span,
+                    || {
+                        self.client()
+                            .batch_get_item()
+                            .set_request_items(Some(batch.clone()))
+                            .send()
+                    },
+                    format_sdk_error,
+                )
+                .await
+                .map_err(|err| DbError::Backend(format_sdk_error(&err)))?;
+
+                if let Some(found) = output.responses().and_then(|tables| tables.get(name)) {
+                    items.extend(found.iter().map(item_from_attribute_map));
+                }
+                let unprocessed = output
+                    .unprocessed_keys()
+                    .and_then(|tables| tables.get(name))
+                    .map(|request| request.keys().to_vec())
+                    .unwrap_or_default();
+                let processed = pending.len() - unprocessed.len();
+                pending = unprocessed;
+                if pending.is_empty() {
+                    break;
+                }
+                stalls = if processed == 0 { stalls + 1 } else { 0 };
+                if stalls > UNPROCESSED_MAX_STALLS {
+                    return Err(DbError::Backend(format!(
+                        "{} keys in {name} still unprocessed after {UNPROCESSED_MAX_STALLS} retries",
+                        pending.len()
+                    )));
+                }
+                tokio::time::sleep(UNPROCESSED_RETRY_DELAY * 2_u32.pow(retries.min(6))).await;
+                retries += 1;
+            }
+        }
+        Ok(items)
+    }
+
     /// Parse a plan's text filter (with the partition-key shortcut), then build
-    /// the SDK request.
+    /// the SDK request. An `ORDER ASC|DESC` clause in the text overrides the
+    /// plan's `descending`.
     fn build_request(
         &self,
         plan: &QueryPlan,
@@ -145,21 +370,35 @@ impl DynamoBackend {
     ) -> Result<DynamoDbRequest> {
         let table_info = TableInfo::from_table_description(table_desc);
         let hash_key = Some(table_info.primary_key.hash_key.as_str()).filter(|key| !key.is_empty());
-        let filter = match plan
-            .filter
-            .as_deref()
-            .map(str::trim)
-            .filter(|t| !t.is_empty())
-        {
-            Some(text) => Some(parse_query_text(text, hash_key).map_err(DbError::Backend)?),
-            None => None,
+        let (text, order) = split_order_clause(plan.filter.as_deref().unwrap_or_default());
+        let descending = order.map_or(plan.descending, |order| order == SortOrder::Descending);
+        let text = text.trim();
+        let filter = if text.is_empty() {
+            None
+        } else {
+            Some(
+                parse_query_with_parameters(text, hash_key, &plan.parameters)
+                    .map_err(DbError::Backend)?,
+            )
         };
-        Ok(self.build_request_for(
+        let filter =
+            filter.map(|filter| coerce_key_values(&filter, &key_attribute_types(table_desc)));
+        let mut request = self.build_request_for(
             filter.as_ref(),
             plan.index_hint.as_ref(),
             plan.key_equals.as_ref(),
             table_desc,
-        ))
+        );
+        if plan.count_only {
+            request.set_count_only(true);
+        } else {
+            request.set_projection(&plan.projection);
+        }
+        if let DynamoDbRequest::Query(builder) = &mut request {
+            builder.set_scan_index_forward(!descending);
+        }
+        request.check_limits().map_err(DbError::Backend)?;
+        Ok(request)
     }
 
     fn build_request_for(
@@ -178,18 +417,29 @@ impl DynamoBackend {
             return DynamoDbRequest::Query(Box::new(QueryBuilder::from_query_type(query_type)));
         }
 
-        let Some(filter) = filter else {
-            return DynamoDbRequest::Scan(ScanBuilder::new());
-        };
-        match index_hint {
-            None => DynamoDbRequest::from_expression_and_table(filter, table_desc),
-            Some(IndexHint::Primary) => {
+        let mut request = match (filter, index_hint) {
+            (None, _) => DynamoDbRequest::Scan(ScanBuilder::new()),
+            (Some(filter), None) => DynamoDbRequest::from_expression_and_table(filter, table_desc),
+            (Some(filter), Some(IndexHint::Primary)) => {
                 request_from_query_type(table_info.primary_query_type(filter), filter)
             }
-            Some(IndexHint::Named(index)) => {
+            (Some(filter), Some(IndexHint::Named(index))) => {
                 request_from_query_type(table_info.index_query_type(index, filter), filter)
             }
+        };
+        // A named index that can't serve the filter as a key condition is
+        // scanned instead of the table.
+        if let DynamoDbRequest::Scan(builder) = &mut request {
+            builder.set_index_name(named_index(index_hint));
         }
+        request
+    }
+}
+
+fn named_index(index_hint: Option<&IndexHint>) -> Option<String> {
+    match index_hint {
+        Some(IndexHint::Named(index)) => Some(index.clone()),
+        None | Some(IndexHint::Primary) => None,
     }
 }
 
@@ -258,7 +508,7 @@ impl Datastore for DynamoBackend {
         let span = tracing::trace_span!("ListTables", validation = true, limit = 1);
         send_dynamo_request(
             span,
-            || self.client.list_tables().limit(1).send(),
+            || self.client().list_tables().limit(1).send(),
             std::string::ToString::to_string,
         )
         .await
@@ -270,18 +520,9 @@ impl Datastore for DynamoBackend {
         let mut names = Vec::new();
         let mut start = None;
         loop {
-            let span = tracing::trace_span!("ListTables");
-            let request = self
-                .client
-                .list_tables()
-                .set_exclusive_start_table_name(start.clone());
-            let output = send_dynamo_request(span, || request.send(), format_sdk_error)
-                .await
-                .map_err(|err| DbError::Backend(format_sdk_error(&err)))?;
-            names.extend(output.table_names().iter().cloned());
-            start = output
-                .last_evaluated_table_name()
-                .map(std::string::ToString::to_string);
+            let page = self.list_collections_page(start.as_deref()).await?;
+            names.extend(page.names);
+            start = page.next;
             if start.is_none() {
                 break;
             }
@@ -289,6 +530,23 @@ impl Datastore for DynamoBackend {
         Ok(names)
     }
 
+    async fn list_collections_page(&self, start: Option<&str>) -> Result<CollectionPage> {
+        let span = tracing::trace_span!("ListTables");
+        let request = self
+            .client()
+            .list_tables()
+            .set_exclusive_start_table_name(start.map(str::to_string));
+        let output = send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+            .await
+            .map_err(|err| DbError::Backend(format_sdk_error(&err)))?;
+        Ok(CollectionPage {
+            names: output.table_names().to_vec(),
+            next: output
+                .last_evaluated_table_name()
+                .map(std::string::ToString::to_string),
+        })
+    }
+
     async fn describe_collection(&self, name: &str) -> Result<CollectionSchema> {
         // A single DescribeTable; TTL is fetched separately via `describe_ttl`
         // only by callers that need it (the picker lists many tables and doesn't).
@@ -297,14 +555,54 @@ impl Datastore for DynamoBackend {
         Ok(collection_schema_from(&desc, None))
     }
 
+    async fn describe_details(&self, name: &str) -> Result<CollectionDetails> {
+        // Always fresh: the details view is where users watch status changes.
+        let desc = self.fetch_table_description(name).await?;
+        self.cache_description(name, desc.clone());
+        let ttl_attribute = self.fetch_ttl_attribute(name).await;
+        let point_in_time_recovery = self.fetch_point_in_time_recovery(name).await;
+        let mut details = collection_details_from(&desc, ttl_attribute);
+        details.point_in_time_recovery = point_in_time_recovery;
+        Ok(details)
+    }
+
     async fn query(&self, name: &str, plan: &QueryPlan, page: Page) -> Result<QueryResult> {
         let table_desc = self.table_description(name).await?;
         let request = self.build_request(plan, &table_desc)?;
-        let start_key = page.cursor.map(|cursor| attribute_map_from_item(&cursor.0));
-        let limit = page.limit.map(|value| value as i32);
-        let output = executor::execute_page(&self.client, name, &request, start_key, limit)
+        let table_info = TableInfo::from_table_description(&table_desc);
+        // A query pinning one item by its full primary key is served by the
+        // cheaper GetItem; it has no further pages. Counting keeps the Query,
+        // which returns no item.
+        if page.cursor.is_none()
+            && !plan.count_only
+            && let Some(key) = request
+                .query_builder()
+                .and_then(|builder| builder.primary_key_lookup(&table_info))
+            && let Some(builder) = request.query_builder()
+        {
+            let output = executor::execute_get_item(
+                &self.client(),
+                name,
+                builder,
+                key,
+                plan.consistent_read,
+            )
             .await
             .map_err(|err| DbError::Backend(err.to_string()))?;
+            return Ok(query_result_from(output));
+        }
+        let start_key = page.cursor.map(|cursor| attribute_map_from_item(&cursor.0));
+        let limit = page.limit.map(|value| value as i32);
+        let output = executor::execute_page(
+            &self.client(),
+            name,
+            &request,
+            start_key,
+            limit,
+            plan.consistent_read,
+        )
+        .await
+        .map_err(|err| DbError::Backend(err.to_string()))?;
         Ok(query_result_from(output))
     }
 
@@ -317,7 +615,7 @@ impl Datastore for DynamoBackend {
         send_dynamo_request(
             span,
             || {
-                self.client
+                self.client()
                     .put_item()
                     .table_name(name)
                     .set_item(Some(attributes.clone()))
@@ -330,6 +628,15 @@ impl Datastore for DynamoBackend {
         .map_err(|err| DbError::Backend(format_sdk_error(&err)))
     }
 
+    async fn update_item(&self, name: &str, key: Key, update: &ItemUpdate) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        update_item::update_item(self.client(), name, &key.0, update)
+            .await
+            .map_err(DbError::Backend)
+    }
+
     async fn delete_item(&self, name: &str, key: Key) -> Result<()> {
         if self.read_only {
             return Err(DbError::ReadOnly);
@@ -339,7 +646,7 @@ impl Datastore for DynamoBackend {
         send_dynamo_request(
             span,
             || {
-                self.client
+                self.client()
                     .delete_item()
                     .table_name(name)
                     .set_key(Some(key_map.clone()))
@@ -356,46 +663,65 @@ impl Datastore for DynamoBackend {
         if self.read_only {
             return Err(DbError::ReadOnly);
         }
-        let mut deleted = 0_u64;
-        for chunk in keys.chunks(BATCH_WRITE_CHUNK) {
-            let mut requests: Vec<WriteRequest> = chunk
-                .iter()
-                .map(|key| {
-                    let delete = DeleteRequest::builder()
-                        .set_key(Some(attribute_map_from_item(&key.0)))
-                        .build()
-                        .map_err(|err| DbError::Backend(err.to_string()))?;
-                    Ok(WriteRequest::builder().delete_request(delete).build())
-                })
-                .collect::<Result<_>>()?;
+        let requests = keys
+            .iter()
+            .map(|key| {
+                let delete = DeleteRequest::builder()
+                    .set_key(Some(attribute_map_from_item(&key.0)))
+                    .build()
+                    .map_err(|err| DbError::Backend(err.to_string()))?;
+                Ok(WriteRequest::builder().delete_request(delete).build())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let deleted = self.batch_write(name, requests).await?;
+        Ok(BatchDeleteOutcome { deleted })
+    }
 
-            // Retry unprocessed items until the batch drains.
-            while !requests.is_empty() {
-                let batch = HashMap::from([(name.to_string(), requests.clone())]);
-                let span = tracing::trace_span!("BatchWriteItem", table = %name);
-                let output = send_dynamo_request(
-                    span,
-                    || {
-                        self.client
-                            .batch_write_item()
-                            .set_request_items(Some(batch.clone()))
-                            .send()
-                    },
-                    format_sdk_error,
-                )
-                .await
-                .map_err(|err| DbError::Backend(format_sdk_error(&err)))?;
+    async fn batch_put(&self, name: &str, items: Vec<Item>) -> Result<BatchPutOutcome> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let requests = items
+            .iter()
+            .map(|item| {
+                let put = PutRequest::builder()
+                    .set_item(Some(attribute_map_from_item(item)))
+                    .build()
+                    .map_err(|err| DbError::Backend(err.to_string()))?;
+                Ok(WriteRequest::builder().put_request(put).build())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let written = self.batch_write(name, requests).await?;
+        Ok(BatchPutOutcome { written })
+    }
 
-                let unprocessed = output
-                    .unprocessed_items()
-                    .and_then(|items| items.get(name))
-                    .cloned()
-                    .unwrap_or_default();
-                deleted += (requests.len() - unprocessed.len()) as u64;
-                requests = unprocessed;
-            }
+    async fn batch_get(
+        &self,
+        name: &str,
+        keys: Vec<Key>,
+        consistent_read: bool,
+    ) -> Result<Vec<Item>> {
+        let desc = self.table_description(name).await?;
+        let schema = collection_schema_from(&desc, None).key;
+        let items = self.batch_get_items(name, &keys, consistent_read).await?;
+        // BatchGetItem returns items in no particular order.
+        let mut by_key: HashMap<String, Item> = items
+            .into_iter()
+            .map(|item| (item_key_text(&item, &schema), item))
+            .collect();
+        Ok(keys
+            .iter()
+            .filter_map(|key| by_key.remove(&key_text(key, &schema)))
+            .collect())
+    }
+
+    async fn transact_write(&self, writes: &[TransactWrite]) -> Result<TransactOutcome> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
         }
-        Ok(BatchDeleteOutcome { deleted })
+        transact::transact_write(self.client(), writes)
+            .await
+            .map_err(DbError::Backend)
     }
 
     async fn create_collection(&self, spec: &CreateCollectionSpec) -> Result<()> {
@@ -403,13 +729,91 @@ impl Datastore for DynamoBackend {
             return Err(DbError::ReadOnly);
         }
         let table_spec = create_table_spec_from(spec)?;
-        let result = create_table(self.client.clone(), table_spec)
+        let result = create_table(self.client(), table_spec)
             .await
             .map_err(DbError::Backend);
         self.invalidate(&spec.name);
         result
     }
 
+    async fn create_index(&self, name: &str, index: &IndexSchema) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        if index.kind == IndexKind::LocalSecondary {
+            return Err(DbError::Backend(
+                "Local secondary indexes can only be defined when the table is created".to_string(),
+            ));
+        }
+        let gsi = gsi_spec_from(index)?;
+        // Provisioned tables must provision the new index too; start it at the
+        // table's own capacity. On-demand tables report no throughput.
+        let desc = self.fetch_table_description(name).await?;
+        let throughput = throughput_from(desc.provisioned_throughput());
+        let result = update_table::create_gsi(self.client(), name, &gsi, throughput)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(name);
+        result
+    }
+
+    async fn update_capacity(&self, name: &str, spec: &CapacitySpec) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let desc = self.fetch_table_description(name).await?;
+        let current = collection_details_from(&desc, None);
+        let result = update_table::update_capacity(self.client(), name, spec, &current)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(name);
+        result
+    }
+
+    async fn enable_ttl(&self, name: &str, attribute: &str) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let result = update_table::enable_ttl(self.client(), name, attribute)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(name);
+        result
+    }
+
+    async fn add_replica(&self, name: &str, region: &str) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let result = update_table::update_replica(self.client(), name, region, true)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(name);
+        result
+    }
+
+    async fn remove_replica(&self, name: &str, region: &str) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let result = update_table::update_replica(self.client(), name, region, false)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(name);
+        result
+    }
+
+    async fn drop_index(&self, name: &str, index_name: &str) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let result = update_table::delete_gsi(self.client(), name, index_name)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(name);
+        result
+    }
+
     async fn drop_collection(&self, name: &str) -> Result<()> {
         if self.read_only {
             return Err(DbError::ReadOnly);
@@ -417,7 +821,7 @@ impl Datastore for DynamoBackend {
         let span = tracing::trace_span!("DeleteTable", table = %name);
         let result = send_dynamo_request(
             span,
-            || self.client.delete_table().table_name(name).send(),
+            || self.client().delete_table().table_name(name).send(),
             format_sdk_error,
         )
         .await
@@ -427,6 +831,79 @@ impl Datastore for DynamoBackend {
         result
     }
 
+    async fn collection_metrics(
+        &self,
+        name: &str,
+        window: MetricsWindow,
+    ) -> Result<CollectionMetrics> {
+        if self.endpoint_url.is_some() {
+            return Err(DbError::Unsupported(
+                "CloudWatch metrics aren't available for a custom endpoint",
+            ));
+        }
+        let now = chrono::Utc::now().timestamp();
+        metrics::table_metrics(self.cloudwatch().await?, name, window, now)
+            .await
+            .map_err(DbError::Backend)
+    }
+
+    async fn list_backups(&self, name: &str) -> Result<Vec<BackupInfo>> {
+        backups::list_backups(self.client(), name)
+            .await
+            .map_err(DbError::Backend)
+    }
+
+    async fn create_backup(&self, name: &str, backup_name: &str) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        backups::create_backup(self.client(), name, backup_name)
+            .await
+            .map_err(DbError::Backend)
+    }
+
+    async fn delete_backup(&self, backup_id: &str) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        backups::delete_backup(self.client(), backup_id)
+            .await
+            .map_err(DbError::Backend)
+    }
+
+    async fn restore_backup(&self, backup_id: &str, target: &str) -> Result<()> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let result = backups::restore_table_from_backup(self.client(), backup_id, target)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(target);
+        result
+    }
+
+    async fn import_collection(
+        &self,
+        spec: &CreateCollectionSpec,
+        source: &ImportSource,
+    ) -> Result<String> {
+        if self.read_only {
+            return Err(DbError::ReadOnly);
+        }
+        let table_spec = create_table_spec_from(spec)?;
+        let result = imports::import_table(self.client(), table_spec, source)
+            .await
+            .map_err(DbError::Backend);
+        self.invalidate(&spec.name);
+        result
+    }
+
+    async fn list_imports(&self) -> Result<Vec<ImportInfo>> {
+        imports::list_imports(self.client())
+            .await
+            .map_err(DbError::Backend)
+    }
+
     async fn describe_ttl(&self, name: &str) -> Result<Option<String>> {
         Ok(self.fetch_ttl_attribute(name).await)
     }
@@ -438,7 +915,13 @@ impl Datastore for DynamoBackend {
         let Ok(request) = self.build_request(plan, &table_desc) else {
             return PlanExplanation::Unknown;
         };
+        let table_info = TableInfo::from_table_description(&table_desc);
         let kind = match request {
+            DynamoDbRequest::Query(builder)
+                if builder.primary_key_lookup(&table_info).is_some() =>
+            {
+                PlanKind::Lookup
+            }
             DynamoDbRequest::Scan(_) => PlanKind::Scan,
             DynamoDbRequest::Query(builder) => PlanKind::IndexedQuery {
                 index: builder.index_name().cloned(),
@@ -446,11 +929,141 @@ impl Datastore for DynamoBackend {
         };
         PlanExplanation::Predicted(kind)
     }
+
+    async fn execute_statement(&self, statement: &str, page: Page) -> Result<QueryResult> {
+        if self.read_only && partiql::is_mutating(statement) {
+            return Err(DbError::ReadOnly);
+        }
+        let next_token = page.cursor.as_ref().and_then(partiql::token_from_cursor);
+        let limit = page.limit.map(|value| value as i32);
+        let output = partiql::execute_statement(&self.client(), statement, next_token, limit)
+            .await
+            .map_err(DbError::Backend)?;
+        let items: Vec<Item> = output.items().iter().map(item_from_attribute_map).collect();
+        Ok(QueryResult {
+            count: items.len() as u64,
+            items,
+            scanned_count: None,
+            next: output.next_token().map(partiql::cursor_from_token),
+            plan_kind: PlanKind::Statement,
+            cost: output.consumed_capacity().map(|capacity| QueryCost {
+                capacity_units: capacity.capacity_units(),
+            }),
+        })
+    }
+
+    async fn scan_segment(
+        &self,
+        name: &str,
+        plan: &QueryPlan,
+        segment: u32,
+        total_segments: u32,
+        page: Page,
+    ) -> Result<QueryResult> {
+        let table_desc = self.table_description(name).await?;
+        let table_info = TableInfo::from_table_description(&table_desc);
+        let hash_key = Some(table_info.primary_key.hash_key.as_str()).filter(|key| !key.is_empty());
+        // Scans have no order, so an `ORDER` clause is dropped.
+        let mut builder = match plan
+            .filter
+            .as_deref()
+            .map(|text| split_order_clause(text).0.trim())
+            .filter(|t| !t.is_empty())
+        {
+            Some(text) => {
+                let filter = parse_query_with_parameters(text, hash_key, &plan.parameters)
+                    .map_err(DbError::Backend)?;
+                ScanBuilder::from_expression(&filter)
+            }
+            None => ScanBuilder::new(),
+        };
+        builder.set_projection(&plan.projection);
+        builder.set_index_name(named_index(plan.index_hint.as_ref()));
+        let segment = (
+            i32::try_from(segment).map_err(|err| DbError::Backend(err.to_string()))?,
+            i32::try_from(total_segments).map_err(|err| DbError::Backend(err.to_string()))?,
+        );
+        let start_key = page.cursor.map(|cursor| attribute_map_from_item(&cursor.0));
+        let limit = page.limit.map(|value| value as i32);
+        let output = executor::execute_scan_segment(
+            &self.client(),
+            name,
+            &builder,
+            segment,
+            start_key,
+            limit,
+            plan.consistent_read,
+        )
+        .await
+        .map_err(|err| DbError::Backend(err.to_string()))?;
+        Ok(query_result_from(output))
+    }
+
+    async fn list_profiles(&self) -> Result<Vec<ProfileInfo>> {
+        Ok(super::profiles::list_profiles())
+    }
+
+    fn active_profile(&self) -> Option<ProfileSelection> {
+        Some(self.selection.read().unwrap().clone())
+    }
+
+    async fn for_region(&self, region: &str) -> Result<Arc<dyn Datastore>> {
+        let selection = ProfileSelection {
+            region: Some(region.to_string()),
+            ..self.selection.read().unwrap().clone()
+        };
+        let client = if selection.role.is_some() {
+            // The role's credentials work in every region, and assuming it
+            // again could need a fresh MFA code.
+            let config = self
+                .client()
+                .config()
+                .to_builder()
+                .region(aws_sdk_dynamodb::config::Region::new(region.to_string()))
+                .build();
+            Client::from_conf(config)
+        } else {
+            super::connect::new_client_for(&selection, self.endpoint_url.as_deref())
+                .await
+                .map_err(DbError::Backend)?
+        };
+        let backend =
+            Self::new(client, self.read_only).with_endpoint_url(self.endpoint_url.clone());
+        *backend.selection.write().unwrap() = selection;
+        Ok(Arc::new(backend))
+    }
+
+    async fn switch_profile(&self, selection: &ProfileSelection) -> Result<()> {
+        let client = super::connect::new_client_for(selection, self.endpoint_url.as_deref())
+            .await
+            .map_err(DbError::Backend)?;
+        let span = tracing::trace_span!("ListTables", validation = true, limit = 1);
+        send_dynamo_request(
+            span,
+            || client.list_tables().limit(1).send(),
+            format_sdk_error,
+        )
+        .await
+        .map_err(|err| DbError::Backend(format_sdk_error(&err)))?;
+
+        let region = client.config().region().map(ToString::to_string);
+        *self.client.write().unwrap() = client;
+        *self.selection.write().unwrap() = ProfileSelection {
+            profile: selection.profile.clone(),
+            region,
+            role: selection.role.clone(),
+        };
+        // Table descriptions belong to the previous account/region.
+        self.schema_cache.lock().unwrap().clear();
+        *self.cloudwatch.lock().unwrap() = None;
+        Ok(())
+    }
 }
 
 fn query_result_from(output: Output) -> QueryResult {
     let plan_kind = match output.kind() {
         Kind::Scan => PlanKind::Scan,
+        Kind::GetItem => PlanKind::Lookup,
         Kind::Query => PlanKind::IndexedQuery { index: None },
         Kind::QueryGSI(name) | Kind::QueryLSI(name) => PlanKind::IndexedQuery {
             index: Some(name.clone()),
@@ -510,6 +1123,80 @@ fn collection_schema_from(
     }
 }
 
+fn collection_details_from(
+    desc: &TableDescription,
+    ttl_attribute: Option<String>,
+) -> CollectionDetails {
+    let mut details = CollectionDetails::from(collection_schema_from(desc, ttl_attribute));
+    for index in &mut details.indexes {
+        let name = index.schema.name.as_str();
+        if let Some(gsi) = desc
+            .global_secondary_indexes()
+            .iter()
+            .find(|gsi| gsi.index_name() == Some(name))
+        {
+            index.status = gsi.index_status().map(|status| status.as_str().to_string());
+            index.backfilling = gsi.backfilling().unwrap_or(false);
+            index.item_count = gsi.item_count();
+            index.size_bytes = gsi.index_size_bytes();
+            index.throughput = throughput_from(gsi.provisioned_throughput());
+        } else if let Some(lsi) = desc
+            .local_secondary_indexes()
+            .iter()
+            .find(|lsi| lsi.index_name() == Some(name))
+        {
+            index.item_count = lsi.item_count();
+            index.size_bytes = lsi.index_size_bytes();
+        }
+    }
+    let throughput = throughput_from(desc.provisioned_throughput());
+    // Tables created before billing modes existed omit the summary; they are
+    // provisioned.
+    details.billing_mode = desc
+        .billing_mode_summary()
+        .and_then(|summary| summary.billing_mode())
+        .map(|mode| mode.as_str().to_string())
+        .or_else(|| throughput.map(|_| "PROVISIONED".to_string()));
+    details.throughput = throughput;
+    details.stream = Some(
+        desc.stream_specification()
+            .filter(|spec| spec.stream_enabled())
+            .and_then(|spec| spec.stream_view_type())
+            .map_or_else(|| "disabled".to_string(), |view| view.as_str().to_string()),
+    );
+    details.deletion_protection = desc.deletion_protection_enabled();
+    details.created_at = desc
+        .creation_date_time()
+        .and_then(|time| time.fmt(aws_smithy_types::date_time::Format::DateTime).ok());
+    details.resource_id = desc.table_arn().map(str::to_string);
+    details.replicas = desc
+        .replicas()
+        .iter()
+        .filter_map(|replica| {
+            Some(ReplicaDetails {
+                region: replica.region_name()?.to_string(),
+                status: replica
+                    .replica_status()
+                    .map(|status| status.as_str().to_string()),
+                progress: replica
+                    .replica_status_percent_progress()
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+    details
+}
+
+/// Provisioned capacity, or `None` for on-demand (which reports zeros).
+fn throughput_from(
+    throughput: Option<&aws_sdk_dynamodb::types::ProvisionedThroughputDescription>,
+) -> Option<Throughput> {
+    let throughput = throughput?;
+    let read = throughput.read_capacity_units().unwrap_or(0);
+    let write = throughput.write_capacity_units().unwrap_or(0);
+    (read > 0 || write > 0).then_some(Throughput { read, write })
+}
+
 fn attribute_types(desc: &TableDescription) -> HashMap<String, ScalarType> {
     desc.attribute_definitions()
         .iter()
@@ -572,16 +1259,7 @@ fn create_table_spec_from(spec: &CreateCollectionSpec) -> Result<CreateTableSpec
     for index in &spec.indexes {
         match index.kind {
             IndexKind::GlobalSecondary | IndexKind::Secondary | IndexKind::Composite => {
-                let gsi_hash =
-                    key_spec_for_role(&index.key, KeyRole::Partition).ok_or_else(|| {
-                        DbError::Backend(format!("Index {} needs a partition key", index.name))
-                    })?;
-                gsis.push(GsiSpec {
-                    name: index.name.clone(),
-                    hash_key: gsi_hash,
-                    sort_key: key_spec_for_role(&index.key, KeyRole::Sort),
-                    projection: index_projection_from(&index.projection),
-                });
+                gsis.push(gsi_spec_from(index)?);
             }
             IndexKind::LocalSecondary => {
                 let sort = key_spec_for_role(&index.key, KeyRole::Sort).ok_or_else(|| {
@@ -605,6 +1283,17 @@ fn create_table_spec_from(spec: &CreateCollectionSpec) -> Result<CreateTableSpec
     })
 }
 
+fn gsi_spec_from(index: &IndexSchema) -> Result<GsiSpec> {
+    let hash_key = key_spec_for_role(&index.key, KeyRole::Partition)
+        .ok_or_else(|| DbError::Backend(format!("Index {} needs a partition key", index.name)))?;
+    Ok(GsiSpec {
+        name: index.name.clone(),
+        hash_key,
+        sort_key: key_spec_for_role(&index.key, KeyRole::Sort),
+        projection: index_projection_from(&index.projection),
+    })
+}
+
 fn key_spec_for_role(schema: &KeySchema, role: KeyRole) -> Option<KeySpec> {
     schema
         .fields
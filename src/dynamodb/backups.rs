@@ -0,0 +1,95 @@
+//! On-demand backups: `ListBackups`, `CreateBackup`, `DeleteBackup`, and
+//! `RestoreTableFromBackup`.
+
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::types::{BackupSummary, BackupTypeFilter};
+
+use super::{format_sdk_error, send_dynamo_request};
+use crate::core::schema::BackupInfo;
+
+/// List the user-created backups of a table, newest first.
+pub async fn list_backups(client: Client, table_name: &str) -> Result<Vec<BackupInfo>, String> {
+    let mut backups = Vec::new();
+    let mut start_arn: Option<String> = None;
+    loop {
+        let request = client
+            .list_backups()
+            .table_name(table_name)
+            .backup_type(BackupTypeFilter::User)
+            .set_exclusive_start_backup_arn(start_arn.take());
+        let span = tracing::trace_span!("ListBackups", table = %table_name);
+        let output = send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+            .await
+            .map_err(|err| format_sdk_error(&err))?;
+        backups.extend(output.backup_summaries().iter().filter_map(backup_info));
+        match output.last_evaluated_backup_arn() {
+            Some(arn) => start_arn = Some(arn.to_string()),
+            None => break,
+        }
+    }
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+/// Start a backup of a table. DynamoDB returns while the backup is still
+/// `CREATING`.
+pub async fn create_backup(
+    client: Client,
+    table_name: &str,
+    backup_name: &str,
+) -> Result<(), String> {
+    let request = client
+        .create_backup()
+        .table_name(table_name)
+        .backup_name(backup_name);
+    let span = tracing::trace_span!("CreateBackup", table = %table_name, backup = %backup_name);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+pub async fn delete_backup(client: Client, backup_arn: &str) -> Result<(), String> {
+    let request = client.delete_backup().backup_arn(backup_arn);
+    let span = tracing::trace_span!("DeleteBackup", backup = %backup_arn);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+/// Start restoring a backup into a new table. The table exists as
+/// `CREATING` until the restore completes.
+pub async fn restore_table_from_backup(
+    client: Client,
+    backup_arn: &str,
+    target_table_name: &str,
+) -> Result<(), String> {
+    let request = client
+        .restore_table_from_backup()
+        .backup_arn(backup_arn)
+        .target_table_name(target_table_name);
+    let span = tracing::trace_span!(
+        "RestoreTableFromBackup",
+        backup = %backup_arn,
+        target = %target_table_name
+    );
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+fn backup_info(summary: &BackupSummary) -> Option<BackupInfo> {
+    Some(BackupInfo {
+        id: summary.backup_arn()?.to_string(),
+        name: summary.backup_name().unwrap_or_default().to_string(),
+        status: summary
+            .backup_status()
+            .map(|status| status.as_str().to_string()),
+        created_at: summary
+            .backup_creation_date_time()
+            .and_then(|time| time.fmt(aws_smithy_types::date_time::Format::DateTime).ok()),
+        size_bytes: summary.backup_size_bytes(),
+    })
+}
@@ -1,38 +1,203 @@
 //! DynamoDB connection setup.
 //!
 //! Builds an `aws_sdk_dynamodb::Client` from the environment (region +
-//! credentials), optionally pointed at a custom endpoint. This is the only place
+//! credentials), optionally assuming an IAM role and pointed at a custom
+//! endpoint. This is the only place
 //! that constructs the SDK client; the rest of the app goes through
-//! [`DynamoBackend`](super::DynamoBackend) and the `Datastore` trait.
+//! [`DynamoBackend`](super::DynamoBackend) and the `Datastore` trait. Other
+//! AWS clients, such as S3's, start from the same [`sdk_config_for`].
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Instant, SystemTime};
 
-use aws_config::BehaviorVersion;
 use aws_config::environment::{
     credentials::EnvironmentVariableCredentialsProvider, region::EnvironmentVariableRegionProvider,
 };
 use aws_config::meta::region::ProvideRegion;
-use aws_sdk_dynamodb::config::ProvideCredentials;
+use aws_config::sts::AssumeRoleProvider;
+use aws_config::{BehaviorVersion, SdkConfig};
+use aws_credential_types::provider::SharedCredentialsProvider;
+use aws_sdk_dynamodb::config::{Credentials, ProvideCredentials, Region};
+use aws_sdk_dynamodb::error::DisplayErrorContext;
+
+use crate::core::connect::{ProfileSelection, RoleSelection};
+
+use super::{format_sdk_error, send_aws_request};
 
 /// Construct a DynamoDB client, validating that region and credentials are
 /// present in the environment.
 pub async fn new_client(endpoint_url: Option<&str>) -> Result<aws_sdk_dynamodb::Client, String> {
-    let region = EnvironmentVariableRegionProvider::new()
-        .region()
-        .await
-        .ok_or_else(|| "AWS region not set. Use AWS_REGION or AWS_DEFAULT_REGION.".to_string())?;
+    new_client_for(&ProfileSelection::default(), endpoint_url).await
+}
 
-    EnvironmentVariableCredentialsProvider::new()
-        .provide_credentials()
-        .await
-        .map_err(|err| format!("AWS credentials not found in environment: {err}"))?;
+/// Region reported to DynamoDB Local, which accepts any.
+const LOCAL_REGION: &str = "us-east-1";
 
-    let mut loader = aws_config::defaults(BehaviorVersion::latest())
-        .region(region)
-        .credentials_provider(EnvironmentVariableCredentialsProvider::new());
+/// Construct a client for DynamoDB Local at `endpoint_url`. Local accepts any
+/// credentials, so fixed placeholders stand in and nothing is read from the
+/// environment.
+pub async fn new_local_client(endpoint_url: &str) -> aws_sdk_dynamodb::Client {
+    let credentials = Credentials::new("local", "local", None, None, "dynamate-local");
+    let config = aws_config::defaults(BehaviorVersion::latest())
+        .region(Region::new(LOCAL_REGION))
+        .credentials_provider(credentials)
+        .endpoint_url(endpoint_url)
+        .load()
+        .await;
+    let config = aws_sdk_dynamodb::config::Builder::from(&config)
+        .retry_config(super::retry::sdk_retry_config())
+        .build();
+    aws_sdk_dynamodb::Client::from_conf(config)
+}
 
+/// Construct a DynamoDB client for a profile/region selection, from
+/// [`sdk_config_for`].
+pub async fn new_client_for(
+    selection: &ProfileSelection,
+    endpoint_url: Option<&str>,
+) -> Result<aws_sdk_dynamodb::Client, String> {
+    let config = sdk_config_for(selection).await?;
+    let mut builder = aws_sdk_dynamodb::config::Builder::from(&config)
+        .retry_config(super::retry::sdk_retry_config());
     if let Some(url) = endpoint_url {
-        loader = loader.endpoint_url(url);
+        builder = builder.endpoint_url(url);
     }
+    Ok(aws_sdk_dynamodb::Client::from_conf(builder.build()))
+}
+
+/// The region and credentials for a profile/region selection. Without a
+/// profile, they come from the environment (the region can still be
+/// overridden); with one, the SDK resolves them from the shared config files.
+/// A selected role is then assumed with those credentials. Credentials are
+/// resolved eagerly so a bad profile or role fails here rather than on the
+/// first request.
+pub async fn sdk_config_for(selection: &ProfileSelection) -> Result<SdkConfig, String> {
+    let loader = if let Some(profile) = selection.profile.as_deref() {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest()).profile_name(profile);
+        if let Some(region) = selection.region.clone() {
+            loader = loader.region(Region::new(region));
+        }
+        loader
+    } else {
+        let region = match selection.region.clone() {
+            Some(region) => Region::new(region),
+            None => EnvironmentVariableRegionProvider::new()
+                .region()
+                .await
+                .ok_or_else(|| {
+                    "AWS region not set. Use AWS_REGION or AWS_DEFAULT_REGION.".to_string()
+                })?,
+        };
+
+        EnvironmentVariableCredentialsProvider::new()
+            .provide_credentials()
+            .await
+            .map_err(|err| format!("AWS credentials not found in environment: {err}"))?;
 
-    let config = loader.load().await;
-    Ok(aws_sdk_dynamodb::Client::new(&config))
+        aws_config::defaults(BehaviorVersion::latest())
+            .region(region)
+            .credentials_provider(EnvironmentVariableCredentialsProvider::new())
+    };
+
+    // Endpoints are left to each service; STS keeps its own for assuming
+    // roles.
+    let mut config = loader.load().await;
+    if let Some(profile) = selection.profile.as_deref() {
+        if config.region().is_none() {
+            return Err(format!("Profile {profile} has no region configured"));
+        }
+        let provider = config
+            .credentials_provider()
+            .ok_or_else(|| format!("Profile {profile} has no credentials"))?;
+        provider
+            .provide_credentials()
+            .await
+            .map_err(|err| format!("Failed to load credentials for profile {profile}: {err}"))?;
+    }
+    if let Some(role) = selection.role.as_ref() {
+        let provider = assume_role(&config, role).await?;
+        config = config.to_builder().credentials_provider(provider).build();
+    }
+    Ok(config)
+}
+
+/// Name of the sessions dynamate assumes roles under, as CloudTrail shows it.
+const ROLE_SESSION_NAME: &str = "dynamate";
+
+/// Credentials assumed with an MFA code, by role, device and code. The code
+/// is only good once, so a second client for the same selection (S3 after
+/// DynamoDB, say) reuses the credentials rather than assuming again.
+static MFA_SESSIONS: LazyLock<Mutex<HashMap<(String, String, String), SharedCredentialsProvider>>> =
+    LazyLock::new(Mutex::default);
+
+/// Credentials for `role`, assumed with `config`'s. Without MFA they are
+/// refreshed before they expire; with it, the code is only good once, so the
+/// credentials last the session's hour and the role has to be assumed again
+/// after.
+async fn assume_role(
+    config: &SdkConfig,
+    role: &RoleSelection,
+) -> Result<SharedCredentialsProvider, String> {
+    let Some(serial) = role.mfa_serial.as_deref() else {
+        let provider = AssumeRoleProvider::builder(role.role_arn.clone())
+            .session_name(ROLE_SESSION_NAME)
+            .configure(config)
+            .build()
+            .await;
+        let started = Instant::now();
+        let result = provider.provide_credentials().await;
+        let duration_ms = started.elapsed().as_millis();
+        if let Err(err) = result {
+            let message = DisplayErrorContext(&err).to_string();
+            tracing::warn!(role = %role.role_arn, duration_ms, error = %message, "AssumeRole complete");
+            return Err(format!("Failed to assume {}: {message}", role.role_arn));
+        }
+        tracing::trace!(role = %role.role_arn, duration_ms, "AssumeRole complete");
+        return Ok(SharedCredentialsProvider::new(provider));
+    };
+    let code = role
+        .mfa_code
+        .as_deref()
+        .ok_or_else(|| format!("{} needs an MFA code", role.role_arn))?;
+    let session = (role.role_arn.clone(), serial.to_string(), code.to_string());
+    if let Some(provider) = MFA_SESSIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&session)
+    {
+        return Ok(provider.clone());
+    }
+    let request = aws_sdk_sts::Client::new(config)
+        .assume_role()
+        .role_arn(&role.role_arn)
+        .role_session_name(ROLE_SESSION_NAME)
+        .serial_number(serial)
+        .token_code(code);
+    let span = tracing::trace_span!("AssumeRole", role = %role.role_arn, mfa = true);
+    let output = send_aws_request(span, request.send(), format_sdk_error)
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to assume {}: {}",
+                role.role_arn,
+                format_sdk_error(&err)
+            )
+        })?;
+    let credentials = output
+        .credentials()
+        .ok_or_else(|| format!("STS returned no credentials for {}", role.role_arn))?;
+    let expiry = SystemTime::try_from(*credentials.expiration()).ok();
+    let provider = SharedCredentialsProvider::new(Credentials::new(
+        credentials.access_key_id(),
+        credentials.secret_access_key(),
+        Some(credentials.session_token().to_string()),
+        expiry,
+        "dynamate-assume-role",
+    ));
+    MFA_SESSIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(session, provider.clone());
+    Ok(provider)
 }
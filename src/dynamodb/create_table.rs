@@ -142,6 +142,54 @@ pub struct GsiSpec {
     pub projection: IndexProjection,
 }
 
+impl GsiSpec {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("GSI name is required".to_string());
+        }
+        if self.hash_key.name.trim().is_empty() {
+            return Err("GSI partition key is required".to_string());
+        }
+        if let Some(sort_key) = self.sort_key.as_ref()
+            && sort_key.name.trim().is_empty()
+        {
+            return Err("GSI sort key name is required".to_string());
+        }
+        self.projection.validate()
+    }
+
+    /// Attribute definitions for the index's key attributes, as `UpdateTable`
+    /// requires when adding the index.
+    pub(super) fn attribute_definitions(&self) -> Result<Vec<AttributeDefinition>, String> {
+        let mut map = HashMap::new();
+        register_attribute(&mut map, &self.hash_key.name, self.hash_key.attr_type)?;
+        if let Some(sort_key) = self.sort_key.as_ref() {
+            register_attribute(&mut map, &sort_key.name, sort_key.attr_type)?;
+        }
+        attribute_definitions(map)
+    }
+
+    pub(super) fn key_schema(&self) -> Result<Vec<KeySchemaElement>, String> {
+        let mut key_schema = vec![
+            KeySchemaElement::builder()
+                .attribute_name(self.hash_key.name.clone())
+                .key_type(KeyType::Hash)
+                .build()
+                .map_err(|err| err.to_string())?,
+        ];
+        if let Some(sort_key) = self.sort_key.as_ref() {
+            key_schema.push(
+                KeySchemaElement::builder()
+                    .attribute_name(sort_key.name.clone())
+                    .key_type(KeyType::Range)
+                    .build()
+                    .map_err(|err| err.to_string())?,
+            );
+        }
+        Ok(key_schema)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LsiSpec {
     pub name: String,
@@ -178,21 +226,10 @@ impl CreateTableSpec {
 
         let mut index_names = HashSet::new();
         for gsi in &self.gsis {
-            if gsi.name.trim().is_empty() {
-                return Err("GSI name is required".to_string());
-            }
+            gsi.validate()?;
             if !index_names.insert(gsi.name.clone()) {
                 return Err(format!("Duplicate index name: {}", gsi.name));
             }
-            if gsi.hash_key.name.trim().is_empty() {
-                return Err("GSI partition key is required".to_string());
-            }
-            if let Some(sort_key) = gsi.sort_key.as_ref()
-                && sort_key.name.trim().is_empty()
-            {
-                return Err("GSI sort key name is required".to_string());
-            }
-            gsi.projection.validate()?;
         }
 
         for lsi in &self.lsis {
@@ -250,67 +287,24 @@ fn register_attribute(
     Ok(())
 }
 
-pub async fn create_table(client: Client, spec: CreateTableSpec) -> Result<(), String> {
-    spec.validate()?;
-
-    let attribute_map = spec.attribute_map()?;
-    let mut attribute_definitions = Vec::with_capacity(attribute_map.len());
-    for (name, attr_type) in attribute_map {
-        let def = AttributeDefinition::builder()
-            .attribute_name(name)
-            .attribute_type(attr_type.to_scalar())
-            .build()
-            .map_err(|err| err.to_string())?;
-        attribute_definitions.push(def);
-    }
-
-    let mut key_schema = Vec::new();
-    key_schema.push(
-        KeySchemaElement::builder()
-            .attribute_name(spec.hash_key.name.clone())
-            .key_type(KeyType::Hash)
-            .build()
-            .map_err(|err| err.to_string())?,
-    );
-    if let Some(sort_key) = spec.sort_key.as_ref() {
-        key_schema.push(
-            KeySchemaElement::builder()
-                .attribute_name(sort_key.name.clone())
-                .key_type(KeyType::Range)
+fn attribute_definitions(
+    map: HashMap<String, AttributeType>,
+) -> Result<Vec<AttributeDefinition>, String> {
+    map.into_iter()
+        .map(|(name, attr_type)| {
+            AttributeDefinition::builder()
+                .attribute_name(name)
+                .attribute_type(attr_type.to_scalar())
                 .build()
-                .map_err(|err| err.to_string())?,
-        );
-    }
+                .map_err(|err| err.to_string())
+        })
+        .collect()
+}
 
-    let mut gsi_defs = Vec::new();
-    for gsi in &spec.gsis {
-        let projection = gsi.projection.build_projection()?;
-        let mut gsi_key_schema = Vec::new();
-        gsi_key_schema.push(
-            KeySchemaElement::builder()
-                .attribute_name(gsi.hash_key.name.clone())
-                .key_type(KeyType::Hash)
-                .build()
-                .map_err(|err| err.to_string())?,
-        );
-        if let Some(sort_key) = gsi.sort_key.as_ref() {
-            gsi_key_schema.push(
-                KeySchemaElement::builder()
-                    .attribute_name(sort_key.name.clone())
-                    .key_type(KeyType::Range)
-                    .build()
-                    .map_err(|err| err.to_string())?,
-            );
-        }
-        let gsi_def = GlobalSecondaryIndex::builder()
-            .index_name(gsi.name.clone())
-            .set_key_schema(Some(gsi_key_schema))
-            .projection(projection)
-            .build()
-            .map_err(|err| err.to_string())?;
-        gsi_defs.push(gsi_def);
-    }
+pub async fn create_table(client: Client, spec: CreateTableSpec) -> Result<(), String> {
+    spec.validate()?;
 
+    let parts = TableParts::from_spec(&spec)?;
     let mut lsi_defs = Vec::new();
     for lsi in &spec.lsis {
         let projection = lsi.projection.build_projection()?;
@@ -340,14 +334,14 @@ pub async fn create_table(client: Client, spec: CreateTableSpec) -> Result<(), S
         .table_name(spec.table_name.clone())
         .billing_mode(BillingMode::PayPerRequest);
 
-    for def in attribute_definitions {
+    for def in parts.attribute_definitions {
         request = request.attribute_definitions(def);
     }
-    for key in key_schema {
+    for key in parts.key_schema {
         request = request.key_schema(key);
     }
-    if !gsi_defs.is_empty() {
-        for gsi in gsi_defs {
+    if !parts.gsis.is_empty() {
+        for gsi in parts.gsis {
             request = request.global_secondary_indexes(gsi);
         }
     }
@@ -363,10 +357,59 @@ pub async fn create_table(client: Client, spec: CreateTableSpec) -> Result<(), S
         gsi_count = spec.gsis.len(),
         lsi_count = spec.lsis.len()
     );
-    let result = send_dynamo_request(span, || request.send(), format_sdk_error).await;
+    let result = send_dynamo_request(span, || request.clone().send(), format_sdk_error).await;
     result.map(|_| ()).map_err(|err| format_sdk_error(&err))
 }
 
+/// The SDK definitions shared by `CreateTable` and `ImportTable`: attribute
+/// definitions, the table key schema, and the GSIs.
+pub(super) struct TableParts {
+    pub(super) attribute_definitions: Vec<AttributeDefinition>,
+    pub(super) key_schema: Vec<KeySchemaElement>,
+    pub(super) gsis: Vec<GlobalSecondaryIndex>,
+}
+
+impl TableParts {
+    pub(super) fn from_spec(spec: &CreateTableSpec) -> Result<Self, String> {
+        let attribute_definitions = attribute_definitions(spec.attribute_map()?)?;
+
+        let mut key_schema = Vec::new();
+        key_schema.push(
+            KeySchemaElement::builder()
+                .attribute_name(spec.hash_key.name.clone())
+                .key_type(KeyType::Hash)
+                .build()
+                .map_err(|err| err.to_string())?,
+        );
+        if let Some(sort_key) = spec.sort_key.as_ref() {
+            key_schema.push(
+                KeySchemaElement::builder()
+                    .attribute_name(sort_key.name.clone())
+                    .key_type(KeyType::Range)
+                    .build()
+                    .map_err(|err| err.to_string())?,
+            );
+        }
+
+        let mut gsis = Vec::new();
+        for gsi in &spec.gsis {
+            let projection = gsi.projection.build_projection()?;
+            let gsi_def = GlobalSecondaryIndex::builder()
+                .index_name(gsi.name.clone())
+                .set_key_schema(Some(gsi.key_schema()?))
+                .projection(projection)
+                .build()
+                .map_err(|err| err.to_string())?;
+            gsis.push(gsi_def);
+        }
+        Ok(Self {
+            attribute_definitions,
+            key_schema,
+            gsis,
+        })
+    }
+}
+
 fn parse_attribute_list(raw: &str) -> Vec<String> {
     raw.split(',')
         .map(str::trim)
@@ -444,4 +487,36 @@ mod tests {
         let err = spec.validate().unwrap_err();
         assert!(err.contains("conflicting types"));
     }
+
+    #[test]
+    fn gsi_attribute_definitions_cover_its_keys() {
+        let gsi = GsiSpec {
+            name: "byStatus".to_string(),
+            hash_key: KeySpec {
+                name: "status".to_string(),
+                attr_type: AttributeType::String,
+            },
+            sort_key: Some(KeySpec {
+                name: "createdAt".to_string(),
+                attr_type: AttributeType::Number,
+            }),
+            projection: IndexProjection::KeysOnly,
+        };
+        gsi.validate().unwrap();
+        let mut names: Vec<String> = gsi
+            .attribute_definitions()
+            .unwrap()
+            .iter()
+            .map(|def| def.attribute_name().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["createdAt", "status"]);
+        assert_eq!(gsi.key_schema().unwrap().len(), 2);
+
+        let unnamed = GsiSpec {
+            name: " ".to_string(),
+            ..gsi
+        };
+        assert!(unnamed.validate().is_err());
+    }
 }
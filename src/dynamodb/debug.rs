@@ -5,9 +5,12 @@ use std::{
     time::{Duration, Instant},
 };
 
+use aws_sdk_dynamodb::config::http::HttpResponse;
 use aws_sdk_dynamodb::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
 use aws_sdk_dynamodb::operation::RequestId;
 
+use super::retry;
+
 const DEBUG_DELAY_ENV: &str = "DYNAMATE_DEBUG_DYNAMO_DELAY_MS";
 
 /// Format an AWS SDK error into a concise, human-readable summary.
@@ -34,33 +37,78 @@ where
     DisplayErrorContext(err).to_string()
 }
 
+/// Send a DynamoDB request, logging how it went. A throttled request, or one
+/// that failed in a way that usually passes (see
+/// [`retry::is_retryable_error`]), is sent again (`send` is called once per
+/// attempt) as the [`retry`] policy allows.
+///
+/// [`retry`]: super::retry
 pub async fn send_dynamo_request<F, Fut, T, E, FE>(
     span: tracing::Span,
     send: F,
     format_error: FE,
-) -> Result<T, E>
+) -> Result<T, SdkError<E, HttpResponse>>
 where
-    F: FnOnce() -> Fut,
-    Fut: Future<Output = Result<T, E>>,
-    FE: FnOnce(&E) -> String,
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, SdkError<E, HttpResponse>>>,
+    E: ProvideErrorMetadata,
+    FE: Fn(&SdkError<E, HttpResponse>) -> String,
 {
     let _enter = span.enter();
     debug_dynamo_delay().await;
+    let mut retry = 0;
+    loop {
+        let started = Instant::now();
+        let result = send().await;
+        let duration = started.elapsed();
+        match &result {
+            Ok(_) => {
+                tracing::trace!(
+                    duration_ms = duration.as_millis(),
+                    "DynamoDB request complete"
+                );
+            }
+            Err(err) => {
+                tracing::warn!(
+                    duration_ms = duration.as_millis(),
+                    error = %format_error(err),
+                    "DynamoDB request complete"
+                );
+                retry += 1;
+                if retry::is_retryable_error(err) && retry::back_off(retry).await {
+                    continue;
+                }
+            }
+        }
+        return result;
+    }
+}
+
+/// Send one request to another AWS service (S3, CloudWatch, STS), logging
+/// how it went the same way. Retries are left to that service's SDK client,
+/// which keeps its standard retryer.
+pub async fn send_aws_request<Fut, T, E, FE>(
+    span: tracing::Span,
+    send: Fut,
+    format_error: FE,
+) -> Result<T, SdkError<E, HttpResponse>>
+where
+    Fut: Future<Output = Result<T, SdkError<E, HttpResponse>>>,
+    FE: Fn(&SdkError<E, HttpResponse>) -> String,
+{
+    let _enter = span.enter();
     let started = Instant::now();
-    let result = send().await;
+    let result = send.await;
     let duration = started.elapsed();
     match &result {
         Ok(_) => {
-            tracing::trace!(
-                duration_ms = duration.as_millis(),
-                "DynamoDB request complete"
-            );
+            tracing::trace!(duration_ms = duration.as_millis(), "AWS request complete");
         }
         Err(err) => {
             tracing::warn!(
                 duration_ms = duration.as_millis(),
                 error = %format_error(err),
-                "DynamoDB request complete"
+                "AWS request complete"
             );
         }
     }
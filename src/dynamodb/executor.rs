@@ -1,7 +1,7 @@
 use aws_sdk_dynamodb::{
     Client, Error,
     operation::{query::QueryOutput, scan::ScanOutput},
-    types::{AttributeValue, ConsumedCapacity},
+    types::{AttributeValue, ConsumedCapacity, ReturnConsumedCapacity, Select},
 };
 use std::collections::HashMap;
 
@@ -13,6 +13,7 @@ pub enum Kind {
     Query,
     QueryGSI(String), // index_name
     QueryLSI(String), // index_name
+    GetItem,
 }
 
 #[derive(Debug, Clone)]
@@ -56,7 +57,7 @@ pub async fn execute(
     table_name: &str,
     db_request: &DynamoDbRequest,
 ) -> Result<Output, Error> {
-    execute_page(client, table_name, db_request, None, None).await
+    execute_page(client, table_name, db_request, None, None, false).await
 }
 
 pub async fn execute_page(
@@ -65,6 +66,7 @@ pub async fn execute_page(
     db_request: &DynamoDbRequest,
     start_key: Option<HashMap<String, AttributeValue>>,
     limit: Option<i32>,
+    consistent_read: bool,
 ) -> Result<Output, Error> {
     if let Some(start_key) = start_key.as_ref() {
         tracing::trace!(
@@ -86,7 +88,15 @@ pub async fn execute_page(
     );
     match db_request {
         DynamoDbRequest::Query(builder) => {
-            let output = execute_query(client, table_name, builder, start_key, limit).await?;
+            let output = execute_query(
+                client,
+                table_name,
+                builder,
+                start_key,
+                limit,
+                consistent_read,
+            )
+            .await?;
             let kind = match builder.query_type() {
                 QueryType::TableQuery { .. } => Kind::Query,
                 QueryType::GlobalSecondaryIndexQuery { index_name, .. } => {
@@ -107,7 +117,16 @@ pub async fn execute_page(
             })
         }
         DynamoDbRequest::Scan(builder) => {
-            let result = execute_scan(client, table_name, builder, start_key, limit).await?;
+            let result = execute_scan(
+                client,
+                table_name,
+                builder,
+                None,
+                start_key,
+                limit,
+                consistent_read,
+            )
+            .await?;
             Ok(Output {
                 items: result.items,
                 count: result.count,
@@ -120,36 +139,141 @@ pub async fn execute_page(
     }
 }
 
+/// Fetch the single item with primary `key`, only asking for the attributes
+/// `builder` projects.
+pub async fn execute_get_item(
+    client: &Client,
+    table_name: &str,
+    builder: &QueryBuilder,
+    key: HashMap<String, AttributeValue>,
+    consistent_read: bool,
+) -> Result<Output, Error> {
+    let mut request = client
+        .get_item()
+        .table_name(table_name)
+        .set_key(Some(key))
+        .return_consumed_capacity(ReturnConsumedCapacity::Total);
+
+    if let Some(projection) = builder.projection_expression() {
+        request = request.projection_expression(projection);
+        // GetItem rejects names the projection doesn't use, such as those
+        // of the key condition it replaces.
+        for placeholder in projection.split(", ") {
+            if let Some(name) = builder.expression_attribute_names().get(placeholder) {
+                request = request.expression_attribute_names(placeholder, name.clone());
+            }
+        }
+    }
+
+    if consistent_read {
+        request = request.consistent_read(true);
+    }
+
+    tracing::trace!(
+        table=%table_name,
+        projection_expression=?builder.projection_expression(),
+        consistent_read,
+        "GetItem"
+    );
+    let span = tracing::trace_span!("GetItem", table = %table_name);
+    let output =
+        send_dynamo_request(span, || request.clone().send(), |err| format!("{err:?}")).await?;
+    let count = i32::from(output.item.is_some());
+    Ok(Output {
+        items: output.item.map(|item| vec![item]),
+        count,
+        scanned_count: count,
+        last_evaluated_key: None,
+        consumed_capacity: output.consumed_capacity,
+        kind: Kind::GetItem,
+    })
+}
+
+/// Run one page of a single segment of a parallel scan. `segment` is the
+/// zero-based `(Segment, TotalSegments)` pair.
+pub async fn execute_scan_segment(
+    client: &Client,
+    table_name: &str,
+    builder: &ScanBuilder,
+    segment: (i32, i32),
+    start_key: Option<HashMap<String, AttributeValue>>,
+    limit: Option<i32>,
+    consistent_read: bool,
+) -> Result<Output, Error> {
+    let result = execute_scan(
+        client,
+        table_name,
+        builder,
+        Some(segment),
+        start_key,
+        limit,
+        consistent_read,
+    )
+    .await?;
+    Ok(Output {
+        items: result.items,
+        count: result.count,
+        scanned_count: result.scanned_count,
+        last_evaluated_key: result.last_evaluated_key,
+        consumed_capacity: result.consumed_capacity,
+        kind: Kind::Scan,
+    })
+}
+
 async fn execute_scan(
     client: &Client,
     table_name: &str,
     builder: &ScanBuilder,
+    segment: Option<(i32, i32)>,
     start_key: Option<HashMap<String, AttributeValue>>,
     limit: Option<i32>,
+    consistent_read: bool,
 ) -> Result<ScanOutput, aws_sdk_dynamodb::Error> {
-    let mut request = client.scan().table_name(table_name);
+    let mut request = client
+        .scan()
+        .table_name(table_name)
+        .return_consumed_capacity(ReturnConsumedCapacity::Total);
 
     tracing::trace!(
         table=%table_name,
+        index_name=?builder.index_name(),
         filter_expression=?builder.filter_expression(),
+        projection_expression=?builder.projection_expression(),
         attribute_names=?builder.expression_attribute_names(),
         attribute_values=?builder.expression_attribute_values(),
         start_key=?start_key,
         start_key_present=start_key.is_some(),
         limit=?limit,
+        segment=?segment,
         "Scan"
     );
 
+    if let Some((segment, total_segments)) = segment {
+        request = request.segment(segment).total_segments(total_segments);
+    }
+
+    if let Some(index_name) = builder.index_name() {
+        request = request.index_name(index_name);
+    }
+
     if let Some(filter_expr) = builder.filter_expression() {
         request = request.filter_expression(filter_expr);
+    }
 
-        for (key, value) in builder.expression_attribute_names() {
-            request = request.expression_attribute_names(key.clone(), value.clone());
-        }
+    if let Some(projection) = builder.projection_expression() {
+        request = request.projection_expression(projection);
+    }
 
-        for (key, value) in builder.expression_attribute_values() {
-            request = request.expression_attribute_values(key.clone(), value.clone());
-        }
+    if builder.count_only() {
+        request = request.select(Select::Count);
+    }
+
+    for (key, value) in builder.expression_attribute_names() {
+        request = request.expression_attribute_names(key.clone(), value.clone());
+    }
+
+    for (key, value) in builder.expression_attribute_values() {
+        request = request.expression_attribute_values(key.clone(), value.clone());
     }
 
     let start_key_present = start_key.is_some();
@@ -161,13 +285,18 @@ async fn execute_scan(
         request = request.limit(limit);
     }
 
+    if consistent_read {
+        request = request.consistent_read(true);
+    }
+
     let span = tracing::trace_span!(
         "Scan",
         table = %table_name,
         start_key_present = start_key_present,
         limit = ?limit
     );
-    let result = send_dynamo_request(span, || request.send(), |err| format!("{err:?}")).await;
+    let result =
+        send_dynamo_request(span, || request.clone().send(), |err| format!("{err:?}")).await;
     Ok(result?)
 }
 
@@ -177,8 +306,12 @@ async fn execute_query(
     builder: &QueryBuilder,
     start_key: Option<HashMap<String, AttributeValue>>,
     limit: Option<i32>,
+    consistent_read: bool,
 ) -> Result<QueryOutput, Error> {
-    let mut request = client.query().table_name(table_name);
+    let mut request = client
+        .query()
+        .table_name(table_name)
+        .return_consumed_capacity(ReturnConsumedCapacity::Total);
 
     // Set index name if this is an index query
     if let Some(index_name) = builder.index_name() {
@@ -195,6 +328,18 @@ async fn execute_query(
         request = request.filter_expression(filter_expr);
     }
 
+    if let Some(projection) = builder.projection_expression() {
+        request = request.projection_expression(projection);
+    }
+
+    if builder.count_only() {
+        request = request.select(Select::Count);
+    }
+
+    if !builder.scan_index_forward() {
+        request = request.scan_index_forward(false);
+    }
+
     // Set expression attribute names and values
     for (key, value) in builder.expression_attribute_names() {
         request = request.expression_attribute_names(key.clone(), value.clone());
@@ -210,6 +355,8 @@ async fn execute_query(
         index=?builder.index_name(),
         key_condition_expression=?builder.key_condition_expression(),
         filter_expression=?builder.filter_expression(),
+        projection_expression=?builder.projection_expression(),
+        scan_index_forward=builder.scan_index_forward(),
         attribute_names=?builder.expression_attribute_names(),
         attribute_values=?builder.expression_attribute_values(),
         start_key=?start_key,
@@ -226,12 +373,17 @@ async fn execute_query(
         request = request.limit(limit);
     }
 
+    if consistent_read {
+        request = request.consistent_read(true);
+    }
+
     let span = tracing::trace_span!(
         "Query",
         table = %table_name,
         start_key_present = start_key_present,
         limit = ?limit
     );
-    let result = send_dynamo_request(span, || request.send(), |err| format!("{err:?}")).await;
+    let result =
+        send_dynamo_request(span, || request.clone().send(), |err| format!("{err:?}")).await;
     Ok(result?)
 }
@@ -0,0 +1,162 @@
+//! Creating tables from S3: `ImportTable`, with `ListImports` and
+//! `DescribeImport` to follow progress.
+
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::types::{
+    BillingMode, ImportTableDescription, InputCompressionType, InputFormat, S3BucketSource,
+    TableCreationParameters,
+};
+use aws_smithy_types::date_time::Format;
+
+use super::create_table::{CreateTableSpec, TableParts};
+use super::{format_sdk_error, send_dynamo_request};
+use crate::core::query::{ImportCompression, ImportFormat, ImportSource};
+use crate::core::schema::ImportInfo;
+
+/// How many recent imports the imports view shows.
+const LIST_LIMIT: i32 = 25;
+
+/// Start importing `source` into a new on-demand table, returning the import
+/// ARN. The table is `CREATING` until the import finishes.
+pub async fn import_table(
+    client: Client,
+    spec: CreateTableSpec,
+    source: &ImportSource,
+) -> Result<String, String> {
+    spec.validate()?;
+    if !spec.lsis.is_empty() {
+        return Err("Imported tables can't have local secondary indexes".to_string());
+    }
+    let parts = TableParts::from_spec(&spec)?;
+    let parameters = TableCreationParameters::builder()
+        .table_name(spec.table_name.clone())
+        .set_attribute_definitions(Some(parts.attribute_definitions))
+        .set_key_schema(Some(parts.key_schema))
+        .billing_mode(BillingMode::PayPerRequest)
+        .set_global_secondary_indexes((!parts.gsis.is_empty()).then_some(parts.gsis))
+        .build()
+        .map_err(|err| err.to_string())?;
+    let bucket_source = S3BucketSource::builder()
+        .s3_bucket(source.bucket.clone())
+        .set_s3_key_prefix((!source.key_prefix.is_empty()).then(|| source.key_prefix.clone()))
+        .build()
+        .map_err(|err| err.to_string())?;
+    let request = client
+        .import_table()
+        .s3_bucket_source(bucket_source)
+        .input_format(input_format(source.format))
+        .input_compression_type(compression_type(source.compression))
+        .table_creation_parameters(parameters);
+    let span = tracing::trace_span!(
+        "ImportTable",
+        table = %spec.table_name,
+        bucket = %source.bucket,
+        prefix = %source.key_prefix
+    );
+    let output = send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map_err(|err| format_sdk_error(&err))?;
+    output
+        .import_table_description()
+        .and_then(ImportTableDescription::import_arn)
+        .map(str::to_string)
+        .ok_or_else(|| "DynamoDB returned no import ARN".to_string())
+}
+
+/// The most recent imports, newest first, each described for its item
+/// counts.
+pub async fn list_imports(client: Client) -> Result<Vec<ImportInfo>, String> {
+    let request = client.list_imports().page_size(LIST_LIMIT);
+    let span = tracing::trace_span!("ListImports");
+    let output = send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map_err(|err| format_sdk_error(&err))?;
+    let arns: Vec<String> = output
+        .import_summary_list()
+        .iter()
+        .filter_map(|summary| summary.import_arn().map(str::to_string))
+        .collect();
+    let described = futures::future::join_all(
+        arns.iter()
+            .map(|arn| describe_import(client.clone(), arn.as_str())),
+    )
+    .await;
+    let mut imports = described.into_iter().collect::<Result<Vec<_>, _>>()?;
+    imports.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(imports)
+}
+
+async fn describe_import(client: Client, import_arn: &str) -> Result<ImportInfo, String> {
+    let request = client.describe_import().import_arn(import_arn);
+    let span = tracing::trace_span!("DescribeImport", import = %import_arn);
+    let output = send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map_err(|err| format_sdk_error(&err))?;
+    output
+        .import_table_description()
+        .map(import_info)
+        .ok_or_else(|| format!("DynamoDB returned no description for {import_arn}"))
+}
+
+fn import_info(description: &ImportTableDescription) -> ImportInfo {
+    let source = description
+        .s3_bucket_source()
+        .map_or_else(String::new, |source| {
+            format!(
+                "s3://{}/{}",
+                source.s3_bucket(),
+                source.s3_key_prefix().unwrap_or_default()
+            )
+        });
+    let table = description
+        .table_creation_parameters()
+        .map(|parameters| parameters.table_name().to_string())
+        .or_else(|| {
+            description
+                .table_arn()
+                .and_then(|arn| arn.rsplit('/').next())
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    let failure = match (description.failure_code(), description.failure_message()) {
+        (Some(code), Some(message)) => Some(format!("{code}: {message}")),
+        (code, message) => code.or(message).map(str::to_string),
+    };
+    ImportInfo {
+        id: description.import_arn().unwrap_or_default().to_string(),
+        table,
+        status: description
+            .import_status()
+            .map_or_else(String::new, |status| status.as_str().to_string()),
+        source,
+        format: description
+            .input_format()
+            .map(|format| format.as_str().to_string()),
+        started_at: description
+            .start_time()
+            .and_then(|time| time.fmt(Format::DateTime).ok()),
+        ended_at: description
+            .end_time()
+            .and_then(|time| time.fmt(Format::DateTime).ok()),
+        processed_items: description.processed_item_count(),
+        imported_items: description.imported_item_count(),
+        errors: description.error_count(),
+        failure,
+    }
+}
+
+fn input_format(format: ImportFormat) -> InputFormat {
+    match format {
+        ImportFormat::DynamoJson => InputFormat::DynamodbJson,
+        ImportFormat::Ion => InputFormat::Ion,
+        ImportFormat::Csv => InputFormat::Csv,
+    }
+}
+
+fn compression_type(compression: ImportCompression) -> InputCompressionType {
+    match compression {
+        ImportCompression::None => InputCompressionType::None,
+        ImportCompression::Gzip => InputCompressionType::Gzip,
+        ImportCompression::Zstd => InputCompressionType::Zstd,
+    }
+}
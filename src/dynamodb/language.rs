@@ -3,19 +3,26 @@
 //! Wraps the existing `expr` parser, the [`completion`] engine and the
 //! `builtins` dialect, with no behavior change for DynamoDB.
 
+use std::collections::HashMap;
+
 use crate::core::language::{
-    Completion, CompletionRequest, QueryLanguage, QueryStatus, ReferenceSection,
+    Completion, CompletionRequest, Condition, ConditionOp, ConditionValue, QueryLanguage,
+    QueryStatus, ReferenceSection,
 };
-use crate::core::query::PlanKind;
+use crate::core::query::{ItemUpdate, PlanKind};
 use crate::core::schema::CollectionSchema;
+use crate::core::value::Item;
 use crate::expr::error::ParseError;
 use crate::expr::{
-    Comparator, DynamoExpression, Operand, format, parse_dynamo_expression,
-    parse_single_value_token,
+    Comparator, DynamoExpression, FunctionName, NumberLiteral, Operand, PathStep, attribute_name,
+    format, parse_dynamo_expression, parse_single_value_token, parse_update_expression, path_steps,
+    split_order_clause,
 };
 
+use super::QueryBuilder;
 use super::completion;
 use super::table_analyzer::{QueryType, TableInfo};
+use super::update_eval::{apply_update_expression, evaluate};
 
 /// The DynamoDB filter-expression language.
 pub struct DynamoLanguage;
@@ -32,7 +39,7 @@ impl QueryLanguage for DynamoLanguage {
     }
 
     fn validate(&self, text: &str, schema: Option<&CollectionSchema>) -> QueryStatus {
-        if text.trim().is_empty() {
+        if split_order_clause(text).0.trim().is_empty() {
             return QueryStatus::Empty;
         }
         match parse_query_classified(text, hash_key(schema)) {
@@ -53,8 +60,15 @@ impl QueryLanguage for DynamoLanguage {
         if trimmed.is_empty() {
             return None;
         }
+        let order = split_order_clause(trimmed).1;
         match parse_query_text(trimmed, hash_key(schema)) {
-            Ok(expr) => Some(format::format_query_summary(&expr)),
+            Ok(expr) => {
+                let summary = format::format_query_summary(&expr);
+                Some(match order {
+                    Some(order) => format!("{summary} ORDER {}", order.keyword()),
+                    None => summary,
+                })
+            }
             Err(_) => Some(trimmed.to_string()),
         }
     }
@@ -104,6 +118,145 @@ impl QueryLanguage for DynamoLanguage {
             },
         ]
     }
+
+    fn parameters(&self, text: &str) -> Vec<String> {
+        let text = split_order_clause(text).0;
+        match parse_dynamo_expression(text) {
+            Ok(expr) => expr.placeholders(),
+            Err(_) => match parse_single_value_token(text) {
+                Ok(Operand::Placeholder(name)) => vec![name],
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    fn build_query(&self, conditions: &[Condition]) -> Result<String, String> {
+        Ok(conditions
+            .iter()
+            .map(condition_expression)
+            .reduce(|left, right| DynamoExpression::And(Box::new(left), Box::new(right)))
+            .map(|expr| format::format_expression(&expr))
+            .unwrap_or_default())
+    }
+
+    fn narrow_query(&self, text: &str, conditions: &[Condition]) -> Result<String, String> {
+        let filter = split_order_clause(text).0;
+        let order = text[filter.len()..].trim();
+        let added = conditions
+            .iter()
+            .map(condition_expression)
+            .reduce(|left, right| DynamoExpression::And(Box::new(left), Box::new(right)));
+        let expr = if filter.trim().is_empty() {
+            added
+        } else {
+            let existing = parse_dynamo_expression(filter)
+                .map_err(|err| format!("can't add conditions to this query: {err}"))?;
+            Some(match added {
+                Some(added) => DynamoExpression::And(Box::new(existing), Box::new(added)),
+                None => existing,
+            })
+        };
+        let query = expr
+            .map(|expr| format::format_expression(&expr))
+            .unwrap_or_default();
+        Ok(format!("{query} {order}").trim().to_string())
+    }
+
+    fn update_placeholder(&self) -> Option<&'static str> {
+        Some("SET status = \"done\", retries = 0 REMOVE lock")
+    }
+
+    fn parse_update(&self, text: &str) -> Result<ItemUpdate, String> {
+        let expression = parse_update_expression(text).map_err(|err| err.to_string())?;
+        // Literal values on top-level attributes need nothing from the item,
+        // so they go through as plain attribute values; anything else is
+        // sent as written.
+        let set_names: Option<Vec<String>> = expression
+            .set
+            .iter()
+            .map(|(path, _)| attribute_name(path))
+            .collect();
+        let remove: Option<Vec<String>> = expression
+            .remove
+            .iter()
+            .map(|path| attribute_name(path))
+            .collect();
+        if let (true, Some(set_names), Some(remove)) = (expression.is_literal(), set_names, remove)
+        {
+            let mut set = Item::new();
+            for (name, (_, value)) in set_names.into_iter().zip(&expression.set) {
+                set.insert(name, evaluate(value, &Item::new())?);
+            }
+            return Ok(ItemUpdate {
+                set,
+                remove,
+                expression: None,
+            });
+        }
+        Ok(ItemUpdate {
+            expression: Some(text.trim().to_string()),
+            ..ItemUpdate::default()
+        })
+    }
+
+    fn apply_update(&self, update: &ItemUpdate, item: &Item) -> Result<Item, String> {
+        match &update.expression {
+            Some(text) => {
+                let expression = parse_update_expression(text).map_err(|err| err.to_string())?;
+                apply_update_expression(&expression, item)
+            }
+            None => Ok(update.apply(item)),
+        }
+    }
+
+    fn updated_attributes(&self, update: &ItemUpdate) -> Vec<String> {
+        let Some(expression) = update
+            .expression
+            .as_deref()
+            .and_then(|text| parse_update_expression(text).ok())
+        else {
+            return update.set.keys().chain(&update.remove).cloned().collect();
+        };
+        let paths = (expression.set.iter().map(|(path, _)| path))
+            .chain(&expression.remove)
+            .chain(expression.add.iter().map(|(path, _)| path))
+            .chain(expression.delete.iter().map(|(path, _)| path));
+        paths
+            .filter_map(|path| match path_steps(path).ok()?.into_iter().next()? {
+                PathStep::Name(name) => Some(name),
+                PathStep::Index(_) => None,
+            })
+            .collect()
+    }
+}
+
+fn condition_expression(condition: &Condition) -> DynamoExpression {
+    let path = Operand::Path(condition.attribute.clone());
+    let value = match &condition.value {
+        ConditionValue::String(text) => Operand::Value(text.clone()),
+        ConditionValue::Number(text) => {
+            Operand::Number(NumberLiteral::parse(text).unwrap_or_else(|| 0.0.into()))
+        }
+        ConditionValue::Boolean(value) => Operand::Boolean(*value),
+    };
+    let comparison = |operator| DynamoExpression::Comparison {
+        left: path.clone(),
+        operator,
+        right: value.clone(),
+    };
+    let function = |name, args| DynamoExpression::Function { name, args };
+    match condition.op {
+        ConditionOp::Equal => comparison(Comparator::Equal),
+        ConditionOp::NotEqual => comparison(Comparator::NotEqual),
+        ConditionOp::Less => comparison(Comparator::Less),
+        ConditionOp::LessOrEqual => comparison(Comparator::LessOrEqual),
+        ConditionOp::Greater => comparison(Comparator::Greater),
+        ConditionOp::GreaterOrEqual => comparison(Comparator::GreaterOrEqual),
+        ConditionOp::BeginsWith => function(FunctionName::BeginsWith, vec![path.clone(), value]),
+        ConditionOp::Contains => function(FunctionName::Contains, vec![path.clone(), value]),
+        ConditionOp::Exists => function(FunctionName::AttributeExists, vec![path.clone()]),
+        ConditionOp::NotExists => function(FunctionName::AttributeNotExists, vec![path.clone()]),
+    }
 }
 
 fn hash_key(schema: Option<&CollectionSchema>) -> Option<&str> {
@@ -117,8 +270,9 @@ enum ParseErrorKind {
 }
 
 /// Parse query text into a [`DynamoExpression`], applying the single-token
-/// partition-key shortcut (`foo` → `<hash_key> = "foo"`) on failure. Shared by
-/// the backend's `query()` and this language's `validate`/`summarize`.
+/// partition-key shortcut (`foo` → `<hash_key> = "foo"`) on failure. A trailing
+/// `ORDER ASC|DESC` clause is ignored; the backend reads it separately. Shared by the
+/// backend's `query()` and this language's `validate`/`summarize`.
 pub fn parse_query_text(text: &str, hash_key: Option<&str>) -> Result<DynamoExpression, String> {
     parse_query_classified(text, hash_key).map_err(|err| match err {
         ParseErrorKind::Incomplete => "incomplete query".to_string(),
@@ -126,10 +280,30 @@ pub fn parse_query_text(text: &str, hash_key: Option<&str>) -> Result<DynamoExpr
     })
 }
 
+/// [`parse_query_text`], then bind its `:name` parameters to `parameters`,
+/// each parsed as a single value token (`"abc"`, `42`, `true`, or a bare word).
+pub fn parse_query_with_parameters(
+    text: &str,
+    hash_key: Option<&str>,
+    parameters: &[(String, String)],
+) -> Result<DynamoExpression, String> {
+    let expr = parse_query_text(text, hash_key)?;
+    let values = parameters
+        .iter()
+        .map(|(name, value)| {
+            parse_single_value_token(value.trim())
+                .map(|operand| (name.clone(), operand))
+                .map_err(|err| format!("Invalid value for parameter ':{name}': {err}"))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    expr.bind(&values).map_err(|err| err.to_string())
+}
+
 fn parse_query_classified(
     text: &str,
     hash_key: Option<&str>,
 ) -> Result<DynamoExpression, ParseErrorKind> {
+    let text = split_order_clause(text).0;
     match parse_dynamo_expression(text) {
         Ok(expr) => Ok(expr),
         Err(parse_error) => {
@@ -160,13 +334,18 @@ fn parse_error_is_incomplete(err: &ParseError) -> bool {
     }
 }
 
-/// Predict whether the parsed query runs as an indexed Query or a full Scan.
+/// Predict whether the parsed query runs as a GetItem, an indexed Query, or a
+/// full Scan.
 pub fn predict_plan_kind(expr: &DynamoExpression, schema: Option<&CollectionSchema>) -> PlanKind {
     let Some(schema) = schema else {
         return PlanKind::Scan;
     };
     let table_info = TableInfo::from_collection_schema(schema);
-    match table_info.analyze_query_type(expr) {
+    let builder = QueryBuilder::new(&table_info, expr);
+    if builder.primary_key_lookup(&table_info).is_some() {
+        return PlanKind::Lookup;
+    }
+    match builder.query_type().clone() {
         QueryType::TableQuery { .. } => PlanKind::IndexedQuery { index: None },
         QueryType::GlobalSecondaryIndexQuery { index_name, .. }
         | QueryType::LocalSecondaryIndexQuery { index_name, .. } => PlanKind::IndexedQuery {
@@ -175,3 +354,135 @@ pub fn predict_plan_kind(expr: &DynamoExpression, schema: Option<&CollectionSche
         QueryType::TableScan => PlanKind::Scan,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DynamoLanguage, predict_plan_kind};
+    use crate::core::language::{Condition, ConditionOp, ConditionValue, QueryLanguage};
+    use crate::core::query::PlanKind;
+    use crate::core::schema::{CollectionSchema, KeyField, KeyRole, KeySchema, ScalarType};
+    use crate::core::value::{Item, Number, Value};
+    use crate::expr::parse_dynamo_expression;
+
+    fn condition(attribute: &str, op: ConditionOp, value: &str) -> Condition {
+        Condition {
+            attribute: attribute.to_string(),
+            op,
+            value: ConditionValue::infer(value),
+        }
+    }
+
+    #[test]
+    fn builder_conditions_become_a_parseable_expression() {
+        let conditions = [
+            condition("PK", ConditionOp::Equal, "USER#1"),
+            condition("total", ConditionOp::GreaterOrEqual, "10.5"),
+            condition("order-id", ConditionOp::BeginsWith, "\"42\""),
+            condition("deleted", ConditionOp::NotExists, ""),
+        ];
+        let text = DynamoLanguage.build_query(&conditions).expect("query");
+        assert_eq!(
+            text,
+            "PK=\"USER#1\" AND total>=10.5 AND begins_with(`order-id`, \"42\") AND attribute_not_exists(deleted)"
+        );
+        assert!(parse_dynamo_expression(&text).is_ok());
+        assert_eq!(DynamoLanguage.build_query(&[]).as_deref(), Ok(""));
+    }
+
+    #[test]
+    fn narrowing_keeps_the_query_and_its_order() {
+        let added = [condition("status", ConditionOp::Contains, "open")];
+        assert_eq!(
+            DynamoLanguage
+                .narrow_query("PK = \"a\" ORDER DESC", &added)
+                .as_deref(),
+            Ok("PK=\"a\" AND contains(status, \"open\") ORDER DESC")
+        );
+        assert_eq!(
+            DynamoLanguage.narrow_query("", &added).as_deref(),
+            Ok("contains(status, \"open\")")
+        );
+        let narrowed = DynamoLanguage
+            .narrow_query("a = 1 OR b = 2", &added)
+            .unwrap();
+        assert_eq!(narrowed, "(a=1 OR b=2) AND contains(status, \"open\")");
+        assert!(DynamoLanguage.narrow_query("a = ", &added).is_err());
+    }
+
+    #[test]
+    fn update_expressions_become_item_updates() {
+        let update = DynamoLanguage
+            .parse_update("SET status = done, n = 2.5, ok = true REMOVE lock")
+            .unwrap();
+        assert_eq!(update.set.get("status"), Some(&Value::from("done")));
+        assert_eq!(update.set.get("n"), Some(&Value::Num(Number::new("2.5"))));
+        assert_eq!(update.set.get("ok"), Some(&Value::Bool(true)));
+        assert_eq!(update.remove, vec!["lock".to_string()]);
+        assert!(DynamoLanguage.parse_update("status = done").is_err());
+        assert!(update.expression.is_none());
+
+        // Anything that reads the item or reaches into a document is kept
+        // as written and previewed against each item.
+        let update = DynamoLanguage
+            .parse_update("SET n = n + 1, address.city = Paris ADD seen 1")
+            .unwrap();
+        assert_eq!(
+            update.expression.as_deref(),
+            Some("SET n = n + 1, address.city = Paris ADD seen 1")
+        );
+        assert!(update.set.is_empty());
+        let item: Item = [("n".to_string(), Value::Num(Number::new("41")))]
+            .into_iter()
+            .collect();
+        let updated = DynamoLanguage.apply_update(&update, &item).unwrap_err();
+        assert!(updated.contains("document path"), "{updated}");
+        let update = DynamoLanguage.parse_update("SET n = n + 1").unwrap();
+        let updated = DynamoLanguage.apply_update(&update, &item).unwrap();
+        assert_eq!(updated["n"], Value::Num(Number::new("42")));
+        let update = DynamoLanguage
+            .parse_update("SET pk.part = x REMOVE items[0] ADD n 1")
+            .unwrap();
+        assert_eq!(
+            DynamoLanguage.updated_attributes(&update),
+            vec!["pk".to_string(), "items".to_string(), "n".to_string()]
+        );
+        let update = DynamoLanguage.parse_update("SET tags = [a, 1]").unwrap();
+        assert_eq!(
+            update.set.get("tags"),
+            Some(&Value::List(vec![
+                Value::from("a"),
+                Value::Num(Number::new("1"))
+            ]))
+        );
+    }
+
+    #[test]
+    fn full_primary_key_equality_predicts_a_get_item() {
+        let key = |name: &str, role| KeyField {
+            name: name.to_string(),
+            role,
+            ty: ScalarType::String,
+        };
+        let schema = CollectionSchema {
+            key: KeySchema {
+                fields: vec![key("PK", KeyRole::Partition), key("SK", KeyRole::Sort)],
+            },
+            ..CollectionSchema::default()
+        };
+        let predict =
+            |text: &str| predict_plan_kind(&parse_dynamo_expression(text).unwrap(), Some(&schema));
+        assert_eq!(predict("PK = \"a\" AND SK = \"b\""), PlanKind::Lookup);
+        assert_eq!(
+            predict("PK = \"a\""),
+            PlanKind::IndexedQuery { index: None }
+        );
+        assert_eq!(
+            predict("PK = \"a\" AND SK = \"b\" AND total > 3"),
+            PlanKind::IndexedQuery { index: None }
+        );
+        assert_eq!(
+            predict("PK = \"a\" AND begins_with(SK, \"b\")"),
+            PlanKind::IndexedQuery { index: None }
+        );
+    }
+}
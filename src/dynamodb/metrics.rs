@@ -0,0 +1,173 @@
+//! A table's capacity, throttling, and latency metrics from CloudWatch
+//! (`GetMetricData`). The CloudWatch client is configured the same way as
+//! DynamoDB's (see [`sdk_config_for`]), so it uses the selected profile,
+//! region, and role.
+
+use aws_sdk_cloudwatch::Client;
+use aws_sdk_cloudwatch::types::{Dimension, Metric, MetricDataQuery, MetricStat};
+use aws_smithy_types::DateTime;
+
+use super::connect::sdk_config_for;
+use super::{format_sdk_error, send_aws_request};
+use crate::core::connect::ProfileSelection;
+use crate::core::metrics::{CollectionMetrics, MetricKind, MetricSeries, MetricsWindow};
+
+const NAMESPACE: &str = "AWS/DynamoDB";
+
+/// A CloudWatch client for `selection`.
+pub async fn connect(selection: &ProfileSelection) -> Result<Client, String> {
+    let config = sdk_config_for(selection).await?;
+    Ok(Client::new(&config))
+}
+
+/// Fetch every [`MetricKind`] of `table_name` over the `window` ending with
+/// the current period.
+pub async fn table_metrics(
+    client: Client,
+    table_name: &str,
+    window: MetricsWindow,
+    now: i64,
+) -> Result<CollectionMetrics, String> {
+    let period = window.period().as_secs() as i64;
+    let end = (now / period + 1) * period;
+    let start = end - window.duration().as_secs() as i64;
+    let queries: Vec<MetricDataQuery> = MetricKind::ALL
+        .iter()
+        .map(|kind| query(*kind, table_name, period as i32))
+        .collect();
+
+    let mut samples: Vec<(MetricKind, Vec<(i64, f64)>)> = MetricKind::ALL
+        .iter()
+        .map(|kind| (*kind, Vec::new()))
+        .collect();
+    let mut next_token: Option<String> = None;
+    loop {
+        let request = client
+            .get_metric_data()
+            .set_metric_data_queries(Some(queries.clone()))
+            .start_time(DateTime::from_secs(start))
+            .end_time(DateTime::from_secs(end))
+            .set_next_token(next_token.take());
+        let span =
+            tracing::trace_span!("GetMetricData", table = %table_name, window = window.label());
+        let output = send_aws_request(span, request.send(), format_sdk_error)
+            .await
+            .map_err(|err| format!("CloudWatch: {}", format_sdk_error(&err)))?;
+        for result in output.metric_data_results() {
+            let Some((_, points)) = samples
+                .iter_mut()
+                .find(|(kind, _)| Some(id(*kind)) == result.id())
+            else {
+                continue;
+            };
+            points.extend(
+                result
+                    .timestamps()
+                    .iter()
+                    .map(DateTime::secs)
+                    .zip(result.values().iter().copied()),
+            );
+        }
+        match output.next_token() {
+            Some(token) => next_token = Some(token.to_string()),
+            None => break,
+        }
+    }
+
+    let series = samples
+        .into_iter()
+        .map(|(kind, points)| MetricSeries::from_samples(kind, window, start, points))
+        .collect();
+    Ok(CollectionMetrics {
+        window,
+        start,
+        series,
+    })
+}
+
+fn id(kind: MetricKind) -> &'static str {
+    match kind {
+        MetricKind::ConsumedRead => "read",
+        MetricKind::ConsumedWrite => "write",
+        MetricKind::Throttled => "throttled",
+        MetricKind::Latency => "latency",
+    }
+}
+
+/// Consumed capacity is reported per table; throttling and latency per
+/// table and operation, so those are searched for and combined.
+fn query(kind: MetricKind, table_name: &str, period: i32) -> MetricDataQuery {
+    let builder = MetricDataQuery::builder().id(id(kind)).return_data(true);
+    match kind {
+        MetricKind::ConsumedRead | MetricKind::ConsumedWrite => {
+            let metric_name = if kind == MetricKind::ConsumedRead {
+                "ConsumedReadCapacityUnits"
+            } else {
+                "ConsumedWriteCapacityUnits"
+            };
+            let metric = Metric::builder()
+                .namespace(NAMESPACE)
+                .metric_name(metric_name)
+                .dimensions(
+                    Dimension::builder()
+                        .name("TableName")
+                        .value(table_name)
+                        .build(),
+                )
+                .build();
+            builder
+                .metric_stat(
+                    MetricStat::builder()
+                        .metric(metric)
+                        .period(period)
+                        .stat("Sum")
+                        .build(),
+                )
+                .build()
+        }
+        MetricKind::Throttled => builder
+            .expression(search(
+                "SUM",
+                "ThrottledRequests",
+                "Sum",
+                table_name,
+                period,
+            ))
+            .period(period)
+            .build(),
+        MetricKind::Latency => builder
+            .expression(search(
+                "AVG",
+                "SuccessfulRequestLatency",
+                "Average",
+                table_name,
+                period,
+            ))
+            .period(period)
+            .build(),
+    }
+}
+
+/// A metric math expression combining `metric_name` across operations.
+/// Table names are limited to letters, digits, `_`, `-`, and `.`, so they
+/// need no escaping.
+fn search(function: &str, metric_name: &str, stat: &str, table_name: &str, period: i32) -> String {
+    format!(
+        "{function}(SEARCH('{{{NAMESPACE},Operation,TableName}} \
+         MetricName=\"{metric_name}\" TableName=\"{table_name}\"', '{stat}', {period}))"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::search;
+
+    #[test]
+    fn searches_across_operations() {
+        assert_eq!(
+            search("SUM", "ThrottledRequests", "Sum", "users", 300),
+            "SUM(SEARCH('{AWS/DynamoDB,Operation,TableName} \
+             MetricName=\"ThrottledRequests\" TableName=\"users\"', 'Sum', 300))"
+        );
+    }
+}
@@ -1,27 +1,39 @@
 pub mod backend;
+pub mod backups;
 pub mod completion;
 pub mod connect;
 pub mod convert;
 pub mod create_table;
 pub mod debug;
 pub mod executor;
+pub mod imports;
 pub mod json;
 pub mod language;
+pub mod metrics;
+pub mod partiql;
+pub mod profiles;
 pub mod query;
 pub mod request_builder;
+pub mod requests;
+pub mod retry;
 pub mod scan;
 pub mod size;
 pub mod table_analyzer;
+pub mod transact;
+pub mod update_eval;
+pub mod update_item;
+pub mod update_table;
 
 pub use backend::DynamoBackend;
 pub use create_table::{
     AttributeType, CreateTableSpec, GsiSpec, IndexProjection, KeySpec, LsiSpec, create_table,
 };
-pub use debug::{format_sdk_error, send_dynamo_request};
+pub use debug::{format_sdk_error, send_aws_request, send_dynamo_request};
 pub use executor::*;
 pub use json::*;
 pub use query::*;
 pub use request_builder::*;
+pub use requests::{RequestHandle, RequestManager};
 pub use scan::*;
 pub use size::*;
 pub use table_analyzer::*;
@@ -0,0 +1,112 @@
+//! PartiQL statements (`ExecuteStatement`).
+//!
+//! The query box accepts PartiQL alongside the compact expression syntax; a
+//! statement is recognized by its leading keyword. Pagination follows the
+//! service's `NextToken`, carried in a [`Cursor`] like any other page token.
+
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::operation::execute_statement::ExecuteStatementOutput;
+use aws_sdk_dynamodb::types::ReturnConsumedCapacity;
+
+use crate::core::query::Cursor;
+use crate::core::value::{Item, Value};
+
+use super::{format_sdk_error, send_dynamo_request};
+
+/// Cursor field holding the `NextToken` of a paginated statement.
+const NEXT_TOKEN_FIELD: &str = "next_token";
+
+/// Leading keywords that make the query text a PartiQL statement.
+const STATEMENT_KEYWORDS: &[&str] = &["SELECT", "UPDATE", "DELETE", "INSERT"];
+
+/// Whether the text is a PartiQL statement rather than a dynamate expression.
+pub fn is_statement(text: &str) -> bool {
+    leading_keyword(text).is_some()
+}
+
+/// Whether the statement writes (anything other than `SELECT`).
+pub fn is_mutating(text: &str) -> bool {
+    leading_keyword(text).is_some_and(|keyword| keyword != "SELECT")
+}
+
+fn leading_keyword(text: &str) -> Option<&'static str> {
+    let word = text.split_whitespace().next()?;
+    STATEMENT_KEYWORDS
+        .iter()
+        .copied()
+        .find(|keyword| word.eq_ignore_ascii_case(keyword))
+}
+
+/// Wrap a `NextToken` as an opaque page cursor.
+pub fn cursor_from_token(token: &str) -> Cursor {
+    let mut item = Item::new();
+    item.insert(NEXT_TOKEN_FIELD.to_string(), Value::Str(token.to_string()));
+    Cursor(item)
+}
+
+/// Recover the `NextToken` from a cursor produced by [`cursor_from_token`].
+pub fn token_from_cursor(cursor: &Cursor) -> Option<String> {
+    match cursor.0.get(NEXT_TOKEN_FIELD) {
+        Some(Value::Str(token)) => Some(token.clone()),
+        _ => None,
+    }
+}
+
+/// Run one page of a PartiQL statement.
+pub async fn execute_statement(
+    client: &Client,
+    statement: &str,
+    next_token: Option<String>,
+    limit: Option<i32>,
+) -> Result<ExecuteStatementOutput, String> {
+    tracing::trace!(
+        statement = %statement,
+        next_token_present = next_token.is_some(),
+        limit = ?limit,
+        "ExecuteStatement"
+    );
+    let span = tracing::trace_span!(
+        "ExecuteStatement",
+        next_token_present = next_token.is_some(),
+        limit = ?limit
+    );
+    let request = client
+        .execute_statement()
+        .statement(statement)
+        .set_next_token(next_token)
+        .set_limit(limit)
+        .return_consumed_capacity(ReturnConsumedCapacity::Total);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map_err(|err| format_sdk_error(&err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cursor_from_token, is_mutating, is_statement, token_from_cursor};
+
+    #[test]
+    fn detects_statements_by_leading_keyword() {
+        assert!(is_statement("SELECT * FROM \"orders\""));
+        assert!(is_statement("  select * from orders"));
+        assert!(is_statement("UPDATE orders SET a = 1 WHERE pk = 'x'"));
+        assert!(is_statement("delete from orders where pk = 'x'"));
+        assert!(!is_statement("status = \"OPEN\""));
+        assert!(!is_statement("selected = true"));
+        assert!(!is_statement(""));
+    }
+
+    #[test]
+    fn only_select_is_read_only() {
+        assert!(!is_mutating("SELECT * FROM t"));
+        assert!(is_mutating("UPDATE t SET a = 1 WHERE pk = 'x'"));
+        assert!(is_mutating("INSERT INTO t VALUE {'pk': 'x'}"));
+        assert!(!is_mutating("pk = \"x\""));
+    }
+
+    #[test]
+    fn cursor_round_trips_next_token() {
+        let cursor = cursor_from_token("abc==");
+        assert_eq!(token_from_cursor(&cursor).as_deref(), Some("abc=="));
+    }
+}
@@ -0,0 +1,177 @@
+//! AWS named-profile discovery.
+//!
+//! Reads the shared config (`~/.aws/config`, or `AWS_CONFIG_FILE`) and
+//! credentials (`~/.aws/credentials`, or `AWS_SHARED_CREDENTIALS_FILE`) files to
+//! list the profiles the user can switch to at runtime. Only section headers and
+//! the `region` key are read; resolving credentials is left to the SDK.
+
+use std::path::PathBuf;
+
+use directories::BaseDirs;
+
+use crate::core::connect::ProfileInfo;
+
+const CONFIG_FILE_ENV: &str = "AWS_CONFIG_FILE";
+const CREDENTIALS_FILE_ENV: &str = "AWS_SHARED_CREDENTIALS_FILE";
+
+/// Regions offered by the region picker, in addition to any region configured
+/// on a profile.
+pub const COMMON_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+    "ca-central-1",
+    "sa-east-1",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-west-3",
+    "eu-central-1",
+    "eu-central-2",
+    "eu-north-1",
+    "eu-south-1",
+    "ap-south-1",
+    "ap-northeast-1",
+    "ap-northeast-2",
+    "ap-northeast-3",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-east-1",
+    "me-central-1",
+    "af-south-1",
+];
+
+/// List the profiles defined in the shared config and credentials files,
+/// sorted by name. Missing or unreadable files contribute nothing.
+pub fn list_profiles() -> Vec<ProfileInfo> {
+    let config = read_shared_file(CONFIG_FILE_ENV, "config");
+    let credentials = read_shared_file(CREDENTIALS_FILE_ENV, "credentials");
+    parse_profiles(&config, &credentials)
+}
+
+fn read_shared_file(env_var: &str, file_name: &str) -> String {
+    let path = std::env::var_os(env_var)
+        .map(PathBuf::from)
+        .or_else(|| BaseDirs::new().map(|dirs| dirs.home_dir().join(".aws").join(file_name)));
+    path.and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default()
+}
+
+/// Merge the profiles from a config file (`[profile name]` / `[default]`
+/// sections) and a credentials file (`[name]` sections).
+pub fn parse_profiles(config: &str, credentials: &str) -> Vec<ProfileInfo> {
+    let mut profiles: Vec<ProfileInfo> = Vec::new();
+    let mut merge = |name: String, region: Option<String>| {
+        if let Some(existing) = profiles.iter_mut().find(|p| p.name == name) {
+            if existing.region.is_none() {
+                existing.region = region;
+            }
+        } else {
+            profiles.push(ProfileInfo { name, region });
+        }
+    };
+    for (name, region) in parse_sections(config, true) {
+        merge(name, region);
+    }
+    for (name, region) in parse_sections(credentials, false) {
+        merge(name, region);
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    profiles
+}
+
+/// Parse `(profile, region)` pairs from an INI-style shared file. In the config
+/// file, non-default profiles are prefixed with `profile ` and other section
+/// kinds (`sso-session`, `services`) are skipped.
+fn parse_sections(contents: &str, config_file: bool) -> Vec<(String, Option<String>)> {
+    let mut sections: Vec<(String, Option<String>)> = Vec::new();
+    let mut in_profile = false;
+    for raw in contents.lines() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let header = header.trim();
+            let name = if !config_file || header == "default" {
+                Some(header)
+            } else {
+                header.strip_prefix("profile ").map(str::trim)
+            };
+            in_profile = match name {
+                Some(name) if !name.is_empty() => {
+                    sections.push((name.to_string(), None));
+                    true
+                }
+                _ => false,
+            };
+            continue;
+        }
+        if !in_profile {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=')
+            && key.trim() == "region"
+            && let Some(section) = sections.last_mut()
+        {
+            let value = value.trim();
+            if !value.is_empty() {
+                section.1 = Some(value.to_string());
+            }
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_profiles;
+
+    #[test]
+    fn merges_config_and_credentials_profiles() {
+        let config = "\
+[default]
+region = us-east-1
+
+[profile prod]
+region=eu-west-1
+output = json
+
+[sso-session corp]
+region = us-west-2
+
+[profile dev]
+";
+        let credentials = "\
+[default]
+aws_access_key_id = AKIA
+
+[dev]
+region = ap-south-1
+
+[legacy]
+";
+        let profiles = parse_profiles(config, credentials);
+        let summary: Vec<(&str, Option<&str>)> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.region.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("default", Some("us-east-1")),
+                ("dev", Some("ap-south-1")),
+                ("legacy", None),
+                ("prod", Some("eu-west-1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_comments_and_empty_files() {
+        assert!(parse_profiles("", "").is_empty());
+        let profiles = parse_profiles("# [profile nope]\n; region = x\n[profile a]\n", "");
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "a");
+    }
+}
@@ -9,6 +9,9 @@ pub struct QueryBuilder {
     query_type: QueryType,
     key_condition_expression: Option<String>,
     filter_expression: Option<String>,
+    projection_expression: Option<String>,
+    count_only: bool,
+    scan_index_forward: bool,
     expression_attribute_names: HashMap<String, String>,
     expression_attribute_values: HashMap<String, AttributeValue>,
 }
@@ -20,6 +23,9 @@ impl QueryBuilder {
             query_type,
             key_condition_expression: None,
             filter_expression: None,
+            projection_expression: None,
+            count_only: false,
+            scan_index_forward: true,
             expression_attribute_names: HashMap::new(),
             expression_attribute_values: HashMap::new(),
         };
@@ -33,6 +39,9 @@ impl QueryBuilder {
             query_type,
             key_condition_expression: None,
             filter_expression: None,
+            projection_expression: None,
+            count_only: false,
+            scan_index_forward: true,
             expression_attribute_names: HashMap::new(),
             expression_attribute_values: HashMap::new(),
         };
@@ -96,6 +105,37 @@ impl QueryBuilder {
         self.filter_expression.as_ref()
     }
 
+    pub fn projection_expression(&self) -> Option<&String> {
+        self.projection_expression.as_ref()
+    }
+
+    /// Only fetch `attributes`; an empty list fetches whole items.
+    pub fn set_projection(&mut self, attributes: &[String]) {
+        self.projection_expression = super::request_builder::projection_expression(
+            attributes,
+            &mut self.expression_attribute_names,
+        );
+    }
+
+    /// `false` returns items in descending sort-key order.
+    pub fn scan_index_forward(&self) -> bool {
+        self.scan_index_forward
+    }
+
+    pub fn set_scan_index_forward(&mut self, forward: bool) {
+        self.scan_index_forward = forward;
+    }
+
+    /// Ask for the number of matches (`Select=COUNT`) instead of the items.
+    /// DynamoDB rejects a projection alongside it, so don't set both.
+    pub fn set_count_only(&mut self, count_only: bool) {
+        self.count_only = count_only;
+    }
+
+    pub fn count_only(&self) -> bool {
+        self.count_only
+    }
+
     pub fn expression_attribute_names(&self) -> &HashMap<String, String> {
         &self.expression_attribute_names
     }
@@ -116,6 +156,41 @@ impl QueryBuilder {
         !matches!(self.query_type, QueryType::TableScan)
     }
 
+    /// The table's full primary key, when this query pins it with equality
+    /// (partition key, plus the sort key if the table has one) and has
+    /// nothing left to filter. Such a query matches at most one item and can
+    /// run as a `GetItem`.
+    pub fn primary_key_lookup(
+        &self,
+        table_info: &TableInfo,
+    ) -> Option<HashMap<String, AttributeValue>> {
+        let QueryType::TableQuery {
+            hash_key_condition,
+            range_key_condition,
+        } = &self.query_type
+        else {
+            return None;
+        };
+        if self.filter_expression.is_some() {
+            return None;
+        }
+        let mut key = HashMap::new();
+        for condition in std::iter::once(hash_key_condition).chain(range_key_condition) {
+            let KeyConditionType::Equal(value) = &condition.condition else {
+                return None;
+            };
+            key.insert(condition.attribute_name.clone(), value.clone());
+        }
+        let pinned = |name: &str| key.contains_key(name);
+        let complete = pinned(&table_info.primary_key.hash_key)
+            && table_info
+                .primary_key
+                .range_key
+                .as_deref()
+                .is_none_or(pinned);
+        complete.then_some(key)
+    }
+
     fn build_conditions_from_expression(&mut self, expr: &DynamoExpression) {
         let mut name_counter = 0;
         let mut value_counter = 0;
@@ -1,6 +1,8 @@
-use aws_sdk_dynamodb::types::TableDescription;
+use std::collections::HashMap;
 
-use super::{QueryBuilder, QueryType, ScanBuilder, TableInfo};
+use aws_sdk_dynamodb::types::{AttributeValue, TableDescription};
+
+use super::{QueryBuilder, QueryType, ScanBuilder, TableInfo, estimate_item_size_bytes};
 use crate::expr::DynamoExpression;
 
 pub enum DynamoDbRequest {
@@ -23,6 +25,22 @@ impl DynamoDbRequest {
         }
     }
 
+    /// Only fetch `attributes`; an empty list fetches whole items.
+    pub fn set_projection(&mut self, attributes: &[String]) {
+        match self {
+            Self::Query(builder) => builder.set_projection(attributes),
+            Self::Scan(builder) => builder.set_projection(attributes),
+        }
+    }
+
+    /// Only count the matches; see [`QueryBuilder::set_count_only`].
+    pub fn set_count_only(&mut self, count_only: bool) {
+        match self {
+            Self::Query(builder) => builder.set_count_only(count_only),
+            Self::Scan(builder) => builder.set_count_only(count_only),
+        }
+    }
+
     pub fn is_query(&self) -> bool {
         matches!(self, Self::Query(_))
     }
@@ -45,6 +63,30 @@ impl DynamoDbRequest {
         }
     }
 
+    /// Whether the request's expressions are within DynamoDB's limits; see
+    /// [`check_expression_limits`].
+    pub fn check_limits(&self) -> Result<(), String> {
+        match self {
+            Self::Query(builder) => check_expression_limits(
+                &[
+                    ("key condition", builder.key_condition_expression()),
+                    ("filter", builder.filter_expression()),
+                    ("projection", builder.projection_expression()),
+                ],
+                builder.expression_attribute_names(),
+                builder.expression_attribute_values(),
+            ),
+            Self::Scan(builder) => check_expression_limits(
+                &[
+                    ("filter", builder.filter_expression()),
+                    ("projection", builder.projection_expression()),
+                ],
+                builder.expression_attribute_names(),
+                builder.expression_attribute_values(),
+            ),
+        }
+    }
+
     pub fn operation_type(&self) -> String {
         match self {
             Self::Query(builder) => match builder.query_type() {
@@ -61,3 +103,187 @@ impl DynamoDbRequest {
         }
     }
 }
+
+/// The most bytes DynamoDB takes in one expression.
+const MAX_EXPRESSION_BYTES: usize = 4 * 1024;
+/// The most bytes of names and values one request's placeholders stand for.
+const MAX_SUBSTITUTION_BYTES: usize = 2 * 1024 * 1024;
+/// The longest attribute name DynamoDB takes.
+const MAX_NAME_BYTES: usize = 64 * 1024;
+
+/// Check `expressions` (each with what it is, for the message) and the names
+/// and values their placeholders stand for against DynamoDB's limits, so an
+/// oversized request fails before it's sent, with the limit it exceeds.
+/// Names always go through placeholders, so reserved words and punctuation
+/// are never a problem; only their length is.
+pub fn check_expression_limits(
+    expressions: &[(&str, Option<&String>)],
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> Result<(), String> {
+    for (what, expression) in expressions {
+        let Some(expression) = expression else {
+            continue;
+        };
+        if expression.len() > MAX_EXPRESSION_BYTES {
+            return Err(format!(
+                "The {what} expression is {} bytes, over DynamoDB's 4 KB limit",
+                expression.len()
+            ));
+        }
+    }
+    for name in names.values() {
+        if name.is_empty() {
+            return Err("Attribute names can't be empty".to_string());
+        }
+        if name.len() > MAX_NAME_BYTES {
+            let start: String = name.chars().take(16).collect();
+            return Err(format!(
+                "The attribute name {start}… is {} bytes, over DynamoDB's 64 KB limit",
+                name.len()
+            ));
+        }
+    }
+    let substituted = names
+        .iter()
+        .map(|(placeholder, name)| placeholder.len() + name.len())
+        .sum::<usize>()
+        + estimate_item_size_bytes(values);
+    if substituted > MAX_SUBSTITUTION_BYTES {
+        return Err(format!(
+            "The expression's names and values are {substituted} bytes, over DynamoDB's 2 MB limit"
+        ));
+    }
+    Ok(())
+}
+
+/// A projection expression naming `attributes` through `#proj{n}` placeholders
+/// registered in `names`, or `None` when there are no attributes.
+pub(super) fn projection_expression(
+    attributes: &[String],
+    names: &mut HashMap<String, String>,
+) -> Option<String> {
+    if attributes.is_empty() {
+        return None;
+    }
+    let placeholders: Vec<String> = attributes
+        .iter()
+        .enumerate()
+        .map(|(index, attribute)| {
+            let placeholder = format!("#proj{index}");
+            names.insert(placeholder.clone(), attribute.clone());
+            placeholder
+        })
+        .collect();
+    Some(placeholders.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::types::{
+        GlobalSecondaryIndexDescription, KeySchemaElement, KeyType, TableDescription,
+    };
+
+    use std::collections::HashMap;
+
+    use super::{DynamoDbRequest, check_expression_limits};
+    use crate::expr::parse_dynamo_expression;
+
+    fn key(name: &str, key_type: KeyType) -> KeySchemaElement {
+        KeySchemaElement::builder()
+            .attribute_name(name)
+            .key_type(key_type)
+            .build()
+            .unwrap()
+    }
+
+    fn request(text: &str) -> DynamoDbRequest {
+        let table = TableDescription::builder()
+            .table_name("orders")
+            .key_schema(key("PK", KeyType::Hash))
+            .key_schema(key("SK", KeyType::Range))
+            .global_secondary_indexes(
+                GlobalSecondaryIndexDescription::builder()
+                    .index_name("by-status")
+                    .key_schema(key("status", KeyType::Hash))
+                    .key_schema(key("created", KeyType::Range))
+                    .build(),
+            )
+            .build();
+        DynamoDbRequest::from_expression_and_table(&parse_dynamo_expression(text).unwrap(), &table)
+    }
+
+    /// The key condition and filter of a query, with placeholders numbered
+    /// as the builder numbers them.
+    fn query_parts(text: &str) -> (String, Option<String>) {
+        let request = request(text);
+        let builder = request.query_builder().expect("a query");
+        (
+            builder.key_condition_expression().cloned().unwrap(),
+            builder.filter_expression().cloned(),
+        )
+    }
+
+    #[test]
+    fn sort_key_conditions_key_the_query() {
+        let cases = [
+            ("SK = 1", "#name1 = :val1"),
+            ("SK < 1", "#name1 < :val1"),
+            ("SK <= 1", "#name1 <= :val1"),
+            ("SK > 1", "#name1 > :val1"),
+            ("SK >= 1", "#name1 >= :val1"),
+            ("SK BETWEEN 1 AND 2", "#name1 BETWEEN :val1 AND :val2"),
+            ("begins_with(SK, \"ORDER#\")", "begins_with(#name1, :val1)"),
+        ];
+        for (condition, range) in cases {
+            let (keys, filter) = query_parts(&format!("PK = \"u\" AND ({condition})"));
+            assert_eq!(keys, format!("#name0 = :val0 AND {range}"), "{condition}");
+            assert_eq!(filter, None, "{condition}");
+        }
+
+        let (keys, filter) =
+            query_parts("status = \"open\" AND created BETWEEN 1 AND 9 AND total > 5");
+        assert_eq!(keys, "#name0 = :val0 AND #name1 BETWEEN :val1 AND :val2");
+        assert!(filter.is_some());
+        assert_eq!(
+            request("status = \"open\" AND begins_with(created, \"2026\")").operation_type(),
+            "Query (GSI: by-status)"
+        );
+    }
+
+    #[test]
+    fn key_conditions_a_query_cannot_take_fall_back_to_a_scan() {
+        assert!(request("PK BETWEEN \"a\" AND \"b\"").is_scan());
+        assert!(request("begins_with(PK, \"u\")").is_scan());
+        assert!(request("PK = \"u\" AND SK <> 1").is_scan());
+        assert!(request("PK = \"u\" AND SK > 1 AND SK < 5").is_scan());
+        assert!(request("PK = \"u\" AND contains(SK, \"x\")").is_scan());
+        assert!(request("PK = \"u\" AND contains(note, \"x\")").is_query());
+    }
+
+    #[test]
+    fn names_are_aliased_and_oversized_expressions_rejected() {
+        let aliased = request("name = \"open\" AND `order-id` = \"42\"");
+        let builder = aliased.scan_builder().expect("a scan");
+        assert_eq!(
+            builder.filter_expression().map(String::as_str),
+            Some("(#name0 = :val0) AND (#name1 = :val1)")
+        );
+        let mut names: Vec<_> = builder.expression_attribute_names().values().collect();
+        names.sort();
+        assert_eq!(names, ["name", "order-id"]);
+        assert!(aliased.check_limits().is_ok());
+
+        let conditions: Vec<String> = (0..300).map(|n| format!("name = \"{n}\"")).collect();
+        let long = request(&conditions.join(" OR "));
+        let err = long.check_limits().unwrap_err();
+        assert!(err.contains("filter expression"), "{err}");
+        assert!(err.contains("4 KB"), "{err}");
+
+        let names = HashMap::from([("#a0".to_string(), "x".repeat(70_000))]);
+        let err = check_expression_limits(&[], &names, &HashMap::new()).unwrap_err();
+        assert!(err.contains("64 KB"), "{err}");
+        let names = HashMap::from([("#a0".to_string(), String::new())]);
+        assert!(check_expression_limits(&[], &names, &HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Cancellable background requests. A [`RequestManager`] hands out a
+//! [`RequestHandle`] per request; starting another cancels the one before, so
+//! a superseded request's late results and progress are dropped instead of
+//! overwriting those of the request that replaced it.
+
+use std::sync::{
+    Arc, Mutex, PoisonError,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+#[derive(Debug, Default)]
+pub struct RequestManager {
+    /// The id of the last request handed out.
+    last_id: AtomicU64,
+    /// The latest request, until it finishes. A cancelled request stays here
+    /// until it stops, so callers can tell it is still winding down.
+    current: Mutex<Option<RequestHandle>>,
+}
+
+/// One request: its id, to match results to it, and its cancel flag.
+#[derive(Debug, Clone)]
+pub struct RequestHandle {
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl RequestManager {
+    /// Start a request, cancelling the one in flight.
+    pub fn start(&self) -> RequestHandle {
+        let handle = RequestHandle {
+            id: self.last_id.fetch_add(1, Ordering::Relaxed) + 1,
+            cancelled: Arc::default(),
+        };
+        if let Some(previous) = self.lock().replace(handle.clone()) {
+            previous.cancel();
+        }
+        handle
+    }
+
+    /// The latest request, unless it was cancelled or has finished.
+    pub fn current(&self) -> Option<RequestHandle> {
+        self.lock()
+            .as_ref()
+            .filter(|handle| !handle.is_cancelled())
+            .cloned()
+    }
+
+    /// Whether request `id` is the latest and hasn't been cancelled, i.e. its
+    /// results should still be used.
+    pub fn is_current(&self, id: u64) -> bool {
+        self.current().is_some_and(|handle| handle.id == id)
+    }
+
+    /// Whether a request was started and hasn't finished, cancelled or not.
+    pub fn is_busy(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    /// Cancel the latest request. Returns whether this cancelled it, as
+    /// opposed to it being cancelled already or there being none.
+    pub fn cancel(&self) -> bool {
+        self.lock().as_ref().is_some_and(RequestHandle::cancel)
+    }
+
+    /// Record that request `id` stopped. A superseded request finishing
+    /// leaves the latest one alone.
+    pub fn finish(&self, id: u64) {
+        let mut current = self.lock();
+        if current.as_ref().is_some_and(|handle| handle.id == id) {
+            *current = None;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<RequestHandle>> {
+        self.current.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl RequestHandle {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The cancel flag, for operations that poll one between steps.
+    pub fn flag(&self) -> &AtomicBool {
+        &self.cancelled
+    }
+
+    /// The cancel flag itself, to share with whatever else may cancel the
+    /// request, like the jobs panel.
+    pub fn shared_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// `report` wrapped to go quiet once this request is cancelled or
+    /// superseded.
+    pub fn progress<P>(&self, report: impl Fn(P)) -> impl Fn(P) {
+        let cancelled = self.cancelled.clone();
+        move |progress| {
+            if !cancelled.load(Ordering::Relaxed) {
+                report(progress);
+            }
+        }
+    }
+
+    fn cancel(&self) -> bool {
+        !self.cancelled.swap(true, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::RequestManager;
+
+    #[test]
+    fn a_new_request_supersedes_the_last() {
+        let requests = RequestManager::default();
+        let first = requests.start();
+        assert!(requests.is_current(first.id()));
+
+        let reported = Cell::new(0);
+        let report = first.progress(|count| reported.set(count));
+        report(1);
+        let second = requests.start();
+        report(2);
+        assert_eq!(reported.get(), 1);
+        assert!(first.is_cancelled());
+        assert!(!requests.is_current(first.id()));
+        assert!(requests.is_current(second.id()));
+
+        requests.finish(first.id());
+        assert!(requests.is_busy());
+        assert!(requests.cancel());
+        assert!(!requests.cancel());
+        assert!(requests.current().is_none());
+        assert!(requests.is_busy());
+        requests.finish(second.id());
+        assert!(!requests.is_busy());
+    }
+}
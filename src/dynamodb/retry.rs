@@ -0,0 +1,212 @@
+//! Retrying throttled DynamoDB requests. A request turned away with
+//! `ProvisionedThroughputExceededException`, `ThrottlingException`, or
+//! `RequestLimitExceeded`, or failing in a way that usually passes (a
+//! transient server error, any 5xx response, a timeout, or a connection that
+//! couldn't be made), is sent again after an exponentially growing, jittered
+//! wait, up to the policy's attempt limit. Counters here feed the footer's "throttled, retrying…"
+//! status and the debug overlay.
+//!
+//! This is the only retry loop: DynamoDB clients are built with the SDK's
+//! own retries turned off (see [`sdk_retry_config`]), so one request is
+//! never retried by both.
+
+use std::{
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_sdk_dynamodb::config::retry::RetryConfig;
+use aws_sdk_dynamodb::error::{ProvideErrorMetadata, SdkError};
+use rand::Rng;
+
+static POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+/// Retries sent since startup.
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+/// Requests waiting out a backoff right now.
+static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+const THROTTLING_CODES: [&str; 3] = [
+    "ProvisionedThroughputExceededException",
+    "ThrottlingException",
+    "RequestLimitExceeded",
+];
+
+/// Server-side failures that usually pass, which the SDK would otherwise
+/// have retried.
+const TRANSIENT_CODES: [&str; 2] = ["InternalServerError", "ServiceUnavailable"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Tries per request, the first included; 1 never retries.
+    pub max_attempts: u32,
+    /// The wait before the first retry, doubled for each one after.
+    pub base_delay: Duration,
+    /// The longest a single wait can grow.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The longest wait before retry number `retry` (1-based); the actual
+    /// wait is a random share of it of at least a half.
+    fn ceiling(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        let ceiling = self.ceiling(retry);
+        ceiling.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// Set the policy for the rest of the process. Only the first call takes
+/// effect; until then the default applies.
+pub fn set(policy: RetryPolicy) {
+    let _ = POLICY.set(policy);
+}
+
+pub fn policy() -> RetryPolicy {
+    POLICY.get().copied().unwrap_or_default()
+}
+
+/// Whether an error code means the request was throttled and is worth
+/// sending again.
+pub fn is_throttling(code: Option<&str>) -> bool {
+    code.is_some_and(|code| THROTTLING_CODES.contains(&code))
+}
+
+/// Whether a request that failed with `code` is worth sending again.
+pub fn is_retryable(code: Option<&str>) -> bool {
+    is_throttling(code) || code.is_some_and(|code| TRANSIENT_CODES.contains(&code))
+}
+
+/// Whether a failed request is worth sending again: throttled, turned away
+/// with a transient code or any 5xx status, timed out, or never sent for a
+/// reason other than a malformed request.
+pub fn is_retryable_error<E: ProvideErrorMetadata>(err: &SdkError<E, HttpResponse>) -> bool {
+    match err {
+        SdkError::TimeoutError(_) => true,
+        SdkError::DispatchFailure(failure) => !failure.is_user(),
+        _ => {
+            is_retryable(err.code())
+                || err
+                    .raw_response()
+                    .is_some_and(|raw| raw.status().is_server_error())
+        }
+    }
+}
+
+/// The retry settings for DynamoDB clients: none, since
+/// [`send_dynamo_request`](super::send_dynamo_request) retries as the
+/// [`policy`] says.
+pub fn sdk_retry_config() -> RetryConfig {
+    RetryConfig::disabled()
+}
+
+/// How many throttled requests have been retried since startup.
+pub fn retries() -> u64 {
+    RETRIES.load(Ordering::Relaxed)
+}
+
+/// Whether some request is waiting to be retried after being throttled.
+pub fn is_retrying() -> bool {
+    WAITING.load(Ordering::Relaxed) > 0
+}
+
+/// Wait before retry number `retry` of a throttled request. Returns `false`
+/// when the policy has no retries left, without waiting.
+pub(super) async fn back_off(retry: u32) -> bool {
+    let policy = policy();
+    if retry >= policy.max_attempts {
+        return false;
+    }
+    let delay = policy.delay(retry);
+    tracing::debug!(retry, delay_ms = delay.as_millis(), "Throttled, retrying");
+    RETRIES.fetch_add(1, Ordering::Relaxed);
+    let _waiting = Waiting::start();
+    tokio::time::sleep(delay).await;
+    true
+}
+
+/// Counts a request in [`WAITING`] for as long as it lives, so a request
+/// dropped mid-wait doesn't leave the status stuck on.
+struct Waiting;
+
+impl Waiting {
+    fn start() -> Self {
+        WAITING.fetch_add(1, Ordering::Relaxed);
+        Self
+    }
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        WAITING.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aws_sdk_dynamodb::config::http::HttpResponse;
+    use aws_sdk_dynamodb::error::SdkError;
+    use aws_sdk_dynamodb::operation::get_item::GetItemError;
+    use aws_smithy_types::body::SdkBody;
+
+    use super::{RetryPolicy, is_retryable, is_retryable_error, is_throttling};
+
+    #[test]
+    fn backs_off_exponentially_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        assert_eq!(policy.ceiling(1), Duration::from_millis(100));
+        assert_eq!(policy.ceiling(3), Duration::from_millis(400));
+        assert_eq!(policy.ceiling(5), Duration::from_secs(1));
+        assert_eq!(policy.ceiling(60), Duration::from_secs(1));
+        let delay = policy.delay(2);
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+
+        assert!(is_throttling(Some("ThrottlingException")));
+        assert!(is_throttling(Some(
+            "ProvisionedThroughputExceededException"
+        )));
+        assert!(!is_throttling(Some("ValidationException")));
+        assert!(!is_throttling(None));
+        assert!(is_retryable(Some("InternalServerError")));
+        assert!(is_retryable(Some("RequestLimitExceeded")));
+        assert!(!is_retryable(Some("ConditionalCheckFailedException")));
+    }
+
+    #[test]
+    fn retries_timeouts_and_server_errors_without_a_code() {
+        let response = |status: u16| {
+            HttpResponse::new(status.try_into().expect("status code"), SdkBody::empty())
+        };
+        let timeout: SdkError<GetItemError, HttpResponse> = SdkError::timeout_error("timed out");
+        assert!(is_retryable_error(&timeout));
+        let unavailable: SdkError<GetItemError, HttpResponse> =
+            SdkError::response_error("bad gateway", response(502));
+        assert!(is_retryable_error(&unavailable));
+        let rejected: SdkError<GetItemError, HttpResponse> =
+            SdkError::response_error("unparseable", response(400));
+        assert!(!is_retryable_error(&rejected));
+    }
+}
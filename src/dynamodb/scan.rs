@@ -6,7 +6,10 @@ use crate::expr::{Comparator, DynamoExpression, FunctionName, Operand};
 
 #[derive(Default)]
 pub struct ScanBuilder {
+    index_name: Option<String>,
     filter_expression: Option<String>,
+    projection_expression: Option<String>,
+    count_only: bool,
     expression_attribute_names: HashMap<String, String>,
     expression_attribute_values: HashMap<String, AttributeValue>,
 }
@@ -37,10 +40,41 @@ impl ScanBuilder {
         self.filter_expression = Some(filter_expr);
     }
 
+    /// Scan a secondary index instead of the table.
+    pub fn set_index_name(&mut self, index_name: Option<String>) {
+        self.index_name = index_name;
+    }
+
+    pub fn index_name(&self) -> Option<&String> {
+        self.index_name.as_ref()
+    }
+
     pub fn filter_expression(&self) -> Option<&String> {
         self.filter_expression.as_ref()
     }
 
+    pub fn projection_expression(&self) -> Option<&String> {
+        self.projection_expression.as_ref()
+    }
+
+    /// Only fetch `attributes`; an empty list fetches whole items.
+    pub fn set_projection(&mut self, attributes: &[String]) {
+        self.projection_expression = super::request_builder::projection_expression(
+            attributes,
+            &mut self.expression_attribute_names,
+        );
+    }
+
+    /// Ask for the number of matches (`Select=COUNT`) instead of the items.
+    /// DynamoDB rejects a projection alongside it, so don't set both.
+    pub fn set_count_only(&mut self, count_only: bool) {
+        self.count_only = count_only;
+    }
+
+    pub fn count_only(&self) -> bool {
+        self.count_only
+    }
+
     pub fn expression_attribute_names(&self) -> &HashMap<String, String> {
         &self.expression_attribute_names
     }
@@ -241,7 +275,7 @@ impl ScanBuilder {
                 *value_counter += 1;
                 attr_values.insert(
                     value_placeholder.clone(),
-                    AttributeValue::N(num.to_string()),
+                    AttributeValue::N(num.text().to_string()),
                 );
                 value_placeholder
             }
@@ -257,6 +291,16 @@ impl ScanBuilder {
                 attr_values.insert(value_placeholder.clone(), AttributeValue::Null(true));
                 value_placeholder
             }
+            // Expressions are bound before they are built into requests. An
+            // unbound parameter is left without a value so DynamoDB rejects
+            // the request instead of matching a made-up value.
+            Operand::Placeholder(name) => format!(":param_{name}"),
+            Operand::Size(path) => {
+                let name_placeholder = format!("#name{name_counter}");
+                *name_counter += 1;
+                attr_names.insert(name_placeholder.clone(), path.clone());
+                format!("size({name_placeholder})")
+            }
         }
     }
 }
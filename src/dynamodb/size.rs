@@ -15,3 +15,8 @@ use crate::dynamodb::convert::item_from_attribute_map;
 pub fn estimate_item_size_bytes(item: &HashMap<String, AttributeValue>) -> usize {
     core_size::estimate_item_size_bytes(&item_from_attribute_map(item))
 }
+
+/// Per-attribute sizes of a DynamoDB item, largest first.
+pub fn attribute_sizes(item: &HashMap<String, AttributeValue>) -> Vec<(String, usize)> {
+    core_size::attribute_sizes(&item_from_attribute_map(item))
+}
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
-use aws_sdk_dynamodb::types::{AttributeValue, KeySchemaElement, KeyType, TableDescription};
+use aws_sdk_dynamodb::types::{
+    AttributeValue, KeySchemaElement, KeyType, ScalarAttributeType, TableDescription,
+};
 
-use crate::expr::{Comparator, DynamoExpression, Operand};
+use crate::expr::{Comparator, DynamoExpression, FunctionName, NumberLiteral, Operand};
 
 #[derive(Debug, Clone)]
 pub struct TableInfo {
@@ -184,24 +186,15 @@ impl TableInfo {
     }
 
     fn try_table_query(&self, conditions: &HashMap<String, ConditionInfo>) -> Option<QueryType> {
-        let hash_condition = conditions.get(&self.primary_key.hash_key)?;
-
-        if let Some(hash_key_condition) = hash_condition.to_key_condition() {
-            let range_key_condition = if let Some(ref range_key) = self.primary_key.range_key {
-                conditions
-                    .get(range_key)
-                    .and_then(ConditionInfo::to_key_condition)
-            } else {
-                None
-            };
-
-            return Some(QueryType::TableQuery {
-                hash_key_condition,
-                range_key_condition,
-            });
-        }
-
-        None
+        let (hash_key_condition, range_key_condition) = key_conditions(
+            conditions,
+            &self.primary_key.hash_key,
+            self.primary_key.range_key.as_ref(),
+        )?;
+        Some(QueryType::TableQuery {
+            hash_key_condition,
+            range_key_condition,
+        })
     }
 
     fn try_gsi_query(
@@ -209,25 +202,13 @@ impl TableInfo {
         gsi: &SecondaryIndex,
         conditions: &HashMap<String, ConditionInfo>,
     ) -> Option<QueryType> {
-        let hash_condition = conditions.get(&gsi.hash_key)?;
-
-        if let Some(hash_key_condition) = hash_condition.to_key_condition() {
-            let range_key_condition = if let Some(ref range_key) = gsi.range_key {
-                conditions
-                    .get(range_key)
-                    .and_then(ConditionInfo::to_key_condition)
-            } else {
-                None
-            };
-
-            return Some(QueryType::GlobalSecondaryIndexQuery {
-                index_name: gsi.name.clone(),
-                hash_key_condition,
-                range_key_condition,
-            });
-        }
-
-        None
+        let (hash_key_condition, range_key_condition) =
+            key_conditions(conditions, &gsi.hash_key, gsi.range_key.as_ref())?;
+        Some(QueryType::GlobalSecondaryIndexQuery {
+            index_name: gsi.name.clone(),
+            hash_key_condition,
+            range_key_condition,
+        })
     }
 
     fn try_lsi_query(
@@ -235,45 +216,72 @@ impl TableInfo {
         lsi: &SecondaryIndex,
         conditions: &HashMap<String, ConditionInfo>,
     ) -> Option<QueryType> {
-        let hash_condition = conditions.get(&lsi.hash_key)?;
-
-        if let Some(hash_key_condition) = hash_condition.to_key_condition() {
-            let range_key_condition = if let Some(ref range_key) = lsi.range_key {
-                conditions
-                    .get(range_key)
-                    .and_then(ConditionInfo::to_key_condition)
-            } else {
-                None
-            };
-
-            return Some(QueryType::LocalSecondaryIndexQuery {
-                index_name: lsi.name.clone(),
-                hash_key_condition,
-                range_key_condition,
-            });
-        }
+        let (hash_key_condition, range_key_condition) =
+            key_conditions(conditions, &lsi.hash_key, lsi.range_key.as_ref())?;
+        Some(QueryType::LocalSecondaryIndexQuery {
+            index_name: lsi.name.clone(),
+            hash_key_condition,
+            range_key_condition,
+        })
+    }
+}
 
-        None
+/// The key condition of a query on `hash_key` and `range_key`, if the
+/// conditions allow one: the hash key pinned by equality, and the range key
+/// either unconstrained or constrained by exactly one key condition. A query's
+/// filter can't name its key attributes, so any other condition on a key
+/// rules the query out.
+fn key_conditions(
+    conditions: &HashMap<String, ConditionInfo>,
+    hash_key: &str,
+    range_key: Option<&String>,
+) -> Option<(KeyCondition, Option<KeyCondition>)> {
+    let hash_key_condition = conditions.get(hash_key)?.to_key_condition()?;
+    if !matches!(hash_key_condition.condition, KeyConditionType::Equal(_)) {
+        return None;
     }
+    let range_key_condition = match range_key.and_then(|range_key| conditions.get(range_key)) {
+        Some(condition) => Some(condition.to_key_condition()?),
+        None => None,
+    };
+    Some((hash_key_condition, range_key_condition))
 }
 
 #[derive(Debug, Clone)]
 struct ConditionInfo {
     attribute_name: String,
     /// The resolved key condition (already includes both BETWEEN bounds and
-    /// begins_with), so downstream just wraps it.
-    condition: KeyConditionType,
+    /// begins_with), so downstream just wraps it. `None` when the attribute
+    /// is constrained in a way no single key condition expresses: `<>`, a
+    /// function other than begins_with, or more than one condition.
+    condition: Option<KeyConditionType>,
 }
 
 impl ConditionInfo {
     fn to_key_condition(&self) -> Option<KeyCondition> {
         Some(KeyCondition {
             attribute_name: self.attribute_name.clone(),
-            condition: self.condition.clone(),
+            condition: self.condition.clone()?,
         })
     }
 }
 
+/// Record a condition on `attr_name`; a second condition on the same
+/// attribute leaves it without a usable key condition.
+fn record_condition(
+    conditions: &mut HashMap<String, ConditionInfo>,
+    attr_name: &str,
+    condition: Option<KeyConditionType>,
+) {
+    conditions
+        .entry(attr_name.to_string())
+        .and_modify(|info| info.condition = None)
+        .or_insert_with(|| ConditionInfo {
+            attribute_name: attr_name.to_string(),
+            condition,
+        });
+}
+
 /// Map a comparison operator + operand to a key condition type (None for the
 /// not-equal operator or a non-value operand, which can't key a query).
 fn comparison_key_condition(operator: &Comparator, operand: &Operand) -> Option<KeyConditionType> {
@@ -366,16 +374,12 @@ fn extract_conditions_recursive(
             operator,
             right,
         } => {
-            if let Operand::Path(attr_name) = left
-                && let Some(condition) = comparison_key_condition(operator, right)
-            {
-                conditions.insert(
-                    attr_name.clone(),
-                    ConditionInfo {
-                        attribute_name: attr_name.clone(),
-                        condition,
-                    },
-                );
+            if let Operand::Path(attr_name) | Operand::Size(attr_name) = left {
+                let condition = match left {
+                    Operand::Path(_) => comparison_key_condition(operator, right),
+                    _ => None,
+                };
+                record_condition(conditions, attr_name, condition);
             }
         }
         DynamoExpression::Between {
@@ -383,39 +387,35 @@ fn extract_conditions_recursive(
             lower,
             upper,
         } => {
-            if let Operand::Path(attr_name) = operand
-                && let (Some(lower), Some(upper)) = (
+            if let Operand::Path(attr_name) | Operand::Size(attr_name) = operand {
+                let condition = match (
+                    operand,
                     operand_to_attribute_value(lower),
                     operand_to_attribute_value(upper),
-                )
-            {
-                conditions.insert(
-                    attr_name.clone(),
-                    ConditionInfo {
-                        attribute_name: attr_name.clone(),
-                        condition: KeyConditionType::Between(lower, upper),
-                    },
-                );
+                ) {
+                    (Operand::Path(_), Some(lower), Some(upper)) => {
+                        Some(KeyConditionType::Between(lower, upper))
+                    }
+                    _ => None,
+                };
+                record_condition(conditions, attr_name, condition);
             }
         }
         DynamoExpression::And(left, right) => {
             extract_conditions_recursive(left, conditions)?;
             extract_conditions_recursive(right, conditions)?;
         }
+        DynamoExpression::Parentheses(inner) => extract_conditions_recursive(inner, conditions)?,
         DynamoExpression::Function { name, args } => {
             // begins_with(path, prefix) is a valid sort-key key condition.
-            if matches!(name, crate::expr::FunctionName::BeginsWith)
-                && args.len() == 2
-                && let (Operand::Path(attr_name), prefix_operand) = (&args[0], &args[1])
-                && let Some(prefix) = operand_to_attribute_value(prefix_operand)
-            {
-                conditions.insert(
-                    attr_name.clone(),
-                    ConditionInfo {
-                        attribute_name: attr_name.clone(),
-                        condition: KeyConditionType::BeginsWith(prefix),
-                    },
-                );
+            if let Some(Operand::Path(attr_name)) = args.first() {
+                let condition = match (name, args.get(1)) {
+                    (crate::expr::FunctionName::BeginsWith, Some(prefix)) if args.len() == 2 => {
+                        operand_to_attribute_value(prefix).map(KeyConditionType::BeginsWith)
+                    }
+                    _ => None,
+                };
+                record_condition(conditions, attr_name, condition);
             }
         }
         _ => {
@@ -429,9 +429,154 @@ fn extract_conditions_recursive(
 fn operand_to_attribute_value(operand: &Operand) -> Option<AttributeValue> {
     match operand {
         Operand::Value(s) => Some(AttributeValue::S(s.clone())),
-        Operand::Number(n) => Some(AttributeValue::N(n.to_string())),
+        Operand::Number(n) => Some(AttributeValue::N(n.text().to_string())),
         Operand::Boolean(b) => Some(AttributeValue::Bool(*b)),
         Operand::Null => Some(AttributeValue::Null(true)),
-        Operand::Path(_) => None, // Path references can't be converted to values
+        // Path references can't be converted to values
+        Operand::Path(_) | Operand::Size(_) => None,
+        // Only seen when predicting the plan of a query whose parameters
+        // aren't bound yet; any value gives the same plan.
+        Operand::Placeholder(name) => Some(AttributeValue::S(format!(":{name}"))),
+    }
+}
+
+/// The declared types of the table's key attributes, its indexes' included.
+pub fn key_attribute_types(table_desc: &TableDescription) -> HashMap<String, ScalarAttributeType> {
+    table_desc
+        .attribute_definitions()
+        .iter()
+        .map(|definition| {
+            (
+                definition.attribute_name().to_string(),
+                definition.attribute_type().clone(),
+            )
+        })
+        .collect()
+}
+
+/// `expr` with the literals compared against key attributes written as the
+/// attribute's declared type: `pk = 123` on a string key looks for `"123"`,
+/// and `pk = "123"` on a number key for `123`. The literal's own type is only
+/// a guess, and a key condition of the wrong type matches nothing. A string
+/// is only taken as a number when it reads back the same, so `"007"` stays a
+/// string (and DynamoDB rejects it) instead of quietly becoming `7`.
+pub fn coerce_key_values(
+    expr: &DynamoExpression,
+    types: &HashMap<String, ScalarAttributeType>,
+) -> DynamoExpression {
+    let typed = |path: &Operand, operand: &Operand| match path {
+        Operand::Path(name) => types
+            .get(name)
+            .map_or_else(|| operand.clone(), |ty| coerce_operand(operand, ty)),
+        _ => operand.clone(),
+    };
+    match expr {
+        DynamoExpression::Comparison {
+            left,
+            operator,
+            right,
+        } => DynamoExpression::Comparison {
+            left: typed(right, left),
+            operator: operator.clone(),
+            right: typed(left, right),
+        },
+        DynamoExpression::Between {
+            operand,
+            lower,
+            upper,
+        } => DynamoExpression::Between {
+            operand: operand.clone(),
+            lower: typed(operand, lower),
+            upper: typed(operand, upper),
+        },
+        DynamoExpression::In { operand, values } => DynamoExpression::In {
+            operand: operand.clone(),
+            values: values.iter().map(|value| typed(operand, value)).collect(),
+        },
+        DynamoExpression::Function {
+            name: FunctionName::BeginsWith,
+            args,
+        } if args.len() == 2 => DynamoExpression::Function {
+            name: FunctionName::BeginsWith,
+            args: vec![args[0].clone(), typed(&args[0], &args[1])],
+        },
+        DynamoExpression::Function { .. } => expr.clone(),
+        DynamoExpression::And(left, right) => DynamoExpression::And(
+            Box::new(coerce_key_values(left, types)),
+            Box::new(coerce_key_values(right, types)),
+        ),
+        DynamoExpression::Or(left, right) => DynamoExpression::Or(
+            Box::new(coerce_key_values(left, types)),
+            Box::new(coerce_key_values(right, types)),
+        ),
+        DynamoExpression::Not(inner) => {
+            DynamoExpression::Not(Box::new(coerce_key_values(inner, types)))
+        }
+        DynamoExpression::Parentheses(inner) => {
+            DynamoExpression::Parentheses(Box::new(coerce_key_values(inner, types)))
+        }
+    }
+}
+
+fn coerce_operand(operand: &Operand, ty: &ScalarAttributeType) -> Operand {
+    match (ty, operand) {
+        (ScalarAttributeType::S, Operand::Number(number)) => {
+            Operand::Value(number.text().to_string())
+        }
+        (ScalarAttributeType::S, Operand::Boolean(value)) => Operand::Value(value.to_string()),
+        (ScalarAttributeType::N, Operand::Value(text)) => {
+            NumberLiteral::parse(text.trim()).map_or_else(|| operand.clone(), Operand::Number)
+        }
+        _ => operand.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::ScalarAttributeType;
+
+    use super::coerce_key_values;
+    use crate::expr::{DynamoExpression, Operand, parse_dynamo_expression};
+
+    #[test]
+    fn key_literals_take_the_declared_type() {
+        let types = HashMap::from([
+            ("pk".to_string(), ScalarAttributeType::S),
+            ("version".to_string(), ScalarAttributeType::N),
+        ]);
+        let coerce =
+            |text: &str| coerce_key_values(&parse_dynamo_expression(text).unwrap(), &types);
+        assert_eq!(
+            coerce("pk = 123 AND version BETWEEN \"1\" AND \"10\""),
+            parse_dynamo_expression("pk = \"123\" AND version BETWEEN 1 AND 10").unwrap()
+        );
+        assert_eq!(
+            coerce("begins_with(pk, 12) OR pk IN (1, \"2\")"),
+            parse_dynamo_expression("begins_with(pk, \"12\") OR pk IN (\"1\", \"2\")").unwrap()
+        );
+        assert_eq!(
+            coerce("version = \"007\" AND total = \"5\" AND version = \"v1\""),
+            parse_dynamo_expression("version = 007 AND total = \"5\" AND version = \"v1\"")
+                .unwrap()
+        );
+
+        // Long IDs keep every digit, whichever way they're coerced.
+        assert_eq!(
+            coerce("pk = 12345678901234567890 AND version = \"98765432109876543210\""),
+            parse_dynamo_expression(
+                "pk = \"12345678901234567890\" AND version = 98765432109876543210"
+            )
+            .unwrap()
+        );
+        let DynamoExpression::Comparison {
+            right: Operand::Value(id),
+            ..
+        } = coerce("pk = 00012345678901234567")
+        else {
+            panic!("expected a string comparison");
+        };
+        assert_eq!(id, "00012345678901234567");
     }
 }
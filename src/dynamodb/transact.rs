@@ -0,0 +1,132 @@
+//! `TransactWriteItems` requests that apply several writes, possibly across
+//! tables, all or nothing.
+
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsError;
+use aws_sdk_dynamodb::types::{CancellationReason, Delete, Put, TransactWriteItem, Update};
+use rand::{Rng, distributions::Alphanumeric};
+
+use super::convert::attribute_map_from_item;
+use super::update_item::update_expression;
+use super::{format_sdk_error, send_dynamo_request};
+use crate::core::query::{TransactOutcome, TransactWrite};
+
+/// Most writes DynamoDB accepts in one transaction.
+pub const MAX_TRANSACT_WRITES: usize = 100;
+
+/// Length of the idempotency token; DynamoDB accepts up to 36 characters.
+const REQUEST_TOKEN_LEN: usize = 36;
+
+/// Apply `writes` in one transaction. A cancellation is reported with each
+/// write's reason rather than as an error.
+pub async fn transact_write(
+    client: Client,
+    writes: &[TransactWrite],
+) -> Result<TransactOutcome, String> {
+    if writes.is_empty() {
+        return Ok(TransactOutcome::Committed);
+    }
+    if writes.len() > MAX_TRANSACT_WRITES {
+        return Err(format!(
+            "A transaction holds at most {MAX_TRANSACT_WRITES} writes, got {}",
+            writes.len()
+        ));
+    }
+    let items = writes
+        .iter()
+        .map(transact_item)
+        .collect::<Result<Vec<_>, _>>()?;
+    // One token for every attempt, so a retry after an attempt that committed
+    // but timed out is recognised rather than applied twice.
+    let token = request_token();
+    let span = tracing::trace_span!("TransactWriteItems", writes = writes.len());
+    let result = send_dynamo_request(
+        span,
+        || {
+            client
+                .transact_write_items()
+                .set_transact_items(Some(items.clone()))
+                .client_request_token(token.clone())
+                .send()
+        },
+        format_sdk_error,
+    )
+    .await;
+    match result {
+        Ok(_) => Ok(TransactOutcome::Committed),
+        Err(err) => match err.as_service_error() {
+            Some(TransactWriteItemsError::TransactionCanceledException(canceled)) => {
+                Ok(TransactOutcome::Canceled {
+                    reasons: canceled
+                        .cancellation_reasons()
+                        .iter()
+                        .map(cancellation_reason)
+                        .collect(),
+                })
+            }
+            _ => Err(format_sdk_error(&err)),
+        },
+    }
+}
+
+fn request_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(REQUEST_TOKEN_LEN)
+        .map(char::from)
+        .collect()
+}
+
+fn transact_item(write: &TransactWrite) -> Result<TransactWriteItem, String> {
+    let item = match write {
+        TransactWrite::Put { collection, item } => TransactWriteItem::builder().put(
+            Put::builder()
+                .table_name(collection)
+                .set_item(Some(attribute_map_from_item(item)))
+                .build()
+                .map_err(|err| err.to_string())?,
+        ),
+        TransactWrite::Update {
+            collection,
+            key,
+            update,
+        } => {
+            if update.is_empty() {
+                return Err(format!("An update in {collection} changes nothing"));
+            }
+            let expression = update_expression(&key.0, update)?;
+            expression.check_limits()?;
+            TransactWriteItem::builder().update(
+                Update::builder()
+                    .table_name(collection)
+                    .set_key(Some(attribute_map_from_item(&key.0)))
+                    .update_expression(expression.expression)
+                    .set_condition_expression(expression.condition)
+                    .set_expression_attribute_names(Some(expression.names))
+                    .set_expression_attribute_values(
+                        (!expression.values.is_empty()).then_some(expression.values),
+                    )
+                    .build()
+                    .map_err(|err| err.to_string())?,
+            )
+        }
+        TransactWrite::Delete { collection, key } => TransactWriteItem::builder().delete(
+            Delete::builder()
+                .table_name(collection)
+                .set_key(Some(attribute_map_from_item(&key.0)))
+                .build()
+                .map_err(|err| err.to_string())?,
+        ),
+    };
+    Ok(item.build())
+}
+
+/// Why one write held up the transaction; `None` when it didn't (DynamoDB
+/// reports the code `None` for those).
+fn cancellation_reason(reason: &CancellationReason) -> Option<String> {
+    let code = reason.code().filter(|code| *code != "None")?;
+    Some(match reason.message().map(str::trim) {
+        Some(message) if !message.is_empty() => format!("{code}: {message}"),
+        _ => code.to_string(),
+    })
+}
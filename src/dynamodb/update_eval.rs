@@ -0,0 +1,382 @@
+//! Evaluating a parsed update expression against an item, the way DynamoDB
+//! would, so bulk updates can be previewed before they're sent.
+//!
+//! Every value is read from the item as it was before the update, and the
+//! parser has already ruled out overlapping paths, so the actions can be
+//! applied one after the other.
+
+use crate::core::value::{Item, Number, Value};
+use crate::expr::{Operand, PathStep, UpdateExpression, UpdateValue, path_steps};
+
+/// `item` as it reads after `update`.
+pub fn apply_update_expression(update: &UpdateExpression, item: &Item) -> Result<Item, String> {
+    let mut writes = Vec::new();
+    let mut removes = Vec::new();
+    for (path, value) in &update.set {
+        writes.push((path_steps(path)?, evaluate(value, item)?));
+    }
+    for (path, value) in &update.add {
+        let steps = path_steps(path)?;
+        let added = match (value_at(item, &steps), added_value(value, item)?) {
+            (None, added) => added,
+            (Some(Value::Num(current)), Value::Num(added)) => {
+                Value::Num(arithmetic(current, &added, false)?)
+            }
+            (Some(current), added) => union(current, added)
+                .ok_or_else(|| format!("ADD needs a number or a set of the same type at {path}"))?,
+        };
+        writes.push((steps, added));
+    }
+    for (path, value) in &update.delete {
+        let steps = path_steps(path)?;
+        let Some(current) = value_at(item, &steps) else {
+            continue;
+        };
+        match difference(current, &added_value(value, item)?) {
+            Some(rest) if set_len(&rest) == 0 => removes.push(steps),
+            Some(rest) => writes.push((steps, rest)),
+            None => return Err(format!("DELETE needs a set of the same type at {path}")),
+        }
+    }
+    for path in &update.remove {
+        removes.push(path_steps(path)?);
+    }
+
+    let mut updated = item.clone();
+    for (steps, value) in writes {
+        write(&mut updated, &steps, value)?;
+    }
+    // Later list indexes first, so removing one doesn't shift the others.
+    removes.sort_unstable_by(|a, b| b.cmp(a));
+    for steps in removes {
+        remove(&mut updated, &steps);
+    }
+    Ok(updated)
+}
+
+/// The value `value` evaluates to against `item`.
+pub(crate) fn evaluate(value: &UpdateValue, item: &Item) -> Result<Value, String> {
+    match value {
+        UpdateValue::Operand(Operand::Path(path)) => path_value(item, path)?
+            .cloned()
+            .ok_or_else(|| format!("{path} isn't set on every item")),
+        UpdateValue::Operand(operand) => Ok(operand_value(operand)),
+        UpdateValue::List(elements) => {
+            Ok(Value::List(elements.iter().map(operand_value).collect()))
+        }
+        UpdateValue::Plus(left, right) | UpdateValue::Minus(left, right) => {
+            let subtract = matches!(value, UpdateValue::Minus(..));
+            match (evaluate(left, item)?, evaluate(right, item)?) {
+                (Value::Num(left), Value::Num(right)) => {
+                    Ok(Value::Num(arithmetic(&left, &right, subtract)?))
+                }
+                _ => Err("+ and - only work on numbers".to_string()),
+            }
+        }
+        UpdateValue::ListAppend(first, second) => {
+            match (evaluate(first, item)?, evaluate(second, item)?) {
+                (Value::List(mut first), Value::List(second)) => {
+                    first.extend(second);
+                    Ok(Value::List(first))
+                }
+                _ => Err("list_append only works on lists".to_string()),
+            }
+        }
+        UpdateValue::IfNotExists(path, fallback) => match path_value(item, path)? {
+            Some(value) => Ok(value.clone()),
+            None => evaluate(fallback, item),
+        },
+    }
+}
+
+/// A literal from an update expression as a stored value.
+fn operand_value(operand: &Operand) -> Value {
+    match operand {
+        Operand::Value(text) | Operand::Path(text) | Operand::Placeholder(text) => {
+            Value::Str(text.clone())
+        }
+        Operand::Number(number) => Value::Num(Number::new(number.text())),
+        Operand::Boolean(value) => Value::Bool(*value),
+        Operand::Null => Value::Null,
+        // Update values are literals; `size()` only appears in conditions.
+        Operand::Size(path) => Value::Str(format!("size({path})")),
+    }
+}
+
+/// The value of an `ADD` or `DELETE`: `[..]` is a number set when every
+/// element is a number and a string set otherwise.
+fn added_value(value: &UpdateValue, item: &Item) -> Result<Value, String> {
+    let UpdateValue::List(elements) = value else {
+        return evaluate(value, item);
+    };
+    if elements.iter().all(|e| matches!(e, Operand::Number(_))) {
+        let numbers = elements.iter().filter_map(|e| match e {
+            Operand::Number(number) => Some(Number::new(number.text())),
+            _ => None,
+        });
+        Ok(Value::NumberSet(dedup(numbers)))
+    } else {
+        let strings = elements.iter().map(|e| match operand_value(e) {
+            Value::Str(text) => text,
+            other => format!("{other:?}"),
+        });
+        Ok(Value::StringSet(dedup(strings)))
+    }
+}
+
+fn dedup<T: PartialEq>(values: impl Iterator<Item = T>) -> Vec<T> {
+    let mut out = Vec::new();
+    for value in values {
+        if !out.contains(&value) {
+            out.push(value);
+        }
+    }
+    out
+}
+
+fn union(current: &Value, added: Value) -> Option<Value> {
+    match (current, added) {
+        (Value::StringSet(current), Value::StringSet(added)) => Some(Value::StringSet(dedup(
+            current.iter().cloned().chain(added),
+        ))),
+        (Value::NumberSet(current), Value::NumberSet(added)) => Some(Value::NumberSet(dedup(
+            current.iter().cloned().chain(added),
+        ))),
+        (Value::BytesSet(current), Value::BytesSet(added)) => {
+            Some(Value::BytesSet(dedup(current.iter().cloned().chain(added))))
+        }
+        _ => None,
+    }
+}
+
+fn difference(current: &Value, taken: &Value) -> Option<Value> {
+    match (current, taken) {
+        (Value::StringSet(current), Value::StringSet(taken)) => Some(Value::StringSet(
+            current
+                .iter()
+                .filter(|e| !taken.contains(e))
+                .cloned()
+                .collect(),
+        )),
+        (Value::NumberSet(current), Value::NumberSet(taken)) => Some(Value::NumberSet(
+            current
+                .iter()
+                .filter(|e| !taken.contains(e))
+                .cloned()
+                .collect(),
+        )),
+        (Value::BytesSet(current), Value::BytesSet(taken)) => Some(Value::BytesSet(
+            current
+                .iter()
+                .filter(|e| !taken.contains(e))
+                .cloned()
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+fn set_len(value: &Value) -> usize {
+    match value {
+        Value::StringSet(set) => set.len(),
+        Value::NumberSet(set) => set.len(),
+        Value::BytesSet(set) => set.len(),
+        _ => 1,
+    }
+}
+
+/// `left + right` or `left - right`, exactly for integers and as `f64`
+/// otherwise.
+fn arithmetic(left: &Number, right: &Number, subtract: bool) -> Result<Number, String> {
+    let integers = left
+        .as_str()
+        .parse::<i128>()
+        .ok()
+        .zip(right.as_str().parse::<i128>().ok());
+    if let Some((left, right)) = integers {
+        let result = if subtract {
+            left.checked_sub(right)
+        } else {
+            left.checked_add(right)
+        };
+        if let Some(result) = result {
+            return Ok(Number::new(result.to_string()));
+        }
+    }
+    match (left.as_f64(), right.as_f64()) {
+        (Some(left), Some(right)) => Ok(Number::new(
+            if subtract { left - right } else { left + right }.to_string(),
+        )),
+        _ => Err(format!("{left} and {right} aren't both numbers")),
+    }
+}
+
+fn path_value<'a>(item: &'a Item, path: &str) -> Result<Option<&'a Value>, String> {
+    Ok(value_at(item, &path_steps(path)?))
+}
+
+fn value_at<'a>(item: &'a Item, steps: &[PathStep]) -> Option<&'a Value> {
+    let (PathStep::Name(first), rest) = steps.split_first()? else {
+        return None;
+    };
+    let mut value = item.get(first)?;
+    for step in rest {
+        value = match (step, value) {
+            (PathStep::Name(name), Value::Map(map)) => map.get(name)?,
+            (PathStep::Index(index), Value::List(list)) => list.get(*index)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// The map or list holding the last step of `steps`, if the path leads to
+/// one.
+fn parent_mut<'a>(item: &'a mut Item, steps: &[PathStep]) -> Option<&'a mut Value> {
+    let (PathStep::Name(first), rest) = steps.split_first()? else {
+        return None;
+    };
+    let mut value = item.get_mut(first)?;
+    for step in rest {
+        value = match (step, value) {
+            (PathStep::Name(name), Value::Map(map)) => map.get_mut(name)?,
+            (PathStep::Index(index), Value::List(list)) => list.get_mut(*index)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn write(item: &mut Item, steps: &[PathStep], value: Value) -> Result<(), String> {
+    let Some((last, parents)) = steps.split_last() else {
+        return Ok(());
+    };
+    if parents.is_empty() {
+        if let PathStep::Name(name) = last {
+            item.insert(name.clone(), value);
+        }
+        return Ok(());
+    }
+    match (parent_mut(item, parents), last) {
+        (Some(Value::Map(map)), PathStep::Name(name)) => {
+            map.insert(name.clone(), value);
+        }
+        (Some(Value::List(list)), PathStep::Index(index)) => match list.get_mut(*index) {
+            Some(slot) => *slot = value,
+            // DynamoDB appends when the index is past the end.
+            None => list.push(value),
+        },
+        _ => return Err("the document path doesn't exist on every item".to_string()),
+    }
+    Ok(())
+}
+
+fn remove(item: &mut Item, steps: &[PathStep]) {
+    let Some((last, parents)) = steps.split_last() else {
+        return;
+    };
+    if parents.is_empty() {
+        if let PathStep::Name(name) = last {
+            item.shift_remove(name);
+        }
+        return;
+    }
+    match (parent_mut(item, parents), last) {
+        (Some(Value::Map(map)), PathStep::Name(name)) => {
+            map.shift_remove(name);
+        }
+        (Some(Value::List(list)), PathStep::Index(index)) if *index < list.len() => {
+            list.remove(*index);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_update_expression;
+    use crate::core::value::{Item, Number, Value};
+    use crate::expr::parse_update_expression;
+
+    fn str(text: &str) -> Value {
+        Value::Str(text.to_string())
+    }
+
+    fn num(text: &str) -> Value {
+        Value::Num(Number::new(text))
+    }
+
+    fn apply(expression: &str, item: &Item) -> Result<Item, String> {
+        apply_update_expression(&parse_update_expression(expression).unwrap(), item)
+    }
+
+    fn item() -> Item {
+        let address: Item = [("city".to_string(), str("Lyon"))].into_iter().collect();
+        [
+            ("pk".to_string(), str("a")),
+            ("n".to_string(), num("12345678901234567890")),
+            ("price".to_string(), num("1.5")),
+            (
+                "tags".to_string(),
+                Value::List(vec![str("x"), str("y"), str("z")]),
+            ),
+            (
+                "labels".to_string(),
+                Value::StringSet(vec!["old".to_string()]),
+            ),
+            ("address".to_string(), Value::Map(address)),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn evaluates_against_the_original_item() {
+        let updated = apply(
+            "SET n = n + 1, price = price - 1, address.city = Paris, \
+             tags = list_append(tags, [w]), seen = if_not_exists(seen, 0) \
+             ADD labels [new] DELETE ids [1]",
+            &item(),
+        )
+        .unwrap();
+        assert_eq!(updated["n"], num("12345678901234567891"));
+        assert_eq!(updated["price"], num("0.5"));
+        let Value::Map(address) = &updated["address"] else {
+            panic!("address isn't a map");
+        };
+        assert_eq!(address["city"], str("Paris"));
+        assert_eq!(
+            updated["tags"],
+            Value::List(vec![str("x"), str("y"), str("z"), str("w")])
+        );
+        assert_eq!(updated["seen"], num("0"));
+        assert_eq!(
+            updated["labels"],
+            Value::StringSet(vec!["old".to_string(), "new".to_string()])
+        );
+        assert!(!updated.contains_key("ids"));
+    }
+
+    #[test]
+    fn removes_list_elements_and_emptied_sets() {
+        let updated = apply("REMOVE tags[0], tags[2] DELETE labels [old]", &item()).unwrap();
+        assert_eq!(updated["tags"], Value::List(vec![str("y")]));
+        assert!(!updated.contains_key("labels"));
+
+        // A past-the-end index appends, as DynamoDB does.
+        let updated = apply("SET tags[9] = w", &item()).unwrap();
+        assert_eq!(
+            updated["tags"],
+            Value::List(vec![str("x"), str("y"), str("z"), str("w")])
+        );
+    }
+
+    #[test]
+    fn reports_what_dynamodb_would_refuse() {
+        assert!(apply("SET missing.city = x", &item()).is_err());
+        assert!(apply("SET pk = pk + 1", &item()).is_err());
+        assert!(apply("ADD labels 1", &item()).is_err());
+        assert!(apply("SET n = other", &item()).is_ok());
+        assert!(apply("SET n = nope + 1", &item()).is_err());
+    }
+}
@@ -0,0 +1,359 @@
+//! `UpdateItem` requests that change individual attributes of an existing
+//! item.
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::types::AttributeValue;
+
+use super::convert::{attribute_map_from_item, value_to_attribute_value};
+use super::{format_sdk_error, send_dynamo_request};
+use crate::core::query::ItemUpdate;
+use crate::core::value::Item;
+use crate::expr::{self, Operand, PathStep, UpdateValue, path_steps};
+
+/// An update expression with its placeholder maps. Attribute names always go
+/// through `#a{n}` placeholders so reserved words and odd characters are safe;
+/// a document path gets one for each name along it, with its `[n]` indexes
+/// kept as they are.
+#[derive(Debug, Default, PartialEq)]
+pub struct UpdateExpression {
+    pub expression: String,
+    pub condition: Option<String>,
+    pub names: HashMap<String, String>,
+    pub values: HashMap<String, AttributeValue>,
+}
+
+impl UpdateExpression {
+    /// A typed update expression (see [`parse_update_expression`]) with its
+    /// attribute names and values moved into placeholders, for `UpdateItem`.
+    ///
+    /// [`parse_update_expression`]: crate::expr::parse_update_expression
+    pub fn from_parsed(update: &expr::UpdateExpression) -> Self {
+        let mut out = Self::default();
+        let mut clauses = Vec::new();
+        if !update.set.is_empty() {
+            let assignments: Vec<String> = update
+                .set
+                .iter()
+                .map(|(path, value)| {
+                    let path = out.path(path);
+                    format!("{path} = {}", out.update_value(value, false))
+                })
+                .collect();
+            clauses.push(format!("SET {}", assignments.join(", ")));
+        }
+        if !update.remove.is_empty() {
+            let paths: Vec<String> = update.remove.iter().map(|path| out.path(path)).collect();
+            clauses.push(format!("REMOVE {}", paths.join(", ")));
+        }
+        for (keyword, actions) in [("ADD", &update.add), ("DELETE", &update.delete)] {
+            if actions.is_empty() {
+                continue;
+            }
+            let actions: Vec<String> = actions
+                .iter()
+                .map(|(path, value)| {
+                    let path = out.path(path);
+                    format!("{path} {}", out.update_value(value, true))
+                })
+                .collect();
+            clauses.push(format!("{keyword} {}", actions.join(", ")));
+        }
+        out.expression = clauses.join(" ");
+        out
+    }
+
+    /// Whether the update and its condition are within DynamoDB's limits.
+    pub fn check_limits(&self) -> Result<(), String> {
+        super::check_expression_limits(
+            &[
+                ("update", Some(&self.expression)),
+                ("condition", self.condition.as_ref()),
+            ],
+            &self.names,
+            &self.values,
+        )
+    }
+
+    /// `value` as expression text. `[...]` is a set when `as_set`, a list
+    /// otherwise.
+    fn update_value(&mut self, value: &UpdateValue, as_set: bool) -> String {
+        match value {
+            UpdateValue::Operand(Operand::Path(path)) => self.path(path),
+            UpdateValue::Operand(operand) => self.value(operand_attribute_value(operand)),
+            UpdateValue::List(elements) => {
+                let value = if !as_set {
+                    AttributeValue::L(elements.iter().map(operand_attribute_value).collect())
+                } else if elements.iter().all(|e| matches!(e, Operand::Number(_))) {
+                    AttributeValue::Ns(elements.iter().map(operand_text).collect())
+                } else {
+                    AttributeValue::Ss(elements.iter().map(operand_text).collect())
+                };
+                self.value(value)
+            }
+            UpdateValue::Plus(left, right) => format!(
+                "{} + {}",
+                self.update_value(left, as_set),
+                self.update_value(right, as_set)
+            ),
+            UpdateValue::Minus(left, right) => format!(
+                "{} - {}",
+                self.update_value(left, as_set),
+                self.update_value(right, as_set)
+            ),
+            UpdateValue::ListAppend(first, second) => format!(
+                "list_append({}, {})",
+                self.update_value(first, as_set),
+                self.update_value(second, as_set)
+            ),
+            UpdateValue::IfNotExists(path, fallback) => {
+                let path = self.path(path);
+                format!(
+                    "if_not_exists({path}, {})",
+                    self.update_value(fallback, as_set)
+                )
+            }
+        }
+    }
+
+    /// A document path as written in an update expression, e.g.
+    /// `#a0.#a1[2]` for `address.lines[2]`.
+    fn path(&mut self, path: &str) -> String {
+        // Parsed paths always split; anything else is taken as one name.
+        let steps = path_steps(path).unwrap_or_else(|_| vec![PathStep::Name(path.to_string())]);
+        let mut text = String::new();
+        for step in steps {
+            match step {
+                PathStep::Name(name) => {
+                    if !text.is_empty() {
+                        text.push('.');
+                    }
+                    text.push_str(&self.name(&name));
+                }
+                PathStep::Index(index) => text.push_str(&format!("[{index}]")),
+            }
+        }
+        text
+    }
+
+    fn name(&mut self, name: &str) -> String {
+        let placeholder = format!("#a{}", self.names.len());
+        self.names.insert(placeholder.clone(), name.to_string());
+        placeholder
+    }
+
+    /// UpdateItem creates missing items; require that the item still exists
+    /// so an update doesn't resurrect one deleted in the meantime.
+    fn require_item(&mut self, key: &Item) {
+        if let Some(key_name) = key.keys().next() {
+            let name = self.name(key_name);
+            self.condition = Some(format!("attribute_exists({name})"));
+        }
+    }
+
+    fn value(&mut self, value: AttributeValue) -> String {
+        let placeholder = format!(":v{}", self.values.len());
+        self.values.insert(placeholder.clone(), value);
+        placeholder
+    }
+}
+
+fn operand_attribute_value(operand: &Operand) -> AttributeValue {
+    match operand {
+        Operand::Number(number) => AttributeValue::N(number.text().to_string()),
+        Operand::Boolean(value) => AttributeValue::Bool(*value),
+        Operand::Null => AttributeValue::Null(true),
+        other => AttributeValue::S(operand_text(other)),
+    }
+}
+
+fn operand_text(operand: &Operand) -> String {
+    match operand {
+        Operand::Value(text) | Operand::Path(text) | Operand::Placeholder(text) => text.clone(),
+        Operand::Number(number) => number.text().to_string(),
+        Operand::Boolean(value) => value.to_string(),
+        Operand::Null => "null".to_string(),
+        Operand::Size(path) => format!("size({path})"),
+    }
+}
+
+/// The `UpdateItem` expression for `update`: its `expression` when it has
+/// one, or its `set` and `remove` with each name taken whole, as edits name
+/// top-level attributes.
+pub(super) fn update_expression(
+    key: &Item,
+    update: &ItemUpdate,
+) -> Result<UpdateExpression, String> {
+    if let Some(text) = &update.expression {
+        let parsed = expr::parse_update_expression(text).map_err(|err| err.to_string())?;
+        let mut out = UpdateExpression::from_parsed(&parsed);
+        out.require_item(key);
+        return Ok(out);
+    }
+    let mut out = UpdateExpression::default();
+
+    let mut clauses = Vec::new();
+    if !update.set.is_empty() {
+        let assignments: Vec<String> = update
+            .set
+            .iter()
+            .map(|(name, value)| {
+                let name = out.name(name);
+                let value = out.value(value_to_attribute_value(value));
+                format!("{name} = {value}")
+            })
+            .collect();
+        clauses.push(format!("SET {}", assignments.join(", ")));
+    }
+    if !update.remove.is_empty() {
+        let names: Vec<String> = update.remove.iter().map(|name| out.name(name)).collect();
+        clauses.push(format!("REMOVE {}", names.join(", ")));
+    }
+    out.expression = clauses.join(" ");
+    out.require_item(key);
+    Ok(out)
+}
+
+/// Apply `update` to the item with `key`. Attributes not named in the update
+/// keep whatever value they have in the table, including ones written since
+/// the item was read. Does nothing when the update is empty.
+pub async fn update_item(
+    client: Client,
+    table_name: &str,
+    key: &Item,
+    update: &ItemUpdate,
+) -> Result<(), String> {
+    if update.is_empty() {
+        return Ok(());
+    }
+    let expression = update_expression(key, update)?;
+    expression.check_limits()?;
+    let request = client
+        .update_item()
+        .table_name(table_name)
+        .set_key(Some(attribute_map_from_item(key)))
+        .update_expression(expression.expression)
+        .set_condition_expression(expression.condition)
+        .set_expression_attribute_names(Some(expression.names))
+        .set_expression_attribute_values(
+            (!expression.values.is_empty()).then_some(expression.values),
+        );
+    let span = tracing::trace_span!(
+        "UpdateItem",
+        table = %table_name,
+        set = update.set.len(),
+        remove = update.remove.len(),
+        expression = ?update.expression
+    );
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::{UpdateExpression, update_expression};
+    use crate::core::query::ItemUpdate;
+    use crate::core::schema::{KeyField, KeyRole, KeySchema, ScalarType};
+    use crate::core::value::{Item, Value};
+
+    fn item(pairs: &[(&str, Value)]) -> Item {
+        pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn update_covers_changed_and_removed_attributes() {
+        let key = KeySchema {
+            fields: vec![KeyField {
+                name: "pk".to_string(),
+                role: KeyRole::Partition,
+                ty: ScalarType::String,
+            }],
+        };
+        let original = item(&[
+            ("pk", Value::Str("a".to_string())),
+            ("name", Value::Str("old".to_string())),
+            ("count", Value::Bool(true)),
+            ("gone", Value::Null),
+        ]);
+        let edited = item(&[
+            ("pk", Value::Str("a".to_string())),
+            ("name", Value::Str("new".to_string())),
+            ("count", Value::Bool(true)),
+        ]);
+        let update = ItemUpdate::between(&original, &edited, &key).unwrap();
+        assert_eq!(update.set, item(&[("name", Value::Str("new".to_string()))]));
+        assert_eq!(update.remove, vec!["gone".to_string()]);
+
+        let mut moved = edited.clone();
+        moved.insert("pk".to_string(), Value::Str("b".to_string()));
+        assert_eq!(ItemUpdate::between(&original, &moved, &key), None);
+
+        let expression =
+            update_expression(&item(&[("pk", Value::Str("a".to_string()))]), &update).unwrap();
+        assert_eq!(expression.expression, "SET #a0 = :v0 REMOVE #a1");
+        assert_eq!(
+            expression.condition.as_deref(),
+            Some("attribute_exists(#a2)")
+        );
+        assert_eq!(expression.names["#a1"], "gone");
+        assert_eq!(expression.names["#a2"], "pk");
+        assert_eq!(
+            expression.values[":v0"],
+            AttributeValue::S("new".to_string())
+        );
+    }
+
+    #[test]
+    fn typed_updates_become_placeholder_expressions() {
+        let parsed = crate::expr::parse_update_expression(
+            "SET n = if_not_exists(n, 0) + 1, tags = list_append(tags, [a]) ADD labels [x, y] DELETE ids [1]",
+        )
+        .unwrap();
+        let expression = UpdateExpression::from_parsed(&parsed);
+        assert_eq!(
+            expression.expression,
+            "SET #a0 = if_not_exists(#a1, :v0) + :v1, #a2 = list_append(#a3, :v2) ADD #a4 :v3 DELETE #a5 :v4"
+        );
+        assert_eq!(expression.names["#a3"], "tags");
+        assert_eq!(
+            expression.values[":v2"],
+            AttributeValue::L(vec![AttributeValue::S("a".to_string())])
+        );
+        assert_eq!(
+            expression.values[":v3"],
+            AttributeValue::Ss(vec!["x".to_string(), "y".to_string()])
+        );
+        assert_eq!(
+            expression.values[":v4"],
+            AttributeValue::Ns(vec!["1".to_string()])
+        );
+        assert_eq!(expression.condition, None);
+
+        // Each name along a document path gets its own placeholder; a name
+        // in backticks is one attribute, dots and all.
+        let update = ItemUpdate {
+            expression: Some("SET address.city = x, `a.b` = y REMOVE items[1].tag".to_string()),
+            ..ItemUpdate::default()
+        };
+        let key = item(&[("pk", Value::Str("a".to_string()))]);
+        let expression = update_expression(&key, &update).unwrap();
+        assert_eq!(
+            expression.expression,
+            "SET #a0.#a1 = :v0, #a2 = :v1 REMOVE #a3[1].#a4"
+        );
+        assert_eq!(expression.names["#a1"], "city");
+        assert_eq!(expression.names["#a2"], "a.b");
+        assert_eq!(
+            expression.condition.as_deref(),
+            Some("attribute_exists(#a5)")
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! `UpdateTable` requests that change an existing table's indexes and
+//! capacity, and the `UpdateTimeToLive` request that turns on its TTL.
+
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::types::{
+    BillingMode, CreateGlobalSecondaryIndexAction, CreateReplicationGroupMemberAction,
+    DeleteGlobalSecondaryIndexAction, DeleteReplicationGroupMemberAction,
+    GlobalSecondaryIndexUpdate, ProvisionedThroughput, ReplicationGroupUpdate,
+    TimeToLiveSpecification, UpdateGlobalSecondaryIndexAction,
+};
+
+use super::create_table::GsiSpec;
+use super::{format_sdk_error, send_dynamo_request};
+use crate::core::query::CapacitySpec;
+use crate::core::schema::{CollectionDetails, Throughput};
+
+/// Add a GSI to an existing table. Provisioned tables need `throughput` for
+/// the new index; on-demand tables pass `None`. DynamoDB returns as soon as
+/// the index is `CREATING` and backfills it in the background.
+pub async fn create_gsi(
+    client: Client,
+    table_name: &str,
+    gsi: &GsiSpec,
+    throughput: Option<Throughput>,
+) -> Result<(), String> {
+    gsi.validate()?;
+    let mut action = CreateGlobalSecondaryIndexAction::builder()
+        .index_name(gsi.name.clone())
+        .set_key_schema(Some(gsi.key_schema()?))
+        .projection(gsi.projection.build_projection()?);
+    if let Some(throughput) = throughput {
+        action = action.provisioned_throughput(provisioned_throughput(throughput)?);
+    }
+    let update = GlobalSecondaryIndexUpdate::builder()
+        .create(action.build().map_err(|err| err.to_string())?)
+        .build();
+
+    let request = client
+        .update_table()
+        .table_name(table_name)
+        .set_attribute_definitions(Some(gsi.attribute_definitions()?))
+        .global_secondary_index_updates(update);
+    let span = tracing::trace_span!("UpdateTable", table = %table_name, create_index = %gsi.name);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+/// Remove a GSI from a table. The index stays `DELETING` for a while after
+/// this returns.
+pub async fn delete_gsi(client: Client, table_name: &str, index_name: &str) -> Result<(), String> {
+    let action = DeleteGlobalSecondaryIndexAction::builder()
+        .index_name(index_name)
+        .build()
+        .map_err(|err| err.to_string())?;
+    let update = GlobalSecondaryIndexUpdate::builder().delete(action).build();
+    let request = client
+        .update_table()
+        .table_name(table_name)
+        .global_secondary_index_updates(update);
+    let span = tracing::trace_span!("UpdateTable", table = %table_name, delete_index = %index_name);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+/// Expire the table's items by the epoch seconds in `attribute`.
+pub async fn enable_ttl(client: Client, table_name: &str, attribute: &str) -> Result<(), String> {
+    let specification = TimeToLiveSpecification::builder()
+        .enabled(true)
+        .attribute_name(attribute)
+        .build()
+        .map_err(|err| err.to_string())?;
+    let request = client
+        .update_time_to_live()
+        .table_name(table_name)
+        .time_to_live_specification(specification);
+    let span =
+        tracing::trace_span!("UpdateTimeToLive", table = %table_name, attribute = %attribute);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+/// Add or remove a replica region of a global table. The replica stays
+/// `CREATING` or `DELETING` for a while after this returns.
+pub async fn update_replica(
+    client: Client,
+    table_name: &str,
+    region: &str,
+    add: bool,
+) -> Result<(), String> {
+    let update = if add {
+        let action = CreateReplicationGroupMemberAction::builder()
+            .region_name(region)
+            .build()
+            .map_err(|err| err.to_string())?;
+        ReplicationGroupUpdate::builder().create(action).build()
+    } else {
+        let action = DeleteReplicationGroupMemberAction::builder()
+            .region_name(region)
+            .build()
+            .map_err(|err| err.to_string())?;
+        ReplicationGroupUpdate::builder().delete(action).build()
+    };
+    let request = client
+        .update_table()
+        .table_name(table_name)
+        .replica_updates(update);
+    let span = tracing::trace_span!("UpdateTable", table = %table_name, replica = %region, add);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+/// The parts of an `UpdateTable` capacity request that differ from the
+/// table's current settings. DynamoDB rejects updates that change nothing,
+/// so unchanged values are left out.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CapacityChanges {
+    billing_mode: Option<BillingMode>,
+    table: Option<Throughput>,
+    indexes: Vec<(String, Throughput)>,
+}
+
+fn capacity_changes(spec: &CapacitySpec, current: &CollectionDetails) -> Option<CapacityChanges> {
+    let on_demand = current.billing_mode.as_deref() == Some("PAY_PER_REQUEST");
+    let changes = match spec {
+        CapacitySpec::OnDemand if on_demand => return None,
+        CapacitySpec::OnDemand => CapacityChanges {
+            billing_mode: Some(BillingMode::PayPerRequest),
+            ..CapacityChanges::default()
+        },
+        // Switching to provisioned must set the capacity of the table and of
+        // every GSI.
+        CapacitySpec::Provisioned { table, indexes } if on_demand => CapacityChanges {
+            billing_mode: Some(BillingMode::Provisioned),
+            table: Some(*table),
+            indexes: indexes.clone(),
+        },
+        CapacitySpec::Provisioned { table, indexes } => CapacityChanges {
+            billing_mode: None,
+            table: (current.throughput != Some(*table)).then_some(*table),
+            indexes: indexes
+                .iter()
+                .filter(|(name, throughput)| {
+                    current
+                        .indexes
+                        .iter()
+                        .find(|index| &index.schema.name == name)
+                        .is_none_or(|index| index.throughput != Some(*throughput))
+                })
+                .cloned()
+                .collect(),
+        },
+    };
+    (changes != CapacityChanges::default()).then_some(changes)
+}
+
+/// Switch billing mode and/or change provisioned capacity, given the table's
+/// current details. Does nothing when the spec matches what is already set.
+pub async fn update_capacity(
+    client: Client,
+    table_name: &str,
+    spec: &CapacitySpec,
+    current: &CollectionDetails,
+) -> Result<(), String> {
+    let Some(changes) = capacity_changes(spec, current) else {
+        return Ok(());
+    };
+    let mut request = client
+        .update_table()
+        .table_name(table_name)
+        .set_billing_mode(changes.billing_mode);
+    if let Some(table) = changes.table {
+        request = request.provisioned_throughput(provisioned_throughput(table)?);
+    }
+    for (index_name, throughput) in &changes.indexes {
+        let action = UpdateGlobalSecondaryIndexAction::builder()
+            .index_name(index_name)
+            .provisioned_throughput(provisioned_throughput(*throughput)?)
+            .build()
+            .map_err(|err| err.to_string())?;
+        request = request.global_secondary_index_updates(
+            GlobalSecondaryIndexUpdate::builder().update(action).build(),
+        );
+    }
+    let span = tracing::trace_span!("UpdateTable", table = %table_name, capacity = ?spec);
+    send_dynamo_request(span, || request.clone().send(), format_sdk_error)
+        .await
+        .map(|_| ())
+        .map_err(|err| format_sdk_error(&err))
+}
+
+fn provisioned_throughput(throughput: Throughput) -> Result<ProvisionedThroughput, String> {
+    ProvisionedThroughput::builder()
+        .read_capacity_units(throughput.read)
+        .write_capacity_units(throughput.write)
+        .build()
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::types::BillingMode;
+
+    use super::{CapacityChanges, capacity_changes};
+    use crate::core::query::CapacitySpec;
+    use crate::core::schema::{
+        CollectionDetails, IndexDetails, IndexKind, IndexSchema, KeySchema, Projection, Throughput,
+    };
+
+    fn provisioned(read: i64, write: i64, index: Option<(i64, i64)>) -> CollectionDetails {
+        let mut gsi = IndexDetails::from_schema(IndexSchema {
+            name: "GSI1".to_string(),
+            kind: IndexKind::GlobalSecondary,
+            key: KeySchema::default(),
+            projection: Projection::All,
+        });
+        gsi.throughput = index.map(|(read, write)| Throughput { read, write });
+        CollectionDetails {
+            billing_mode: Some("PROVISIONED".to_string()),
+            throughput: Some(Throughput { read, write }),
+            indexes: vec![gsi],
+            ..CollectionDetails::default()
+        }
+    }
+
+    #[test]
+    fn capacity_changes_skip_unchanged_values() {
+        let current = provisioned(5, 5, Some((2, 2)));
+        let same = CapacitySpec::Provisioned {
+            table: Throughput { read: 5, write: 5 },
+            indexes: vec![("GSI1".to_string(), Throughput { read: 2, write: 2 })],
+        };
+        assert_eq!(capacity_changes(&same, &current), None);
+
+        let bump_index = CapacitySpec::Provisioned {
+            table: Throughput { read: 5, write: 5 },
+            indexes: vec![("GSI1".to_string(), Throughput { read: 4, write: 2 })],
+        };
+        assert_eq!(
+            capacity_changes(&bump_index, &current),
+            Some(CapacityChanges {
+                billing_mode: None,
+                table: None,
+                indexes: vec![("GSI1".to_string(), Throughput { read: 4, write: 2 })],
+            })
+        );
+
+        let on_demand = capacity_changes(&CapacitySpec::OnDemand, &current).unwrap();
+        assert_eq!(on_demand.billing_mode, Some(BillingMode::PayPerRequest));
+        assert_eq!(on_demand.table, None);
+    }
+
+    #[test]
+    fn switching_to_provisioned_sets_every_capacity() {
+        let mut current = provisioned(0, 0, None);
+        current.billing_mode = Some("PAY_PER_REQUEST".to_string());
+        current.throughput = None;
+        assert_eq!(capacity_changes(&CapacitySpec::OnDemand, &current), None);
+
+        let spec = CapacitySpec::Provisioned {
+            table: Throughput { read: 5, write: 5 },
+            indexes: vec![("GSI1".to_string(), Throughput { read: 1, write: 1 })],
+        };
+        let changes = capacity_changes(&spec, &current).unwrap();
+        assert_eq!(changes.billing_mode, Some(BillingMode::Provisioned));
+        assert_eq!(changes.table, Some(Throughput { read: 5, write: 5 }));
+        assert_eq!(changes.indexes.len(), 1);
+    }
+}
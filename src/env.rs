@@ -28,7 +28,9 @@ pub enum AppCommand {
     Invalidate,
     ForceRedraw,
     PushWidget(Box<dyn Widget>),
-    PopWidget,
+    /// Pop the top widget of the tab holding the given widget.
+    PopWidget(WidgetId),
+    OpenTab(Box<dyn Widget>),
     SetPopup(Box<dyn Popup>),
     DismissPopup,
     ShowToast(Toast),
@@ -80,6 +82,8 @@ pub enum ToastAction {
         label: String,
         value: String,
     },
+    /// Put back the most recently deleted item.
+    RestoreDeleted { key: char, label: String },
 }
 
 impl ToastAction {
@@ -91,15 +95,24 @@ impl ToastAction {
         }
     }
 
+    pub fn restore_deleted() -> Self {
+        Self::RestoreDeleted {
+            key: 'u',
+            label: "undo".to_string(),
+        }
+    }
+
     pub fn key(&self) -> char {
         match self {
-            ToastAction::CopyPath { key, .. } => *key,
+            ToastAction::CopyPath { key, .. } | ToastAction::RestoreDeleted { key, .. } => *key,
         }
     }
 
     pub fn label(&self) -> &str {
         match self {
-            ToastAction::CopyPath { label, .. } => label.as_str(),
+            ToastAction::CopyPath { label, .. } | ToastAction::RestoreDeleted { label, .. } => {
+                label.as_str()
+            }
         }
     }
 }
@@ -175,7 +188,12 @@ impl WidgetCtx {
     }
 
     pub fn pop_widget(&self) {
-        self.bus.command(AppCommand::PopWidget);
+        self.bus.command(AppCommand::PopWidget(self.id.clone()));
+    }
+
+    /// Open `widget` in a new tab and switch to it.
+    pub fn open_tab(&self, widget: Box<dyn Widget>) {
+        self.bus.command(AppCommand::OpenTab(widget));
     }
 
     pub fn set_popup(&self, popup: Box<dyn Popup>) {
@@ -0,0 +1,139 @@
+//! Environment tags: `environments` in the config file marks profiles and
+//! endpoint URLs as prod, staging, or dev. The tag of the current connection
+//! is shown in the title bar, and a prod tag raises every confirmation a tier
+//! (see [`crate::safety`]).
+
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+static TAGS: OnceLock<Tags> = OnceLock::new();
+/// The connection in use, kept up to date across profile switches.
+static CURRENT: Mutex<Connection> = Mutex::new(Connection {
+    endpoint: None,
+    environment: None,
+});
+
+struct Connection {
+    /// The custom endpoint or connection URL, which profile switches keep.
+    endpoint: Option<String>,
+    environment: Option<Environment>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    Prod,
+    Staging,
+    Dev,
+}
+
+impl Environment {
+    /// The environment named `name` in the config file.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "prod" => Some(Self::Prod),
+            "staging" => Some(Self::Staging),
+            "dev" => Some(Self::Dev),
+            _ => None,
+        }
+    }
+
+    /// The title bar badge.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Prod => "PROD",
+            Self::Staging => "STAGING",
+            Self::Dev => "DEV",
+        }
+    }
+}
+
+/// Profile names and endpoint URLs with their environment.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Tags(Vec<(String, Environment)>);
+
+impl Tags {
+    pub fn new(tags: Vec<(String, Environment)>) -> Self {
+        Self(tags)
+    }
+
+    /// The tag of a connection to `endpoint` (a custom endpoint or connection
+    /// URL) with `profile`. An endpoint's tag wins over its profile's.
+    pub fn lookup(&self, endpoint: Option<&str>, profile: &str) -> Option<Environment> {
+        let find = |name: &str| {
+            self.0
+                .iter()
+                .find(|(tagged, _)| tagged.trim_end_matches('/') == name.trim_end_matches('/'))
+                .map(|(_, environment)| *environment)
+        };
+        endpoint.and_then(find).or_else(|| find(profile))
+    }
+}
+
+/// Set the tags for the rest of the process. Only the first call takes effect.
+pub fn set(tags: Tags) {
+    let _ = TAGS.set(tags);
+}
+
+/// Record a new connection to `endpoint` with `profile`.
+pub fn connect(endpoint: Option<&str>, profile: Option<&str>) {
+    let mut current = CURRENT.lock().unwrap_or_else(PoisonError::into_inner);
+    current.endpoint = endpoint.map(str::to_string);
+    current.environment = lookup(endpoint, profile);
+}
+
+/// Record a switch to `profile` on the same endpoint.
+pub fn switch_profile(profile: Option<&str>) {
+    let mut current = CURRENT.lock().unwrap_or_else(PoisonError::into_inner);
+    current.environment = lookup(current.endpoint.as_deref(), profile);
+}
+
+/// The tag of the connection in use, if it has one.
+pub fn current() -> Option<Environment> {
+    CURRENT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .environment
+}
+
+/// The custom endpoint or connection URL in use, if any.
+pub fn endpoint() -> Option<String> {
+    CURRENT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .endpoint
+        .clone()
+}
+
+/// A `profile` of `None` is `AWS_PROFILE`, or else `default`.
+fn lookup(endpoint: Option<&str>, profile: Option<&str>) -> Option<Environment> {
+    let profile = profile
+        .map(str::to_string)
+        .or_else(|| std::env::var("AWS_PROFILE").ok())
+        .unwrap_or_else(|| "default".to_string());
+    TAGS.get()?.lookup(endpoint, &profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Environment, Tags};
+
+    #[test]
+    fn looks_up_endpoints_before_profiles() {
+        let tags = Tags::new(vec![
+            ("prod-admin".to_string(), Environment::Prod),
+            ("http://localhost:8000/".to_string(), Environment::Dev),
+            ("default".to_string(), Environment::Staging),
+        ]);
+        assert_eq!(tags.lookup(None, "prod-admin"), Some(Environment::Prod));
+        assert_eq!(
+            tags.lookup(Some("http://localhost:8000"), "prod-admin"),
+            Some(Environment::Dev)
+        );
+        assert_eq!(
+            tags.lookup(Some("http://localhost:9000"), "default"),
+            Some(Environment::Staging)
+        );
+        assert_eq!(tags.lookup(None, "sandbox"), None);
+        assert_eq!(Environment::parse("prod"), Some(Environment::Prod));
+        assert_eq!(Environment::parse("production"), None);
+    }
+}
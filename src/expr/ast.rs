@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use super::error::ParseError;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DynamoExpression {
     Comparison {
@@ -28,9 +32,77 @@ pub enum DynamoExpression {
 pub enum Operand {
     Path(String),
     Value(String),
-    Number(f64),
+    Number(NumberLiteral),
     Boolean(bool),
     Null,
+    /// A `:name` parameter (stored without the colon), replaced by a value
+    /// with [`DynamoExpression::bind`] before the expression runs.
+    Placeholder(String),
+    /// `size(path)`: a string's length, a binary's bytes, or how many
+    /// elements a list, map, or set holds. Compared like a number.
+    Size(String),
+}
+
+/// A number as written in the expression: its value, for what the client
+/// works out itself, and its text, which is what gets sent, so long IDs and
+/// exact decimals aren't rounded through an `f64`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberLiteral {
+    value: f64,
+    text: String,
+}
+
+impl NumberLiteral {
+    /// `text` as a number when it's written like one: digits, with an
+    /// optional sign, decimal point, and exponent.
+    pub fn parse(text: &str) -> Option<Self> {
+        if !text.chars().any(|ch| ch.is_ascii_digit())
+            || !text
+                .chars()
+                .all(|ch| ch.is_ascii_digit() || matches!(ch, '.' | '-' | '+' | 'e' | 'E'))
+        {
+            return None;
+        }
+        let value = text.parse::<f64>().ok().filter(|value| value.is_finite())?;
+        Some(Self {
+            value,
+            text: text.to_string(),
+        })
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// The number as it was written.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<f64> for NumberLiteral {
+    fn from(value: f64) -> Self {
+        Self {
+            value,
+            text: value.to_string(),
+        }
+    }
+}
+
+/// The sort-key order requested by a trailing `ORDER ASC` / `ORDER DESC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Ascending => "ASC",
+            Self::Descending => "DESC",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -66,3 +138,109 @@ pub struct KeyValue {
     pub key: String,
     pub value: Value,
 }
+
+impl DynamoExpression {
+    /// The names of the `:name` parameters in the expression, in order of
+    /// first appearance and without duplicates.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.visit_operands(&mut |operand| {
+            if let Operand::Placeholder(name) = operand
+                && !names.contains(name)
+            {
+                names.push(name.clone());
+            }
+        });
+        names
+    }
+
+    /// Replace every parameter with its value from `values`, failing on the
+    /// first parameter that has none.
+    pub fn bind(self, values: &HashMap<String, Operand>) -> Result<Self, ParseError> {
+        let mut missing = None;
+        let bound = self.map_operands(&mut |operand| match operand {
+            Operand::Placeholder(name) => {
+                if let Some(value) = values.get(&name) {
+                    value.clone()
+                } else {
+                    missing.get_or_insert(name.clone());
+                    Operand::Placeholder(name)
+                }
+            }
+            other => other,
+        });
+        match missing {
+            Some(name) => Err(ParseError::UnboundPlaceholder { name }),
+            None => Ok(bound),
+        }
+    }
+
+    fn visit_operands(&self, f: &mut impl FnMut(&Operand)) {
+        match self {
+            Self::Comparison { left, right, .. } => {
+                f(left);
+                f(right);
+            }
+            Self::Between {
+                operand,
+                lower,
+                upper,
+            } => {
+                f(operand);
+                f(lower);
+                f(upper);
+            }
+            Self::In { operand, values } => {
+                f(operand);
+                values.iter().for_each(&mut *f);
+            }
+            Self::Function { args, .. } => args.iter().for_each(f),
+            Self::And(left, right) | Self::Or(left, right) => {
+                left.visit_operands(f);
+                right.visit_operands(f);
+            }
+            Self::Not(inner) | Self::Parentheses(inner) => inner.visit_operands(f),
+        }
+    }
+
+    fn map_operands(self, f: &mut impl FnMut(Operand) -> Operand) -> Self {
+        match self {
+            Self::Comparison {
+                left,
+                operator,
+                right,
+            } => Self::Comparison {
+                left: f(left),
+                operator,
+                right: f(right),
+            },
+            Self::Between {
+                operand,
+                lower,
+                upper,
+            } => Self::Between {
+                operand: f(operand),
+                lower: f(lower),
+                upper: f(upper),
+            },
+            Self::In { operand, values } => Self::In {
+                operand: f(operand),
+                values: values.into_iter().map(&mut *f).collect(),
+            },
+            Self::Function { name, args } => Self::Function {
+                name,
+                args: args.into_iter().map(f).collect(),
+            },
+            Self::And(left, right) => Self::And(
+                Box::new(left.map_operands(f)),
+                Box::new(right.map_operands(f)),
+            ),
+            Self::Or(left, right) => Self::Or(
+                Box::new(left.map_operands(f)),
+                Box::new(right.map_operands(f)),
+            ),
+            Self::Not(inner) => Self::Not(Box::new(inner.map_operands(f))),
+            Self::Parentheses(inner) => Self::Parentheses(Box::new(inner.map_operands(f))),
+        }
+    }
+}
@@ -68,7 +68,7 @@ pub static FUNCTIONS: &[FunctionDoc] = &[
         name: "attribute_type",
         func: FunctionName::AttributeType,
         signature: "attribute_type(path, type)",
-        summary: "True when the attribute has the given DynamoDB type (S, N, B, BOOL, M, L, SS, NS, BS, NULL).",
+        summary: "True when the attribute has the given DynamoDB type (S, N, B, BOOL, M, L, SS, NS, BS, NULL, or a name like number).",
         example: "attribute_type(age, \"N\")",
         takes_type_code: true,
     },
@@ -92,7 +92,7 @@ pub static FUNCTIONS: &[FunctionDoc] = &[
         name: "size",
         func: FunctionName::Size,
         signature: "size(path)",
-        summary: "The size of the attribute (string length, or element count of a list/map/set). Use in a comparison.",
+        summary: "The size of the attribute (string length, or element count of a list/map/set). Compare it, or use it in BETWEEN or IN.",
         example: "size(items) > 0",
         takes_type_code: false,
     },
@@ -134,6 +134,11 @@ pub static KEYWORDS: &[KeywordDoc] = &[
         summary: "Null literal.",
         example: "middle_name = null",
     },
+    KeywordDoc {
+        word: "ORDER ASC / DESC",
+        summary: "At the end of a key query, the sort-key order of the results.",
+        example: "PK = \"USER#1\" ORDER DESC",
+    },
 ];
 
 pub static OPERATORS: &[OperatorDoc] = &[
@@ -7,6 +7,7 @@ pub enum ParseError {
     UnexpectedToken { token: String, position: usize },
     UnexpectedEndOfInput { position: usize },
     InvalidFunction { name: String, position: usize },
+    UnboundPlaceholder { name: String },
 }
 
 impl std::fmt::Display for ParseError {
@@ -39,6 +40,9 @@ impl std::fmt::Display for ParseError {
             ParseError::InvalidFunction { name, position } => {
                 write!(f, "Invalid function '{name}' at position {position}")
             }
+            ParseError::UnboundPlaceholder { name } => {
+                write!(f, "No value for parameter ':{name}'")
+            }
         }
     }
 }
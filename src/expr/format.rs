@@ -16,6 +16,12 @@ pub fn format_query_summary(expr: &DynamoExpression) -> String {
     format_expr(expr, 0)
 }
 
+/// Render an expression as query text that parses back to the same
+/// expression.
+pub fn format_expression(expr: &DynamoExpression) -> String {
+    format_expr(expr, 0)
+}
+
 fn contains_or_or_not(expr: &DynamoExpression) -> bool {
     use DynamoExpression::{And, Between, Comparison, Function, In, Not, Or, Parentheses};
     match expr {
@@ -159,13 +165,15 @@ fn format_expr_compact(expr: &DynamoExpression) -> String {
     }
 }
 
-fn format_operand(operand: &Operand) -> String {
+pub(super) fn format_operand(operand: &Operand) -> String {
     match operand {
         Operand::Path(path) => format_path(path),
         Operand::Value(value) => format_string(value),
-        Operand::Number(num) => format_number(*num),
+        Operand::Number(number) => number.text().to_string(),
         Operand::Boolean(value) => value.to_string(),
         Operand::Null => "null".to_string(),
+        Operand::Placeholder(name) => format!(":{name}"),
+        Operand::Size(path) => format!("size({})", format_path(path)),
     }
 }
 
@@ -196,11 +204,3 @@ fn format_path(path: &str) -> String {
 fn format_string(value: &str) -> String {
     serde_json::to_string(value).unwrap_or_else(|_| format!("\"{value}\""))
 }
-
-fn format_number(value: f64) -> String {
-    if value.fract() == 0.0 {
-        format!("{value:.0}")
-    } else {
-        value.to_string()
-    }
-}
@@ -1,3 +1,4 @@
+use super::ast::NumberLiteral;
 use super::error::ParseError;
 
 pub struct Lexer {
@@ -10,7 +11,7 @@ pub enum Token {
     Identifier(String),
     Path(String),
     String(String),
-    Number(f64),
+    Number(NumberLiteral),
     Boolean(bool),
     Null,
     Equal,
@@ -26,6 +27,8 @@ pub enum Token {
     In,
     LeftParen,
     RightParen,
+    LeftBracket,
+    RightBracket,
     Comma,
     EOF,
 }
@@ -99,15 +102,22 @@ impl Lexer {
         })
     }
 
+    /// A bare token. A `]` ends it unless it closes a `[` inside it, so list
+    /// indexes like `items[0]` stay in the path while `[1, 2]` is a list.
     pub fn read_identifier(&mut self) -> String {
         let mut result = String::new();
+        let mut open_brackets = 0usize;
         while let Some(ch) = self.current_char() {
-            if is_bare_token_char(ch) {
-                result.push(ch);
-                self.advance();
-            } else {
+            if !is_bare_token_char(ch) || (ch == ']' && open_brackets == 0) {
                 break;
             }
+            match ch {
+                '[' => open_brackets += 1,
+                ']' => open_brackets -= 1,
+                _ => {}
+            }
+            result.push(ch);
+            self.advance();
         }
         result
     }
@@ -125,6 +135,14 @@ impl Lexer {
                 self.advance();
                 Ok(Token::RightParen)
             }
+            Some('[') => {
+                self.advance();
+                Ok(Token::LeftBracket)
+            }
+            Some(']') => {
+                self.advance();
+                Ok(Token::RightBracket)
+            }
             Some(',') => {
                 self.advance();
                 Ok(Token::Comma)
@@ -220,23 +238,10 @@ fn classify_bare_token(token: &str) -> Result<Token, ParseError> {
         "TRUE" => Token::Boolean(true),
         "FALSE" => Token::Boolean(false),
         "NULL" => Token::Null,
-        _ => match parse_numeric_bare_token(token) {
+        _ => match NumberLiteral::parse(token) {
             Some(number) => Token::Number(number),
             None => Token::Identifier(token.to_string()),
         },
     };
     Ok(classified)
 }
-
-fn parse_numeric_bare_token(token: &str) -> Option<f64> {
-    if !token.chars().any(|ch| ch.is_ascii_digit()) {
-        return None;
-    }
-    if !token
-        .chars()
-        .all(|ch| ch.is_ascii_digit() || matches!(ch, '.' | '-' | '+' | 'e' | 'E'))
-    {
-        return None;
-    }
-    token.parse::<f64>().ok()
-}
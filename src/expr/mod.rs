@@ -6,9 +6,16 @@ pub mod key_value;
 pub mod lexer;
 pub mod parser;
 mod tests;
+pub mod update;
 
 pub use ast::*;
 pub use builtins::*;
 pub use error::*;
 pub use key_value::*;
-pub use parser::{parse_dynamo_expression, parse_dynamo_expression_with, parse_single_value_token};
+pub use parser::{
+    parse_dynamo_expression, parse_dynamo_expression_with, parse_single_value_token,
+    split_order_clause,
+};
+pub use update::{
+    PathStep, UpdateExpression, UpdateValue, attribute_name, parse_update_expression, path_steps,
+};
@@ -1,6 +1,7 @@
-use super::ast::{Comparator, DynamoExpression, Operand};
+use super::ast::{Comparator, DynamoExpression, FunctionName, NumberLiteral, Operand, SortOrder};
 use super::builtins::{self, Dialect};
 use super::error::ParseError;
+use super::format;
 use super::lexer::{Lexer, Token};
 
 /// Parse a query expression using the default (DynamoDB) dialect.
@@ -27,6 +28,34 @@ pub fn parse_dynamo_expression_with(
     }
 }
 
+/// Split a trailing `ORDER ASC` / `ORDER DESC` clause (any case) off a query,
+/// returning the rest of the text and the order. The clause sits outside the
+/// expression grammar so attributes named `order` or `desc` keep working.
+pub fn split_order_clause(input: &str) -> (&str, Option<SortOrder>) {
+    let trimmed = input.trim_end();
+    let Some((rest, direction)) = trimmed.rsplit_once(char::is_whitespace) else {
+        return (input, None);
+    };
+    let order = if direction.eq_ignore_ascii_case("asc") {
+        SortOrder::Ascending
+    } else if direction.eq_ignore_ascii_case("desc") {
+        SortOrder::Descending
+    } else {
+        return (input, None);
+    };
+    let rest = rest.trim_end();
+    match rest.len().checked_sub("order".len()) {
+        Some(split)
+            if rest.is_char_boundary(split)
+                && rest[split..].eq_ignore_ascii_case("order")
+                && (split == 0 || rest[..split].ends_with(char::is_whitespace)) =>
+        {
+            (rest[..split].trim_end(), Some(order))
+        }
+        _ => (input, None),
+    }
+}
+
 /// Recursive-descent parser bound to a [`Dialect`] for function recognition.
 struct Parser<'a> {
     lexer: Lexer,
@@ -113,7 +142,9 @@ impl Parser<'_> {
                 }
             }
             Token::Identifier(_) => {
-                if self.is_function_start()? {
+                // `size()` is an operand, compared like a number, not a
+                // condition of its own.
+                if self.is_function_start()? && !self.is_size_start() {
                     self.parse_function()
                 } else {
                     self.parse_operand_expression()
@@ -135,6 +166,42 @@ impl Parser<'_> {
         }
     }
 
+    fn is_size_start(&mut self) -> bool {
+        matches!(
+            self.lexer.peek_token(),
+            Ok(Token::Identifier(name)) if self
+                .dialect
+                .function_by_name(&name)
+                .is_some_and(|doc| doc.func == FunctionName::Size)
+        )
+    }
+
+    /// `size(path)`, if that is what comes next; otherwise nothing is
+    /// consumed, so an attribute named `size` still reads as a path.
+    fn parse_size_operand(&mut self) -> Result<Option<Operand>, ParseError> {
+        let saved_position = self.lexer.position;
+        if let Ok(Token::Identifier(name)) = self.lexer.next_token()
+            && self
+                .dialect
+                .function_by_name(&name)
+                .is_some_and(|doc| doc.func == FunctionName::Size)
+            && let Ok(Token::LeftParen) = self.lexer.next_token()
+        {
+            let Operand::Path(path) = self.parse_path_operand()? else {
+                unreachable!("parse_path_operand returns paths");
+            };
+            return match self.lexer.next_token()? {
+                Token::RightParen => Ok(Some(Operand::Size(path))),
+                token => Err(ParseError::UnexpectedToken {
+                    token: format!("{token:?}"),
+                    position: self.lexer.position,
+                }),
+            };
+        }
+        self.lexer.position = saved_position;
+        Ok(None)
+    }
+
     fn parse_function(&mut self) -> Result<DynamoExpression, ParseError> {
         let name_token = self.lexer.next_token()?;
         let name = if let Token::Identifier(name) = name_token {
@@ -196,11 +263,20 @@ impl Parser<'_> {
             }
         }
 
+        if name == FunctionName::AttributeType
+            && let Some(type_arg) = args.get_mut(1)
+        {
+            *type_arg = type_code_operand(type_arg, self.lexer.position)?;
+        }
+
         Ok(DynamoExpression::Function { name, args })
     }
 
     fn parse_operand_expression(&mut self) -> Result<DynamoExpression, ParseError> {
-        let left = self.parse_path_operand()?;
+        let left = match self.parse_size_operand()? {
+            Some(size) => size,
+            None => self.parse_path_operand()?,
+        };
 
         match self.lexer.peek_token()? {
             Token::Between => {
@@ -260,6 +336,13 @@ impl Parser<'_> {
                     }
                 }
 
+                if values.is_empty() || values.len() > MAX_IN_VALUES {
+                    return Err(ParseError::InvalidSyntax {
+                        message: format!("IN takes 1 to {MAX_IN_VALUES} values"),
+                        position: self.lexer.position,
+                    });
+                }
+
                 Ok(DynamoExpression::In {
                     operand: left,
                     values,
@@ -319,6 +402,10 @@ impl Parser<'_> {
                     right,
                 })
             }
+            _ if matches!(left, Operand::Size(_)) => Err(ParseError::InvalidSyntax {
+                message: "size() must be compared, as in size(items) > 0".to_string(),
+                position: self.lexer.position,
+            }),
             _ => Err(ParseError::InvalidSyntax {
                 message: "Expected comparison operator, BETWEEN, or IN".to_string(),
                 position: self.lexer.position,
@@ -338,6 +425,9 @@ impl Parser<'_> {
     }
 
     fn parse_value_operand(&mut self) -> Result<Operand, ParseError> {
+        if let Some(size) = self.parse_size_operand()? {
+            return Ok(size);
+        }
         match self.lexer.next_token()? {
             Token::Identifier(name) => Ok(infer_identifier_operand(&name)),
             Token::Path(name) => Ok(Operand::Path(name)),
@@ -353,7 +443,53 @@ impl Parser<'_> {
     }
 }
 
+/// DynamoDB's limit on the values of one `IN` list.
+const MAX_IN_VALUES: usize = 100;
+
+/// The type codes `attribute_type` accepts.
+const TYPE_CODES: [&str; 10] = ["S", "SS", "N", "NS", "B", "BS", "BOOL", "NULL", "L", "M"];
+
+/// The type argument of `attribute_type` as the code DynamoDB expects: codes
+/// in any case, or the type's name (`string`, `number`, `list`, ...).
+fn type_code_operand(operand: &Operand, position: usize) -> Result<Operand, ParseError> {
+    let unknown = |text: &str| ParseError::InvalidSyntax {
+        message: format!(
+            "Unknown attribute type '{text}', expected one of {}",
+            TYPE_CODES.join(", ")
+        ),
+        position,
+    };
+    let text = match operand {
+        Operand::Value(text) | Operand::Path(text) => text.clone(),
+        Operand::Null => "NULL".to_string(),
+        Operand::Placeholder(_) => return Ok(operand.clone()),
+        Operand::Number(_) | Operand::Boolean(_) | Operand::Size(_) => {
+            return Err(unknown(&format::format_operand(operand)));
+        }
+    };
+    let upper = text.to_ascii_uppercase();
+    let code = match upper.as_str() {
+        "STRING" => "S",
+        "NUMBER" => "N",
+        "BINARY" => "B",
+        "BOOLEAN" => "BOOL",
+        "LIST" => "L",
+        "MAP" => "M",
+        "STRING_SET" => "SS",
+        "NUMBER_SET" => "NS",
+        "BINARY_SET" => "BS",
+        code => TYPE_CODES
+            .into_iter()
+            .find(|known| *known == code)
+            .ok_or_else(|| unknown(&text))?,
+    };
+    Ok(Operand::Value(code.to_string()))
+}
+
 fn infer_identifier_operand(token: &str) -> Operand {
+    if let Some(name) = placeholder_name(token) {
+        return Operand::Placeholder(name.to_string());
+    }
     let lower = token.to_ascii_lowercase();
     if lower == "true" {
         return Operand::Boolean(true);
@@ -364,21 +500,22 @@ fn infer_identifier_operand(token: &str) -> Operand {
     if lower == "null" {
         return Operand::Null;
     }
-    if let Some(num) = parse_numeric_identifier(token) {
-        return Operand::Number(num);
+    if let Some(number) = NumberLiteral::parse(token) {
+        return Operand::Number(number);
     }
     Operand::Value(token.to_string())
 }
 
-fn parse_numeric_identifier(token: &str) -> Option<f64> {
-    if !token.chars().any(|c| c.is_ascii_digit()) {
-        return None;
-    }
-    if !token
+/// The name of a `:name` parameter token. Names are ASCII letters, digits and
+/// underscores; anything else after the colon stays a plain value.
+fn placeholder_name(token: &str) -> Option<&str> {
+    let name = token.strip_prefix(':')?;
+    let valid = name
         .chars()
-        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
-    {
-        return None;
-    }
-    token.parse::<f64>().ok()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    valid.then_some(name)
 }
@@ -11,11 +11,62 @@ mod expr_tests {
             DynamoExpression::Comparison {
                 left: Operand::Path("age".to_string()),
                 operator: Comparator::Equal,
-                right: Operand::Number(25.0),
+                right: Operand::Number(25.0.into()),
             }
         );
     }
 
+    #[test]
+    fn test_placeholders_bind_to_values() {
+        let result =
+            parse_dynamo_expression("user = :user AND age > :age OR name = :user").unwrap();
+        assert_eq!(
+            result.placeholders(),
+            vec!["user".to_string(), "age".to_string()]
+        );
+
+        let mut values = std::collections::HashMap::new();
+        values.insert("user".to_string(), Operand::Value("u1".to_string()));
+        assert!(matches!(
+            result.clone().bind(&values),
+            Err(ParseError::UnboundPlaceholder { name }) if name == "age"
+        ));
+
+        values.insert("age".to_string(), Operand::Number(30.0.into()));
+        let bound = result.bind(&values).unwrap();
+        assert!(bound.placeholders().is_empty());
+        assert_eq!(
+            bound,
+            parse_dynamo_expression(r#"user = "u1" AND age > 30 OR name = "u1""#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_split_order_clause() {
+        assert_eq!(
+            split_order_clause(r#"PK = "u" ORDER DESC"#),
+            (r#"PK = "u""#, Some(SortOrder::Descending))
+        );
+        assert_eq!(
+            split_order_clause("PK = u  order asc "),
+            ("PK = u", Some(SortOrder::Ascending))
+        );
+        assert_eq!(
+            split_order_clause("ORDER DESC"),
+            ("", Some(SortOrder::Descending))
+        );
+        // Attributes named like the keywords are left alone.
+        assert_eq!(split_order_clause("order = desc"), ("order = desc", None));
+        assert_eq!(
+            split_order_clause("sortorder DESC"),
+            ("sortorder DESC", None)
+        );
+        assert_eq!(
+            split_order_clause(r#"name = "x ORDER DESC""#),
+            (r#"name = "x ORDER DESC""#, None)
+        );
+    }
+
     #[test]
     fn test_string_comparison() {
         let result = parse_dynamo_expression(r#"name = "John""#).unwrap();
@@ -118,7 +169,7 @@ mod expr_tests {
             DynamoExpression::Comparison {
                 left: Operand::Path("count".to_string()),
                 operator: Comparator::NotEqual,
-                right: Operand::Number(0.0),
+                right: Operand::Number(0.0.into()),
             }
         );
     }
@@ -169,7 +220,7 @@ mod expr_tests {
                     assert_eq!(left_op, Operand::Path("status".to_string()));
                     assert_eq!(right_op, Operand::Value("inactive".to_string()));
                     assert_eq!(left_op2, Operand::Path("age".to_string()));
-                    assert_eq!(right_op2, Operand::Number(18.0));
+                    assert_eq!(right_op2, Operand::Number(18.0.into()));
                 }
                 _ => panic!("Unexpected expression structure"),
             },
@@ -224,8 +275,8 @@ mod expr_tests {
             result,
             DynamoExpression::Between {
                 operand: Operand::Path("age".to_string()),
-                lower: Operand::Number(18.0),
-                upper: Operand::Number(65.0),
+                lower: Operand::Number(18.0.into()),
+                upper: Operand::Number(65.0.into()),
             }
         );
     }
@@ -287,7 +338,7 @@ mod expr_tests {
                     },
                 ) => {
                     assert_eq!(left_op, Operand::Path("age".to_string()));
-                    assert_eq!(right_op, Operand::Number(18.0));
+                    assert_eq!(right_op, Operand::Number(18.0.into()));
                     assert_eq!(left_op2, Operand::Path("status".to_string()));
                     assert_eq!(right_op2, Operand::Value("active".to_string()));
                 }
@@ -389,7 +440,6 @@ mod expr_tests {
             ("attribute_type(path, \"S\")", FunctionName::AttributeType),
             ("begins_with(path, \"prefix\")", FunctionName::BeginsWith),
             ("contains(path, \"substring\")", FunctionName::Contains),
-            ("size(path)", FunctionName::Size),
         ];
 
         for (input, expected_func) in functions {
@@ -403,6 +453,75 @@ mod expr_tests {
         }
     }
 
+    #[test]
+    fn test_size_comparisons_and_type_codes() {
+        let result = parse_dynamo_expression("size(items) > 3").unwrap();
+        assert_eq!(
+            result,
+            DynamoExpression::Comparison {
+                left: Operand::Size("items".to_string()),
+                operator: Comparator::Greater,
+                right: Operand::Number(3.0.into()),
+            }
+        );
+        let result = parse_dynamo_expression("size(a) BETWEEN 1 AND size(b)").unwrap();
+        assert_eq!(
+            result,
+            DynamoExpression::Between {
+                operand: Operand::Size("a".to_string()),
+                lower: Operand::Number(1.0.into()),
+                upper: Operand::Size("b".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_dynamo_expression("size = 3").unwrap(),
+            DynamoExpression::Comparison {
+                left: Operand::Path("size".to_string()),
+                operator: Comparator::Equal,
+                right: Operand::Number(3.0.into()),
+            }
+        );
+        assert!(parse_dynamo_expression("size(items)").is_err());
+
+        let type_arg = |input: &str| match parse_dynamo_expression(input).unwrap() {
+            DynamoExpression::Function { args, .. } => args[1].clone(),
+            other => panic!("Expected function expression, got {other:?}"),
+        };
+        assert_eq!(
+            type_arg("attribute_type(tags, ss)"),
+            Operand::Value("SS".to_string())
+        );
+        assert_eq!(
+            type_arg("attribute_type(age, 'number')"),
+            Operand::Value("N".to_string())
+        );
+        assert_eq!(
+            type_arg("attribute_type(gone, NULL)"),
+            Operand::Value("NULL".to_string())
+        );
+        assert!(parse_dynamo_expression("attribute_type(age, \"int\")").is_err());
+    }
+
+    #[test]
+    fn test_in_with_mixed_values_and_paths() {
+        let result = parse_dynamo_expression("status IN (\"open\", 3, true, `fallback`)").unwrap();
+        assert_eq!(
+            result,
+            DynamoExpression::In {
+                operand: Operand::Path("status".to_string()),
+                values: vec![
+                    Operand::Value("open".to_string()),
+                    Operand::Number(3.0.into()),
+                    Operand::Boolean(true),
+                    Operand::Path("fallback".to_string()),
+                ],
+            }
+        );
+        assert!(parse_dynamo_expression("status IN ()").is_err());
+        let values = vec!["1"; 101].join(", ");
+        assert!(parse_dynamo_expression(&format!("n IN ({values})")).is_err());
+    }
+
     #[test]
     fn test_parse_errors() {
         let invalid_inputs = vec![
@@ -438,7 +557,7 @@ mod expr_tests {
         );
         assert_eq!(
             parse_single_value_token("123").unwrap(),
-            Operand::Number(123.0)
+            Operand::Number(123.0.into())
         );
         assert_eq!(
             parse_single_value_token("true").unwrap(),
@@ -670,4 +789,119 @@ mod expr_tests {
         assert_eq!(result[1].key, "key2");
         assert_eq!(result[1].value, Value::String("value2".to_string()));
     }
+
+    #[test]
+    fn test_update_expression_clauses() {
+        let update = parse_update_expression(
+            "SET status = \"done\", retries = 0, `odd name` = open REMOVE lock, draft",
+        )
+        .unwrap();
+        let literal = |operand| UpdateValue::Operand(operand);
+        assert_eq!(
+            update.set,
+            vec![
+                (
+                    "status".to_string(),
+                    literal(Operand::Value("done".to_string()))
+                ),
+                ("retries".to_string(), literal(Operand::Number(0.0.into()))),
+                (
+                    "`odd name`".to_string(),
+                    literal(Operand::Value("open".to_string()))
+                ),
+            ]
+        );
+        assert_eq!(update.remove, vec!["lock".to_string(), "draft".to_string()]);
+
+        let update = parse_update_expression("remove lock set done = true").unwrap();
+        assert_eq!(update.remove, vec!["lock".to_string()]);
+        assert_eq!(
+            update.set,
+            vec![("done".to_string(), literal(Operand::Boolean(true)))]
+        );
+    }
+
+    #[test]
+    fn test_update_expression_arithmetic_functions_and_sets() {
+        let update = parse_update_expression(
+            "SET n = n - 1, tags = list_append(tags, [\"new\", 2]), \
+             seen = if_not_exists(seen, 0) + 1 ADD visits 1, labels [a, b] DELETE old [3]",
+        )
+        .unwrap();
+        let path = |name: &str| Box::new(UpdateValue::Operand(Operand::Path(name.to_string())));
+        let number = |n: f64| Box::new(UpdateValue::Operand(Operand::Number(n.into())));
+        assert_eq!(update.set[0].1, UpdateValue::Minus(path("n"), number(1.0)));
+        assert_eq!(
+            update.set[1].1,
+            UpdateValue::ListAppend(
+                path("tags"),
+                Box::new(UpdateValue::List(vec![
+                    Operand::Value("new".to_string()),
+                    Operand::Number(2.0.into()),
+                ])),
+            )
+        );
+        assert_eq!(
+            update.set[2].1,
+            UpdateValue::Plus(
+                Box::new(UpdateValue::IfNotExists("seen".to_string(), number(0.0))),
+                number(1.0),
+            )
+        );
+        assert_eq!(update.add[0], ("visits".to_string(), *number(1.0)));
+        assert_eq!(
+            update.delete,
+            vec![(
+                "old".to_string(),
+                UpdateValue::List(vec![Operand::Number(3.0.into())])
+            )]
+        );
+        assert!(!update.is_literal());
+
+        let update = parse_update_expression("SET items[0] = [] REMOVE a[1]").unwrap();
+        assert_eq!(update.set[0].0, "items[0]");
+        assert_eq!(update.remove, vec!["a[1]".to_string()]);
+        assert!(update.is_literal());
+        assert_eq!(
+            path_steps("address.lines[2][0].`a.b`"),
+            Ok(vec![
+                PathStep::Name("address".to_string()),
+                PathStep::Name("lines".to_string()),
+                PathStep::Index(2),
+                PathStep::Index(0),
+                PathStep::Name("a.b".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_update_expression_errors() {
+        for input in [
+            "",
+            "status = 1",
+            "SET status",
+            "SET status = ",
+            "SET a = 1,",
+            "SET a = 1 b = 2",
+            "SET a = 1 SET b = 2",
+            "SET a = :value",
+            "REMOVE a b",
+            "SET a = 1 REMOVE a",
+            "SET a.b = 1 REMOVE a",
+            "SET a[0] = 1, a[0] = 2",
+            "SET a[x] = 1",
+            "ADD a.b 1",
+            "DELETE a[0] [1]",
+            "SET a = b + \"x\"",
+            "SET a = list_append(a, 1)",
+            "SET a = nope(b)",
+            "SET a = [1, 2",
+            "ADD a \"x\"",
+            "ADD a [1, \"x\"]",
+            "DELETE a 1",
+            "DELETE a []",
+        ] {
+            assert!(parse_update_expression(input).is_err(), "{input}");
+        }
+    }
 }
@@ -0,0 +1,445 @@
+use super::ast::Operand;
+use super::error::ParseError;
+use super::lexer::{Lexer, Token};
+
+/// An update expression, e.g. `SET status = "done", retries = retries + 1
+/// REMOVE lock ADD seen 1 DELETE labels ["stale"]`.
+///
+/// Paths are kept as written: a bare path such as `address.city` or
+/// `items[0]` is a document path, and a name in backticks is one attribute,
+/// whatever it contains. [`path_steps`] splits them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UpdateExpression {
+    pub set: Vec<(String, UpdateValue)>,
+    pub remove: Vec<String>,
+    /// A number added to a number attribute, or a set of elements added to a
+    /// set attribute.
+    pub add: Vec<(String, UpdateValue)>,
+    /// A set of elements taken out of a set attribute.
+    pub delete: Vec<(String, UpdateValue)>,
+}
+
+/// The value side of a `SET`, `ADD`, or `DELETE` action.
+///
+/// A bare word on its own is a string, like on the right-hand side of a
+/// filter; inside arithmetic and functions it names an attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateValue {
+    /// A literal, or another attribute's value as [`Operand::Path`].
+    Operand(Operand),
+    /// `[a, b]`: a list in `SET`, a set in `ADD` and `DELETE`.
+    List(Vec<Operand>),
+    Plus(Box<UpdateValue>, Box<UpdateValue>),
+    Minus(Box<UpdateValue>, Box<UpdateValue>),
+    ListAppend(Box<UpdateValue>, Box<UpdateValue>),
+    /// `if_not_exists(path, value)`: the attribute's value, or `value` when
+    /// it has none.
+    IfNotExists(String, Box<UpdateValue>),
+}
+
+impl UpdateExpression {
+    /// Whether every action writes values known up front: literal `SET`s and
+    /// `REMOVE`s, nothing computed from the item.
+    pub fn is_literal(&self) -> bool {
+        self.add.is_empty()
+            && self.delete.is_empty()
+            && self.set.iter().all(|(_, value)| value.is_literal())
+    }
+}
+
+/// One step of a document path.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PathStep {
+    Name(String),
+    Index(usize),
+}
+
+/// The steps of a path as an update expression keeps it: names separated by
+/// `.`, each followed by any `[n]` list indexes, with names in backticks
+/// taken whole.
+pub fn path_steps(path: &str) -> Result<Vec<PathStep>, String> {
+    let mut steps = Vec::new();
+    let mut rest = path;
+    loop {
+        let name;
+        if let Some(quoted) = rest.strip_prefix('`') {
+            let end = quoted
+                .find('`')
+                .ok_or_else(|| format!("unclosed ` in {path}"))?;
+            (name, rest) = (&quoted[..end], &quoted[end + 1..]);
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            (name, rest) = rest.split_at(end);
+        }
+        if name.is_empty() {
+            return Err(format!("missing attribute name in {path}"));
+        }
+        steps.push(PathStep::Name(name.to_string()));
+        while let Some(index) = rest.strip_prefix('[') {
+            let end = index
+                .find(']')
+                .ok_or_else(|| format!("unclosed [ in {path}"))?;
+            let position = index[..end]
+                .parse()
+                .map_err(|_| format!("list indexes are numbers, in {path}"))?;
+            steps.push(PathStep::Index(position));
+            rest = &index[end + 1..];
+        }
+        if rest.is_empty() {
+            return Ok(steps);
+        }
+        rest = rest
+            .strip_prefix('.')
+            .ok_or_else(|| format!("expected . or [ after a name in {path}"))?;
+    }
+}
+
+/// The attribute `path` names when it's a top-level attribute rather than a
+/// path into one.
+pub fn attribute_name(path: &str) -> Option<String> {
+    match path_steps(path).ok()?.as_slice() {
+        [PathStep::Name(name)] => Some(name.clone()),
+        _ => None,
+    }
+}
+
+impl UpdateValue {
+    pub fn is_literal(&self) -> bool {
+        match self {
+            Self::Operand(operand) => !matches!(operand, Operand::Path(_)),
+            Self::List(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Clause {
+    Set,
+    Remove,
+    Add,
+    Delete,
+}
+
+pub fn parse_update_expression(input: &str) -> Result<UpdateExpression, ParseError> {
+    let mut lexer = Lexer::new(input);
+    let mut update = UpdateExpression::default();
+    let mut clauses = Vec::new();
+    let mut paths = Vec::new();
+    loop {
+        let token = lexer.next_token()?;
+        if token == Token::EOF {
+            break;
+        }
+        if let Some(next) = clause_keyword(&token) {
+            if clauses.contains(&next) {
+                return Err(ParseError::InvalidSyntax {
+                    message: "each clause may appear only once".to_string(),
+                    position: lexer.position,
+                });
+            }
+            clauses.push(next);
+            continue;
+        }
+        let Some(&current) = clauses.last() else {
+            return Err(ParseError::InvalidSyntax {
+                message: "expected SET, REMOVE, ADD, or DELETE".to_string(),
+                position: lexer.position,
+            });
+        };
+        let path = attribute_path(token, lexer.position)?;
+        let steps = path_steps(&path).map_err(|message| ParseError::InvalidSyntax {
+            message,
+            position: lexer.position,
+        })?;
+        // DynamoDB refuses an action on a path another action covers.
+        if paths
+            .iter()
+            .any(|other: &Vec<PathStep>| other.starts_with(&steps) || steps.starts_with(other))
+        {
+            return Err(ParseError::InvalidSyntax {
+                message: format!("{path} overlaps another path in the update"),
+                position: lexer.position,
+            });
+        }
+        if matches!(current, Clause::Add | Clause::Delete) && steps.len() > 1 {
+            return Err(ParseError::InvalidSyntax {
+                message: format!("ADD and DELETE only work on top-level attributes, not {path}"),
+                position: lexer.position,
+            });
+        }
+        paths.push(steps);
+        match current {
+            Clause::Set => {
+                expect(&mut lexer, &Token::Equal)?;
+                let value = set_value(&mut lexer)?;
+                update.set.push((path, value));
+            }
+            Clause::Remove => update.remove.push(path),
+            Clause::Add => {
+                let value = term(&mut lexer, false)?;
+                check_add_value(&value, lexer.position)?;
+                update.add.push((path, value));
+            }
+            Clause::Delete => {
+                let value = term(&mut lexer, false)?;
+                check_set_value("DELETE", &value, lexer.position)?;
+                update.delete.push((path, value));
+            }
+        }
+        // Actions are separated by commas; anything else must start the next
+        // clause.
+        match lexer.peek_token()? {
+            Token::Comma => {
+                lexer.next_token()?;
+                if matches!(lexer.peek_token()?, Token::EOF) {
+                    return Err(ParseError::UnexpectedEndOfInput {
+                        position: lexer.position,
+                    });
+                }
+            }
+            Token::EOF => {}
+            token if clause_keyword(&token).is_some() => {}
+            token => {
+                return Err(ParseError::UnexpectedToken {
+                    token: format!("{token:?}"),
+                    position: lexer.position,
+                });
+            }
+        }
+    }
+    if paths.is_empty() {
+        return Err(ParseError::InvalidSyntax {
+            message: "expected SET, REMOVE, ADD, or DELETE".to_string(),
+            position: lexer.position,
+        });
+    }
+    Ok(update)
+}
+
+fn clause_keyword(token: &Token) -> Option<Clause> {
+    let Token::Identifier(word) = token else {
+        return None;
+    };
+    match word.to_ascii_uppercase().as_str() {
+        "SET" => Some(Clause::Set),
+        "REMOVE" => Some(Clause::Remove),
+        "ADD" => Some(Clause::Add),
+        "DELETE" => Some(Clause::Delete),
+        _ => None,
+    }
+}
+
+/// `term`, or `term + term` / `term - term`.
+fn set_value(lexer: &mut Lexer) -> Result<UpdateValue, ParseError> {
+    let start = lexer.position;
+    let value = term(lexer, false)?;
+    let Some(plus) = arithmetic_operator(&lexer.peek_token()?) else {
+        return Ok(value);
+    };
+    // Arithmetic works on attributes, so read the left side again with bare
+    // words as paths.
+    lexer.position = start;
+    let left = term(lexer, true)?;
+    lexer.next_token()?; // consume + or -
+    let right = term(lexer, true)?;
+    for operand in [&left, &right] {
+        if !matches!(
+            operand,
+            UpdateValue::Operand(Operand::Number(_) | Operand::Path(_))
+                | UpdateValue::IfNotExists(..)
+        ) {
+            return Err(ParseError::InvalidSyntax {
+                message: "+ and - take numbers, attributes, or if_not_exists()".to_string(),
+                position: lexer.position,
+            });
+        }
+    }
+    let (left, right) = (Box::new(left), Box::new(right));
+    Ok(if plus {
+        UpdateValue::Plus(left, right)
+    } else {
+        UpdateValue::Minus(left, right)
+    })
+}
+
+/// `Some(true)` for `+`, `Some(false)` for `-`. They need spaces around
+/// them, since `a-b` is one bare word.
+fn arithmetic_operator(token: &Token) -> Option<bool> {
+    match token {
+        Token::Identifier(word) if word == "+" => Some(true),
+        Token::Identifier(word) if word == "-" => Some(false),
+        _ => None,
+    }
+}
+
+/// A literal, a path, a `[...]` list, or a call to `list_append` or
+/// `if_not_exists`. `bare_is_path` reads bare words as attribute names.
+fn term(lexer: &mut Lexer, bare_is_path: bool) -> Result<UpdateValue, ParseError> {
+    let token = lexer.next_token()?;
+    if let Token::Identifier(word) = &token
+        && lexer.peek_token()? == Token::LeftParen
+    {
+        return function(lexer, word);
+    }
+    match token {
+        Token::LeftBracket => list(lexer),
+        token @ Token::Path(_) => Ok(UpdateValue::Operand(Operand::Path(attribute_path(
+            token,
+            lexer.position,
+        )?))),
+        Token::Identifier(word) if bare_is_path && !word.starts_with(':') => {
+            let path = attribute_path(Token::Identifier(word), lexer.position)?;
+            Ok(UpdateValue::Operand(Operand::Path(path)))
+        }
+        token => literal(token, lexer.position).map(UpdateValue::Operand),
+    }
+}
+
+fn function(lexer: &mut Lexer, name: &str) -> Result<UpdateValue, ParseError> {
+    let position = lexer.position;
+    lexer.next_token()?; // consume (
+    let value = match name.to_ascii_lowercase().as_str() {
+        "list_append" => {
+            let first = term(lexer, true)?;
+            expect(lexer, &Token::Comma)?;
+            let second = term(lexer, true)?;
+            for operand in [&first, &second] {
+                if !matches!(
+                    operand,
+                    UpdateValue::List(_)
+                        | UpdateValue::Operand(Operand::Path(_))
+                        | UpdateValue::IfNotExists(..)
+                ) {
+                    return Err(ParseError::InvalidSyntax {
+                        message: "list_append takes lists or attributes".to_string(),
+                        position: lexer.position,
+                    });
+                }
+            }
+            UpdateValue::ListAppend(Box::new(first), Box::new(second))
+        }
+        "if_not_exists" => {
+            let path = attribute_path(lexer.next_token()?, lexer.position)?;
+            expect(lexer, &Token::Comma)?;
+            let fallback = term(lexer, false)?;
+            UpdateValue::IfNotExists(path, Box::new(fallback))
+        }
+        _ => {
+            return Err(ParseError::InvalidFunction {
+                name: name.to_string(),
+                position,
+            });
+        }
+    };
+    expect(lexer, &Token::RightParen)?;
+    Ok(value)
+}
+
+/// The rest of a `[...]` list after its opening bracket. Elements are
+/// literals; bare words are strings.
+fn list(lexer: &mut Lexer) -> Result<UpdateValue, ParseError> {
+    let mut elements = Vec::new();
+    if lexer.peek_token()? == Token::RightBracket {
+        lexer.next_token()?;
+        return Ok(UpdateValue::List(elements));
+    }
+    loop {
+        elements.push(literal(lexer.next_token()?, lexer.position)?);
+        match lexer.next_token()? {
+            Token::Comma => {}
+            Token::RightBracket => return Ok(UpdateValue::List(elements)),
+            Token::EOF => {
+                return Err(ParseError::UnexpectedEndOfInput {
+                    position: lexer.position,
+                });
+            }
+            token => {
+                return Err(ParseError::UnexpectedToken {
+                    token: format!("{token:?}"),
+                    position: lexer.position,
+                });
+            }
+        }
+    }
+}
+
+fn check_add_value(value: &UpdateValue, position: usize) -> Result<(), ParseError> {
+    if matches!(value, UpdateValue::Operand(Operand::Number(_))) {
+        return Ok(());
+    }
+    check_set_value("ADD", value, position)
+}
+
+/// A non-empty `[...]` of only strings or only numbers, as a set's elements.
+fn check_set_value(clause: &str, value: &UpdateValue, position: usize) -> Result<(), ParseError> {
+    let is_set = match value {
+        UpdateValue::List(elements) => {
+            !elements.is_empty()
+                && (elements.iter().all(|e| matches!(e, Operand::Value(_)))
+                    || elements.iter().all(|e| matches!(e, Operand::Number(_))))
+        }
+        _ => false,
+    };
+    if is_set {
+        return Ok(());
+    }
+    let expected = if clause == "ADD" {
+        "a number or a set like [\"a\", \"b\"]"
+    } else {
+        "a set like [\"a\", \"b\"]"
+    };
+    Err(ParseError::InvalidSyntax {
+        message: format!("{clause} takes {expected}, of only strings or only numbers"),
+        position,
+    })
+}
+
+fn expect(lexer: &mut Lexer, expected: &Token) -> Result<(), ParseError> {
+    match lexer.next_token()? {
+        token if token == *expected => Ok(()),
+        Token::EOF => Err(ParseError::UnexpectedEndOfInput {
+            position: lexer.position,
+        }),
+        token => Err(ParseError::UnexpectedToken {
+            token: format!("{token:?}"),
+            position: lexer.position,
+        }),
+    }
+}
+
+/// A path as [`UpdateExpression`] keeps it: bare paths as written, checked
+/// with [`path_steps`], and backtick names back in their backticks.
+fn attribute_path(token: Token, position: usize) -> Result<String, ParseError> {
+    match token {
+        Token::Identifier(path) => match path_steps(&path) {
+            Ok(_) => Ok(path),
+            Err(message) => Err(ParseError::InvalidSyntax { message, position }),
+        },
+        Token::Path(name) => Ok(format!("`{name}`")),
+        Token::EOF => Err(ParseError::UnexpectedEndOfInput { position }),
+        token => Err(ParseError::UnexpectedToken {
+            token: format!("{token:?}"),
+            position,
+        }),
+    }
+}
+
+/// Bare words are strings, like on the right-hand side of a filter.
+fn literal(token: Token, position: usize) -> Result<Operand, ParseError> {
+    match token {
+        Token::Identifier(word) if word.starts_with(':') => Err(ParseError::InvalidSyntax {
+            message: "parameters are not supported in updates".to_string(),
+            position,
+        }),
+        Token::Identifier(word) | Token::String(word) => Ok(Operand::Value(word)),
+        Token::Number(number) => Ok(Operand::Number(number)),
+        Token::Boolean(value) => Ok(Operand::Boolean(value)),
+        Token::Null => Ok(Operand::Null),
+        Token::EOF => Err(ParseError::UnexpectedEndOfInput { position }),
+        token => Err(ParseError::UnexpectedToken {
+            token: format!("{token:?}"),
+            position,
+        }),
+    }
+}
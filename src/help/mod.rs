@@ -204,11 +204,11 @@ fn make_spans<'a>(
 pub fn height<'a>(
     entries: &'a [&Entry<'a>],
     area: Rect,
+    theme: &Theme,
     modifiers: KeyModifiers,
     mode: ModDisplay,
 ) -> u16 {
-    let theme = Theme::default();
-    let total_width: usize = make_spans(entries, &theme, modifiers, mode)
+    let total_width: usize = make_spans(entries, theme, modifiers, mode)
         .iter()
         .map(|s| s.content.width())
         .sum();
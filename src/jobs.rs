@@ -0,0 +1,312 @@
+//! Long-running background work: purges, copies, renames, seeding, exports,
+//! and bulk updates. Each registers a job with [`start`] (or [`track`], to
+//! share a cancel flag it already has) and reports through the
+//! [`JobHandle`]: progress and the outcome are shown as toasts and kept for
+//! the jobs panel (`J`), which also cancels jobs.
+//!
+//! The registry is process-wide, like the throttle and the safety policy, so
+//! a job outlives the widget that started it.
+
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError,
+    atomic::{AtomicBool, Ordering},
+};
+use std::time::{Duration, Instant};
+
+use crate::env::{AppBus, AppCommand, Toast, ToastKind};
+
+/// Finished jobs kept for the panel; older ones are dropped first.
+const KEEP_FINISHED: usize = 20;
+
+static JOBS: Mutex<Registry> = Mutex::new(Registry {
+    last_id: 0,
+    jobs: Vec::new(),
+    bus: None,
+});
+
+struct Registry {
+    last_id: u64,
+    jobs: Vec<Job>,
+    /// Where toasts and redraws go; unset outside the TUI.
+    bus: Option<AppBus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    /// Cancel was asked for and the job hasn't stopped yet.
+    Canceling,
+    Done,
+    Canceled,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Canceling => "canceling",
+            Self::Done => "done",
+            Self::Canceled => "canceled",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Canceled | Self::Failed)
+    }
+}
+
+/// A job as the panel shows it.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id: u64,
+    pub label: String,
+    pub status: JobStatus,
+    /// The latest progress, or the outcome once finished.
+    pub message: String,
+    pub started: Instant,
+    pub finished: Option<Instant>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Job {
+    pub fn elapsed(&self) -> Duration {
+        self.finished
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.started)
+    }
+}
+
+/// What a job reports through. Clones report on the same job.
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    id: u64,
+    cancel: Arc<AtomicBool>,
+}
+
+/// Route toasts and redraws to the running app.
+pub fn init(bus: AppBus) {
+    lock().bus = Some(bus);
+}
+
+/// Register a job with its own cancel flag.
+pub fn start(label: impl Into<String>) -> JobHandle {
+    track(label, Arc::default())
+}
+
+/// Register a job that stops when `cancel` is set.
+pub fn track(label: impl Into<String>, cancel: Arc<AtomicBool>) -> JobHandle {
+    let mut registry = lock();
+    registry.last_id += 1;
+    let id = registry.last_id;
+    registry.jobs.push(Job {
+        id,
+        label: label.into(),
+        status: JobStatus::Running,
+        message: String::new(),
+        started: Instant::now(),
+        finished: None,
+        cancel: cancel.clone(),
+    });
+    registry.redraw();
+    JobHandle { id, cancel }
+}
+
+/// Every job, oldest first.
+pub fn list() -> Vec<Job> {
+    lock()
+        .jobs
+        .iter()
+        .map(|job| {
+            let mut job = job.clone();
+            // The flag may have been set by whoever shares it.
+            if job.status == JobStatus::Running && job.cancel.load(Ordering::Relaxed) {
+                job.status = JobStatus::Canceling;
+            }
+            job
+        })
+        .collect()
+}
+
+/// How many jobs haven't finished.
+pub fn running() -> usize {
+    lock()
+        .jobs
+        .iter()
+        .filter(|job| !job.status.is_finished())
+        .count()
+}
+
+/// Ask job `id` to stop. Returns whether this cancelled it, as opposed to it
+/// being cancelled or finished already.
+pub fn cancel(id: u64) -> bool {
+    let mut registry = lock();
+    let Some(job) = registry
+        .jobs
+        .iter_mut()
+        .find(|job| job.id == id && job.status == JobStatus::Running)
+    else {
+        return false;
+    };
+    if job.cancel.swap(true, Ordering::Relaxed) {
+        return false;
+    }
+    job.status = JobStatus::Canceling;
+    let message = format!("Canceling {}...", lower_first(&job.label));
+    registry.toast(message, ToastKind::Info, Duration::from_hours(1));
+    true
+}
+
+/// Forget the finished jobs.
+pub fn clear_finished() {
+    let mut registry = lock();
+    registry.jobs.retain(|job| !job.status.is_finished());
+    registry.redraw();
+}
+
+impl JobHandle {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// The cancel flag, for operations that poll one between steps.
+    pub fn flag(&self) -> &AtomicBool {
+        &self.cancel
+    }
+
+    /// Ask the job to stop. Returns whether this cancelled it.
+    pub fn cancel(&self) -> bool {
+        cancel(self.id)
+    }
+
+    /// Record how far the job has got and show it in a toast, unless the job
+    /// is being canceled.
+    pub fn progress(&self, message: impl Into<String>) {
+        let message = message.into();
+        let mut registry = lock();
+        let Some(job) = registry.job_mut(self.id) else {
+            return;
+        };
+        job.message.clone_from(&message);
+        if job.status == JobStatus::Running && !job.cancel.load(Ordering::Relaxed) {
+            registry.toast(message, ToastKind::Info, Duration::from_hours(1));
+        } else {
+            registry.redraw();
+        }
+    }
+
+    /// Record the outcome and show it in a toast.
+    pub fn finish(&self, result: Result<String, String>) {
+        let toast = match &result {
+            Ok(message) => (message.clone(), ToastKind::Info, Duration::from_secs(3)),
+            Err(message) => (message.clone(), ToastKind::Error, Duration::from_secs(4)),
+        };
+        let mut registry = lock();
+        registry.finish(self.id, result);
+        let (message, kind, duration) = toast;
+        registry.toast(message, kind, duration);
+    }
+
+    /// Record the outcome for a caller that shows it itself, e.g. in a
+    /// summary popup.
+    pub fn finish_quietly(&self, result: Result<String, String>) {
+        let mut registry = lock();
+        registry.finish(self.id, result);
+        registry.redraw();
+    }
+}
+
+impl Registry {
+    fn job_mut(&mut self, id: u64) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    fn finish(&mut self, id: u64, result: Result<String, String>) {
+        let Some(job) = self.job_mut(id) else {
+            return;
+        };
+        (job.status, job.message) = match result {
+            Ok(message) | Err(message) if job.cancel.load(Ordering::Relaxed) => {
+                (JobStatus::Canceled, message)
+            }
+            Ok(message) => (JobStatus::Done, message),
+            Err(message) => (JobStatus::Failed, message),
+        };
+        job.finished = Some(Instant::now());
+        let finished = self
+            .jobs
+            .iter()
+            .filter(|job| job.status.is_finished())
+            .count();
+        let mut excess = finished.saturating_sub(KEEP_FINISHED);
+        self.jobs.retain(|job| {
+            let drop = excess > 0 && job.status.is_finished();
+            excess -= usize::from(drop);
+            !drop
+        });
+    }
+
+    fn toast(&self, message: String, kind: ToastKind, duration: Duration) {
+        if let Some(bus) = self.bus.as_ref() {
+            bus.command(AppCommand::ShowToast(Toast {
+                message,
+                kind,
+                duration,
+                action: None,
+            }));
+        }
+    }
+
+    fn redraw(&self) {
+        if let Some(bus) = self.bus.as_ref() {
+            bus.command(AppCommand::Invalidate);
+        }
+    }
+}
+
+fn lower_first(label: &str) -> String {
+    let mut chars = label.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_lowercase().chain(chars).collect()
+    })
+}
+
+fn lock() -> MutexGuard<'static, Registry> {
+    JOBS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JobStatus, cancel, clear_finished, list, start};
+
+    #[test]
+    fn tracks_progress_cancellation_and_outcome() {
+        let purge = start("Purge orders");
+        let copy = start("Copy orders");
+        purge.progress("Purging orders... 10 items deleted");
+        let status = |id| list().into_iter().find(|job| job.id == id).unwrap();
+        assert_eq!(
+            status(purge.id).message,
+            "Purging orders... 10 items deleted"
+        );
+
+        assert!(cancel(purge.id));
+        assert!(!purge.cancel());
+        assert!(purge.is_cancelled());
+        assert_eq!(status(purge.id).status, JobStatus::Canceling);
+        purge.finish(Ok("Purged 10 items".to_string()));
+        assert_eq!(status(purge.id).status, JobStatus::Canceled);
+
+        copy.finish_quietly(Err("Table not found".to_string()));
+        assert_eq!(status(copy.id).status, JobStatus::Failed);
+        assert!(!cancel(copy.id));
+
+        clear_finished();
+        assert!(
+            list()
+                .iter()
+                .all(|job| job.id != purge.id && job.id != copy.id)
+        );
+    }
+}
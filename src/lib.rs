@@ -3,4 +3,6 @@ pub mod dynamodb;
 pub mod expr;
 pub mod mongo;
 pub mod readonly;
+pub mod s3;
 pub mod sql;
+pub mod throttle;
@@ -1,10 +1,20 @@
-use std::path::PathBuf;
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    path::PathBuf,
+    sync::{LazyLock, Mutex, PoisonError},
+};
 
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
+use tracing::field::{Field, Visit};
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{self, Layer, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    self, Layer,
+    layer::{Context, SubscriberExt},
+    util::SubscriberInitExt,
+};
 
 lazy_static! {
     pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().clone();
@@ -15,13 +25,97 @@ lazy_static! {
     pub static ref LOG_ENV: String = format!("{}_LOGLEVEL", PROJECT_NAME.clone());
     pub static ref LOG_STDERR_ENV: String = format!("{}_LOG_STDERR", PROJECT_NAME.clone());
     pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+    pub static ref AUDIT_FILE: String = format!("{}-audit.ndjson", env!("CARGO_PKG_NAME"));
 }
 
-fn project_directory() -> Option<ProjectDirs> {
+/// How many of the newest log records the in-app log viewer can show.
+const RECENT_CAPACITY: usize = 5000;
+
+static RECENT: LazyLock<Mutex<RecentRecords>> = LazyLock::new(Mutex::default);
+
+/// One log line as the log viewer shows it.
+#[derive(Debug, Clone)]
+pub(crate) struct LogRecord {
+    /// Local wall-clock time, to the millisecond.
+    pub(crate) at: String,
+    pub(crate) level: tracing::Level,
+    pub(crate) target: String,
+    /// The message followed by the event's other fields as `name=value`.
+    pub(crate) message: String,
+}
+
+#[derive(Default)]
+struct RecentRecords {
+    records: VecDeque<LogRecord>,
+    /// Records ever kept, so readers can tell when there are new ones.
+    total: u64,
+}
+
+/// The newest log records, oldest first.
+pub(crate) fn recent() -> Vec<LogRecord> {
+    let recent = RECENT.lock().unwrap_or_else(PoisonError::into_inner);
+    recent.records.iter().cloned().collect()
+}
+
+/// How many records have been logged so far; it changes whenever [`recent`]
+/// does.
+pub(crate) fn recent_total() -> u64 {
+    RECENT.lock().unwrap_or_else(PoisonError::into_inner).total
+}
+
+/// Keeps the events that pass the log filter in [`RECENT`], alongside the
+/// log file.
+struct MemoryLayer;
+
+impl<S: tracing::Subscriber> Layer<S> for MemoryLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let record = LogRecord {
+            at: chrono::Local::now().format("%H:%M:%S%.3f").to_string(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message + &visitor.fields,
+        };
+        let mut recent = RECENT.lock().unwrap_or_else(PoisonError::into_inner);
+        if recent.records.len() == RECENT_CAPACITY {
+            recent.records.pop_front();
+        }
+        recent.records.push_back(record);
+        recent.total += 1;
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+pub(crate) fn project_directory() -> Option<ProjectDirs> {
     ProjectDirs::from("com", "garciahierro.com", env!("CARGO_PKG_NAME"))
 }
 
-fn get_data_dir() -> PathBuf {
+pub(crate) fn get_data_dir() -> PathBuf {
     if let Some(s) = DATA_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
@@ -54,6 +148,7 @@ pub fn initialize() -> Result<()> {
         .with_filter(env_filter.clone());
     let registry = tracing_subscriber::registry()
         .with(file_subscriber)
+        .with(MemoryLayer.with_filter(env_filter.clone()))
         .with(ErrorLayer::default());
     if stderr_logging_enabled() {
         let stderr_subscriber = tracing_subscriber::fmt::layer()
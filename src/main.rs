@@ -33,7 +33,7 @@ use crossterm::event::{
     Event, EventStream, KeyCode, KeyEventKind, ModifierKeyCode, MouseButton, MouseEventKind, poll,
     read,
 };
-use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
 use ratatui::style::{Modifier, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Clear;
@@ -49,11 +49,19 @@ use std::backtrace::Backtrace;
 #[cfg(unix)]
 use tokio::signal::unix::{SignalKind, signal};
 
+mod access_patterns;
+mod clipboard;
+mod config;
 mod env;
+mod environment;
 mod help;
 mod input;
+mod jobs;
 mod logging;
+mod resume;
+mod safety;
 mod subcommands;
+mod tabs;
 mod util;
 mod widgets;
 
@@ -70,7 +78,10 @@ use crate::widgets::theme::Theme;
     name = "dynamate",
     version,
     about = "Your DynamoDB table mate",
-    long_about = None
+    long_about = None,
+    group = clap::ArgGroup::new("open_table")
+        .args(["table", "target", "table_arg"])
+        .multiple(true)
 )]
 struct Cli {
     /// Increase output verbosity (-v, -vv, etc.)
@@ -83,10 +94,15 @@ struct Cli {
 
     /// Connection target. A `mongodb://` / `mongodb+srv://` URI selects MongoDB;
     /// an `http(s)://` URL is treated as a DynamoDB endpoint. The backend is
-    /// inferred from the scheme unless `--backend` is given.
+    /// inferred from the scheme unless `--backend` is given. Anything else is
+    /// the table to open, as in `dynamate orders`.
     #[arg(value_name = "TARGET")]
     target: Option<String>,
 
+    /// Table to open directly, after a connection target
+    #[arg(value_name = "TABLE")]
+    table_arg: Option<String>,
+
     /// Storage backend to connect to (inferred from TARGET when omitted)
     #[arg(long, value_enum)]
     backend: Option<dynamate::core::connect::BackendKind>,
@@ -95,30 +111,136 @@ struct Cli {
     #[arg(long)]
     endpoint_url: Option<String>,
 
+    /// IAM role to assume for DynamoDB, e.g. to browse tables in another
+    /// account
+    #[arg(long, value_name = "ARN")]
+    role_arn: Option<String>,
+
+    /// MFA device the role's trust policy requires; the code is prompted for
+    /// unless --mfa-code is given
+    #[arg(long, value_name = "SERIAL", requires = "role_arn")]
+    mfa_serial: Option<String>,
+
+    /// Current code of the --mfa-serial device
+    #[arg(long, value_name = "CODE", requires = "mfa_serial")]
+    mfa_code: Option<String>,
+
     /// Table name to open directly
     #[arg(short, long)]
     table: Option<String>,
 
     /// Query to run immediately when opening a table
-    #[arg(long, requires = "table")]
+    #[arg(long, requires = "open_table")]
     query: Option<String>,
 
+    /// Re-run the query every SECS seconds, marking the rows that changed
+    #[arg(
+        long,
+        requires = "open_table",
+        value_name = "SECS",
+        value_parser = clap::value_parser!(u64).range(1..=86_400)
+    )]
+    watch: Option<u64>,
+
+    /// Color theme: dark, light, solarized, high-contrast, auto, or the path
+    /// of a JSON palette file (overrides `theme` in the config file)
+    #[arg(long, global = true, value_name = "THEME")]
+    theme: Option<String>,
+
+    /// Capture the mouse: click to select, double-click to open, scroll, and
+    /// click column headers to sort. Disables the terminal's text selection.
+    #[arg(long, global = true)]
+    mouse: bool,
+
     /// Disable all write operations (safe mode for production)
     #[arg(long)]
     readonly: bool,
 
+    /// Skip every confirmation prompt, for local development (overrides
+    /// `confirm` in the config file)
+    #[arg(long = "unsafe", global = true)]
+    skip_confirmations: bool,
+
+    /// Regions the table list shows at once when `R` is pressed, e.g.
+    /// `us-east-1,eu-west-1` (overrides `regions` in the config file)
+    #[arg(long, global = true, value_name = "REGIONS", value_delimiter = ',')]
+    regions: Vec<String>,
+
+    /// Limit scans, exports, and purges to this many read capacity units per
+    /// second (overrides `max_rcu` in the config file)
+    #[arg(long, global = true, value_name = "RCU")]
+    max_rcu: Option<f64>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+impl Cli {
+    /// Settle which positional is the table to open: a target that isn't a
+    /// connection URL names the table, so `dynamate orders --query ...` opens
+    /// `orders` with the query run. The table ends up in `table`.
+    fn resolve_table(&mut self) -> Result<(), clap::Error> {
+        use clap::{CommandFactory, error::ErrorKind};
+
+        let dynamodb = self
+            .backend
+            .is_none_or(|backend| backend == dynamate::core::connect::BackendKind::Dynamodb);
+        if dynamodb
+            && self.table_arg.is_none()
+            && self
+                .target
+                .as_deref()
+                .is_some_and(|target| !target.contains("://"))
+        {
+            self.table_arg = self.target.take();
+        }
+        if let Some(table) = self.table_arg.take() {
+            if self.table.is_some() {
+                return Err(Self::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "give the table as an argument or with --table, not both",
+                ));
+            }
+            self.table = Some(table);
+        }
+        if self.table.is_none() && (self.query.is_some() || self.watch.is_some()) {
+            return Err(Self::command().error(
+                ErrorKind::MissingRequiredArgument,
+                "--query and --watch need a table to open",
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(clap::Subcommand)]
 enum Commands {
-    ListTables {
-        /// Output in JSON format
-        #[arg(short, long)]
-        json: bool,
-    },
+    /// List tables, optionally filtered and with their metadata
+    ListTables(subcommands::list_tables::Args),
+    /// Create a table from flags or a JSON spec
     CreateTable(subcommands::create_table::Args),
+    /// Delete a table and all of its items
+    DeleteTable(subcommands::delete_table::Args),
+    /// Create a table with the schema of another and copy its items into it
+    CopyTable(subcommands::copy_table::Args),
+    /// Print a table's keys, indexes, capacity, and settings
+    Describe(subcommands::describe::Args),
+    /// Run a query non-interactively and print the matching items
+    Query(subcommands::query::Args),
+    /// Fetch the items for a list of primary keys
+    Get(subcommands::get::Args),
+    /// Export a table (optionally filtered) to a file or stdout
+    Export(subcommands::export::Args),
+    /// Export a table (optionally filtered) and upload it to S3
+    ExportS3(subcommands::export_s3::Args),
+    /// Load items from a JSON, NDJSON, or DynamoDB JSON file
+    Import(subcommands::import::Args),
+    /// Delete every item of a table, or those matching a filter
+    Purge(subcommands::purge::Args),
+    /// Write generated items into a table, for load tests and demos
+    Seed(subcommands::seed::Args),
+    /// Browse DynamoDB Local, starting its Docker container when needed
+    Local(subcommands::local::Args),
 }
 
 #[tokio::main]
@@ -128,16 +250,63 @@ async fn main() -> Result<()> {
         .expect("install aws-lc-rs provider");
 
     color_eyre::install()?;
-    let cli = <Cli as clap::Parser>::parse();
+    let mut cli = <Cli as clap::Parser>::parse();
+    if let Err(err) = cli.resolve_table() {
+        err.exit();
+    }
     dynamate::readonly::set(cli.readonly);
+    let config = config::load(cli.config.as_deref())?;
+    let budget = match (&cli.command, cli.max_rcu) {
+        (
+            Some(Commands::Purge(subcommands::purge::Args {
+                rate: Some(rate), ..
+            })),
+            _,
+        ) => Some(dynamate::throttle::Budget::Items(*rate)),
+        (_, Some(rcu)) => Some(dynamate::throttle::Budget::CapacityUnits(rcu)),
+        (_, None) => config.budget(),
+    };
+    dynamate::throttle::set(budget);
+    dynamate::dynamodb::retry::set(config.retry);
+    environment::set(config.environments.clone());
+    safety::set(if cli.skip_confirmations {
+        config.safety.clone().disabled()
+    } else {
+        config.safety.clone()
+    });
+    util::set_mouse_capture(cli.mouse || config.mouse);
+    util::set_regions(if cli.regions.is_empty() {
+        config.regions.clone()
+    } else {
+        cli.regions.clone()
+    });
+    util::set_query_limit(config.max_items, config.max_pages);
+    util::set_item_templates(config.templates.clone());
+    util::set_entity_rules(config.entities.clone());
+    util::set_follow_rules(config.follow.clone());
+    if let Some(editor) = &config.editor {
+        util::set_editor(editor.clone());
+    }
+    dynamate::core::audit::set(match &config.audit_log {
+        config::AuditLogSetting::Default => {
+            Some(logging::get_data_dir().join(logging::AUDIT_FILE.as_str()))
+        }
+        config::AuditLogSetting::Off => None,
+        config::AuditLogSetting::Path(path) => Some(path.clone()),
+    });
+
+    let role = role_selection(&cli)?;
 
     match cli.command {
-        Some(Commands::ListTables { json }) => {
-            let (kind, options) =
-                resolve_connection(cli.backend, cli.target.clone(), cli.endpoint_url.clone());
+        Some(Commands::ListTables(args)) => {
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
             let db = open_backend(kind, options, cli.readonly).await?;
-            let opts = subcommands::list_tables::Options { json };
-            subcommands::list_tables::command(db.as_ref(), opts).await?;
+            subcommands::list_tables::command(db.as_ref(), args).await?;
             Ok(())
         }
         Some(Commands::CreateTable(args)) => {
@@ -145,25 +314,206 @@ async fn main() -> Result<()> {
                 eprintln!("{}", dynamate::core::error::DbError::READ_ONLY_MESSAGE);
                 std::process::exit(1);
             }
-            let (kind, options) =
-                resolve_connection(cli.backend, cli.target.clone(), cli.endpoint_url.clone());
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
             let db = open_backend(kind, options, cli.readonly).await?;
             subcommands::create_table::command(db.as_ref(), args).await?;
             Ok(())
         }
+        Some(Commands::DeleteTable(args)) => {
+            if cli.readonly {
+                eprintln!("{}", dynamate::core::error::DbError::READ_ONLY_MESSAGE);
+                std::process::exit(1);
+            }
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::delete_table::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::CopyTable(args)) => {
+            if cli.readonly {
+                eprintln!("{}", dynamate::core::error::DbError::READ_ONLY_MESSAGE);
+                std::process::exit(1);
+            }
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::copy_table::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Query(args)) => {
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::query::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Describe(args)) => {
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::describe::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Get(args)) => {
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::get::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Export(args)) => {
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::export::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::ExportS3(args)) => {
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::export_s3::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Import(args)) => {
+            if cli.readonly && !args.dry_run {
+                eprintln!("{}", dynamate::core::error::DbError::READ_ONLY_MESSAGE);
+                std::process::exit(1);
+            }
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::import::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Purge(args)) => {
+            if cli.readonly && !args.dry_run {
+                eprintln!("{}", dynamate::core::error::DbError::READ_ONLY_MESSAGE);
+                std::process::exit(1);
+            }
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::purge::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Seed(args)) => {
+            if cli.readonly && !args.dry_run {
+                eprintln!("{}", dynamate::core::error::DbError::READ_ONLY_MESSAGE);
+                std::process::exit(1);
+            }
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
+            let db = open_backend(kind, options, cli.readonly).await?;
+            subcommands::seed::command(db.as_ref(), args).await?;
+            Ok(())
+        }
+        Some(Commands::Local(args)) => {
+            let settings = subcommands::local::Settings::resolve(&args, &config.local);
+            if let Some(action) = args.action {
+                return subcommands::local::command(&settings, action, cli.readonly).await;
+            }
+            let db = subcommands::local::open(&settings, cli.readonly).await?;
+            environment::connect(Some(&settings.endpoint()), None);
+            let theme = resolve_theme(cli.theme.as_deref(), &config)?;
+            logging::initialize()?;
+            App::new(theme)
+                .run_tui(db, cli.table.as_deref(), cli.query.as_deref(), cli.watch)
+                .await?;
+            Ok(())
+        }
         None => {
-            let (kind, options) =
-                resolve_connection(cli.backend, cli.target.clone(), cli.endpoint_url.clone());
+            let (kind, options) = resolve_connection(
+                cli.backend,
+                cli.target.clone(),
+                cli.endpoint_url.clone(),
+                role.clone(),
+            );
             let db = open_backend(kind, options, cli.readonly).await?;
+            let theme = resolve_theme(cli.theme.as_deref(), &config)?;
             logging::initialize()?;
-            App::default()
-                .run_tui(db, cli.table.as_deref(), cli.query.as_deref())
+            App::new(theme)
+                .run_tui(db, cli.table.as_deref(), cli.query.as_deref(), cli.watch)
                 .await?;
             Ok(())
         }
     }
 }
 
+/// The TUI's theme: `--theme`, then the config file, then `DYNAMATE_THEME`.
+fn resolve_theme(choice: Option<&str>, config: &config::Config) -> Result<Theme> {
+    Theme::resolve(choice.or(config.theme.as_deref())).map_err(|err| color_eyre::eyre::eyre!(err))
+}
+
+/// The role `--role-arn` asks to assume, prompting for the MFA code when
+/// `--mfa-serial` comes without `--mfa-code`.
+fn role_selection(cli: &Cli) -> Result<Option<dynamate::core::connect::RoleSelection>> {
+    let Some(role_arn) = cli.role_arn.clone() else {
+        return Ok(None);
+    };
+    let mfa_code = match (cli.mfa_serial.as_deref(), cli.mfa_code.clone()) {
+        (Some(serial), None) => {
+            eprint!("MFA code for {serial}: ");
+            std::io::Write::flush(&mut std::io::stderr())?;
+            let mut code = String::new();
+            std::io::stdin().read_line(&mut code)?;
+            Some(code.trim().to_string())
+        }
+        (_, code) => code,
+    };
+    Ok(Some(dynamate::core::connect::RoleSelection {
+        role_arn,
+        mfa_serial: cli.mfa_serial.clone(),
+        mfa_code,
+    }))
+}
+
 /// Resolve the backend kind and its connection options from the CLI arguments,
 /// inferring the backend from the connection target's scheme when `--backend`
 /// is not given.
@@ -171,6 +521,7 @@ fn resolve_connection(
     backend: Option<dynamate::core::connect::BackendKind>,
     target: Option<String>,
     endpoint_url: Option<String>,
+    role: Option<dynamate::core::connect::RoleSelection>,
 ) -> (
     dynamate::core::connect::BackendKind,
     dynamate::core::connect::ConnOptions,
@@ -192,7 +543,7 @@ fn resolve_connection(
                 Some(t) if t.starts_with("http://") || t.starts_with("https://") => Some(t),
                 _ => endpoint_url,
             };
-            ConnOptions::Dynamo { endpoint_url }
+            ConnOptions::Dynamo { endpoint_url, role }
         }
     };
     (kind, options)
@@ -210,6 +561,19 @@ async fn open_backend(
     db.validate()
         .await
         .map_err(|err| color_eyre::eyre::eyre!(err.to_string()))?;
+    let endpoint = match &options {
+        dynamate::core::connect::ConnOptions::Dynamo { endpoint_url, .. } => {
+            endpoint_url.as_deref()
+        }
+        dynamate::core::connect::ConnOptions::Mongo { uri } => Some(uri.as_str()),
+        dynamate::core::connect::ConnOptions::Sql { url } => Some(url.as_str()),
+    };
+    environment::connect(
+        endpoint,
+        db.active_profile()
+            .and_then(|profile| profile.profile)
+            .as_deref(),
+    );
     Ok(db)
 }
 
@@ -220,7 +584,9 @@ struct App {
     should_quit: bool,
     should_redraw: bool,
     input_grace_until: Option<Instant>,
-    widgets: Vec<Box<dyn crate::widgets::Widget>>,
+    tabs: tabs::Tabs<Box<dyn crate::widgets::Widget>>,
+    /// Backend new tabs open against; set once the app runs.
+    db: Option<Arc<dyn dynamate::core::datastore::Datastore>>,
     popup: Option<Box<dyn crate::widgets::Popup>>,
     toast: Option<ToastState>,
     toast_rect: Cell<Option<Rect>>,
@@ -231,6 +597,9 @@ struct App {
     toast_throbber: RefCell<ThrobberState>,
     last_toast_throbber_tick: Cell<Option<Instant>>,
     show_frame_render_duration: bool,
+    theme: Theme,
+    /// Where the popup was last drawn, so a click outside it can close it.
+    popup_rect: Cell<Option<Rect>>,
 }
 
 impl App {
@@ -319,6 +688,70 @@ impl App {
             alt: None,
         },
     ];
+    const HELP_WITHOUT_POPUP_CLOSE_TAB: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed(""),
+            short: Cow::Borrowed(""),
+            long: Cow::Borrowed(""),
+            ctrl: Some(help::Variant {
+                keys: Some(Cow::Borrowed("^q")),
+                short: Some(Cow::Borrowed("quit")),
+                long: Some(Cow::Borrowed("Quit dynamate")),
+            }),
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("h"),
+            short: Cow::Borrowed("help"),
+            long: Cow::Borrowed("Show help"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close tab"),
+            long: Cow::Borrowed("Close tab"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+    const HELP_TABS: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("[/]"),
+            short: Cow::Borrowed("tabs"),
+            long: Cow::Borrowed("Previous/next tab (1-9 jumps to a tab)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("T"),
+            short: Cow::Borrowed("new tab"),
+            long: Cow::Borrowed("Open the table list in a new tab"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("L"),
+            short: Cow::Borrowed("logs"),
+            long: Cow::Borrowed("Show this session's log"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("J"),
+            short: Cow::Borrowed("jobs"),
+            long: Cow::Borrowed("Show background jobs"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
     const HELP_WITH_POPUP: &'static [help::Entry<'static>] = &[
         help::Entry {
             keys: Cow::Borrowed(""),
@@ -350,8 +783,9 @@ impl App {
         },
     ];
 
-    pub fn default() -> Self {
+    pub fn new(theme: Theme) -> Self {
         let (bus, AppBusRx { cmd_rx, event_rx }) = AppBus::new();
+        jobs::init(bus.clone());
         App {
             bus,
             cmd_rx,
@@ -359,7 +793,8 @@ impl App {
             should_quit: false,
             should_redraw: true,
             input_grace_until: None,
-            widgets: Vec::new(),
+            tabs: tabs::Tabs::new(),
+            db: None,
             popup: None,
             toast: None,
             toast_rect: Cell::new(None),
@@ -371,6 +806,8 @@ impl App {
             last_toast_throbber_tick: Cell::new(None),
             show_frame_render_duration: cfg!(debug_assertions)
                 || env_flag(Self::FRAME_RENDER_DURATION_ENV),
+            theme,
+            popup_rect: Cell::new(None),
         }
     }
 
@@ -379,11 +816,12 @@ impl App {
         db: Arc<dyn dynamate::core::datastore::Datastore>,
         table_name: Option<&str>,
         initial_query: Option<&str>,
+        watch_secs: Option<u64>,
     ) -> Result<()> {
         let mut app = self;
         let terminal = ratatui::init();
         // Mouse capture disables terminal selection, so keep it opt-in for copy/paste.
-        if env_flag("DYNAMATE_MOUSE_CAPTURE") {
+        if util::mouse_capture() {
             crossterm::execute!(std::io::stdout(), crossterm::event::EnableMouseCapture)?;
         }
 
@@ -393,7 +831,9 @@ impl App {
         // Give a short grace period so those don't trigger actions at startup.
         app.input_grace_until = Some(Instant::now() + Duration::from_millis(250));
 
-        let app_result = app.run(terminal, db, table_name, initial_query).await;
+        let app_result = app
+            .run(terminal, db, table_name, initial_query, watch_secs)
+            .await;
         crossterm::execute!(std::io::stdout(), crossterm::event::DisableMouseCapture)?;
         ratatui::restore();
         app_result
@@ -405,20 +845,25 @@ impl App {
         db: Arc<dyn dynamate::core::datastore::Datastore>,
         table_name: Option<&str>,
         initial_query: Option<&str>,
+        watch_secs: Option<u64>,
     ) -> Result<()> {
         let event_driven_render = env_flag("DYNAMATE_EVENT_DRIVEN_RENDER");
         let widget: Box<dyn crate::widgets::Widget> = match (table_name, initial_query) {
-            (Some(name), Some(query)) => Box::new(widgets::QueryWidget::new_with_text_query(
-                db.clone(),
-                name,
-                query,
-                env::WidgetId::app(),
-            )),
-            (Some(name), None) => Box::new(widgets::QueryWidget::new(
-                db.clone(),
-                name,
-                env::WidgetId::app(),
-            )),
+            (Some(name), query) => {
+                let widget = match query {
+                    Some(query) => widgets::QueryWidget::new_with_text_query(
+                        db.clone(),
+                        name,
+                        query,
+                        env::WidgetId::app(),
+                    ),
+                    None => widgets::QueryWidget::new(db.clone(), name, env::WidgetId::app()),
+                };
+                match watch_secs {
+                    Some(secs) => Box::new(widget.with_watch(Duration::from_secs(secs))),
+                    None => Box::new(widget),
+                }
+            }
             (None, _) => Box::new(widgets::TablePickerWidget::new(
                 db.clone(),
                 env::WidgetId::app(),
@@ -427,7 +872,8 @@ impl App {
         let ctx = widget.inner().ctx(self.bus.clone());
         widget.start(ctx);
 
-        self.widgets.push(widget);
+        self.tabs.open(widget);
+        self.db = Some(db);
 
         let period = Duration::from_secs_f32(1.0 / Self::FRAMES_PER_SECOND);
         let mut interval = tokio::time::interval(period);
@@ -551,7 +997,7 @@ impl App {
     fn make_help(&self) -> Vec<&help::Entry<'_>> {
         let help = if let Some(popup) = self.popup.as_ref() {
             popup.help()
-        } else if let Some(widget) = self.widgets.last() {
+        } else if let Some(widget) = self.tabs.top() {
             widget.help()
         } else {
             None
@@ -568,20 +1014,19 @@ impl App {
             } else {
                 App::HELP_WITH_POPUP
             }
-        } else if self
-            .widgets
-            .last()
-            .is_some_and(|w| w.suppress_global_help())
-        {
+        } else if self.tabs.top().is_some_and(|w| w.suppress_global_help()) {
             &[]
         } else if export_cancel_active || self.widget_declares_esc() {
             App::HELP_WITHOUT_POPUP_NO_ESC
-        } else if self.widgets.len() > 1 {
+        } else if self.tabs.active().len() > 1 {
             App::HELP_WITHOUT_POPUP_BACK
+        } else if self.tabs.len() > 1 {
+            App::HELP_WITHOUT_POPUP_CLOSE_TAB
         } else {
             App::HELP_WITHOUT_POPUP_EXIT
         };
-        let entries: Vec<&help::Entry<'_>> = [help, Some(app_help)]
+        let tab_help = (self.popup.is_none() && !app_help.is_empty()).then_some(App::HELP_TABS);
+        let entries: Vec<&help::Entry<'_>> = [help, Some(app_help), tab_help]
             .into_iter()
             .flatten()
             .flatten()
@@ -604,21 +1049,21 @@ impl App {
 
     fn render(&mut self, frame: &mut Frame) {
         let start = Instant::now();
-        let theme = Theme::default();
+        let theme = self.theme;
         let area = frame.area();
         let buf = frame.buffer_mut();
         fill_bg(buf, area, theme.bg());
         self.toast_rect.set(None);
         let loading_line = self
-            .widgets
-            .last()
+            .tabs
+            .top()
             .is_some_and(|w| w.is_loading())
             .then(|| self.loading_indicator_line(&theme));
         let all_help = self.make_help();
         let modifiers = self.modifiers;
         let help_mode = self.help_mode;
-        let help_height = help::height(&all_help, frame.area(), modifiers, help_mode);
-        let status = self.widgets.last().map(|w| w.status()).unwrap_or_default();
+        let help_height = help::height(&all_help, frame.area(), &theme, modifiers, help_mode);
+        let status = self.tabs.top().map(|w| w.status()).unwrap_or_default();
         let layout = Layout::vertical([
             Constraint::Length(1),
             Constraint::Fill(1),
@@ -627,7 +1072,8 @@ impl App {
         ]);
         let [title_area, body_area, status_area, footer_area] = frame.area().layout(&layout);
 
-        // Title bar: "dynamate" (+ READ-ONLY badge) on the left, table context on the right.
+        // Title bar: "dynamate" (+ READ-ONLY and environment badges) on the left, table
+        // context on the right.
         let mut title_spans = vec![Span::styled(
             "dynamate",
             Style::default()
@@ -642,6 +1088,25 @@ impl App {
                     .add_modifier(Modifier::BOLD),
             ));
         }
+        if let Some(environment) = environment::current() {
+            let color = match environment {
+                environment::Environment::Prod => theme.error(),
+                environment::Environment::Staging => theme.warning(),
+                environment::Environment::Dev => theme.success(),
+            };
+            title_spans.push(Span::raw("  "));
+            title_spans.push(Span::styled(
+                format!(" {} ", environment.label()),
+                Style::default()
+                    .fg(theme.bg())
+                    .bg(color)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        if self.tabs.len() > 1 {
+            title_spans.push(Span::raw("  "));
+            title_spans.extend(self.tab_spans(&theme));
+        }
         frame.render_widget(Line::from(title_spans), title_area);
         if let Some(context) = status.context.as_deref() {
             frame.render_widget(
@@ -673,12 +1138,20 @@ impl App {
         if !status_spans.is_empty() {
             frame.render_widget(Line::from(status_spans), status_area);
         }
-        if let Some(line) = loading_line {
+        if dynamate::dynamodb::retry::is_retrying() {
+            let mut spans = vec![Span::styled(
+                "throttled, retrying…",
+                Style::default().fg(theme.warning()),
+            )];
+            spans.extend(loading_line.map_or_else(|| vec![Span::raw(" ")], |line| line.spans));
+            frame.render_widget(Line::from(spans).right_aligned(), status_area);
+        } else if let Some(line) = loading_line {
             frame.render_widget(line.right_aligned(), status_area);
         }
-        if let Some(widget) = self.widgets.last() {
+        if let Some(widget) = self.tabs.top() {
             let back_title = self
-                .widgets
+                .tabs
+                .active()
                 .iter()
                 .rev()
                 .nth(1)
@@ -688,6 +1161,7 @@ impl App {
         }
         if let Some(popup) = self.popup.as_ref() {
             let popup_area = popup.rect(body_area);
+            self.popup_rect.set(Some(popup_area));
             frame.render_widget(Clear, popup_area);
             popup.render_with_nav(frame, popup_area, &theme, &widgets::NavContext::default());
         }
@@ -699,8 +1173,13 @@ impl App {
         help::render(&all_help, frame, footer_area, &theme, modifiers, help_mode);
         if self.show_frame_render_duration {
             let duration = start.elapsed();
-            // Render duration in red at the bottom right corner
-            let duration_str = format!("{duration:.2?}");
+            // Render duration (and throttled retries) in red at the bottom
+            // right corner
+            let mut duration_str = format!("{duration:.2?}");
+            let retries = dynamate::dynamodb::retry::retries();
+            if retries > 0 {
+                duration_str.push_str(&format!("  {retries} retries"));
+            }
             let area = frame.area();
             let len = duration_str.len();
             let x = area.x + area.width.saturating_sub(len as u16 + 1);
@@ -759,6 +1238,20 @@ impl App {
             }
         }
 
+        if let Some(mouse) = event.as_mouse_event()
+            && matches!(mouse.kind, MouseEventKind::Down(_))
+            && self.popup.is_some()
+            && self
+                .popup_rect
+                .get()
+                .is_some_and(|rect| !rect.contains(Position::new(mouse.column, mouse.row)))
+        {
+            self.popup = None;
+            self.popup_rect.set(None);
+            self.should_redraw = true;
+            return true;
+        }
+
         if let Some(popup) = self.popup.as_ref()
             && popup.handle_event(self.make_ctx(popup.as_ref()), event)
         {
@@ -774,7 +1267,7 @@ impl App {
             return true;
         }
 
-        if let Some(widget) = self.widgets.last()
+        if let Some(widget) = self.tabs.top()
             && widget.handle_event(self.make_ctx(widget.as_ref()), event)
         {
             return true;
@@ -811,22 +1304,34 @@ impl App {
         if let Some(key) = event.as_key_press_event() {
             match key.code {
                 KeyCode::Char('h') => {
-                    if self
-                        .widgets
-                        .last()
-                        .is_some_and(|w| w.suppress_global_help())
-                    {
+                    if self.tabs.top().is_some_and(|w| w.suppress_global_help()) {
                         return true;
                     }
                     self.popup = Some(Box::new(help::Widget::new(
                         self.make_help(),
                         self.modifiers,
                         ModDisplay::Both,
-                        self.widgets
-                            .last()
-                            .map_or_else(env::WidgetId::app, |w| w.id()),
+                        self.tabs.top().map_or_else(env::WidgetId::app, |w| w.id()),
                     )));
                 }
+                KeyCode::Char('[') => {
+                    self.tabs.previous();
+                    self.should_redraw = true;
+                }
+                KeyCode::Char(']') => {
+                    self.tabs.next();
+                    self.should_redraw = true;
+                }
+                KeyCode::Char(digit @ '1'..='9') if self.tabs.len() > 1 => {
+                    let index = digit as usize - '1' as usize;
+                    if !self.tabs.select(index) {
+                        return false;
+                    }
+                    self.should_redraw = true;
+                }
+                KeyCode::Char('T') => self.open_table_list_tab(),
+                KeyCode::Char('L') => self.open_log_viewer(),
+                KeyCode::Char('J') => self.open_jobs(),
                 KeyCode::Esc => {
                     if self.popup.is_some() {
                         self.popup = None;
@@ -838,9 +1343,8 @@ impl App {
                         self.toast_rect.set(None);
                         self.last_toast_throbber_tick.set(None);
                         self.should_redraw = true;
-                    } else if self.widgets.len() > 1 {
-                        self.widgets.pop();
-                        self.should_redraw = true;
+                    } else if self.tabs.len() > 1 || self.tabs.active().len() > 1 {
+                        self.pop_widget(self.tabs.active_index());
                     } else {
                         self.should_quit = true;
                     }
@@ -853,7 +1357,7 @@ impl App {
     }
 
     fn widget_declares_esc(&self) -> bool {
-        let Some(widget) = self.widgets.last() else {
+        let Some(widget) = self.tabs.top() else {
             return false;
         };
         let Some(entries) = widget.help() else {
@@ -866,6 +1370,100 @@ impl App {
         widget.inner().ctx(self.bus.clone())
     }
 
+    fn start_widget(&self, widget: &dyn crate::widgets::Widget) {
+        let ctx = self.make_ctx(widget);
+        ctx.emit_self(WidgetEvent::Started { id: widget.id() });
+        ctx.broadcast_event(WidgetEvent::Created {
+            id: widget.id(),
+            parent: ctx.parent.clone(),
+        });
+        widget.start(ctx);
+    }
+
+    /// Pop the top widget of `tab`, closing the tab once it is empty and
+    /// quitting once no tabs are left.
+    fn pop_widget(&mut self, tab: usize) {
+        if let Some(widget) = self.tabs.pop(tab) {
+            let ctx = self.make_ctx(widget.as_ref());
+            ctx.broadcast_event(WidgetEvent::Closed { id: widget.id() });
+        }
+        if self.tabs.is_empty() {
+            self.should_quit = true;
+        } else {
+            self.should_redraw = true;
+        }
+    }
+
+    fn open_table_list_tab(&mut self) {
+        let Some(db) = self.db.clone() else {
+            return;
+        };
+        let widget: Box<dyn crate::widgets::Widget> =
+            Box::new(widgets::TablePickerWidget::new(db, env::WidgetId::app()));
+        self.start_widget(widget.as_ref());
+        self.tabs.open(widget);
+        self.should_redraw = true;
+    }
+
+    /// Push the log viewer onto the active tab, unless it is already on top.
+    fn open_log_viewer(&mut self) {
+        let Some(top) = self.tabs.top() else {
+            return;
+        };
+        if top.navigation_title().as_deref() == Some("Logs") {
+            return;
+        }
+        let widget: Box<dyn crate::widgets::Widget> =
+            Box::new(widgets::log_viewer::LogViewerWidget::new(top.id()));
+        self.start_widget(widget.as_ref());
+        self.tabs.push(self.tabs.active_index(), widget);
+        self.should_redraw = true;
+    }
+
+    fn open_jobs(&mut self) {
+        let Some(top) = self.tabs.top() else {
+            return;
+        };
+        if top.navigation_title().as_deref() == Some("Jobs") {
+            return;
+        }
+        let widget: Box<dyn crate::widgets::Widget> =
+            Box::new(widgets::jobs::JobsWidget::new(top.id()));
+        self.start_widget(widget.as_ref());
+        self.tabs.push(self.tabs.active_index(), widget);
+        self.should_redraw = true;
+    }
+
+    /// Tab labels for the title bar: the number to jump to each tab and the
+    /// title of the widget on top of it.
+    fn tab_spans(&self, theme: &Theme) -> Vec<Span<'static>> {
+        const MAX_TITLE: usize = 24;
+        self.tabs
+            .stacks()
+            .enumerate()
+            .map(|(index, stack)| {
+                let title = stack
+                    .last()
+                    .and_then(|w| w.navigation_title())
+                    .unwrap_or_default();
+                let title: String = if title.chars().count() > MAX_TITLE {
+                    title.chars().take(MAX_TITLE - 1).chain(['…']).collect()
+                } else {
+                    title
+                };
+                let style = if index == self.tabs.active_index() {
+                    Style::default()
+                        .fg(theme.selection_fg())
+                        .bg(theme.selection_bg())
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text_muted())
+                };
+                Span::styled(format!(" {}:{title} ", index + 1), style)
+            })
+            .collect()
+    }
+
     fn update_help_modifiers(&mut self) {
         let polled = input::poll_modifiers(self.modifiers);
         if polled != self.modifiers {
@@ -884,7 +1482,7 @@ impl App {
     }
 
     fn process_widget_self_events(&mut self) {
-        for widget in &self.widgets {
+        for widget in self.tabs.iter() {
             let ctx = self.make_ctx(widget.as_ref());
             for event in widget.inner().drain_self_events() {
                 widget.on_self_event(ctx.clone(), &event);
@@ -925,7 +1523,7 @@ impl App {
                 }
             }
         }
-        for widget in &self.widgets {
+        for widget in self.tabs.iter() {
             let ctx = self.make_ctx(widget.as_ref());
             widget.on_app_event(ctx, event);
         }
@@ -938,27 +1536,28 @@ impl App {
     fn handle_cmd(&mut self, cmd: AppCommand) {
         match cmd {
             AppCommand::PushWidget(widget) => {
-                let ctx = self.make_ctx(widget.as_ref());
-                ctx.emit_self(WidgetEvent::Started { id: widget.id() });
-                ctx.broadcast_event(WidgetEvent::Created {
-                    id: widget.id(),
-                    parent: ctx.parent.clone(),
-                });
-                widget.start(ctx);
-                self.widgets.push(widget);
+                // Push onto the tab of the widget that opened it, which may
+                // no longer be the active one.
+                let parent = widget.inner().parent();
+                let tab = self
+                    .tabs
+                    .position(|w| w.id() == parent)
+                    .unwrap_or(self.tabs.active_index());
+                self.start_widget(widget.as_ref());
+                self.tabs.push(tab, widget);
                 self.should_redraw = true;
             }
-            AppCommand::PopWidget => {
-                let popped = self.widgets.pop();
-                if let Some(widget) = popped.as_ref() {
-                    let ctx = self.make_ctx(widget.as_ref());
-                    ctx.broadcast_event(WidgetEvent::Closed { id: widget.id() });
-                }
-                if self.widgets.is_empty() {
-                    self.should_quit = true;
-                } else {
-                    self.should_redraw = true;
-                }
+            AppCommand::PopWidget(source) => {
+                let tab = self
+                    .tabs
+                    .position(|w| w.id() == source)
+                    .unwrap_or(self.tabs.active_index());
+                self.pop_widget(tab);
+            }
+            AppCommand::OpenTab(widget) => {
+                self.start_widget(widget.as_ref());
+                self.tabs.open(widget);
+                self.should_redraw = true;
             }
             AppCommand::SetPopup(popup) => {
                 assert!(self.popup.is_none(), "popup is already set");
@@ -1170,8 +1769,8 @@ impl App {
             .as_ref()
             .is_some_and(|toast| is_export_progress_toast(&toast.message))
             && self
-                .widgets
-                .last()
+                .tabs
+                .top()
                 .is_some_and(|widget| widget.esc_cancels_export())
     }
 
@@ -1209,7 +1808,7 @@ impl App {
 
     fn handle_toast_action(&mut self, action: &ToastAction) {
         match action {
-            ToastAction::CopyPath { value, .. } => match copy_to_clipboard(value) {
+            ToastAction::CopyPath { value, .. } => match clipboard::copy(value) {
                 Ok(()) => {
                     self.toast = Some(ToastState::from(Toast {
                         message: "Path copied to clipboard".to_string(),
@@ -1229,17 +1828,22 @@ impl App {
                     self.should_redraw = true;
                 }
             },
+            ToastAction::RestoreDeleted { .. } => {
+                self.toast = None;
+                self.should_redraw = true;
+                let Some(db) = self.db.clone() else {
+                    return;
+                };
+                let bus = self.bus.clone();
+                widgets::undo::restore_last(db, move |restored| {
+                    bus.command(AppCommand::ShowToast(restored.toast()));
+                    bus.broadcast(AppEvent::new(env::WidgetId::app(), restored));
+                });
+            }
         }
     }
 }
 
-fn copy_to_clipboard(text: &str) -> Result<(), String> {
-    let mut clipboard = arboard::Clipboard::new().map_err(|err| err.to_string())?;
-    clipboard
-        .set_text(text.to_string())
-        .map_err(|err| err.to_string())
-}
-
 fn drain_pending_input() -> Result<()> {
     let mut drained = 0;
     while poll(Duration::from_millis(0))? {
@@ -1377,6 +1981,34 @@ mod tests {
         assert!(cli.command.is_none());
     }
 
+    #[test]
+    fn positional_table_opens_directly() {
+        let mut cli = Cli::try_parse_from(["dynamate", "orders", "--query", "pk = foo"]).unwrap();
+        cli.resolve_table().unwrap();
+        assert_eq!(cli.table.as_deref(), Some("orders"));
+        assert_eq!(cli.target, None);
+        assert_eq!(cli.query.as_deref(), Some("pk = foo"));
+
+        let mut cli = Cli::try_parse_from(["dynamate", "http://localhost:8000", "orders"]).unwrap();
+        cli.resolve_table().unwrap();
+        assert_eq!(cli.target.as_deref(), Some("http://localhost:8000"));
+        assert_eq!(cli.table.as_deref(), Some("orders"));
+
+        let mut cli =
+            Cli::try_parse_from(["dynamate", "mongodb://localhost", "--query", "x"]).unwrap();
+        assert!(cli.resolve_table().is_err());
+        let mut cli = Cli::try_parse_from(["dynamate", "orders", "--table", "users"]).unwrap();
+        assert!(cli.resolve_table().is_err());
+    }
+
+    #[test]
+    fn watch_needs_a_table_and_a_positive_interval() {
+        let cli = Cli::try_parse_from(["dynamate", "--table", "orders", "--watch", "5"]).unwrap();
+        assert_eq!(cli.watch, Some(5));
+        assert!(Cli::try_parse_from(["dynamate", "--watch", "5"]).is_err());
+        assert!(Cli::try_parse_from(["dynamate", "--table", "orders", "--watch", "0"]).is_err());
+    }
+
     #[test]
     fn readonly_flag_defaults_to_false() {
         let cli = Cli::try_parse_from(["dynamate"]).unwrap();
@@ -1389,6 +2021,17 @@ mod tests {
         assert!(cli.readonly);
     }
 
+    #[test]
+    fn unsafe_flag_parses_after_a_subcommand() {
+        let cli = Cli::try_parse_from(["dynamate", "delete-table", "orders", "--unsafe"]).unwrap();
+        assert!(cli.skip_confirmations);
+        assert!(
+            !Cli::try_parse_from(["dynamate"])
+                .unwrap()
+                .skip_confirmations
+        );
+    }
+
     #[test]
     fn cli_version_matches_package_version() {
         let cmd = Cli::command();
@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use futures::TryStreamExt;
 use mongodb::{
     Collection, Database, IndexModel,
-    bson::{Document, doc},
+    bson::{Bson, Document, doc},
     options::{Hint, IndexOptions},
 };
 
@@ -13,8 +13,8 @@ use crate::core::datastore::Datastore;
 use crate::core::error::{DbError, Result};
 use crate::core::language::QueryLanguage;
 use crate::core::query::{
-    BatchDeleteOutcome, CreateCollectionSpec, Cursor, IndexHint, Key, Page, PlanKind, QueryPlan,
-    QueryResult,
+    BatchDeleteOutcome, CreateCollectionSpec, Cursor, IndexHint, ItemUpdate, Key, Page, PlanKind,
+    QueryPlan, QueryResult,
 };
 use crate::core::schema::{
     CollectionSchema, IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, ScalarType,
@@ -38,6 +38,21 @@ const CAPABILITIES: Capabilities = Capabilities {
     scanned_count: false,
     consumed_capacity: false,
     raw_query: false,
+    statements: false,
+    profiles: false,
+    parallel_scan: false,
+    index_management: false,
+    capacity: false,
+    partial_update: true,
+    read_options: false,
+    backups: false,
+    batch_get: false,
+    transactions: false,
+    regions: false,
+    replicas: false,
+    imports: false,
+    metrics: false,
+    typed_keys: false,
 };
 
 /// Documents per `$or` chunk in a batch delete (keeps the command well under
@@ -238,6 +253,39 @@ impl Datastore for MongoBackend {
         }
     }
 
+    async fn update_item(&self, name: &str, key: Key, update: &ItemUpdate) -> Result<()> {
+        self.ensure_writable()?;
+        if update.expression.is_some() {
+            return Err(DbError::Unsupported("update expressions on MongoDB"));
+        }
+        if update.is_empty() {
+            return Ok(());
+        }
+        let mut changes = Document::new();
+        if !update.set.is_empty() {
+            changes.insert("$set", item_to_document(&update.set));
+        }
+        if !update.remove.is_empty() {
+            let unset: Document = update
+                .remove
+                .iter()
+                .map(|field| (field.clone(), Bson::String(String::new())))
+                .collect();
+            changes.insert("$unset", unset);
+        }
+        let outcome = self
+            .collection(name)
+            .update_one(item_to_document(&key.0), changes)
+            .await
+            .map_err(|err| DbError::Backend(err.to_string()))?;
+        if outcome.matched_count == 0 {
+            return Err(DbError::Backend(
+                "the document no longer exists".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     async fn delete_item(&self, name: &str, key: Key) -> Result<()> {
         self.ensure_writable()?;
         let key_doc = item_to_document(&key.0);
@@ -3,11 +3,12 @@
 
 use mongodb::bson::{Bson, Document};
 
+use crate::core::json::item_from_json;
 use crate::core::language::{
-    Completion, CompletionRequest, QueryLanguage, QueryStatus, ReferenceSection, Suggestion,
-    SuggestionKind, TokenSpan,
+    Completion, CompletionRequest, Condition, ConditionOp, ConditionValue, QueryLanguage,
+    QueryStatus, ReferenceSection, Suggestion, SuggestionKind, TokenSpan,
 };
-use crate::core::query::PlanKind;
+use crate::core::query::{ItemUpdate, PlanKind};
 use crate::core::schema::CollectionSchema;
 
 /// MongoDB query operators offered by autocompletion / the reference popup.
@@ -131,6 +132,58 @@ impl QueryLanguage for MongoLanguage {
             },
         ]
     }
+
+    fn build_query(&self, conditions: &[Condition]) -> Result<String, String> {
+        let mut clauses: Vec<serde_json::Value> = conditions.iter().map(condition_clause).collect();
+        let filter = if clauses.len() == 1 {
+            clauses.remove(0)
+        } else if clauses.is_empty() {
+            return Ok(String::new());
+        } else {
+            serde_json::json!({ "$and": clauses })
+        };
+        Ok(filter.to_string())
+    }
+
+    fn update_placeholder(&self) -> Option<&'static str> {
+        Some(r#"{ "$set": { "status": "done" }, "$unset": { "lock": "" } }"#)
+    }
+
+    fn parse_update(&self, text: &str) -> Result<ItemUpdate, String> {
+        let json: serde_json::Value =
+            serde_json::from_str(text.trim()).map_err(|err| err.to_string())?;
+        let serde_json::Value::Object(operators) = json else {
+            return Err("update must be a JSON object".to_string());
+        };
+        let mut update = ItemUpdate::default();
+        for (operator, fields) in &operators {
+            match (operator.as_str(), fields) {
+                ("$set", fields) => {
+                    update.set = item_from_json(fields).map_err(|err| err.to_string())?;
+                }
+                ("$unset", serde_json::Value::Object(fields)) => {
+                    update.remove = fields.keys().cloned().collect();
+                }
+                ("$unset", serde_json::Value::Array(fields)) => {
+                    update.remove = fields
+                        .iter()
+                        .map(|field| {
+                            field
+                                .as_str()
+                                .map(str::to_owned)
+                                .ok_or_else(|| "$unset takes field names".to_string())
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                ("$unset", _) => return Err("$unset takes an object or an array".to_string()),
+                (operator, _) => return Err(format!("unsupported update operator {operator}")),
+            }
+        }
+        if update.is_empty() {
+            return Err("update needs $set or $unset".to_string());
+        }
+        Ok(update)
+    }
 }
 
 /// Parse the query-box text into a MongoDB filter document (extended JSON
@@ -147,6 +200,49 @@ pub fn parse_filter(text: &str) -> Result<Document, String> {
     }
 }
 
+/// One builder condition as a filter document.
+fn condition_clause(condition: &Condition) -> serde_json::Value {
+    let value = match &condition.value {
+        ConditionValue::String(text) => serde_json::Value::from(text.clone()),
+        ConditionValue::Number(text) => {
+            serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::from(text.clone()))
+        }
+        ConditionValue::Boolean(value) => serde_json::Value::from(*value),
+    };
+    let text = match &condition.value {
+        ConditionValue::String(text) | ConditionValue::Number(text) => text.clone(),
+        ConditionValue::Boolean(value) => value.to_string(),
+    };
+    let test = match condition.op {
+        ConditionOp::Equal => value,
+        ConditionOp::NotEqual => serde_json::json!({ "$ne": value }),
+        ConditionOp::Less => serde_json::json!({ "$lt": value }),
+        ConditionOp::LessOrEqual => serde_json::json!({ "$lte": value }),
+        ConditionOp::Greater => serde_json::json!({ "$gt": value }),
+        ConditionOp::GreaterOrEqual => serde_json::json!({ "$gte": value }),
+        ConditionOp::BeginsWith => {
+            serde_json::json!({ "$regex": format!("^{}", escape_regex(&text)) })
+        }
+        ConditionOp::Contains => serde_json::json!({ "$regex": escape_regex(&text) }),
+        ConditionOp::Exists => serde_json::json!({ "$exists": true }),
+        ConditionOp::NotExists => serde_json::json!({ "$exists": false }),
+    };
+    let mut clause = serde_json::Map::new();
+    clause.insert(condition.attribute.clone(), test);
+    serde_json::Value::Object(clause)
+}
+
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
 fn plan_kind_for(
     map: &serde_json::Map<String, serde_json::Value>,
     schema: Option<&CollectionSchema>,
@@ -197,6 +293,7 @@ fn token_span(input: &str, cursor: usize) -> TokenSpan {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::value::Value;
 
     fn req<'a>(
         text: &'a str,
@@ -213,6 +310,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn update_documents_become_item_updates() {
+        let update = MongoLanguage
+            .parse_update(r#"{"$set": {"status": "done", "n": 2}, "$unset": ["lock"]}"#)
+            .unwrap();
+        assert_eq!(update.set.get("status"), Some(&Value::from("done")));
+        assert_eq!(update.set.get("n"), Some(&Value::from(2)));
+        assert_eq!(update.remove, vec!["lock".to_string()]);
+        assert!(MongoLanguage.parse_update(r#"{"$inc": {"n": 1}}"#).is_err());
+        assert!(MongoLanguage.parse_update("{}").is_err());
+    }
+
+    #[test]
+    fn builder_conditions_become_a_filter_document() {
+        let condition = |attribute: &str, op, value: &str| Condition {
+            attribute: attribute.to_string(),
+            op,
+            value: ConditionValue::infer(value),
+        };
+        let single = MongoLanguage
+            .build_query(&[condition("age", ConditionOp::Greater, "21")])
+            .unwrap();
+        assert_eq!(single, r#"{"age":{"$gt":21}}"#);
+        let both = MongoLanguage
+            .build_query(&[
+                condition("name", ConditionOp::BeginsWith, "a.b"),
+                condition("active", ConditionOp::Equal, "true"),
+            ])
+            .unwrap();
+        assert_eq!(
+            both,
+            r#"{"$and":[{"name":{"$regex":"^a\\.b"}},{"active":true}]}"#
+        );
+        assert!(matches!(
+            MongoLanguage.validate(&both, None),
+            QueryStatus::Valid { .. }
+        ));
+    }
+
     #[test]
     fn empty_is_empty_status() {
         assert!(matches!(
@@ -0,0 +1,295 @@
+//! Checkpoints of the table picker's copies, renames, and purges, saved to
+//! `jobs.json` in the data directory while they run. When dynamate is
+//! restarted after a crash or Ctrl-C, the picker offers to carry on with the
+//! ones that didn't finish.
+//!
+//! Checkpoints are kept per connection, at most one of each kind, like the
+//! picker runs them. Saves closer together than [`SAVE_INTERVAL`] are
+//! skipped: resuming from an earlier checkpoint only redoes some work.
+//!
+//! Exports aren't checkpointed. One runs from the query view with its query,
+//! parameters, projection, and selection, which the picker can't rebuild on
+//! its own, and its scan reports items rather than the key each segment
+//! stopped at. An interrupted NDJSON export keeps the lines written so far;
+//! running it again starts over.
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use dynamate::core::{copy::CopyCheckpoint, datastore::Datastore, rename::RenameJob};
+use serde_json::{Map, Value as Json};
+
+use crate::{environment, logging};
+
+const FILE: &str = "jobs.json";
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+static STATE: Mutex<State> = Mutex::new(State {
+    offered: Vec::new(),
+    saved: Vec::new(),
+});
+
+struct State {
+    /// Connections whose checkpoints were already offered this session.
+    offered: Vec<String>,
+    /// When each connection's checkpoint of each kind was last saved.
+    saved: Vec<(String, Kind, Instant)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Copy,
+    Rename,
+    Purge,
+}
+
+impl Kind {
+    fn key(self) -> &'static str {
+        match self {
+            Self::Copy => "copy",
+            Self::Rename => "rename",
+            Self::Purge => "purge",
+        }
+    }
+}
+
+/// Where an interrupted job stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Checkpoint {
+    Copy(CopyCheckpoint),
+    Rename(RenameJob),
+    /// Purges start over, deleting whatever is left.
+    Purge {
+        table: String,
+        deleted: u64,
+    },
+}
+
+impl Checkpoint {
+    pub fn kind(&self) -> Kind {
+        match self {
+            Self::Copy(_) => Kind::Copy,
+            Self::Rename(_) => Kind::Rename,
+            Self::Purge { .. } => Kind::Purge,
+        }
+    }
+
+    /// One line for the resume prompt.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Copy(checkpoint) => format!(
+                "Copy {} to {} ({} items copied)",
+                checkpoint.source, checkpoint.destination, checkpoint.copied
+            ),
+            Self::Rename(job) => {
+                let delete = if job.drop_source {
+                    format!(", then delete {}", job.source)
+                } else {
+                    String::new()
+                };
+                format!(
+                    "Rename {} to {}{delete} (stopped while {})",
+                    job.source,
+                    job.destination,
+                    job.step.label()
+                )
+            }
+            Self::Purge { table, deleted } => {
+                format!("Purge {table} ({deleted} items deleted)")
+            }
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        match self {
+            Self::Copy(checkpoint) => checkpoint.to_json(),
+            Self::Rename(job) => job.to_json(),
+            Self::Purge { table, deleted } => {
+                let mut map = Map::new();
+                map.insert("table".to_string(), Json::from(table.clone()));
+                map.insert("deleted".to_string(), Json::from(*deleted));
+                Json::Object(map)
+            }
+        }
+    }
+
+    fn from_json(kind: &str, value: &Json) -> Option<Self> {
+        match kind {
+            "copy" => CopyCheckpoint::from_json(value).ok().map(Self::Copy),
+            "rename" => RenameJob::from_json(value).ok().map(Self::Rename),
+            "purge" => Some(Self::Purge {
+                table: value.get("table")?.as_str()?.to_string(),
+                deleted: value.get("deleted").and_then(Json::as_u64).unwrap_or(0),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// What checkpoints made against `db` are saved under: the endpoint, and the
+/// profile and region on AWS.
+pub fn connection(db: &dyn Datastore) -> String {
+    let endpoint = environment::endpoint().unwrap_or_else(|| "aws".to_string());
+    match db.active_profile() {
+        Some(profile) => format!("{endpoint} {}", profile.label()),
+        None => endpoint,
+    }
+}
+
+/// Record where a job on `connection` has got to, replacing the checkpoint
+/// of the same kind.
+pub fn save(connection: &str, checkpoint: &Checkpoint) {
+    let kind = checkpoint.kind();
+    let mut state = lock();
+    let now = Instant::now();
+    match state
+        .saved
+        .iter_mut()
+        .find(|(saved, saved_kind, _)| saved == connection && *saved_kind == kind)
+    {
+        Some((_, _, at)) if now.duration_since(*at) < SAVE_INTERVAL => return,
+        Some((_, _, at)) => *at = now,
+        None => state.saved.push((connection.to_string(), kind, now)),
+    }
+    update(|root| set(root, connection, Some(checkpoint), kind));
+}
+
+/// Forget the checkpoint of `kind` on `connection`, once its job finished.
+pub fn clear(connection: &str, kind: Kind) {
+    let mut state = lock();
+    state
+        .saved
+        .retain(|(saved, saved_kind, _)| saved != connection || *saved_kind != kind);
+    update(|root| set(root, connection, None, kind));
+}
+
+/// The checkpoints saved for `connection`, the first time it is asked for
+/// this session; empty after that.
+pub fn unoffered(connection: &str) -> Vec<Checkpoint> {
+    let mut state = lock();
+    if state.offered.iter().any(|offered| offered == connection) {
+        return Vec::new();
+    }
+    state.offered.push(connection.to_string());
+    checkpoints(&read(), connection)
+}
+
+fn checkpoints(root: &Map<String, Json>, connection: &str) -> Vec<Checkpoint> {
+    root.get(connection)
+        .and_then(Json::as_object)
+        .map(|saved| {
+            saved
+                .iter()
+                .filter_map(|(kind, value)| Checkpoint::from_json(kind, value))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn set(
+    root: &mut Map<String, Json>,
+    connection: &str,
+    checkpoint: Option<&Checkpoint>,
+    kind: Kind,
+) {
+    let saved = root
+        .entry(connection.to_string())
+        .or_insert_with(|| Json::Object(Map::new()));
+    if let Json::Object(saved) = saved {
+        match checkpoint {
+            Some(checkpoint) => {
+                saved.insert(kind.key().to_string(), checkpoint.to_json());
+            }
+            None => {
+                saved.remove(kind.key());
+            }
+        }
+    }
+    if root
+        .get(connection)
+        .and_then(Json::as_object)
+        .is_some_and(Map::is_empty)
+    {
+        root.remove(connection);
+    }
+}
+
+/// Read, change, and write back the saved checkpoints. Called with the state
+/// locked, so saves from concurrent jobs don't overwrite each other.
+fn update(change: impl FnOnce(&mut Map<String, Json>)) {
+    let mut root = read();
+    change(&mut root);
+    let path = path();
+    let temp = path.with_extension("json.tmp");
+    let result = std::fs::write(&temp, Json::Object(root).to_string())
+        .and_then(|()| std::fs::rename(&temp, &path));
+    if let Err(err) = result {
+        tracing::warn!(path = %path.display(), error = %err, "job_checkpoint_save_failed");
+    }
+}
+
+fn read() -> Map<String, Json> {
+    std::fs::read_to_string(path())
+        .ok()
+        .and_then(|text| serde_json::from_str::<Json>(&text).ok())
+        .and_then(|value| match value {
+            Json::Object(root) => Some(root),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn path() -> PathBuf {
+    logging::get_data_dir().join(FILE)
+}
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::{
+        copy::CopyCheckpoint,
+        rename::{RenameJob, RenameStep},
+    };
+    use serde_json::Map;
+
+    use super::{Checkpoint, Kind, checkpoints, set};
+
+    #[test]
+    fn keeps_one_checkpoint_of_each_kind_per_connection() {
+        let mut root = Map::new();
+        let mut rename = RenameJob::new("orders", "orders-v2", true, 2);
+        let mut copied = CopyCheckpoint::new("orders", "orders-v2", 2);
+        copied.copied = 40;
+        rename.step = RenameStep::Copy(copied);
+        let purge = Checkpoint::Purge {
+            table: "events".to_string(),
+            deleted: 10,
+        };
+        set(
+            &mut root,
+            "aws prod",
+            Some(&Checkpoint::Rename(rename.clone())),
+            Kind::Rename,
+        );
+        set(&mut root, "aws prod", Some(&purge), Kind::Purge);
+        let later = Checkpoint::Purge {
+            table: "events".to_string(),
+            deleted: 25,
+        };
+        set(&mut root, "aws prod", Some(&later), Kind::Purge);
+
+        let saved = checkpoints(&root, "aws prod");
+        assert_eq!(saved.len(), 2);
+        assert!(saved.contains(&Checkpoint::Rename(rename)));
+        assert!(saved.contains(&later));
+        assert!(checkpoints(&root, "aws dev").is_empty());
+
+        set(&mut root, "aws prod", None, Kind::Rename);
+        set(&mut root, "aws prod", None, Kind::Purge);
+        assert!(root.is_empty());
+    }
+}
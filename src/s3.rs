@@ -0,0 +1,238 @@
+//! Uploading exports to S3.
+//!
+//! Uses the AWS SDK's S3 client, with the region and credentials resolved the
+//! same way as DynamoDB's (see [`sdk_config_for`]), so a selected profile and
+//! assumed role apply to uploads too. Files up to [`PART_SIZE`] go up in a
+//! single `PutObject`; larger ones use a multipart upload, aborted again when
+//! a part fails. `AWS_ENDPOINT_URL_S3` points the uploads at an
+//! S3-compatible endpoint (path-style), e.g. for LocalStack.
+
+use std::fmt;
+use std::path::Path;
+
+use aws_sdk_s3::Client;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use tokio::io::AsyncReadExt;
+
+use crate::core::connect::ProfileSelection;
+use crate::dynamodb::connect::sdk_config_for;
+use crate::dynamodb::{format_sdk_error, send_aws_request};
+
+/// The size of each part of a multipart upload, and the largest file sent
+/// with a single request. S3 requires at least 5 MiB for all but the last part.
+pub const PART_SIZE: usize = 8 * 1024 * 1024;
+
+const ENDPOINT_VAR: &str = "AWS_ENDPOINT_URL_S3";
+
+/// A bucket and key, from an `s3://bucket/key` URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Uri {
+    pub bucket: String,
+    /// Empty, or ending in `/`, when the URI names a prefix rather than an
+    /// object.
+    pub key: String,
+}
+
+impl S3Uri {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let rest = text
+            .trim()
+            .strip_prefix("s3://")
+            .ok_or_else(|| format!("Not an S3 URI (expected s3://bucket/key): {text}"))?;
+        let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(format!("The S3 URI has no bucket: {text}"));
+        }
+        Ok(Self {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    pub fn is_prefix(&self) -> bool {
+        self.key.is_empty() || self.key.ends_with('/')
+    }
+
+    /// The object to upload `file_name` to: the URI itself, or `file_name`
+    /// under it when the URI names a prefix.
+    #[must_use]
+    pub fn object_for(&self, file_name: &str) -> Self {
+        if self.is_prefix() {
+            Self {
+                bucket: self.bucket.clone(),
+                key: format!("{}{file_name}", self.key),
+            }
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl fmt::Display for S3Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "s3://{}/{}", self.bucket, self.key)
+    }
+}
+
+/// Upload the file at `path` to the object `uri`, with credentials from
+/// `selection` (the environment and default profile when it is empty).
+pub async fn upload_file(
+    selection: &ProfileSelection,
+    path: &Path,
+    uri: &S3Uri,
+) -> Result<(), String> {
+    if uri.is_prefix() {
+        return Err(format!("{uri} names a prefix, not an object"));
+    }
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|err| format!("Failed to open {}: {err}", path.display()))?;
+    let size = file
+        .metadata()
+        .await
+        .map_err(|err| format!("Failed to read {}: {err}", path.display()))?
+        .len();
+    let client = connect(selection).await?;
+    if size <= PART_SIZE as u64 {
+        let body = read_part(&mut file, path).await?;
+        let request = client
+            .put_object()
+            .bucket(&uri.bucket)
+            .key(&uri.key)
+            .body(ByteStream::from(body));
+        let span = tracing::trace_span!("PutObject", object = %uri, size);
+        send_aws_request(span, Box::pin(request.send()), format_sdk_error)
+            .await
+            .map_err(|err| format!("S3 upload failed: {}", format_sdk_error(&err)))?;
+        return Ok(());
+    }
+
+    let request = client
+        .create_multipart_upload()
+        .bucket(&uri.bucket)
+        .key(&uri.key);
+    let span = tracing::trace_span!("CreateMultipartUpload", object = %uri, size);
+    let created = send_aws_request(span, Box::pin(request.send()), format_sdk_error)
+        .await
+        .map_err(|err| format!("S3 upload failed: {}", format_sdk_error(&err)))?;
+    let upload_id = created
+        .upload_id()
+        .ok_or("S3 returned no upload id")?
+        .to_string();
+    let completed = async {
+        let mut parts = Vec::new();
+        loop {
+            let body = read_part(&mut file, path).await?;
+            if body.is_empty() {
+                break;
+            }
+            let number = i32::try_from(parts.len() + 1)
+                .map_err(|_| format!("{} has too many parts", path.display()))?;
+            let request = client
+                .upload_part()
+                .bucket(&uri.bucket)
+                .key(&uri.key)
+                .upload_id(&upload_id)
+                .part_number(number)
+                .body(ByteStream::from(body));
+            let span = tracing::trace_span!("UploadPart", object = %uri, part = number);
+            let part = send_aws_request(span, Box::pin(request.send()), format_sdk_error)
+                .await
+                .map_err(|err| format!("S3 upload failed: {}", format_sdk_error(&err)))?;
+            parts.push(
+                CompletedPart::builder()
+                    .part_number(number)
+                    .set_e_tag(part.e_tag().map(str::to_string))
+                    .build(),
+            );
+        }
+        let request = client
+            .complete_multipart_upload()
+            .bucket(&uri.bucket)
+            .key(&uri.key)
+            .upload_id(&upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            );
+        let span = tracing::trace_span!("CompleteMultipartUpload", object = %uri);
+        send_aws_request(span, Box::pin(request.send()), format_sdk_error)
+            .await
+            .map_err(|err| format!("S3 upload failed: {}", format_sdk_error(&err)))?;
+        Ok::<_, String>(())
+    }
+    .await;
+    if let Err(err) = completed {
+        // Best effort: leaving the parts behind would keep billing for them.
+        let request = client
+            .abort_multipart_upload()
+            .bucket(&uri.bucket)
+            .key(&uri.key)
+            .upload_id(&upload_id);
+        let span = tracing::trace_span!("AbortMultipartUpload", object = %uri);
+        if let Err(abort_err) =
+            send_aws_request(span, Box::pin(request.send()), format_sdk_error).await
+        {
+            tracing::warn!(
+                object = %uri,
+                upload_id = %upload_id,
+                error = %format_sdk_error(&abort_err),
+                "Failed to abort the multipart upload; its parts stay billed until removed"
+            );
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// An S3 client for `selection`, addressing buckets path-style on a custom
+/// endpoint.
+async fn connect(selection: &ProfileSelection) -> Result<Client, String> {
+    let config = sdk_config_for(selection).await?;
+    let mut builder = aws_sdk_s3::config::Builder::from(&config);
+    if let Some(endpoint) = std::env::var(ENDPOINT_VAR)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+    {
+        builder = builder.endpoint_url(endpoint).force_path_style(true);
+    }
+    Ok(Client::from_conf(builder.build()))
+}
+
+/// Read up to [`PART_SIZE`] bytes; empty at the end of the file.
+async fn read_part(file: &mut tokio::fs::File, path: &Path) -> Result<Vec<u8>, String> {
+    let mut part = Vec::with_capacity(PART_SIZE);
+    file.take(PART_SIZE as u64)
+        .read_to_end(&mut part)
+        .await
+        .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+    Ok(part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::S3Uri;
+
+    #[test]
+    fn parses_uris_and_names_objects_under_prefixes() {
+        let uri = S3Uri::parse("s3://exports/daily/").unwrap();
+        assert!(uri.is_prefix());
+        assert_eq!(
+            uri.object_for("users.json").to_string(),
+            "s3://exports/daily/users.json"
+        );
+        let object = S3Uri::parse("s3://exports/users.csv").unwrap();
+        assert_eq!(object.object_for("users.json"), object);
+        assert_eq!(
+            S3Uri::parse("s3://exports")
+                .unwrap()
+                .object_for("a.json")
+                .key,
+            "a.json"
+        );
+        assert!(S3Uri::parse("exports/users.json").is_err());
+        assert!(S3Uri::parse("s3:///users.json").is_err());
+    }
+}
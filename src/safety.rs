@@ -0,0 +1,256 @@
+//! How much confirmation destructive actions ask for. Each [`Action`] has a
+//! [`Confirmation`] tier; the defaults can be changed per action with
+//! `confirm` in the config file, and `--unsafe` turns every prompt off. Against
+//! a prod-tagged environment every tier is raised one step, `--unsafe` or not.
+//! The TUI's confirm popups and the subcommands' prompts both go through
+//! [`confirmation`], so the policy is enforced in one place.
+
+use std::sync::OnceLock;
+
+use crate::environment::{self, Environment};
+
+static POLICY: OnceLock<SafetyPolicy> = OnceLock::new();
+
+/// What has to happen before an action runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Run it straight away.
+    None,
+    /// Ask yes or no.
+    Confirm,
+    /// Have the name of what is affected typed out.
+    TypeName,
+}
+
+impl Confirmation {
+    /// The tier named `name` in the config file.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(Self::None),
+            "confirm" => Some(Self::Confirm),
+            "type_name" => Some(Self::TypeName),
+            _ => None,
+        }
+    }
+
+    /// The next stricter tier.
+    fn raised(self) -> Self {
+        match self {
+            Self::None => Self::Confirm,
+            Self::Confirm | Self::TypeName => Self::TypeName,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    DeleteTable,
+    PurgeTable,
+    /// Deleting single items or a selection of them.
+    DeleteItems,
+    DropIndex,
+    RemoveReplica,
+    DeleteBackup,
+    /// Overwriting an existing file with an export.
+    Export,
+    /// Creating an item. Edits are confirmed with their diff either way.
+    Write,
+    /// A filtered scan estimated to read a large table.
+    Scan,
+    /// Copying items to another table, or moving them there.
+    CopyItems,
+    /// Putting a deleted item back from the audit log.
+    RestoreItem,
+    /// Updating every item a query matches. The preview counts as the
+    /// yes-or-no step.
+    BulkUpdate,
+    /// Committing the staged transaction.
+    Transact,
+    /// Resuming jobs interrupted when dynamate last stopped.
+    ResumeJobs,
+    /// Deleting every item a query matches. Choosing to delete in its popup
+    /// counts as the yes-or-no step.
+    DeleteMatching,
+    /// Writing generated items into a table.
+    Seed,
+    /// Copying a table into a new one.
+    CopyTable,
+    /// Changing a table's billing mode or capacity.
+    UpdateTable,
+    /// Adding a GSI to a table.
+    CreateIndex,
+    /// Writing items from a file into a table.
+    Import,
+}
+
+impl Action {
+    pub const ALL: [Self; 20] = [
+        Self::DeleteTable,
+        Self::PurgeTable,
+        Self::DeleteItems,
+        Self::DropIndex,
+        Self::RemoveReplica,
+        Self::DeleteBackup,
+        Self::Export,
+        Self::Write,
+        Self::Scan,
+        Self::CopyItems,
+        Self::RestoreItem,
+        Self::BulkUpdate,
+        Self::Transact,
+        Self::ResumeJobs,
+        Self::DeleteMatching,
+        Self::Seed,
+        Self::CopyTable,
+        Self::UpdateTable,
+        Self::CreateIndex,
+        Self::Import,
+    ];
+
+    /// The action's key under `confirm` in the config file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::DeleteTable => "delete_table",
+            Self::PurgeTable => "purge",
+            Self::DeleteItems => "delete_items",
+            Self::DropIndex => "drop_index",
+            Self::RemoveReplica => "remove_replica",
+            Self::DeleteBackup => "delete_backup",
+            Self::Export => "export",
+            Self::Write => "write",
+            Self::Scan => "scan",
+            Self::CopyItems => "copy_items",
+            Self::RestoreItem => "restore_item",
+            Self::BulkUpdate => "bulk_update",
+            Self::Transact => "transact",
+            Self::ResumeJobs => "resume_jobs",
+            Self::DeleteMatching => "delete_matching",
+            Self::Seed => "seed",
+            Self::CopyTable => "copy_table",
+            Self::UpdateTable => "update_table",
+            Self::CreateIndex => "create_index",
+            Self::Import => "import",
+        }
+    }
+
+    fn default_confirmation(self) -> Confirmation {
+        match self {
+            Self::DeleteTable | Self::PurgeTable | Self::DeleteMatching => Confirmation::TypeName,
+            // These add to or reconfigure a table rather than remove from it,
+            // so only prod asks before them.
+            Self::Write
+            | Self::Seed
+            | Self::CopyTable
+            | Self::UpdateTable
+            | Self::CreateIndex
+            | Self::Import => Confirmation::None,
+            _ => Confirmation::Confirm,
+        }
+    }
+}
+
+/// The tier of each action; unset ones use the defaults.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    overrides: Vec<(Action, Confirmation)>,
+    /// `--unsafe`: nothing is confirmed.
+    disabled: bool,
+}
+
+impl SafetyPolicy {
+    pub fn with(mut self, action: Action, confirmation: Confirmation) -> Self {
+        self.overrides.retain(|(existing, _)| *existing != action);
+        self.overrides.push((action, confirmation));
+        self
+    }
+
+    /// Turn every confirmation off, for local development.
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+
+    pub fn confirmation(&self, action: Action) -> Confirmation {
+        if self.disabled {
+            return Confirmation::None;
+        }
+        self.overrides
+            .iter()
+            .find(|(existing, _)| *existing == action)
+            .map_or_else(|| action.default_confirmation(), |(_, tier)| *tier)
+    }
+}
+
+/// Set the policy for the rest of the process. Only the first call takes
+/// effect; until then the defaults apply.
+pub fn set(policy: SafetyPolicy) {
+    let _ = POLICY.set(policy);
+}
+
+/// What `action` needs before it runs against the connection in use.
+pub fn confirmation(action: Action) -> Confirmation {
+    let confirmation = POLICY.get().map_or_else(
+        || action.default_confirmation(),
+        |policy| policy.confirmation(action),
+    );
+    if environment::current() == Some(Environment::Prod) {
+        confirmation.raised()
+    } else {
+        confirmation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Confirmation, SafetyPolicy};
+
+    #[test]
+    fn overrides_and_disables_the_defaults() {
+        let policy = SafetyPolicy::default();
+        assert_eq!(
+            policy.confirmation(Action::DeleteTable),
+            Confirmation::TypeName
+        );
+        assert_eq!(
+            policy.confirmation(Action::DeleteItems),
+            Confirmation::Confirm
+        );
+        assert_eq!(
+            policy.confirmation(Action::BulkUpdate),
+            Confirmation::Confirm
+        );
+        assert_eq!(
+            policy.confirmation(Action::DeleteMatching),
+            Confirmation::TypeName
+        );
+        assert_eq!(policy.confirmation(Action::Import), Confirmation::None);
+        let mut keys: Vec<&str> = Action::ALL.iter().map(|action| action.key()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), Action::ALL.len());
+
+        let policy = policy
+            .with(Action::Export, Confirmation::None)
+            .with(Action::DeleteItems, Confirmation::TypeName)
+            .with(Action::Export, Confirmation::Confirm);
+        assert_eq!(policy.confirmation(Action::Export), Confirmation::Confirm);
+        assert_eq!(
+            policy.confirmation(Action::DeleteItems),
+            Confirmation::TypeName
+        );
+
+        let policy = policy.disabled();
+        assert!(
+            Action::ALL
+                .iter()
+                .all(|action| policy.confirmation(*action) == Confirmation::None)
+        );
+        assert_eq!(Confirmation::None.raised(), Confirmation::Confirm);
+        assert_eq!(Confirmation::TypeName.raised(), Confirmation::TypeName);
+        assert_eq!(
+            Confirmation::parse("type_name"),
+            Some(Confirmation::TypeName)
+        );
+        assert_eq!(Confirmation::parse("always"), None);
+    }
+}
@@ -181,6 +181,21 @@ const fn capabilities(dialect: SqlDialectKind) -> Capabilities {
         scanned_count: false,
         consumed_capacity: false,
         raw_query: true,
+        statements: false,
+        profiles: false,
+        parallel_scan: false,
+        index_management: false,
+        capacity: false,
+        partial_update: false,
+        read_options: false,
+        backups: false,
+        batch_get: false,
+        transactions: false,
+        regions: false,
+        replicas: false,
+        imports: false,
+        metrics: false,
+        typed_keys: false,
     }
 }
 
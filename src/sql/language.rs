@@ -2,8 +2,8 @@
 //! free-form `SELECT`. One struct with a mode flag backs both.
 
 use crate::core::language::{
-    Completion, CompletionRequest, QueryLanguage, QueryStatus, ReferenceSection, Suggestion,
-    SuggestionKind, TokenSpan,
+    Completion, CompletionRequest, Condition, ConditionOp, ConditionValue, QueryLanguage,
+    QueryStatus, ReferenceSection, Suggestion, SuggestionKind, TokenSpan,
 };
 use crate::core::query::PlanKind;
 use crate::core::schema::CollectionSchema;
@@ -268,6 +268,68 @@ impl QueryLanguage for SqlLanguage {
             }],
         }
     }
+
+    fn build_query(&self, conditions: &[Condition]) -> Result<String, String> {
+        if self.mode != SqlLangMode::Filter {
+            return Err("the query builder works on a single table".to_string());
+        }
+        conditions
+            .iter()
+            .map(condition_predicate)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|predicates| predicates.join(" AND "))
+    }
+}
+
+/// One builder condition as a `WHERE` predicate. Column names are written
+/// bare since quoting differs between dialects.
+fn condition_predicate(condition: &Condition) -> Result<String, String> {
+    let column = condition.attribute.as_str();
+    let plain = column
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && column
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !plain {
+        return Err(format!("type a query to filter on column {column:?}"));
+    }
+    let text = match &condition.value {
+        ConditionValue::String(text) | ConditionValue::Number(text) => text.clone(),
+        ConditionValue::Boolean(value) => value.to_string(),
+    };
+    let literal = match &condition.value {
+        ConditionValue::String(text) => quote(text),
+        ConditionValue::Number(text) => text.clone(),
+        ConditionValue::Boolean(value) => value.to_string().to_uppercase(),
+    };
+    let pattern = |prefix: &str, suffix: &str| {
+        let escaped: String = text
+            .chars()
+            .flat_map(|c| {
+                let escape = matches!(c, '%' | '_' | '\\').then_some('\\');
+                escape.into_iter().chain(std::iter::once(c))
+            })
+            .collect();
+        quote(&format!("{prefix}{escaped}{suffix}"))
+    };
+    Ok(match condition.op {
+        ConditionOp::Equal => format!("{column} = {literal}"),
+        ConditionOp::NotEqual => format!("{column} <> {literal}"),
+        ConditionOp::Less => format!("{column} < {literal}"),
+        ConditionOp::LessOrEqual => format!("{column} <= {literal}"),
+        ConditionOp::Greater => format!("{column} > {literal}"),
+        ConditionOp::GreaterOrEqual => format!("{column} >= {literal}"),
+        ConditionOp::BeginsWith => format!("{column} LIKE {}", pattern("", "%")),
+        ConditionOp::Contains => format!("{column} LIKE {}", pattern("%", "%")),
+        ConditionOp::Exists => format!("{column} IS NOT NULL"),
+        ConditionOp::NotExists => format!("{column} IS NULL"),
+    })
+}
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
 }
 
 fn starts_with_ci(candidate: &str, prefix: &str) -> bool {
@@ -467,6 +529,36 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn builder_conditions_become_a_where_predicate() {
+        let filter = SqlLanguage {
+            mode: SqlLangMode::Filter,
+        };
+        let condition = |attribute: &str, op, value: &str| Condition {
+            attribute: attribute.to_string(),
+            op,
+            value: ConditionValue::infer(value),
+        };
+        let predicate = filter
+            .build_query(&[
+                condition("name", ConditionOp::Equal, "O'Brien"),
+                condition("total", ConditionOp::GreaterOrEqual, "10"),
+                condition("code", ConditionOp::BeginsWith, "50%"),
+                condition("deleted_at", ConditionOp::NotExists, ""),
+            ])
+            .unwrap();
+        assert_eq!(
+            predicate,
+            "name = 'O''Brien' AND total >= 10 AND code LIKE '50\\%%' AND deleted_at IS NULL"
+        );
+        assert!(
+            filter
+                .build_query(&[condition("first name", ConditionOp::Equal, "a")])
+                .is_err()
+        );
+        assert!(lang().build_query(&[]).is_err());
+    }
+
     #[test]
     fn empty_is_empty() {
         assert!(matches!(lang().validate("  ", None), QueryStatus::Empty));
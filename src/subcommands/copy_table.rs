@@ -0,0 +1,144 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::copy::{CopyCheckpoint, copy_items, copy_spec, wait_until_active};
+use dynamate::core::datastore::Datastore;
+
+use super::delete_table::confirm;
+use crate::safety::Action;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to copy from
+    #[arg(value_name = "SOURCE")]
+    pub source: String,
+
+    /// Table to create and copy into
+    #[arg(value_name = "DESTINATION")]
+    pub destination: String,
+
+    /// Split the scan into this many segments copied in parallel
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=1_000_000)
+    )]
+    pub segments: u32,
+
+    /// Save progress to this file after every page. When the file already
+    /// exists the copy resumes from it instead of creating the destination.
+    #[arg(long, value_name = "PATH")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Copy without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let source = args.source.trim();
+    let destination = args.destination.trim();
+    if source == destination {
+        return Err(eyre!("Source and destination must be different tables"));
+    }
+    if args.segments > 1 && !db.capabilities().parallel_scan {
+        return Err(eyre!(
+            "{} does not support parallel scans; drop --segments",
+            db.capabilities().backend_label
+        ));
+    }
+
+    let schema = db
+        .describe_collection(source)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    if !args.yes {
+        confirm(
+            Action::CopyTable,
+            destination,
+            &format!("This copies every item in {source} to {destination}"),
+        )?;
+    }
+    let checkpoint = if let Some(path) = args.checkpoint.as_deref().filter(|path| path.exists()) {
+        let checkpoint = read_checkpoint(path)?;
+        if checkpoint.source != source || checkpoint.destination != destination {
+            return Err(eyre!(
+                "{} is a checkpoint for copying {} to {}",
+                path.display(),
+                checkpoint.source,
+                checkpoint.destination
+            ));
+        }
+        eprintln!(
+            "Resuming copy of {source} to {destination} after {} items",
+            checkpoint.copied
+        );
+        checkpoint
+    } else {
+        db.create_collection(&copy_spec(&schema, destination))
+            .await
+            .map_err(|err| eyre!("Failed to create {destination}: {err}"))?;
+        eprintln!("Created {destination}; waiting for it to become active");
+        let checkpoint = CopyCheckpoint::new(source, destination, args.segments);
+        if let Some(path) = args.checkpoint.as_deref() {
+            write_checkpoint(path, &checkpoint)
+                .map_err(|err| eyre!("Failed to write {}: {err}", path.display()))?;
+        }
+        checkpoint
+    };
+    wait_until_active(db, destination)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+
+    let estimate = schema
+        .item_count
+        .map(|count| format!(" of ~{count}"))
+        .unwrap_or_default();
+    let checkpoint_path = args.checkpoint.as_deref();
+    let on_progress = |checkpoint: &CopyCheckpoint| {
+        eprint!("\rCopied {}{estimate} items", checkpoint.copied);
+        let _ = io::stderr().flush();
+        if let Some(path) = checkpoint_path
+            && let Err(err) = write_checkpoint(path, checkpoint)
+        {
+            tracing::warn!(error = %err, "copy_checkpoint_write_failed");
+        }
+    };
+    let result = copy_items(db, checkpoint, &AtomicBool::new(false), &on_progress).await;
+    eprintln!();
+    match result {
+        Ok(checkpoint) => {
+            if let Some(path) = checkpoint_path {
+                let _ = std::fs::remove_file(path);
+            }
+            eprintln!(
+                "Copied {} items from {source} to {destination}",
+                checkpoint.copied
+            );
+            Ok(())
+        }
+        Err(err) => match checkpoint_path {
+            Some(path) => Err(eyre!(
+                "{err}\nProgress is saved in {}; run the same command to resume",
+                path.display()
+            )),
+            None => Err(eyre!(err.to_string())),
+        },
+    }
+}
+
+fn read_checkpoint(path: &Path) -> Result<CopyCheckpoint> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| eyre!("Failed to read {}: {err}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|err| eyre!("Invalid checkpoint {}: {err}", path.display()))?;
+    CopyCheckpoint::from_json(&value).map_err(|err| eyre!(err.to_string()))
+}
+
+fn write_checkpoint(path: &Path, checkpoint: &CopyCheckpoint) -> io::Result<()> {
+    std::fs::write(path, checkpoint.to_json().to_string())
+}
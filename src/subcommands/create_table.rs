@@ -1,3 +1,6 @@
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
 use color_eyre::eyre::{Result, eyre};
 
 use dynamate::core::datastore::Datastore;
@@ -5,17 +8,20 @@ use dynamate::core::query::CreateCollectionSpec;
 use dynamate::core::schema::{
     IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, Projection, ScalarType,
 };
-use dynamate::dynamodb::{AttributeType, GsiSpec, IndexProjection, KeySpec, LsiSpec};
+use dynamate::core::table_spec::{self, TableSpec};
+use dynamate::dynamodb::{
+    AttributeType, CreateTableSpec, GsiSpec, IndexProjection, KeySpec, LsiSpec,
+};
 
 #[derive(clap::Args, Debug)]
 pub struct Args {
     /// Table name to create
-    #[arg(long, value_name = "TABLE")]
-    pub table: String,
+    #[arg(long, value_name = "TABLE", required_unless_present = "spec")]
+    pub table: Option<String>,
 
     /// Partition key, format: NAME:TYPE (TYPE is S|N|B)
-    #[arg(long, value_name = "NAME:TYPE")]
-    pub pk: String,
+    #[arg(long, value_name = "NAME:TYPE", required_unless_present = "spec")]
+    pub pk: Option<String>,
 
     /// Sort key, format: NAME:TYPE (TYPE is S|N|B)
     #[arg(long, value_name = "NAME:TYPE")]
@@ -30,11 +36,61 @@ pub struct Args {
     /// PROJECTION can be: all | keys_only | include=attr1,attr2
     #[arg(long, value_name = "LSI", action = clap::ArgAction::Append)]
     pub lsi: Vec<String>,
+
+    /// Read the whole table spec (keys, indexes, TTL, and billing) from a
+    /// JSON or TOML file instead of the flags above, such as one `describe
+    /// --spec` printed; `-` reads standard input
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["table", "pk", "sk", "gsi", "lsi"])]
+    pub spec: Option<PathBuf>,
 }
 
 pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
-    let table_name = args.table.trim().to_string();
-    let hash_key = parse_key_spec(&args.pk).map_err(|err| eyre!("Invalid --pk value: {err}"))?;
+    let spec = match args.spec.as_deref() {
+        Some(path) => read_spec(path)?,
+        None => TableSpec {
+            collection: collection_spec(&spec_from_flags(&args)?),
+            ttl_attribute: None,
+            capacity: None,
+        },
+    };
+
+    table_spec::create(db, &spec)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+
+    println!("Created table {}", spec.collection.name);
+    Ok(())
+}
+
+/// A `.toml` file, or standard input that isn't a JSON object, is read as
+/// TOML.
+fn read_spec(path: &Path) -> Result<TableSpec> {
+    let text = if path.as_os_str() == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|err| eyre!("Failed to read {}: {err}", path.display()))?
+    };
+    let toml = match path.extension() {
+        Some(ext) => ext.eq_ignore_ascii_case("toml"),
+        None => !text.trim_start().starts_with('{'),
+    };
+    let spec = if toml {
+        TableSpec::from_toml(&text)
+    } else {
+        serde_json::from_str(&text)
+            .map_err(|err| err.to_string())
+            .and_then(|value| TableSpec::from_json(&value))
+    };
+    spec.map_err(|err| eyre!("Invalid spec {}: {err}", path.display()))
+}
+
+fn spec_from_flags(args: &Args) -> Result<CreateTableSpec> {
+    let table_name = args.table.as_deref().unwrap_or_default().trim().to_string();
+    let hash_key = parse_key_spec(args.pk.as_deref().unwrap_or_default())
+        .map_err(|err| eyre!("Invalid --pk value: {err}"))?;
     let sort_key = match args.sk.as_deref() {
         Some(raw) => Some(parse_key_spec(raw).map_err(|err| eyre!("Invalid --sk value: {err}"))?),
         None => None,
@@ -52,13 +108,24 @@ pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
         lsis.push(spec);
     }
 
-    let mut key_fields = vec![key_field(&hash_key, KeyRole::Partition)];
-    if let Some(sort_key) = sort_key.as_ref() {
+    Ok(CreateTableSpec {
+        table_name,
+        hash_key,
+        sort_key,
+        gsis,
+        lsis,
+    })
+}
+
+/// The neutral spec the datastore trait accepts.
+fn collection_spec(spec: &CreateTableSpec) -> CreateCollectionSpec {
+    let mut key_fields = vec![key_field(&spec.hash_key, KeyRole::Partition)];
+    if let Some(sort_key) = spec.sort_key.as_ref() {
         key_fields.push(key_field(sort_key, KeyRole::Sort));
     }
 
     let mut indexes = Vec::new();
-    for gsi in &gsis {
+    for gsi in &spec.gsis {
         let mut fields = vec![key_field(&gsi.hash_key, KeyRole::Partition)];
         if let Some(sort_key) = gsi.sort_key.as_ref() {
             fields.push(key_field(sort_key, KeyRole::Sort));
@@ -70,7 +137,7 @@ pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
             projection: projection(&gsi.projection),
         });
     }
-    for lsi in &lsis {
+    for lsi in &spec.lsis {
         indexes.push(IndexSchema {
             name: lsi.name.clone(),
             kind: IndexKind::LocalSecondary,
@@ -81,18 +148,11 @@ pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
         });
     }
 
-    let spec = CreateCollectionSpec {
-        name: table_name,
+    CreateCollectionSpec {
+        name: spec.table_name.clone(),
         key: KeySchema { fields: key_fields },
         indexes,
-    };
-
-    db.create_collection(&spec)
-        .await
-        .map_err(|err| eyre!(err.to_string()))?;
-
-    println!("Created table {}", spec.name);
-    Ok(())
+    }
 }
 
 fn key_field(spec: &KeySpec, role: KeyRole) -> KeyField {
@@ -255,8 +315,8 @@ mod tests {
     #[test]
     fn parse_args_minimal() {
         let cli = Cli::try_parse_from(["dynamate", "--table", "demo", "--pk", "PK:S"]).unwrap();
-        assert_eq!(cli.args.table, "demo");
-        assert_eq!(cli.args.pk, "PK:S");
+        assert_eq!(cli.args.table.as_deref(), Some("demo"));
+        assert_eq!(cli.args.pk.as_deref(), Some("PK:S"));
         assert!(cli.args.sk.is_none());
         assert!(cli.args.gsi.is_empty());
         assert!(cli.args.lsi.is_empty());
@@ -284,6 +344,16 @@ mod tests {
         assert_eq!(cli.args.lsi.len(), 1);
     }
 
+    #[test]
+    fn parse_args_takes_a_spec_file_instead_of_flags() {
+        let cli = Cli::try_parse_from(["dynamate", "--spec", "orders.json"]).unwrap();
+        assert_eq!(cli.args.spec.unwrap().to_str(), Some("orders.json"));
+        assert!(
+            Cli::try_parse_from(["dynamate", "--spec", "orders.json", "--pk", "PK:S"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["dynamate", "--table", "demo"]).is_err());
+    }
+
     #[test]
     fn parse_args_rejects_bad_key_format() {
         let err = parse_key_spec("PK").unwrap_err();
@@ -0,0 +1,103 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use color_eyre::eyre::{Result, bail, eyre};
+
+use dynamate::core::datastore::Datastore;
+
+use crate::safety::{self, Action, Confirmation};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to delete
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// Delete without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table = args.table.trim();
+    if !args.yes {
+        confirm(
+            Action::DeleteTable,
+            table,
+            &format!("This deletes {table} and all of its items"),
+        )?;
+    }
+
+    db.drop_collection(table)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    println!("Deleted table {table}");
+    Ok(())
+}
+
+/// Ask whether to go ahead with `action` on `table` after `warning`, as the
+/// safety policy says: not at all, yes or no, or by typing the table name.
+/// Without a terminal to ask on, this fails and points at `--yes`.
+pub(super) fn confirm(action: Action, table: &str, warning: &str) -> Result<()> {
+    let confirmation = safety::confirmation(action);
+    if confirmation == Confirmation::None {
+        return Ok(());
+    }
+    if !io::stdin().is_terminal() {
+        bail!("Refusing to change {table} without confirmation; pass --yes");
+    }
+    if confirmation == Confirmation::TypeName {
+        eprint!("{warning}. Type the table name to confirm: ");
+    } else {
+        eprint!("{warning}. Continue? [y/N] ");
+    }
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    if !confirms(&answer, table, confirmation) {
+        bail!("Not confirmed; {table} was left alone");
+    }
+    Ok(())
+}
+
+fn confirms(answer: &str, table: &str, confirmation: Confirmation) -> bool {
+    match confirmation {
+        Confirmation::None => true,
+        Confirmation::Confirm => matches!(answer.trim(), "y" | "Y" | "yes"),
+        Confirmation::TypeName => answer.trim() == table,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Args, confirms};
+    use crate::safety::Confirmation;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn parses_args_and_checks_the_confirmation() {
+        let cli = Cli::try_parse_from(["dynamate", "orders", "--yes"]).unwrap();
+        assert_eq!(cli.args.table, "orders");
+        assert!(cli.args.yes);
+        assert!(
+            !Cli::try_parse_from(["dynamate", "orders"])
+                .unwrap()
+                .args
+                .yes
+        );
+
+        let typed = Confirmation::TypeName;
+        assert!(confirms("orders\n", "orders", typed));
+        assert!(!confirms("order\n", "orders", typed));
+        assert!(!confirms("\n", "orders", typed));
+        assert!(!confirms("y\n", "orders", typed));
+        assert!(confirms("y\n", "orders", Confirmation::Confirm));
+        assert!(!confirms("\n", "orders", Confirmation::Confirm));
+    }
+}
@@ -0,0 +1,80 @@
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::describe;
+use dynamate::core::table_spec::TableSpec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SpecFormat {
+    Json,
+    Toml,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to describe
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// Print the description as JSON
+    #[arg(short, long)]
+    pub json: bool,
+
+    /// Print the table's definition (keys, indexes, TTL, and billing) as a
+    /// spec `create-table --spec` takes, in JSON unless TOML is asked for
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "json",
+        conflicts_with = "json"
+    )]
+    pub spec: Option<SpecFormat>,
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let details = db
+        .describe_details(args.table.trim())
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    match args.spec {
+        Some(SpecFormat::Json) => println!(
+            "{}",
+            serde_json::to_string_pretty(&TableSpec::from_details(&details).to_json())?
+        ),
+        Some(SpecFormat::Toml) => print!(
+            "{}",
+            TableSpec::from_details(&details)
+                .to_toml()
+                .map_err(|err| eyre!(err))?
+        ),
+        None if args.json => println!(
+            "{}",
+            serde_json::to_string_pretty(&describe::to_json(&details))?
+        ),
+        None => print!("{}", describe::text(&describe::sections(&details))),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Args, SpecFormat};
+
+    #[derive(Parser, Debug)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn spec_defaults_to_json() {
+        let cli = Cli::try_parse_from(["dynamate", "orders", "--spec"]).unwrap();
+        assert_eq!(cli.args.spec, Some(SpecFormat::Json));
+        let cli = Cli::try_parse_from(["dynamate", "orders", "--spec", "toml"]).unwrap();
+        assert_eq!(cli.args.spec, Some(SpecFormat::Toml));
+        assert!(Cli::try_parse_from(["dynamate", "orders", "--spec", "--json"]).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Result, eyre};
+use futures::stream::{self, Stream, StreamExt};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::query::{Cursor, Page, QueryPlan};
+use dynamate::core::value::Item;
+
+use super::output::{ItemWriter, OutputFormat};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to export
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// File to write; prints to stdout when omitted or `-`
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Output format. Inferred from the output file extension when omitted,
+    /// defaulting to JSON.
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Only export items matching this expression (same syntax as the TUI
+    /// query box)
+    #[arg(long, value_name = "EXPRESSION")]
+    pub filter: Option<String>,
+
+    /// Split the scan into this many segments fetched in parallel
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=1_000_000)
+    )]
+    pub segments: u32,
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table_name = args.table.trim();
+    let path = args.output.filter(|path| path.as_os_str() != "-");
+    let format = args
+        .format
+        .or_else(|| path.as_deref().and_then(OutputFormat::from_path))
+        .unwrap_or_default();
+    let out: Box<dyn Write> = match path.as_deref() {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|err| {
+                eyre!("Failed to create {}: {err}", path.display())
+            })?))
+        }
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    let count = write_items(
+        db,
+        table_name,
+        args.filter.as_deref(),
+        args.segments,
+        format,
+        out,
+    )
+    .await?;
+
+    match path {
+        Some(path) => eprintln!(
+            "Exported {count} items from {table_name} to {}",
+            path.display()
+        ),
+        None => eprintln!("Exported {count} items from {table_name}"),
+    }
+    Ok(())
+}
+
+/// Write the items of `table_name` matching `filter` to `out`, returning how
+/// many were written.
+pub(super) async fn write_items(
+    db: &dyn Datastore,
+    table_name: &str,
+    filter: Option<&str>,
+    segments: u32,
+    format: OutputFormat,
+    out: Box<dyn Write>,
+) -> Result<usize> {
+    if segments > 1 && !db.capabilities().parallel_scan {
+        return Err(eyre!(
+            "{} does not support parallel scans; drop --segments",
+            db.capabilities().backend_label
+        ));
+    }
+    let schema = db
+        .describe_collection(table_name)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    let key_columns = schema.key.fields.iter().map(|f| f.name.clone()).collect();
+    let filter = filter
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string);
+    let plan = QueryPlan::new(filter, None);
+    let mut writer = ItemWriter::new(format, out, key_columns);
+
+    // A single segment runs the plan as-is, so a key condition in the filter
+    // still becomes a Query rather than a full scan.
+    let mut batches =
+        if segments > 1 {
+            stream::select_all((0..segments).map(|segment| {
+                pages(db, table_name, &plan, Some((segment, segments))).boxed_local()
+            }))
+        } else {
+            stream::select_all([pages(db, table_name, &plan, None).boxed_local()])
+        };
+    while let Some(items) = batches.next().await {
+        for item in items? {
+            writer.write(item)?;
+        }
+    }
+    writer.finish()
+}
+
+/// Page through one scan segment (or the whole plan when `segment` is
+/// `None`), yielding each page's items.
+fn pages<'a>(
+    db: &'a dyn Datastore,
+    table_name: &'a str,
+    plan: &'a QueryPlan,
+    segment: Option<(u32, u32)>,
+) -> impl Stream<Item = Result<Vec<Item>>> + 'a {
+    stream::unfold(Some(None::<Cursor>), move |state| async move {
+        let cursor = state?;
+        let page = Page {
+            cursor,
+            limit: None,
+        };
+        dynamate::throttle::acquire().await;
+        let result = match segment {
+            Some((segment, total_segments)) => {
+                db.scan_segment(table_name, plan, segment, total_segments, page)
+                    .await
+            }
+            None => db.query(table_name, plan, page).await,
+        };
+        match result {
+            Ok(result) => {
+                dynamate::throttle::charge(&result);
+                Some((Ok(result.items), result.next.map(Some)))
+            }
+            Err(err) => Some((Err(eyre!(err.to_string())), None)),
+        }
+    })
+}
@@ -0,0 +1,90 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::s3::{self, S3Uri};
+
+use super::export::write_items;
+use super::output::OutputFormat;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to export
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// Object to upload to, e.g. `s3://bucket/exports/users.json`. A URI
+    /// ending in `/` names a prefix, and the export is uploaded under it as
+    /// `<TABLE>.<format>`.
+    #[arg(value_name = "S3_URI")]
+    pub uri: String,
+
+    /// Output format. Inferred from the object key's extension when omitted,
+    /// defaulting to JSON.
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Only export items matching this expression (same syntax as the TUI
+    /// query box)
+    #[arg(long, value_name = "EXPRESSION")]
+    pub filter: Option<String>,
+
+    /// Split the scan into this many segments fetched in parallel
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=1_000_000)
+    )]
+    pub segments: u32,
+}
+
+/// Export to a temporary file, then upload it; the file is removed either way.
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table_name = args.table.trim();
+    let uri = S3Uri::parse(&args.uri).map_err(|err| eyre!(err))?;
+    let format = args
+        .format
+        .or_else(|| {
+            (!uri.is_prefix())
+                .then(|| OutputFormat::from_path(Path::new(&uri.key)))
+                .flatten()
+        })
+        .unwrap_or_default();
+    let object = uri.object_for(&format!("{table_name}.{}", format.extension()));
+
+    let temp = std::env::temp_dir().join(format!(
+        "dynamate-export-{}.{}",
+        std::process::id(),
+        format.extension()
+    ));
+    let out: Box<dyn Write> =
+        Box::new(BufWriter::new(File::create(&temp).map_err(|err| {
+            eyre!("Failed to create {}: {err}", temp.display())
+        })?));
+    let result: Result<usize> = async {
+        let count = write_items(
+            db,
+            table_name,
+            args.filter.as_deref(),
+            args.segments,
+            format,
+            out,
+        )
+        .await?;
+        eprintln!("Uploading {count} items to {object}...");
+        let selection = db.active_profile().unwrap_or_default();
+        s3::upload_file(&selection, &temp, &object)
+            .await
+            .map_err(|err| eyre!(err))?;
+        Ok(count)
+    }
+    .await;
+    let _ = std::fs::remove_file(&temp);
+    let count = result?;
+    eprintln!("Exported {count} items from {table_name} to {object}");
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::io::{self, BufWriter, Read};
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::key_list::parse_key_list;
+
+use super::output::{ItemWriter, OutputFormat};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to read from
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// File of primary keys: a JSON array or NDJSON of key objects (plain or
+    /// DynamoDB JSON), or one bare value per line for single-attribute keys.
+    /// `-` reads standard input.
+    #[arg(long, value_name = "PATH")]
+    pub keys_file: PathBuf,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    pub output: OutputFormat,
+
+    /// Use strongly consistent reads
+    #[arg(long)]
+    pub consistent: bool,
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table_name = args.table.trim();
+    if !db.capabilities().batch_get {
+        return Err(eyre!(
+            "{} does not support batch key lookups",
+            db.capabilities().backend_label
+        ));
+    }
+    let schema = db
+        .describe_collection(table_name)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    let input = read_input(&args.keys_file)?;
+    let keys = parse_key_list(&input, &schema.key).map_err(|err| eyre!(err))?;
+    let requested = keys.len();
+    let key_columns = schema.key.fields.iter().map(|f| f.name.clone()).collect();
+
+    dynamate::throttle::acquire().await;
+    let items = db
+        .batch_get(table_name, keys, args.consistent)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    let mut writer = ItemWriter::new(args.output, BufWriter::new(io::stdout()), key_columns);
+    for item in items {
+        writer.write(item)?;
+    }
+    let found = writer.finish()?;
+    if found < requested {
+        eprintln!("{} of {requested} keys not found", requested - found);
+    }
+    Ok(())
+}
+
+fn read_input(path: &Path) -> Result<String> {
+    if path.as_os_str() == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return Ok(input);
+    }
+    std::fs::read_to_string(path).map_err(|err| eyre!("Failed to read {}: {err}", path.display()))
+}
@@ -0,0 +1,528 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, eyre};
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde::de::{Deserializer as _, MapAccess, SeqAccess, Visitor, value::MapAccessDeserializer};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::json::{item_from_json, item_from_typed_json};
+use dynamate::core::value::Item;
+
+use super::delete_table::confirm;
+use crate::safety::Action;
+
+/// Items sent per `batch_put` call.
+const BATCH_SIZE: usize = 25;
+/// Errors listed individually in the summary before the rest are elided.
+const MAX_REPORTED_ERRORS: usize = 10;
+/// DynamoDB type tags recognized when sniffing DynamoDB JSON.
+const TYPE_TAGS: &[&str] = &["S", "N", "B", "BOOL", "NULL", "M", "L", "SS", "NS", "BS"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputFormat {
+    /// A JSON array of items (or a single item)
+    Json,
+    /// One JSON item per line
+    Ndjson,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Encoding {
+    /// Detect DynamoDB JSON per item, plain JSON otherwise
+    #[default]
+    Auto,
+    /// Plain JSON values
+    Plain,
+    /// DynamoDB JSON (`{"S": ".."}` tagged values, optionally wrapped in
+    /// `{"Item": ..}` as in S3 exports)
+    Dynamodb,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to write into
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// File to read; reads stdin when `-`
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Input layout. Inferred from the file extension (`.ndjson`/`.jsonl`) or
+    /// the contents when omitted.
+    #[arg(long, value_enum)]
+    pub format: Option<InputFormat>,
+
+    /// How item values are encoded
+    #[arg(long, value_enum, default_value_t = Encoding::Auto)]
+    pub encoding: Encoding,
+
+    /// Number of batches written concurrently
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..=64)
+    )]
+    pub concurrency: u32,
+
+    /// Parse and validate the items without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Write without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// A record that could not be imported, identified by its position in the
+/// input (array index or line number, 1-based).
+#[derive(Debug)]
+struct Failure {
+    position: usize,
+    message: String,
+}
+
+/// What the reader hands over to be written.
+#[derive(Debug)]
+enum Parsed {
+    /// Up to [`BATCH_SIZE`] items, none with the same key.
+    Batch(Vec<(usize, Item)>),
+    Failed(Failure),
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table_name = args.table.trim();
+    let input = open_input(&args.file)?;
+    let schema = db
+        .describe_collection(table_name)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    if !args.dry_run && !args.yes {
+        confirm(
+            Action::Import,
+            table_name,
+            &format!(
+                "This writes the items in {} to {table_name}, replacing any with the same key",
+                args.file.display()
+            ),
+        )?;
+    }
+
+    // The input is read on its own thread while the batches before it are
+    // written, so a large file is never held in memory at once.
+    let (tx, rx) = mpsc::channel(args.concurrency as usize);
+    let mut batcher = Batcher {
+        key_names: schema.key.fields.into_iter().map(|f| f.name).collect(),
+        encoding: args.encoding,
+        batch: Vec::new(),
+        tx,
+    };
+    let (path, format) = (args.file, args.format);
+    let reader = tokio::task::spawn_blocking(move || {
+        let (head, input) = peek(input).map_err(|err| err.to_string())?;
+        let format = format.unwrap_or_else(|| detect_format(&path, &head));
+        read_records(input, format, &mut |position, parsed| {
+            batcher.push(position, parsed)
+        })?;
+        batcher.finish();
+        Ok::<(), String>(())
+    });
+
+    let dry_run = args.dry_run;
+    let mut results = ReceiverStream::new(rx)
+        .map(|parsed| async move {
+            match parsed {
+                Parsed::Failed(failure) => (0, vec![failure]),
+                Parsed::Batch(batch) if dry_run => (batch.len() as u64, Vec::new()),
+                Parsed::Batch(batch) => write_batch(db, table_name, batch).await,
+            }
+        })
+        .buffer_unordered(args.concurrency as usize);
+    let mut failures = Vec::new();
+    let mut written = 0_u64;
+    while let Some((count, batch_failures)) = results.next().await {
+        written += count;
+        failures.extend(batch_failures);
+    }
+    let read = reader.await?;
+
+    if dry_run {
+        println!("Dry run: {written} items would be written to {table_name}");
+    } else {
+        println!("Written: {written}");
+    }
+    failures.sort_by_key(|failure| failure.position);
+    println!("Failed: {}", failures.len());
+    for failure in failures.iter().take(MAX_REPORTED_ERRORS) {
+        eprintln!("  record {}: {}", failure.position, failure.message);
+    }
+    if failures.len() > MAX_REPORTED_ERRORS {
+        eprintln!("  … and {} more", failures.len() - MAX_REPORTED_ERRORS);
+    }
+    read.map_err(|err| eyre!(err))?;
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!("{} items were not imported", failures.len()))
+    }
+}
+
+async fn write_batch(
+    db: &dyn Datastore,
+    table_name: &str,
+    batch: Vec<(usize, Item)>,
+) -> (u64, Vec<Failure>) {
+    let first = batch.first().map_or(0, |(position, _)| *position);
+    let positions: Vec<usize> = batch.iter().map(|(position, _)| *position).collect();
+    let items = batch.into_iter().map(|(_, item)| item).collect();
+    match db.batch_put(table_name, items).await {
+        Ok(outcome) => (outcome.written, Vec::new()),
+        Err(err) => {
+            let message = format!("batch starting at record {first}: {err}");
+            let failures = positions
+                .into_iter()
+                .map(|position| Failure {
+                    position,
+                    message: message.clone(),
+                })
+                .collect();
+            (0, failures)
+        }
+    }
+}
+
+fn open_input(path: &Path) -> Result<Box<dyn BufRead + Send>> {
+    if path.as_os_str() == "-" {
+        return Ok(Box::new(BufReader::new(io::stdin())));
+    }
+    let file = File::open(path).map_err(|err| eyre!("Failed to read {}: {err}", path.display()))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Read up to the first line with anything on it, for [`detect_format`], and
+/// hand it back along with the whole input.
+fn peek(mut input: impl BufRead) -> io::Result<(String, impl BufRead)> {
+    let mut head = String::new();
+    while head.trim().is_empty() {
+        if input.read_line(&mut head)? == 0 {
+            break;
+        }
+    }
+    Ok((head.clone(), Cursor::new(head).chain(input)))
+}
+
+fn detect_format(path: &Path, head: &str) -> InputFormat {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    if matches!(extension.as_deref(), Some("ndjson" | "jsonl")) {
+        return InputFormat::Ndjson;
+    }
+    // An array, or an object spread over several lines, is JSON; an object
+    // per line is NDJSON.
+    let first_line = head
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if !first_line.starts_with('[') && serde_json::from_str::<serde_json::Value>(first_line).is_ok()
+    {
+        InputFormat::Ndjson
+    } else {
+        InputFormat::Json
+    }
+}
+
+type Record = std::result::Result<serde_json::Value, String>;
+
+/// Pass the records of `input` to `record` one at a time, as they are read,
+/// until it returns `false`. A malformed NDJSON line fails on its own; a
+/// malformed JSON document stops the import where it breaks.
+fn read_records(
+    input: impl BufRead,
+    format: InputFormat,
+    record: &mut dyn FnMut(usize, Record) -> bool,
+) -> std::result::Result<(), String> {
+    match format {
+        InputFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_reader(input);
+            deserializer
+                .deserialize_any(Records(record))
+                .and_then(|()| deserializer.end())
+                .map_err(|err| format!("Invalid JSON input: {err}"))
+        }
+        InputFormat::Ndjson => {
+            for (idx, line) in input.lines().enumerate() {
+                let line = line.map_err(|err| format!("Failed to read line {}: {err}", idx + 1))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let value =
+                    serde_json::from_str(&line).map_err(|err| format!("invalid JSON: {err}"));
+                if !record(idx + 1, value) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Walks a JSON document: each element of an array is a record, as is a
+/// lone item.
+struct Records<'a>(&'a mut dyn FnMut(usize, Record) -> bool);
+
+impl<'de> Visitor<'de> for Records<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an item or an array of items")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        let mut position = 0;
+        while let Some(value) = seq.next_element()? {
+            position += 1;
+            if !(self.0)(position, Ok(value)) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<(), A::Error> {
+        let value = serde_json::Value::deserialize(MapAccessDeserializer::new(map))?;
+        (self.0)(1, Ok(value));
+        Ok(())
+    }
+}
+
+/// Decodes records and groups the items into batches for the writers.
+struct Batcher {
+    key_names: Vec<String>,
+    encoding: Encoding,
+    batch: Vec<(usize, Item)>,
+    tx: mpsc::Sender<Parsed>,
+}
+
+impl Batcher {
+    /// Add a record to the batch, sending the batch on once it is full.
+    /// `false` once nothing is listening any more.
+    fn push(&mut self, position: usize, record: Record) -> bool {
+        let item = record
+            .and_then(|value| decode_item(&value, self.encoding))
+            .and_then(|item| self.check(&item).map(|()| item));
+        let parsed = match item {
+            Ok(item) => {
+                self.batch.push((position, item));
+                if self.batch.len() < BATCH_SIZE {
+                    return true;
+                }
+                Parsed::Batch(mem::take(&mut self.batch))
+            }
+            Err(message) => Parsed::Failed(Failure { position, message }),
+        };
+        self.tx.blocking_send(parsed).is_ok()
+    }
+
+    /// Send the last, partial batch.
+    fn finish(mut self) {
+        if !self.batch.is_empty() {
+            let _ = self
+                .tx
+                .blocking_send(Parsed::Batch(mem::take(&mut self.batch)));
+        }
+    }
+
+    /// Whether `item` has the whole key, and a key no other item in the batch
+    /// has: DynamoDB rejects a batch that writes the same key twice.
+    fn check(&self, item: &Item) -> std::result::Result<(), String> {
+        if let Some(missing) = self
+            .key_names
+            .iter()
+            .find(|name| !item.contains_key(name.as_str()))
+        {
+            return Err(format!("missing key attribute {missing}"));
+        }
+        let same_key = |other: &Item| {
+            self.key_names
+                .iter()
+                .all(|name| other.get(name) == item.get(name))
+        };
+        match self.batch.iter().find(|(_, other)| same_key(other)) {
+            Some((earlier, _)) => Err(format!(
+                "same key as record {earlier}, which is in the same batch"
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+fn decode_item(value: &serde_json::Value, encoding: Encoding) -> std::result::Result<Item, String> {
+    let typed = match encoding {
+        Encoding::Plain => false,
+        Encoding::Dynamodb => true,
+        Encoding::Auto => looks_typed(value),
+    };
+    let result = if typed {
+        // S3 exports wrap each item as `{"Item": {..}}`.
+        let inner = value
+            .as_object()
+            .filter(|map| map.len() == 1)
+            .and_then(|map| map.get("Item"))
+            .filter(|inner| inner.is_object())
+            .unwrap_or(value);
+        item_from_typed_json(inner)
+    } else {
+        item_from_json(value)
+    };
+    result.map_err(|err| err.to_string())
+}
+
+/// Whether an item is DynamoDB JSON: every attribute is an object with a
+/// single type tag (possibly inside an `{"Item": ..}` wrapper).
+fn looks_typed(value: &serde_json::Value) -> bool {
+    let Some(map) = value.as_object() else {
+        return false;
+    };
+    if map.len() == 1
+        && let Some(inner) = map.get("Item")
+        && inner.is_object()
+    {
+        return looks_typed(inner);
+    }
+    !map.is_empty()
+        && map.values().all(|attribute| {
+            attribute.as_object().is_some_and(|tagged| {
+                tagged.len() == 1 && tagged.keys().all(|tag| TYPE_TAGS.contains(&tag.as_str()))
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use dynamate::core::value::Value;
+    use serde_json::json;
+
+    use tokio::sync::mpsc;
+
+    use super::{Batcher, Encoding, InputFormat, Parsed, decode_item, detect_format, read_records};
+
+    /// The position of each record in `input`, and whether it parsed.
+    fn positions(input: &str, format: InputFormat) -> Vec<(usize, bool)> {
+        let mut positions = Vec::new();
+        read_records(input.as_bytes(), format, &mut |position, value| {
+            positions.push((position, value.is_ok()));
+            true
+        })
+        .expect("records should be read");
+        positions
+    }
+
+    #[test]
+    fn detects_input_format() {
+        assert_eq!(
+            detect_format(Path::new("items.jsonl"), "[]"),
+            InputFormat::Ndjson
+        );
+        assert_eq!(
+            detect_format(Path::new("items.json"), "  [{\"a\": 1}]"),
+            InputFormat::Json
+        );
+        assert_eq!(
+            detect_format(Path::new("-"), "{\"a\": 1}\n{\"a\": 2}\n"),
+            InputFormat::Ndjson
+        );
+        assert_eq!(
+            detect_format(Path::new("-"), "{\n  \"a\": 1\n}\n"),
+            InputFormat::Json
+        );
+    }
+
+    #[test]
+    fn ndjson_lines_fail_independently() {
+        assert_eq!(
+            positions("{\"a\": 1}\n\nnot json\n{\"a\": 2}\n", InputFormat::Ndjson),
+            vec![(1, true), (3, false), (4, true)]
+        );
+    }
+
+    #[test]
+    fn json_arrays_are_read_item_by_item() {
+        assert_eq!(
+            positions("[{\"a\": 1},\n {\"a\": 2}]", InputFormat::Json),
+            vec![(1, true), (2, true)]
+        );
+        assert_eq!(
+            positions("{\n  \"a\": 1\n}\n", InputFormat::Json),
+            vec![(1, true)]
+        );
+        assert!(
+            read_records(
+                "[{\"a\": 1}, oops]".as_bytes(),
+                InputFormat::Json,
+                &mut |_, _| true
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn a_key_repeated_within_a_batch_fails_naming_the_first() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let mut batcher = Batcher {
+            key_names: vec!["PK".to_string()],
+            encoding: Encoding::Plain,
+            batch: Vec::new(),
+            tx,
+        };
+        assert!(batcher.push(1, Ok(json!({"PK": "a"}))));
+        assert!(batcher.push(2, Ok(json!({"PK": "b"}))));
+        assert!(batcher.push(3, Ok(json!({"PK": "a", "n": 1}))));
+        batcher.finish();
+
+        match rx.try_recv() {
+            Ok(Parsed::Failed(failure)) => {
+                assert_eq!(failure.position, 3);
+                assert!(failure.message.contains("record 1"), "{}", failure.message);
+            }
+            other => panic!("expected a failure, got {other:?}"),
+        }
+        match rx.try_recv() {
+            Ok(Parsed::Batch(batch)) => assert_eq!(batch.len(), 2),
+            other => panic!("expected a batch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decodes_plain_and_dynamodb_json() {
+        let plain = decode_item(&json!({"PK": "a", "n": 1}), Encoding::Auto).expect("plain item");
+        assert_eq!(plain.get("PK"), Some(&Value::from("a")));
+
+        let typed = decode_item(
+            &json!({"Item": {"PK": {"S": "a"}, "tags": {"SS": ["x"]}}}),
+            Encoding::Auto,
+        )
+        .expect("typed item");
+        assert_eq!(typed.get("PK"), Some(&Value::from("a")));
+        assert_eq!(
+            typed.get("tags"),
+            Some(&Value::StringSet(vec!["x".to_string()]))
+        );
+
+        let forced_plain =
+            decode_item(&json!({"PK": {"S": "a"}}), Encoding::Plain).expect("plain map");
+        assert!(matches!(forced_plain.get("PK"), Some(Value::Map(_))));
+    }
+}
@@ -1,24 +1,188 @@
-use color_eyre::Result;
+use std::io::{self, BufWriter};
+
+use color_eyre::eyre::{Result, eyre};
 use dynamate::core::datastore::Datastore;
+use dynamate::core::schema::CollectionSchema;
+use dynamate::core::value::{Item, Value};
+use futures::StreamExt;
+use regex::Regex;
+
+use super::output::{ItemWriter, OutputFormat};
+
+/// Columns of the `--details` output, in order.
+const DETAIL_COLUMNS: [&str; 6] = [
+    "name",
+    "status",
+    "item_count",
+    "size_bytes",
+    "gsi_count",
+    "lsi_count",
+];
 
-pub struct Options {
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Print the names as a JSON array
+    #[arg(short, long, conflicts_with_all = ["output", "details"])]
     pub json: bool,
+
+    /// Only list tables whose names start with PREFIX
+    #[arg(long, value_name = "PREFIX")]
+    pub prefix: Option<String>,
+
+    /// Only list tables whose names match REGEX
+    #[arg(long, value_name = "REGEX")]
+    pub regex: Option<String>,
+
+    /// Describe each table for its status, item count, size, and index counts
+    #[arg(short, long)]
+    pub details: bool,
+
+    /// Output format; without one, names are printed one per line (or as a
+    /// table with --details)
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputFormat>,
+
+    /// Number of tables described concurrently with --details
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 8,
+        value_parser = clap::value_parser!(u32).range(1..=64)
+    )]
+    pub concurrency: u32,
 }
 
-pub async fn command(db: &dyn Datastore, options: Options) -> Result<()> {
-    let table_names = db.list_collections().await.map_err(|err| eyre(&err))?;
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let filter = NameFilter::new(args.prefix, args.regex.as_deref())?;
+    let table_names: Vec<String> = db
+        .list_collections()
+        .await
+        .map_err(|err| eyre!(err.to_string()))?
+        .into_iter()
+        .filter(|name| filter.matches(name))
+        .collect();
 
-    if options.json {
+    if args.json {
         println!("{}", serde_json::to_string(&table_names)?);
         return Ok(());
     }
+    let format = match (args.output, args.details) {
+        (Some(format), _) => format,
+        (None, true) => OutputFormat::Table,
+        (None, false) => {
+            for table in table_names {
+                println!("{table}");
+            }
+            return Ok(());
+        }
+    };
 
-    for table in table_names {
-        println!("{table}");
+    let (rows, columns) = if args.details {
+        let rows = futures::stream::iter(&table_names)
+            .map(|name| async move {
+                db.describe_collection(name)
+                    .await
+                    .map(|schema| detail_row(&schema))
+                    .map_err(|err| eyre!("Failed to describe {name}: {err}"))
+            })
+            .buffered(args.concurrency as usize)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+        (rows, DETAIL_COLUMNS.map(str::to_string).to_vec())
+    } else {
+        let rows = table_names
+            .into_iter()
+            .map(|name| Item::from([("name".to_string(), Value::from(name))]))
+            .collect();
+        (rows, vec!["name".to_string()])
+    };
+    let mut writer = ItemWriter::new(format, BufWriter::new(io::stdout()), columns);
+    for row in rows {
+        writer.write(row)?;
     }
+    writer.finish()?;
     Ok(())
 }
 
-fn eyre(err: &dynamate::core::error::DbError) -> color_eyre::eyre::Error {
-    color_eyre::eyre::eyre!(err.to_string())
+/// `--prefix` and `--regex`; a name has to pass both.
+struct NameFilter {
+    prefix: Option<String>,
+    regex: Option<Regex>,
+}
+
+impl NameFilter {
+    fn new(prefix: Option<String>, regex: Option<&str>) -> Result<Self> {
+        let regex = regex
+            .map(|pattern| Regex::new(pattern).map_err(|err| eyre!("Invalid --regex: {err}")))
+            .transpose()?;
+        Ok(Self { prefix, regex })
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.prefix
+            .as_deref()
+            .is_none_or(|prefix| name.starts_with(prefix))
+            && self.regex.as_ref().is_none_or(|regex| regex.is_match(name))
+    }
+}
+
+/// A table's `--details` row, in [`DETAIL_COLUMNS`] order. Values the
+/// backend doesn't report are left out, so they come out empty (or missing in
+/// JSON).
+fn detail_row(schema: &CollectionSchema) -> Item {
+    let values = [
+        Some(Value::from(schema.name.clone())),
+        schema.status.clone().map(Value::from),
+        schema.item_count.map(Value::from),
+        schema.size_bytes.map(Value::from),
+        Some(Value::from(schema.global_secondary_index_count() as i64)),
+        Some(Value::from(schema.local_secondary_index_count() as i64)),
+    ];
+    DETAIL_COLUMNS
+        .into_iter()
+        .zip(values)
+        .filter_map(|(column, value)| Some((column.to_string(), value?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::schema::{CollectionSchema, IndexKind, IndexSchema, KeySchema, Projection};
+    use dynamate::core::value::Value;
+
+    use super::{NameFilter, detail_row};
+
+    #[test]
+    fn filters_names_and_builds_detail_rows() {
+        let filter = NameFilter::new(Some("orders".to_string()), Some("-(dev|prod)$")).unwrap();
+        assert!(filter.matches("orders-prod"));
+        assert!(!filter.matches("orders-test"));
+        assert!(!filter.matches("users-prod"));
+        assert!(NameFilter::new(None, Some("(")).is_err());
+
+        let schema = CollectionSchema {
+            name: "orders-prod".to_string(),
+            indexes: vec![IndexSchema {
+                name: "by-customer".to_string(),
+                kind: IndexKind::GlobalSecondary,
+                key: KeySchema::default(),
+                projection: Projection::All,
+            }],
+            status: Some("ACTIVE".to_string()),
+            item_count: Some(42),
+            ..CollectionSchema::default()
+        };
+        let row = detail_row(&schema);
+        assert_eq!(row.get("status"), Some(&Value::from("ACTIVE")));
+        assert_eq!(row.get("item_count"), Some(&Value::from(42_i64)));
+        assert_eq!(row.get("gsi_count"), Some(&Value::from(1_i64)));
+        assert_eq!(row.get("lsi_count"), Some(&Value::from(0_i64)));
+        assert_eq!(row.get("size_bytes"), None);
+        assert_eq!(
+            row.keys().collect::<Vec<_>>(),
+            ["name", "status", "item_count", "gsi_count", "lsi_count"]
+        );
+    }
 }
@@ -0,0 +1,308 @@
+//! `dynamate local`: run against DynamoDB Local, managing its Docker container
+//! unless an endpoint is given.
+
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::dynamodb::DynamoBackend;
+
+use crate::config::LocalConfig;
+
+const DEFAULT_PORT: u16 = 8000;
+const DEFAULT_IMAGE: &str = "amazon/dynamodb-local:latest";
+const DEFAULT_CONTAINER: &str = "dynamate-dynamodb-local";
+/// Port DynamoDB Local listens on inside the container.
+const CONTAINER_PORT: u16 = 8000;
+/// How long a fresh container gets to start answering requests.
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const READY_POLL: Duration = Duration::from_millis(250);
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(subcommand)]
+    pub action: Option<Action>,
+
+    /// Use a DynamoDB Local that is already running instead of a container
+    #[arg(long, value_name = "URL", global = true)]
+    pub endpoint: Option<String>,
+
+    /// Host port for the container [default: 8000]
+    #[arg(long, global = true)]
+    pub port: Option<u16>,
+
+    /// Container image [default: amazon/dynamodb-local:latest]
+    #[arg(long, global = true)]
+    pub image: Option<String>,
+
+    /// Container name [default: dynamate-dynamodb-local]
+    #[arg(long, global = true)]
+    pub container: Option<String>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone, Copy)]
+pub enum Action {
+    /// Start the container and wait until it answers
+    Start,
+    /// Stop and remove the container, discarding its data
+    Stop,
+    /// Show whether DynamoDB Local is up
+    Status,
+}
+
+/// Where DynamoDB Local runs: the command line wins over the config file,
+/// which wins over the defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    endpoint_url: Option<String>,
+    port: u16,
+    image: String,
+    container: String,
+}
+
+impl Settings {
+    pub fn resolve(args: &Args, config: &LocalConfig) -> Self {
+        Self {
+            endpoint_url: args
+                .endpoint
+                .clone()
+                .or_else(|| config.endpoint_url.clone()),
+            port: args.port.or(config.port).unwrap_or(DEFAULT_PORT),
+            image: args
+                .image
+                .clone()
+                .or_else(|| config.image.clone())
+                .unwrap_or_else(|| DEFAULT_IMAGE.to_string()),
+            container: args
+                .container
+                .clone()
+                .or_else(|| config.container.clone())
+                .unwrap_or_else(|| DEFAULT_CONTAINER.to_string()),
+        }
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.endpoint_url
+            .clone()
+            .unwrap_or_else(|| format!("http://localhost:{}", self.port))
+    }
+
+    /// Arguments for `docker run` that start an in-memory DynamoDB Local.
+    fn run_args(&self) -> Vec<String> {
+        [
+            "run",
+            "--detach",
+            "--name",
+            &self.container,
+            "--publish",
+            &format!("{}:{CONTAINER_PORT}", self.port),
+            &self.image,
+            "-jar",
+            "DynamoDBLocal.jar",
+            "-inMemory",
+            "-sharedDb",
+        ]
+        .into_iter()
+        .map(str::to_owned)
+        .collect()
+    }
+
+    fn managed(&self) -> Result<()> {
+        match &self.endpoint_url {
+            Some(url) => Err(eyre!(
+                "DynamoDB Local at {url} isn't managed by dynamate; there is no container to change"
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContainerState {
+    Running,
+    Stopped,
+    Missing,
+}
+
+pub async fn command(settings: &Settings, action: Action, read_only: bool) -> Result<()> {
+    match action {
+        Action::Start => {
+            settings.managed()?;
+            open(settings, read_only).await?;
+            println!("DynamoDB Local is running at {}", settings.endpoint());
+            println!("Run `dynamate local` to browse it.");
+        }
+        Action::Stop => {
+            settings.managed()?;
+            if container_state(&settings.container)? == ContainerState::Missing {
+                println!("{} is not running", settings.container);
+            } else {
+                docker(&["rm", "--force", &settings.container])?;
+                println!("Stopped and removed {}", settings.container);
+            }
+        }
+        Action::Status => {
+            if settings.endpoint_url.is_none() {
+                let state = match container_state(&settings.container)? {
+                    ContainerState::Running => "running",
+                    ContainerState::Stopped => "stopped",
+                    ContainerState::Missing => "not created",
+                };
+                println!("Container {}: {state}", settings.container);
+            }
+            let db = client(settings, true).await;
+            let reachable = db.validate().await.is_ok();
+            println!(
+                "{}: {}",
+                settings.endpoint(),
+                if reachable {
+                    "reachable"
+                } else {
+                    "unreachable"
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Start the container when dynamate manages it, then connect once DynamoDB
+/// Local answers.
+pub async fn open(settings: &Settings, read_only: bool) -> Result<Arc<dyn Datastore>> {
+    let started = settings.endpoint_url.is_none() && ensure_container(settings)?;
+    let db = client(settings, read_only).await;
+    // A container that was just started takes a moment to accept requests.
+    let deadline = Instant::now() + if started { READY_TIMEOUT } else { READY_POLL };
+    loop {
+        match db.validate().await {
+            Ok(()) => return Ok(db),
+            Err(err) if Instant::now() >= deadline => {
+                return Err(eyre!(
+                    "DynamoDB Local at {} is not answering: {err}",
+                    settings.endpoint()
+                ));
+            }
+            Err(_) => tokio::time::sleep(READY_POLL).await,
+        }
+    }
+}
+
+async fn client(settings: &Settings, read_only: bool) -> Arc<dyn Datastore> {
+    let endpoint = settings.endpoint();
+    let client = dynamate::dynamodb::connect::new_local_client(&endpoint).await;
+    dynamate::core::audit::wrap(Arc::new(
+        DynamoBackend::new(client, read_only).with_endpoint_url(Some(endpoint)),
+    ))
+}
+
+/// Make sure the container runs. Returns whether it had to be started.
+fn ensure_container(settings: &Settings) -> Result<bool> {
+    match container_state(&settings.container)? {
+        ContainerState::Running => return Ok(false),
+        ContainerState::Stopped => {
+            eprintln!("Starting {}", settings.container);
+            docker(&["start", &settings.container])?;
+        }
+        ContainerState::Missing => {
+            eprintln!(
+                "Starting {} from {} on port {}",
+                settings.container, settings.image, settings.port
+            );
+            let args = settings.run_args();
+            docker(&args.iter().map(String::as_str).collect::<Vec<_>>())?;
+        }
+    }
+    Ok(true)
+}
+
+fn container_state(name: &str) -> Result<ContainerState> {
+    let filter = format!("name=^/{name}$");
+    let output = docker(&["ps", "--all", "--filter", &filter, "--format", "{{.State}}"])?;
+    Ok(parse_state(&output))
+}
+
+/// The state `docker ps --format {{.State}}` prints, or nothing when the
+/// container doesn't exist.
+fn parse_state(output: &str) -> ContainerState {
+    match output.lines().map(str::trim).find(|line| !line.is_empty()) {
+        Some("running") => ContainerState::Running,
+        Some(_) => ContainerState::Stopped,
+        None => ContainerState::Missing,
+    }
+}
+
+fn docker(args: &[&str]) -> Result<String> {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .map_err(|err| eyre!("Failed to run docker ({err}); is Docker installed?"))?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "docker {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Args, ContainerState, Settings, parse_state};
+    use crate::config::LocalConfig;
+
+    fn args() -> Args {
+        Args {
+            action: None,
+            endpoint: None,
+            port: None,
+            image: None,
+            container: None,
+        }
+    }
+
+    #[test]
+    fn settings_prefer_flags_then_config() {
+        let config = LocalConfig {
+            port: Some(8001),
+            container: Some("ddb".to_string()),
+            ..LocalConfig::default()
+        };
+        let settings = Settings::resolve(
+            &Args {
+                port: Some(9000),
+                ..args()
+            },
+            &config,
+        );
+        assert_eq!(settings.port, 9000);
+        assert_eq!(settings.container, "ddb");
+        assert_eq!(settings.image, "amazon/dynamodb-local:latest");
+        assert_eq!(settings.endpoint(), "http://localhost:9000");
+        assert!(settings.managed().is_ok());
+        assert_eq!(
+            settings.run_args()[..6],
+            ["run", "--detach", "--name", "ddb", "--publish", "9000:8000"]
+        );
+
+        let existing = Settings::resolve(
+            &args(),
+            &LocalConfig {
+                endpoint_url: Some("http://ddb:8000".to_string()),
+                ..LocalConfig::default()
+            },
+        );
+        assert_eq!(existing.endpoint(), "http://ddb:8000");
+        assert!(existing.managed().is_err());
+    }
+
+    #[test]
+    fn container_state_from_docker_ps() {
+        assert_eq!(parse_state("running\n"), ContainerState::Running);
+        assert_eq!(parse_state("exited\n"), ContainerState::Stopped);
+        assert_eq!(parse_state("\n"), ContainerState::Missing);
+    }
+}
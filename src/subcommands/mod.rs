@@ -1,2 +1,14 @@
+pub mod copy_table;
 pub mod create_table;
+pub mod delete_table;
+pub mod describe;
+pub mod export;
+pub mod export_s3;
+pub mod get;
+pub mod import;
 pub mod list_tables;
+pub mod local;
+pub mod output;
+pub mod purge;
+pub mod query;
+pub mod seed;
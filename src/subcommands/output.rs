@@ -0,0 +1,371 @@
+//! Item output for the scripting subcommands.
+//!
+//! JSON and NDJSON are streamed as items arrive; CSV and table output need
+//! every column up front, so they buffer until [`ItemWriter::finish`].
+//! Binary values are base64-encoded and sets become arrays, so every item can
+//! be written without failing on types plain JSON can't carry.
+
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use base64::Engine;
+use color_eyre::eyre::Result;
+use dynamate::core::value::{Item, Value};
+use unicode_width::UnicodeWidthStr;
+
+/// Widest cell rendered by the `table` format before truncating.
+const MAX_TABLE_CELL_WIDTH: usize = 40;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// A pretty-printed JSON array
+    #[default]
+    Json,
+    /// One JSON document per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Aligned columns for reading in a terminal
+    Table,
+}
+
+impl OutputFormat {
+    /// The format implied by a file extension (`.ndjson`/`.jsonl`, `.csv`),
+    /// if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("json") => Some(Self::Json),
+            Some("ndjson" | "jsonl") => Some(Self::Ndjson),
+            Some("csv") => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// The extension for a file in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+            Self::Table => "txt",
+        }
+    }
+}
+
+pub struct ItemWriter<W: Write> {
+    format: OutputFormat,
+    out: W,
+    key_columns: Vec<String>,
+    buffered: Vec<Item>,
+    count: usize,
+}
+
+impl<W: Write> ItemWriter<W> {
+    /// `key_columns` lead the CSV/table columns; the remaining attributes
+    /// follow in name order.
+    pub fn new(format: OutputFormat, out: W, key_columns: Vec<String>) -> Self {
+        Self {
+            format,
+            out,
+            key_columns,
+            buffered: Vec::new(),
+            count: 0,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn write(&mut self, item: Item) -> Result<()> {
+        match self.format {
+            OutputFormat::Json => {
+                let separator: &[u8] = if self.count == 0 { b"[\n" } else { b",\n" };
+                self.out.write_all(separator)?;
+                let payload = serde_json::to_string_pretty(&item_to_json(&item))?;
+                for (idx, line) in payload.lines().enumerate() {
+                    if idx > 0 {
+                        self.out.write_all(b"\n")?;
+                    }
+                    write!(self.out, "  {line}")?;
+                }
+            }
+            OutputFormat::Ndjson => {
+                serde_json::to_writer(&mut self.out, &item_to_json(&item))?;
+                self.out.write_all(b"\n")?;
+            }
+            OutputFormat::Csv | OutputFormat::Table => self.buffered.push(item),
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Flush any buffered output and return the number of items written.
+    pub fn finish(mut self) -> Result<usize> {
+        match self.format {
+            OutputFormat::Json => {
+                let closing: &[u8] = if self.count == 0 { b"[]\n" } else { b"\n]\n" };
+                self.out.write_all(closing)?;
+            }
+            OutputFormat::Ndjson => {}
+            OutputFormat::Csv => {
+                let columns = columns(&self.key_columns, &self.buffered);
+                write_csv(&mut self.out, &columns, &self.buffered)?;
+            }
+            OutputFormat::Table => {
+                let columns = columns(&self.key_columns, &self.buffered);
+                write_table(&mut self.out, &columns, &self.buffered)?;
+            }
+        }
+        self.out.flush()?;
+        Ok(self.count)
+    }
+}
+
+fn columns(key_columns: &[String], items: &[Item]) -> Vec<String> {
+    let rest: BTreeSet<&String> = items
+        .iter()
+        .flat_map(|item| item.keys())
+        .filter(|name| !key_columns.contains(name))
+        .collect();
+    key_columns
+        .iter()
+        .cloned()
+        .chain(rest.into_iter().cloned())
+        .collect()
+}
+
+fn write_csv<W: Write>(out: &mut W, columns: &[String], items: &[Item]) -> Result<()> {
+    let header: Vec<String> = columns.iter().map(|name| csv_field(name)).collect();
+    writeln!(out, "{}", header.join(","))?;
+    for item in items {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|name| csv_field(&item.get(name).map(cell_text).unwrap_or_default()))
+            .collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn write_table<W: Write>(out: &mut W, columns: &[String], items: &[Item]) -> Result<()> {
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            columns
+                .iter()
+                .map(|name| table_cell(&item.get(name).map(cell_text).unwrap_or_default()))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            rows.iter()
+                .map(|row| row[idx].width())
+                .chain(std::iter::once(name.width()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let header: Vec<String> = columns.iter().map(|name| table_cell(name)).collect();
+    write_table_row(out, &header, &widths)?;
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    write_table_row(out, &rule, &widths)?;
+    for row in &rows {
+        write_table_row(out, row, &widths)?;
+    }
+    Ok(())
+}
+
+fn write_table_row<W: Write>(out: &mut W, cells: &[String], widths: &[usize]) -> Result<()> {
+    let mut line = String::new();
+    for (idx, (cell, width)) in cells.iter().zip(widths).enumerate() {
+        if idx > 0 {
+            line.push_str("  ");
+        }
+        line.push_str(cell);
+        line.push_str(&" ".repeat(width.saturating_sub(cell.width())));
+    }
+    writeln!(out, "{}", line.trim_end())?;
+    Ok(())
+}
+
+/// Flatten a cell onto one line and cap its width.
+fn table_cell(text: &str) -> String {
+    let text = text.replace(['\n', '\r', '\t'], " ");
+    if text.width() <= MAX_TABLE_CELL_WIDTH {
+        return text;
+    }
+    let mut truncated = String::new();
+    for ch in text.chars() {
+        if truncated.width() + ch.to_string().width() >= MAX_TABLE_CELL_WIDTH {
+            break;
+        }
+        truncated.push(ch);
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// The text of a single CSV/table cell: scalars as-is, nested values as
+/// compact JSON.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => b.to_string(),
+        Value::Str(s) => s.clone(),
+        Value::Num(n) => n.to_string(),
+        Value::Bytes(bytes) => encode_bytes(bytes),
+        Value::List(_)
+        | Value::Map(_)
+        | Value::StringSet(_)
+        | Value::NumberSet(_)
+        | Value::BytesSet(_) => value_to_json(value).to_string(),
+    }
+}
+
+pub fn item_to_json(item: &Item) -> serde_json::Value {
+    serde_json::Value::Object(
+        item.iter()
+            .map(|(name, value)| (name.clone(), value_to_json(value)))
+            .collect(),
+    )
+}
+
+fn value_to_json(value: &Value) -> serde_json::Value {
+    use serde_json::Value as Json;
+
+    let number = |text: &str| {
+        serde_json::Number::from_str(text)
+            .map_or_else(|_| Json::String(text.to_string()), Json::Number)
+    };
+    match value {
+        Value::Null => Json::Null,
+        Value::Bool(b) => Json::Bool(*b),
+        Value::Str(s) => Json::String(s.clone()),
+        Value::Num(n) => number(n.as_str()),
+        Value::Bytes(bytes) => Json::String(encode_bytes(bytes)),
+        Value::List(list) => Json::Array(list.iter().map(value_to_json).collect()),
+        Value::Map(map) => item_to_json(map),
+        Value::StringSet(set) => Json::Array(set.iter().cloned().map(Json::String).collect()),
+        Value::NumberSet(set) => Json::Array(set.iter().map(|n| number(n.as_str())).collect()),
+        Value::BytesSet(set) => Json::Array(
+            set.iter()
+                .map(|bytes| Json::String(encode_bytes(bytes)))
+                .collect(),
+        ),
+    }
+}
+
+fn encode_bytes(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use dynamate::core::value::{Item, Number, Value};
+
+    use super::{ItemWriter, OutputFormat};
+
+    fn item(pk: &str, extra: &[(&str, Value)]) -> Item {
+        let mut item = Item::new();
+        item.insert("PK".to_string(), Value::from(pk));
+        for (name, value) in extra {
+            item.insert((*name).to_string(), value.clone());
+        }
+        item
+    }
+
+    fn render(format: OutputFormat, items: Vec<Item>) -> String {
+        let mut out = Vec::new();
+        let mut writer = ItemWriter::new(format, &mut out, vec!["PK".to_string()]);
+        for item in items {
+            writer.write(item).expect("item should be written");
+        }
+        writer.finish().expect("writer should finish");
+        String::from_utf8(out).expect("output should be utf-8")
+    }
+
+    #[test]
+    fn format_is_inferred_from_extension() {
+        assert_eq!(
+            OutputFormat::from_path(Path::new("out.JSONL")),
+            Some(OutputFormat::Ndjson)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("out.csv")),
+            Some(OutputFormat::Csv)
+        );
+        assert_eq!(OutputFormat::from_path(Path::new("out.txt")), None);
+    }
+
+    #[test]
+    fn json_output_is_an_array_even_when_empty() {
+        assert_eq!(render(OutputFormat::Json, Vec::new()), "[]\n");
+        let output = render(
+            OutputFormat::Json,
+            vec![item("a", &[]), item("b", &[("n", Value::from(2))])],
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&output).expect("valid json");
+        assert_eq!(parsed[1]["n"], 2);
+    }
+
+    #[test]
+    fn ndjson_writes_sets_and_binary_as_arrays_and_base64() {
+        let output = render(
+            OutputFormat::Ndjson,
+            vec![item(
+                "a",
+                &[
+                    ("tags", Value::StringSet(vec!["x".to_string()])),
+                    ("scores", Value::NumberSet(vec![Number::new("1.5")])),
+                    ("blob", Value::Bytes(vec![1, 2, 3])),
+                ],
+            )],
+        );
+        assert_eq!(
+            output,
+            "{\"PK\":\"a\",\"tags\":[\"x\"],\"scores\":[1.5],\"blob\":\"AQID\"}\n"
+        );
+    }
+
+    #[test]
+    fn csv_puts_keys_first_and_quotes_fields() {
+        let output = render(
+            OutputFormat::Csv,
+            vec![
+                item("a", &[("z", Value::from("x, y")), ("b", Value::from(true))]),
+                item("b", &[("z", Value::from("say \"hi\""))]),
+            ],
+        );
+        assert_eq!(output, "PK,b,z\na,true,\"x, y\"\nb,,\"say \"\"hi\"\"\"\n");
+    }
+
+    #[test]
+    fn table_aligns_columns() {
+        let output = render(
+            OutputFormat::Table,
+            vec![item("alpha", &[("n", Value::from(1))]), item("b", &[])],
+        );
+        assert_eq!(output, "PK     n\n-----  -\nalpha  1\nb\n");
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::purge::{PurgeOptions, PurgeProgress, purge_items};
+use dynamate::core::query::QueryPlan;
+
+use super::delete_table::confirm;
+use crate::safety::Action;
+
+/// Characters in the progress bar.
+const BAR_WIDTH: usize = 30;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to delete items from
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// Only delete items matching this expression (same syntax as the TUI
+    /// query box)
+    #[arg(long, value_name = "EXPRESSION")]
+    pub filter: Option<String>,
+
+    /// Count the items that would be deleted without deleting them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Split the scan into this many segments purged in parallel
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..=1_000_000)
+    )]
+    pub segments: u32,
+
+    /// Read at most this many items per second; takes precedence over
+    /// --max-rcu and the config file's budget
+    #[arg(long, value_name = "ITEMS")]
+    pub rate: Option<f64>,
+
+    /// Delete without asking for the table name as confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table_name = args.table.trim();
+    // Backends without parallel scans page through the table in one go.
+    let segments = if db.capabilities().parallel_scan {
+        args.segments
+    } else {
+        1
+    };
+    let schema = db
+        .describe_collection(table_name)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    if !args.dry_run && !args.yes {
+        let what = if args.filter.is_some() {
+            "the matching items"
+        } else {
+            "every item"
+        };
+        confirm(
+            Action::PurgeTable,
+            table_name,
+            &format!("This deletes {what} in {table_name}"),
+        )?;
+    }
+
+    let filter = args
+        .filter
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string);
+    let plan = QueryPlan::new(filter, None);
+    let options = PurgeOptions {
+        segments,
+        dry_run: args.dry_run,
+    };
+    // Without a filter every item goes, so the table's item count is the
+    // bar's end; a filtered purge only shows counts.
+    let estimate = schema.item_count.filter(|_| plan.filter.is_none());
+    let on_progress = |progress: PurgeProgress| {
+        eprint!("\r{}", progress_line(progress, estimate, args.dry_run));
+        let _ = io::stderr().flush();
+    };
+    let cancel = AtomicBool::new(false);
+    let purge = purge_items(db, table_name, &plan, options, &cancel, &on_progress);
+    tokio::pin!(purge);
+    let result = loop {
+        tokio::select! {
+            result = &mut purge => break result,
+            _ = tokio::signal::ctrl_c(), if !cancel.load(Ordering::Relaxed) => {
+                cancel.store(true, Ordering::Relaxed);
+                eprint!("\nStopping after the batches in flight...");
+            }
+        }
+    };
+    eprintln!();
+    let summary = result.map_err(|err| eyre!(err.to_string()))?;
+
+    let verb = if summary.dry_run {
+        "Would delete"
+    } else {
+        "Deleted"
+    };
+    eprintln!(
+        "{verb} {} items from {table_name} ({} scanned) in {:.1}s",
+        summary.progress.deleted,
+        summary.progress.scanned,
+        summary.elapsed.as_secs_f64()
+    );
+    for error in &summary.errors {
+        eprintln!("  {error}");
+    }
+    if summary.cancelled {
+        Err(eyre!("Purge interrupted; run it again to delete the rest"))
+    } else if summary.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!("{} segments failed", summary.errors.len()))
+    }
+}
+
+/// The stderr progress line: a bar when the total is known, counts otherwise.
+fn progress_line(progress: PurgeProgress, estimate: Option<i64>, dry_run: bool) -> String {
+    let verb = if dry_run { "matched" } else { "deleted" };
+    let counts = format!("{} {verb}, {} scanned", progress.deleted, progress.scanned);
+    let Some(total) = estimate
+        .and_then(|total| u64::try_from(total).ok())
+        .filter(|total| *total > 0)
+    else {
+        return counts;
+    };
+    // The item count DynamoDB reports lags behind, so keep the bar in range.
+    let done = progress.deleted.min(total);
+    let filled = (done as f64 / total as f64 * BAR_WIDTH as f64).round() as usize;
+    format!(
+        "[{}{}] {:>3}% {counts} of ~{total}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled),
+        done * 100 / total
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::purge::PurgeProgress;
+
+    use super::progress_line;
+
+    #[test]
+    fn progress_line_shows_a_bar_when_the_total_is_known() {
+        let progress = PurgeProgress {
+            deleted: 50,
+            scanned: 60,
+        };
+        assert_eq!(
+            progress_line(progress, Some(200), false),
+            format!(
+                "[{}{}]  25% 50 deleted, 60 scanned of ~200",
+                "#".repeat(8),
+                "-".repeat(22)
+            )
+        );
+        assert_eq!(
+            progress_line(progress, None, true),
+            "50 matched, 60 scanned"
+        );
+        assert!(progress_line(progress, Some(10), false).starts_with("[####"));
+        assert!(progress_line(progress, Some(10), false).contains("100%"));
+    }
+}
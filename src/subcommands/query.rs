@@ -0,0 +1,156 @@
+use std::io::{self, BufWriter, Read};
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::query::{Page, QueryPlan};
+
+use super::output::{ItemWriter, OutputFormat};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to query
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// Query expression, in the same syntax as the TUI query box. Runs a
+    /// scan when omitted; `-` reads it from standard input.
+    #[arg(value_name = "EXPRESSION")]
+    pub expression: Option<String>,
+
+    /// Read the query expression from a file instead, e.g. for long
+    /// multi-line expressions; `-` reads standard input
+    #[arg(long, value_name = "PATH", conflicts_with = "expression")]
+    pub query_file: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    pub output: OutputFormat,
+
+    /// Stop after this many items
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Use strongly consistent reads
+    #[arg(long)]
+    pub consistent: bool,
+
+    /// Value for a `:name` parameter in the expression, written like a
+    /// literal in the query box (e.g. `--param id='"USER#1"'`). Repeatable.
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+    pub params: Vec<(String, String)>,
+}
+
+fn parse_param(arg: &str) -> Result<(String, String), String> {
+    let (name, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got `{arg}`"))?;
+    let name = name.trim().trim_start_matches(':');
+    if name.is_empty() {
+        return Err(format!("missing parameter name in `{arg}`"));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table_name = args.table.trim();
+    let schema = db
+        .describe_collection(table_name)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    let key_columns = schema.key.fields.iter().map(|f| f.name.clone()).collect();
+    let filter = expression(&args)?
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string);
+    let plan = QueryPlan {
+        consistent_read: args.consistent,
+        parameters: args.params,
+        ..QueryPlan::new(filter, None)
+    };
+
+    let mut writer = ItemWriter::new(args.output, BufWriter::new(io::stdout()), key_columns);
+    let mut cursor = None;
+    loop {
+        let remaining = args.limit.map(|limit| limit.saturating_sub(writer.count()));
+        if remaining == Some(0) {
+            break;
+        }
+        let page = Page {
+            cursor: cursor.take(),
+            limit: remaining.map(|n| u32::try_from(n).unwrap_or(u32::MAX)),
+        };
+        dynamate::throttle::acquire().await;
+        let result = db
+            .query(table_name, &plan, page)
+            .await
+            .map_err(|err| eyre!(err.to_string()))?;
+        dynamate::throttle::charge(&result);
+        for item in result.items {
+            if args.limit == Some(writer.count()) {
+                break;
+            }
+            writer.write(item)?;
+        }
+        match result.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// The expression given inline, in `--query-file`, or on standard input.
+fn expression(args: &Args) -> Result<Option<String>> {
+    match (args.expression.as_deref(), args.query_file.as_deref()) {
+        (Some("-"), _) => read_input(Path::new("-")).map(Some),
+        (Some(expression), _) => Ok(Some(expression.to_string())),
+        (None, Some(path)) => read_input(path).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+fn read_input(path: &Path) -> Result<String> {
+    if path.as_os_str() == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return Ok(input);
+    }
+    std::fs::read_to_string(path).map_err(|err| eyre!("Failed to read {}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Args, expression};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: Args,
+    }
+
+    #[test]
+    fn reads_the_expression_from_a_file() {
+        let path = std::env::temp_dir().join(format!("dynamate-query-{}.txt", std::process::id()));
+        std::fs::write(&path, "PK = \"USER#1\"\n  AND status = \"OPEN\"\n").unwrap();
+        let cli =
+            Cli::try_parse_from(["dynamate", "orders", "--query-file", path.to_str().unwrap()])
+                .unwrap();
+        assert_eq!(
+            expression(&cli.args).unwrap().as_deref(),
+            Some("PK = \"USER#1\"\n  AND status = \"OPEN\"\n")
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(
+            Cli::try_parse_from(["dynamate", "orders", "PK = 1", "--query-file", "q.txt"]).is_err()
+        );
+        let cli = Cli::try_parse_from(["dynamate", "orders", "PK = 1"]).unwrap();
+        assert_eq!(expression(&cli.args).unwrap().as_deref(), Some("PK = 1"));
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::atomic::AtomicBool;
+
+use color_eyre::eyre::{Result, eyre};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::json::item_to_json_string;
+use dynamate::core::seed::{
+    DEFAULT_TEMPLATES, SeedOptions, SeedSpec, Template, parse_template_assignment, parse_templates,
+    seed_items, seeded_rng,
+};
+
+use super::delete_table::confirm;
+use crate::safety::Action;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Table to write into
+    #[arg(value_name = "TABLE")]
+    pub table: String,
+
+    /// Number of items to generate
+    #[arg(short = 'n', long, default_value_t = 100)]
+    pub count: u64,
+
+    /// Template for an attribute, e.g. `pk=user#{uuid}` or
+    /// `score={int:1..100}`; repeat for more attributes. Key attributes
+    /// without one get `{uuid}` (strings) or `{seq}` (numbers). Without any,
+    /// a few sample attributes are generated.
+    #[arg(
+        short,
+        long = "attr",
+        value_name = "NAME=TEMPLATE",
+        value_parser = parse_template_assignment
+    )]
+    pub attrs: Vec<(String, Template)>,
+
+    /// Seed for the random values, to generate the same items again
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+
+    /// Number of batches written concurrently
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..=64)
+    )]
+    pub concurrency: u32,
+
+    /// Print the items as NDJSON instead of writing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Write without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+pub async fn command(db: &dyn Datastore, args: Args) -> Result<()> {
+    let table_name = args.table.trim();
+    let schema = db
+        .describe_collection(table_name)
+        .await
+        .map_err(|err| eyre!(err.to_string()))?;
+    let templates = if args.attrs.is_empty() {
+        parse_templates(DEFAULT_TEMPLATES).map_err(|err| eyre!(err))?
+    } else {
+        args.attrs
+    };
+    let spec = SeedSpec::new(&schema.key, templates).map_err(|err| eyre!(err))?;
+
+    if args.dry_run {
+        let mut rng = seeded_rng(args.seed);
+        for seq in 1..=args.count {
+            let item = spec.item(seq, &mut rng);
+            println!("{}", item_to_json_string(&item)?);
+        }
+        return Ok(());
+    }
+
+    if !args.yes {
+        confirm(
+            Action::Seed,
+            table_name,
+            &format!("This writes {} generated items to {table_name}", args.count),
+        )?;
+    }
+
+    let options = SeedOptions {
+        count: args.count,
+        concurrency: args.concurrency as usize,
+        seed: args.seed,
+    };
+    let summary = seed_items(
+        db,
+        table_name,
+        &spec,
+        options,
+        &AtomicBool::new(false),
+        &|_| {},
+    )
+    .await
+    .map_err(|err| eyre!(err.to_string()))?;
+
+    println!("Written: {}", summary.progress.written);
+    println!("Failed: {}", summary.progress.failed);
+    for error in &summary.errors {
+        eprintln!("  {error}");
+    }
+    if summary.progress.failed == 0 {
+        Ok(())
+    } else {
+        Err(eyre!("{} items were not written", summary.progress.failed))
+    }
+}
@@ -0,0 +1,158 @@
+//! Tabs of independent widget stacks. Each tab keeps its own navigation
+//! history; only the active tab is drawn and receives input, but widgets in
+//! every tab keep processing their own events in the background.
+
+pub struct Tabs<T> {
+    stacks: Vec<Vec<T>>,
+    active: usize,
+}
+
+impl<T> Tabs<T> {
+    pub fn new() -> Self {
+        Self {
+            stacks: Vec::new(),
+            active: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Open a new tab after the others with `root` at the bottom of its
+    /// stack, and make it active.
+    pub fn open(&mut self, root: T) {
+        self.stacks.push(vec![root]);
+        self.active = self.stacks.len() - 1;
+    }
+
+    /// The active tab's stack, bottom first.
+    pub fn active(&self) -> &[T] {
+        self.stacks.get(self.active).map_or(&[], Vec::as_slice)
+    }
+
+    /// The widget on top of the active tab.
+    pub fn top(&self) -> Option<&T> {
+        self.active().last()
+    }
+
+    /// Every tab's stack, in tab order.
+    pub fn stacks(&self) -> impl Iterator<Item = &[T]> {
+        self.stacks.iter().map(Vec::as_slice)
+    }
+
+    /// Every widget in every tab.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.stacks.iter().flatten()
+    }
+
+    /// The tab holding a widget that matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<usize> {
+        self.stacks
+            .iter()
+            .position(|stack| stack.iter().any(&predicate))
+    }
+
+    pub fn push(&mut self, tab: usize, widget: T) {
+        if let Some(stack) = self.stacks.get_mut(tab) {
+            stack.push(widget);
+        }
+    }
+
+    /// Pop the top widget of `tab`. A tab left empty is closed.
+    pub fn pop(&mut self, tab: usize) -> Option<T> {
+        let stack = self.stacks.get_mut(tab)?;
+        let popped = stack.pop();
+        if stack.is_empty() {
+            self.close(tab);
+        }
+        popped
+    }
+
+    /// Close `tab`. Closing another tab keeps the active one; closing the
+    /// active tab activates the one after it, or the one before if it was last.
+    pub fn close(&mut self, tab: usize) -> Vec<T> {
+        if tab >= self.stacks.len() {
+            return Vec::new();
+        }
+        let closed = self.stacks.remove(tab);
+        if tab < self.active || (tab == self.active && self.active == self.stacks.len()) {
+            self.active = self.active.saturating_sub(1);
+        }
+        closed
+    }
+
+    /// Make `tab` active. Returns false when there is no such tab.
+    pub fn select(&mut self, tab: usize) -> bool {
+        if tab < self.stacks.len() {
+            self.active = tab;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn next(&mut self) {
+        if !self.stacks.is_empty() {
+            self.active = (self.active + 1) % self.stacks.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.stacks.is_empty() {
+            self.active = (self.active + self.stacks.len() - 1) % self.stacks.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tabs;
+
+    #[test]
+    fn switching_wraps_around() {
+        let mut tabs = Tabs::new();
+        tabs.open("a");
+        tabs.open("b");
+        tabs.open("c");
+        assert_eq!(tabs.top(), Some(&"c"));
+        tabs.next();
+        assert_eq!(tabs.top(), Some(&"a"));
+        tabs.previous();
+        assert_eq!(tabs.top(), Some(&"c"));
+        assert!(tabs.select(1));
+        assert_eq!(tabs.top(), Some(&"b"));
+        assert!(!tabs.select(3));
+        assert_eq!(tabs.active_index(), 1);
+    }
+
+    #[test]
+    fn stacks_are_independent_and_empty_tabs_close() {
+        let mut tabs = Tabs::new();
+        tabs.open("tables");
+        tabs.open("orders");
+        tabs.push(0, "users");
+        assert_eq!(tabs.active(), ["orders"]);
+        assert_eq!(tabs.position(|w| *w == "users"), Some(0));
+
+        assert_eq!(tabs.pop(1), Some("orders"));
+        assert_eq!(tabs.len(), 1);
+        assert_eq!(tabs.active(), ["tables", "users"]);
+
+        tabs.open("orders");
+        tabs.select(1);
+        tabs.close(0);
+        assert_eq!(tabs.active(), ["orders"]);
+        tabs.close(0);
+        assert!(tabs.is_empty());
+        assert_eq!(tabs.top(), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Client-side throttling of bulk reads.
+//!
+//! Paginated scans, exports, and purges can read a table far faster than its
+//! provisioned (or affordable) capacity allows, starving production traffic.
+//! When a [`Budget`] is set, callers wait in [`acquire`] before each request
+//! and report what the request cost with [`charge`]; the shared token bucket
+//! keeps the average rate under the budget across concurrent requests.
+
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::core::query::QueryResult;
+
+static THROTTLE: OnceLock<Throttle> = OnceLock::new();
+
+/// How fast bulk reads may go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    /// Read capacity units per second. Backends that don't report consumed
+    /// capacity are charged one unit per item examined.
+    CapacityUnits(f64),
+    /// Items examined per second.
+    Items(f64),
+}
+
+impl Budget {
+    fn rate(self) -> f64 {
+        match self {
+            Budget::CapacityUnits(rate) | Budget::Items(rate) => rate,
+        }
+    }
+
+    /// What one page of results counts against the budget.
+    pub fn cost(self, result: &QueryResult) -> f64 {
+        let examined = result.scanned_count.unwrap_or(result.count) as f64;
+        match self {
+            Budget::CapacityUnits(_) => result
+                .cost
+                .as_ref()
+                .and_then(|cost| cost.capacity_units)
+                .unwrap_or(examined),
+            Budget::Items(_) => examined,
+        }
+    }
+}
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Budget::CapacityUnits(rate) => write!(f, "{rate} RCU/s"),
+            Budget::Items(rate) => write!(f, "{rate} items/s"),
+        }
+    }
+}
+
+struct Throttle {
+    budget: Budget,
+    bucket: Mutex<Bucket>,
+}
+
+/// A token bucket holding at most one second of budget. Requests are charged
+/// after the fact, so the balance goes negative and the next request waits
+/// until it refills.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        Self {
+            tokens: rate,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, rate: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.updated = now;
+    }
+
+    /// How long until the balance is back to zero.
+    fn wait(&mut self, rate: f64, now: Instant) -> Duration {
+        self.refill(rate, now);
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / rate)
+        }
+    }
+
+    fn charge(&mut self, rate: f64, cost: f64, now: Instant) {
+        self.refill(rate, now);
+        self.tokens -= cost;
+    }
+}
+
+/// Set the budget for the rest of the process. Only the first call takes
+/// effect; `None` (or a non-positive rate) leaves reads unthrottled.
+pub fn set(budget: Option<Budget>) {
+    let Some(budget) = budget.filter(|budget| budget.rate() > 0.0) else {
+        return;
+    };
+    let _ = THROTTLE.set(Throttle {
+        budget,
+        bucket: Mutex::new(Bucket::new(budget.rate(), Instant::now())),
+    });
+}
+
+pub fn budget() -> Option<Budget> {
+    THROTTLE.get().map(|throttle| throttle.budget)
+}
+
+/// Wait until the budget allows another request.
+pub async fn acquire() {
+    let Some(throttle) = THROTTLE.get() else {
+        return;
+    };
+    let rate = throttle.budget.rate();
+    loop {
+        let wait = throttle
+            .bucket
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .wait(rate, Instant::now());
+        if wait.is_zero() {
+            return;
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Count a page of results against the budget.
+pub fn charge(result: &QueryResult) {
+    let Some(throttle) = THROTTLE.get() else {
+        return;
+    };
+    let budget = throttle.budget;
+    throttle
+        .bucket
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .charge(budget.rate(), budget.cost(result), Instant::now());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Bucket, Budget};
+    use crate::core::query::{PlanKind, QueryCost, QueryResult};
+
+    fn result(scanned: u64, capacity: Option<f64>) -> QueryResult {
+        QueryResult {
+            items: Vec::new(),
+            count: 1,
+            scanned_count: Some(scanned),
+            next: None,
+            plan_kind: PlanKind::Scan,
+            cost: capacity.map(|units| QueryCost {
+                capacity_units: Some(units),
+            }),
+        }
+    }
+
+    #[test]
+    fn bucket_waits_off_debt_at_the_budget_rate() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(10.0, start);
+        assert_eq!(bucket.wait(10.0, start), Duration::ZERO);
+        bucket.charge(10.0, 30.0, start);
+        assert_eq!(bucket.wait(10.0, start), Duration::from_secs(2));
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.wait(10.0, later), Duration::from_secs(1));
+        // Idle time never banks more than one second of budget.
+        let much_later = start + Duration::from_secs(90);
+        bucket.charge(10.0, 15.0, much_later);
+        assert_eq!(bucket.wait(10.0, much_later), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn capacity_budget_falls_back_to_items_examined() {
+        let budget = Budget::CapacityUnits(100.0);
+        assert_eq!(budget.cost(&result(40, Some(2.5))).to_string(), "2.5");
+        assert_eq!(budget.cost(&result(40, None)).to_string(), "40");
+        let items = Budget::Items(100.0);
+        assert_eq!(items.cost(&result(40, Some(2.5))).to_string(), "40");
+        assert_eq!(Budget::CapacityUnits(50.0).to_string(), "50 RCU/s");
+    }
+}
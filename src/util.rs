@@ -1,4 +1,14 @@
-use std::{env, path::Path};
+use std::{
+    cell::Cell,
+    env,
+    path::Path,
+    process::Command,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 use directories::BaseDirs;
 
@@ -35,6 +45,221 @@ pub fn env_flag(name: &str) -> bool {
     }
 }
 
+/// Segments a full scan is split into when every page is fetched up front,
+/// from `DYNAMATE_SCAN_SEGMENTS` (default 4; `1` pages sequentially).
+pub fn scan_segments() -> u32 {
+    env::var("DYNAMATE_SCAN_SEGMENTS")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(4)
+}
+
+static MOUSE_CAPTURE: AtomicBool = AtomicBool::new(false);
+
+/// Capture the mouse in the TUI (`--mouse`, or `mouse` in the config file).
+pub fn set_mouse_capture(enabled: bool) {
+    MOUSE_CAPTURE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the TUI captures the mouse. Capturing disables the terminal's own
+/// text selection, so it stays opt-in; `DYNAMATE_MOUSE_CAPTURE` also turns it
+/// on.
+pub fn mouse_capture() -> bool {
+    MOUSE_CAPTURE.load(Ordering::Relaxed) || env_flag("DYNAMATE_MOUSE_CAPTURE")
+}
+
+static REGIONS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// The regions the table list can show at once (`--regions`, or `regions` in
+/// the config file).
+pub fn set_regions(regions: Vec<String>) {
+    let _ = REGIONS.set(regions);
+}
+
+pub fn regions() -> &'static [String] {
+    REGIONS.get().map_or(&[], Vec::as_slice)
+}
+
+static EDITOR: OnceLock<String> = OnceLock::new();
+
+/// What items and long queries are edited in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Editor {
+    /// The editor popup inside the TUI.
+    Builtin,
+    /// A shell command, given the path of the file to edit.
+    Command(String),
+}
+
+/// `editor` in the config file: `builtin`, or a command to use instead of
+/// `$EDITOR`.
+pub fn set_editor(editor: String) {
+    let _ = EDITOR.set(editor);
+}
+
+/// The configured editor, else `$EDITOR`, else the built-in one.
+pub fn editor() -> Editor {
+    match EDITOR.get() {
+        Some(editor) if editor == "builtin" => Editor::Builtin,
+        Some(editor) => Editor::Command(editor.clone()),
+        None => env::var("EDITOR")
+            .ok()
+            .filter(|editor| !editor.trim().is_empty())
+            .map_or(Editor::Builtin, Editor::Command),
+    }
+}
+
+/// The process that opens `path` in `editor`. A shell runs the editor
+/// command on Unix, so `EDITOR` may hold arguments or shell syntax, and the
+/// path is passed as a separate argument, so it needs no quoting. Windows has
+/// no `sh`, so the command is split into words and run directly.
+pub fn editor_command(editor: &str, path: &Path) -> Command {
+    if cfg!(windows) {
+        direct_command(editor, path)
+    } else {
+        shell_command(editor, path)
+    }
+}
+
+fn shell_command(editor: &str, path: &Path) -> Command {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("sh")
+        .arg(path);
+    command
+}
+
+fn direct_command(editor: &str, path: &Path) -> Command {
+    let mut words = command_words(editor).into_iter();
+    let mut command = Command::new(words.next().unwrap_or_default());
+    command.args(words).arg(path);
+    command
+}
+
+/// `command` split on whitespace, with double quotes keeping words together,
+/// as in `"C:\Program Files\Notepad++\notepad++.exe" -multiInst`.
+fn command_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut in_word = false;
+    for ch in command.chars() {
+        match ch {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            ch if ch.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            ch => {
+                word.push(ch);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+static ITEM_TEMPLATES: OnceLock<Vec<crate::config::SavedTemplate>> = OnceLock::new();
+
+/// The items new ones can start from (`templates` in the config file).
+pub fn set_item_templates(templates: Vec<crate::config::SavedTemplate>) {
+    let _ = ITEM_TEMPLATES.set(templates);
+}
+
+/// The saved templates for `table`.
+pub fn item_templates(table: &str) -> impl Iterator<Item = &'static crate::config::SavedTemplate> {
+    ITEM_TEMPLATES
+        .get()
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .filter(move |template| template.table == table)
+}
+
+static ENTITY_RULES: OnceLock<Vec<crate::config::EntityRule>> = OnceLock::new();
+
+/// How rows are told apart by entity (`entities` in the config file).
+pub fn set_entity_rules(rules: Vec<crate::config::EntityRule>) {
+    let _ = ENTITY_RULES.set(rules);
+}
+
+/// The entity rules for `table`.
+pub fn entity_rules(table: &str) -> impl Iterator<Item = &'static crate::config::EntityRule> {
+    ENTITY_RULES
+        .get()
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .filter(move |rule| rule.table == table)
+}
+
+static FOLLOW_RULES: OnceLock<Vec<crate::config::FollowRule>> = OnceLock::new();
+
+/// The items attributes refer to (`follow` in the config file).
+pub fn set_follow_rules(rules: Vec<crate::config::FollowRule>) {
+    let _ = FOLLOW_RULES.set(rules);
+}
+
+/// The rules for following attributes of items in `table`.
+pub fn follow_rules(table: &str) -> impl Iterator<Item = &'static crate::config::FollowRule> {
+    FOLLOW_RULES
+        .get()
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .filter(move |rule| rule.table == table)
+}
+
+/// The rule for following `attribute` of items in `table`, if there is one.
+pub fn follow_rule(table: &str, attribute: &str) -> Option<&'static crate::config::FollowRule> {
+    follow_rules(table).find(|rule| rule.attribute == attribute)
+}
+
+static QUERY_LIMIT: OnceLock<(Option<usize>, Option<usize>)> = OnceLock::new();
+
+/// The items and pages a query view loads before stopping to ask
+/// (`max_items` and `max_pages` in the config file).
+pub fn set_query_limit(max_items: Option<usize>, max_pages: Option<usize>) {
+    let _ = QUERY_LIMIT.set((max_items, max_pages));
+}
+
+pub fn query_limit() -> (Option<usize>, Option<usize>) {
+    QUERY_LIMIT.get().copied().unwrap_or_default()
+}
+
+/// Tells a double click from two single ones: the second click must land on
+/// the same target soon after the first.
+#[derive(Debug, Default)]
+pub struct ClickTracker {
+    last: Cell<Option<(Instant, usize)>>,
+}
+
+impl ClickTracker {
+    const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+    /// Record a click on `target`; returns whether it completes a double click.
+    pub fn click(&self, target: usize) -> bool {
+        self.click_at(target, Instant::now())
+    }
+
+    fn click_at(&self, target: usize, now: Instant) -> bool {
+        let double = self.last.get().is_some_and(|(at, last)| {
+            last == target && now.duration_since(at) <= Self::DOUBLE_CLICK
+        });
+        // A third click starts over rather than making another double click.
+        self.last.set((!double).then_some((now, target)));
+        double
+    }
+}
+
 pub fn abbreviate_home(path: &Path) -> String {
     let Some(base_dirs) = BaseDirs::new() else {
         return path.display().to_string();
@@ -50,3 +275,59 @@ pub fn abbreviate_home(path: &Path) -> String {
         path.display().to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::OsStr,
+        path::Path,
+        time::{Duration, Instant},
+    };
+
+    use super::{ClickTracker, command_words, direct_command, shell_command};
+
+    #[test]
+    fn launches_editors_on_paths_with_spaces() {
+        let path = Path::new("/tmp/my items/dynamate-edit-1.json");
+        let command = shell_command("code --wait", path);
+        assert_eq!(command.get_program(), "sh");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            [
+                OsStr::new("-c"),
+                OsStr::new("code --wait \"$1\""),
+                OsStr::new("sh"),
+                path.as_os_str()
+            ]
+        );
+
+        let path = Path::new(r"C:\Users\Jo Smith\AppData\Local\Temp\dynamate-edit-1.json");
+        let command = direct_command(
+            r#""C:\Program Files\Notepad++\notepad++.exe" -multiInst"#,
+            path,
+        );
+        assert_eq!(
+            command.get_program(),
+            r"C:\Program Files\Notepad++\notepad++.exe"
+        );
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            [OsStr::new("-multiInst"), path.as_os_str()]
+        );
+
+        assert_eq!(command_words("  notepad  "), ["notepad"]);
+        assert_eq!(command_words(r#"a "" b"#), ["a", "", "b"]);
+        assert!(command_words("").is_empty());
+    }
+
+    #[test]
+    fn double_clicks_need_the_same_target_in_time() {
+        let clicks = ClickTracker::default();
+        let start = Instant::now();
+        assert!(!clicks.click_at(3, start));
+        assert!(clicks.click_at(3, start + Duration::from_millis(200)));
+        assert!(!clicks.click_at(3, start + Duration::from_millis(300)));
+        assert!(!clicks.click_at(4, start + Duration::from_millis(350)));
+        assert!(!clicks.click_at(4, start + Duration::from_secs(2)));
+    }
+}
@@ -0,0 +1,771 @@
+//! A full-screen profile of one table's items: attribute frequency and types,
+//! item size percentiles, key cardinality, hot keys and prefixes, and
+//! attributes that would make sparse indexes. Opened with `a` from the table
+//! picker, it starts on a sample; `f` reads the whole table, `^r` takes a new
+//! sample, and `e` exports the report as JSON.
+//!
+//! `p` switches to the partitions table, every partition key value (or, with
+//! `g`, prefix) ranked by items or, with `s`, by size, for finding hot
+//! partitions; `e` there exports the table as CSV.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    env, fs,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use humansize::{BINARY, format_size};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+};
+
+use dynamate::core::analyze::{
+    AnalyzeOptions, PartitionReport, PartitionStats, Share, TableReport, analyze_items,
+};
+use dynamate::core::datastore::Datastore;
+use dynamate::core::schema::KeyRole;
+
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    util::pad,
+    widgets::{WidgetInner, purge::format_elapsed, theme::Theme},
+};
+
+/// Items read by a sampled analysis.
+const DEFAULT_SAMPLE: u64 = 1_000;
+/// Width of the label column in each section.
+const LABEL_WIDTH: usize = 24;
+/// Widest the partition column of the partitions table gets.
+const PARTITION_WIDTH: usize = 40;
+
+pub struct AnalyzeWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    table_name: String,
+    state: RefCell<AnalyzeState>,
+    scroll: Cell<usize>,
+    /// Visible content rows and total rows, recorded on render for clamping.
+    viewport: Cell<usize>,
+    content_len: Cell<usize>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Default)]
+struct AnalyzeState {
+    report: Option<TableReport>,
+    error: Option<String>,
+    /// Set while an analysis runs; storing `true` stops it.
+    cancel: Option<Arc<AtomicBool>>,
+    /// Items read by the running analysis.
+    read: u64,
+    full_scan: bool,
+    /// Showing the partitions table rather than the report.
+    partitions: bool,
+    /// Group partitions by key prefix rather than by value.
+    by_prefix: bool,
+    /// Rank partitions by size rather than by items.
+    by_size: bool,
+}
+
+struct AnalyzeProgressEvent {
+    read: u64,
+}
+
+struct AnalyzeFinishedEvent {
+    result: Result<TableReport, String>,
+}
+
+impl AnalyzeWidget {
+    pub fn new(db: Arc<dyn Datastore>, table_name: &str, parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            table_name: table_name.to_string(),
+            state: RefCell::new(AnalyzeState::default()),
+            scroll: Cell::new(0),
+            viewport: Cell::new(0),
+            content_len: Cell::new(0),
+            help_entries: build_help(),
+        }
+    }
+
+    fn analyze(&self, ctx: crate::env::WidgetCtx, full_scan: bool) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        {
+            let mut state = self.state.borrow_mut();
+            if state.cancel.is_some() {
+                return;
+            }
+            state.cancel = Some(cancel.clone());
+            state.read = 0;
+            state.full_scan = full_scan;
+            state.error = None;
+        }
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        let options = AnalyzeOptions {
+            sample: (!full_scan).then_some(DEFAULT_SAMPLE),
+        };
+        tokio::spawn(async move {
+            let on_progress = |read: u64| ctx.emit_self(AnalyzeProgressEvent { read });
+            let result = analyze_items(db.as_ref(), &table_name, options, &cancel, &on_progress)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(AnalyzeFinishedEvent { result });
+        });
+    }
+
+    fn cancel(&self, ctx: &crate::env::WidgetCtx) {
+        if let Some(cancel) = self.state.borrow().cancel.as_ref() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        ctx.show_toast(Toast {
+            message: "Stopping analysis...".to_string(),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(2),
+            action: None,
+        });
+    }
+
+    /// Write the report as JSON, or the partitions table as CSV when it is
+    /// shown.
+    fn export(&self, ctx: &crate::env::WidgetCtx) {
+        let (path, text) = {
+            let state = self.state.borrow();
+            let Some(report) = state.report.as_ref() else {
+                return;
+            };
+            match report.partitions.as_ref().filter(|_| state.partitions) {
+                Some(partitions) => (
+                    export_path(&self.table_name, "partitions", "csv"),
+                    Ok(partitions_csv(&ranked_partitions(
+                        partitions,
+                        state.by_prefix,
+                        state.by_size,
+                    ))),
+                ),
+                None => (
+                    export_path(&self.table_name, "analysis", "json"),
+                    serde_json::to_string_pretty(&report.to_json(&self.table_name))
+                        .map_err(|err| err.to_string()),
+                ),
+            }
+        };
+        let result = text.and_then(|text| fs::write(&path, text).map_err(|err| err.to_string()));
+        let (message, kind) = match result {
+            Ok(()) => (format!("Exported to {}", path.display()), ToastKind::Info),
+            Err(err) => (format!("Export failed: {err}"), ToastKind::Error),
+        };
+        ctx.show_toast(Toast {
+            message,
+            kind,
+            duration: Duration::from_secs(4),
+            action: None,
+        });
+    }
+
+    /// Change how the partitions table is shown, from the top.
+    fn set_partitions_view(
+        &self,
+        ctx: &crate::env::WidgetCtx,
+        change: impl FnOnce(&mut AnalyzeState),
+    ) {
+        change(&mut self.state.borrow_mut());
+        self.scroll.set(0);
+        ctx.invalidate();
+    }
+
+    fn scroll_by(&self, delta: isize) -> bool {
+        let max = self.content_len.get().saturating_sub(self.viewport.get());
+        let current = self.scroll.get() as isize;
+        let next = (current + delta).clamp(0, max as isize);
+        if next == current {
+            return false;
+        }
+        self.scroll.set(next as usize);
+        true
+    }
+}
+
+impl crate::widgets::Widget for AnalyzeWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.analyze(ctx, false);
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some(format!("{} analysis", self.table_name))
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            context: Some(self.table_name.clone()),
+            mode: Some("ANALYZE".to_string()),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().cancel.is_some()
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let state = self.state.borrow();
+        let mut heading = format!("Analyze: {}", self.table_name);
+        if state.cancel.is_some() {
+            let scope = if state.full_scan {
+                "scanning".to_string()
+            } else {
+                format!("sampling {DEFAULT_SAMPLE}")
+            };
+            heading.push_str(&format!(" · {scope}, {} items read", state.read));
+        } else if let Some(report) = state.report.as_ref() {
+            heading.push_str(&format!(" · {}", scope_label(report)));
+        }
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        let lines = match (&state.report, &state.error) {
+            (_, Some(err)) => vec![Line::styled(
+                err.clone(),
+                Style::default().fg(theme.error()),
+            )],
+            (Some(report), None) if state.partitions => match report.partitions.as_ref() {
+                Some(partitions) => {
+                    partition_lines(partitions, state.by_prefix, state.by_size, theme)
+                }
+                None => vec![Line::styled(
+                    "This table has no partition key",
+                    Style::default().fg(theme.warning()),
+                )],
+            },
+            (Some(report), None) => section_lines(&report_sections(report), theme),
+            (None, None) => vec![Line::styled(
+                "Reading items...",
+                Style::default().fg(theme.warning()),
+            )],
+        };
+        drop(state);
+
+        let inner = block.inner(area);
+        self.content_len.set(lines.len());
+        self.viewport.set(inner.height as usize);
+        let max = lines.len().saturating_sub(inner.height as usize);
+        if self.scroll.get() > max {
+            self.scroll.set(max);
+        }
+        let body = Paragraph::new(lines)
+            .scroll((self.scroll.get() as u16, 0))
+            .block(block);
+        frame.render_widget(body, area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let running = self.state.borrow().cancel.is_some();
+        let page = self.viewport.get().max(1) as isize;
+        let scrolled = match key.code {
+            KeyCode::Esc if running => {
+                self.cancel(&ctx);
+                return true;
+            }
+            KeyCode::Esc => {
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.analyze(ctx, false);
+                return true;
+            }
+            KeyCode::Char('f') if key.modifiers.is_empty() => {
+                self.analyze(ctx, true);
+                return true;
+            }
+            KeyCode::Char('e') if key.modifiers.is_empty() => {
+                self.export(&ctx);
+                return true;
+            }
+            KeyCode::Char('p') if key.modifiers.is_empty() => {
+                self.set_partitions_view(&ctx, |state| state.partitions = !state.partitions);
+                return true;
+            }
+            KeyCode::Char('g') if key.modifiers.is_empty() && self.state.borrow().partitions => {
+                self.set_partitions_view(&ctx, |state| state.by_prefix = !state.by_prefix);
+                return true;
+            }
+            KeyCode::Char('s') if key.modifiers.is_empty() && self.state.borrow().partitions => {
+                self.set_partitions_view(&ctx, |state| state.by_size = !state.by_size);
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.scroll_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll_by(-1),
+            KeyCode::PageDown => self.scroll_by(page),
+            KeyCode::PageUp => self.scroll_by(-page),
+            _ => return false,
+        };
+        if scrolled {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(progress) = event.payload::<AnalyzeProgressEvent>() {
+            let mut state = self.state.borrow_mut();
+            if state.cancel.is_some() {
+                state.read = progress.read;
+                ctx.invalidate();
+            }
+            return;
+        }
+        if let Some(finished) = event.payload::<AnalyzeFinishedEvent>() {
+            let mut state = self.state.borrow_mut();
+            state.cancel = None;
+            match finished.result.as_ref() {
+                Ok(report) => state.report = Some(report.clone()),
+                Err(err) => state.error = Some(format!("Failed to analyze table: {err}")),
+            }
+            ctx.invalidate();
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+fn build_help() -> Vec<help::Entry<'static>> {
+    vec![
+        help_entry("j/k/↑/↓/PgUp/PgDn", "scroll", "Scroll report"),
+        help_entry("f", "full scan", "Analyze every item"),
+        help_ctrl("^r", "resample", "Analyze a new sample"),
+        help_entry("p", "partitions", "Toggle the partitions table"),
+        help_entry("g", "group", "Group partitions by key value or prefix"),
+        help_entry("s", "sort", "Rank partitions by items or size"),
+        help_entry("e", "export", "Export report as JSON, or partitions as CSV"),
+        help_entry("esc", "back", "Stop the analysis, or back to tables"),
+    ]
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+fn help_ctrl(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(""),
+        short: Cow::Borrowed(""),
+        long: Cow::Borrowed(""),
+        ctrl: Some(help::Variant {
+            keys: Some(Cow::Borrowed(keys)),
+            short: Some(Cow::Borrowed(short)),
+            long: Some(Cow::Borrowed(long)),
+        }),
+        shift: None,
+        alt: None,
+    }
+}
+
+/// Where `e` writes the report: a timestamped file in the working directory.
+fn export_path(table_name: &str, kind: &str, extension: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let table: String = table_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let base = env::current_dir().unwrap_or_else(|_| env::temp_dir());
+    base.join(format!("{table}-{kind}-{timestamp}.{extension}"))
+}
+
+/// How much of the table the report covers.
+fn scope_label(report: &TableReport) -> String {
+    let scope = if report.cancelled {
+        format!("stopped after {} items", report.items)
+    } else if report.sampled {
+        format!("sample of {} items", report.items)
+    } else {
+        format!("all {} items", report.items)
+    };
+    format!("{scope} in {}", format_elapsed(report.elapsed))
+}
+
+fn percent(share: f64) -> String {
+    format!("{:.1}%", share * 100.0)
+}
+
+fn bytes(size: usize) -> String {
+    format_size(size as u64, BINARY)
+}
+
+/// `value (count, share)`, flagged when hot.
+fn share_label(share: &Share) -> String {
+    let hot = if share.hot { " · hot" } else { "" };
+    format!(
+        "{} ({}, {}){hot}",
+        share.value,
+        share.count,
+        percent(share.share)
+    )
+}
+
+/// A titled group of label/value rows.
+#[derive(Debug, PartialEq, Eq)]
+struct Section {
+    title: String,
+    rows: Vec<(String, String)>,
+}
+
+fn report_sections(report: &TableReport) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let sizes = &report.sizes;
+    sections.push(Section {
+        title: "Item size".to_string(),
+        rows: vec![
+            ("Average".to_string(), bytes(sizes.average.round() as usize)),
+            (
+                "p50 / p90 / p99".to_string(),
+                format!(
+                    "{} / {} / {}",
+                    bytes(sizes.p50),
+                    bytes(sizes.p90),
+                    bytes(sizes.p99)
+                ),
+            ),
+            ("Largest".to_string(), bytes(sizes.max)),
+            (
+                "Near the 400 KB limit".to_string(),
+                sizes.near_limit.to_string(),
+            ),
+        ],
+    });
+
+    for key in &report.keys {
+        let role = match key.role {
+            KeyRole::Partition => "Partition key",
+            KeyRole::Sort => "Sort key",
+        };
+        let mut distinct = key.distinct.to_string();
+        if key.capped {
+            distinct.push('+');
+        }
+        let mut rows = vec![
+            ("Distinct values".to_string(), distinct),
+            (
+                "Cardinality".to_string(),
+                key.cardinality.label().to_string(),
+            ),
+        ];
+        for (idx, share) in key.top_values.iter().enumerate() {
+            let label = if idx == 0 { "Top values" } else { "" };
+            rows.push((label.to_string(), share_label(share)));
+        }
+        for (idx, share) in key.top_prefixes.iter().enumerate() {
+            let label = if idx == 0 { "Top prefixes" } else { "" };
+            rows.push((label.to_string(), share_label(share)));
+        }
+        sections.push(Section {
+            title: format!("{role} {}", key.name),
+            rows,
+        });
+    }
+
+    sections.push(Section {
+        title: "Sparse index candidates".to_string(),
+        rows: if report.sparse_candidates.is_empty() {
+            vec![("None".to_string(), String::new())]
+        } else {
+            report
+                .sparse_candidates
+                .iter()
+                .map(|candidate| {
+                    (
+                        candidate.name.clone(),
+                        format!(
+                            "{} in {} items ({})",
+                            candidate.ty,
+                            candidate.count,
+                            percent(candidate.frequency)
+                        ),
+                    )
+                })
+                .collect()
+        },
+    });
+
+    sections.push(Section {
+        title: format!("Attributes ({})", report.attributes.len()),
+        rows: report
+            .attributes
+            .iter()
+            .map(|attribute| {
+                let types = attribute
+                    .types
+                    .iter()
+                    .map(|(ty, count)| format!("{ty} {count}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (
+                    attribute.name.clone(),
+                    format!("{:>6}  {types}", percent(attribute.frequency)),
+                )
+            })
+            .collect(),
+    });
+    sections
+}
+
+/// The partitions in the order the table shows them.
+fn ranked_partitions(
+    report: &PartitionReport,
+    by_prefix: bool,
+    by_size: bool,
+) -> Vec<&PartitionStats> {
+    let partitions = if by_prefix {
+        &report.prefixes
+    } else {
+        &report.values
+    };
+    let mut ranked: Vec<&PartitionStats> = partitions.iter().collect();
+    if by_size {
+        ranked.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.value.cmp(&b.value)));
+    }
+    ranked
+}
+
+fn partition_lines(
+    report: &PartitionReport,
+    by_prefix: bool,
+    by_size: bool,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let ranked = ranked_partitions(report, by_prefix, by_size);
+    let grouping = if by_prefix { "prefix" } else { "value" };
+    let order = if by_size { "size" } else { "items" };
+    let mut distinct = report.values.len().to_string();
+    if report.capped {
+        distinct.push('+');
+    }
+    let mut lines = vec![
+        Line::styled(
+            pad(
+                format!(
+                    "Partition key {} · {distinct} partitions · by {grouping}, largest {order} first",
+                    report.key
+                ),
+                1,
+            ),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        ),
+        Line::styled(
+            format!(
+                "   The busiest partition holds {:.1}× the mean items, the largest {:.1}× the mean size",
+                report.item_skew(),
+                report.size_skew()
+            ),
+            Style::default().fg(theme.text_muted()),
+        ),
+        Line::raw(""),
+    ];
+    if ranked.is_empty() {
+        lines.push(Line::styled(
+            format!("   No {grouping}s found"),
+            Style::default().fg(theme.warning()),
+        ));
+        return lines;
+    }
+    let width = ranked
+        .iter()
+        .map(|partition| partition.value.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(9, PARTITION_WIDTH);
+    lines.push(Line::styled(
+        format!(
+            "   {:<width$}  {:>9}  {:>6}  {:>10}  {:>6}  {:>10}",
+            "Partition", "Items", "Items", "Size", "Size", "Avg item"
+        ),
+        Style::default()
+            .fg(theme.text_muted())
+            .add_modifier(Modifier::BOLD),
+    ));
+    for partition in ranked {
+        let value: String = partition.value.chars().take(width).collect();
+        let average = partition.bytes.checked_div(partition.items).unwrap_or(0);
+        let style = if partition.is_hot() {
+            Style::default().fg(theme.warning())
+        } else {
+            Style::default().fg(theme.text())
+        };
+        lines.push(Line::styled(
+            format!(
+                "   {value:<width$}  {:>9}  {:>6}  {:>10}  {:>6}  {:>10}",
+                partition.items,
+                percent(partition.item_share),
+                format_size(partition.bytes, BINARY),
+                percent(partition.byte_share),
+                format_size(average, BINARY),
+            ),
+            style,
+        ));
+    }
+    lines
+}
+
+/// The partitions table as CSV, for export.
+fn partitions_csv(partitions: &[&PartitionStats]) -> String {
+    let mut csv = String::from("partition,items,item_share,bytes,byte_share,hot\n");
+    for partition in partitions {
+        let value = if partition.value.contains([',', '"', '\n']) {
+            format!("\"{}\"", partition.value.replace('"', "\"\""))
+        } else {
+            partition.value.clone()
+        };
+        csv.push_str(&format!(
+            "{value},{},{:.4},{},{:.4},{}\n",
+            partition.items,
+            partition.item_share,
+            partition.bytes,
+            partition.byte_share,
+            partition.is_hot()
+        ));
+    }
+    csv
+}
+
+fn section_lines(sections: &[Section], theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for (idx, section) in sections.iter().enumerate() {
+        if idx > 0 {
+            lines.push(Line::raw(""));
+        }
+        lines.push(Line::styled(
+            pad(&section.title, 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        ));
+        for (label, value) in &section.rows {
+            let label: String = label.chars().take(LABEL_WIDTH - 1).collect();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("   {label:<LABEL_WIDTH$}"),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(value.clone(), Style::default().fg(theme.text())),
+            ]));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use dynamate::core::analyze::Analyzer;
+    use dynamate::core::schema::{KeyField, KeyRole, KeySchema, ScalarType};
+    use dynamate::core::value::{Item, Value};
+
+    use super::{partitions_csv, ranked_partitions, report_sections, scope_label};
+
+    #[test]
+    fn sections_cover_sizes_keys_candidates_and_attributes() {
+        let key = KeySchema {
+            fields: vec![KeyField {
+                name: "pk".to_string(),
+                role: KeyRole::Partition,
+                ty: ScalarType::String,
+            }],
+        };
+        let mut analyzer = Analyzer::new(&key);
+        for pk in ["user#1", "user#2"] {
+            let mut item = Item::new();
+            item.insert("pk".to_string(), Value::Str(pk.to_string()));
+            analyzer.add(&item);
+        }
+        let report = analyzer.finish(true, false, Duration::from_secs(3));
+
+        let sections = report_sections(&report);
+        let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Item size",
+                "Partition key pk",
+                "Sparse index candidates",
+                "Attributes (1)"
+            ]
+        );
+        assert!(sections[1].rows.contains(&(
+            "Top prefixes".to_string(),
+            "user# (2, 100.0%) · hot".to_string()
+        )));
+        assert_eq!(scope_label(&report), "sample of 2 items in 3s");
+
+        let partitions = report.partitions.as_ref().unwrap();
+        let by_prefix = ranked_partitions(partitions, true, false);
+        assert_eq!(by_prefix.len(), 1);
+        let csv = partitions_csv(&by_prefix);
+        assert!(csv.starts_with("partition,items,item_share,bytes,byte_share,hot\n"));
+        assert!(csv.contains("\nuser#,2,1.0000,"));
+    }
+}
@@ -0,0 +1,308 @@
+use std::{borrow::Cow, cell::RefCell, sync::Arc};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::connect::{ProfileSelection, RoleSelection};
+use dynamate::core::datastore::Datastore;
+
+use super::create_table::TextInput;
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// The inputs, in Tab order.
+const ROLE_ARN: usize = 0;
+const MFA_SERIAL: usize = 1;
+const MFA_CODE: usize = 2;
+
+struct SwitchEvent {
+    selection: ProfileSelection,
+    result: Result<(), String>,
+}
+
+/// Assume an IAM role with the current profile's credentials, or drop the
+/// assumed one, and reconnect the datastore as it.
+pub struct AssumeRolePopup {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    state: RefCell<AssumeRoleState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct AssumeRoleState {
+    inputs: [TextInput; 3],
+    focus: usize,
+    switching: bool,
+    error: Option<String>,
+}
+
+impl AssumeRolePopup {
+    pub fn new(db: Arc<dyn Datastore>, parent: crate::env::WidgetId) -> Self {
+        let role = db.active_profile().and_then(|profile| profile.role);
+        let mut inputs = [
+            TextInput::new(
+                "Role ARN",
+                role.as_ref().map_or("", |role| role.role_arn.as_str()),
+            )
+            .with_placeholder("(empty to use the profile's own credentials)"),
+            TextInput::new(
+                "MFA serial",
+                role.as_ref()
+                    .and_then(|role| role.mfa_serial.as_deref())
+                    .unwrap_or_default(),
+            )
+            .with_placeholder("(optional)"),
+            TextInput::new("MFA code", "").with_placeholder("(required with an MFA serial)"),
+        ];
+        inputs[ROLE_ARN].set_active(true);
+        let help_entries = vec![
+            help_entry("tab", "next", "Move to the next field"),
+            help_entry("⏎", "assume", "Assume the role and reconnect"),
+            help_entry("esc", "cancel", "Cancel"),
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            state: RefCell::new(AssumeRoleState {
+                inputs,
+                focus: ROLE_ARN,
+                switching: false,
+                error: None,
+            }),
+            help_entries,
+        }
+    }
+
+    fn move_focus(&self, forward: bool) {
+        let mut state = self.state.borrow_mut();
+        let len = state.inputs.len();
+        let focus = state.focus;
+        state.inputs[focus].set_active(false);
+        state.focus = if forward {
+            (focus + 1) % len
+        } else {
+            (focus + len - 1) % len
+        };
+        let focus = state.focus;
+        state.inputs[focus].set_active(true);
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let role = {
+            let mut state = self.state.borrow_mut();
+            match role_from_inputs(
+                state.inputs[ROLE_ARN].value(),
+                state.inputs[MFA_SERIAL].value(),
+                state.inputs[MFA_CODE].value(),
+            ) {
+                Ok(role) => role,
+                Err(err) => {
+                    state.error = Some(err);
+                    return;
+                }
+            }
+        };
+        let selection = ProfileSelection {
+            role,
+            ..self.db.active_profile().unwrap_or_default()
+        };
+        {
+            let mut state = self.state.borrow_mut();
+            state.switching = true;
+            state.error = None;
+        }
+        let db = self.db.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let result = db
+                .switch_profile(&selection)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(SwitchEvent { selection, result });
+        });
+    }
+}
+
+/// The role to assume from what was typed, or `None` for an empty ARN. An MFA
+/// code is needed when there's a serial, since there's no terminal to prompt
+/// on.
+fn role_from_inputs(
+    role_arn: &str,
+    mfa_serial: &str,
+    mfa_code: &str,
+) -> Result<Option<RoleSelection>, String> {
+    let role_arn = role_arn.trim();
+    if role_arn.is_empty() {
+        return Ok(None);
+    }
+    if !role_arn.starts_with("arn:") {
+        return Err("Role ARN must start with arn:".to_string());
+    }
+    let mfa_serial = Some(mfa_serial.trim())
+        .filter(|serial| !serial.is_empty())
+        .map(str::to_string);
+    let mfa_code = Some(mfa_code.trim())
+        .filter(|code| !code.is_empty())
+        .map(str::to_string);
+    if mfa_serial.is_some() && mfa_code.is_none() {
+        return Err("Enter the MFA code for the serial".to_string());
+    }
+    Ok(Some(RoleSelection {
+        role_arn: role_arn.to_string(),
+        mfa_serial,
+        mfa_code,
+    }))
+}
+
+impl crate::widgets::Widget for AssumeRolePopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Assume role", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let [arn_area, serial_area, code_area, status_area] = inner.layout(&Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]));
+
+        let state = self.state.borrow();
+        for (input, input_area) in state.inputs.iter().zip([arn_area, serial_area, code_area]) {
+            input.render(frame, input_area, theme);
+        }
+        let (status, style) = if state.switching {
+            ("Connecting...", Style::default().fg(theme.warning()))
+        } else if let Some(error) = state.error.as_ref() {
+            (error.as_str(), Style::default().fg(theme.error()))
+        } else {
+            (
+                "Assumed with the current profile's credentials",
+                Style::default().fg(theme.text_muted()),
+            )
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        if self.state.borrow().switching {
+            return true;
+        }
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Tab | KeyCode::Down => self.move_focus(true),
+            KeyCode::BackTab | KeyCode::Up => self.move_focus(false),
+            _ => {
+                let mut state = self.state.borrow_mut();
+                let focus = state.focus;
+                if state.inputs[focus].handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        let Some(switched) = event.payload::<SwitchEvent>() else {
+            return;
+        };
+        match &switched.result {
+            Ok(()) => {
+                let selection = self
+                    .db
+                    .active_profile()
+                    .unwrap_or_else(|| switched.selection.clone());
+                super::profile_picker::announce_switch(&ctx, selection);
+                ctx.dismiss_popup();
+            }
+            Err(err) => {
+                let mut state = self.state.borrow_mut();
+                state.switching = false;
+                state.error = Some(err.clone());
+            }
+        }
+        ctx.invalidate();
+    }
+}
+
+impl Popup for AssumeRolePopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        // Three inputs, the status line, and the borders.
+        let height = 12.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::role_from_inputs;
+
+    #[test]
+    fn builds_the_role_from_the_inputs() {
+        assert_eq!(role_from_inputs("  ", "", ""), Ok(None));
+        assert!(role_from_inputs("ops-admin", "", "").is_err());
+        let arn = "arn:aws:iam::123456789012:role/ops-admin";
+        let serial = "arn:aws:iam::111111111111:mfa/alice";
+        assert!(role_from_inputs(arn, serial, "").is_err());
+        let role = role_from_inputs(arn, serial, " 123456 ").unwrap().unwrap();
+        assert_eq!(role.role_arn, arn);
+        assert_eq!(role.mfa_serial.as_deref(), Some(serial));
+        assert_eq!(role.mfa_code.as_deref(), Some("123456"));
+        assert_eq!(role.name(), "ops-admin");
+        let role = role_from_inputs(arn, "", "").unwrap().unwrap();
+        assert_eq!(role.mfa_serial, None);
+    }
+}
@@ -0,0 +1,453 @@
+//! A full-screen view of the write audit log, newest first. Opened with `l`
+//! from the table picker. The selected entry's key and prior item show below
+//! the list, and `u` restores the most recently deleted item that hasn't been
+//! written over since.
+
+use std::{borrow::Cow, cell::RefCell, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState, Wrap,
+    },
+};
+
+use dynamate::core::audit::{self, AuditEntry, AuditOp};
+use dynamate::core::datastore::Datastore;
+use dynamate::core::value::Item;
+
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    safety::Action,
+    widgets::{
+        WidgetInner,
+        confirm::{ConfirmAction, ConfirmPopup},
+        theme::Theme,
+    },
+};
+
+/// Only the newest entries are shown; older ones stay in the file.
+const MAX_ENTRIES: usize = 2000;
+
+pub struct AuditLogWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    state: RefCell<AuditLogState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Default)]
+struct AuditLogState {
+    loading: bool,
+    /// Oldest first, as in the file; rows show them in reverse.
+    entries: Vec<AuditEntry>,
+    error: Option<String>,
+    table_state: TableState,
+}
+
+impl AuditLogState {
+    fn selected(&self) -> Option<&AuditEntry> {
+        let row = self.table_state.selected()?;
+        self.entries
+            .len()
+            .checked_sub(row + 1)
+            .and_then(|idx| self.entries.get(idx))
+    }
+}
+
+struct AuditLogLoadedEvent {
+    result: Result<Vec<AuditEntry>, String>,
+}
+
+struct UndoRequest(AuditEntry);
+
+struct UndoDoneEvent {
+    table: String,
+    result: Result<(), String>,
+}
+
+impl AuditLogWidget {
+    pub fn new(db: Arc<dyn Datastore>, parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            state: RefCell::new(AuditLogState::default()),
+            help_entries: build_help(),
+        }
+    }
+
+    fn reload(&self, ctx: crate::env::WidgetCtx) {
+        let Some(path) = audit::path() else {
+            return;
+        };
+        self.state.borrow_mut().loading = true;
+        ctx.invalidate();
+        tokio::spawn(async move {
+            let result = audit::read(path)
+                .map(|mut entries| {
+                    let excess = entries.len().saturating_sub(MAX_ENTRIES);
+                    entries.drain(..excess);
+                    entries
+                })
+                .map_err(|err| format!("Failed to read {}: {err}", path.display()));
+            ctx.emit_self(AuditLogLoadedEvent { result });
+        });
+    }
+
+    fn select_by(&self, delta: isize) -> bool {
+        let mut state = self.state.borrow_mut();
+        let len = state.entries.len();
+        if len == 0 {
+            return false;
+        }
+        let current = state.table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        if state.table_state.selected() == Some(next) {
+            return false;
+        }
+        state.table_state.select(Some(next));
+        true
+    }
+
+    fn confirm_undo(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            ctx.show_toast(Toast {
+                message: dynamate::readonly::REJECT_MESSAGE.to_string(),
+                kind: ToastKind::Warning,
+                duration: dynamate::readonly::TOAST_DURATION,
+                action: None,
+            });
+            return;
+        }
+        let entry = {
+            let mut state = self.state.borrow_mut();
+            let Some(idx) = audit::last_undoable_delete(&state.entries, self.db.label()) else {
+                drop(state);
+                ctx.show_toast(Toast {
+                    message: "No deleted item to restore".to_string(),
+                    kind: ToastKind::Warning,
+                    duration: Duration::from_secs(3),
+                    action: None,
+                });
+                return;
+            };
+            let row = state.entries.len() - 1 - idx;
+            state.table_state.select(Some(row));
+            state.entries[idx].clone()
+        };
+        let message = format!(
+            "Table={}\nKey={}\nDeleted {}",
+            entry.table,
+            item_text(entry.key.as_ref()),
+            entry.at
+        );
+        let ctx_for_action = ctx.clone();
+        let subject = entry.table.clone();
+        ConfirmPopup::new_with_action(
+            "Undo delete",
+            message,
+            "Restore",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Char('u'),
+                KeyModifiers::CONTROL,
+                "^u",
+                "restore",
+                "Restore the deleted item",
+            ),
+            move || ctx_for_action.emit_self(UndoRequest(entry.clone())),
+            self.inner.id(),
+        )
+        .show(&ctx, Action::RestoreItem, &subject);
+    }
+
+    fn undo(&self, ctx: crate::env::WidgetCtx, entry: &AuditEntry) {
+        let Some(before) = entry.before.clone() else {
+            return;
+        };
+        self.state.borrow_mut().loading = true;
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table = entry.table.clone();
+        tokio::spawn(async move {
+            let result = db
+                .put_item(&table, before)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(UndoDoneEvent { table, result });
+        });
+    }
+}
+
+impl crate::widgets::Widget for AuditLogWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.reload(ctx);
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some("Audit log".to_string())
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        let state = self.state.borrow();
+        crate::widgets::StatusInfo {
+            mode: Some("AUDIT".to_string()),
+            stats: Some(format!("{} writes", state.entries.len())),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().loading
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let heading = match audit::path() {
+            Some(path) => format!("Audit log: {}", path.display()),
+            None => "Audit log".to_string(),
+        };
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        let message = if audit::path().is_none() {
+            Some((
+                "The audit log is off; audit_log in config.json turns it on".to_string(),
+                theme.warning(),
+            ))
+        } else if let Some(err) = state.error.as_ref() {
+            Some((err.clone(), theme.error()))
+        } else if state.entries.is_empty() {
+            let text = if state.loading {
+                "Loading audit log..."
+            } else {
+                "No writes recorded yet"
+            };
+            Some((text.to_string(), theme.warning()))
+        } else {
+            None
+        };
+        if let Some((text, color)) = message {
+            let body = Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+
+        frame.render_widget(block.clone(), area);
+        let [list_area, detail_area] = block.inner(area).layout(&Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(5),
+        ]));
+
+        let header = Row::new(vec!["Time", "Operation", "Table", "Key", "Detail"]).style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows: Vec<Row> = state
+            .entries
+            .iter()
+            .rev()
+            .map(|entry| {
+                let op_style = match entry.op {
+                    AuditOp::Delete
+                    | AuditOp::DropTable
+                    | AuditOp::DropIndex
+                    | AuditOp::RemoveReplica => Style::default().fg(theme.error()),
+                    AuditOp::Put | AuditOp::Update => Style::default().fg(theme.success()),
+                    _ => Style::default().fg(theme.warning()),
+                };
+                Row::new(vec![
+                    Cell::from(entry.at.replace('T', " ")),
+                    Cell::from(entry.op.as_str()).style(op_style),
+                    Cell::from(entry.table.clone()),
+                    Cell::from(item_text(entry.key.as_ref())),
+                    Cell::from(entry.detail.clone().unwrap_or_default()),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(25),
+            Constraint::Length(16),
+            Constraint::Length(20),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("❯ ")
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(table, list_area, frame.buffer_mut(), &mut state.table_state);
+
+        let before = match state.selected() {
+            Some(AuditEntry {
+                before: Some(before),
+                ..
+            }) => format!("Before: {}", item_text(Some(before))),
+            Some(_) => "No prior item recorded".to_string(),
+            None => String::new(),
+        };
+        let detail = Paragraph::new(before)
+            .style(Style::default().fg(theme.text_muted()))
+            .wrap(Wrap { trim: true })
+            .block(Block::new().borders(ratatui::widgets::Borders::TOP));
+        frame.render_widget(detail, detail_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let moved = match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.reload(ctx);
+                return true;
+            }
+            KeyCode::Char('u') if key.modifiers.is_empty() => {
+                self.confirm_undo(ctx);
+                return true;
+            }
+            KeyCode::Esc => {
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.select_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_by(-1),
+            KeyCode::PageDown => self.select_by(10),
+            KeyCode::PageUp => self.select_by(-10),
+            _ => return false,
+        };
+        if moved {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(loaded) = event.payload::<AuditLogLoadedEvent>() {
+            let mut state = self.state.borrow_mut();
+            state.loading = false;
+            match loaded.result.as_ref() {
+                Ok(entries) => {
+                    state.entries.clone_from(entries);
+                    let selected = (!entries.is_empty()).then_some(0);
+                    state.table_state.select(selected);
+                    state.error = None;
+                }
+                Err(err) => state.error = Some(err.clone()),
+            }
+            ctx.invalidate();
+            return;
+        }
+        if let Some(UndoRequest(entry)) = event.payload::<UndoRequest>() {
+            self.undo(ctx, entry);
+            return;
+        }
+        if let Some(done) = event.payload::<UndoDoneEvent>() {
+            let toast = match done.result.as_ref() {
+                Ok(()) => Toast {
+                    message: format!("Restored the deleted item in {}", done.table),
+                    kind: ToastKind::Info,
+                    duration: Duration::from_secs(3),
+                    action: None,
+                },
+                Err(err) => Toast {
+                    message: format!("Failed to restore: {err}"),
+                    kind: ToastKind::Error,
+                    duration: Duration::from_secs(4),
+                    action: None,
+                },
+            };
+            ctx.show_toast(toast);
+            self.reload(ctx);
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+/// An item as one line of JSON, or a dash when there is none.
+fn item_text(item: Option<&Item>) -> String {
+    let Some(item) = item else {
+        return "—".to_string();
+    };
+    dynamate::core::json::item_to_json(item)
+        .or_else(|_| dynamate::core::json::item_to_typed_json(item))
+        .map_or_else(|err| err.to_string(), |json| json.to_string())
+}
+
+fn build_help() -> Vec<help::Entry<'static>> {
+    vec![
+        help_entry("j/k/↑/↓", "move", "Move selection"),
+        help_entry("u", "undo delete", "Restore the last deleted item"),
+        help_ctrl("^r", "refresh", "Reload the log"),
+        help_entry("esc", "back", "Back"),
+    ]
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+fn help_ctrl(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(""),
+        short: Cow::Borrowed(""),
+        long: Cow::Borrowed(""),
+        ctrl: Some(help::Variant {
+            keys: Some(Cow::Borrowed(keys)),
+            short: Some(Cow::Borrowed(short)),
+            long: Some(Cow::Borrowed(long)),
+        }),
+        shift: None,
+        alt: None,
+    }
+}
@@ -0,0 +1,769 @@
+//! A full-screen list of one table's on-demand backups. Opened with `b` from
+//! the table picker or the details view. `^n` starts a backup, `^d` deletes
+//! the selected one, and `r` restores it into a new table. The view polls
+//! while a backup is being created or a restore is running.
+
+use std::{borrow::Cow, cell::RefCell, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use humansize::{BINARY, format_size};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table,
+        TableState,
+    },
+};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::schema::BackupInfo;
+
+use super::create_table::{TableCreatedEvent, TextInput};
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    safety::Action,
+    util::{fill_bg, pad},
+    widgets::{
+        Popup, WidgetInner,
+        confirm::{ConfirmAction, ConfirmPopup},
+        theme::Theme,
+    },
+};
+
+/// How often to refresh while a backup or restore is in progress.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct BackupsWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    table_name: String,
+    state: RefCell<BackupsState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Default)]
+struct BackupsState {
+    loading: bool,
+    backups: Vec<BackupInfo>,
+    error: Option<String>,
+    table_state: TableState,
+    /// Tables being restored into, with their last reported status.
+    restores: Vec<(String, String)>,
+    /// Whether a poll is scheduled; keeps manual refreshes from stacking
+    /// extra polls.
+    poll_pending: bool,
+}
+
+impl BackupsState {
+    fn selected(&self) -> Option<&BackupInfo> {
+        self.table_state
+            .selected()
+            .and_then(|idx| self.backups.get(idx))
+    }
+}
+
+struct BackupsLoadedEvent {
+    result: Result<Vec<BackupInfo>, String>,
+    /// Restore targets with their current status; finished ones report
+    /// "ACTIVE".
+    restores: Vec<(String, Result<String, String>)>,
+}
+
+struct PollTick;
+
+#[derive(Clone)]
+enum BackupRequest {
+    Create(String),
+    Delete(BackupInfo),
+    Restore { backup: BackupInfo, target: String },
+}
+
+struct BackupActionEvent {
+    /// What to toast on success.
+    done: String,
+    /// Set for restores, so the view tracks the new table.
+    restore_target: Option<String>,
+    result: Result<(), String>,
+}
+
+impl BackupsWidget {
+    pub fn new(db: Arc<dyn Datastore>, table_name: &str, parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            table_name: table_name.to_string(),
+            state: RefCell::new(BackupsState::default()),
+            help_entries: build_help(),
+        }
+    }
+
+    fn reload(&self, ctx: crate::env::WidgetCtx) {
+        let restores: Vec<String> = {
+            let mut state = self.state.borrow_mut();
+            state.loading = true;
+            state
+                .restores
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect()
+        };
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        tokio::spawn(async move {
+            let result = db
+                .list_backups(&table_name)
+                .await
+                .map_err(|err| err.to_string());
+            let mut statuses = Vec::with_capacity(restores.len());
+            for target in restores {
+                let status = db
+                    .describe_collection(&target)
+                    .await
+                    .map(|schema| schema.status.unwrap_or_else(|| "ACTIVE".to_string()))
+                    .map_err(|err| err.to_string());
+                statuses.push((target, status));
+            }
+            ctx.emit_self(BackupsLoadedEvent {
+                result,
+                restores: statuses,
+            });
+        });
+    }
+
+    /// Refresh after `POLL_INTERVAL` if a backup or restore is still running
+    /// and no poll is already scheduled.
+    fn schedule_poll(&self, ctx: crate::env::WidgetCtx) {
+        let mut state = self.state.borrow_mut();
+        let running = !state.restores.is_empty()
+            || state
+                .backups
+                .iter()
+                .any(|backup| backup.status.as_deref() == Some("CREATING"));
+        if !running || state.poll_pending {
+            return;
+        }
+        state.poll_pending = true;
+        tokio::spawn(async move {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            ctx.emit_self(PollTick);
+        });
+    }
+
+    fn select_by(&self, delta: isize) -> bool {
+        let mut state = self.state.borrow_mut();
+        let len = state.backups.len();
+        if len == 0 {
+            return false;
+        }
+        let current = state.table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        if state.table_state.selected() == Some(next) {
+            return false;
+        }
+        state.table_state.select(Some(next));
+        true
+    }
+
+    fn selected_backup(&self, ctx: &crate::env::WidgetCtx) -> Option<BackupInfo> {
+        let backup = self.state.borrow().selected().cloned();
+        if backup.is_none() {
+            ctx.show_toast(Toast {
+                message: "No backup selected".to_string(),
+                kind: ToastKind::Warning,
+                duration: Duration::from_secs(3),
+                action: None,
+            });
+        }
+        backup
+    }
+
+    fn show_create_backup(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let name = format!(
+            "{}-{}",
+            self.table_name,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let ctx_for_submit = ctx.clone();
+        ctx.set_popup(Box::new(NamePopup::new(
+            format!("Back up {}", self.table_name),
+            "Backup name",
+            &name,
+            "Letters, digits, '_', '-', and '.'",
+            move |name| ctx_for_submit.emit_self(BackupRequest::Create(name)),
+            self.inner.id(),
+        )));
+    }
+
+    fn confirm_delete_backup(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let Some(backup) = self.selected_backup(&ctx) else {
+            return;
+        };
+        let message = format!("Table={}\nBackup={}", self.table_name, backup.name);
+        let ctx_for_action = ctx.clone();
+        let subject = backup.name.clone();
+        ConfirmPopup::new_with_action(
+            "Delete backup",
+            message,
+            "Delete",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Char('d'),
+                KeyModifiers::CONTROL,
+                "^d",
+                "delete",
+                "Delete backup",
+            ),
+            move || ctx_for_action.emit_self(BackupRequest::Delete(backup.clone())),
+            self.inner.id(),
+        )
+        .show(&ctx, Action::DeleteBackup, &subject);
+    }
+
+    fn show_restore(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let Some(backup) = self.selected_backup(&ctx) else {
+            return;
+        };
+        let ctx_for_submit = ctx.clone();
+        ctx.set_popup(Box::new(NamePopup::new(
+            format!("Restore {}", backup.name),
+            "New table",
+            &format!("{}-restored", self.table_name),
+            "Restored into a new table",
+            move |target| {
+                ctx_for_submit.emit_self(BackupRequest::Restore {
+                    backup: backup.clone(),
+                    target,
+                });
+            },
+            self.inner.id(),
+        )));
+    }
+
+    fn run(&self, ctx: crate::env::WidgetCtx, request: &BackupRequest) {
+        self.state.borrow_mut().loading = true;
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        let (done, restore_target) = match request {
+            BackupRequest::Create(name) => (format!("Creating backup {name}"), None),
+            BackupRequest::Delete(backup) => (format!("Deleted backup {}", backup.name), None),
+            BackupRequest::Restore { backup, target } => (
+                format!("Restoring {} to {target}", backup.name),
+                Some(target.clone()),
+            ),
+        };
+        let request = request.clone();
+        tokio::spawn(async move {
+            let result = match &request {
+                BackupRequest::Create(name) => db.create_backup(&table_name, name).await,
+                BackupRequest::Delete(backup) => db.delete_backup(&backup.id).await,
+                BackupRequest::Restore { backup, target } => {
+                    db.restore_backup(&backup.id, target).await
+                }
+            };
+            ctx.emit_self(BackupActionEvent {
+                done,
+                restore_target,
+                result: result.map_err(|err| err.to_string()),
+            });
+        });
+    }
+
+    fn on_loaded(&self, ctx: &crate::env::WidgetCtx, loaded: &BackupsLoadedEvent) {
+        let mut state = self.state.borrow_mut();
+        state.loading = false;
+        match loaded.result.as_ref() {
+            Ok(backups) => {
+                let selected_id = state.selected().map(|backup| backup.id.clone());
+                state.backups.clone_from(backups);
+                let selected = selected_id
+                    .and_then(|id| backups.iter().position(|backup| backup.id == id))
+                    .or((!backups.is_empty()).then_some(0))
+                    .map(|idx| idx.min(backups.len().saturating_sub(1)));
+                state.table_state.select(selected);
+                state.error = None;
+            }
+            Err(err) => state.error = Some(format!("Failed to list backups: {err}")),
+        }
+        for (target, status) in &loaded.restores {
+            let finished = match status {
+                Ok(status) if status.eq_ignore_ascii_case("active") => {
+                    ctx.show_toast(Toast {
+                        message: format!("Restored {target}"),
+                        kind: ToastKind::Info,
+                        duration: Duration::from_secs(3),
+                        action: None,
+                    });
+                    ctx.broadcast_event(TableCreatedEvent {
+                        table_name: target.clone(),
+                    });
+                    true
+                }
+                Ok(status) => {
+                    if let Some(entry) = state.restores.iter_mut().find(|(name, _)| name == target)
+                    {
+                        entry.1.clone_from(status);
+                    }
+                    false
+                }
+                Err(err) => {
+                    ctx.show_toast(Toast {
+                        message: format!("Failed to check restore of {target}: {err}"),
+                        kind: ToastKind::Error,
+                        duration: Duration::from_secs(4),
+                        action: None,
+                    });
+                    true
+                }
+            };
+            if finished {
+                state.restores.retain(|(name, _)| name != target);
+            }
+        }
+    }
+}
+
+impl crate::widgets::Widget for BackupsWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.reload(ctx);
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some(format!("{} backups", self.table_name))
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            context: Some(self.table_name.clone()),
+            mode: Some("BACKUPS".to_string()),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().loading
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let heading = format!("Backups: {}", self.table_name);
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let mut block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+        for (target, status) in &state.restores {
+            block = block.title_bottom(Line::styled(
+                pad(format!("Restoring to {target}: {status}"), 1),
+                Style::default().fg(theme.warning()),
+            ));
+        }
+
+        let message = if let Some(err) = state.error.as_ref() {
+            Some((err.clone(), theme.error()))
+        } else if state.backups.is_empty() {
+            let text = if state.loading {
+                "Loading backups..."
+            } else {
+                "No backups yet; ^n creates one"
+            };
+            Some((text.to_string(), theme.warning()))
+        } else {
+            None
+        };
+        if let Some((text, color)) = message {
+            let body = Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+
+        let header = Row::new(vec!["Name", "Status", "Created", "Size"]).style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows: Vec<Row> = state
+            .backups
+            .iter()
+            .map(|backup| {
+                let status = backup.status.clone().unwrap_or_else(|| "—".to_string());
+                let status_style = if status == "AVAILABLE" {
+                    Style::default().fg(theme.success())
+                } else {
+                    Style::default().fg(theme.warning())
+                };
+                Row::new(vec![
+                    Cell::from(backup.name.clone()),
+                    Cell::from(status).style(status_style),
+                    Cell::from(backup.created_at.clone().unwrap_or_else(|| "—".to_string())),
+                    Cell::from(format_bytes(backup.size_bytes)),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Length(21),
+            Constraint::Length(10),
+        ];
+        let table = Table::new(rows, widths)
+            .block(block)
+            .header(header)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("❯ ")
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(table, area, frame.buffer_mut(), &mut state.table_state);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let moved = match key.code {
+            KeyCode::Char('r') if ctrl => {
+                self.reload(ctx);
+                return true;
+            }
+            KeyCode::Char('n') if ctrl => {
+                self.show_create_backup(ctx);
+                return true;
+            }
+            KeyCode::Char('d') if ctrl => {
+                self.confirm_delete_backup(ctx);
+                return true;
+            }
+            KeyCode::Char('r') if key.modifiers.is_empty() => {
+                self.show_restore(ctx);
+                return true;
+            }
+            KeyCode::Esc => {
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.select_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_by(-1),
+            _ => return false,
+        };
+        if moved {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(loaded) = event.payload::<BackupsLoadedEvent>() {
+            self.on_loaded(&ctx, loaded);
+            self.schedule_poll(ctx.clone());
+            ctx.invalidate();
+            return;
+        }
+        if event.payload::<PollTick>().is_some() {
+            self.state.borrow_mut().poll_pending = false;
+            self.reload(ctx);
+            return;
+        }
+        if let Some(request) = event.payload::<BackupRequest>() {
+            self.run(ctx, request);
+            return;
+        }
+        if let Some(action) = event.payload::<BackupActionEvent>() {
+            match action.result.as_ref() {
+                Ok(()) => {
+                    ctx.show_toast(Toast {
+                        message: action.done.clone(),
+                        kind: ToastKind::Info,
+                        duration: Duration::from_secs(3),
+                        action: None,
+                    });
+                    if let Some(target) = action.restore_target.clone() {
+                        self.state
+                            .borrow_mut()
+                            .restores
+                            .push((target.clone(), "CREATING".to_string()));
+                        ctx.broadcast_event(TableCreatedEvent { table_name: target });
+                    }
+                    self.reload(ctx);
+                }
+                Err(err) => {
+                    self.state.borrow_mut().loading = false;
+                    ctx.show_toast(Toast {
+                        message: err.clone(),
+                        kind: ToastKind::Error,
+                        duration: Duration::from_secs(4),
+                        action: None,
+                    });
+                    ctx.invalidate();
+                }
+            }
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+fn build_help() -> Vec<help::Entry<'static>> {
+    vec![
+        help_entry("j/k/↑/↓", "move", "Move selection"),
+        help_ctrl("^n", "backup", "Create a backup"),
+        help_entry("r", "restore", "Restore to a new table"),
+        help_ctrl("^d", "delete", "Delete the selected backup"),
+        help_ctrl("^r", "refresh", "Refresh backups"),
+        help_entry("esc", "back", "Back"),
+    ]
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+fn help_ctrl(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(""),
+        short: Cow::Borrowed(""),
+        long: Cow::Borrowed(""),
+        ctrl: Some(help::Variant {
+            keys: Some(Cow::Borrowed(keys)),
+            short: Some(Cow::Borrowed(short)),
+            long: Some(Cow::Borrowed(long)),
+        }),
+        shift: None,
+        alt: None,
+    }
+}
+
+fn format_bytes(size: Option<i64>) -> String {
+    size.and_then(|size| u64::try_from(size).ok())
+        .map_or_else(|| "—".to_string(), |size| format_size(size, BINARY))
+}
+
+fn show_readonly_toast(ctx: &crate::env::WidgetCtx) {
+    ctx.show_toast(Toast {
+        message: dynamate::readonly::REJECT_MESSAGE.to_string(),
+        kind: ToastKind::Warning,
+        duration: dynamate::readonly::TOAST_DURATION,
+        action: None,
+    });
+}
+
+/// Asks for a backup, table, or region name, or any other short value with
+/// its own validation.
+pub(crate) struct NamePopup {
+    inner: WidgetInner,
+    title: String,
+    hint: &'static str,
+    state: RefCell<NameState>,
+    validate: fn(&str) -> Result<(), String>,
+    on_submit: Box<dyn Fn(String) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct NameState {
+    input: TextInput,
+    error: Option<String>,
+}
+
+impl NamePopup {
+    pub(crate) fn new(
+        title: String,
+        label: &str,
+        value: &str,
+        hint: &'static str,
+        on_submit: impl Fn(String) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut input = TextInput::new(label, value);
+        input.set_active(true);
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            title,
+            hint,
+            state: RefCell::new(NameState { input, error: None }),
+            validate: validate_name,
+            on_submit: Box::new(on_submit),
+            help_entries: vec![
+                help_entry("⏎", "ok", "Confirm"),
+                help_entry("esc", "cancel", "Cancel"),
+            ],
+        }
+    }
+
+    /// Check the value with `validate` rather than the DynamoDB naming rules.
+    #[must_use]
+    pub(crate) fn with_validation(mut self, validate: fn(&str) -> Result<(), String>) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let name = {
+            let mut state = self.state.borrow_mut();
+            let name = state.input.value().trim().to_string();
+            if let Err(err) = (self.validate)(&name) {
+                state.error = Some(err);
+                return;
+            }
+            name
+        };
+        ctx.dismiss_popup();
+        (self.on_submit)(name);
+    }
+}
+
+impl crate::widgets::Widget for NamePopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad(&self.title, 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([Constraint::Length(3), Constraint::Length(1)]);
+        let [input_area, status_area] = inner.layout(&layout);
+
+        let state = self.state.borrow();
+        state.input.render(frame, input_area, theme);
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (self.hint, Style::default().fg(theme.text_muted())),
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            _ => {
+                let mut state = self.state.borrow_mut();
+                if state.input.handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for NamePopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 56.min(area.width.saturating_sub(4));
+        // The input, the status line, and the borders.
+        let height = 6.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// DynamoDB table and backup names: 3-255 of letters, digits, `_`, `-`, `.`.
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.len() < 3 || name.len() > 255 {
+        return Err("Use 3 to 255 characters".to_string());
+    }
+    if let Some(ch) = name
+        .chars()
+        .find(|ch| !(ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.')))
+    {
+        return Err(format!("'{ch}' is not allowed"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_name;
+
+    #[test]
+    fn names_follow_dynamodb_rules() {
+        assert!(validate_name("orders-20260101-120000").is_ok());
+        assert!(validate_name("a.b_c").is_ok());
+        assert!(validate_name("ab").is_err());
+        assert_eq!(
+            validate_name("orders backup").unwrap_err(),
+            "' ' is not allowed"
+        );
+    }
+}
@@ -0,0 +1,580 @@
+use std::{borrow::Cow, cell::RefCell, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::query::CapacitySpec;
+use dynamate::core::schema::{CollectionDetails, IndexKind, Throughput};
+
+use super::confirm::{ConfirmAction, ConfirmPopup};
+use super::create_table::TextInput;
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    safety::Action,
+    util::fill_bg,
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Capacity offered when switching an on-demand table to provisioned.
+const DEFAULT_CAPACITY: i64 = 5;
+const ROW_HEIGHT: u16 = 3;
+
+/// Broadcast after a table's billing mode or capacity was changed, so views
+/// showing that table can refresh.
+#[derive(Debug, Clone)]
+pub struct CapacityChangedEvent {
+    pub table_name: String,
+}
+
+/// Switches a table between on-demand and provisioned billing and edits the
+/// read/write capacity of the table and each GSI.
+pub struct CapacityPopup {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    table_name: String,
+    state: RefCell<CapacityState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    OnDemand,
+    Provisioned,
+}
+
+impl Mode {
+    fn label(self) -> &'static str {
+        match self {
+            Mode::OnDemand => "On-demand (pay per request)",
+            Mode::Provisioned => "Provisioned (RCU/WCU)",
+        }
+    }
+
+    fn toggle(self) -> Self {
+        match self {
+            Mode::OnDemand => Mode::Provisioned,
+            Mode::Provisioned => Mode::OnDemand,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Mode,
+    Read(usize),
+    Write(usize),
+}
+
+/// Read/write inputs for the table (row 0) or one GSI.
+struct CapacityRow {
+    /// `None` for the table itself.
+    index_name: Option<String>,
+    read: TextInput,
+    write: TextInput,
+}
+
+impl CapacityRow {
+    fn new(index_name: Option<String>, throughput: Throughput) -> Self {
+        let prefix = index_name.as_deref().unwrap_or("Table");
+        Self {
+            read: TextInput::new(format!("{prefix} RCU"), throughput.read.to_string()),
+            write: TextInput::new(format!("{prefix} WCU"), throughput.write.to_string()),
+            index_name,
+        }
+    }
+
+    fn throughput(&self) -> Result<Throughput, String> {
+        let label = self.index_name.as_deref().unwrap_or("table");
+        Ok(Throughput {
+            read: parse_units(self.read.value(), label, "read")?,
+            write: parse_units(self.write.value(), label, "write")?,
+        })
+    }
+}
+
+enum Status {
+    Loading,
+    Ready,
+}
+
+struct CapacityState {
+    status: Status,
+    mode: Mode,
+    rows: Vec<CapacityRow>,
+    active_field: Field,
+    scroll_row: usize,
+    error: Option<String>,
+}
+
+impl CapacityState {
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![Field::Mode];
+        if self.mode == Mode::Provisioned {
+            for idx in 0..self.rows.len() {
+                fields.extend([Field::Read(idx), Field::Write(idx)]);
+            }
+        }
+        fields
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        let fields = self.fields();
+        let idx = fields
+            .iter()
+            .position(|f| *f == self.active_field)
+            .unwrap_or(0);
+        let len = fields.len();
+        let next = if forward {
+            (idx + 1) % len
+        } else {
+            (idx + len - 1) % len
+        };
+        self.active_field = fields[next];
+        self.sync_active();
+    }
+
+    fn sync_active(&mut self) {
+        let active = self.active_field;
+        for (idx, row) in self.rows.iter_mut().enumerate() {
+            row.read.set_active(active == Field::Read(idx));
+            row.write.set_active(active == Field::Write(idx));
+        }
+    }
+
+    fn load(&mut self, details: &CollectionDetails) {
+        self.mode = if details.billing_mode.as_deref() == Some("PAY_PER_REQUEST") {
+            Mode::OnDemand
+        } else {
+            Mode::Provisioned
+        };
+        let table = details.throughput.unwrap_or(Throughput {
+            read: DEFAULT_CAPACITY,
+            write: DEFAULT_CAPACITY,
+        });
+        self.rows = vec![CapacityRow::new(None, table)];
+        self.rows.extend(
+            details
+                .indexes
+                .iter()
+                .filter(|index| index.schema.kind == IndexKind::GlobalSecondary)
+                .map(|index| {
+                    CapacityRow::new(
+                        Some(index.schema.name.clone()),
+                        index.throughput.unwrap_or(table),
+                    )
+                }),
+        );
+        self.status = Status::Ready;
+        self.sync_active();
+    }
+
+    fn spec(&self) -> Result<CapacitySpec, String> {
+        match self.mode {
+            Mode::OnDemand => Ok(CapacitySpec::OnDemand),
+            Mode::Provisioned => {
+                let mut rows = self.rows.iter();
+                let table = rows
+                    .next()
+                    .ok_or_else(|| "Table capacity is missing".to_string())?
+                    .throughput()?;
+                let indexes = rows
+                    .map(|row| {
+                        Ok((
+                            row.index_name.clone().unwrap_or_default(),
+                            row.throughput()?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(CapacitySpec::Provisioned { table, indexes })
+            }
+        }
+    }
+}
+
+struct DetailsLoaded {
+    result: Result<CollectionDetails, String>,
+}
+
+impl CapacityPopup {
+    pub fn new(db: Arc<dyn Datastore>, table_name: &str, parent: crate::env::WidgetId) -> Self {
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("tab/shift+tab"),
+                short: Cow::Borrowed("move"),
+                long: Cow::Borrowed("Next/previous field"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("space"),
+                short: Cow::Borrowed("toggle"),
+                long: Cow::Borrowed("Switch billing mode"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("^enter"),
+                short: Cow::Borrowed("apply"),
+                long: Cow::Borrowed("Apply capacity"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("cancel"),
+                long: Cow::Borrowed("Cancel"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            table_name: table_name.to_string(),
+            state: RefCell::new(CapacityState {
+                status: Status::Loading,
+                mode: Mode::OnDemand,
+                rows: Vec::new(),
+                active_field: Field::Mode,
+                scroll_row: 0,
+                error: None,
+            }),
+            help_entries,
+        }
+    }
+
+    /// Close the form and apply `spec` once the safety policy's
+    /// confirmation, if any, is given. The outcome is reported in a toast.
+    fn submit(&self, ctx: crate::env::WidgetCtx) {
+        let spec = self.state.borrow().spec();
+        let spec = match spec {
+            Ok(spec) => spec,
+            Err(err) => {
+                self.state.borrow_mut().error = Some(err);
+                ctx.invalidate();
+                return;
+            }
+        };
+        ctx.dismiss_popup();
+        let mode = match spec {
+            CapacitySpec::OnDemand => Mode::OnDemand,
+            CapacitySpec::Provisioned { .. } => Mode::Provisioned,
+        };
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        let ctx_for_action = ctx.clone();
+        ConfirmPopup::new_with_action(
+            "Update capacity",
+            format!("Table={}\nBilling={}", self.table_name, mode.label()),
+            "Apply",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Enter,
+                KeyModifiers::CONTROL,
+                "^enter",
+                "apply",
+                "Apply capacity",
+            ),
+            move || {
+                let (db, table_name, spec) = (db.clone(), table_name.clone(), spec.clone());
+                let ctx = ctx_for_action.clone();
+                tokio::spawn(async move {
+                    match db.update_capacity(&table_name, &spec).await {
+                        Ok(()) => {
+                            ctx.show_toast(Toast {
+                                message: format!("Updating capacity for {table_name}"),
+                                kind: ToastKind::Info,
+                                duration: Duration::from_secs(3),
+                                action: None,
+                            });
+                            ctx.broadcast_event(CapacityChangedEvent { table_name });
+                        }
+                        Err(err) => ctx.show_toast(Toast {
+                            message: format!("Failed to update capacity for {table_name}: {err}"),
+                            kind: ToastKind::Error,
+                            duration: Duration::from_secs(4),
+                            action: None,
+                        }),
+                    }
+                });
+            },
+            self.inner.parent(),
+        )
+        .show(&ctx, Action::UpdateTable, &self.table_name);
+    }
+}
+
+impl crate::widgets::Widget for CapacityPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        tokio::spawn(async move {
+            let result = db
+                .describe_details(&table_name)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(DetailsLoaded { result });
+        });
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                format!("Capacity: {}", self.table_name),
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+        ])
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 1));
+        let layout = Layout::vertical([
+            Constraint::Length(ROW_HEIGHT),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ]);
+        let [mode_area, rows_area, status_area] = inner.layout(&layout);
+
+        let mut state = self.state.borrow_mut();
+        if matches!(state.status, Status::Loading) {
+            frame.render_widget(
+                Paragraph::new("Loading capacity...").style(Style::default().fg(theme.warning())),
+                status_area,
+            );
+            return;
+        }
+
+        let mode_active = state.active_field == Field::Mode;
+        let border = if mode_active {
+            theme.accent()
+        } else {
+            theme.border()
+        };
+        let mode = Paragraph::new(Line::from(Span::styled(
+            state.mode.label(),
+            Style::default().fg(theme.text()),
+        )))
+        .block(
+            Block::bordered()
+                .title("Billing mode")
+                .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()))
+                .border_style(Style::default().fg(border)),
+        )
+        .alignment(Alignment::Center);
+        frame.render_widget(mode, mode_area);
+
+        if state.mode == Mode::OnDemand {
+            let hint = "On-demand tables and their indexes scale automatically; there is no \
+                        capacity to set.";
+            frame.render_widget(
+                Paragraph::new(hint)
+                    .style(Style::default().fg(theme.text_muted()))
+                    .wrap(ratatui::widgets::Wrap { trim: true }),
+                rows_area.inner(Margin::new(0, 1)),
+            );
+        } else {
+            let visible = usize::from(rows_area.height / ROW_HEIGHT).max(1);
+            if let Field::Read(idx) | Field::Write(idx) = state.active_field {
+                if idx < state.scroll_row {
+                    state.scroll_row = idx;
+                } else if idx >= state.scroll_row + visible {
+                    state.scroll_row = idx + 1 - visible;
+                }
+            }
+            let columns =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]);
+            for (slot, row) in state
+                .rows
+                .iter()
+                .skip(state.scroll_row)
+                .take(visible)
+                .enumerate()
+            {
+                let y = rows_area.y + slot as u16 * ROW_HEIGHT;
+                let row_area = Rect::new(rows_area.x, y, rows_area.width, ROW_HEIGHT);
+                let [read_area, write_area] = row_area.layout(&columns);
+                row.read.render(frame, read_area, theme);
+                row.write.render(frame, write_area, theme);
+            }
+        }
+
+        if let Some(error) = state.error.as_ref() {
+            frame.render_widget(
+                Paragraph::new(format!("Error: {error}")).style(Style::default().fg(theme.error())),
+                status_area,
+            );
+        }
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        if key.code == KeyCode::Esc {
+            ctx.dismiss_popup();
+            ctx.invalidate();
+            return true;
+        }
+        if !matches!(self.state.borrow().status, Status::Ready) {
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Enter if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.submit(ctx);
+                return true;
+            }
+            KeyCode::Tab | KeyCode::Enter => {
+                self.state.borrow_mut().move_focus(true);
+                ctx.invalidate();
+                return true;
+            }
+            KeyCode::BackTab => {
+                self.state.borrow_mut().move_focus(false);
+                ctx.invalidate();
+                return true;
+            }
+            _ => {}
+        }
+
+        let mut state = self.state.borrow_mut();
+        let handled = match state.active_field {
+            Field::Mode => {
+                if matches!(
+                    key.code,
+                    KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right
+                ) {
+                    state.mode = state.mode.toggle();
+                    true
+                } else {
+                    false
+                }
+            }
+            Field::Read(idx) => state.rows[idx].read.handle_event(event),
+            Field::Write(idx) => state.rows[idx].write.handle_event(event),
+        };
+        if handled {
+            state.error = None;
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(loaded) = event.payload::<DetailsLoaded>() {
+            let mut state = self.state.borrow_mut();
+            match loaded.result.as_ref() {
+                Ok(details) => state.load(details),
+                Err(err) => {
+                    state.status = Status::Ready;
+                    state.error = Some(format!("Failed to describe table: {err}"));
+                }
+            }
+            ctx.invalidate();
+        }
+    }
+}
+
+impl Popup for CapacityPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let rows = self.state.borrow().rows.len().max(1) as u16;
+        // Borders, margins, the mode row, and the status line around the rows.
+        let height = (rows * ROW_HEIGHT + ROW_HEIGHT + 6).min(area.height.saturating_sub(4));
+        let width = (area.width as f32 * 0.6) as u16;
+        let width = width.max(56).min(area.width.saturating_sub(4));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+fn parse_units(raw: &str, label: &str, kind: &str) -> Result<i64, String> {
+    raw.trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|units| *units > 0)
+        .ok_or_else(|| format!("{label} {kind} capacity must be a positive whole number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::query::CapacitySpec;
+    use dynamate::core::schema::{
+        CollectionDetails, IndexDetails, IndexKind, IndexSchema, KeySchema, Projection, Throughput,
+    };
+
+    use super::{CapacityState, Field, Mode, Status};
+
+    fn state_for(details: &CollectionDetails) -> CapacityState {
+        let mut state = CapacityState {
+            status: Status::Loading,
+            mode: Mode::OnDemand,
+            rows: Vec::new(),
+            active_field: Field::Mode,
+            scroll_row: 0,
+            error: None,
+        };
+        state.load(details);
+        state
+    }
+
+    #[test]
+    fn switching_to_provisioned_prefills_every_gsi() {
+        let mut details = CollectionDetails {
+            billing_mode: Some("PAY_PER_REQUEST".to_string()),
+            ..CollectionDetails::default()
+        };
+        details.indexes.push(IndexDetails::from_schema(IndexSchema {
+            name: "GSI1".to_string(),
+            kind: IndexKind::GlobalSecondary,
+            key: KeySchema::default(),
+            projection: Projection::All,
+        }));
+        let mut state = state_for(&details);
+        assert_eq!(state.mode, Mode::OnDemand);
+        assert_eq!(state.spec(), Ok(CapacitySpec::OnDemand));
+
+        state.mode = Mode::Provisioned;
+        assert_eq!(
+            state.spec(),
+            Ok(CapacitySpec::Provisioned {
+                table: Throughput { read: 5, write: 5 },
+                indexes: vec![("GSI1".to_string(), Throughput { read: 5, write: 5 })],
+            })
+        );
+    }
+}
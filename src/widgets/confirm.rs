@@ -1,9 +1,12 @@
-use std::{borrow::Cow, cell::Cell};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+};
 
-use crossterm::event::{KeyCode, KeyModifiers};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    layout::{Alignment, Constraint, Layout, Margin, Position, Rect},
     style::{Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, BorderType, Paragraph, Wrap},
@@ -11,6 +14,7 @@ use ratatui::{
 
 use crate::{
     help,
+    safety::{self, Action, Confirmation},
     util::{fill_bg, pad},
     widgets::{Popup, WidgetInner, theme::Theme},
 };
@@ -22,9 +26,21 @@ pub struct ConfirmPopup {
     confirm_label: String,
     cancel_label: String,
     on_confirm: Box<dyn Fn() + Send + 'static>,
+    /// Runs when the popup is closed without confirming.
+    on_cancel: Option<Box<dyn Fn() + Send + 'static>>,
     selection: Cell<Selection>,
     confirm_action: ConfirmAction,
     help_entries: Vec<help::Entry<'static>>,
+    /// Where the confirm and cancel buttons were last drawn, for clicks.
+    buttons: Cell<Option<(Rect, Rect)>>,
+    /// What has to be typed before confirming, when the safety policy asks
+    /// for it.
+    typed: RefCell<Option<TypedName>>,
+}
+
+struct TypedName {
+    expected: String,
+    input: String,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -149,11 +165,87 @@ impl ConfirmPopup {
             confirm_label: confirm_label.into(),
             cancel_label: cancel_label.into(),
             on_confirm: Box::new(on_confirm),
+            on_cancel: None,
             selection: Cell::new(Selection::Cancel),
             confirm_action,
             help_entries,
+            buttons: Cell::new(None),
+            typed: RefCell::new(None),
+        }
+    }
+
+    /// Run `on_cancel` when the popup is closed without confirming.
+    pub fn on_cancel(mut self, on_cancel: impl Fn() + Send + 'static) -> Self {
+        self.on_cancel = Some(Box::new(on_cancel));
+        self
+    }
+
+    fn cancel(&self) {
+        if let Some(on_cancel) = self.on_cancel.as_ref() {
+            on_cancel();
+        }
+    }
+
+    /// Show the popup as the safety policy asks for `action`: confirm straight
+    /// away without it, ask, or have `subject` typed before confirming.
+    pub fn show(self, ctx: &crate::env::WidgetCtx, action: Action, subject: &str) {
+        match safety::confirmation(action) {
+            Confirmation::None => (self.on_confirm)(),
+            Confirmation::Confirm => ctx.set_popup(Box::new(self)),
+            Confirmation::TypeName => {
+                *self.typed.borrow_mut() = Some(TypedName {
+                    expected: subject.to_string(),
+                    input: String::new(),
+                });
+                ctx.set_popup(Box::new(self));
+            }
+        }
+    }
+
+    /// Like [`show`](Self::show), after the user already reviewed what
+    /// `action` does (a preview, say), which stands in for the yes-or-no
+    /// step: only a typed name is still asked for.
+    pub fn show_reviewed(self, ctx: &crate::env::WidgetCtx, action: Action, subject: &str) {
+        match safety::confirmation(action) {
+            Confirmation::None | Confirmation::Confirm => (self.on_confirm)(),
+            Confirmation::TypeName => self.show(ctx, action, subject),
         }
     }
+
+    fn can_confirm(&self) -> bool {
+        self.typed
+            .borrow()
+            .as_ref()
+            .is_none_or(|typed| typed.input == typed.expected)
+    }
+
+    /// Feed a key to the typed name. Returns whether it was taken.
+    fn type_key(&self, key: &crossterm::event::KeyEvent) -> bool {
+        let mut typed = self.typed.borrow_mut();
+        let Some(typed) = typed.as_mut() else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                typed.input.push(c);
+            }
+            KeyCode::Backspace => {
+                typed.input.pop();
+            }
+            _ => return false,
+        }
+        let matches = typed.input == typed.expected;
+        self.selection.set(if matches {
+            Selection::Confirm
+        } else {
+            Selection::Cancel
+        });
+        true
+    }
 }
 
 impl crate::widgets::Widget for ConfirmPopup {
@@ -182,7 +274,14 @@ impl crate::widgets::Widget for ConfirmPopup {
 
         frame.render_widget(block.clone(), area);
         let inner = block.inner(area).inner(Margin::new(1, 1));
-        let layout = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+        let typed = self.typed.borrow();
+        let typed_height = u16::from(typed.is_some());
+        let layout = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(typed_height),
+            Constraint::Length(1),
+        ])
+        .split(inner);
 
         let mut lines = Vec::new();
         for line in self.message.lines() {
@@ -214,9 +313,32 @@ impl crate::widgets::Widget for ConfirmPopup {
             .wrap(Wrap { trim: true });
         frame.render_widget(body, layout[0]);
 
+        if let Some(typed) = typed.as_ref() {
+            let prompt = format!("Type {} to confirm: ", typed.expected);
+            let line = Line::from(vec![
+                Span::styled(prompt.clone(), Style::default().fg(theme.text_muted())),
+                Span::styled(
+                    typed.input.as_str(),
+                    Style::default()
+                        .fg(theme.text())
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]);
+            let row = layout[1];
+            let width = line.width() as u16;
+            let start = row.x + row.width.saturating_sub(width) / 2;
+            frame.render_widget(Paragraph::new(line).alignment(Alignment::Center), row);
+            frame.set_cursor_position(Position::new(
+                (start + width).min(row.right().saturating_sub(1)),
+                row.y,
+            ));
+        }
+
         let confirm_selected = self.selection.get() == Selection::Confirm;
         let cancel_selected = self.selection.get() == Selection::Cancel;
-        let confirm_style = if confirm_selected {
+        let confirm_style = if !self.can_confirm() {
+            Style::default().fg(theme.text_muted())
+        } else if confirm_selected {
             Style::default()
                 .bg(theme.error())
                 .fg(theme.selection_fg())
@@ -236,18 +358,50 @@ impl crate::widgets::Widget for ConfirmPopup {
         };
         let confirm_button = Span::styled(format!("[ {} ]", self.confirm_label), confirm_style);
         let cancel_button = Span::styled(format!("[ {} ]", self.cancel_label), cancel_style);
+        let (confirm_width, cancel_width) =
+            (confirm_button.width() as u16, cancel_button.width() as u16);
+        let row = layout[2];
+        let start = row.x + row.width.saturating_sub(confirm_width + 2 + cancel_width) / 2;
+        self.buttons.set(Some((
+            Rect::new(start, row.y, confirm_width, 1),
+            Rect::new(start + confirm_width + 2, row.y, cancel_width, 1),
+        )));
         let buttons = Line::from(vec![confirm_button, Span::raw("  "), cancel_button]);
         let footer = Paragraph::new(Text::from(buttons)).alignment(Alignment::Center);
-        frame.render_widget(footer, layout[1]);
+        frame.render_widget(footer, layout[2]);
     }
 
     fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &crossterm::event::Event) -> bool {
+        if let Some(mouse) = event.as_mouse_event()
+            && matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left))
+            && let Some((confirm, cancel)) = self.buttons.get()
+        {
+            let position = Position::new(mouse.column, mouse.row);
+            if confirm.contains(position) {
+                if !self.can_confirm() {
+                    return true;
+                }
+                (self.on_confirm)();
+                ctx.dismiss_popup();
+            } else if cancel.contains(position) {
+                self.cancel();
+                ctx.dismiss_popup();
+            }
+            ctx.invalidate();
+            return true;
+        }
         let Some(key) = event.as_key_press_event() else {
             return true;
         };
 
+        if self.type_key(&key) {
+            ctx.invalidate();
+            return true;
+        }
+
         if key.code == self.confirm_action.hotkey.code
             && key.modifiers.contains(self.confirm_action.hotkey.modifiers)
+            && self.can_confirm()
         {
             (self.on_confirm)();
             ctx.dismiss_popup();
@@ -258,22 +412,25 @@ impl crate::widgets::Widget for ConfirmPopup {
         match key.code {
             KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
                 let next = match self.selection.get() {
-                    Selection::Confirm => Selection::Cancel,
-                    Selection::Cancel => Selection::Confirm,
+                    Selection::Cancel if self.can_confirm() => Selection::Confirm,
+                    _ => Selection::Cancel,
                 };
                 self.selection.set(next);
                 ctx.invalidate();
                 true
             }
             KeyCode::Enter => {
-                if self.selection.get() == Selection::Confirm {
+                if self.selection.get() == Selection::Confirm && self.can_confirm() {
                     (self.on_confirm)();
+                } else {
+                    self.cancel();
                 }
                 ctx.dismiss_popup();
                 ctx.invalidate();
                 true
             }
             KeyCode::Esc => {
+                self.cancel();
                 ctx.dismiss_popup();
                 ctx.invalidate();
                 true
@@ -288,7 +445,9 @@ impl Popup for ConfirmPopup {
         let width = (area.width as f32 * 0.4) as u16;
         let height = (area.height as f32 * 0.18) as u16;
         let width = width.max(34).min(area.width.saturating_sub(4));
-        let height = height.max(7).min(area.height.saturating_sub(4));
+        // Room for the typed name's line.
+        let extra = u16::from(self.typed.borrow().is_some());
+        let height = (height.max(7) + extra).min(area.height.saturating_sub(4));
         let x = area.x + (area.width - width) / 2;
         let y = area.y + (area.height - height) / 2;
         Rect {
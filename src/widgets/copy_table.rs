@@ -0,0 +1,258 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use super::create_table::TextInput;
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Asks for the name of the table a copy is written to, or that a table is
+/// renamed to.
+pub struct CopyTablePopup {
+    inner: WidgetInner,
+    source: String,
+    state: RefCell<CopyTableState>,
+    /// Gets the destination and whether to delete the source afterwards.
+    on_submit: Box<dyn Fn(String, bool) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct CopyTableState {
+    destination: TextInput,
+    /// Whether a rename deletes the old table; `None` for a copy.
+    drop_source: Option<bool>,
+    error: Option<String>,
+}
+
+impl CopyTablePopup {
+    /// `destination` prefills the name, e.g. to resume an interrupted copy.
+    pub fn new(
+        source: &str,
+        destination: &str,
+        on_submit: impl Fn(String) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("⏎"),
+                short: Cow::Borrowed("copy"),
+                long: Cow::Borrowed("Create the table and copy items"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            cancel_help(),
+        ];
+        Self::build(
+            source,
+            TextInput::new("Destination table", destination),
+            None,
+            Box::new(move |destination, _| on_submit(destination)),
+            help_entries,
+            parent,
+        )
+    }
+
+    /// Ask for the new name of `source`, and whether to delete it once its
+    /// items have been copied and counted.
+    pub fn rename(
+        source: &str,
+        destination: &str,
+        drop_source: bool,
+        on_submit: impl Fn(String, bool) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("⏎"),
+                short: Cow::Borrowed("rename"),
+                long: Cow::Borrowed("Create the table, copy items, and compare counts"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed(""),
+                short: Cow::Borrowed(""),
+                long: Cow::Borrowed(""),
+                ctrl: Some(help::Variant {
+                    keys: Some(Cow::Borrowed("^d")),
+                    short: Some(Cow::Borrowed("delete old")),
+                    long: Some(Cow::Borrowed("Toggle deleting the old table")),
+                }),
+                shift: None,
+                alt: None,
+            },
+            cancel_help(),
+        ];
+        Self::build(
+            source,
+            TextInput::new("New name", destination),
+            Some(drop_source),
+            Box::new(on_submit),
+            help_entries,
+            parent,
+        )
+    }
+
+    fn build(
+        source: &str,
+        mut input: TextInput,
+        drop_source: Option<bool>,
+        on_submit: Box<dyn Fn(String, bool) + Send + 'static>,
+        help_entries: Vec<help::Entry<'static>>,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        input.set_active(true);
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            source: source.to_string(),
+            state: RefCell::new(CopyTableState {
+                destination: input,
+                drop_source,
+                error: None,
+            }),
+            on_submit,
+            help_entries,
+        }
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let (destination, drop_source) = {
+            let mut state = self.state.borrow_mut();
+            let destination = state.destination.value().trim().to_string();
+            if destination.is_empty() {
+                state.error = Some("Table name is required".to_string());
+                return;
+            }
+            if destination == self.source {
+                state.error = Some("Pick a different table name".to_string());
+                return;
+            }
+            (destination, state.drop_source.unwrap_or_default())
+        };
+        ctx.dismiss_popup();
+        (self.on_submit)(destination, drop_source);
+    }
+}
+
+fn cancel_help() -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed("esc"),
+        short: Cow::Borrowed("cancel"),
+        long: Cow::Borrowed("Cancel"),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+impl crate::widgets::Widget for CopyTablePopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let state = self.state.borrow();
+        let verb = if state.drop_source.is_some() {
+            "Rename"
+        } else {
+            "Copy"
+        };
+        let title = Line::styled(
+            pad(format!("{verb} {}", self.source), 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(u16::from(state.drop_source.is_some())),
+            Constraint::Length(1),
+        ]);
+        let [input_area, checkbox_area, status_area] = inner.layout(&layout);
+
+        state.destination.render(frame, input_area, theme);
+        if let Some(drop_source) = state.drop_source {
+            let checked = if drop_source { "[x]" } else { "[ ]" };
+            frame.render_widget(
+                Paragraph::new(format!(
+                    "{checked} Delete {} once the counts match (^d)",
+                    self.source
+                )),
+                checkbox_area,
+            );
+        }
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (
+                "Created with the same keys and indexes",
+                Style::default().fg(theme.text_muted()),
+            ),
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(drop_source) = self.state.borrow_mut().drop_source.as_mut() {
+                    *drop_source = !*drop_source;
+                }
+            }
+            _ => {
+                let mut state = self.state.borrow_mut();
+                if state.destination.handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for CopyTablePopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 56.min(area.width.saturating_sub(4));
+        // The input, the rename's checkbox, the status line, and the borders.
+        let checkbox = u16::from(self.state.borrow().drop_source.is_some());
+        let height = (6 + checkbox).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use std::{borrow::Cow, cell::RefCell, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::datastore::Datastore;
+
+use super::confirm::{ConfirmAction, ConfirmPopup};
+use super::create_table::{GsiInput, gsi_index_schema, render_key_row, render_projection_row};
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    safety::Action,
+    util::fill_bg,
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Broadcast after an index was added to or dropped from a table, so views
+/// showing that table can refresh.
+#[derive(Debug, Clone)]
+pub struct IndexChangedEvent {
+    pub table_name: String,
+}
+
+/// Adds a GSI to an existing table, using the same fields as the create-table
+/// form.
+pub struct CreateIndexPopup {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    table_name: String,
+    state: RefCell<CreateIndexState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct CreateIndexState {
+    gsi: GsiInput,
+    active_field: Field,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    HashName,
+    HashType,
+    SortName,
+    SortType,
+    ProjectionKind,
+    ProjectionAttrs,
+}
+
+impl Field {
+    const ORDER: [Field; 7] = [
+        Field::Name,
+        Field::HashName,
+        Field::HashType,
+        Field::SortName,
+        Field::SortType,
+        Field::ProjectionKind,
+        Field::ProjectionAttrs,
+    ];
+
+    fn step(self, forward: bool) -> Self {
+        let idx = Self::ORDER.iter().position(|f| *f == self).unwrap_or(0);
+        let len = Self::ORDER.len();
+        let next = if forward {
+            (idx + 1) % len
+        } else {
+            (idx + len - 1) % len
+        };
+        Self::ORDER[next]
+    }
+}
+
+impl CreateIndexState {
+    fn sync_active(&mut self) {
+        let active = self.active_field;
+        self.gsi.name.set_active(active == Field::Name);
+        self.gsi.hash_key.name.set_active(active == Field::HashName);
+        self.gsi
+            .hash_key
+            .key_type
+            .set_active(active == Field::HashType);
+        self.gsi.sort_key.name.set_active(active == Field::SortName);
+        self.gsi
+            .sort_key
+            .key_type
+            .set_active(active == Field::SortType);
+        self.gsi
+            .projection
+            .kind
+            .set_active(active == Field::ProjectionKind);
+        self.gsi
+            .projection
+            .include_attrs
+            .set_active(active == Field::ProjectionAttrs);
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        self.active_field = self.active_field.step(forward);
+        self.sync_active();
+    }
+}
+
+impl CreateIndexPopup {
+    /// `index_id` seeds the default index and key names (`GSI{id}`), so pass
+    /// the number of GSIs the table already has.
+    pub fn new(
+        db: Arc<dyn Datastore>,
+        table_name: &str,
+        index_id: usize,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("tab/shift+tab"),
+                short: Cow::Borrowed("move"),
+                long: Cow::Borrowed("Next/previous field"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("space"),
+                short: Cow::Borrowed("toggle"),
+                long: Cow::Borrowed("Change type/projection"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("^enter"),
+                short: Cow::Borrowed("create"),
+                long: Cow::Borrowed("Create index"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("cancel"),
+                long: Cow::Borrowed("Cancel"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        let mut state = CreateIndexState {
+            gsi: GsiInput::new(index_id),
+            active_field: Field::Name,
+            error: None,
+        };
+        state.sync_active();
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            table_name: table_name.to_string(),
+            state: RefCell::new(state),
+            help_entries,
+        }
+    }
+
+    /// Close the form and create the index once the safety policy's
+    /// confirmation, if any, is given. The outcome is reported in a toast.
+    fn submit(&self, ctx: crate::env::WidgetCtx) {
+        let spec = {
+            let state = self.state.borrow();
+            state.gsi.to_spec().and_then(|spec| {
+                spec.validate()?;
+                Ok(spec)
+            })
+        };
+        let spec = match spec {
+            Ok(spec) => spec,
+            Err(err) => {
+                self.state.borrow_mut().error = Some(err);
+                ctx.invalidate();
+                return;
+            }
+        };
+        ctx.dismiss_popup();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        let index = gsi_index_schema(&spec);
+        let message = format!("Table={}\nIndex={}", self.table_name, index.name);
+        let ctx_for_action = ctx.clone();
+        ConfirmPopup::new_with_action(
+            "Create index",
+            message,
+            "Create",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Enter,
+                KeyModifiers::CONTROL,
+                "^enter",
+                "create",
+                "Create index",
+            ),
+            move || {
+                let (db, table_name, index) = (db.clone(), table_name.clone(), index.clone());
+                let ctx = ctx_for_action.clone();
+                tokio::spawn(async move {
+                    match db.create_index(&table_name, &index).await {
+                        Ok(()) => {
+                            ctx.show_toast(Toast {
+                                message: format!("Creating index {} on {table_name}", index.name),
+                                kind: ToastKind::Info,
+                                duration: Duration::from_secs(3),
+                                action: None,
+                            });
+                            ctx.broadcast_event(IndexChangedEvent { table_name });
+                        }
+                        Err(err) => ctx.show_toast(Toast {
+                            message: format!("Failed to create index {}: {err}", index.name),
+                            kind: ToastKind::Error,
+                            duration: Duration::from_secs(4),
+                            action: None,
+                        }),
+                    }
+                });
+            },
+            self.inner.parent(),
+        )
+        .show(&ctx, Action::CreateIndex, &self.table_name);
+    }
+}
+
+impl crate::widgets::Widget for CreateIndexPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                format!("Add GSI to {}", self.table_name),
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+        ])
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 1));
+
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]);
+        let [
+            name_area,
+            hash_area,
+            sort_area,
+            projection_area,
+            status_area,
+        ] = inner.layout(&layout);
+
+        let state = self.state.borrow();
+        state.gsi.name.render(frame, name_area, theme);
+        render_key_row(frame, hash_area, &state.gsi.hash_key, theme);
+        render_key_row(frame, sort_area, &state.gsi.sort_key, theme);
+        render_projection_row(frame, projection_area, &state.gsi.projection, theme);
+
+        if let Some(error) = state.error.as_ref() {
+            frame.render_widget(
+                Paragraph::new(format!("Error: {error}")).style(Style::default().fg(theme.error())),
+                status_area,
+            );
+        }
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+
+        match key.code {
+            KeyCode::Enter if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.submit(ctx);
+                return true;
+            }
+            KeyCode::Esc => {
+                ctx.dismiss_popup();
+                ctx.invalidate();
+                return true;
+            }
+            KeyCode::Tab | KeyCode::Enter => {
+                self.state.borrow_mut().move_focus(true);
+                ctx.invalidate();
+                return true;
+            }
+            KeyCode::BackTab => {
+                self.state.borrow_mut().move_focus(false);
+                ctx.invalidate();
+                return true;
+            }
+            _ => {}
+        }
+
+        let mut state = self.state.borrow_mut();
+        let active_field = state.active_field;
+        let gsi = &mut state.gsi;
+        let handled = match active_field {
+            Field::Name => gsi.name.handle_event(event),
+            Field::HashName => gsi.hash_key.name.handle_event(event),
+            Field::HashType => gsi.hash_key.key_type.handle_event(event),
+            Field::SortName => gsi.sort_key.name.handle_event(event),
+            Field::SortType => gsi.sort_key.key_type.handle_event(event),
+            Field::ProjectionKind => gsi.projection.kind.handle_event(event),
+            Field::ProjectionAttrs => gsi.projection.include_attrs.handle_event(event),
+        };
+        if handled {
+            state.error = None;
+            ctx.invalidate();
+        }
+        true
+    }
+}
+
+impl Popup for CreateIndexPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = (area.width as f32 * 0.6) as u16;
+        let width = width.max(64).min(area.width.saturating_sub(4));
+        let height = 17.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
@@ -10,13 +10,14 @@ use ratatui::{
 };
 
 use dynamate::core::datastore::Datastore;
-use dynamate::core::query::CreateCollectionSpec;
+use dynamate::core::query::{CreateCollectionSpec, ImportCompression, ImportFormat, ImportSource};
 use dynamate::core::schema::{
     IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, Projection, ScalarType,
 };
 use dynamate::dynamodb::{
     AttributeType, CreateTableSpec, GsiSpec, IndexProjection, KeySpec, LsiSpec,
 };
+use dynamate::s3::S3Uri;
 
 use crate::{
     env::{Toast, ToastKind},
@@ -30,6 +31,11 @@ pub struct TableCreatedEvent {
     pub table_name: String,
 }
 
+/// Broadcast once an import from S3 has started, so the imports view can
+/// follow it.
+#[derive(Debug, Clone)]
+pub struct ImportStartedEvent;
+
 pub struct CreateTablePopup {
     inner: WidgetInner,
     db: Arc<dyn Datastore>,
@@ -115,12 +121,30 @@ impl CreateTablePopup {
         }
     }
 
+    /// The same form with an S3 source on top: the table is created by
+    /// importing the files there (DynamoDB `ImportTable`).
+    pub fn new_import(db: Arc<dyn Datastore>, parent: crate::env::WidgetId) -> Self {
+        let mut popup = Self::new(db, parent);
+        for entry in &mut popup.help_entries {
+            if entry.keys == "^enter" {
+                entry.short = Cow::Borrowed("import");
+                entry.long = Cow::Borrowed("Start import");
+            }
+        }
+        {
+            let mut state = popup.state.borrow_mut();
+            state.import = Some(ImportInput::new());
+            state.set_active(FieldId::ImportSource);
+        }
+        popup
+    }
+
     fn submit(&self, ctx: crate::env::WidgetCtx) {
         let spec_result = {
             let state = self.state.borrow();
-            build_spec(&state)
+            build_spec(&state).and_then(|spec| Ok((spec, build_import_source(&state)?)))
         };
-        let spec = match spec_result {
+        let (spec, source) = match spec_result {
             Ok(spec) => spec,
             Err(err) => {
                 let mut state = self.state.borrow_mut();
@@ -131,6 +155,7 @@ impl CreateTablePopup {
         };
 
         let table_name = spec.table_name.clone();
+        let source_given = source.is_some();
         {
             let mut state = self.state.borrow_mut();
             state.status = CreateStatus::Submitting;
@@ -142,11 +167,19 @@ impl CreateTablePopup {
         let collection_spec = to_collection_spec(&spec);
         let ctx_clone = ctx.clone();
         tokio::spawn(async move {
-            let result = db
-                .create_collection(&collection_spec)
-                .await
-                .map_err(|err| err.to_string());
-            ctx_clone.emit_self(CreateTableResult { table_name, result });
+            let result = match source {
+                Some(source) => db
+                    .import_collection(&collection_spec, &source)
+                    .await
+                    .map(|_| ()),
+                None => db.create_collection(&collection_spec).await,
+            }
+            .map_err(|err| err.to_string());
+            ctx_clone.emit_self(CreateTableResult {
+                table_name,
+                imported: source_given,
+                result,
+            });
         });
     }
 
@@ -174,10 +207,15 @@ impl crate::widgets::Widget for CreateTablePopup {
 
     fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let importing = self.state.borrow().import.is_some();
         let title = Line::from(vec![
             Span::raw(" "),
             Span::styled(
-                "Create table",
+                if importing {
+                    "Import table from S3"
+                } else {
+                    "Create table"
+                },
                 Style::default()
                     .fg(theme.accent())
                     .add_modifier(Modifier::BOLD),
@@ -242,6 +280,7 @@ impl crate::widgets::Widget for CreateTablePopup {
                 theme,
                 matches!(state.active_field, FieldId::Actions),
                 state.selected_action,
+                importing,
             );
             let status_area = Rect::new(inner.x, inner.y + content_height + 1, inner.width, 1);
             render_status(frame, status_area, &state, theme, scroll_info.clone());
@@ -256,6 +295,7 @@ impl crate::widgets::Widget for CreateTablePopup {
                     theme,
                     matches!(state.active_field, FieldId::Actions),
                     state.selected_action,
+                    importing,
                 );
                 if scroll_info.is_some() {
                     render_status(frame, footer_area, &state, theme, scroll_info.clone());
@@ -438,6 +478,18 @@ impl crate::widgets::Widget for CreateTablePopup {
 
         let mut state = self.state.borrow_mut();
         let handled = match state.active_field {
+            FieldId::ImportSource => state
+                .import
+                .as_mut()
+                .is_some_and(|import| import.source.handle_event(event)),
+            FieldId::ImportFormat => state
+                .import
+                .as_mut()
+                .is_some_and(|import| import.format.handle_event(event)),
+            FieldId::ImportCompression => state
+                .import
+                .as_mut()
+                .is_some_and(|import| import.compression.handle_event(event)),
             FieldId::TableName => state.table_name.handle_event(event),
             FieldId::HashKeyName => state.hash_key.name.handle_event(event),
             FieldId::HashKeyType => state.hash_key.key_type.handle_event(event),
@@ -474,8 +526,13 @@ impl crate::widgets::Widget for CreateTablePopup {
         };
         match result.result.as_ref() {
             Ok(()) => {
+                let message = if result.imported {
+                    format!("Importing into {}", result.table_name)
+                } else {
+                    format!("Table {} created", result.table_name)
+                };
                 ctx.show_toast(Toast {
-                    message: format!("Table {} created", result.table_name),
+                    message,
                     kind: ToastKind::Info,
                     duration: Duration::from_secs(3),
                     action: None,
@@ -484,6 +541,9 @@ impl crate::widgets::Widget for CreateTablePopup {
                 ctx.broadcast_event(TableCreatedEvent {
                     table_name: result.table_name.clone(),
                 });
+                if result.imported {
+                    ctx.broadcast_event(ImportStartedEvent);
+                }
                 ctx.invalidate();
             }
             Err(err) => {
@@ -515,6 +575,9 @@ impl Popup for CreateTablePopup {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum FieldId {
+    ImportSource,
+    ImportFormat,
+    ImportCompression,
     TableName,
     HashKeyName,
     HashKeyType,
@@ -586,6 +649,8 @@ impl SectionKind {
 
 #[derive(Debug, Clone, Copy)]
 enum RowKind {
+    ImportSource,
+    ImportFormat,
     TableName,
     HashKey,
     SortKey,
@@ -605,6 +670,10 @@ enum RowKind {
 impl RowKind {
     fn matches_active(self, active: FieldId) -> bool {
         match self {
+            RowKind::ImportSource => matches!(active, FieldId::ImportSource),
+            RowKind::ImportFormat => {
+                matches!(active, FieldId::ImportFormat | FieldId::ImportCompression)
+            }
             RowKind::TableName => matches!(active, FieldId::TableName),
             RowKind::HashKey => matches!(active, FieldId::HashKeyName | FieldId::HashKeyType),
             RowKind::SortKey => matches!(active, FieldId::SortKeyName | FieldId::SortKeyType),
@@ -696,6 +765,8 @@ impl ProjectionKind {
 }
 
 struct CreateTableState {
+    /// Set when the popup imports the table from S3.
+    import: Option<ImportInput>,
     table_name: TextInput,
     hash_key: KeyInput,
     sort_key: KeyInput,
@@ -717,6 +788,7 @@ struct CreateTableState {
 impl Default for CreateTableState {
     fn default() -> Self {
         Self {
+            import: None,
             table_name: TextInput::new("Table name", ""),
             hash_key: KeyInput::new("Partition key name", AttributeType::String, "PK"),
             sort_key: KeyInput::new("Sort key name (optional)", AttributeType::String, "SK"),
@@ -739,13 +811,21 @@ impl Default for CreateTableState {
 
 impl CreateTableState {
     fn field_order(&self) -> Vec<FieldId> {
-        let mut fields = vec![
+        let mut fields = Vec::new();
+        if self.import.is_some() {
+            fields.extend([
+                FieldId::ImportSource,
+                FieldId::ImportFormat,
+                FieldId::ImportCompression,
+            ]);
+        }
+        fields.extend([
             FieldId::TableName,
             FieldId::HashKeyName,
             FieldId::HashKeyType,
             FieldId::SortKeyName,
             FieldId::SortKeyType,
-        ];
+        ]);
         for idx in 0..self.gsis.len() {
             fields.extend([
                 FieldId::GsiName(idx),
@@ -865,6 +945,17 @@ impl CreateTableState {
     }
 
     fn sync_active(&mut self) {
+        if let Some(import) = self.import.as_mut() {
+            import
+                .source
+                .set_active(matches!(self.active_field, FieldId::ImportSource));
+            import
+                .format
+                .set_active(matches!(self.active_field, FieldId::ImportFormat));
+            import
+                .compression
+                .set_active(matches!(self.active_field, FieldId::ImportCompression));
+        }
         self.table_name
             .set_active(matches!(self.active_field, FieldId::TableName));
         self.hash_key
@@ -1117,12 +1208,17 @@ fn build_rows(state: &CreateTableState) -> Vec<RowSpec> {
     const INPUT_HEIGHT: u16 = 3;
     const LABEL_HEIGHT: u16 = 1;
 
-    let mut rows = vec![
+    let mut rows = Vec::new();
+    if state.import.is_some() {
+        rows.push(RowSpec::new(RowKind::ImportSource, INPUT_HEIGHT));
+        rows.push(RowSpec::new(RowKind::ImportFormat, INPUT_HEIGHT));
+    }
+    rows.extend([
         RowSpec::new(RowKind::TableName, INPUT_HEIGHT),
         RowSpec::new(RowKind::HashKey, INPUT_HEIGHT),
         RowSpec::new(RowKind::SortKey, INPUT_HEIGHT),
         RowSpec::new(RowKind::SectionHeader(SectionKind::Gsi), LABEL_HEIGHT),
-    ];
+    ]);
     if state.gsis.is_empty() {
         rows.push(RowSpec::new(
             RowKind::EmptyHint(SectionKind::Gsi),
@@ -1192,9 +1288,9 @@ fn scroll_indicator(rows: &[RowSpec], viewport_height: u16, scroll_offset: u16)
     Some(format!("Scroll {page}/{pages}"))
 }
 
-struct KeyInput {
-    name: TextInput,
-    key_type: TypeSelect,
+pub(super) struct KeyInput {
+    pub(super) name: TextInput,
+    pub(super) key_type: TypeSelect,
 }
 
 impl KeyInput {
@@ -1206,15 +1302,17 @@ impl KeyInput {
     }
 }
 
-struct GsiInput {
-    name: TextInput,
-    hash_key: KeyInput,
-    sort_key: KeyInput,
-    projection: ProjectionInput,
+/// The form fields for one GSI; shared with the add-index popup in the table
+/// details view.
+pub(super) struct GsiInput {
+    pub(super) name: TextInput,
+    pub(super) hash_key: KeyInput,
+    pub(super) sort_key: KeyInput,
+    pub(super) projection: ProjectionInput,
 }
 
 impl GsiInput {
-    fn new(id: usize) -> Self {
+    pub(super) fn new(id: usize) -> Self {
         Self {
             name: TextInput::new("GSI name", format!("GSI{id}")),
             hash_key: KeyInput::new(
@@ -1232,6 +1330,34 @@ impl GsiInput {
     }
 }
 
+impl GsiInput {
+    /// Read the fields into a spec. An empty sort key name means no sort key.
+    pub(super) fn to_spec(&self) -> Result<GsiSpec, String> {
+        let sort_name = self.sort_key.name.value().trim().to_string();
+        let sort_key = if sort_name.is_empty() {
+            None
+        } else {
+            Some(KeySpec {
+                name: sort_name,
+                attr_type: self.sort_key.key_type.value,
+            })
+        };
+        let projection = projection_from_input(
+            self.projection.kind.value,
+            self.projection.include_attrs.value(),
+        )?;
+        Ok(GsiSpec {
+            name: self.name.value().trim().to_string(),
+            hash_key: KeySpec {
+                name: self.hash_key.name.value().trim().to_string(),
+                attr_type: self.hash_key.key_type.value,
+            },
+            sort_key,
+            projection,
+        })
+    }
+}
+
 struct LsiInput {
     name: TextInput,
     sort_key: KeyInput,
@@ -1252,9 +1378,9 @@ impl LsiInput {
     }
 }
 
-struct ProjectionInput {
-    kind: ProjectionSelect,
-    include_attrs: TextInput,
+pub(super) struct ProjectionInput {
+    pub(super) kind: ProjectionSelect,
+    pub(super) include_attrs: TextInput,
 }
 
 impl ProjectionInput {
@@ -1266,7 +1392,7 @@ impl ProjectionInput {
     }
 }
 
-struct ProjectionSelect {
+pub(super) struct ProjectionSelect {
     label: String,
     value: ProjectionKind,
     active: bool,
@@ -1281,11 +1407,11 @@ impl ProjectionSelect {
         }
     }
 
-    fn set_active(&mut self, active: bool) {
+    pub(super) fn set_active(&mut self, active: bool) {
         self.active = active;
     }
 
-    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let border = if self.active {
             theme.accent()
         } else {
@@ -1305,7 +1431,7 @@ impl ProjectionSelect {
         frame.render_widget(input, area);
     }
 
-    fn handle_event(&mut self, evt: &Event) -> bool {
+    pub(super) fn handle_event(&mut self, evt: &Event) -> bool {
         if !self.active {
             return false;
         }
@@ -1326,7 +1452,7 @@ impl ProjectionSelect {
     }
 }
 
-struct TypeSelect {
+pub(super) struct TypeSelect {
     label: String,
     value: AttributeType,
     active: bool,
@@ -1341,11 +1467,11 @@ impl TypeSelect {
         }
     }
 
-    fn set_active(&mut self, active: bool) {
+    pub(super) fn set_active(&mut self, active: bool) {
         self.active = active;
     }
 
-    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let border = if self.active {
             theme.accent()
         } else {
@@ -1365,7 +1491,7 @@ impl TypeSelect {
         frame.render_widget(input, area);
     }
 
-    fn handle_event(&mut self, evt: &Event) -> bool {
+    pub(super) fn handle_event(&mut self, evt: &Event) -> bool {
         if !self.active {
             return false;
         }
@@ -1394,15 +1520,89 @@ impl TypeSelect {
     }
 }
 
-struct TextInput {
+/// Cycles through a fixed list of options with space and the arrow keys.
+pub(super) struct ChoiceSelect<T: 'static> {
+    label: String,
+    options: &'static [T],
+    selected: usize,
+    describe: fn(T) -> &'static str,
+    active: bool,
+}
+
+impl<T: Copy> ChoiceSelect<T> {
+    fn new(
+        label: impl Into<String>,
+        options: &'static [T],
+        describe: fn(T) -> &'static str,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            options,
+            selected: 0,
+            describe,
+            active: false,
+        }
+    }
+
+    fn value(&self) -> T {
+        self.options[self.selected]
+    }
+
+    fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let border = if self.active {
+            theme.accent()
+        } else {
+            theme.border()
+        };
+        let block = Block::bordered()
+            .title(self.label.as_str())
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()))
+            .border_style(Style::default().fg(border));
+        let input = Paragraph::new(Line::from(Span::styled(
+            (self.describe)(self.value()),
+            Style::default().fg(theme.text()),
+        )))
+        .block(block)
+        .alignment(Alignment::Center);
+        frame.render_widget(input, area);
+    }
+
+    fn handle_event(&mut self, evt: &Event) -> bool {
+        if !self.active {
+            return false;
+        }
+        let Some(key) = evt.as_key_press_event() else {
+            return false;
+        };
+        let len = self.options.len();
+        match key.code {
+            KeyCode::Char(' ') | KeyCode::Right => {
+                self.selected = (self.selected + 1) % len;
+                true
+            }
+            KeyCode::Left => {
+                self.selected = (self.selected + len - 1) % len;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub(super) struct TextInput {
     label: String,
     value: String,
     cursor: usize,
     active: bool,
+    placeholder: &'static str,
 }
 
 impl TextInput {
-    fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
+    pub(super) fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
         let value = value.into();
         let cursor = value.len();
         Self {
@@ -1410,21 +1610,28 @@ impl TextInput {
             value,
             cursor,
             active: false,
+            placeholder: "(required)",
         }
     }
 
-    fn value(&self) -> &str {
+    /// Shown in place of an empty value.
+    pub(super) fn with_placeholder(mut self, placeholder: &'static str) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    pub(super) fn value(&self) -> &str {
         &self.value
     }
 
-    fn set_active(&mut self, active: bool) {
+    pub(super) fn set_active(&mut self, active: bool) {
         self.active = active;
         if self.cursor > self.value.len() {
             self.cursor = self.value.len();
         }
     }
 
-    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+    pub(super) fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let border = if self.active {
             theme.accent()
         } else {
@@ -1435,7 +1642,7 @@ impl TextInput {
             .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()))
             .border_style(Style::default().fg(border));
         let value = if self.value.is_empty() {
-            Span::styled("(required)", Style::default().fg(theme.text_muted()))
+            Span::styled(self.placeholder, Style::default().fg(theme.text_muted()))
         } else {
             Span::styled(self.value.as_str(), Style::default().fg(theme.text()))
         };
@@ -1449,7 +1656,7 @@ impl TextInput {
         }
     }
 
-    fn handle_event(&mut self, evt: &Event) -> bool {
+    pub(super) fn handle_event(&mut self, evt: &Event) -> bool {
         if !self.active {
             return false;
         }
@@ -1502,14 +1709,14 @@ impl TextInput {
     }
 }
 
-fn render_key_row(frame: &mut Frame, area: Rect, key: &KeyInput, theme: &Theme) {
+pub(super) fn render_key_row(frame: &mut Frame, area: Rect, key: &KeyInput, theme: &Theme) {
     let layout = Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)]);
     let [name_area, type_area] = area.layout(&layout);
     key.name.render(frame, name_area, theme);
     key.key_type.render(frame, type_area, theme);
 }
 
-fn render_projection_row(
+pub(super) fn render_projection_row(
     frame: &mut Frame,
     area: Rect,
     projection: &ProjectionInput,
@@ -1529,6 +1736,20 @@ fn render_row(
     theme: &Theme,
 ) {
     match kind {
+        RowKind::ImportSource => {
+            if let Some(import) = state.import.as_ref() {
+                import.source.render(frame, area, theme);
+            }
+        }
+        RowKind::ImportFormat => {
+            if let Some(import) = state.import.as_ref() {
+                let layout =
+                    Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]);
+                let [format_area, compression_area] = area.layout(&layout);
+                import.format.render(frame, format_area, theme);
+                import.compression.render(frame, compression_area, theme);
+            }
+        }
         RowKind::TableName => state.table_name.render(frame, area, theme),
         RowKind::HashKey => render_key_row(frame, area, &state.hash_key, theme),
         RowKind::SortKey => render_key_row(frame, area, &state.sort_key, theme),
@@ -1578,7 +1799,14 @@ fn render_empty_hint(frame: &mut Frame, area: Rect, label: &str, theme: &Theme)
     frame.render_widget(Paragraph::new(text), area);
 }
 
-fn render_actions(frame: &mut Frame, area: Rect, theme: &Theme, active: bool, selected: ActionId) {
+fn render_actions(
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    active: bool,
+    selected: ActionId,
+    importing: bool,
+) {
     let base_style = Style::default()
         .fg(theme.accent())
         .add_modifier(Modifier::BOLD);
@@ -1602,7 +1830,10 @@ fn render_actions(frame: &mut Frame, area: Rect, theme: &Theme, active: bool, se
         Span::raw("  "),
         Span::styled("[- Index]", style_for(ActionId::Remove)),
         Span::raw("  "),
-        Span::styled("[Create]", style_for(ActionId::Create)),
+        Span::styled(
+            if importing { "[Import]" } else { "[Create]" },
+            style_for(ActionId::Create),
+        ),
     ]);
     let actions = Paragraph::new(line).alignment(Alignment::Center);
     frame.render_widget(actions, area);
@@ -1621,10 +1852,12 @@ fn render_status(
             Style::default().fg(theme.error()),
         ))
     } else if matches!(state.status, CreateStatus::Submitting) {
-        Some((
-            "Creating table...".to_string(),
-            Style::default().fg(theme.warning()),
-        ))
+        let text = if state.import.is_some() {
+            "Starting import..."
+        } else {
+            "Creating table..."
+        };
+        Some((text.to_string(), Style::default().fg(theme.warning())))
     } else {
         None
     };
@@ -1709,9 +1942,50 @@ fn remove_picker_rect(area: Rect, item_count: usize) -> Rect {
 #[derive(Debug, Clone)]
 struct CreateTableResult {
     table_name: String,
+    /// Whether the table is being imported rather than created empty.
+    imported: bool,
     result: Result<(), String>,
 }
 
+/// The S3 source fields of an import.
+struct ImportInput {
+    source: TextInput,
+    format: ChoiceSelect<ImportFormat>,
+    compression: ChoiceSelect<ImportCompression>,
+}
+
+impl ImportInput {
+    fn new() -> Self {
+        Self {
+            source: TextInput::new("S3 source (bucket and key prefix)", "")
+                .with_placeholder("s3://bucket/prefix/"),
+            format: ChoiceSelect::new("Format", &ImportFormat::ALL, ImportFormat::label),
+            compression: ChoiceSelect::new(
+                "Compression",
+                &ImportCompression::ALL,
+                ImportCompression::label,
+            ),
+        }
+    }
+}
+
+/// The import's source, or `None` when the popup creates an empty table.
+fn build_import_source(state: &CreateTableState) -> Result<Option<ImportSource>, String> {
+    let Some(import) = state.import.as_ref() else {
+        return Ok(None);
+    };
+    if !state.lsis.is_empty() {
+        return Err("Imported tables can't have LSIs".to_string());
+    }
+    let uri = S3Uri::parse(import.source.value())?;
+    Ok(Some(ImportSource {
+        bucket: uri.bucket,
+        key_prefix: uri.key,
+        format: import.format.value(),
+        compression: import.compression.value(),
+    }))
+}
+
 /// Convert the widget's DynamoDB-flavored spec into the neutral spec the
 /// datastore trait accepts.
 fn to_collection_spec(spec: &CreateTableSpec) -> CreateCollectionSpec {
@@ -1720,21 +1994,7 @@ fn to_collection_spec(spec: &CreateTableSpec) -> CreateCollectionSpec {
         fields.push(key_field(sort_key, KeyRole::Sort));
     }
 
-    let mut indexes = Vec::new();
-    for gsi in &spec.gsis {
-        let mut index_fields = vec![key_field(&gsi.hash_key, KeyRole::Partition)];
-        if let Some(sort_key) = gsi.sort_key.as_ref() {
-            index_fields.push(key_field(sort_key, KeyRole::Sort));
-        }
-        indexes.push(IndexSchema {
-            name: gsi.name.clone(),
-            kind: IndexKind::GlobalSecondary,
-            key: KeySchema {
-                fields: index_fields,
-            },
-            projection: projection(&gsi.projection),
-        });
-    }
+    let mut indexes: Vec<IndexSchema> = spec.gsis.iter().map(gsi_index_schema).collect();
     for lsi in &spec.lsis {
         indexes.push(IndexSchema {
             name: lsi.name.clone(),
@@ -1753,6 +2013,20 @@ fn to_collection_spec(spec: &CreateTableSpec) -> CreateCollectionSpec {
     }
 }
 
+/// The neutral index schema for a GSI spec.
+pub(super) fn gsi_index_schema(gsi: &GsiSpec) -> IndexSchema {
+    let mut fields = vec![key_field(&gsi.hash_key, KeyRole::Partition)];
+    if let Some(sort_key) = gsi.sort_key.as_ref() {
+        fields.push(key_field(sort_key, KeyRole::Sort));
+    }
+    IndexSchema {
+        name: gsi.name.clone(),
+        kind: IndexKind::GlobalSecondary,
+        key: KeySchema { fields },
+        projection: projection(&gsi.projection),
+    }
+}
+
 fn key_field(spec: &KeySpec, role: KeyRole) -> KeyField {
     KeyField {
         name: spec.name.clone(),
@@ -1796,33 +2070,11 @@ fn build_spec(state: &CreateTableState) -> Result<CreateTableSpec, String> {
         })
     };
 
-    let mut gsis = Vec::new();
-    for gsi in &state.gsis {
-        let name = gsi.name.value().trim().to_string();
-        let hash_name = gsi.hash_key.name.value().trim().to_string();
-        let sort_name = gsi.sort_key.name.value().trim().to_string();
-        let sort_key = if sort_name.is_empty() {
-            None
-        } else {
-            Some(KeySpec {
-                name: sort_name,
-                attr_type: gsi.sort_key.key_type.value,
-            })
-        };
-        let projection = projection_from_input(
-            gsi.projection.kind.value,
-            gsi.projection.include_attrs.value(),
-        )?;
-        gsis.push(GsiSpec {
-            name,
-            hash_key: KeySpec {
-                name: hash_name,
-                attr_type: gsi.hash_key.key_type.value,
-            },
-            sort_key,
-            projection,
-        });
-    }
+    let gsis = state
+        .gsis
+        .iter()
+        .map(GsiInput::to_spec)
+        .collect::<Result<Vec<_>, _>>()?;
 
     let mut lsis = Vec::new();
     for lsi in &state.lsis {
@@ -0,0 +1,424 @@
+//! A full-screen list of recent imports from S3. Opened with `I` from the
+//! table picker. `^n` starts an import into a new table; the view polls while
+//! any import is still running and shows the selected one's failure, if any.
+
+use std::{borrow::Cow, cell::RefCell, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState},
+};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::schema::ImportInfo;
+
+use super::create_table::{CreateTablePopup, ImportStartedEvent, TableCreatedEvent};
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    util::pad,
+    widgets::{WidgetInner, theme::Theme},
+};
+
+/// How often to refresh while an import is running.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct ImportsWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    state: RefCell<ImportsState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Default)]
+struct ImportsState {
+    loading: bool,
+    imports: Vec<ImportInfo>,
+    error: Option<String>,
+    table_state: TableState,
+    /// Whether a poll is scheduled; keeps manual refreshes from stacking
+    /// extra polls.
+    poll_pending: bool,
+}
+
+impl ImportsState {
+    fn selected(&self) -> Option<&ImportInfo> {
+        self.table_state
+            .selected()
+            .and_then(|idx| self.imports.get(idx))
+    }
+}
+
+struct ImportsLoadedEvent {
+    result: Result<Vec<ImportInfo>, String>,
+}
+
+struct PollTick;
+
+impl ImportsWidget {
+    pub fn new(db: Arc<dyn Datastore>, parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            state: RefCell::new(ImportsState::default()),
+            help_entries: build_help(),
+        }
+    }
+
+    fn reload(&self, ctx: crate::env::WidgetCtx) {
+        self.state.borrow_mut().loading = true;
+        ctx.invalidate();
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let result = db.list_imports().await.map_err(|err| err.to_string());
+            ctx.emit_self(ImportsLoadedEvent { result });
+        });
+    }
+
+    /// Refresh after `POLL_INTERVAL` if an import is still running and no
+    /// poll is already scheduled.
+    fn schedule_poll(&self, ctx: crate::env::WidgetCtx) {
+        let mut state = self.state.borrow_mut();
+        let running = state.imports.iter().any(ImportInfo::is_running);
+        if !running || state.poll_pending {
+            return;
+        }
+        state.poll_pending = true;
+        tokio::spawn(async move {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            ctx.emit_self(PollTick);
+        });
+    }
+
+    fn select_by(&self, delta: isize) -> bool {
+        let mut state = self.state.borrow_mut();
+        let len = state.imports.len();
+        if len == 0 {
+            return false;
+        }
+        let current = state.table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        if state.table_state.selected() == Some(next) {
+            return false;
+        }
+        state.table_state.select(Some(next));
+        true
+    }
+
+    fn show_import(&self, ctx: &crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            ctx.show_toast(Toast {
+                message: dynamate::readonly::REJECT_MESSAGE.to_string(),
+                kind: ToastKind::Warning,
+                duration: dynamate::readonly::TOAST_DURATION,
+                action: None,
+            });
+            return;
+        }
+        ctx.set_popup(Box::new(CreateTablePopup::new_import(
+            self.db.clone(),
+            self.inner.id(),
+        )));
+    }
+
+    fn on_loaded(&self, ctx: &crate::env::WidgetCtx, loaded: &ImportsLoadedEvent) {
+        let mut state = self.state.borrow_mut();
+        state.loading = false;
+        let imports = match loaded.result.as_ref() {
+            Ok(imports) => imports,
+            Err(err) => {
+                state.error = Some(format!("Failed to list imports: {err}"));
+                return;
+            }
+        };
+        // Imports that were running on the last load and have finished since.
+        for import in imports.iter().filter(|import| !import.is_running()) {
+            let was_running = state
+                .imports
+                .iter()
+                .any(|previous| previous.id == import.id && previous.is_running());
+            if !was_running {
+                continue;
+            }
+            if import.status == "COMPLETED" {
+                ctx.show_toast(Toast {
+                    message: format!("Imported {}", import.table),
+                    kind: ToastKind::Info,
+                    duration: Duration::from_secs(3),
+                    action: None,
+                });
+                ctx.broadcast_event(TableCreatedEvent {
+                    table_name: import.table.clone(),
+                });
+            } else {
+                ctx.show_toast(Toast {
+                    message: format!("Import into {} {}", import.table, import.status),
+                    kind: ToastKind::Error,
+                    duration: Duration::from_secs(4),
+                    action: None,
+                });
+            }
+        }
+        let selected_id = state.selected().map(|import| import.id.clone());
+        state.imports.clone_from(imports);
+        let selected = selected_id
+            .and_then(|id| imports.iter().position(|import| import.id == id))
+            .or((!imports.is_empty()).then_some(0));
+        state.table_state.select(selected);
+        state.error = None;
+    }
+}
+
+impl crate::widgets::Widget for ImportsWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.reload(ctx);
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some("imports".to_string())
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            mode: Some("IMPORTS".to_string()),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().loading
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let heading = "Imports from S3";
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let mut block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+        if let Some(failure) = state.selected().and_then(|import| import.failure.as_ref()) {
+            block = block.title_bottom(Line::styled(
+                pad(failure, 1),
+                Style::default().fg(theme.error()),
+            ));
+        }
+
+        let message = if let Some(err) = state.error.as_ref() {
+            Some((err.clone(), theme.error()))
+        } else if state.imports.is_empty() {
+            let text = if state.loading {
+                "Loading imports..."
+            } else {
+                "No imports yet; ^n imports a table from S3"
+            };
+            Some((text.to_string(), theme.warning()))
+        } else {
+            None
+        };
+        if let Some((text, color)) = message {
+            let body = Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+
+        let header = Row::new(vec![
+            "Table", "Status", "Source", "Items", "Errors", "Started",
+        ])
+        .style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows: Vec<Row> = state
+            .imports
+            .iter()
+            .map(|import| {
+                let status_style = match import.status.as_str() {
+                    "COMPLETED" => Style::default().fg(theme.success()),
+                    "FAILED" | "CANCELLED" => Style::default().fg(theme.error()),
+                    _ => Style::default().fg(theme.warning()),
+                };
+                Row::new(vec![
+                    Cell::from(import.table.clone()),
+                    Cell::from(import.status.clone()).style(status_style),
+                    Cell::from(import.source.clone()),
+                    Cell::from(format_items(import)),
+                    Cell::from(import.errors.to_string()),
+                    Cell::from(import.started_at.clone().unwrap_or_else(|| "—".to_string())),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(11),
+            Constraint::Fill(2),
+            Constraint::Length(15),
+            Constraint::Length(7),
+            Constraint::Length(21),
+        ];
+        let table = Table::new(rows, widths)
+            .block(block)
+            .header(header)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("❯ ")
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(table, area, frame.buffer_mut(), &mut state.table_state);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let moved = match key.code {
+            KeyCode::Char('r') if ctrl => {
+                self.reload(ctx);
+                return true;
+            }
+            KeyCode::Char('n') if ctrl => {
+                self.show_import(&ctx);
+                return true;
+            }
+            KeyCode::Esc => {
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.select_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_by(-1),
+            _ => return false,
+        };
+        if moved {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(loaded) = event.payload::<ImportsLoadedEvent>() {
+            self.on_loaded(&ctx, loaded);
+            self.schedule_poll(ctx.clone());
+            ctx.invalidate();
+            return;
+        }
+        if event.payload::<PollTick>().is_some() {
+            self.state.borrow_mut().poll_pending = false;
+            self.reload(ctx);
+        }
+    }
+
+    fn on_app_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if event.payload::<ImportStartedEvent>().is_some() {
+            self.reload(ctx);
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+/// Imported out of processed items; the two differ when rows were rejected.
+fn format_items(import: &ImportInfo) -> String {
+    if import.imported_items == import.processed_items {
+        import.imported_items.to_string()
+    } else {
+        format!("{}/{}", import.imported_items, import.processed_items)
+    }
+}
+
+fn build_help() -> Vec<help::Entry<'static>> {
+    vec![
+        help_entry("j/k/↑/↓", "move", "Move selection"),
+        help_ctrl("^n", "import", "Import a table from S3"),
+        help_ctrl("^r", "refresh", "Refresh imports"),
+        help_entry("esc", "back", "Back"),
+    ]
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+fn help_ctrl(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(""),
+        short: Cow::Borrowed(""),
+        long: Cow::Borrowed(""),
+        ctrl: Some(help::Variant {
+            keys: Some(Cow::Borrowed(keys)),
+            short: Some(Cow::Borrowed(short)),
+            long: Some(Cow::Borrowed(long)),
+        }),
+        shift: None,
+        alt: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::schema::ImportInfo;
+
+    use super::format_items;
+
+    #[test]
+    fn items_show_rejected_rows() {
+        let mut import = ImportInfo {
+            id: "arn".to_string(),
+            table: "orders".to_string(),
+            status: "IN_PROGRESS".to_string(),
+            source: "s3://bucket/orders/".to_string(),
+            format: None,
+            started_at: None,
+            ended_at: None,
+            processed_items: 10,
+            imported_items: 10,
+            errors: 0,
+            failure: None,
+        };
+        assert_eq!(format_items(&import), "10");
+        import.imported_items = 8;
+        assert_eq!(format_items(&import), "8/10");
+    }
+}
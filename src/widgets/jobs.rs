@@ -0,0 +1,224 @@
+//! The jobs panel, opened with `J` from anywhere. It lists the background
+//! jobs of this session with their latest progress or outcome; `x` cancels
+//! the selected one and `c` clears the finished ones.
+
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState},
+};
+
+use crate::{
+    help,
+    jobs::{self, Job, JobStatus},
+    widgets::{WidgetInner, purge::format_elapsed, theme::Theme},
+};
+
+pub struct JobsWidget {
+    inner: WidgetInner,
+    state: RefCell<JobsState>,
+    help: Vec<help::Entry<'static>>,
+}
+
+struct JobsState {
+    /// The jobs as of the last render, to map the selection to a job.
+    jobs: Vec<Job>,
+    table_state: TableState,
+}
+
+impl JobsState {
+    fn refresh(&mut self) {
+        self.jobs = jobs::list();
+        let last = self.jobs.len().checked_sub(1);
+        let selected = self
+            .table_state
+            .selected()
+            .map_or(last, |row| last.map(|last| row.min(last)));
+        self.table_state.select(selected);
+    }
+
+    fn select_by(&mut self, delta: isize) -> bool {
+        let len = self.jobs.len();
+        if len == 0 {
+            return false;
+        }
+        let current = self.table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        if self.table_state.selected() == Some(next) {
+            return false;
+        }
+        self.table_state.select(Some(next));
+        true
+    }
+}
+
+impl JobsWidget {
+    pub fn new(parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            state: RefCell::new(JobsState {
+                jobs: Vec::new(),
+                table_state: TableState::default(),
+            }),
+            help: vec![
+                help_entry("j/k/↑/↓", "move", "Move selection"),
+                help_entry("x", "cancel", "Cancel the selected job"),
+                help_entry("c", "clear", "Clear finished jobs"),
+                help_entry("esc", "back", "Back"),
+            ],
+        }
+    }
+}
+
+impl crate::widgets::Widget for JobsWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.state.borrow_mut().refresh();
+        ctx.invalidate();
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some("Jobs".to_string())
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            mode: Some("JOBS".to_string()),
+            stats: Some(format!("{} running", jobs::running())),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let mut state = self.state.borrow_mut();
+        state.refresh();
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled("Jobs", Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled("Jobs", Style::default().fg(theme.text()))
+        };
+        let block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        if state.jobs.is_empty() {
+            let body = Paragraph::new("No background jobs yet")
+                .style(Style::default().fg(theme.warning()))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+
+        let header = Row::new(vec!["Job", "Status", "Time", "Progress"]).style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows: Vec<Row> = state
+            .jobs
+            .iter()
+            .map(|job| {
+                let color = match job.status {
+                    JobStatus::Running => theme.accent(),
+                    JobStatus::Canceling | JobStatus::Canceled => theme.warning(),
+                    JobStatus::Done => theme.success(),
+                    JobStatus::Failed => theme.error(),
+                };
+                Row::new(vec![
+                    Cell::from(job.label.clone()),
+                    Cell::from(job.status.label()).style(Style::default().fg(color)),
+                    Cell::from(format_elapsed(job.elapsed())),
+                    Cell::from(job.message.clone()),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(32),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("❯ ")
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(table, area, frame.buffer_mut(), &mut state.table_state);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let mut state = self.state.borrow_mut();
+        let changed = match key.code {
+            KeyCode::Char('x') => state
+                .table_state
+                .selected()
+                .and_then(|row| state.jobs.get(row))
+                .is_some_and(|job| jobs::cancel(job.id)),
+            KeyCode::Char('c') => {
+                jobs::clear_finished();
+                state.refresh();
+                true
+            }
+            KeyCode::Esc => {
+                drop(state);
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => state.select_by(1),
+            KeyCode::Char('k') | KeyCode::Up => state.select_by(-1),
+            _ => return false,
+        };
+        if changed {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(&self.help)
+    }
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
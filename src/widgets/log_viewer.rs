@@ -0,0 +1,424 @@
+//! The in-app log viewer, opened with `L` from anywhere. It shows the records
+//! this session has logged (what passes `DYNAMATE_LOGLEVEL`, as in the log
+//! file) and follows new ones as they arrive. `v` raises the minimum level
+//! shown and `/` searches messages and targets.
+
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState,
+        Wrap,
+    },
+};
+use tracing::Level;
+
+use crate::{
+    help,
+    logging::{self, LogRecord},
+    widgets::{WidgetInner, filter_input::FilterInput, theme::Theme},
+};
+
+/// Minimum levels `v` steps through, most verbose first.
+const LEVELS: [Level; 5] = [
+    Level::TRACE,
+    Level::DEBUG,
+    Level::INFO,
+    Level::WARN,
+    Level::ERROR,
+];
+
+pub struct LogViewerWidget {
+    inner: WidgetInner,
+    state: RefCell<LogViewerState>,
+    help_base: Vec<help::Entry<'static>>,
+    help_search_applied: Vec<help::Entry<'static>>,
+    help_search_edit: Vec<help::Entry<'static>>,
+}
+
+struct LogViewerState {
+    records: Vec<LogRecord>,
+    /// [`logging::recent_total`] when `records` was taken.
+    seen: u64,
+    /// Indexes into `records` of the ones that pass the level and search.
+    visible: Vec<usize>,
+    min_level: Level,
+    search: FilterInput,
+    /// Keep the newest record selected as more arrive.
+    follow: bool,
+    table_state: TableState,
+}
+
+impl LogViewerState {
+    fn refresh(&mut self) {
+        let total = logging::recent_total();
+        if total != self.seen {
+            self.seen = total;
+            self.records = logging::recent();
+            self.apply_filter();
+        }
+    }
+
+    fn apply_filter(&mut self) {
+        let query = self.search.value.to_lowercase();
+        self.visible = self
+            .records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| passes(record, self.min_level, &query))
+            .map(|(idx, _)| idx)
+            .collect();
+        let last = self.visible.len().checked_sub(1);
+        let selected = if self.follow {
+            last
+        } else {
+            self.table_state
+                .selected()
+                .map(|row| row.min(last.unwrap_or(0)))
+        };
+        self.table_state
+            .select(selected.filter(|_| !self.visible.is_empty()));
+    }
+
+    fn selected(&self) -> Option<&LogRecord> {
+        let row = self.table_state.selected()?;
+        self.visible.get(row).map(|idx| &self.records[*idx])
+    }
+
+    fn select_by(&mut self, delta: isize) -> bool {
+        let len = self.visible.len();
+        if len == 0 {
+            return false;
+        }
+        let current = self.table_state.selected().unwrap_or(len - 1) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        self.follow = next == len - 1;
+        if self.table_state.selected() == Some(next) {
+            return false;
+        }
+        self.table_state.select(Some(next));
+        true
+    }
+}
+
+impl LogViewerWidget {
+    pub fn new(parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            state: RefCell::new(LogViewerState {
+                records: Vec::new(),
+                seen: 0,
+                visible: Vec::new(),
+                min_level: Level::TRACE,
+                search: FilterInput::default(),
+                follow: true,
+                table_state: TableState::default(),
+            }),
+            help_base: build_help(false),
+            help_search_applied: build_help(true),
+            help_search_edit: vec![
+                help_entry("esc", "clear", "Clear the search"),
+                help_entry("⏎", "apply", "Apply the search"),
+            ],
+        }
+    }
+
+    fn cycle_level(&self) {
+        let mut state = self.state.borrow_mut();
+        let current = LEVELS
+            .iter()
+            .position(|level| *level == state.min_level)
+            .unwrap_or(0);
+        state.min_level = LEVELS[(current + 1) % LEVELS.len()];
+        state.apply_filter();
+    }
+}
+
+impl crate::widgets::Widget for LogViewerWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.state.borrow_mut().refresh();
+        ctx.invalidate();
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some("Logs".to_string())
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        let state = self.state.borrow();
+        let mut stats = format!(
+            "{} of {} records · {} and above",
+            state.visible.len(),
+            state.records.len(),
+            state.min_level
+        );
+        if !state.follow {
+            stats.push_str(" · paused");
+        }
+        crate::widgets::StatusInfo {
+            mode: Some(
+                if state.search.is_active() {
+                    "SEARCH"
+                } else {
+                    "LOGS"
+                }
+                .to_string(),
+            ),
+            stats: Some(stats),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let mut state = self.state.borrow_mut();
+        state.refresh();
+        let area = if state.search.is_active() || !state.search.value.is_empty() {
+            let [search_area, rest] = area.layout(&Layout::vertical([
+                Constraint::Length(3),
+                Constraint::Fill(1),
+            ]));
+            state
+                .search
+                .render_with_title(frame, search_area, theme, "Search");
+            rest
+        } else {
+            area
+        };
+
+        let heading = format!(
+            "Logs: {}",
+            logging::get_data_dir()
+                .join(logging::LOG_FILE.as_str())
+                .display()
+        );
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        if state.visible.is_empty() {
+            let text = if state.records.is_empty() {
+                "Nothing logged yet"
+            } else {
+                "No records match"
+            };
+            let body = Paragraph::new(text)
+                .style(Style::default().fg(theme.warning()))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+
+        frame.render_widget(block.clone(), area);
+        let [list_area, detail_area] = block.inner(area).layout(&Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(5),
+        ]));
+
+        let header = Row::new(vec!["Time", "Level", "Target", "Message"]).style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows: Vec<Row> = state
+            .visible
+            .iter()
+            .map(|idx| {
+                let record = &state.records[*idx];
+                let level_color = match record.level {
+                    Level::ERROR => theme.error(),
+                    Level::WARN => theme.warning(),
+                    Level::INFO => theme.success(),
+                    _ => theme.text_muted(),
+                };
+                Row::new(vec![
+                    Cell::from(record.at.clone()),
+                    Cell::from(record.level.as_str()).style(Style::default().fg(level_color)),
+                    Cell::from(record.target.clone()),
+                    Cell::from(record.message.clone()),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(5),
+            Constraint::Length(28),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("❯ ")
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(table, list_area, frame.buffer_mut(), &mut state.table_state);
+
+        let detail = state
+            .selected()
+            .map(|record| record.message.clone())
+            .unwrap_or_default();
+        let detail = Paragraph::new(detail)
+            .style(Style::default().fg(theme.text_muted()))
+            .wrap(Wrap { trim: true })
+            .block(Block::new().borders(Borders::TOP));
+        frame.render_widget(detail, detail_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.search.handle_event(event) {
+                state.apply_filter();
+                ctx.invalidate();
+                return true;
+            }
+        }
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let mut state = self.state.borrow_mut();
+        let moved = match key.code {
+            KeyCode::Char('/') => {
+                state.search.set_active(true);
+                true
+            }
+            KeyCode::Char('v') => {
+                drop(state);
+                self.cycle_level();
+                true
+            }
+            KeyCode::Esc if !state.search.value.is_empty() => {
+                state.search.clear();
+                state.apply_filter();
+                true
+            }
+            KeyCode::Esc => {
+                drop(state);
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => state.select_by(1),
+            KeyCode::Char('k') | KeyCode::Up => state.select_by(-1),
+            KeyCode::PageDown => state.select_by(10),
+            KeyCode::PageUp => state.select_by(-10),
+            KeyCode::Char('g') | KeyCode::Home => state.select_by(isize::MIN / 2),
+            KeyCode::Char('G') | KeyCode::End => state.select_by(isize::MAX / 2),
+            _ => return false,
+        };
+        if moved {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        let state = self.state.borrow();
+        if state.search.is_active() {
+            Some(&self.help_search_edit)
+        } else if !state.search.value.is_empty() {
+            Some(&self.help_search_applied)
+        } else {
+            Some(&self.help_base)
+        }
+    }
+
+    fn suppress_global_help(&self) -> bool {
+        self.state.borrow().search.is_active()
+    }
+}
+
+/// Whether `record` is at `min_level` or more severe and, when `query`
+/// (lowercased) isn't empty, mentions it in its message or target.
+fn passes(record: &LogRecord, min_level: Level, query: &str) -> bool {
+    // Levels order from most verbose up, so ERROR is the smallest.
+    record.level <= min_level
+        && (query.is_empty()
+            || record.message.to_lowercase().contains(query)
+            || record.target.to_lowercase().contains(query))
+}
+
+fn build_help(search_applied: bool) -> Vec<help::Entry<'static>> {
+    vec![
+        help_entry("j/k/↑/↓", "move", "Move selection"),
+        help_entry(
+            "g/G",
+            "top/follow",
+            "Jump to the oldest, or follow the newest",
+        ),
+        help_entry("v", "level", "Raise the minimum level shown"),
+        help_entry("/", "search", "Search messages and targets"),
+        if search_applied {
+            help_entry("esc", "clear search", "Clear the search")
+        } else {
+            help_entry("esc", "back", "Back")
+        },
+    ]
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::Level;
+
+    use super::passes;
+    use crate::logging::LogRecord;
+
+    #[test]
+    fn filters_by_level_and_search() {
+        let record = LogRecord {
+            at: "12:00:00.000".to_string(),
+            level: Level::WARN,
+            target: "dynamate::dynamodb::debug".to_string(),
+            message: "DynamoDB request complete error=ThrottlingException".to_string(),
+        };
+        assert!(passes(&record, Level::TRACE, ""));
+        assert!(passes(&record, Level::WARN, ""));
+        assert!(!passes(&record, Level::ERROR, ""));
+        assert!(passes(&record, Level::INFO, "throttling"));
+        assert!(passes(&record, Level::INFO, "debug"));
+        assert!(!passes(&record, Level::INFO, "timeout"));
+    }
+}
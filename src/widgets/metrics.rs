@@ -0,0 +1,375 @@
+//! A full-screen view of one table's recent CloudWatch metrics: consumed
+//! read and write capacity, throttled requests, and request latency, each as
+//! a sparkline. Opened with `M` from the table picker. `w` switches between
+//! the last hour, three hours, and day; the view refreshes every minute.
+
+use std::{borrow::Cow, cell::RefCell, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Sparkline, SparklineBar},
+};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::metrics::{CollectionMetrics, MetricKind, MetricSeries, MetricsWindow};
+
+use crate::{
+    help,
+    util::pad,
+    widgets::{WidgetInner, theme::Theme},
+};
+
+/// How often the open view fetches the metrics again.
+const POLL_INTERVAL: Duration = Duration::from_mins(1);
+
+/// Bar heights are whole numbers; values are scaled by this first so
+/// fractional capacity and latency still show.
+const BAR_SCALE: f64 = 100.0;
+
+pub struct MetricsWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    table_name: String,
+    state: RefCell<MetricsState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Default)]
+struct MetricsState {
+    loading: bool,
+    window: MetricsWindow,
+    metrics: Option<CollectionMetrics>,
+    error: Option<String>,
+    /// Whether a poll is scheduled; keeps manual refreshes from stacking
+    /// extra polls.
+    poll_pending: bool,
+}
+
+struct MetricsLoadedEvent {
+    window: MetricsWindow,
+    result: Result<CollectionMetrics, String>,
+}
+
+struct PollTick;
+
+impl MetricsWidget {
+    pub fn new(db: Arc<dyn Datastore>, table_name: &str, parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            table_name: table_name.to_string(),
+            state: RefCell::new(MetricsState::default()),
+            help_entries: build_help(),
+        }
+    }
+
+    fn reload(&self, ctx: crate::env::WidgetCtx) {
+        let window = {
+            let mut state = self.state.borrow_mut();
+            state.loading = true;
+            state.window
+        };
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        tokio::spawn(async move {
+            let result = db
+                .collection_metrics(&table_name, window)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(MetricsLoadedEvent { window, result });
+        });
+    }
+
+    fn schedule_poll(&self, ctx: crate::env::WidgetCtx) {
+        let mut state = self.state.borrow_mut();
+        if state.error.is_some() || state.poll_pending {
+            return;
+        }
+        state.poll_pending = true;
+        tokio::spawn(async move {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            ctx.emit_self(PollTick);
+        });
+    }
+
+    fn on_loaded(&self, loaded: &MetricsLoadedEvent) {
+        let mut state = self.state.borrow_mut();
+        // A reply for a window the user has since switched away from.
+        if loaded.window != state.window {
+            return;
+        }
+        state.loading = false;
+        match loaded.result.as_ref() {
+            Ok(metrics) => {
+                state.metrics = Some(metrics.clone());
+                state.error = None;
+            }
+            Err(err) => state.error = Some(err.clone()),
+        }
+    }
+
+    fn render_series(
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        series: &MetricSeries,
+        window: MetricsWindow,
+    ) {
+        let [header_area, chart_area] = area.layout(&Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ]));
+        let kind = series.kind;
+        let unit = if kind.is_count() {
+            format!("{} per {} min", kind.unit(), window.period().as_secs() / 60)
+        } else {
+            kind.unit().to_string()
+        };
+        let header = Line::from(vec![
+            Span::styled(
+                kind.label(),
+                Style::default()
+                    .fg(theme.text())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" ({unit})  "),
+                Style::default().fg(theme.text_muted()),
+            ),
+            Span::styled(summary(series), Style::default().fg(theme.text_muted())),
+        ]);
+        frame.render_widget(Paragraph::new(header), header_area);
+
+        let color = if kind == MetricKind::Throttled {
+            theme.error()
+        } else {
+            theme.accent()
+        };
+        let sparkline = Sparkline::default()
+            .data(bars(series, usize::from(chart_area.width)))
+            .style(Style::default().fg(color))
+            .absent_value_style(Style::default().fg(theme.text_muted()));
+        frame.render_widget(sparkline, chart_area);
+    }
+}
+
+impl crate::widgets::Widget for MetricsWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.reload(ctx);
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some(format!("{} metrics", self.table_name))
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            context: Some(self.table_name.clone()),
+            mode: Some("METRICS".to_string()),
+            stats: Some(format!("last {}", self.state.borrow().window.label())),
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().loading
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let state = self.state.borrow();
+        let heading = format!(
+            "Metrics: {} · last {}",
+            self.table_name,
+            state.window.label()
+        );
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        let message = match (state.error.as_ref(), state.metrics.as_ref()) {
+            (Some(err), _) => Some((err.clone(), theme.error())),
+            (None, None) => Some(("Loading metrics...".to_string(), theme.warning())),
+            (None, Some(_)) => None,
+        };
+        if let Some((text, color)) = message {
+            let body = Paragraph::new(pad(text, 1))
+                .style(Style::default().fg(color))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+        let Some(metrics) = state.metrics.as_ref() else {
+            return;
+        };
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let layout = Layout::vertical(metrics.series.iter().map(|_| Constraint::Fill(1)))
+            .spacing(1)
+            .horizontal_margin(1);
+        for (series, series_area) in metrics.series.iter().zip(layout.split(inner).iter()) {
+            Self::render_series(frame, *series_area, theme, series, metrics.window);
+        }
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.reload(ctx);
+            }
+            KeyCode::Char('w') if key.modifiers.is_empty() => {
+                {
+                    let mut state = self.state.borrow_mut();
+                    state.window = state.window.next();
+                    state.metrics = None;
+                    state.error = None;
+                }
+                self.reload(ctx);
+            }
+            KeyCode::Esc => ctx.pop_widget(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(loaded) = event.payload::<MetricsLoadedEvent>() {
+            self.on_loaded(loaded);
+            self.schedule_poll(ctx.clone());
+            ctx.invalidate();
+            return;
+        }
+        if event.payload::<PollTick>().is_some() {
+            self.state.borrow_mut().poll_pending = false;
+            self.reload(ctx);
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+/// The most recent points that fit in `width` columns, scaled for the
+/// sparkline.
+fn bars(series: &MetricSeries, width: usize) -> Vec<SparklineBar> {
+    let skip = series.points.len().saturating_sub(width);
+    series.points[skip..]
+        .iter()
+        .map(|point| {
+            SparklineBar::from(point.map(|value| (value.max(0.0) * BAR_SCALE).round() as u64))
+        })
+        .collect()
+}
+
+fn summary(series: &MetricSeries) -> String {
+    let value = |value: Option<f64>| value.map_or_else(|| "—".to_string(), format_value);
+    format!(
+        "latest {}  max {}  avg {}",
+        value(series.latest()),
+        value(series.max()),
+        value(series.average())
+    )
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        let text = format!("{value:.2}");
+        text.trim_end_matches('0').to_string()
+    }
+}
+
+fn build_help() -> Vec<help::Entry<'static>> {
+    vec![
+        help_entry("w", "window", "Switch between the last 1h, 3h, and 24h"),
+        help::Entry {
+            keys: Cow::Borrowed(""),
+            short: Cow::Borrowed(""),
+            long: Cow::Borrowed(""),
+            ctrl: Some(help::Variant {
+                keys: Some(Cow::Borrowed("^r")),
+                short: Some(Cow::Borrowed("refresh")),
+                long: Some(Cow::Borrowed("Refresh metrics")),
+            }),
+            shift: None,
+            alt: None,
+        },
+        help_entry("esc", "back", "Back"),
+    ]
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::metrics::{MetricKind, MetricSeries};
+    use ratatui::widgets::SparklineBar;
+
+    use super::{bars, summary};
+
+    #[test]
+    fn shows_the_latest_points_and_summary() {
+        let series = MetricSeries {
+            kind: MetricKind::Latency,
+            points: vec![Some(1.0), None, Some(2.5), Some(0.126)],
+        };
+        assert_eq!(
+            bars(&series, 3),
+            vec![
+                SparklineBar::from(None),
+                SparklineBar::from(250),
+                SparklineBar::from(13),
+            ]
+        );
+        assert_eq!(summary(&series), "latest 0.13  max 2.5  avg 1.21");
+        let empty = MetricSeries {
+            kind: MetricKind::Latency,
+            points: vec![None],
+        };
+        assert_eq!(summary(&empty), "latest —  max —  avg —");
+    }
+}
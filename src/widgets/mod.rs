@@ -6,14 +6,32 @@ use rand::{Rng, distributions::Alphanumeric};
 use ratatui::{Frame, layout::Rect};
 use theme::Theme;
 
+pub mod analyze;
+pub mod assume_role;
+pub mod audit_log;
+pub mod backups;
+pub mod capacity;
 pub mod confirm;
+pub mod copy_table;
+pub mod create_index;
 pub mod create_table;
 pub mod error;
 pub(crate) mod filter_input;
+pub mod imports;
+pub mod jobs;
+pub mod log_viewer;
+pub mod metrics;
+pub mod profile_picker;
+pub mod purge;
 mod query;
 pub mod schema_popup;
+pub mod seed;
+pub mod table_details;
 mod table_picker;
+pub mod text_editor;
 pub mod theme;
+pub mod transaction;
+pub mod undo;
 
 pub use query::QueryWidget;
 pub use table_picker::TablePickerWidget;
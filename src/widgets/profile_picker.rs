@@ -0,0 +1,450 @@
+use std::{borrow::Cow, cell::RefCell, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    prelude::StatefulWidget,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, HighlightSpacing, Paragraph, Row, Table, TableState},
+};
+
+use dynamate::core::connect::{ProfileInfo, ProfileSelection};
+use dynamate::core::datastore::Datastore;
+use dynamate::dynamodb::profiles::COMMON_REGIONS;
+
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    util::pad,
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Broadcast after the datastore has been reconnected with a different
+/// profile/region. Every open widget shares the same `Arc<dyn Datastore>`, so
+/// the new client is already in place; widgets react by reloading whatever they
+/// fetched through the old one.
+pub struct ProfileSwitchedEvent {
+    pub selection: ProfileSelection,
+}
+
+/// Label for the pseudo-profile that uses credentials from the environment.
+const ENV_PROFILE_LABEL: &str = "(environment)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Profiles,
+    Regions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+    Idle,
+    Switching,
+    Error(String),
+}
+
+struct PickerState {
+    focus: Focus,
+    profiles: TableState,
+    regions: TableState,
+    status: Status,
+}
+
+struct SwitchEvent {
+    selection: ProfileSelection,
+    result: Result<(), String>,
+}
+
+/// Pick an AWS profile and region, then reconnect the datastore with them.
+pub struct ProfilePickerWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    /// `None` is the environment pseudo-profile, always listed first.
+    profiles: Vec<Option<ProfileInfo>>,
+    regions: Vec<String>,
+    state: RefCell<PickerState>,
+}
+
+impl ProfilePickerWidget {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓/j/k"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⇥"),
+            short: Cow::Borrowed("profile/region"),
+            long: Cow::Borrowed("Switch between profile and region lists"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("connect"),
+            long: Cow::Borrowed("Connect with the selected profile and region"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close"),
+            long: Cow::Borrowed("Close picker"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    pub fn new(
+        db: Arc<dyn Datastore>,
+        profiles: Vec<ProfileInfo>,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let active = db.active_profile().unwrap_or_default();
+        let mut entries: Vec<Option<ProfileInfo>> = vec![None];
+        entries.extend(profiles.into_iter().map(Some));
+        let regions = region_choices(&entries, active.region.as_deref());
+
+        let profile_index = entries
+            .iter()
+            .position(|entry| entry.as_ref().map(|p| &p.name) == active.profile.as_ref())
+            .unwrap_or(0);
+        let region_index = active
+            .region
+            .as_deref()
+            .and_then(|region| regions.iter().position(|r| r == region))
+            .unwrap_or(0);
+
+        let mut profile_state = TableState::default();
+        profile_state.select(Some(profile_index));
+        let mut region_state = TableState::default();
+        region_state.select(Some(region_index));
+
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            profiles: entries,
+            regions,
+            state: RefCell::new(PickerState {
+                focus: Focus::Profiles,
+                profiles: profile_state,
+                regions: region_state,
+                status: Status::Idle,
+            }),
+        }
+    }
+
+    fn selected_profile(&self) -> Option<&ProfileInfo> {
+        let state = self.state.borrow();
+        state
+            .profiles
+            .selected()
+            .and_then(|idx| self.profiles.get(idx))
+            .and_then(Option::as_ref)
+    }
+
+    fn selected_region(&self) -> Option<String> {
+        let state = self.state.borrow();
+        state
+            .regions
+            .selected()
+            .and_then(|idx| self.regions.get(idx))
+            .cloned()
+    }
+
+    /// Moving through profiles preselects the profile's configured region so
+    /// Enter on a profile "just works".
+    fn sync_region_to_profile(&self) {
+        let Some(region) = self.selected_profile().and_then(|p| p.region.clone()) else {
+            return;
+        };
+        if let Some(index) = self.regions.iter().position(|r| *r == region) {
+            self.state.borrow_mut().regions.select(Some(index));
+        }
+    }
+
+    fn move_selection(&self, down: bool) {
+        let focus = {
+            let mut state = self.state.borrow_mut();
+            let (table_state, len) = match state.focus {
+                Focus::Profiles => (&mut state.profiles, self.profiles.len()),
+                Focus::Regions => (&mut state.regions, self.regions.len()),
+            };
+            let current = table_state.selected().unwrap_or(0);
+            let next = if down {
+                (current + 1).min(len.saturating_sub(1))
+            } else {
+                current.saturating_sub(1)
+            };
+            table_state.select(Some(next));
+            state.focus
+        };
+        if focus == Focus::Profiles {
+            self.sync_region_to_profile();
+        }
+    }
+
+    fn switch(&self, ctx: crate::env::WidgetCtx) {
+        let selection = ProfileSelection {
+            profile: self.selected_profile().map(|p| p.name.clone()),
+            region: self.selected_region(),
+            // A role was assumed with the old profile's credentials.
+            role: None,
+        };
+        self.state.borrow_mut().status = Status::Switching;
+        ctx.invalidate();
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let result = db
+                .switch_profile(&selection)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(SwitchEvent { selection, result });
+        });
+    }
+
+    fn render_list(
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        title: &str,
+        rows: Vec<Row<'static>>,
+        state: &mut TableState,
+        focused: bool,
+    ) {
+        let border = if focused {
+            theme.accent()
+        } else {
+            theme.border()
+        };
+        let block = Block::bordered()
+            .title(Line::styled(
+                pad(title, 1),
+                Style::default().fg(theme.text_muted()),
+            ))
+            .border_style(Style::default().fg(border));
+        let highlight = if focused {
+            Style::default()
+                .bg(theme.selection_bg())
+                .fg(theme.selection_fg())
+        } else {
+            Style::default().add_modifier(Modifier::BOLD)
+        };
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(16)])
+            .block(block)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol(">")
+            .row_highlight_style(highlight);
+        StatefulWidget::render(table, area, frame.buffer_mut(), state);
+    }
+}
+
+impl crate::widgets::Widget for ProfilePickerWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let active = self.db.active_profile().unwrap_or_default();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::styled(
+                "AWS profile",
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Line::styled(
+                pad(format!("current: {}", active.label()), 1),
+                Style::default().fg(theme.text_muted()),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        let body = block.inner(area);
+        frame.render_widget(block, area);
+
+        let layout = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]);
+        let [lists_area, status_area] = body.inner(Margin::new(1, 0)).layout(&layout);
+        let columns = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]);
+        let [profiles_area, regions_area] = lists_area.layout(&columns);
+
+        let profile_rows: Vec<Row<'static>> = self
+            .profiles
+            .iter()
+            .map(|entry| match entry {
+                Some(profile) => Row::new(vec![
+                    Line::from(profile.name.clone()),
+                    Line::styled(
+                        profile.region.clone().unwrap_or_default(),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                ]),
+                None => Row::new(vec![
+                    Line::styled(ENV_PROFILE_LABEL, Style::default().fg(theme.text_muted())),
+                    Line::from(""),
+                ]),
+            })
+            .collect();
+        let region_rows: Vec<Row<'static>> = self
+            .regions
+            .iter()
+            .map(|region| Row::new(vec![Line::from(region.clone())]))
+            .collect();
+
+        let mut state = self.state.borrow_mut();
+        let focus = state.focus;
+        Self::render_list(
+            frame,
+            profiles_area,
+            theme,
+            "Profiles",
+            profile_rows,
+            &mut state.profiles,
+            focus == Focus::Profiles,
+        );
+        Self::render_list(
+            frame,
+            regions_area,
+            theme,
+            "Regions",
+            region_rows,
+            &mut state.regions,
+            focus == Focus::Regions,
+        );
+
+        let status = match &state.status {
+            Status::Idle => None,
+            Status::Switching => Some(Line::styled(
+                "Connecting...",
+                Style::default().fg(theme.warning()),
+            )),
+            Status::Error(message) => Some(Line::styled(
+                message.clone(),
+                Style::default().fg(theme.error()),
+            )),
+        };
+        if let Some(line) = status {
+            frame.render_widget(Paragraph::new(line), status_area);
+        }
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        if self.state.borrow().status == Status::Switching {
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                ctx.dismiss_popup();
+                ctx.invalidate();
+            }
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Left | KeyCode::Right => {
+                let mut state = self.state.borrow_mut();
+                state.focus = match state.focus {
+                    Focus::Profiles => Focus::Regions,
+                    Focus::Regions => Focus::Profiles,
+                };
+                ctx.invalidate();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.move_selection(false);
+                ctx.invalidate();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.move_selection(true);
+                ctx.invalidate();
+            }
+            KeyCode::Enter => self.switch(ctx),
+            _ => {}
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        let Some(switched) = event.payload::<SwitchEvent>() else {
+            return;
+        };
+        match &switched.result {
+            Ok(()) => {
+                let selection = self
+                    .db
+                    .active_profile()
+                    .unwrap_or_else(|| switched.selection.clone());
+                announce_switch(&ctx, selection);
+                ctx.dismiss_popup();
+            }
+            Err(err) => {
+                self.state.borrow_mut().status = Status::Error(err.clone());
+            }
+        }
+        ctx.invalidate();
+    }
+}
+
+impl Popup for ProfilePickerWidget {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = (area.width as f32 * 0.6) as u16;
+        let height = (area.height as f32 * 0.6) as u16;
+        let width = width.max(56).min(area.width.saturating_sub(4));
+        let height = height.max(12).min(area.height.saturating_sub(4));
+        let x = area.x + (area.width - width) / 2;
+        let y = area.y + (area.height - height) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Tell the user and every open widget that the datastore was reconnected as
+/// `selection`.
+pub(super) fn announce_switch(ctx: &crate::env::WidgetCtx, selection: ProfileSelection) {
+    crate::environment::switch_profile(selection.profile.as_deref());
+    ctx.show_toast(Toast {
+        message: format!("Connected to {}", selection.label()),
+        kind: ToastKind::Info,
+        duration: Duration::from_secs(3),
+        action: None,
+    });
+    // Staged writes and deleted items belong to the old account.
+    super::transaction::clear();
+    super::undo::clear();
+    ctx.broadcast_event(ProfileSwitchedEvent { selection });
+}
+
+/// The regions offered in the picker: the current region and every region a
+/// profile is configured for, followed by the common regions, without repeats.
+fn region_choices(profiles: &[Option<ProfileInfo>], current: Option<&str>) -> Vec<String> {
+    let mut regions: Vec<String> = Vec::new();
+    let configured = profiles
+        .iter()
+        .flatten()
+        .filter_map(|p| p.region.as_deref());
+    for region in current
+        .into_iter()
+        .chain(configured)
+        .chain(COMMON_REGIONS.iter().copied())
+    {
+        if !regions.iter().any(|r| r == region) {
+            regions.push(region.to_string());
+        }
+    }
+    regions
+}
@@ -0,0 +1,188 @@
+use std::{borrow::Cow, time::Duration};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph, Wrap},
+};
+
+use dynamate::core::purge::PurgeSummary;
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Reports how a purge ended: what it deleted (or in a dry run would have),
+/// how long it took, and the errors of any segments that failed.
+pub struct PurgeSummaryPopup {
+    inner: WidgetInner,
+    title: String,
+    table_name: String,
+    summary: PurgeSummary,
+}
+
+impl PurgeSummaryPopup {
+    const HELP: &'static [help::Entry<'static>] = &[help::Entry {
+        keys: Cow::Borrowed("esc/⏎"),
+        short: Cow::Borrowed("close"),
+        long: Cow::Borrowed("Close"),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }];
+
+    pub fn new(
+        title: impl Into<String>,
+        table_name: impl Into<String>,
+        summary: PurgeSummary,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            title: title.into(),
+            table_name: table_name.into(),
+            summary,
+        }
+    }
+
+    fn rows(&self) -> Vec<(&'static str, String)> {
+        let progress = self.summary.progress;
+        let status = if !self.summary.errors.is_empty() {
+            "failed"
+        } else if self.summary.cancelled {
+            "canceled"
+        } else {
+            "finished"
+        };
+        vec![
+            ("Table", self.table_name.clone()),
+            (
+                if self.summary.dry_run {
+                    "Matched"
+                } else {
+                    "Deleted"
+                },
+                format!("{} items", progress.deleted),
+            ),
+            ("Scanned", format!("{} items", progress.scanned)),
+            ("Took", format_elapsed(self.summary.elapsed)),
+            ("Status", status.to_string()),
+        ]
+    }
+}
+
+/// Whole seconds, with minutes once there are any.
+pub(super) fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    }
+}
+
+impl crate::widgets::Widget for PurgeSummaryPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let failed = !self.summary.errors.is_empty();
+        let accent = if failed {
+            theme.error()
+        } else {
+            theme.accent()
+        };
+        let title = Line::styled(
+            pad(self.title.as_str(), 1),
+            Style::default().fg(accent).add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let mut lines: Vec<Line> = self
+            .rows()
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{label:<9}"),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                    Span::styled(value, Style::default().fg(theme.text())),
+                ])
+            })
+            .collect();
+        if failed {
+            lines.push(Line::default());
+            lines.extend(
+                self.summary
+                    .errors
+                    .iter()
+                    .map(|err| Line::styled(err.clone(), Style::default().fg(theme.error()))),
+            );
+        }
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+            ctx.dismiss_popup();
+        }
+        true
+    }
+}
+
+impl Popup for PurgeSummaryPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 60.min(area.width.saturating_sub(4));
+        // Five rows, a blank line and the errors when there are any, and the
+        // borders.
+        let errors = if self.summary.errors.is_empty() {
+            0
+        } else {
+            self.summary.errors.len() as u16 + 1
+        };
+        let height = (7 + errors).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::format_elapsed;
+
+    #[test]
+    fn elapsed_shows_minutes_once_there_are_any() {
+        assert_eq!(format_elapsed(Duration::from_millis(4_900)), "4s");
+        assert_eq!(format_elapsed(Duration::from_secs(125)), "2m 5s");
+    }
+}
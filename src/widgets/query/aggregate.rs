@@ -0,0 +1,408 @@
+use std::{borrow::Cow, cell::Cell, collections::HashMap};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use super::projection;
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Count, sum, min, max and average of the numeric values of one attribute,
+/// and how often each of its values occurs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct ColumnStats {
+    /// Items looked at, with or without the attribute.
+    pub(super) items: usize,
+    /// Items that have the attribute.
+    pub(super) present: usize,
+    /// Items per distinct value, keyed by the value as the table shows it.
+    pub(super) values: HashMap<String, usize>,
+    /// Items whose attribute is a number.
+    pub(super) numeric: usize,
+    pub(super) sum: f64,
+    pub(super) min: Option<f64>,
+    pub(super) max: Option<f64>,
+}
+
+impl ColumnStats {
+    pub(super) fn add_items<'a>(
+        &mut self,
+        column: &str,
+        items: impl IntoIterator<Item = &'a HashMap<String, AttributeValue>>,
+    ) {
+        for item in items {
+            self.items += 1;
+            let Some(value) = projection::attribute_at(item, column) else {
+                continue;
+            };
+            self.present += 1;
+            *self.values.entry(value_label(value)).or_default() += 1;
+            let AttributeValue::N(number) = value else {
+                continue;
+            };
+            let Ok(value) = number.parse::<f64>() else {
+                continue;
+            };
+            self.numeric += 1;
+            self.sum += value;
+            self.min = Some(self.min.map_or(value, |min| min.min(value)));
+            self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        }
+    }
+
+    pub(super) fn avg(&self) -> Option<f64> {
+        (self.numeric > 0).then(|| self.sum / self.numeric as f64)
+    }
+
+    /// The distinct values, most frequent first; ties in value order.
+    pub(super) fn histogram(&self) -> Vec<(&str, usize)> {
+        let mut values: Vec<(&str, usize)> = self
+            .values
+            .iter()
+            .map(|(value, count)| (value.as_str(), *count))
+            .collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        values
+    }
+}
+
+/// A value as one histogram label: scalars as text, other types by their
+/// shape.
+fn value_label(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::S(text) | AttributeValue::N(text) => text.clone(),
+        AttributeValue::Bool(flag) => flag.to_string(),
+        AttributeValue::Null(_) => "null".to_string(),
+        AttributeValue::B(bytes) => format!("<binary:{}>", bytes.as_ref().len()),
+        AttributeValue::Ss(values) | AttributeValue::Ns(values) => {
+            format!("[{}]", values.join(", "))
+        }
+        AttributeValue::L(values) => format!("<list:{}>", values.len()),
+        AttributeValue::M(map) => format!("<map:{}>", map.len()),
+        _ => "<other>".to_string(),
+    }
+}
+
+/// A bar of up to `width` cells for `count` out of `max`.
+fn bar(count: usize, max: usize, width: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    let cells = (count * width).div_ceil(max).min(width);
+    "█".repeat(cells)
+}
+
+/// Integers print without a fraction; everything else with up to four
+/// decimals.
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{value:.0}");
+    }
+    let text = format!("{value:.4}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Rows the popup shows of the value histogram before it scrolls.
+const HISTOGRAM_ROWS: usize = 10;
+
+/// Shows the [`ColumnStats`] of a column: the numeric stats when it has
+/// numbers, and a histogram of its values. When the results have more pages,
+/// `on_fetch_all` recomputes them over every matching item.
+pub(super) struct AggregatePopup {
+    inner: WidgetInner,
+    column: String,
+    stats: ColumnStats,
+    partial: bool,
+    /// First histogram row shown.
+    scroll: Cell<usize>,
+    on_fetch_all: Box<dyn Fn() + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+impl AggregatePopup {
+    /// `partial` is true when `stats` only cover the pages loaded so far.
+    pub(super) fn new(
+        column: impl Into<String>,
+        stats: ColumnStats,
+        partial: bool,
+        on_fetch_all: impl Fn() + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("j/k"),
+                short: Cow::Borrowed("scroll"),
+                long: Cow::Borrowed("Scroll the values"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("close"),
+                long: Cow::Borrowed("Close"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        if partial {
+            help_entries.insert(
+                0,
+                help::Entry {
+                    keys: Cow::Borrowed("a"),
+                    short: Cow::Borrowed("all items"),
+                    long: Cow::Borrowed("Fetch every page and recompute"),
+                    ctrl: None,
+                    shift: None,
+                    alt: None,
+                },
+            );
+        }
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            column: column.into(),
+            stats,
+            partial,
+            scroll: Cell::new(0),
+            on_fetch_all: Box::new(on_fetch_all),
+            help_entries,
+        }
+    }
+
+    /// The numeric stats; just the value count when none of the values are
+    /// numbers.
+    fn rows(&self) -> Vec<(&'static str, String)> {
+        let stats = &self.stats;
+        if stats.numeric == 0 {
+            return vec![(
+                "count",
+                format!("{} of {} items", stats.present, stats.items),
+            )];
+        }
+        let or_dash = |value: Option<f64>| value.map_or_else(|| "–".to_string(), format_number);
+        vec![
+            (
+                "count",
+                format!("{} of {} items", stats.numeric, stats.items),
+            ),
+            ("sum", format_number(stats.sum)),
+            ("min", or_dash(stats.min)),
+            ("max", or_dash(stats.max)),
+            ("avg", or_dash(stats.avg())),
+        ]
+    }
+}
+
+impl AggregatePopup {
+    fn render_histogram(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let histogram = self.stats.histogram();
+        let max = histogram.first().map_or(0, |(_, count)| *count);
+        let count_width = max.to_string().len();
+        let label_width = (usize::from(area.width) / 2).max(1);
+        let bar_width = usize::from(area.width).saturating_sub(label_width + count_width + 2);
+        let lines: Vec<Line> = histogram
+            .iter()
+            .skip(self.scroll.get())
+            .take(usize::from(area.height))
+            .map(|(value, count)| {
+                let label: String = value.chars().take(label_width).collect();
+                Line::from(vec![
+                    Span::styled(
+                        format!("{label:<label_width$} "),
+                        Style::default().fg(theme.text()),
+                    ),
+                    Span::styled(
+                        format!("{count:>count_width$} "),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                    Span::styled(
+                        bar(*count, max, bar_width),
+                        Style::default().fg(theme.accent()),
+                    ),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+impl crate::widgets::Widget for AggregatePopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad(self.column.as_str(), 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let rows = self.rows();
+        let layout = Layout::vertical([
+            Constraint::Length(rows.len() as u16 + 1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ]);
+        let [body_area, histogram_area, footer_area] = inner.layout(&layout);
+
+        let mut lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{label:<6}"),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                    Span::styled(value, Style::default().fg(theme.text())),
+                ])
+            })
+            .collect();
+        lines.push(Line::styled(
+            format!("{} distinct values", self.stats.values.len()),
+            Style::default().fg(theme.text_muted()),
+        ));
+        frame.render_widget(Paragraph::new(lines), body_area);
+        self.render_histogram(frame, histogram_area, theme);
+
+        let footer = if self.partial {
+            "Loaded pages only · a: all items"
+        } else {
+            "All matching items"
+        };
+        frame.render_widget(
+            Paragraph::new(footer).style(Style::default().fg(theme.text_muted())),
+            footer_area,
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        let visible = HISTOGRAM_ROWS.min(self.stats.values.len());
+        let max_scroll = self.stats.values.len().saturating_sub(visible);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.scroll.set((self.scroll.get() + 1).min(max_scroll));
+                ctx.invalidate();
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.scroll.set(self.scroll.get().saturating_sub(1));
+                ctx.invalidate();
+            }
+            KeyCode::Char('a') if self.partial => {
+                ctx.dismiss_popup();
+                (self.on_fetch_all)();
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => ctx.dismiss_popup(),
+            _ => {}
+        }
+        true
+    }
+}
+
+impl Popup for AggregatePopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 56.min(area.width.saturating_sub(4));
+        // The stat rows, the distinct count, the histogram, the footer, and
+        // the borders.
+        let histogram = HISTOGRAM_ROWS.min(self.stats.values.len());
+        let height = (self.rows().len() + histogram + 4) as u16;
+        let height = height.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::{ColumnStats, bar, format_number};
+
+    fn item(value: AttributeValue) -> HashMap<String, AttributeValue> {
+        HashMap::from([("amount".to_string(), value)])
+    }
+
+    #[test]
+    fn stats_skip_missing_and_non_numeric_values() {
+        let items = [
+            item(AttributeValue::N("10".to_string())),
+            item(AttributeValue::N("2.5".to_string())),
+            item(AttributeValue::S("12".to_string())),
+            HashMap::new(),
+        ];
+        let mut stats = ColumnStats::default();
+        stats.add_items("amount", &items);
+        assert_eq!(stats.items, 4);
+        assert_eq!(stats.present, 3);
+        assert_eq!(stats.numeric, 2);
+        assert_eq!(format_number(stats.sum), "12.5");
+        assert_eq!(stats.min.map(format_number).as_deref(), Some("2.5"));
+        assert_eq!(stats.max.map(format_number).as_deref(), Some("10"));
+        assert_eq!(stats.avg().map(format_number).as_deref(), Some("6.25"));
+        assert_eq!(ColumnStats::default().avg(), None);
+    }
+
+    #[test]
+    fn histogram_orders_values_by_count() {
+        let status = |value: &str| {
+            HashMap::from([("status".to_string(), AttributeValue::S(value.to_string()))])
+        };
+        let items = [
+            status("open"),
+            status("closed"),
+            status("open"),
+            status("archived"),
+            HashMap::from([("status".to_string(), AttributeValue::Bool(true))]),
+        ];
+        let mut stats = ColumnStats::default();
+        stats.add_items("status", &items);
+        assert_eq!(
+            stats.histogram(),
+            vec![("open", 2), ("archived", 1), ("closed", 1), ("true", 1)]
+        );
+        assert_eq!(bar(2, 2, 10), "██████████");
+        assert_eq!(bar(1, 3, 10), "████");
+    }
+
+    #[test]
+    fn numbers_drop_trailing_zeros() {
+        assert_eq!(format_number(42.0), "42");
+        assert_eq!(format_number(6.25), "6.25");
+        assert_eq!(format_number(1.0 / 3.0), "0.3333");
+    }
+}
@@ -0,0 +1,301 @@
+//! Updating every item the active query matches (`U`).
+//!
+//! The update is asked for, a dry run shows the first matches before and
+//! after it, and once applied the matches are updated in the background with
+//! per-item errors collected in a summary. Each step comes back as one of the
+//! events here; [`handle`] takes it from there and tells the query widget what
+//! it has to do about it.
+
+use std::{sync::Arc, time::Duration};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use dynamate::core::bulk_update::{UpdateProgress, UpdateSummary, preview_update, update_items};
+use dynamate::core::datastore::Datastore;
+use dynamate::core::json::item_to_json;
+use dynamate::core::query::{ItemUpdate, QueryPlan};
+use dynamate::core::value::Item;
+use dynamate::dynamodb::requests::RequestManager;
+
+use crate::{
+    env::{AppEvent, Toast, ToastKind, WidgetCtx, WidgetId},
+    jobs,
+    safety::Action,
+    widgets::confirm::{ConfirmAction, ConfirmPopup},
+};
+
+use super::{
+    diff_popup::DiffPopup,
+    update_matching::{UpdateMatchingPopup, UpdateSummaryPopup},
+};
+
+/// Matches shown in the dry-run preview of a bulk update.
+const UPDATE_PREVIEW_ITEMS: usize = 5;
+/// Bulk-update writes in flight at once.
+const UPDATE_CONCURRENCY: usize = 8;
+
+struct UpdatePreviewRequest {
+    update: ItemUpdate,
+}
+
+struct UpdatePreviewEvent {
+    update: ItemUpdate,
+    result: Result<Vec<(Item, Item)>, String>,
+}
+
+struct UpdateMatchingRequest {
+    update: ItemUpdate,
+    /// Passed the safety policy's confirmation, on top of the preview.
+    confirmed: bool,
+}
+
+struct UpdateMatchingEvent {
+    run_id: u64,
+    result: Result<UpdateSummary, String>,
+}
+
+/// What the update needs from the query it applies to.
+pub(super) struct UpdateSource<'a> {
+    pub(super) db: Arc<dyn Datastore>,
+    pub(super) table_name: String,
+    /// The active query, with its parameters and options.
+    pub(super) plan: QueryPlan,
+    /// The widget's bulk runs, so only one bulk change goes at a time.
+    pub(super) bulk: &'a RequestManager,
+    pub(super) parent: WidgetId,
+}
+
+/// Where an update got to, for the query widget to act on.
+pub(super) enum UpdateStep {
+    /// The next step is under way; nothing to do.
+    Continuing,
+    /// The update stopped; nothing more was written.
+    Failed(String),
+    /// The update finished, having changed this many items.
+    Updated(u64),
+}
+
+/// Ask for the update to apply to every item `query` matches.
+pub(super) fn prompt(db: Arc<dyn Datastore>, query: String, parent: WidgetId, ctx: &WidgetCtx) {
+    let ctx_for_preview = ctx.clone();
+    ctx.set_popup(Box::new(UpdateMatchingPopup::new(
+        db,
+        query,
+        move |update| {
+            ctx_for_preview.emit_self(UpdatePreviewRequest { update });
+        },
+        parent,
+    )));
+}
+
+/// Take the update one step further if `event` is one of its own. `source`
+/// is only built then.
+pub(super) fn handle<'a>(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    source: impl FnOnce() -> UpdateSource<'a>,
+) -> Option<UpdateStep> {
+    if let Some(request) = event.payload::<UpdatePreviewRequest>() {
+        preview(source(), request.update.clone(), ctx);
+        return Some(UpdateStep::Continuing);
+    }
+    if let Some(preview) = event.payload::<UpdatePreviewEvent>() {
+        return Some(match &preview.result {
+            Ok(items) => match show_preview(&source(), &preview.update, items, ctx) {
+                Ok(()) => UpdateStep::Continuing,
+                Err(err) => UpdateStep::Failed(err),
+            },
+            Err(err) => UpdateStep::Failed(err.clone()),
+        });
+    }
+    if let Some(request) = event.payload::<UpdateMatchingRequest>() {
+        if request.confirmed {
+            update(source(), request.update.clone(), ctx);
+        } else {
+            confirm(&source(), &request.update, ctx);
+        }
+        return Some(UpdateStep::Continuing);
+    }
+    if let Some(finished) = event.payload::<UpdateMatchingEvent>() {
+        let source = source();
+        source.bulk.finish(finished.run_id);
+        return Some(match &finished.result {
+            Ok(summary) => {
+                show_summary(&source, summary.clone(), ctx);
+                UpdateStep::Updated(summary.progress.updated)
+            }
+            Err(err) => UpdateStep::Failed(err.clone()),
+        });
+    }
+    None
+}
+
+/// The dry run: fetch the first matches and show them before and after.
+fn preview(source: UpdateSource<'_>, update: ItemUpdate, ctx: &WidgetCtx) {
+    let UpdateSource {
+        db,
+        table_name,
+        plan,
+        ..
+    } = source;
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let result = preview_update(
+            db.as_ref(),
+            &table_name,
+            &plan,
+            &update,
+            UPDATE_PREVIEW_ITEMS,
+        )
+        .await
+        .map_err(|err| err.to_string());
+        ctx.emit_self(UpdatePreviewEvent { update, result });
+    });
+}
+
+fn show_preview(
+    source: &UpdateSource<'_>,
+    update: &ItemUpdate,
+    preview: &[(Item, Item)],
+    ctx: &WidgetCtx,
+) -> Result<(), String> {
+    if preview.is_empty() {
+        ctx.show_toast(Toast {
+            message: "No items match".to_string(),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(3),
+            action: None,
+        });
+        return Ok(());
+    }
+    let as_json = |items: Vec<&Item>| {
+        items
+            .into_iter()
+            .map(item_to_json)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|items| serde_json::Value::Array(items).to_string())
+            .map_err(|err| err.to_string())
+    };
+    let before = as_json(preview.iter().map(|(before, _)| before).collect())?;
+    let after = as_json(preview.iter().map(|(_, after)| after).collect())?;
+    let title = format!("Preview: the first {} matches", preview.len());
+    let ctx_for_apply = ctx.clone();
+    let update = update.clone();
+    let popup = DiffPopup::new(
+        title,
+        &before,
+        &after,
+        move || {
+            ctx_for_apply.emit_self(UpdateMatchingRequest {
+                update: update.clone(),
+                confirmed: false,
+            });
+        },
+        source.parent.clone(),
+    )
+    .with_action("apply", "Apply to every matching item");
+    ctx.set_popup(Box::new(popup));
+    Ok(())
+}
+
+/// The preview was the yes-or-no step; a stricter policy still has the
+/// table name typed before every match is updated.
+fn confirm(source: &UpdateSource<'_>, update: &ItemUpdate, ctx: &WidgetCtx) {
+    let ctx_for_update = ctx.clone();
+    let update = update.clone();
+    ConfirmPopup::new_with_action(
+        "Update matching items",
+        format!(
+            "Table={}\n\nApply the update to every matching item?",
+            source.table_name
+        ),
+        "Update",
+        "cancel",
+        ConfirmAction::new(
+            KeyCode::Char('u'),
+            KeyModifiers::CONTROL,
+            "^u",
+            "update",
+            "Update every matching item",
+        ),
+        move || {
+            ctx_for_update.emit_self(UpdateMatchingRequest {
+                update: update.clone(),
+                confirmed: true,
+            });
+        },
+        source.parent.clone(),
+    )
+    .show_reviewed(ctx, Action::BulkUpdate, &source.table_name);
+}
+
+fn update(source: UpdateSource<'_>, update: ItemUpdate, ctx: &WidgetCtx) {
+    let run = source.bulk.start();
+    let job = jobs::track(
+        format!("Update matches in {}", source.table_name),
+        run.shared_flag(),
+    );
+    job.progress(progress_message(UpdateProgress::default()));
+    let UpdateSource {
+        db,
+        table_name,
+        plan,
+        ..
+    } = source;
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let on_progress = run.progress(|progress: UpdateProgress| {
+            job.progress(progress_message(progress));
+        });
+        let result = update_items(
+            db.as_ref(),
+            &table_name,
+            &plan,
+            &update,
+            UPDATE_CONCURRENCY,
+            run.flag(),
+            &on_progress,
+        )
+        .await
+        .map_err(|err| err.to_string());
+        job.finish_quietly(
+            result
+                .as_ref()
+                .map(|summary| format!("Updated {} items", summary.progress.updated))
+                .map_err(Clone::clone),
+        );
+        ctx.emit_self(UpdateMatchingEvent {
+            run_id: run.id(),
+            result,
+        });
+    });
+}
+
+fn show_summary(source: &UpdateSource<'_>, summary: UpdateSummary, ctx: &WidgetCtx) {
+    ctx.show_toast(Toast {
+        message: format!("Updated {} items", summary.progress.updated),
+        kind: ToastKind::Info,
+        duration: Duration::from_secs(3),
+        action: None,
+    });
+    ctx.set_popup(Box::new(UpdateSummaryPopup::new(
+        source.table_name.clone(),
+        summary,
+        source.parent.clone(),
+    )));
+}
+
+fn progress_message(progress: UpdateProgress) -> String {
+    let done = progress.updated + progress.failed;
+    if done == 0 {
+        format!(
+            "Finding matches... {} of {} scanned items match (esc cancels)",
+            progress.matched, progress.scanned
+        )
+    } else {
+        format!(
+            "Updating matches... {} of {} done, {} failed (esc cancels)",
+            done, progress.matched, progress.failed
+        )
+    }
+}
@@ -0,0 +1,154 @@
+//! Copying from the table view to the clipboard: the selected item (`y`),
+//! its value in the first visible column (`Y`), the query (`^y`), and the
+//! table's ARN (`M-y`).
+//!
+//! Every copy is confirmed with a toast. The ARN is looked up in the
+//! background first and comes back as an event; [`handle`] takes it from
+//! there and tells the query widget whether it failed.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+
+use dynamate::core::datastore::Datastore;
+use dynamate::dynamodb::json;
+
+use crate::env::{AppEvent, Toast, ToastKind, WidgetCtx};
+
+/// The table's ARN, looked up for copying to the clipboard.
+struct TableArnEvent {
+    result: Result<Option<String>, String>,
+}
+
+/// Copy `text` to the clipboard and confirm with a toast naming `what`.
+pub(super) fn copy(ctx: &WidgetCtx, what: &str, text: &str) {
+    let toast = match crate::clipboard::copy(text) {
+        Ok(()) => Toast {
+            message: format!("Copied {what} to clipboard"),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(2),
+            action: None,
+        },
+        Err(err) => Toast {
+            message: format!("Failed to copy {what}: {err}"),
+            kind: ToastKind::Error,
+            duration: Duration::from_secs(3),
+            action: None,
+        },
+    };
+    ctx.show_toast(toast);
+}
+
+/// Copy a whole item. Items with types plain JSON can't express (sets,
+/// binary) are copied as DynamoDB JSON, as in the editor.
+pub(super) fn copy_item(
+    ctx: &WidgetCtx,
+    item: &HashMap<String, AttributeValue>,
+) -> Result<(), String> {
+    let text = json::to_json_string(item)
+        .or_else(|_| json::to_dynamodb_json_string(item))
+        .map_err(|err| err.to_string())?;
+    copy(ctx, "item", &text);
+    Ok(())
+}
+
+/// Copy one attribute of an item, named after its column.
+pub(super) fn copy_cell(
+    ctx: &WidgetCtx,
+    column: &str,
+    value: &AttributeValue,
+) -> Result<(), String> {
+    let text = cell_text(column, value)?;
+    copy(ctx, column, &text);
+    Ok(())
+}
+
+pub(super) fn copy_query(ctx: &WidgetCtx, query: &str) {
+    let query = query.trim();
+    if query.is_empty() {
+        ctx.show_toast(Toast {
+            message: "No query to copy".to_string(),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(2),
+            action: None,
+        });
+        return;
+    }
+    copy(ctx, "query", query);
+}
+
+/// Look up the table's ARN in the background; [`handle`] copies it.
+pub(super) fn copy_table_arn(db: Arc<dyn Datastore>, table_name: String, ctx: &WidgetCtx) {
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let result = db
+            .describe_details(&table_name)
+            .await
+            .map(|details| details.resource_id)
+            .map_err(|err| err.to_string());
+        ctx.emit_self(TableArnEvent { result });
+    });
+}
+
+/// Copy the ARN if `event` is the lookup's reply; `Some(Err)` when the
+/// lookup failed.
+pub(super) fn handle(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    table_name: &str,
+) -> Option<Result<(), String>> {
+    let arn_event = event.payload::<TableArnEvent>()?;
+    Some(match &arn_event.result {
+        Ok(Some(arn)) => {
+            copy(ctx, "table ARN", arn);
+            Ok(())
+        }
+        Ok(None) => {
+            ctx.show_toast(Toast {
+                message: format!("{table_name} has no ARN"),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(2),
+                action: None,
+            });
+            Ok(())
+        }
+        Err(err) => Err(err.clone()),
+    })
+}
+
+/// The clipboard text for one attribute: scalars as their bare value, anything
+/// else as JSON (DynamoDB JSON when plain JSON can't express it).
+fn cell_text(name: &str, value: &AttributeValue) -> Result<String, String> {
+    match value {
+        AttributeValue::S(text) | AttributeValue::N(text) => return Ok(text.clone()),
+        AttributeValue::Bool(flag) => return Ok(flag.to_string()),
+        AttributeValue::Null(_) => return Ok("null".to_string()),
+        _ => {}
+    }
+    let item = HashMap::from([(name.to_string(), value.clone())]);
+    let json = json::to_json(&item)
+        .or_else(|_| json::to_dynamodb_json(&item))
+        .map_err(|err| err.to_string())?;
+    serde_json::to_string_pretty(&json[name]).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::cell_text;
+
+    #[test]
+    fn cell_text_copies_scalars_bare_and_others_as_json() {
+        assert_eq!(
+            cell_text("name", &AttributeValue::S("Ada".to_string())),
+            Ok("Ada".to_string())
+        );
+        assert_eq!(
+            cell_text("age", &AttributeValue::N("36".to_string())),
+            Ok("36".to_string())
+        );
+        let tags = AttributeValue::L(vec![AttributeValue::S("a".to_string())]);
+        assert_eq!(cell_text("tags", &tags), Ok("[\n  \"a\"\n]".to_string()));
+    }
+}
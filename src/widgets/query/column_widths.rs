@@ -0,0 +1,95 @@
+//! Column widths set by hand in the results table: `+`/`-` widen or narrow
+//! the first visible column and `=` gives it back its natural width. Widths
+//! are kept per table for the session, so a table reopened later is laid out
+//! the way it was left.
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+/// How many cells one `+` or `-` adds or takes away.
+const STEP: usize = 4;
+const MIN_WIDTH: usize = 3;
+/// Widest a column can be made; wider than the usual cap, so a long value
+/// can be read whole.
+pub(super) const MAX_WIDTH: usize = 160;
+
+static TABLES: LazyLock<Mutex<HashMap<String, HashMap<String, usize>>>> =
+    LazyLock::new(Mutex::default);
+
+#[derive(Debug, Default)]
+pub(super) struct ColumnWidths {
+    table: String,
+    widths: HashMap<String, usize>,
+}
+
+impl ColumnWidths {
+    /// The widths last set for `table`'s columns.
+    pub(super) fn for_table(table: &str) -> Self {
+        let widths = TABLES
+            .lock()
+            .ok()
+            .and_then(|tables| tables.get(table).cloned())
+            .unwrap_or_default();
+        Self {
+            table: table.to_string(),
+            widths,
+        }
+    }
+
+    pub(super) fn get(&self, column: &str) -> Option<usize> {
+        self.widths.get(column).copied()
+    }
+
+    /// Make `column`, now `current` cells wide, one step wider or narrower.
+    pub(super) fn resize(&mut self, column: &str, current: usize, wider: bool) {
+        let width = if wider {
+            current.saturating_add(STEP)
+        } else {
+            current.saturating_sub(STEP)
+        };
+        self.widths
+            .insert(column.to_string(), width.clamp(MIN_WIDTH, MAX_WIDTH));
+        self.save();
+    }
+
+    /// Go back to sizing `column` by its values. Returns whether it had a
+    /// width set.
+    pub(super) fn reset(&mut self, column: &str) -> bool {
+        let removed = self.widths.remove(column).is_some();
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    fn save(&self) {
+        if let Ok(mut tables) = TABLES.lock() {
+            tables.insert(self.table.clone(), self.widths.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnWidths, MAX_WIDTH, MIN_WIDTH};
+
+    #[test]
+    fn resizes_within_bounds_and_remembers_per_table() {
+        let mut widths = ColumnWidths::for_table("widths-test");
+        widths.resize("name", 10, true);
+        assert_eq!(widths.get("name"), Some(14));
+        widths.resize("note", 5, false);
+        assert_eq!(widths.get("note"), Some(MIN_WIDTH));
+        widths.resize("body", MAX_WIDTH, true);
+        assert_eq!(widths.get("body"), Some(MAX_WIDTH));
+
+        let reopened = ColumnWidths::for_table("widths-test");
+        assert_eq!(reopened.get("name"), Some(14));
+        assert_eq!(ColumnWidths::for_table("other-table").get("name"), None);
+
+        widths.reset("name");
+        assert_eq!(ColumnWidths::for_table("widths-test").get("name"), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Comparing every result of the active query with a saved export or the
+//! same query against another table (`C`).
+//!
+//! The other side is asked for, both sides are read in the background, and
+//! the diff opens in a [`ResultDiffWidget`]. Each step comes back as one of
+//! the events here; [`handle`] takes it from there and tells the query widget
+//! what it has to do about it.
+
+use std::{fs, path::Path, sync::Arc, time::Duration};
+
+use futures::future::BoxFuture;
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::diff::{ItemDiff, diff_items, parse_snapshot};
+use dynamate::core::query::{Key, Page, QueryPlan};
+use dynamate::core::schema::KeySchema;
+use dynamate::core::value::Item;
+
+use crate::{
+    env::{AppEvent, Toast, ToastKind, WidgetCtx, WidgetId},
+    util::abbreviate_home,
+};
+
+use super::result_diff::{DiffSourcePopup, ResultDiffWidget};
+
+/// Most items either side of a diff may hold.
+pub(super) const MAX_DIFF_ITEMS: usize = 100_000;
+
+struct DiffRequest {
+    source: String,
+}
+
+struct DiffReadyEvent {
+    /// What the results were compared with.
+    other: String,
+    result: Result<(ItemDiff, KeySchema), String>,
+}
+
+/// What the results are compared with, as the query widget hands it over.
+pub(super) struct Comparison {
+    pub(super) db: Arc<dyn Datastore>,
+    /// The path of a saved export, or the name of another table.
+    pub(super) source: String,
+    pub(super) key: KeySchema,
+    /// The active query, run against the other table.
+    pub(super) plan: QueryPlan,
+    /// The keys a key-list query reads, fetched from the other table as-is.
+    pub(super) keys: Option<Vec<Key>>,
+    /// Whether the active query is a statement, which only a saved export
+    /// can be compared with.
+    pub(super) statement: bool,
+}
+
+/// Where a comparison got to, for the query widget to act on.
+pub(super) enum CompareStep {
+    /// The other side was picked; the widget gathers its results for
+    /// [`start`].
+    Requested(String),
+    /// The comparison finished: the diff is open, or it failed.
+    Finished(Result<(), String>),
+}
+
+/// Ask what to compare the results with.
+pub(super) fn prompt(parent: WidgetId, ctx: &WidgetCtx) {
+    let ctx_for_submit = ctx.clone();
+    ctx.set_popup(Box::new(DiffSourcePopup::new(
+        move |source| ctx_for_submit.emit_self(DiffRequest { source }),
+        parent,
+    )));
+}
+
+/// Take the comparison one step further if `event` is one of its own.
+pub(super) fn handle(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    table_name: &str,
+    parent: WidgetId,
+) -> Option<CompareStep> {
+    if let Some(request) = event.payload::<DiffRequest>() {
+        return Some(CompareStep::Requested(request.source.clone()));
+    }
+    let ready = event.payload::<DiffReadyEvent>()?;
+    Some(CompareStep::Finished(match &ready.result {
+        Ok((diff, key)) => {
+            ctx.push_widget(Box::new(ResultDiffWidget::new(
+                table_name,
+                ready.other.clone(),
+                diff.clone(),
+                key.clone(),
+                parent,
+            )));
+            Ok(())
+        }
+        Err(err) => Err(format!("Failed to compare: {err}")),
+    }))
+}
+
+/// Read both sides in the background and diff them; `results` gathers every
+/// result of the active query.
+pub(super) fn start(
+    comparison: Comparison,
+    results: BoxFuture<'static, Result<Vec<Item>, String>>,
+    ctx: &WidgetCtx,
+) {
+    ctx.show_toast(Toast {
+        message: format!("Comparing the results with {}…", comparison.source),
+        kind: ToastKind::Info,
+        duration: Duration::from_secs(3),
+        action: None,
+    });
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let other = comparison.label();
+        let result = async move {
+            let before = comparison.load().await?;
+            let after = results.await?;
+            let diff = diff_items(before, after, &comparison.key)?;
+            Ok((diff, comparison.key))
+        }
+        .await;
+        ctx.emit_self(DiffReadyEvent { other, result });
+    });
+}
+
+impl Comparison {
+    fn path(&self) -> Option<&Path> {
+        let path = Path::new(&self.source);
+        path.is_file().then_some(path)
+    }
+
+    fn label(&self) -> String {
+        match self.path() {
+            Some(path) => abbreviate_home(path),
+            None => format!("table {}", self.source),
+        }
+    }
+
+    /// The other side: the saved export, or the other table read with the
+    /// active query.
+    async fn load(&self) -> Result<Vec<Item>, String> {
+        if let Some(path) = self.path() {
+            let text = fs::read_to_string(path)
+                .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+            return parse_snapshot(&text);
+        }
+        if self.statement {
+            return Err(format!(
+                "{} is not a file; statements can only be compared with a saved export",
+                self.source
+            ));
+        }
+        if let Some(keys) = &self.keys {
+            return self
+                .db
+                .batch_get(&self.source, keys.clone(), false)
+                .await
+                .map_err(|err| err.to_string());
+        }
+        let mut items = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = Page {
+                cursor,
+                limit: None,
+            };
+            let output = self
+                .db
+                .query(&self.source, &self.plan, page)
+                .await
+                .map_err(|err| err.to_string())?;
+            items.extend(output.items);
+            if items.len() > MAX_DIFF_ITEMS {
+                return Err(format!(
+                    "{} has more than {MAX_DIFF_ITEMS} matching items",
+                    self.source
+                ));
+            }
+            match output.next {
+                Some(next) => cursor = Some(next),
+                None => return Ok(items),
+            }
+        }
+    }
+}
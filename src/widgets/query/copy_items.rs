@@ -0,0 +1,339 @@
+//! Copying or moving the selected items to another table (`M`).
+//!
+//! The tables are listed, one is picked, the items are checked against its
+//! key, and once confirmed they are written in batches (and, for a move,
+//! deleted from the source). Each step runs in the background and comes back
+//! as one of the events here; [`handle`] takes it from there and tells the
+//! query widget what it has to do about it.
+
+use std::{sync::Arc, time::Duration};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use dynamate::core::copy::{ItemsCheck, check_items};
+use dynamate::core::datastore::Datastore;
+use dynamate::core::error::{DbError, Result as DbResult};
+use dynamate::core::purge::item_key;
+use dynamate::core::value::Item;
+
+use crate::{
+    env::{AppEvent, Toast, ToastKind, WidgetCtx, WidgetId},
+    safety::Action,
+    widgets::confirm::{ConfirmAction, ConfirmPopup},
+};
+
+use super::copy_target::{CopyTargetPicker, TransferMode};
+
+/// Items written per `batch_put`, and deleted per `batch_delete` on a move.
+const COPY_BATCH_SIZE: usize = 25;
+
+struct CopyTablesEvent {
+    items: Vec<Item>,
+    result: Result<Vec<String>, String>,
+}
+
+struct CopyTargetEvent {
+    destination: String,
+    mode: TransferMode,
+    items: Vec<Item>,
+}
+
+struct CopyCheckEvent {
+    destination: String,
+    mode: TransferMode,
+    items: Vec<Item>,
+    result: Result<ItemsCheck, String>,
+}
+
+#[derive(Clone)]
+struct CopyItemsRequest {
+    destination: String,
+    mode: TransferMode,
+    items: Vec<Item>,
+}
+
+struct CopyItemsEvent {
+    destination: String,
+    mode: TransferMode,
+    result: Result<u64, String>,
+}
+
+/// What the copy needs from the table it copies from.
+pub(super) struct CopySource {
+    pub(super) db: Arc<dyn Datastore>,
+    pub(super) table_name: String,
+    /// The source table's key attributes, for deleting moved items.
+    pub(super) key_fields: Result<Vec<String>, String>,
+    pub(super) parent: WidgetId,
+}
+
+/// Where a copy got to, for the query widget to act on.
+pub(super) enum CopyStep {
+    /// The next step is under way; nothing to do.
+    Continuing,
+    /// The copy stopped before anything was written.
+    Failed(String),
+    /// The items are being written.
+    Writing,
+    /// The writes finished: `Ok(true)` when the items were moved, so the
+    /// source rows are gone.
+    Written(Result<bool, String>),
+}
+
+/// List the tables the `items` could go to, then let the user pick one.
+pub(super) fn start(db: Arc<dyn Datastore>, items: Vec<Item>, ctx: WidgetCtx) {
+    tokio::spawn(async move {
+        let result = db.list_collections().await.map_err(|err| err.to_string());
+        ctx.emit_self(CopyTablesEvent { items, result });
+    });
+}
+
+/// Take the copy one step further if `event` is one of its own. `source` is
+/// only built then.
+pub(super) fn handle(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    source: impl FnOnce() -> CopySource,
+) -> Option<CopyStep> {
+    if let Some(tables_event) = event.payload::<CopyTablesEvent>() {
+        return Some(match &tables_event.result {
+            Ok(tables) => {
+                show_picker(&source(), tables, &tables_event.items, ctx);
+                CopyStep::Continuing
+            }
+            Err(err) => CopyStep::Failed(format!("Failed to list tables: {err}")),
+        });
+    }
+    if let Some(target) = event.payload::<CopyTargetEvent>() {
+        check_target(&source(), target, ctx);
+        return Some(CopyStep::Continuing);
+    }
+    if let Some(check_event) = event.payload::<CopyCheckEvent>() {
+        return Some(match confirm(&source(), check_event, ctx) {
+            Ok(()) => CopyStep::Continuing,
+            Err(err) => CopyStep::Failed(err),
+        });
+    }
+    if let Some(request) = event.payload::<CopyItemsRequest>() {
+        return Some(match copy(source(), request, ctx) {
+            Ok(()) => CopyStep::Writing,
+            Err(err) => CopyStep::Failed(err),
+        });
+    }
+    if let Some(copy_event) = event.payload::<CopyItemsEvent>() {
+        return Some(CopyStep::Written(finished(copy_event, ctx)));
+    }
+    None
+}
+
+fn show_picker(source: &CopySource, tables: &[String], items: &[Item], ctx: &WidgetCtx) {
+    let tables: Vec<String> = tables
+        .iter()
+        .filter(|table| **table != source.table_name)
+        .cloned()
+        .collect();
+    let items = items.to_vec();
+    let ctx_for_select = ctx.clone();
+    ctx.set_popup(Box::new(CopyTargetPicker::new(
+        items.len(),
+        tables,
+        move |destination, mode| {
+            ctx_for_select.emit_self(CopyTargetEvent {
+                destination,
+                mode,
+                items: items.clone(),
+            });
+        },
+        source.parent.clone(),
+    )));
+}
+
+/// Describe the picked table to check the items against its keys.
+fn check_target(source: &CopySource, target: &CopyTargetEvent, ctx: &WidgetCtx) {
+    let db = source.db.clone();
+    let destination = target.destination.clone();
+    let mode = target.mode;
+    let items = target.items.clone();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let result = db
+            .describe_collection(&destination)
+            .await
+            .map(|schema| check_items(&items, &schema))
+            .map_err(|err| err.to_string());
+        ctx.emit_self(CopyCheckEvent {
+            destination,
+            mode,
+            items,
+            result,
+        });
+    });
+}
+
+/// Ask before writing the checked items.
+fn confirm(
+    source: &CopySource,
+    check_event: &CopyCheckEvent,
+    ctx: &WidgetCtx,
+) -> Result<(), String> {
+    let message = confirmation(
+        &source.table_name,
+        check_event.mode,
+        check_event.items.len(),
+        &check_event.destination,
+        &check_event.result,
+    )?;
+    let verb = check_event.mode.verb();
+    let request = CopyItemsRequest {
+        destination: check_event.destination.clone(),
+        mode: check_event.mode,
+        items: check_event.items.clone(),
+    };
+    let ctx_for_copy = ctx.clone();
+    ConfirmPopup::new_with_action(
+        format!("{verb} items"),
+        message,
+        verb,
+        "cancel",
+        ConfirmAction::new(
+            KeyCode::Char('y'),
+            KeyModifiers::NONE,
+            "y",
+            verb.to_lowercase(),
+            format!("{verb} the items"),
+        ),
+        move || ctx_for_copy.emit_self(request.clone()),
+        source.parent.clone(),
+    )
+    .show(ctx, Action::CopyItems, &check_event.destination);
+    Ok(())
+}
+
+/// What the confirmation says about the copy. Items missing a destination
+/// key stop it; type mismatches are listed as a warning.
+fn confirmation(
+    source_table: &str,
+    mode: TransferMode,
+    count: usize,
+    destination: &str,
+    check: &Result<ItemsCheck, String>,
+) -> Result<String, String> {
+    let check = check
+        .as_ref()
+        .map_err(|err| format!("Failed to describe {destination}: {err}"))?;
+    if !check.missing.is_empty() {
+        return Err(format!(
+            "The items don't fit {destination}'s key:\n{}",
+            check.missing.join("\n")
+        ));
+    }
+    let mut lines = vec![format!("{} {count} items to {destination}", mode.verb())];
+    if mode == TransferMode::Move {
+        lines.push(format!("and delete them from {source_table} once written"));
+    }
+    if !check.mismatches.is_empty() {
+        lines.push(String::new());
+        lines.push("Warning: writes may be rejected".to_string());
+        lines.extend(check.mismatches.iter().cloned());
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Write the items to the destination in batches; a move then deletes
+/// what was written from the source table.
+fn copy(source: CopySource, request: &CopyItemsRequest, ctx: &WidgetCtx) -> Result<(), String> {
+    let key_fields = source.key_fields?;
+    let db = source.db;
+    let table_name = source.table_name;
+    let CopyItemsRequest {
+        destination,
+        mode,
+        items,
+    } = request.clone();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let result = async {
+            let mut written = 0;
+            for batch in items.chunks(COPY_BATCH_SIZE) {
+                written += db.batch_put(&destination, batch.to_vec()).await?.written;
+                if mode == TransferMode::Move {
+                    let keys = batch
+                        .iter()
+                        .map(|item| item_key(item, &key_fields))
+                        .collect::<DbResult<Vec<_>>>()?;
+                    db.batch_delete(&table_name, keys).await?;
+                }
+            }
+            Ok::<_, DbError>(written)
+        }
+        .await
+        .map_err(|err| err.to_string());
+        ctx.emit_self(CopyItemsEvent {
+            destination,
+            mode,
+            result,
+        });
+    });
+    Ok(())
+}
+
+fn finished(copy_event: &CopyItemsEvent, ctx: &WidgetCtx) -> Result<bool, String> {
+    let count = copy_event.result.as_ref().map_err(|err| {
+        format!(
+            "Failed to {} items to {}: {err}",
+            copy_event.mode.verb().to_lowercase(),
+            copy_event.destination
+        )
+    })?;
+    let verb = match copy_event.mode {
+        TransferMode::Copy => "Copied",
+        TransferMode::Move => "Moved",
+    };
+    ctx.show_toast(Toast {
+        message: format!("{verb} {count} items to {}", copy_event.destination),
+        kind: ToastKind::Info,
+        duration: Duration::from_secs(4),
+        action: None,
+    });
+    Ok(copy_event.mode == TransferMode::Move)
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::copy::ItemsCheck;
+
+    use super::confirmation;
+    use crate::widgets::query::copy_target::TransferMode;
+
+    #[test]
+    fn confirmation_stops_on_missing_keys_and_warns_on_mismatches() {
+        let check = ItemsCheck {
+            missing: Vec::new(),
+            mismatches: vec!["pk is a number in archive".to_string()],
+        };
+        assert_eq!(
+            confirmation("users", TransferMode::Move, 3, "archive", &Ok(check)),
+            Ok("Move 3 items to archive\n\
+                and delete them from users once written\n\
+                \n\
+                Warning: writes may be rejected\n\
+                pk is a number in archive"
+                .to_string())
+        );
+        let check = ItemsCheck {
+            missing: vec!["item 1 has no sk".to_string()],
+            mismatches: Vec::new(),
+        };
+        assert!(confirmation("users", TransferMode::Copy, 1, "archive", &Ok(check)).is_err());
+        assert!(
+            confirmation(
+                "users",
+                TransferMode::Copy,
+                1,
+                "archive",
+                &Err("gone".to_string())
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    prelude::StatefulWidget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, HighlightSpacing, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{
+    help,
+    util::fill_bg,
+    widgets::{Popup, WidgetInner, filter_input::FilterInput, theme::Theme},
+};
+
+/// Whether the items stay in the table they came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TransferMode {
+    Copy,
+    /// Copy, then delete the items that were written from the source table.
+    Move,
+}
+
+impl TransferMode {
+    pub(super) fn verb(self) -> &'static str {
+        match self {
+            Self::Copy => "Copy",
+            Self::Move => "Move",
+        }
+    }
+
+    fn toggled(self) -> Self {
+        match self {
+            Self::Copy => Self::Move,
+            Self::Move => Self::Copy,
+        }
+    }
+}
+
+/// Picks the table to copy or move the selected items to.
+pub(super) struct CopyTargetPicker {
+    inner: WidgetInner,
+    count: usize,
+    tables: Vec<String>,
+    /// Indexes into `tables` of the ones matching the filter.
+    visible: RefCell<Vec<usize>>,
+    filter: RefCell<FilterInput>,
+    mode: Cell<TransferMode>,
+    state: RefCell<ListState>,
+    on_select: Box<dyn Fn(String, TransferMode) + Send + 'static>,
+}
+
+impl CopyTargetPicker {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓/j/k"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("/"),
+            short: Cow::Borrowed("filter"),
+            long: Cow::Borrowed("Filter tables"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("tab"),
+            short: Cow::Borrowed("copy/move"),
+            long: Cow::Borrowed("Switch between copying and moving the items"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("select"),
+            long: Cow::Borrowed("Use the selected table"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close"),
+            long: Cow::Borrowed("Close picker"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    /// `count` is how many items are being copied, for the title.
+    pub(super) fn new(
+        count: usize,
+        tables: Vec<String>,
+        on_select: impl Fn(String, TransferMode) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let visible = (0..tables.len()).collect::<Vec<_>>();
+        let mut state = ListState::default();
+        if !visible.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            count,
+            tables,
+            visible: RefCell::new(visible),
+            filter: RefCell::new(FilterInput::default()),
+            mode: Cell::new(TransferMode::Copy),
+            state: RefCell::new(state),
+            on_select: Box::new(on_select),
+        }
+    }
+
+    fn apply_filter(&self) {
+        let query = self.filter.borrow().value.to_lowercase();
+        let visible: Vec<usize> = self
+            .tables
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| name.to_lowercase().contains(&query))
+            .map(|(idx, _)| idx)
+            .collect();
+        let mut state = self.state.borrow_mut();
+        state.select((!visible.is_empty()).then_some(0));
+        *self.visible.borrow_mut() = visible;
+    }
+
+    fn selected_table(&self) -> Option<String> {
+        let row = self.state.borrow().selected()?;
+        let idx = *self.visible.borrow().get(row)?;
+        self.tables.get(idx).cloned()
+    }
+}
+
+impl crate::widgets::Widget for CopyTargetPicker {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let mode = self.mode.get();
+        let noun = if self.count == 1 { "item" } else { "items" };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::styled(
+                format!(" {} {} {noun} to ", mode.verb(), self.count),
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let [filter_area, list_area, hint_area] = block.inner(area).layout(&Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ]));
+        self.filter
+            .borrow()
+            .render_with_title(frame, filter_area, theme, "Filter");
+
+        let items: Vec<ListItem> = self
+            .visible
+            .borrow()
+            .iter()
+            .map(|idx| ListItem::new(self.tables[*idx].as_str()))
+            .collect();
+        if items.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No other tables").style(Style::default().fg(theme.warning())),
+                list_area,
+            );
+        } else {
+            let list = List::new(items)
+                .highlight_spacing(HighlightSpacing::Always)
+                .highlight_symbol("> ")
+                .highlight_style(
+                    Style::default()
+                        .bg(theme.selection_bg())
+                        .fg(theme.selection_fg()),
+                );
+            StatefulWidget::render(
+                list,
+                list_area,
+                frame.buffer_mut(),
+                &mut self.state.borrow_mut(),
+            );
+        }
+
+        let hint = match mode {
+            TransferMode::Copy => "tab: move instead, deleting the items here",
+            TransferMode::Move => "tab: copy instead, keeping the items here",
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                hint,
+                Style::default().fg(theme.text_muted()),
+            ))),
+            hint_area,
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        if self.filter.borrow_mut().handle_event(event) {
+            self.apply_filter();
+            ctx.invalidate();
+            return true;
+        }
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Char('/') => self.filter.borrow_mut().set_active(true),
+            KeyCode::Tab | KeyCode::BackTab => self.mode.set(self.mode.get().toggled()),
+            KeyCode::Up | KeyCode::Char('k') => self.state.borrow_mut().select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.state.borrow_mut().select_next(),
+            KeyCode::Enter => {
+                if let Some(table) = self.selected_table() {
+                    ctx.dismiss_popup();
+                    (self.on_select)(table, self.mode.get());
+                }
+            }
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for CopyTargetPicker {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 56.min(area.width.saturating_sub(4));
+        let height = (area.height * 3 / 5)
+            .max(12)
+            .min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
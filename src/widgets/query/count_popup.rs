@@ -0,0 +1,138 @@
+use std::borrow::Cow;
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::count::CountSummary;
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, purge::format_elapsed, theme::Theme},
+};
+
+/// Reports how many items the active query matches, from a count-only run
+/// over every page.
+pub(super) struct CountSummaryPopup {
+    inner: WidgetInner,
+    query: String,
+    summary: CountSummary,
+}
+
+impl CountSummaryPopup {
+    const HELP: &'static [help::Entry<'static>] = &[help::Entry {
+        keys: Cow::Borrowed("esc/⏎"),
+        short: Cow::Borrowed("close"),
+        long: Cow::Borrowed("Close"),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }];
+
+    pub(super) fn new(
+        query: impl Into<String>,
+        summary: CountSummary,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            query: query.into(),
+            summary,
+        }
+    }
+
+    fn rows(&self) -> Vec<(&'static str, String)> {
+        let summary = &self.summary;
+        let mut rows = vec![
+            ("Query", self.query.clone()),
+            ("Matched", format!("{} items", summary.matched)),
+            (
+                "Scanned",
+                format!("{} items in {} pages", summary.scanned, summary.pages),
+            ),
+        ];
+        if let Some(units) = summary.capacity_units {
+            rows.push(("Consumed", format!("{units:.1} RCU")));
+        }
+        rows.push(("Took", format_elapsed(summary.elapsed)));
+        if summary.cancelled {
+            rows.push(("Status", "canceled; the counts are partial".to_string()));
+        }
+        rows
+    }
+}
+
+impl crate::widgets::Widget for CountSummaryPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Count matches", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let lines: Vec<Line> = self
+            .rows()
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{label:<9}"),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                    Span::styled(value, Style::default().fg(theme.text())),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+            ctx.dismiss_popup();
+        }
+        true
+    }
+}
+
+impl Popup for CountSummaryPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 60.min(area.width.saturating_sub(4));
+        let rows = u16::try_from(self.rows().len()).unwrap_or(u16::MAX);
+        let height = rows.saturating_add(2).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
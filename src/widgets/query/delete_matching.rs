@@ -0,0 +1,365 @@
+//! Deleting every item the active query matches, or counting them in a dry
+//! run (`D`).
+//!
+//! [`DeleteMatchingPopup`] offers the choice; deleting then goes through the
+//! safety policy, and either run scans in the background with its progress
+//! in the jobs panel. Each step comes back as one of the events here;
+//! [`handle`] takes it from there and tells the query widget what it has to do
+//! about it.
+
+use std::{borrow::Cow, sync::Arc, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph, Wrap},
+};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::purge::{PurgeOptions, PurgeProgress, PurgeSummary, purge_items};
+use dynamate::core::query::QueryPlan;
+use dynamate::dynamodb::requests::RequestManager;
+
+use crate::{
+    env::{AppEvent, Toast, ToastKind, WidgetCtx, WidgetId},
+    help, jobs,
+    safety::Action,
+    util::{fill_bg, pad},
+    widgets::{
+        Popup, WidgetInner,
+        confirm::{ConfirmAction, ConfirmPopup},
+        purge::PurgeSummaryPopup,
+        theme::Theme,
+    },
+};
+
+use super::widget::show_readonly_toast;
+
+struct DeleteMatchingRequest {
+    dry_run: bool,
+    /// Passed the safety policy's confirmation, on top of the popup.
+    confirmed: bool,
+}
+
+struct DeleteMatchingEvent {
+    run_id: u64,
+    result: Result<PurgeSummary, String>,
+}
+
+/// What the delete needs from the query it applies to.
+pub(super) struct DeleteSource<'a> {
+    pub(super) db: Arc<dyn Datastore>,
+    pub(super) table_name: String,
+    /// The active query, with its parameters and options.
+    pub(super) plan: QueryPlan,
+    pub(super) segments: u32,
+    /// The widget's bulk runs, so only one bulk change goes at a time.
+    pub(super) bulk: &'a RequestManager,
+    pub(super) parent: WidgetId,
+}
+
+/// Where a delete got to, for the query widget to act on.
+pub(super) enum DeleteStep {
+    /// The next step is under way; nothing to do.
+    Continuing,
+    /// The run stopped; nothing more was deleted.
+    Failed(String),
+    /// The run finished, having deleted this many items (none in a dry run).
+    Deleted(u64),
+}
+
+/// Offer to delete every item `query` matches, or to count them.
+pub(super) fn prompt(query: String, parent: WidgetId, ctx: &WidgetCtx) {
+    let ctx_for_run = ctx.clone();
+    ctx.set_popup(Box::new(DeleteMatchingPopup::new(
+        query,
+        move |dry_run| {
+            ctx_for_run.emit_self(DeleteMatchingRequest {
+                dry_run,
+                confirmed: false,
+            });
+        },
+        parent,
+    )));
+}
+
+/// Take the delete one step further if `event` is one of its own. `source`
+/// is only built then.
+pub(super) fn handle<'a>(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    source: impl FnOnce() -> DeleteSource<'a>,
+) -> Option<DeleteStep> {
+    if let Some(request) = event.payload::<DeleteMatchingRequest>() {
+        if request.dry_run || request.confirmed {
+            run(source(), request.dry_run, ctx);
+        } else {
+            confirm(&source(), ctx);
+        }
+        return Some(DeleteStep::Continuing);
+    }
+    let finished = event.payload::<DeleteMatchingEvent>()?;
+    let source = source();
+    source.bulk.finish(finished.run_id);
+    Some(match &finished.result {
+        Ok(summary) => {
+            show_summary(&source, summary.clone(), ctx);
+            DeleteStep::Deleted(if summary.dry_run {
+                0
+            } else {
+                summary.progress.deleted
+            })
+        }
+        Err(err) => DeleteStep::Failed(err.clone()),
+    })
+}
+
+/// Choosing to delete in the popup was the yes-or-no step; a stricter
+/// policy still has the table name typed.
+fn confirm(source: &DeleteSource<'_>, ctx: &WidgetCtx) {
+    let ctx_for_delete = ctx.clone();
+    ConfirmPopup::new_with_action(
+        "Delete matching items",
+        format!("Table={}\n\nDelete every matching item?", source.table_name),
+        "Delete",
+        "cancel",
+        ConfirmAction::new(
+            KeyCode::Char('d'),
+            KeyModifiers::CONTROL,
+            "^d",
+            "delete",
+            "Delete every matching item",
+        ),
+        move || {
+            ctx_for_delete.emit_self(DeleteMatchingRequest {
+                dry_run: false,
+                confirmed: true,
+            });
+        },
+        source.parent.clone(),
+    )
+    .show_reviewed(ctx, Action::DeleteMatching, &source.table_name);
+}
+
+fn run(source: DeleteSource<'_>, dry_run: bool, ctx: &WidgetCtx) {
+    if !dry_run && source.db.is_read_only() {
+        show_readonly_toast(ctx);
+        return;
+    }
+    let options = PurgeOptions {
+        segments: source.segments,
+        dry_run,
+    };
+    let run = source.bulk.start();
+    let label = if dry_run { "Count" } else { "Delete" };
+    let job = jobs::track(
+        format!("{label} matches in {}", source.table_name),
+        run.shared_flag(),
+    );
+    job.progress(progress_message(PurgeProgress::default(), dry_run));
+    let DeleteSource {
+        db,
+        table_name,
+        plan,
+        ..
+    } = source;
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let on_progress = run.progress(|progress: PurgeProgress| {
+            job.progress(progress_message(progress, dry_run));
+        });
+        let result = purge_items(
+            db.as_ref(),
+            &table_name,
+            &plan,
+            options,
+            run.flag(),
+            &on_progress,
+        )
+        .await
+        .map_err(|err| err.to_string());
+        job.finish_quietly(
+            result
+                .as_ref()
+                .map(|summary| format!("{} items", summary.progress.deleted))
+                .map_err(Clone::clone),
+        );
+        ctx.emit_self(DeleteMatchingEvent {
+            run_id: run.id(),
+            result,
+        });
+    });
+}
+
+fn show_summary(source: &DeleteSource<'_>, summary: PurgeSummary, ctx: &WidgetCtx) {
+    let deleted = summary.progress.deleted;
+    let (message, title) = if summary.dry_run {
+        (format!("{deleted} items match"), "Dry run")
+    } else {
+        (format!("Deleted {deleted} items"), "Delete matching")
+    };
+    ctx.show_toast(Toast {
+        message,
+        kind: ToastKind::Info,
+        duration: Duration::from_secs(3),
+        action: None,
+    });
+    ctx.set_popup(Box::new(PurgeSummaryPopup::new(
+        title,
+        source.table_name.clone(),
+        summary,
+        source.parent.clone(),
+    )));
+}
+
+fn progress_message(progress: PurgeProgress, dry_run: bool) -> String {
+    if dry_run {
+        format!(
+            "Counting matches... {} of {} scanned items match (esc cancels)",
+            progress.deleted, progress.scanned
+        )
+    } else {
+        format!(
+            "Deleting matches... {} items deleted, {} scanned (esc cancels)",
+            progress.deleted, progress.scanned
+        )
+    }
+}
+
+/// Offers to delete every item the current query matches, or to count them in
+/// a dry run. Choosing to delete is the yes-or-no step; the safety policy's
+/// `delete_matching` tier decides whether the table name is typed after.
+struct DeleteMatchingPopup {
+    inner: WidgetInner,
+    query: String,
+    on_run: Box<dyn Fn(bool) + Send + 'static>,
+}
+
+impl DeleteMatchingPopup {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("delete"),
+            long: Cow::Borrowed("Delete the matching items"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("^r"),
+            short: Cow::Borrowed("dry run"),
+            long: Cow::Borrowed("Count the matching items without deleting"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("cancel"),
+            long: Cow::Borrowed("Cancel"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    /// `query` describes what matches; `on_run` gets `true` for a dry run.
+    fn new(
+        query: impl Into<String>,
+        on_run: impl Fn(bool) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            query: query.into(),
+            on_run: Box::new(on_run),
+        }
+    }
+}
+
+impl crate::widgets::Widget for DeleteMatchingPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Delete matching", 1),
+            Style::default()
+                .fg(theme.error())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ]);
+        let [query_area, _, hint_area] = inner.layout(&layout);
+
+        let query = Line::from(vec![
+            Span::styled(
+                "Deletes every item matching ",
+                Style::default().fg(theme.text_muted()),
+            ),
+            Span::styled(self.query.clone(), Style::default().fg(theme.text())),
+        ]);
+        frame.render_widget(Paragraph::new(query).wrap(Wrap { trim: true }), query_area);
+        let hint = Line::styled(
+            "⏎ deletes · ^r counts the matches without deleting",
+            Style::default().fg(theme.text_muted()),
+        );
+        frame.render_widget(Paragraph::new(hint), hint_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.dismiss_popup();
+                (self.on_run)(true);
+            }
+            KeyCode::Enter => {
+                ctx.dismiss_popup();
+                (self.on_run)(false);
+            }
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for DeleteMatchingPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 64.min(area.width.saturating_sub(4));
+        // The query, a gap, the hint, and the borders.
+        let height = 6.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
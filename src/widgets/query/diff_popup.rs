@@ -0,0 +1,335 @@
+use std::{borrow::Cow, cell::Cell};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// One line of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Shows the unified diff between an item before and after an `$EDITOR` edit
+/// and runs `on_confirm` only when the user accepts it.
+pub(super) struct DiffPopup {
+    inner: WidgetInner,
+    title: String,
+    lines: Vec<DiffLine>,
+    on_confirm: Box<dyn Fn() + Send + 'static>,
+    action: &'static str,
+    scroll: Cell<usize>,
+    viewport: Cell<usize>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+impl DiffPopup {
+    pub(super) fn new(
+        title: impl Into<String>,
+        original: &str,
+        edited: &str,
+        on_confirm: impl Fn() + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("j/k/↑/↓/PgUp/PgDn"),
+                short: Cow::Borrowed("scroll"),
+                long: Cow::Borrowed("Scroll diff"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("^s/⏎"),
+                short: Cow::Borrowed("save"),
+                long: Cow::Borrowed("Save changes"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("discard"),
+                long: Cow::Borrowed("Discard changes"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        let original = canonical_json(original).unwrap_or_else(|| original.to_string());
+        let edited = canonical_json(edited).unwrap_or_else(|| edited.to_string());
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            title: title.into(),
+            lines: diff_lines(&original, &edited),
+            on_confirm: Box::new(on_confirm),
+            action: "save",
+            scroll: Cell::new(0),
+            viewport: Cell::new(0),
+            help_entries,
+        }
+    }
+
+    /// Name what confirming does, in place of saving an edit.
+    pub(super) fn with_action(mut self, short: &'static str, long: &'static str) -> Self {
+        if let Some(entry) = self
+            .help_entries
+            .iter_mut()
+            .find(|entry| entry.short == self.action)
+        {
+            entry.short = Cow::Borrowed(short);
+            entry.long = Cow::Borrowed(long);
+        }
+        self.action = short;
+        self
+    }
+
+    fn scroll_by(&self, delta: isize) {
+        let max = self.lines.len().saturating_sub(self.viewport.get());
+        let next = (self.scroll.get() as isize + delta).clamp(0, max as isize);
+        self.scroll.set(next as usize);
+    }
+}
+
+impl crate::widgets::Widget for DiffPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad(self.title.as_str(), 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]);
+        let [body_area, footer_area] = inner.layout(&layout);
+
+        self.viewport.set(body_area.height as usize);
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .skip(self.scroll.get())
+            .take(body_area.height as usize)
+            .map(|line| match line {
+                DiffLine::Same(text) => {
+                    Line::styled(format!("  {text}"), Style::default().fg(theme.text_muted()))
+                }
+                DiffLine::Removed(text) => {
+                    Line::styled(format!("- {text}"), Style::default().fg(theme.error()))
+                }
+                DiffLine::Added(text) => {
+                    Line::styled(format!("+ {text}"), Style::default().fg(theme.success()))
+                }
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), body_area);
+
+        let (added, removed) = change_counts(&self.lines);
+        let footer = Line::from(vec![
+            Span::styled(format!("+{added}"), Style::default().fg(theme.success())),
+            Span::raw(" "),
+            Span::styled(format!("-{removed}"), Style::default().fg(theme.error())),
+            Span::styled(
+                format!("   ^s/⏎ {} · esc discard", self.action),
+                Style::default().fg(theme.text_muted()),
+            ),
+        ]);
+        frame.render_widget(
+            Paragraph::new(footer).alignment(Alignment::Center),
+            footer_area,
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &crossterm::event::Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        let page = self.viewport.get().max(1) as isize;
+        match key.code {
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                (self.on_confirm)();
+                ctx.dismiss_popup();
+            }
+            KeyCode::Enter => {
+                (self.on_confirm)();
+                ctx.dismiss_popup();
+            }
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Char('j') | KeyCode::Down => self.scroll_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll_by(-1),
+            KeyCode::PageDown => self.scroll_by(page),
+            KeyCode::PageUp => self.scroll_by(-page),
+            _ => return true,
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for DiffPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = (area.width as f32 * 0.8) as u16;
+        let width = width.max(40).min(area.width.saturating_sub(4));
+        // Borders and the footer around the diff lines.
+        let wanted = self.lines.len().saturating_add(3) as u16;
+        let height = wanted
+            .max(8)
+            .min((area.height as f32 * 0.8) as u16)
+            .min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+fn change_counts(lines: &[DiffLine]) -> (usize, usize) {
+    lines
+        .iter()
+        .fold((0, 0), |(added, removed), line| match line {
+            DiffLine::Added(_) => (added + 1, removed),
+            DiffLine::Removed(_) => (added, removed + 1),
+            DiffLine::Same(_) => (added, removed),
+        })
+}
+
+/// Pretty-print JSON with object keys sorted, so the diff ignores attribute
+/// order and whitespace. `None` when the text isn't valid JSON.
+fn canonical_json(text: &str) -> Option<String> {
+    fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut entries: Vec<_> = map.into_iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                serde_json::Value::Object(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (key, sort_keys(value)))
+                        .collect(),
+                )
+            }
+            serde_json::Value::Array(values) => {
+                serde_json::Value::Array(values.into_iter().map(sort_keys).collect())
+            }
+            other => other,
+        }
+    }
+    let value = serde_json::from_str(text).ok()?;
+    serde_json::to_string_pretty(&sort_keys(value)).ok()
+}
+
+/// A line-level diff (longest common subsequence). The common prefix and
+/// suffix are matched up front, so the quadratic table only covers the edited
+/// middle.
+pub(super) fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j]: LCS length of old_mid[i..] and new_mid[j..].
+    let mut lcs = vec![vec![0_u32; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<DiffLine> = old[..prefix]
+        .iter()
+        .map(|line| DiffLine::Same((*line).to_string()))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            lines.push(DiffLine::Same(old_mid[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j < new_mid.len() && (i == old_mid.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            lines.push(DiffLine::Added(new_mid[j].to_string()));
+            j += 1;
+        } else {
+            lines.push(DiffLine::Removed(old_mid[i].to_string()));
+            i += 1;
+        }
+    }
+    lines.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| DiffLine::Same((*line).to_string())),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffLine, canonical_json, diff_lines};
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let lines = diff_lines("a\nb\nc\nd", "a\nc\nx\nd");
+        assert_eq!(
+            lines,
+            vec![
+                DiffLine::Same("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Same("c".to_string()),
+                DiffLine::Added("x".to_string()),
+                DiffLine::Same("d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn canonical_json_ignores_key_order_and_whitespace() {
+        assert_eq!(
+            canonical_json("{\"b\": 1, \"a\": {\"d\": 2, \"c\": 3}}"),
+            canonical_json("{\"a\":{\"c\":3,\"d\":2},\"b\":1}")
+        );
+        assert_eq!(canonical_json("not json"), None);
+    }
+}
@@ -0,0 +1,391 @@
+//! Single-table design entities: which kind of item each row is, going by the
+//! `entities` prefix rules in the config file. The results table shows the
+//! entity of each row in its own colored column, and `V` picks one entity to
+//! show or groups the rows by entity.
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    prelude::StatefulWidget,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, HighlightSpacing, List, ListItem, ListState},
+};
+
+use crate::{
+    help,
+    util::{self, fill_bg},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// The entities of one table.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Entities {
+    names: Vec<String>,
+    /// The entity each rule names, and its attribute prefixes.
+    rules: Vec<(usize, Vec<(String, String)>)>,
+}
+
+impl Entities {
+    pub(super) fn for_table(table: &str) -> Self {
+        Self::new(util::entity_rules(table).map(|rule| (rule.name.clone(), rule.prefixes.clone())))
+    }
+
+    fn new(rules: impl IntoIterator<Item = (String, Vec<(String, String)>)>) -> Self {
+        let mut entities = Self::default();
+        for (name, prefixes) in rules {
+            let index = entities
+                .names
+                .iter()
+                .position(|known| *known == name)
+                .unwrap_or_else(|| {
+                    entities.names.push(name);
+                    entities.names.len() - 1
+                });
+            entities.rules.push((index, prefixes));
+        }
+        entities
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub(super) fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The entity `item` is. When several rules match, the one with the most
+    /// prefixes wins, then the one with the longest: `USER#` with `ORDER#`
+    /// is more specific than `USER#` alone.
+    pub(super) fn classify(&self, item: &HashMap<String, AttributeValue>) -> Option<usize> {
+        self.rules
+            .iter()
+            .filter(|(_, prefixes)| {
+                prefixes.iter().all(|(attribute, prefix)| {
+                    item.get(attribute)
+                        .and_then(|value| value.as_s().ok())
+                        .is_some_and(|value| value.starts_with(prefix.as_str()))
+                })
+            })
+            .max_by_key(|(_, prefixes)| {
+                (
+                    prefixes.len(),
+                    prefixes
+                        .iter()
+                        .map(|(_, prefix)| prefix.len())
+                        .sum::<usize>(),
+                )
+            })
+            .map(|(index, _)| *index)
+    }
+
+    /// The width of the entity column.
+    pub(super) fn column_width(&self) -> usize {
+        self.names
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max(ENTITY_COLUMN.len())
+    }
+}
+
+/// The header of the entity column.
+pub(super) const ENTITY_COLUMN: &str = "entity";
+
+/// Which rows the view shows by entity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) enum EntityScope {
+    #[default]
+    All,
+    Entity(usize),
+    /// Rows no rule matches.
+    Other,
+}
+
+impl EntityScope {
+    pub(super) fn includes(self, entity: Option<usize>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Entity(index) => entity == Some(index),
+            Self::Other => entity.is_none(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct EntityView {
+    pub(super) scope: EntityScope,
+    /// Rows of the same entity are shown together, in entity order.
+    pub(super) grouped: bool,
+}
+
+/// The color of an entity's name, cycling through the theme's accents.
+pub(super) fn color(theme: &Theme, index: usize) -> Color {
+    let palette = [
+        theme.accent(),
+        theme.success(),
+        theme.warning(),
+        theme.accent_alt(),
+        theme.error(),
+    ];
+    palette[index % palette.len()]
+}
+
+/// Picks the entity the view shows, or all of them, and whether rows are
+/// grouped by entity.
+pub(super) struct EntityPicker {
+    inner: WidgetInner,
+    /// Each choice, its label, its color, and how many loaded rows it covers.
+    choices: Vec<(EntityScope, String, Option<usize>, usize)>,
+    grouped: RefCell<bool>,
+    state: RefCell<ListState>,
+    on_select: Box<dyn Fn(EntityView) + Send + 'static>,
+}
+
+impl EntityPicker {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓/j/k"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎/0-9"),
+            short: Cow::Borrowed("show"),
+            long: Cow::Borrowed("Show only the entity's rows (0 for all)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("g"),
+            short: Cow::Borrowed("group"),
+            long: Cow::Borrowed("Group rows by entity"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close"),
+            long: Cow::Borrowed("Close picker"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    /// `counts` has the loaded rows of each entity, then those of no entity.
+    pub(super) fn new(
+        names: &[String],
+        counts: &[usize],
+        current: EntityView,
+        on_select: impl Fn(EntityView) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let total = counts.iter().sum();
+        let mut choices = vec![(EntityScope::All, "All entities".to_string(), None, total)];
+        choices.extend(names.iter().enumerate().map(|(index, name)| {
+            (
+                EntityScope::Entity(index),
+                name.clone(),
+                Some(index),
+                counts.get(index).copied().unwrap_or(0),
+            )
+        }));
+        let other = counts.get(names.len()).copied().unwrap_or(0);
+        if other > 0 || current.scope == EntityScope::Other {
+            choices.push((EntityScope::Other, "Other".to_string(), None, other));
+        }
+        let mut state = ListState::default();
+        state.select(Some(
+            choices
+                .iter()
+                .position(|(scope, ..)| *scope == current.scope)
+                .unwrap_or(0),
+        ));
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            choices,
+            grouped: RefCell::new(current.grouped),
+            state: RefCell::new(state),
+            on_select: Box::new(on_select),
+        }
+    }
+
+    fn select(&self, ctx: &crate::env::WidgetCtx, row: usize) {
+        if let Some((scope, ..)) = self.choices.get(row) {
+            ctx.dismiss_popup();
+            (self.on_select)(EntityView {
+                scope: *scope,
+                grouped: *self.grouped.borrow(),
+            });
+        }
+    }
+}
+
+impl crate::widgets::Widget for EntityPicker {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let grouped = if *self.grouped.borrow() {
+            "grouped"
+        } else {
+            "not grouped"
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::styled(
+                " Entities ",
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Line::styled(
+                format!(" {grouped} · g toggles "),
+                Style::default().fg(theme.text_muted()),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+
+        let items: Vec<ListItem> = self
+            .choices
+            .iter()
+            .enumerate()
+            .map(|(row, (_, label, entity, count))| {
+                let number = if row < 10 {
+                    format!("{row} ")
+                } else {
+                    "  ".to_string()
+                };
+                let style = entity.map_or_else(Style::default, |index| {
+                    Style::default().fg(color(theme, index))
+                });
+                ListItem::new(Line::from(vec![
+                    Span::styled(number, Style::default().fg(theme.text_muted())),
+                    Span::styled(label.as_str(), style),
+                    Span::styled(
+                        format!("  {count} loaded"),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("> ")
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(
+            list,
+            block.inner(area),
+            frame.buffer_mut(),
+            &mut self.state.borrow_mut(),
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Up | KeyCode::Char('k') => self.state.borrow_mut().select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.state.borrow_mut().select_next(),
+            KeyCode::Enter => {
+                let selected = self.state.borrow().selected();
+                if let Some(row) = selected {
+                    self.select(&ctx, row);
+                }
+            }
+            KeyCode::Char(digit @ '0'..='9') => self.select(&ctx, digit as usize - '0' as usize),
+            KeyCode::Char('g') => {
+                let mut grouped = self.grouped.borrow_mut();
+                *grouped = !*grouped;
+            }
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for EntityPicker {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 48.min(area.width.saturating_sub(4));
+        let rows = u16::try_from(self.choices.len()).unwrap_or(u16::MAX);
+        let height = rows.saturating_add(2).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::{Entities, EntityScope};
+
+    #[test]
+    fn classifies_items_by_the_most_specific_rule() {
+        let prefixes = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(attribute, prefix)| ((*attribute).to_string(), (*prefix).to_string()))
+                .collect::<Vec<_>>()
+        };
+        let entities = Entities::new([
+            ("User".to_string(), prefixes(&[("PK", "USER#")])),
+            (
+                "Order".to_string(),
+                prefixes(&[("PK", "USER#"), ("SK", "ORDER#")]),
+            ),
+            ("Order".to_string(), prefixes(&[("PK", "ORDER#")])),
+        ]);
+        assert_eq!(entities.names(), ["User", "Order"]);
+
+        let item = |pk: &str, sk: &str| {
+            HashMap::from([
+                ("PK".to_string(), AttributeValue::S(pk.to_string())),
+                ("SK".to_string(), AttributeValue::S(sk.to_string())),
+            ])
+        };
+        assert_eq!(entities.classify(&item("USER#1", "PROFILE")), Some(0));
+        assert_eq!(entities.classify(&item("USER#1", "ORDER#7")), Some(1));
+        assert_eq!(entities.classify(&item("ORDER#7", "ORDER#7")), Some(1));
+        assert_eq!(entities.classify(&item("PRODUCT#3", "PRODUCT#3")), None);
+
+        assert!(EntityScope::All.includes(None));
+        assert!(EntityScope::Entity(1).includes(Some(1)));
+        assert!(!EntityScope::Entity(1).includes(Some(0)));
+        assert!(EntityScope::Other.includes(None));
+    }
+}
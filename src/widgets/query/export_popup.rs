@@ -4,8 +4,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyModifiers};
 use directories::BaseDirs;
+use dynamate::s3::S3Uri;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Layout, Margin, Rect},
@@ -14,6 +15,7 @@ use ratatui::{
     widgets::{Block, BorderType, Paragraph},
 };
 
+use super::export_writer::Compression;
 use crate::{
     help,
     util::{abbreviate_home, fill_bg, pad},
@@ -24,7 +26,9 @@ use crate::{
 enum Focus {
     Directory,
     Filename,
+    Upload,
     Checkbox,
+    Columns,
     Export,
     Cancel,
 }
@@ -135,14 +139,33 @@ fn char_to_byte_idx(value: &str, char_idx: usize) -> usize {
         .map_or_else(|| value.len(), |(idx, _)| idx)
 }
 
+/// What the user confirmed in the export popup.
+pub(crate) struct ExportChoice {
+    pub(crate) path: PathBuf,
+    /// Whether the popup's checkbox option was ticked.
+    pub(crate) option: bool,
+    /// Where to upload the file once written, when an S3 URI was given.
+    pub(crate) upload: Option<S3Uri>,
+    /// Whether only the visible columns are exported (`with_columns_option`).
+    pub(crate) visible_columns: bool,
+}
+
 pub(crate) struct ExportPopup {
     inner: WidgetInner,
     dir_input: RefCell<FormInput>,
     file_input: RefCell<FormInput>,
+    /// Present when the export can be uploaded to S3 (`with_s3_upload`).
+    s3_input: Option<RefCell<FormInput>>,
     option_label: Option<Cow<'static, str>>,
     option_enabled: Cell<bool>,
+    /// Present when the export can be cut down to the visible columns
+    /// (`with_columns_option`); whether it is.
+    columns_option: Option<Cell<bool>>,
+    /// Whether ^z cycles the file name's compression suffix
+    /// (`with_compression`).
+    compression: bool,
     focus: Cell<Focus>,
-    on_confirm: Box<dyn Fn(PathBuf, bool) + Send + 'static>,
+    on_confirm: Box<dyn Fn(ExportChoice) + Send + 'static>,
     help_entries: Vec<help::Entry<'static>>,
 }
 
@@ -152,7 +175,7 @@ impl ExportPopup {
         path: PathBuf,
         option_label: Option<Cow<'static, str>>,
         option_enabled: bool,
-        on_confirm: impl Fn(PathBuf, bool) + Send + 'static,
+        on_confirm: impl Fn(ExportChoice) + Send + 'static,
         parent: crate::env::WidgetId,
     ) -> Self {
         let (dir, file) = split_path(&path);
@@ -199,38 +222,92 @@ impl ExportPopup {
             inner: WidgetInner::new::<Self>(parent),
             dir_input: RefCell::new(FormInput::new(dir)),
             file_input: RefCell::new(FormInput::new(file)),
+            s3_input: None,
             option_label,
             option_enabled: Cell::new(option_enabled),
+            columns_option: None,
+            compression: false,
             focus: Cell::new(Focus::Export),
             on_confirm: Box::new(on_confirm),
             help_entries,
         }
     }
 
+    /// Add an optional S3 URI row; the file is uploaded there after it is
+    /// written.
+    pub(crate) fn with_s3_upload(mut self) -> Self {
+        self.s3_input = Some(RefCell::new(FormInput::new(String::new())));
+        self
+    }
+
+    /// Add a checkbox to export only the columns the fields popup leaves
+    /// visible.
+    pub(crate) fn with_columns_option(mut self) -> Self {
+        self.columns_option = Some(Cell::new(false));
+        self.help_entries.insert(
+            self.help_entries.len() - 1,
+            help::Entry {
+                keys: Cow::Borrowed("space"),
+                short: Cow::Borrowed("columns"),
+                long: Cow::Borrowed("Export only the visible columns"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        );
+        self
+    }
+
+    /// Let ^z switch the file name between no compression, `.gz`, and
+    /// `.zst`.
+    pub(crate) fn with_compression(mut self) -> Self {
+        self.compression = true;
+        self.help_entries.insert(
+            self.help_entries.len() - 1,
+            help::Entry {
+                keys: Cow::Borrowed("^z"),
+                short: Cow::Borrowed("compress"),
+                long: Cow::Borrowed("Cycle the compression: none, gzip, zstd"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        );
+        self
+    }
+
+    fn cycle_compression(&self) {
+        let mut input = self.file_input.borrow_mut();
+        *input = FormInput::new(Compression::cycle(input.value()));
+    }
+
+    fn focus_order(&self) -> Vec<Focus> {
+        let mut order = vec![Focus::Directory, Focus::Filename];
+        if self.s3_input.is_some() {
+            order.push(Focus::Upload);
+        }
+        if self.option_label.is_some() {
+            order.push(Focus::Checkbox);
+        }
+        if self.columns_option.is_some() {
+            order.push(Focus::Columns);
+        }
+        order.extend([Focus::Export, Focus::Cancel]);
+        order
+    }
+
     fn next_focus(&self) {
-        let has_checkbox = self.option_label.is_some();
-        let next = match (self.focus.get(), has_checkbox) {
-            (Focus::Directory, _) => Focus::Filename,
-            (Focus::Filename, true) => Focus::Checkbox,
-            (Focus::Filename, false) => Focus::Export,
-            (Focus::Checkbox, _) => Focus::Export,
-            (Focus::Export, _) => Focus::Cancel,
-            (Focus::Cancel, _) => Focus::Directory,
-        };
-        self.focus.set(next);
+        let order = self.focus_order();
+        let idx = order.iter().position(|f| *f == self.focus.get());
+        let next = idx.map_or(0, |idx| (idx + 1) % order.len());
+        self.focus.set(order[next]);
     }
 
     fn prev_focus(&self) {
-        let has_checkbox = self.option_label.is_some();
-        let prev = match (self.focus.get(), has_checkbox) {
-            (Focus::Directory, _) => Focus::Cancel,
-            (Focus::Filename, _) => Focus::Directory,
-            (Focus::Checkbox, _) => Focus::Filename,
-            (Focus::Export, true) => Focus::Checkbox,
-            (Focus::Export, false) => Focus::Filename,
-            (Focus::Cancel, _) => Focus::Export,
-        };
-        self.focus.set(prev);
+        let order = self.focus_order();
+        let idx = order.iter().position(|f| *f == self.focus.get());
+        let prev = idx.map_or(0, |idx| (idx + order.len() - 1) % order.len());
+        self.focus.set(order[prev]);
     }
 
     fn toggle_option(&self) {
@@ -240,7 +317,20 @@ impl ExportPopup {
     }
 
     fn export_enabled(&self) -> bool {
-        !self.file_input.borrow().value().trim().is_empty()
+        !self.file_input.borrow().value().trim().is_empty() && self.upload().is_ok()
+    }
+
+    /// The S3 URI to upload to; `Ok(None)` when the row is absent or empty.
+    fn upload(&self) -> Result<Option<S3Uri>, String> {
+        let Some(input) = self.s3_input.as_ref() else {
+            return Ok(None);
+        };
+        let input = input.borrow();
+        let value = input.value().trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        S3Uri::parse(value).map(Some)
     }
 
     fn build_path(&self) -> PathBuf {
@@ -305,7 +395,51 @@ impl ExportPopup {
         }
     }
 
-    fn render_option_row(&self, frame: &mut Frame, area: Rect, focused: bool, theme: &Theme) {
+    /// The S3 row: a placeholder while empty, and the label in the error
+    /// color while the URI doesn't parse (Export stays disabled).
+    fn render_upload_row(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(input) = self.s3_input.as_ref() else {
+            return;
+        };
+        let focused = self.focus.get() == Focus::Upload;
+        let input = input.borrow();
+        self.render_input_row(frame, area, "S3 URI", &input, focused, theme);
+        if input.value().trim().is_empty() {
+            if !focused {
+                let hint_area = Rect::new(
+                    area.x + Self::LABEL_WIDTH + 1,
+                    area.y,
+                    area.width.saturating_sub(Self::LABEL_WIDTH + 1),
+                    1,
+                );
+                frame.render_widget(
+                    Paragraph::new("optional, e.g. s3://bucket/prefix/")
+                        .style(Style::default().fg(theme.text_muted())),
+                    hint_area,
+                );
+            }
+        } else if self.upload().is_err() {
+            let label_area = Rect::new(area.x, area.y, Self::LABEL_WIDTH, 1);
+            frame.render_widget(
+                Paragraph::new("S3 URI").style(
+                    Style::default()
+                        .fg(theme.error())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                label_area,
+            );
+        }
+    }
+
+    fn render_checkbox_row(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        label: &str,
+        checked: bool,
+        focused: bool,
+        theme: &Theme,
+    ) {
         let label_area = Rect::new(area.x, area.y, Self::LABEL_WIDTH, 1);
         frame.render_widget(Paragraph::new(""), label_area);
         let input_area = Rect::new(
@@ -314,15 +448,8 @@ impl ExportPopup {
             area.width.saturating_sub(Self::LABEL_WIDTH + 1),
             1,
         );
-        let checked = if self.option_enabled.get() {
-            "[x]"
-        } else {
-            "[ ]"
-        };
-        let text = format!(
-            "{checked} {}",
-            self.option_label.as_deref().unwrap_or_default()
-        );
+        let checked = if checked { "[x]" } else { "[ ]" };
+        let text = format!("{checked} {label}");
         let style = if focused {
             Style::default()
                 .fg(theme.accent())
@@ -395,12 +522,23 @@ impl crate::widgets::Widget for ExportPopup {
         let inner = block.inner(area).inner(Margin::new(1, 1));
 
         let mut rows = vec![Constraint::Length(1), Constraint::Length(1)];
+        if self.s3_input.is_some() {
+            rows.push(Constraint::Length(1));
+        }
         let mut checkbox_row = None;
         if self.option_label.is_some() {
             rows.push(Constraint::Length(1));
             rows.push(Constraint::Length(1));
             checkbox_row = Some(rows.len() - 1);
         }
+        let mut columns_row = None;
+        if self.columns_option.is_some() {
+            if checkbox_row.is_none() {
+                rows.push(Constraint::Length(1));
+            }
+            rows.push(Constraint::Length(1));
+            columns_row = Some(rows.len() - 1);
+        }
         rows.push(Constraint::Length(2));
         rows.push(Constraint::Length(1));
         let layout = Layout::vertical(rows).split(inner);
@@ -425,14 +563,30 @@ impl crate::widgets::Widget for ExportPopup {
             theme,
         );
 
+        if self.s3_input.is_some() {
+            self.render_upload_row(frame, layout[2], theme);
+        }
+
         if let Some(row) = checkbox_row {
-            self.render_option_row(
+            self.render_checkbox_row(
                 frame,
                 layout[row],
+                self.option_label.as_deref().unwrap_or_default(),
+                self.option_enabled.get(),
                 self.focus.get() == Focus::Checkbox,
                 theme,
             );
         }
+        if let (Some(row), Some(columns)) = (columns_row, self.columns_option.as_ref()) {
+            self.render_checkbox_row(
+                frame,
+                layout[row],
+                "Only the visible columns",
+                columns.get(),
+                self.focus.get() == Focus::Columns,
+                theme,
+            );
+        }
         let button_row = layout.len().saturating_sub(1);
         self.render_buttons(frame, layout[button_row], theme);
     }
@@ -458,6 +612,11 @@ impl crate::widgets::Widget for ExportPopup {
                 ctx.invalidate();
                 return true;
             }
+            KeyCode::Char('z') if self.compression && key.modifiers == KeyModifiers::CONTROL => {
+                self.cycle_compression();
+                ctx.invalidate();
+                return true;
+            }
             _ => {}
         }
 
@@ -476,6 +635,14 @@ impl crate::widgets::Widget for ExportPopup {
                     return true;
                 }
             }
+            Focus::Upload => {
+                if let Some(input) = self.s3_input.as_ref()
+                    && input.borrow_mut().handle_key(&key)
+                {
+                    ctx.invalidate();
+                    return true;
+                }
+            }
             Focus::Checkbox => {
                 if matches!(key.code, KeyCode::Char(' ' | 'f') | KeyCode::Enter) {
                     self.toggle_option();
@@ -488,6 +655,20 @@ impl crate::widgets::Widget for ExportPopup {
                     return true;
                 }
             }
+            Focus::Columns => {
+                if let Some(columns) = self.columns_option.as_ref()
+                    && matches!(key.code, KeyCode::Char(' ') | KeyCode::Enter)
+                {
+                    columns.set(!columns.get());
+                    ctx.invalidate();
+                    return true;
+                }
+                if matches!(key.code, KeyCode::Left | KeyCode::Right) {
+                    self.next_focus();
+                    ctx.invalidate();
+                    return true;
+                }
+            }
             Focus::Export | Focus::Cancel => {
                 if matches!(key.code, KeyCode::Left | KeyCode::Right) {
                     let next = if self.focus.get() == Focus::Export {
@@ -501,8 +682,12 @@ impl crate::widgets::Widget for ExportPopup {
                 }
                 if matches!(key.code, KeyCode::Enter) {
                     if self.focus.get() == Focus::Export && self.export_enabled() {
-                        let path = self.build_path();
-                        (self.on_confirm)(path, self.option_enabled.get());
+                        (self.on_confirm)(ExportChoice {
+                            path: self.build_path(),
+                            option: self.option_enabled.get(),
+                            upload: self.upload().ok().flatten(),
+                            visible_columns: self.columns_option.as_ref().is_some_and(Cell::get),
+                        });
                         ctx.dismiss_popup();
                         ctx.invalidate();
                         return true;
@@ -521,7 +706,13 @@ impl crate::widgets::Widget for ExportPopup {
 
 impl Popup for ExportPopup {
     fn rect(&self, area: Rect) -> Rect {
-        let content_height = if self.option_label.is_some() { 7 } else { 5 };
+        let mut content_height = if self.option_label.is_some() { 7 } else { 5 };
+        if self.s3_input.is_some() {
+            content_height += 1;
+        }
+        if self.columns_option.is_some() {
+            content_height += if self.option_label.is_some() { 1 } else { 2 };
+        }
         let min_height = content_height as u16 + 4;
         let height = min_height.min(area.height.saturating_sub(2));
         let min_width = 44;
@@ -0,0 +1,372 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use dynamate::dynamodb::json;
+use flate2::write::GzEncoder;
+
+use super::widget::ensure_export_parent;
+
+const EXPORT_FORMAT_ENV: &str = "DYNAMATE_EXPORT_FORMAT";
+
+/// On-disk layout of an export, picked from the file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ExportFormat {
+    /// A single pretty-printed JSON array.
+    Json,
+    /// One compact JSON document per line (`.ndjson` / `.jsonl`).
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub(super) fn from_path(path: &Path) -> Self {
+        let path = match Compression::from_path(path) {
+            Compression::None => path,
+            Compression::Gzip | Compression::Zstd => {
+                Path::new(path.file_stem().unwrap_or_default())
+            }
+        };
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("ndjson" | "jsonl") => Self::Ndjson,
+            _ => Self::Json,
+        }
+    }
+
+    /// The format suggested for new export file names, from
+    /// `DYNAMATE_EXPORT_FORMAT` (`json` or `ndjson`; defaults to `json`).
+    pub(super) fn preferred() -> Self {
+        match env::var(EXPORT_FORMAT_ENV)
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Ok("ndjson" | "jsonl") => Self::Ndjson,
+            _ => Self::Json,
+        }
+    }
+
+    pub(super) fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// How an export is compressed on disk, picked from a trailing `.gz` or
+/// `.zst` after the format's extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub(super) fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("gz") => Self::Gzip,
+            Some("zst") => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+
+    fn extension(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gz"),
+            Self::Zstd => Some("zst"),
+        }
+    }
+
+    /// `file_name` with its compression suffix swapped for the next one in
+    /// none, gzip, zstd order.
+    pub(super) fn cycle(file_name: &str) -> String {
+        let current = Self::from_path(Path::new(file_name));
+        let base = current
+            .extension()
+            .and_then(|ext| file_name.get(..file_name.len() - ext.len() - 1))
+            .unwrap_or(file_name);
+        let next = match current {
+            Self::None => Self::Gzip,
+            Self::Gzip => Self::Zstd,
+            Self::Zstd => Self::None,
+        };
+        match next.extension() {
+            Some(ext) => format!("{base}.{ext}"),
+            None => base.to_string(),
+        }
+    }
+}
+
+/// An export file, streamed through an encoder when its name asks for
+/// compression. [`ExportFile::finish`] has to be called to write the
+/// compressed stream's trailer.
+pub(super) enum ExportFile {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl ExportFile {
+    /// Create `file`, compressed as `path`'s name says; `file` may be a temp
+    /// file that is renamed to `path` later.
+    pub(super) fn create(file: &Path, path: &Path) -> Result<Self, String> {
+        let writer = BufWriter::new(File::create(file).map_err(|err| err.to_string())?);
+        Ok(match Compression::from_path(path) {
+            Compression::None => Self::Plain(writer),
+            Compression::Gzip => Self::Gzip(GzEncoder::new(writer, flate2::Compression::default())),
+            Compression::Zstd => {
+                Self::Zstd(zstd::Encoder::new(writer, 0).map_err(|err| err.to_string())?)
+            }
+        })
+    }
+
+    fn is_compressed(&self) -> bool {
+        !matches!(self, Self::Plain(_))
+    }
+
+    pub(super) fn finish(self) -> Result<(), String> {
+        let mut writer = match self {
+            Self::Plain(writer) => writer,
+            Self::Gzip(encoder) => encoder.finish().map_err(|err| err.to_string())?,
+            Self::Zstd(encoder) => encoder.finish().map_err(|err| err.to_string())?,
+        };
+        writer.flush().map_err(|err| err.to_string())
+    }
+}
+
+impl Write for ExportFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Appends one JSON line per item straight to the destination. Unlike the
+/// JSON array writer there is no temp file: every line is a complete document,
+/// so a canceled or failed export leaves a usable partial file behind.
+///
+/// Uncompressed output is flushed after every page, so progress is on disk
+/// while a long export runs and survives the app being killed. Compressed
+/// output isn't, as flushing an encoder mid-stream costs compression; it is
+/// finished when the writer is dropped instead, and is only readable once
+/// that happens.
+pub(super) struct StreamedNdjsonWriter {
+    path: PathBuf,
+    /// Taken by [`Self::finish`]; still here when dropped on cancel or
+    /// failure.
+    writer: Option<ExportFile>,
+    count: usize,
+}
+
+impl StreamedNdjsonWriter {
+    pub(super) fn create(path: &Path) -> Result<Self, String> {
+        ensure_export_parent(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Some(ExportFile::create(path, path)?),
+            count: 0,
+        })
+    }
+
+    pub(super) fn write_items(
+        &mut self,
+        items: &[HashMap<String, AttributeValue>],
+    ) -> Result<(), String> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| "Export writer is closed".to_string())?;
+        for item in items {
+            let value = json::to_json(item)
+                .map_err(|err| format!("Failed to convert item {}: {err}", self.count + 1))?;
+            serde_json::to_writer(&mut *writer, &value).map_err(|err| err.to_string())?;
+            writer.write_all(b"\n").map_err(|err| err.to_string())?;
+            self.count += 1;
+        }
+        if writer.is_compressed() {
+            return Ok(());
+        }
+        writer
+            .flush()
+            .map_err(|err| format!("Failed to write {}: {err}", self.path.display()))
+    }
+
+    pub(super) fn finish(mut self) -> Result<usize, String> {
+        self.writer
+            .take()
+            .ok_or_else(|| "Export writer is closed".to_string())?
+            .finish()?;
+        Ok(self.count)
+    }
+}
+
+impl Drop for StreamedNdjsonWriter {
+    fn drop(&mut self) {
+        // Write the compressed stream's trailer so the lines so far can be
+        // read back.
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.finish();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        env, fs,
+        io::{Read, Write},
+        path::Path,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::{Compression, ExportFile, ExportFormat, StreamedNdjsonWriter};
+
+    #[test]
+    fn format_follows_extension() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.ndjson")),
+            ExportFormat::Ndjson
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.JSONL")),
+            ExportFormat::Ndjson
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.json")),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out")),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.ndjson.zst")),
+            ExportFormat::Ndjson
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.json.gz")),
+            ExportFormat::Json
+        );
+    }
+
+    #[test]
+    fn compresses_by_suffix() {
+        assert_eq!(Compression::cycle("out.json"), "out.json.gz");
+        assert_eq!(Compression::cycle("out.json.gz"), "out.json.zst");
+        assert_eq!(Compression::cycle("out.json.zst"), "out.json");
+
+        let payload = b"[{\"PK\": \"USER#1\"}]";
+        for suffix in ["gz", "zst"] {
+            let path = env::temp_dir().join(format!(
+                "dynamate-export-test-{}.json.{suffix}",
+                std::process::id()
+            ));
+            let mut file = ExportFile::create(&path, &path).expect("file should be created");
+            file.write_all(payload).expect("payload should be written");
+            file.finish().expect("file should finish");
+
+            let compressed = fs::read(&path).expect("export file should exist");
+            let mut decoded = Vec::new();
+            if suffix == "gz" {
+                flate2::read::GzDecoder::new(compressed.as_slice())
+                    .read_to_end(&mut decoded)
+                    .expect("gzip should decode");
+            } else {
+                decoded = zstd::decode_all(compressed.as_slice()).expect("zstd should decode");
+            }
+            assert_eq!(decoded, payload);
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn ndjson_writer_appends_one_line_per_item_across_pages() {
+        let path = env::temp_dir().join(format!(
+            "dynamate-export-test-{}-{}.ndjson",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        ));
+        let item =
+            |pk: &str| HashMap::from([("PK".to_string(), AttributeValue::S(pk.to_string()))]);
+
+        let mut writer = StreamedNdjsonWriter::create(&path).expect("writer should be created");
+        writer
+            .write_items(&[item("USER#1"), item("USER#2")])
+            .expect("first page should be written");
+        // The first page is already on disk before the export finishes.
+        let partial = fs::read_to_string(&path).expect("export file should exist");
+        assert_eq!(partial.lines().count(), 2);
+        writer
+            .write_items(&[item("USER#3")])
+            .expect("second page should be written");
+        let count = writer.finish().expect("writer should finish");
+
+        let payload = fs::read_to_string(&path).expect("export file should exist");
+        let lines: Vec<serde_json::Value> = payload
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line should be json"))
+            .collect();
+        assert_eq!(count, 3);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2]["PK"], "USER#3");
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn compressed_ndjson_is_readable_after_a_canceled_export() {
+        let path = env::temp_dir().join(format!(
+            "dynamate-export-test-{}-canceled.ndjson.gz",
+            std::process::id()
+        ));
+        let item = HashMap::from([("PK".to_string(), AttributeValue::S("USER#1".to_string()))]);
+
+        let mut writer = StreamedNdjsonWriter::create(&path).expect("writer should be created");
+        writer
+            .write_items(&[item])
+            .expect("first page should be written");
+        drop(writer);
+
+        let compressed = fs::read(&path).expect("export file should exist");
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decoded)
+            .expect("gzip should decode");
+        assert_eq!(decoded.lines().count(), 1);
+
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,131 @@
+//! Following a reference attribute to the item it names, by the `follow`
+//! rules in the config file: the rule's key template is filled in from the
+//! item, and the target table opens on a query for that key.
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use dynamate::core::language::{Condition, ConditionOp, ConditionValue};
+
+use super::projection;
+
+/// The conditions matching the key `key` templates name, filled in from
+/// `item`: `{value}` is the value at `attribute`, and `{name}` the value of
+/// the item's `name` attribute. A template that is just one placeholder keeps
+/// the value's type, so numeric keys stay numbers.
+pub(super) fn key_conditions(
+    key: &[(String, String)],
+    attribute: &str,
+    item: &HashMap<String, AttributeValue>,
+) -> Result<Vec<Condition>, String> {
+    key.iter()
+        .map(|(name, template)| {
+            Ok(Condition {
+                attribute: name.clone(),
+                op: ConditionOp::Equal,
+                value: fill(template, attribute, item)?,
+            })
+        })
+        .collect()
+}
+
+/// The key of the item `key` templates name, filled in from `item` like
+/// [`key_conditions`], as attribute values to look the item up by.
+pub(super) fn key_item(
+    key: &[(String, String)],
+    attribute: &str,
+    item: &HashMap<String, AttributeValue>,
+) -> Result<HashMap<String, AttributeValue>, String> {
+    key.iter()
+        .map(|(name, template)| {
+            let value = match fill(template, attribute, item)? {
+                ConditionValue::String(text) => AttributeValue::S(text),
+                ConditionValue::Number(number) => AttributeValue::N(number),
+                ConditionValue::Boolean(value) => AttributeValue::Bool(value),
+            };
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+fn fill(
+    template: &str,
+    attribute: &str,
+    item: &HashMap<String, AttributeValue>,
+) -> Result<ConditionValue, String> {
+    let lookup = |name: &str| {
+        let path = if name == "value" { attribute } else { name };
+        match projection::attribute_at(item, path) {
+            Some(AttributeValue::S(text)) => Ok(ConditionValue::String(text.clone())),
+            Some(AttributeValue::N(number)) => Ok(ConditionValue::Number(number.clone())),
+            Some(_) => Err(format!("{path} is not a string or a number")),
+            None => Err(format!("the item has no {path}")),
+        }
+    };
+    if let Some(name) = template
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .filter(|name| !name.contains(['{', '}']))
+    {
+        return lookup(name);
+    }
+    let mut filled = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        filled.push_str(&rest[..start]);
+        match lookup(&rest[start + 1..start + end])? {
+            ConditionValue::String(text) | ConditionValue::Number(text) => {
+                filled.push_str(&text);
+            }
+            ConditionValue::Boolean(value) => filled.push_str(&value.to_string()),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    filled.push_str(rest);
+    Ok(ConditionValue::String(filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+    use dynamate::core::language::ConditionValue;
+
+    use super::key_conditions;
+
+    #[test]
+    fn fills_key_templates_from_the_item() {
+        let item = HashMap::from([
+            (
+                "customerId".to_string(),
+                AttributeValue::S("42".to_string()),
+            ),
+            ("region".to_string(), AttributeValue::S("eu".to_string())),
+            ("storeId".to_string(), AttributeValue::N("7".to_string())),
+        ]);
+        let key = [
+            ("PK".to_string(), "CUSTOMER#{value}".to_string()),
+            ("SK".to_string(), "REGION#{region}#PROFILE".to_string()),
+        ];
+        let conditions = key_conditions(&key, "customerId", &item).unwrap();
+        let values: Vec<_> = conditions.iter().map(|c| c.value.clone()).collect();
+        assert_eq!(
+            values,
+            [
+                ConditionValue::String("CUSTOMER#42".to_string()),
+                ConditionValue::String("REGION#eu#PROFILE".to_string())
+            ]
+        );
+
+        let key = [("id".to_string(), "{value}".to_string())];
+        let conditions = key_conditions(&key, "storeId", &item).unwrap();
+        assert_eq!(conditions[0].value, ConditionValue::Number("7".to_string()));
+
+        let key = [("PK".to_string(), "ORDER#{orderId}".to_string())];
+        assert!(key_conditions(&key, "customerId", &item).is_err());
+    }
+}
@@ -0,0 +1,528 @@
+//! Joining related items into the results (`H`): for each shown row, the item
+//! a `follow` rule's reference names is fetched with batched key lookups, a
+//! few batches at a time, and the chosen attributes of it are shown in extra
+//! columns named `reference→attribute`. The columns are only displayed; the
+//! rows themselves are left alone, so edits never write them back.
+//!
+//! Each step comes back as one of the events here; [`handle`] takes it from
+//! there and tells the query widget what it has to do about it.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    sync::Arc,
+    time::Duration,
+};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use futures::StreamExt;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::{datastore::Datastore, query::Key};
+use dynamate::dynamodb::convert::{attribute_map_from_item, item_from_attribute_map};
+
+use super::{follow, projection};
+use crate::{
+    config::FollowRule,
+    env::{AppEvent, Toast, ToastKind, WidgetCtx, WidgetId},
+    help,
+    util::{self, fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, theme::Theme},
+};
+
+/// How many key lookups run at once.
+const CONCURRENCY: usize = 4;
+/// Keys per lookup, the most `BatchGetItem` takes.
+const BATCH_SIZE: usize = 100;
+
+/// Join `attributes` of the items `rule` refers to into the results.
+struct HydrateRequest {
+    rule: &'static FollowRule,
+    attributes: Vec<String>,
+}
+
+/// Remove the joined columns.
+struct ClearHydrationRequest;
+
+/// The related items looked up for the rows.
+struct HydratedEvent(Result<Hydration, String>);
+
+/// What a join needs from the results it applies to.
+pub(super) struct JoinSource {
+    pub(super) db: Arc<dyn Datastore>,
+    /// The rows shown, which the related items are looked up for.
+    pub(super) rows: Vec<HashMap<String, AttributeValue>>,
+}
+
+/// Where a join got to, for the query widget to act on.
+pub(super) enum HydrateStep {
+    /// The lookup is under way; nothing to do.
+    Continuing,
+    /// Show these joined columns in place of the ones before, or none.
+    Joined(Option<Hydration>),
+    Failed(String),
+}
+
+/// The related items joined into the results, and the columns they fill.
+#[derive(Debug, Clone)]
+pub(super) struct Hydration {
+    pub(super) target: String,
+    attribute: String,
+    key: Vec<(String, String)>,
+    /// Each column, and the attribute of the related item it shows.
+    columns: Vec<(String, String)>,
+    /// The related items found, by their key.
+    related: HashMap<String, HashMap<String, AttributeValue>>,
+    /// How many distinct related items the rows refer to.
+    pub(super) requested: usize,
+}
+
+impl Hydration {
+    pub(super) fn columns(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(column, _)| column.as_str())
+    }
+
+    /// The joined attributes, as typed in the popup.
+    pub(super) fn attributes(&self) -> String {
+        self.columns
+            .iter()
+            .map(|(_, attribute)| attribute.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub(super) fn found(&self) -> usize {
+        self.related.len()
+    }
+
+    /// Add the joined columns for the row `source` to `shown`, the attributes
+    /// the row displays.
+    pub(super) fn merge(
+        &self,
+        source: &HashMap<String, AttributeValue>,
+        shown: &mut HashMap<String, AttributeValue>,
+    ) {
+        let Some(related) = follow::key_item(&self.key, &self.attribute, source)
+            .ok()
+            .and_then(|key| self.related.get(&key_text(&key)))
+        else {
+            return;
+        };
+        for (column, attribute) in &self.columns {
+            if let Some(value) = projection::attribute_at(related, attribute) {
+                shown.insert(column.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Look up the items `rule` says `rows` refer to, in batches, and keep
+/// `attributes` of them. Rows whose reference can't be made into a key are
+/// skipped.
+async fn hydrate(
+    db: Arc<dyn Datastore>,
+    rule: &FollowRule,
+    attributes: Vec<String>,
+    rows: Vec<HashMap<String, AttributeValue>>,
+) -> Result<Hydration, String> {
+    let mut keys: HashMap<String, HashMap<String, AttributeValue>> = HashMap::new();
+    for row in &rows {
+        if let Ok(key) = follow::key_item(&rule.key, &rule.attribute, row) {
+            keys.entry(key_text(&key)).or_insert(key);
+        }
+    }
+    let requested = keys.len();
+    let keys: Vec<Key> = keys
+        .into_values()
+        .map(|key| Key(item_from_attribute_map(&key)))
+        .collect();
+    let mut batches = futures::stream::iter(keys.chunks(BATCH_SIZE).map(<[Key]>::to_vec))
+        .map(|batch| {
+            let db = db.clone();
+            let target = rule.target.clone();
+            async move {
+                dynamate::throttle::acquire().await;
+                db.batch_get(&target, batch, false).await
+            }
+        })
+        .buffer_unordered(CONCURRENCY);
+    let names: Vec<&str> = rule.key.iter().map(|(name, _)| name.as_str()).collect();
+    let mut related = HashMap::new();
+    while let Some(found) = batches.next().await {
+        for item in found.map_err(|err| err.to_string())? {
+            let item = attribute_map_from_item(&item);
+            let key: HashMap<String, AttributeValue> = names
+                .iter()
+                .filter_map(|name| Some(((*name).to_string(), item.get(*name)?.clone())))
+                .collect();
+            related.insert(key_text(&key), item);
+        }
+    }
+    Ok(Hydration {
+        target: rule.target.clone(),
+        attribute: rule.attribute.clone(),
+        key: rule.key.clone(),
+        columns: attributes
+            .into_iter()
+            .map(|attribute| (format!("{}→{attribute}", rule.attribute), attribute))
+            .collect(),
+        related,
+        requested,
+    })
+}
+
+/// A key's values in attribute name order, to match lookups with the items
+/// they found.
+fn key_text(key: &HashMap<String, AttributeValue>) -> String {
+    let mut names: Vec<&String> = key.keys().collect();
+    names.sort_unstable();
+    names
+        .into_iter()
+        .map(|name| match &key[name] {
+            AttributeValue::S(text) | AttributeValue::N(text) => format!("{name}={text}"),
+            other => format!("{name}={other:?}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\u{1f}")
+}
+
+/// Pick a reference from `table_name` whose related items to join, starting
+/// from the `current` attributes.
+pub(super) fn prompt(
+    db: &dyn Datastore,
+    table_name: &str,
+    current: &str,
+    parent: WidgetId,
+    ctx: &WidgetCtx,
+) -> Result<(), String> {
+    if !db.capabilities().batch_get {
+        return Err("This backend has no batch key lookups to join with".to_string());
+    }
+    let rules: Vec<_> = util::follow_rules(table_name).collect();
+    if rules.is_empty() {
+        ctx.show_toast(Toast {
+            message: format!(
+                "No references from {table_name}: add rules under \"follow\" in config.json"
+            ),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(4),
+            action: None,
+        });
+        return Ok(());
+    }
+    let ctx_for_submit = ctx.clone();
+    let ctx_for_clear = ctx.clone();
+    let popup = HydratePopup::new(
+        rules,
+        current,
+        move |rule, attributes| ctx_for_submit.emit_self(HydrateRequest { rule, attributes }),
+        move || ctx_for_clear.emit_self(ClearHydrationRequest),
+        parent,
+    );
+    ctx.set_popup(Box::new(popup));
+    Ok(())
+}
+
+/// Take the join one step further if `event` is one of its own. `source` is
+/// only built then.
+pub(super) fn handle(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    source: impl FnOnce() -> JoinSource,
+) -> Option<HydrateStep> {
+    if let Some(request) = event.payload::<HydrateRequest>() {
+        let JoinSource { db, rows } = source();
+        ctx.show_toast(Toast {
+            message: format!(
+                "Looking up {} items for {} rows",
+                request.rule.target,
+                rows.len()
+            ),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(2),
+            action: None,
+        });
+        let rule = request.rule;
+        let attributes = request.attributes.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let result = hydrate(db, rule, attributes, rows).await;
+            ctx.emit_self(HydratedEvent(result));
+        });
+        return Some(HydrateStep::Continuing);
+    }
+    if event.payload::<ClearHydrationRequest>().is_some() {
+        return Some(HydrateStep::Joined(None));
+    }
+    let HydratedEvent(result) = event.payload::<HydratedEvent>()?;
+    Some(match result {
+        Ok(hydration) => {
+            ctx.show_toast(Toast {
+                message: format!(
+                    "Joined {} of {} {} items",
+                    hydration.found(),
+                    hydration.requested,
+                    hydration.target
+                ),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(3),
+                action: None,
+            });
+            HydrateStep::Joined(Some(hydration.clone()))
+        }
+        Err(err) => HydrateStep::Failed(format!("Join failed: {err}")),
+    })
+}
+
+/// Picks the reference to join on and the attributes to show.
+pub(super) struct HydratePopup {
+    inner: WidgetInner,
+    rules: Vec<&'static FollowRule>,
+    selected: Cell<usize>,
+    state: RefCell<HydrateState>,
+    on_submit: Box<dyn Fn(&'static FollowRule, Vec<String>) + Send + 'static>,
+    on_clear: Box<dyn Fn() + Send + 'static>,
+}
+
+struct HydrateState {
+    input: TextInput,
+    error: Option<String>,
+}
+
+impl HydratePopup {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓"),
+            short: Cow::Borrowed("reference"),
+            long: Cow::Borrowed("Pick the reference to join on"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("join"),
+            long: Cow::Borrowed("Fetch the related items and show the attributes"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("^d"),
+            short: Cow::Borrowed("remove"),
+            long: Cow::Borrowed("Remove the joined columns"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("cancel"),
+            long: Cow::Borrowed("Cancel"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    pub(super) fn new(
+        rules: Vec<&'static FollowRule>,
+        attributes: &str,
+        on_submit: impl Fn(&'static FollowRule, Vec<String>) + Send + 'static,
+        on_clear: impl Fn() + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut input = TextInput::new("Attributes", attributes);
+        input.set_active(true);
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            rules,
+            selected: Cell::new(0),
+            state: RefCell::new(HydrateState { input, error: None }),
+            on_submit: Box::new(on_submit),
+            on_clear: Box::new(on_clear),
+        }
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let attributes: Vec<String> = {
+            let mut state = self.state.borrow_mut();
+            let attributes: Vec<String> = state
+                .input
+                .value()
+                .split(',')
+                .map(str::trim)
+                .filter(|attribute| !attribute.is_empty())
+                .map(str::to_string)
+                .collect();
+            if attributes.is_empty() {
+                state.error = Some("Name the attributes to show".to_string());
+                return;
+            }
+            attributes
+        };
+        let Some(rule) = self.rules.get(self.selected.get()) else {
+            return;
+        };
+        ctx.dismiss_popup();
+        (self.on_submit)(rule, attributes);
+    }
+}
+
+impl crate::widgets::Widget for HydratePopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Join related items", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let rows = u16::try_from(self.rules.len()).unwrap_or(u16::MAX);
+        let [rules_area, input_area, status_area] = inner.layout(&Layout::vertical([
+            Constraint::Length(rows),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]));
+        let lines: Vec<Line> = self
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(row, rule)| {
+                let selected = row == self.selected.get();
+                let style = if selected {
+                    Style::default()
+                        .bg(theme.selection_bg())
+                        .fg(theme.selection_fg())
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::raw(if selected { "> " } else { "  " }),
+                    Span::styled(format!("{} → {}", rule.attribute, rule.target), style),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), rules_area);
+
+        let state = self.state.borrow();
+        state.input.render(frame, input_area, theme);
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (
+                "Attributes of the related item, e.g. name, email",
+                Style::default().fg(theme.text_muted()),
+            ),
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Up => self.selected.set(self.selected.get().saturating_sub(1)),
+            KeyCode::Down => self
+                .selected
+                .set((self.selected.get() + 1).min(self.rules.len().saturating_sub(1))),
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                ctx.dismiss_popup();
+                (self.on_clear)();
+            }
+            _ => {
+                let mut state = self.state.borrow_mut();
+                if state.input.handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for HydratePopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 64.min(area.width.saturating_sub(4));
+        // The references, the input, the status line, and the borders.
+        let rows = u16::try_from(self.rules.len()).unwrap_or(u16::MAX);
+        let height = rows.saturating_add(6).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::{Hydration, key_text};
+
+    #[test]
+    fn merges_the_related_items_attributes_into_columns() {
+        let s = |text: &str| AttributeValue::S(text.to_string());
+        let customer = HashMap::from([
+            ("PK".to_string(), s("CUSTOMER#42")),
+            ("name".to_string(), s("Ada")),
+        ]);
+        let key = HashMap::from([("PK".to_string(), s("CUSTOMER#42"))]);
+        let hydration = Hydration {
+            target: "customers".to_string(),
+            attribute: "customerId".to_string(),
+            key: vec![("PK".to_string(), "CUSTOMER#{value}".to_string())],
+            columns: vec![
+                ("customerId→name".to_string(), "name".to_string()),
+                ("customerId→email".to_string(), "email".to_string()),
+            ],
+            related: HashMap::from([(key_text(&key), customer)]),
+            requested: 2,
+        };
+
+        let order = HashMap::from([("customerId".to_string(), s("42"))]);
+        let mut shown = order.clone();
+        hydration.merge(&order, &mut shown);
+        assert_eq!(shown.get("customerId→name"), Some(&s("Ada")));
+        assert!(!shown.contains_key("customerId→email"));
+
+        let other = HashMap::from([("customerId".to_string(), s("7"))]);
+        let mut shown = other.clone();
+        hydration.merge(&other, &mut shown);
+        assert_eq!(shown, other);
+    }
+}
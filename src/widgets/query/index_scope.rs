@@ -0,0 +1,222 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::KeyCode;
+use dynamate::core::schema::{IndexKind, IndexSchema, KeyRole, Projection};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    prelude::StatefulWidget,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, HighlightSpacing, Row, Table, TableState},
+};
+
+use crate::{
+    help,
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Picks what the query view browses: the table itself or one of its
+/// secondary indexes.
+pub struct IndexScopePicker {
+    inner: WidgetInner,
+    indexes: Vec<IndexSchema>,
+    state: RefCell<TableState>,
+    on_select: Box<dyn Fn(Option<String>) + Send + 'static>,
+}
+
+impl IndexScopePicker {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓/j/k"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("browse"),
+            long: Cow::Borrowed("Browse the table or index"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close"),
+            long: Cow::Borrowed("Close picker"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    /// `current` is the index being browsed, if any; it starts selected.
+    pub fn new(
+        indexes: Vec<IndexSchema>,
+        current: Option<&str>,
+        on_select: impl Fn(Option<String>) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let selected = current
+            .and_then(|name| indexes.iter().position(|index| index.name == name))
+            .map_or(0, |idx| idx + 1);
+        let mut state = TableState::default();
+        state.select(Some(selected));
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            indexes,
+            state: RefCell::new(state),
+            on_select: Box::new(on_select),
+        }
+    }
+}
+
+impl crate::widgets::Widget for IndexScopePicker {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::styled(
+                "Browse",
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+
+        let header = Row::new(vec![
+            Line::from("Index"),
+            Line::from("Key"),
+            Line::from("Attributes"),
+        ])
+        .style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let table_row = Row::new(vec![Line::from("Table"), Line::from(""), Line::from("all")]);
+        let rows = std::iter::once(table_row).chain(self.indexes.iter().map(|index| {
+            Row::new(vec![
+                Line::from(format!("{} ({})", index.name, kind_label(index.kind))),
+                Line::from(key_label(index)),
+                Line::from(projection_label(&index.projection)),
+            ])
+        }));
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(28),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .block(block)
+        .header(header)
+        .highlight_spacing(HighlightSpacing::Always)
+        .highlight_symbol(">")
+        .row_highlight_style(
+            Style::default()
+                .bg(theme.selection_bg())
+                .fg(theme.selection_fg()),
+        );
+
+        let mut state = self.state.borrow_mut();
+        StatefulWidget::render(table, area, frame.buffer_mut(), &mut state);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &crossterm::event::Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                ctx.dismiss_popup();
+                ctx.invalidate();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.state.borrow_mut().scroll_up_by(1);
+                ctx.invalidate();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let mut state = self.state.borrow_mut();
+                let last = self.indexes.len();
+                let next = state.selected().map_or(0, |idx| (idx + 1).min(last));
+                state.select(Some(next));
+                ctx.invalidate();
+            }
+            KeyCode::Enter => {
+                let selected = self.state.borrow().selected().unwrap_or(0);
+                let index = selected
+                    .checked_sub(1)
+                    .and_then(|idx| self.indexes.get(idx))
+                    .map(|index| index.name.clone());
+                ctx.dismiss_popup();
+                (self.on_select)(index);
+                ctx.invalidate();
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+impl Popup for IndexScopePicker {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = (area.width as f32 * 0.7) as u16;
+        let width = width.max(60).min(area.width.saturating_sub(4));
+        // One row per index plus the table row, the header, and the borders.
+        let height = (self.indexes.len() as u16 + 4).min(area.height.saturating_sub(4));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+fn kind_label(kind: IndexKind) -> &'static str {
+    match kind {
+        IndexKind::GlobalSecondary => "GSI",
+        IndexKind::LocalSecondary => "LSI",
+        IndexKind::Secondary | IndexKind::Composite => "index",
+    }
+}
+
+fn key_label(index: &IndexSchema) -> String {
+    index
+        .key
+        .fields
+        .iter()
+        .map(|field| match field.role {
+            KeyRole::Partition => field.name.clone(),
+            KeyRole::Sort => format!("{} (sort)", field.name),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The attributes an index returns, as shown in the picker and the view title.
+pub(super) fn projection_label(projection: &Projection) -> String {
+    match projection {
+        Projection::All => "all".to_string(),
+        Projection::KeysOnly => "keys only".to_string(),
+        Projection::Include(attrs) => format!("keys, {}", attrs.join(", ")),
+    }
+}
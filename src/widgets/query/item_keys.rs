@@ -6,6 +6,9 @@ use dynamate::core::schema::{CollectionSchema, IndexKind, KeySchema};
 pub struct ItemKeys {
     set: HashSet<String>,
     hidden: HashSet<String>,
+    /// Nested path columns added from the fields popup, e.g.
+    /// `metadata.version`, shown after the attributes in the order added.
+    paths: Vec<String>,
     sorted: Vec<String>,
     visible: Vec<String>,
 }
@@ -44,6 +47,7 @@ impl ItemKeys {
                 .then_with(|| a.cmp(b))
         });
         self.sorted = keys;
+        self.append_paths();
         self.update_visible();
     }
 
@@ -52,9 +56,43 @@ impl ItemKeys {
         let mut keys: Vec<String> = self.set.iter().cloned().collect();
         keys.sort();
         self.sorted = keys;
+        self.append_paths();
         self.update_visible();
     }
 
+    /// Add a nested path column. Returns `false` if it's already a column.
+    pub fn add_path(&mut self, path: &str) -> bool {
+        if self.set.contains(path) || self.paths.iter().any(|known| known == path) {
+            return false;
+        }
+        self.paths.push(path.to_string());
+        self.sorted.push(path.to_string());
+        self.update_visible();
+        true
+    }
+
+    pub fn remove_path(&mut self, path: &str) {
+        self.paths.retain(|known| known != path);
+        self.hidden.remove(path);
+        self.sorted
+            .retain(|key| key != path || self.set.contains(key));
+        self.update_visible();
+    }
+
+    pub fn is_path(&self, key: &str) -> bool {
+        self.paths.iter().any(|path| path == key)
+    }
+
+    fn append_paths(&mut self) {
+        let extra: Vec<String> = self
+            .paths
+            .iter()
+            .filter(|path| !self.set.contains(*path))
+            .cloned()
+            .collect();
+        self.sorted.extend(extra);
+    }
+
     /// Sorted keys including hidden fields.
     pub fn sorted(&self) -> &[String] {
         &self.sorted
@@ -79,11 +117,13 @@ impl ItemKeys {
         self.hidden.contains(key)
     }
 
+    /// Forget the attributes seen so far. Path columns were chosen by the
+    /// user rather than seen in items, so they stay.
     pub fn clear(&mut self) {
         self.set.clear();
         self.hidden.clear();
-        self.sorted.clear();
-        self.visible.clear();
+        self.sorted.clone_from(&self.paths);
+        self.update_visible();
     }
 
     fn update_visible(&mut self) {
@@ -160,3 +200,25 @@ fn rank(name: &str, ordering: &KeyOrdering) -> u8 {
         6
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ItemKeys;
+
+    #[test]
+    fn path_columns_follow_attributes_and_survive_clear() {
+        let mut keys = ItemKeys::default();
+        keys.extend_unordered(["b".to_string(), "a".to_string()]);
+        assert!(keys.add_path("meta.version"));
+        assert!(!keys.add_path("a"));
+        assert_eq!(keys.sorted(), ["a", "b", "meta.version"]);
+
+        keys.extend_unordered(["c".to_string()]);
+        assert_eq!(keys.sorted(), ["a", "b", "c", "meta.version"]);
+
+        keys.clear();
+        assert_eq!(keys.visible(), ["meta.version"]);
+        keys.remove_path("meta.version");
+        assert!(keys.sorted().is_empty());
+    }
+}
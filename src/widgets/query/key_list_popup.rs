@@ -0,0 +1,188 @@
+use std::{borrow::Cow, cell::RefCell, path::Path};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::{key_list::parse_key_list, query::Key, schema::KeySchema};
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, theme::Theme},
+};
+
+/// Asks for a list of primary keys to fetch in one batch: the path of a keys
+/// file, or the keys themselves typed inline.
+pub(super) struct KeyListPopup {
+    inner: WidgetInner,
+    schema: KeySchema,
+    hint: String,
+    state: RefCell<KeyListState>,
+    on_submit: Box<dyn Fn(Vec<Key>) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct KeyListState {
+    input: TextInput,
+    error: Option<String>,
+}
+
+impl KeyListPopup {
+    pub(super) fn new(
+        schema: KeySchema,
+        on_submit: impl Fn(Vec<Key>) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut input = TextInput::new("Keys", String::new()).with_placeholder("keys.ndjson");
+        input.set_active(true);
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("⏎"),
+                short: Cow::Borrowed("get"),
+                long: Cow::Borrowed("Fetch the items for these keys"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("cancel"),
+                long: Cow::Borrowed("Cancel"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            hint: hint(&schema),
+            schema,
+            state: RefCell::new(KeyListState { input, error: None }),
+            on_submit: Box::new(on_submit),
+            help_entries,
+        }
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let keys = {
+            let mut state = self.state.borrow_mut();
+            match read_keys(state.input.value().trim(), &self.schema) {
+                Ok(keys) => keys,
+                Err(err) => {
+                    state.error = Some(err);
+                    return;
+                }
+            }
+        };
+        (self.on_submit)(keys);
+        ctx.dismiss_popup();
+    }
+}
+
+/// What can be entered, for the table's key shape.
+fn hint(schema: &KeySchema) -> String {
+    match schema.fields.as_slice() {
+        [_] => "A keys file, or values separated by commas".to_string(),
+        fields => {
+            let pairs: Vec<String> = fields
+                .iter()
+                .map(|field| format!("\"{}\": …", field.name))
+                .collect();
+            format!("A keys file, or [{{{}}}, …]", pairs.join(", "))
+        }
+    }
+}
+
+/// Keys from the file at `input`, when it names one, or else from `input`
+/// itself.
+fn read_keys(input: &str, schema: &KeySchema) -> Result<Vec<Key>, String> {
+    if input.is_empty() {
+        return Err("Enter a keys file or a list of keys".to_string());
+    }
+    let path = Path::new(input);
+    if path.is_file() {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {input}: {err}"))?;
+        return parse_key_list(&contents, schema);
+    }
+    parse_key_list(input, schema)
+}
+
+impl crate::widgets::Widget for KeyListPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Get items by key", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let state = self.state.borrow();
+        let [input_area, status_area] = inner.layout(&Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]));
+        state.input.render(frame, input_area, theme);
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (self.hint.as_str(), Style::default().fg(theme.text_muted())),
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            _ => {
+                let mut state = self.state.borrow_mut();
+                if state.input.handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for KeyListPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        let height = 6.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
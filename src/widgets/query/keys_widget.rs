@@ -9,10 +9,11 @@ use ratatui::{
     widgets::{Block, HighlightSpacing, Padding, Row, StatefulWidget, Table, TableState},
 };
 
+use super::time_format::TimeFormat;
 use crate::{
     help,
     util::{fill_bg, pad},
-    widgets::{Popup, WidgetInner, theme},
+    widgets::{Popup, WidgetInner, filter_input::FilterInput, theme},
 };
 
 pub struct KeysWidget {
@@ -25,18 +26,28 @@ pub struct KeysWidget {
 pub struct Key {
     pub name: String,
     pub hidden: bool,
+    /// A nested path column (e.g. `metadata.version`) rather than an
+    /// attribute; only these can be removed.
+    pub path: bool,
+    /// Shown as a timestamp in this format.
+    pub time: Option<TimeFormat>,
 }
 
 #[derive(Debug, Default)]
 struct KeysWidgetState {
     keys: Vec<Key>,
     table_state: TableState,
+    /// Where a new path column is typed, after `+`.
+    path_input: FilterInput,
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
     KeyHidden(String),
     KeyUnhidden(String),
+    PathAdded(String),
+    PathRemoved(String),
+    TimeFormat(String, Option<TimeFormat>),
 }
 
 impl KeysWidget {
@@ -73,6 +84,22 @@ impl KeysWidget {
             shift: None,
             alt: None,
         },
+        help::Entry {
+            keys: Cow::Borrowed("+/-"),
+            short: Cow::Borrowed("path"),
+            long: Cow::Borrowed("Add a nested path column (e.g. metadata.version)/remove one"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("t"),
+            short: Cow::Borrowed("time"),
+            long: Cow::Borrowed("Show as time: epoch seconds, epoch millis, or relative ISO"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
     ];
     pub fn new(
         keys: &[Key],
@@ -105,6 +132,44 @@ impl KeysWidget {
             }
         }
     }
+
+    /// Handle a key while a new path column is being typed.
+    fn handle_path_input(&self, event: &crossterm::event::Event) -> bool {
+        let mut state = self.state.borrow_mut();
+        let submitted = event
+            .as_key_press_event()
+            .is_some_and(|key| key.code == KeyCode::Enter);
+        let path = state.path_input.value.trim().to_string();
+        if !state.path_input.handle_event(event) {
+            return false;
+        }
+        if submitted && !path.is_empty() && !state.keys.iter().any(|key| key.name == path) {
+            state.keys.push(Key {
+                name: path.clone(),
+                hidden: false,
+                path: true,
+                time: None,
+            });
+            let last = state.keys.len() - 1;
+            state.table_state.select(Some(last));
+            (self.on_event)(Event::PathAdded(path));
+        }
+        if !state.path_input.is_active() {
+            state.path_input.clear();
+        }
+        true
+    }
+
+    fn remove_selected_path(&self) {
+        let mut state = self.state.borrow_mut();
+        let Some(selected) = state.table_state.selected() else {
+            return;
+        };
+        if state.keys.get(selected).is_some_and(|key| key.path) {
+            let key = state.keys.remove(selected);
+            (self.on_event)(Event::PathRemoved(key.name));
+        }
+    }
 }
 
 impl crate::widgets::Widget for KeysWidget {
@@ -136,7 +201,14 @@ impl crate::widgets::Widget for KeysWidget {
                 } else {
                     Span::styled("✓", Style::default().fg(theme.success()))
                 };
-                let name = key.name.clone();
+                let mut name = if key.path {
+                    format!("{} (path)", key.name)
+                } else {
+                    key.name.clone()
+                };
+                if let Some(time) = key.time {
+                    name.push_str(&format!(" · {}", time.label()));
+                }
                 let right = if key.hidden {
                     Span::styled(name, Style::default().fg(theme.text_muted()))
                 } else {
@@ -161,6 +233,17 @@ impl crate::widgets::Widget for KeysWidget {
         let mut table_area = area.inner(Margin::new(1, 0));
         table_area.y += 1;
         table_area.height -= 1;
+        if state.path_input.is_active() && table_area.height > 3 {
+            let input_area = Rect {
+                y: area.bottom().saturating_sub(4),
+                height: 3,
+                ..table_area
+            };
+            table_area.height -= 3;
+            state
+                .path_input
+                .render_with_title(frame, input_area, theme, "Add path column");
+        }
         StatefulWidget::render(
             table,
             table_area,
@@ -170,6 +253,9 @@ impl crate::widgets::Widget for KeysWidget {
     }
 
     fn handle_event(&self, _ctx: crate::env::WidgetCtx, event: &crossterm::event::Event) -> bool {
+        if self.state.borrow().path_input.is_active() {
+            return self.handle_path_input(event);
+        }
         if let Some(key) = event.as_key_press_event() {
             match key.code {
                 KeyCode::Down => {
@@ -198,6 +284,19 @@ impl crate::widgets::Widget for KeysWidget {
                 KeyCode::Char('n') => {
                     self.update_all(true);
                 }
+                KeyCode::Char('+') => {
+                    self.state.borrow_mut().path_input.set_active(true);
+                }
+                KeyCode::Char('-') | KeyCode::Delete => self.remove_selected_path(),
+                KeyCode::Char('t') => {
+                    let mut state = self.state.borrow_mut();
+                    if let Some(selected) = state.table_state.selected()
+                        && let Some(key) = state.keys.get_mut(selected)
+                    {
+                        key.time = TimeFormat::cycle(key.time);
+                        (self.on_event)(Event::TimeFormat(key.name.clone(), key.time));
+                    }
+                }
                 _ => {
                     return false; // not handled
                 }
@@ -0,0 +1,77 @@
+//! The result cap of a query (`max_items`/`max_pages` in the options popup or
+//! the config file): pages stop loading once a run of them reaches it, until
+//! `c` allows another run.
+
+use super::options_popup::QueryOptions;
+
+#[derive(Debug, Default)]
+pub(super) struct Limit {
+    /// Items and pages loaded since the query started or was last continued.
+    items: usize,
+    pages: usize,
+    reached: bool,
+}
+
+impl Limit {
+    /// Count a loaded page of `items` rows. Returns whether this page reached
+    /// the cap.
+    pub(super) fn record(&mut self, items: usize, options: &QueryOptions) -> bool {
+        self.items += items;
+        self.pages += 1;
+        let reached = options.max_items.is_some_and(|max| self.items >= max)
+            || options.max_pages.is_some_and(|max| self.pages >= max);
+        let newly = reached && !self.reached;
+        self.reached = reached;
+        newly
+    }
+
+    pub(super) fn is_reached(&self) -> bool {
+        self.reached
+    }
+
+    /// Allow another run of pages up to the cap.
+    pub(super) fn resume(&mut self) {
+        *self = Self::default();
+    }
+
+    /// What loading stopped at, for the footer and the toast.
+    pub(super) fn prompt(&self) -> String {
+        let pages = if self.pages == 1 { "page" } else { "pages" };
+        format!(
+            "stopped after {} items in {} {pages}, c loads more",
+            self.items, self.pages
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Limit;
+    use crate::widgets::query::options_popup::QueryOptions;
+
+    #[test]
+    fn stops_at_items_or_pages_until_resumed() {
+        let options = QueryOptions {
+            max_items: Some(150),
+            max_pages: Some(3),
+            ..QueryOptions::default()
+        };
+        let mut limit = Limit::default();
+        assert!(!limit.record(100, &options));
+        assert!(limit.record(100, &options));
+        assert!(limit.is_reached());
+        assert_eq!(
+            limit.prompt(),
+            "stopped after 200 items in 2 pages, c loads more"
+        );
+
+        limit.resume();
+        assert!(!limit.is_reached());
+        assert!(!limit.record(0, &options));
+        assert!(!limit.record(0, &options));
+        assert!(limit.record(0, &options));
+        assert!(!limit.record(0, &options));
+        assert!(!limit.record(0, &QueryOptions::default()));
+        assert!(!limit.is_reached());
+    }
+}
@@ -1,11 +1,44 @@
+mod aggregate;
+mod bulk_update;
+mod clipboard;
+mod column_widths;
+mod compare;
+mod copy_items;
+mod copy_target;
+mod count_popup;
+mod delete_matching;
+mod diff_popup;
+mod entities;
 mod export_popup;
+mod export_writer;
+mod follow;
+mod hydrate;
 mod index_picker;
+mod index_scope;
 mod input;
 mod item_keys;
+mod key_list_popup;
 mod keys_widget;
+mod limit;
+mod options_popup;
+mod pages;
+mod parameters_popup;
+mod pattern_picker;
+mod projection;
+mod projection_popup;
+mod query_builder;
 mod reference_popup;
+mod result_diff;
+mod row_filter;
 mod selection;
+mod size_popup;
+mod template_picker;
+mod time_format;
 mod tree;
+mod tree_view;
+mod type_convert;
+mod update_matching;
+mod watch;
 mod widget;
 
 pub use widget::QueryWidget;
@@ -0,0 +1,430 @@
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::query::{IndexHint, QueryPlan};
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, theme::Theme},
+};
+
+/// Read options applied to every request of the active query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct QueryOptions {
+    pub(super) consistent_read: bool,
+    /// Attributes to fetch; empty fetches whole items.
+    pub(super) projection: Vec<String>,
+    pub(super) descending: bool,
+    /// Secondary index the view browses instead of the table. Chosen with the
+    /// index scope picker rather than this popup.
+    pub(super) index: Option<String>,
+    /// Stop loading pages after this many items, until `c` continues.
+    pub(super) max_items: Option<usize>,
+    /// Stop loading after this many pages, however few items matched.
+    pub(super) max_pages: Option<usize>,
+}
+
+impl QueryOptions {
+    pub(super) fn apply(&self, plan: &mut QueryPlan) {
+        plan.consistent_read = self.consistent_read;
+        plan.projection.clone_from(&self.projection);
+        plan.descending = self.descending;
+        // Index picker lookups already name their index.
+        if plan.index_hint.is_none() {
+            plan.index_hint = self.index.clone().map(IndexHint::Named);
+        }
+    }
+
+    /// A one-line summary of the options that differ from the defaults, or
+    /// `None` when all are at their defaults.
+    pub(super) fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.consistent_read {
+            parts.push("consistent".to_string());
+        }
+        if !self.projection.is_empty() {
+            parts.push(format!("only {}", self.projection.join(", ")));
+        }
+        if self.descending {
+            parts.push("descending".to_string());
+        }
+        if let Some(max) = self.max_items {
+            parts.push(format!("max {max} items"));
+        }
+        if let Some(max) = self.max_pages {
+            parts.push(format!("max {max} pages"));
+        }
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+/// A result cap typed into the popup: blank for none, else a positive count.
+fn parse_limit(text: &str, what: &str) -> Result<Option<usize>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    match text.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(Some(count)),
+        _ => Err(format!("Max {what} must be a positive number")),
+    }
+}
+
+/// Split a comma-separated attribute list, dropping blanks and repeats.
+fn parse_projection(text: &str) -> Vec<String> {
+    let mut attributes: Vec<String> = Vec::new();
+    for name in text
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !attributes.iter().any(|existing| existing == name) {
+            attributes.push(name.to_string());
+        }
+    }
+    attributes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    ConsistentRead,
+    Projection,
+    Order,
+    MaxItems,
+    MaxPages,
+}
+
+impl Focus {
+    const ALL: [Focus; 5] = [
+        Focus::ConsistentRead,
+        Focus::Projection,
+        Focus::Order,
+        Focus::MaxItems,
+        Focus::MaxPages,
+    ];
+
+    fn step(self, delta: isize) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|focus| *focus == self)
+            .unwrap_or(0);
+        let len = Self::ALL.len() as isize;
+        Self::ALL[(index as isize + delta).rem_euclid(len) as usize]
+    }
+}
+
+/// Edits the consistent read, projection, sort order, and result cap options
+/// of a query.
+pub(super) struct OptionsPopup {
+    inner: WidgetInner,
+    consistent_read: Cell<bool>,
+    descending: Cell<bool>,
+    projection: RefCell<TextInput>,
+    max_items: RefCell<TextInput>,
+    max_pages: RefCell<TextInput>,
+    /// Why the last apply was refused.
+    error: RefCell<Option<String>>,
+    /// Carried through unchanged.
+    index: Option<String>,
+    focus: Cell<Focus>,
+    on_apply: Box<dyn Fn(QueryOptions) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+impl OptionsPopup {
+    pub(super) fn new(
+        options: &QueryOptions,
+        on_apply: impl Fn(QueryOptions) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let projection = TextInput::new("Projection", options.projection.join(", "))
+            .with_placeholder("(all attributes)");
+        let limit_input = |label: &str, value: Option<usize>| {
+            TextInput::new(
+                label,
+                value.map(|value| value.to_string()).unwrap_or_default(),
+            )
+            .with_placeholder("(no limit)")
+        };
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("tab/shift+tab"),
+                short: Cow::Borrowed("move"),
+                long: Cow::Borrowed("Next/previous option"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("space"),
+                short: Cow::Borrowed("toggle"),
+                long: Cow::Borrowed("Toggle option"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("⏎"),
+                short: Cow::Borrowed("apply"),
+                long: Cow::Borrowed("Apply and re-run query"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("cancel"),
+                long: Cow::Borrowed("Cancel"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            consistent_read: Cell::new(options.consistent_read),
+            descending: Cell::new(options.descending),
+            projection: RefCell::new(projection),
+            max_items: RefCell::new(limit_input("Max items", options.max_items)),
+            max_pages: RefCell::new(limit_input("Max pages", options.max_pages)),
+            error: RefCell::new(None),
+            index: options.index.clone(),
+            focus: Cell::new(Focus::ConsistentRead),
+            on_apply: Box::new(on_apply),
+            help_entries,
+        }
+    }
+
+    fn set_focus(&self, focus: Focus) {
+        self.focus.set(focus);
+        self.projection
+            .borrow_mut()
+            .set_active(focus == Focus::Projection);
+        self.max_items
+            .borrow_mut()
+            .set_active(focus == Focus::MaxItems);
+        self.max_pages
+            .borrow_mut()
+            .set_active(focus == Focus::MaxPages);
+    }
+
+    fn options(&self) -> Result<QueryOptions, String> {
+        Ok(QueryOptions {
+            consistent_read: self.consistent_read.get(),
+            projection: parse_projection(self.projection.borrow().value()),
+            descending: self.descending.get(),
+            index: self.index.clone(),
+            max_items: parse_limit(self.max_items.borrow().value(), "items")?,
+            max_pages: parse_limit(self.max_pages.borrow().value(), "pages")?,
+        })
+    }
+
+    /// The text input with focus, if any.
+    fn focused_input(&self) -> Option<&RefCell<TextInput>> {
+        match self.focus.get() {
+            Focus::Projection => Some(&self.projection),
+            Focus::MaxItems => Some(&self.max_items),
+            Focus::MaxPages => Some(&self.max_pages),
+            Focus::ConsistentRead | Focus::Order => None,
+        }
+    }
+
+    fn render_toggle(frame: &mut Frame, area: Rect, text: &str, focused: bool, theme: &Theme) {
+        let style = if focused {
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text())
+        };
+        frame.render_widget(Paragraph::new(text).style(style), area);
+    }
+}
+
+impl crate::widgets::Widget for OptionsPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Query options", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ]);
+        let [
+            consistent_area,
+            projection_area,
+            order_area,
+            limit_area,
+            _,
+            hint_area,
+        ] = inner.layout(&layout);
+        let [max_items_area, max_pages_area] = limit_area.layout(&Layout::horizontal([
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ]));
+
+        let focus = self.focus.get();
+        let consistent = if self.consistent_read.get() {
+            "[x] Consistent read"
+        } else {
+            "[ ] Consistent read"
+        };
+        Self::render_toggle(
+            frame,
+            consistent_area,
+            consistent,
+            focus == Focus::ConsistentRead,
+            theme,
+        );
+        self.projection
+            .borrow()
+            .render(frame, projection_area, theme);
+        let order = if self.descending.get() {
+            "Sort key order: descending"
+        } else {
+            "Sort key order: ascending"
+        };
+        Self::render_toggle(frame, order_area, order, focus == Focus::Order, theme);
+        self.max_items.borrow().render(frame, max_items_area, theme);
+        self.max_pages.borrow().render(frame, max_pages_area, theme);
+
+        let hint = match focus {
+            Focus::ConsistentRead => "Not available on global secondary indexes",
+            Focus::Projection => "Comma-separated attribute names; keys are always fetched",
+            Focus::Order => "Queries only; an ORDER clause in the query takes precedence",
+            Focus::MaxItems | Focus::MaxPages => "Loading stops there until c continues",
+        };
+        let (hint, style) = match self.error.borrow().as_ref() {
+            Some(error) => (error.clone(), Style::default().fg(theme.error())),
+            None => (hint.to_string(), Style::default().fg(theme.text_muted())),
+        };
+        frame.render_widget(Paragraph::new(hint).style(style), hint_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        let focus = self.focus.get();
+        match key.code {
+            KeyCode::Enter => match self.options() {
+                Ok(options) => {
+                    (self.on_apply)(options);
+                    ctx.dismiss_popup();
+                }
+                Err(err) => {
+                    self.error.replace(Some(err));
+                }
+            },
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Tab | KeyCode::Down => self.set_focus(focus.step(1)),
+            KeyCode::BackTab | KeyCode::Up => self.set_focus(focus.step(-1)),
+            KeyCode::Char(' ') if focus == Focus::ConsistentRead => {
+                self.consistent_read.set(!self.consistent_read.get());
+            }
+            KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right if focus == Focus::Order => {
+                self.descending.set(!self.descending.get());
+            }
+            _ => {
+                if let Some(input) = self.focused_input()
+                    && input.borrow_mut().handle_event(event)
+                {
+                    self.error.replace(None);
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for OptionsPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 64.min(area.width.saturating_sub(4));
+        // The options, a blank row, and the hint, plus the borders.
+        let height = 12.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QueryOptions, parse_limit, parse_projection};
+
+    #[test]
+    fn projection_drops_blanks_and_repeats() {
+        assert_eq!(
+            parse_projection(" name, ,status,name "),
+            vec!["name".to_string(), "status".to_string()]
+        );
+        assert!(parse_projection("  ").is_empty());
+    }
+
+    #[test]
+    fn summary_lists_changed_options() {
+        assert_eq!(QueryOptions::default().summary(), None);
+        let options = QueryOptions {
+            consistent_read: true,
+            projection: vec!["name".to_string()],
+            descending: true,
+            index: Some("byStatus".to_string()),
+            max_items: Some(500),
+            max_pages: None,
+        };
+        assert_eq!(
+            options.summary().as_deref(),
+            Some("consistent · only name · descending · max 500 items")
+        );
+    }
+
+    #[test]
+    fn limits_are_blank_or_positive() {
+        assert_eq!(parse_limit(" ", "items"), Ok(None));
+        assert_eq!(parse_limit("250", "items"), Ok(Some(250)));
+        assert!(parse_limit("0", "pages").is_err());
+        assert!(parse_limit("many", "pages").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Page boundaries of the loaded results. Each backend page's cursor is kept,
+//! so `<`, `>`, and `g` can go back to an earlier page (or forward to one seen
+//! before) without re-reading everything in between. That also lets a long
+//! run of pages unload its oldest ones and fetch them again when asked.
+//!
+//! The page `g` asks for comes back as an event; [`handle`] hands its number
+//! to the query widget, and [`go_to`] tells it where that page is.
+
+use std::{ops::Range, time::Duration};
+
+use dynamate::core::query::Cursor;
+
+use crate::{
+    env::{AppEvent, Toast, ToastKind, WidgetCtx, WidgetId},
+    widgets::backups::NamePopup,
+};
+
+/// A page number entered in the go-to-page prompt (`g`).
+struct GoToPageRequest(usize);
+
+/// Where a page is, for the query widget to go to.
+pub(super) enum PageJump {
+    /// Loaded already, as these rows.
+    Loaded(Range<usize>),
+    /// Fetched from this cursor, replacing the loaded rows.
+    Fetch(Option<Cursor>),
+}
+
+#[derive(Default)]
+pub(super) struct Pages {
+    /// The cursors that start the second page onward; the first page starts
+    /// at the beginning.
+    cursors: Vec<Cursor>,
+    /// Zero-based number of the first loaded page.
+    first: usize,
+    /// Where each loaded page starts among the loaded rows.
+    starts: Vec<usize>,
+    /// The zero-based page a jump is fetching, until it lands.
+    pending: Option<usize>,
+}
+
+impl Pages {
+    /// Record a page that landed at `first_row` of the loaded rows. Pages that
+    /// replace the rows (not `append`) are the first page or a jump.
+    pub(super) fn loaded(&mut self, append: bool, first_row: usize, next: Option<&Cursor>) {
+        if !append {
+            self.first = self.pending.take().unwrap_or(0);
+            self.starts.clear();
+        }
+        self.starts.push(first_row);
+        let page = self.first + self.starts.len() - 1;
+        match next {
+            Some(next) if self.cursors.get(page) == Some(next) => {}
+            Some(next) => {
+                self.cursors.truncate(page);
+                self.cursors.push(next.clone());
+            }
+            None => self.cursors.truncate(page),
+        }
+    }
+
+    /// Unload whole pages from the front until at most `cap` of the `loaded`
+    /// rows remain, always keeping the last page. Returns how many leading
+    /// rows to drop; the dropped pages stay reachable by their cursors.
+    pub(super) fn evict(&mut self, loaded: usize, cap: usize) -> usize {
+        let mut dropped = 0;
+        while dropped + 1 < self.starts.len() && loaded - self.starts[dropped] > cap {
+            dropped += 1;
+        }
+        if dropped == 0 {
+            return 0;
+        }
+        let rows = self.starts[dropped];
+        self.starts.drain(..dropped);
+        for start in &mut self.starts {
+            *start -= rows;
+        }
+        self.first += dropped;
+        rows
+    }
+
+    /// The 1-based page the loaded rows start at.
+    pub(super) fn first_loaded(&self) -> usize {
+        self.first + 1
+    }
+
+    /// Pages whose start is known: the ones seen so far and the next one.
+    pub(super) fn known(&self) -> usize {
+        self.cursors.len() + 1
+    }
+
+    /// The 1-based page the loaded row `row` came from.
+    pub(super) fn page_of(&self, row: usize) -> usize {
+        self.first + self.starts.partition_point(|start| *start <= row).max(1)
+    }
+
+    /// The loaded rows of 1-based `page`, if it is loaded.
+    pub(super) fn rows_of(&self, page: usize, loaded: usize) -> Option<Range<usize>> {
+        let idx = page.checked_sub(1)?.checked_sub(self.first)?;
+        let start = *self.starts.get(idx)?;
+        let end = self.starts.get(idx + 1).copied().unwrap_or(loaded);
+        Some(start..end)
+    }
+
+    /// Whether 1-based `page` can be fetched: its cursor is known.
+    pub(super) fn is_reachable(&self, page: usize) -> bool {
+        (1..=self.known()).contains(&page)
+    }
+
+    /// The cursor that fetches 1-based `page`; the first page has none.
+    pub(super) fn cursor_of(&self, page: usize) -> Option<Cursor> {
+        page.checked_sub(2)
+            .and_then(|idx| self.cursors.get(idx))
+            .cloned()
+    }
+
+    /// Replace the loaded rows with 1-based `page` once its rows land.
+    pub(super) fn begin_jump(&mut self, page: usize) {
+        self.pending = Some(page.saturating_sub(1));
+    }
+
+    /// The footer label: the current page out of those known, with a `+`
+    /// while the results go on.
+    pub(super) fn label(&self, current: usize, more: bool) -> String {
+        let more = if more { "+" } else { "" };
+        format!("page {current}/{}{more}", self.known())
+    }
+}
+
+/// Ask for a page to go to, starting from the `current` one.
+pub(super) fn prompt(pages: &Pages, current: usize, parent: WidgetId, ctx: &WidgetCtx) {
+    let emitter = ctx.clone();
+    let popup = NamePopup::new(
+        format!("Go to page ({} known)", pages.known()),
+        "Page",
+        &current.to_string(),
+        "Pages seen so far, or the next one",
+        move |page| {
+            if let Ok(page) = page.parse() {
+                emitter.emit_self(GoToPageRequest(page));
+            }
+        },
+        parent,
+    )
+    .with_validation(validate_page);
+    ctx.set_popup(Box::new(popup));
+}
+
+/// The 1-based page asked for, if `event` is the prompt's answer.
+pub(super) fn handle(event: &AppEvent) -> Option<usize> {
+    event
+        .payload::<GoToPageRequest>()
+        .map(|GoToPageRequest(page)| *page)
+}
+
+/// Where 1-based `page` is with `loaded` rows loaded. A page that hasn't been
+/// reached is warned about instead.
+pub(super) fn go_to(
+    pages: &Pages,
+    page: usize,
+    loaded: usize,
+    ctx: &WidgetCtx,
+) -> Option<PageJump> {
+    if let Some(rows) = pages.rows_of(page, loaded) {
+        return Some(PageJump::Loaded(rows));
+    }
+    if pages.is_reachable(page) {
+        return Some(PageJump::Fetch(pages.cursor_of(page)));
+    }
+    ctx.show_toast(Toast {
+        message: format!(
+            "Page {page} hasn't been reached; {} pages known",
+            pages.known()
+        ),
+        kind: ToastKind::Warning,
+        duration: Duration::from_secs(3),
+        action: None,
+    });
+    None
+}
+
+/// A page number typed into the go-to-page prompt.
+fn validate_page(value: &str) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(page) if page > 0 => Ok(()),
+        _ => Err("Enter a page number".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::query::Cursor;
+    use dynamate::core::value::{Item, Value};
+
+    use super::Pages;
+
+    fn cursor(id: &str) -> Cursor {
+        Cursor(Item::from([("id".to_string(), Value::Str(id.to_string()))]))
+    }
+
+    #[test]
+    fn keeps_cursors_across_jumps() {
+        let mut pages = Pages::default();
+        pages.loaded(false, 0, Some(&cursor("b")));
+        pages.loaded(true, 10, Some(&cursor("c")));
+        pages.loaded(true, 20, None);
+        assert_eq!(pages.known(), 3);
+        assert_eq!(pages.page_of(15), 2);
+        assert_eq!(pages.rows_of(3, 25), Some(20..25));
+        assert_eq!(pages.label(2, false), "page 2/3");
+
+        pages.begin_jump(2);
+        pages.loaded(false, 0, Some(&cursor("c")));
+        assert_eq!(pages.known(), 3);
+        assert_eq!(pages.page_of(0), 2);
+        assert_eq!(pages.rows_of(1, 10), None);
+        assert_eq!(pages.cursor_of(3), Some(cursor("c")));
+        assert!(pages.is_reachable(3));
+        assert!(!pages.is_reachable(4));
+    }
+
+    #[test]
+    fn evicts_whole_pages_from_the_front() {
+        let mut pages = Pages::default();
+        pages.loaded(false, 0, Some(&cursor("b")));
+        pages.loaded(true, 10, Some(&cursor("c")));
+        pages.loaded(true, 20, Some(&cursor("d")));
+        assert_eq!(pages.evict(30, 30), 0);
+        assert_eq!(pages.evict(30, 15), 20);
+        assert_eq!(pages.first_loaded(), 3);
+        assert_eq!(pages.page_of(0), 3);
+        assert_eq!(pages.rows_of(3, 10), Some(0..10));
+        assert_eq!(pages.cursor_of(2), Some(cursor("b")));
+        assert_eq!(pages.evict(10, 5), 0);
+    }
+}
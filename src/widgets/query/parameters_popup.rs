@@ -0,0 +1,216 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, theme::Theme},
+};
+
+/// Collects values for the `:name` parameters of a query before it runs.
+pub(super) struct ParametersPopup {
+    inner: WidgetInner,
+    names: Vec<String>,
+    state: RefCell<ParametersState>,
+    on_submit: Box<dyn Fn(Vec<(String, String)>) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct ParametersState {
+    inputs: Vec<TextInput>,
+    active: usize,
+    error: Option<String>,
+}
+
+impl ParametersState {
+    fn focus(&mut self, index: usize) {
+        self.active = index;
+        for (i, input) in self.inputs.iter_mut().enumerate() {
+            input.set_active(i == index);
+        }
+    }
+}
+
+impl ParametersPopup {
+    /// `previous` prefills parameters that were given a value before.
+    pub(super) fn new(
+        names: Vec<String>,
+        previous: &[(String, String)],
+        on_submit: impl Fn(Vec<(String, String)>) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let inputs = names
+            .iter()
+            .map(|name| {
+                let value = previous
+                    .iter()
+                    .find(|(previous, _)| previous == name)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_default();
+                TextInput::new(format!(":{name}"), value)
+            })
+            .collect();
+        let mut state = ParametersState {
+            inputs,
+            active: 0,
+            error: None,
+        };
+        state.focus(0);
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("tab/shift+tab"),
+                short: Cow::Borrowed("move"),
+                long: Cow::Borrowed("Next/previous parameter"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("⏎"),
+                short: Cow::Borrowed("run"),
+                long: Cow::Borrowed("Run query"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("cancel"),
+                long: Cow::Borrowed("Cancel"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            names,
+            state: RefCell::new(state),
+            on_submit: Box::new(on_submit),
+            help_entries,
+        }
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let values = {
+            let mut state = self.state.borrow_mut();
+            if let Some(empty) = state
+                .inputs
+                .iter()
+                .position(|input| input.value().trim().is_empty())
+            {
+                state.error = Some(format!("Enter a value for :{}", self.names[empty]));
+                state.focus(empty);
+                return;
+            }
+            self.names
+                .iter()
+                .cloned()
+                .zip(state.inputs.iter().map(|input| input.value().to_string()))
+                .collect()
+        };
+        (self.on_submit)(values);
+        ctx.dismiss_popup();
+    }
+}
+
+impl crate::widgets::Widget for ParametersPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Query parameters", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let state = self.state.borrow();
+        let mut constraints = vec![Constraint::Length(3); state.inputs.len()];
+        constraints.push(Constraint::Length(1));
+        let areas = Layout::vertical(constraints).split(inner);
+        for (input, input_area) in state.inputs.iter().zip(areas.iter()) {
+            input.render(frame, *input_area, theme);
+        }
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (
+                "Values use query syntax: \"text\", 42, true",
+                Style::default().fg(theme.text_muted()),
+            ),
+        };
+        if let Some(status_area) = areas.last() {
+            frame.render_widget(Paragraph::new(status).style(style), *status_area);
+        }
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Tab | KeyCode::Down => {
+                let mut state = self.state.borrow_mut();
+                let next = (state.active + 1) % state.inputs.len();
+                state.focus(next);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                let mut state = self.state.borrow_mut();
+                let len = state.inputs.len();
+                let previous = (state.active + len - 1) % len;
+                state.focus(previous);
+            }
+            _ => {
+                let mut state = self.state.borrow_mut();
+                let active = state.active;
+                if state.inputs[active].handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for ParametersPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 56.min(area.width.saturating_sub(4));
+        // Three rows per input, the status line, and the borders.
+        let wanted = (self.names.len() * 3 + 3) as u16;
+        let height = wanted.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    prelude::StatefulWidget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, HighlightSpacing, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{
+    access_patterns::{self, AccessPattern},
+    help,
+    util::fill_bg,
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Lists a table's access patterns to run one, with `1`-`9` for the first
+/// nine, and saves the current query as a new one.
+pub(super) struct PatternPicker {
+    inner: WidgetInner,
+    table: String,
+    patterns: RefCell<Vec<AccessPattern>>,
+    state: RefCell<ListState>,
+    on_run: Box<dyn Fn(String) + Send + 'static>,
+    on_save: Box<dyn Fn() + Send + 'static>,
+}
+
+impl PatternPicker {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓/j/k"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎/1-9"),
+            short: Cow::Borrowed("run"),
+            long: Cow::Borrowed("Run the access pattern, asking for its parameters"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("a"),
+            short: Cow::Borrowed("add"),
+            long: Cow::Borrowed("Save the current query as an access pattern"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("^d"),
+            short: Cow::Borrowed("delete"),
+            long: Cow::Borrowed("Delete the access pattern"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close"),
+            long: Cow::Borrowed("Close picker"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    pub(super) fn new(
+        table: String,
+        patterns: Vec<AccessPattern>,
+        on_run: impl Fn(String) + Send + 'static,
+        on_save: impl Fn() + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut state = ListState::default();
+        state.select((!patterns.is_empty()).then_some(0));
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            table,
+            patterns: RefCell::new(patterns),
+            state: RefCell::new(state),
+            on_run: Box::new(on_run),
+            on_save: Box::new(on_save),
+        }
+    }
+
+    fn run(&self, ctx: &crate::env::WidgetCtx, row: usize) {
+        let query = self
+            .patterns
+            .borrow()
+            .get(row)
+            .map(|pattern| pattern.query.clone());
+        if let Some(query) = query {
+            ctx.dismiss_popup();
+            (self.on_run)(query);
+        }
+    }
+
+    fn delete_selected(&self) {
+        let mut patterns = self.patterns.borrow_mut();
+        let mut state = self.state.borrow_mut();
+        let Some(row) = state.selected().filter(|row| *row < patterns.len()) else {
+            return;
+        };
+        let pattern = patterns.remove(row);
+        access_patterns::remove(&self.table, &pattern.name);
+        state.select(if patterns.is_empty() {
+            None
+        } else {
+            Some(row.min(patterns.len() - 1))
+        });
+    }
+}
+
+impl crate::widgets::Widget for PatternPicker {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::styled(
+                format!(" Access patterns: {} ", self.table),
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+
+        let patterns = self.patterns.borrow();
+        if patterns.is_empty() {
+            frame.render_widget(
+                Paragraph::new("None saved yet; press a to save the current query")
+                    .style(Style::default().fg(theme.text_muted())),
+                block.inner(area),
+            );
+            return;
+        }
+        let items: Vec<ListItem> = patterns
+            .iter()
+            .enumerate()
+            .map(|(row, pattern)| {
+                let number = if row < 9 {
+                    format!("{} ", row + 1)
+                } else {
+                    "  ".to_string()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(number, Style::default().fg(theme.text_muted())),
+                    Span::raw(pattern.name.as_str()),
+                    Span::styled(
+                        format!("  {}", pattern.query),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("> ")
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(
+            list,
+            block.inner(area),
+            frame.buffer_mut(),
+            &mut self.state.borrow_mut(),
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Up | KeyCode::Char('k') => self.state.borrow_mut().select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.state.borrow_mut().select_next(),
+            KeyCode::Enter => {
+                let selected = self.state.borrow().selected();
+                if let Some(row) = selected {
+                    self.run(&ctx, row);
+                }
+            }
+            KeyCode::Char(digit @ '1'..='9') => self.run(&ctx, digit as usize - '1' as usize),
+            KeyCode::Char('a') => {
+                ctx.dismiss_popup();
+                (self.on_save)();
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.delete_selected();
+            }
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for PatternPicker {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        let rows = u16::try_from(self.patterns.borrow().len().max(1)).unwrap_or(u16::MAX);
+        let height = rows.saturating_add(2).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! Result projections: a comma-separated list of attribute paths that
+//! reshapes each loaded item before it is shown or exported, e.g.
+//! `pk, city=address.city, first_tag=tags[0]`. `*` keeps every top-level
+//! attribute, so `*, address.city` adds a flattened column.
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+
+/// One step into a nested value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// A document path such as `address.lines[0]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributePath(Vec<PathSegment>);
+
+impl AttributePath {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Empty attribute path".to_string());
+        }
+        let mut segments = Vec::new();
+        for part in text.split('.') {
+            let (name, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+            if name.is_empty() {
+                return Err(format!("Missing attribute name in {text}"));
+            }
+            segments.push(PathSegment::Key(name.to_string()));
+            while !rest.is_empty() {
+                let Some(end) = rest.find(']').filter(|_| rest.starts_with('[')) else {
+                    return Err(format!("Malformed list index in {text}"));
+                };
+                let index = rest[1..end]
+                    .parse::<usize>()
+                    .map_err(|_| format!("List index must be a number in {text}"))?;
+                segments.push(PathSegment::Index(index));
+                rest = &rest[end + 1..];
+            }
+        }
+        Ok(Self(segments))
+    }
+
+    /// The value at this path, if every step exists.
+    pub fn resolve<'a>(
+        &self,
+        item: &'a HashMap<String, AttributeValue>,
+    ) -> Option<&'a AttributeValue> {
+        let mut segments = self.0.iter();
+        let PathSegment::Key(first) = segments.next()? else {
+            return None;
+        };
+        let mut value = item.get(first)?;
+        for segment in segments {
+            value = match (segment, value) {
+                (PathSegment::Key(key), AttributeValue::M(map)) => map.get(key)?,
+                (PathSegment::Index(index), AttributeValue::L(list)) => list.get(*index)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Like [`Self::resolve`], for changing the value in place.
+    pub fn resolve_mut<'a>(
+        &self,
+        item: &'a mut HashMap<String, AttributeValue>,
+    ) -> Option<&'a mut AttributeValue> {
+        let mut segments = self.0.iter();
+        let PathSegment::Key(first) = segments.next()? else {
+            return None;
+        };
+        let mut value = item.get_mut(first)?;
+        for segment in segments {
+            value = match (segment, value) {
+                (PathSegment::Key(key), AttributeValue::M(map)) => map.get_mut(key)?,
+                (PathSegment::Index(index), AttributeValue::L(list)) => list.get_mut(*index)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+}
+
+/// The value of `key` in `item`: the attribute itself, or, for a nested
+/// path column such as `metadata.version`, the value the path leads to.
+pub fn attribute_at<'a>(
+    item: &'a HashMap<String, AttributeValue>,
+    key: &str,
+) -> Option<&'a AttributeValue> {
+    if let Some(value) = item.get(key) {
+        return Some(value);
+    }
+    if !key.contains(['.', '[']) {
+        return None;
+    }
+    AttributePath::parse(key).ok()?.resolve(item)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    /// `*`: every top-level attribute of the item.
+    All,
+    Path {
+        name: String,
+        path: AttributePath,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowProjection {
+    text: String,
+    fields: Vec<Field>,
+    /// The only columns kept once the fields are applied, for exports of the
+    /// visible columns.
+    keep: Option<Vec<String>>,
+}
+
+impl RowProjection {
+    /// Parse `field, field, …` where a field is `*`, `path`, or `name=path`.
+    /// A bare path names its column after the path itself.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut fields = Vec::new();
+        for raw in text.split(',') {
+            let raw = raw.trim();
+            if raw.is_empty() {
+                continue;
+            }
+            if raw == "*" {
+                fields.push(Field::All);
+                continue;
+            }
+            let (name, path) = match raw.split_once('=') {
+                Some((name, path)) if !name.trim().is_empty() => (name.trim(), path.trim()),
+                Some(_) => return Err(format!("Missing column name in {raw}")),
+                None => (raw, raw),
+            };
+            fields.push(Field::Path {
+                name: name.to_string(),
+                path: AttributePath::parse(path)?,
+            });
+        }
+        if fields.is_empty() {
+            return Err("Projection lists no attributes".to_string());
+        }
+        Ok(Self {
+            text: text.trim().to_string(),
+            fields,
+            keep: None,
+        })
+    }
+
+    /// `projection` (or the whole item, without one) cut down to `columns`,
+    /// which may be nested path columns of the projected rows.
+    pub fn only_columns(projection: Option<&Self>, columns: Vec<String>) -> Self {
+        let (text, fields) = projection.map_or_else(
+            || ("*".to_string(), vec![Field::All]),
+            |projection| (projection.text.clone(), projection.fields.clone()),
+        );
+        Self {
+            text,
+            fields,
+            keep: Some(columns),
+        }
+    }
+
+    /// The projection as it was typed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Reshape one item. Paths the item doesn't have are left out.
+    pub fn apply(&self, item: &HashMap<String, AttributeValue>) -> HashMap<String, AttributeValue> {
+        let mut projected = HashMap::new();
+        for field in &self.fields {
+            match field {
+                Field::All => {
+                    for (key, value) in item {
+                        projected
+                            .entry(key.clone())
+                            .or_insert_with(|| value.clone());
+                    }
+                }
+                Field::Path { name, path } => {
+                    if let Some(value) = path.resolve(item) {
+                        projected.insert(name.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        match self.keep.as_ref() {
+            Some(columns) => columns
+                .iter()
+                .filter_map(|column| {
+                    attribute_at(&projected, column).map(|value| (column.clone(), value.clone()))
+                })
+                .collect(),
+            None => projected,
+        }
+    }
+
+    /// Column order for the projected rows: fields in the order given, with
+    /// `*` standing for `attributes` (the table's usual columns).
+    pub fn columns(&self, attributes: &[String]) -> Vec<String> {
+        if let Some(columns) = self.keep.as_ref() {
+            return columns.clone();
+        }
+        let mut columns: Vec<String> = Vec::new();
+        for field in &self.fields {
+            match field {
+                Field::All => columns.extend(attributes.iter().cloned()),
+                Field::Path { name, .. } => columns.push(name.clone()),
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        columns.retain(|column| seen.insert(column.clone()));
+        columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::{AttributePath, PathSegment, RowProjection, attribute_at};
+
+    fn item() -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("pk".to_string(), AttributeValue::S("user#1".to_string())),
+            (
+                "address".to_string(),
+                AttributeValue::M(HashMap::from([(
+                    "city".to_string(),
+                    AttributeValue::S("Lisbon".to_string()),
+                )])),
+            ),
+            (
+                "tags".to_string(),
+                AttributeValue::L(vec![AttributeValue::S("new".to_string())]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn parses_keys_and_list_indexes() {
+        assert_eq!(
+            AttributePath::parse("a.b[2][0].c").unwrap(),
+            AttributePath(vec![
+                PathSegment::Key("a".to_string()),
+                PathSegment::Key("b".to_string()),
+                PathSegment::Index(2),
+                PathSegment::Index(0),
+                PathSegment::Key("c".to_string()),
+            ])
+        );
+        assert!(AttributePath::parse("a..b").is_err());
+        assert!(AttributePath::parse("a[x]").is_err());
+        assert!(AttributePath::parse("a[1").is_err());
+    }
+
+    #[test]
+    fn flattens_nested_values_into_named_columns() {
+        let projection =
+            RowProjection::parse("pk, city=address.city, tags[0], missing.path").unwrap();
+        let projected = projection.apply(&item());
+        assert_eq!(
+            projected,
+            HashMap::from([
+                ("pk".to_string(), AttributeValue::S("user#1".to_string())),
+                ("city".to_string(), AttributeValue::S("Lisbon".to_string())),
+                ("tags[0]".to_string(), AttributeValue::S("new".to_string())),
+            ])
+        );
+        assert_eq!(
+            projection.columns(&[]),
+            vec!["pk", "city", "tags[0]", "missing.path"]
+        );
+    }
+
+    #[test]
+    fn star_keeps_the_item_and_its_columns() {
+        let projection = RowProjection::parse("*, address.city").unwrap();
+        let projected = projection.apply(&item());
+        assert_eq!(projected.len(), 4);
+        assert_eq!(
+            projection.columns(&["pk".to_string(), "address".to_string()]),
+            vec!["pk", "address", "address.city"]
+        );
+    }
+
+    #[test]
+    fn only_columns_keeps_the_visible_ones() {
+        let columns = vec!["pk".to_string(), "address.city".to_string()];
+        let projected = RowProjection::only_columns(None, columns.clone()).apply(&item());
+        assert_eq!(
+            projected,
+            HashMap::from([
+                ("pk".to_string(), AttributeValue::S("user#1".to_string())),
+                (
+                    "address.city".to_string(),
+                    AttributeValue::S("Lisbon".to_string())
+                ),
+            ])
+        );
+
+        let projection = RowProjection::parse("pk, city=address.city, tags").unwrap();
+        let only = RowProjection::only_columns(Some(&projection), vec!["city".to_string()]);
+        assert_eq!(
+            only.apply(&item()),
+            HashMap::from([("city".to_string(), AttributeValue::S("Lisbon".to_string()))])
+        );
+        assert_eq!(only.columns(&[]), vec!["city"]);
+    }
+
+    #[test]
+    fn attribute_at_resolves_nested_paths() {
+        let item = item();
+        assert_eq!(
+            attribute_at(&item, "address.city"),
+            Some(&AttributeValue::S("Lisbon".to_string()))
+        );
+        assert_eq!(
+            attribute_at(&item, "tags[0]"),
+            Some(&AttributeValue::S("new".to_string()))
+        );
+        assert_eq!(attribute_at(&item, "address.zip"), None);
+        assert_eq!(attribute_at(&item, "missing"), None);
+    }
+
+    #[test]
+    fn rejects_empty_projections_and_names() {
+        assert!(RowProjection::parse(" , ").is_err());
+        assert!(RowProjection::parse("=address.city").is_err());
+    }
+}
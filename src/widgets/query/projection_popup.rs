@@ -0,0 +1,165 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use super::projection::RowProjection;
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, theme::Theme},
+};
+
+/// Edits the projection applied to loaded rows. Submitting an empty input
+/// removes it.
+pub(super) struct ProjectionPopup {
+    inner: WidgetInner,
+    state: RefCell<ProjectionState>,
+    on_submit: Box<dyn Fn(Option<RowProjection>) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct ProjectionState {
+    input: TextInput,
+    error: Option<String>,
+}
+
+impl ProjectionPopup {
+    pub(super) fn new(
+        current: Option<&RowProjection>,
+        on_submit: impl Fn(Option<RowProjection>) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut input = TextInput::new(
+            "Projection",
+            current.map(RowProjection::text).unwrap_or_default(),
+        );
+        input.set_active(true);
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("⏎"),
+                short: Cow::Borrowed("apply"),
+                long: Cow::Borrowed("Apply projection (empty removes it)"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("cancel"),
+                long: Cow::Borrowed("Cancel"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            state: RefCell::new(ProjectionState { input, error: None }),
+            on_submit: Box::new(on_submit),
+            help_entries,
+        }
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let projection = {
+            let mut state = self.state.borrow_mut();
+            let text = state.input.value().trim().to_string();
+            if text.is_empty() {
+                None
+            } else {
+                match RowProjection::parse(&text) {
+                    Ok(projection) => Some(projection),
+                    Err(err) => {
+                        state.error = Some(err);
+                        return;
+                    }
+                }
+            }
+        };
+        (self.on_submit)(projection);
+        ctx.dismiss_popup();
+    }
+}
+
+impl crate::widgets::Widget for ProjectionPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Project results", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let state = self.state.borrow();
+        let [input_area, status_area] = inner.layout(&Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]));
+        state.input.render(frame, input_area, theme);
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (
+                "e.g. pk, city=address.city, tags[0] (* keeps all)",
+                Style::default().fg(theme.text_muted()),
+            ),
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            _ => {
+                let mut state = self.state.borrow_mut();
+                if state.input.handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for ProjectionPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 64.min(area.width.saturating_sub(4));
+        let height = 6.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
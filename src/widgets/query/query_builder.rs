@@ -0,0 +1,400 @@
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    sync::Arc,
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::{
+    datastore::Datastore,
+    language::{Condition, ConditionOp, ConditionValue},
+};
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, theme::Theme},
+};
+
+/// Conditions shown before the form scrolls.
+const VISIBLE_ROWS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Attribute,
+    Operator,
+    Value,
+}
+
+struct ConditionRow {
+    attribute: TextInput,
+    op: ConditionOp,
+    value: TextInput,
+}
+
+impl ConditionRow {
+    fn new(attribute: &str) -> Self {
+        Self {
+            attribute: TextInput::new("Attribute", attribute),
+            op: ConditionOp::Equal,
+            value: TextInput::new("Value", "").with_placeholder("(empty)"),
+        }
+    }
+
+    /// The row as a condition, or `None` while it names no attribute.
+    fn condition(&self) -> Option<Condition> {
+        let attribute = self.attribute.value().trim();
+        (!attribute.is_empty()).then(|| Condition {
+            attribute: attribute.to_string(),
+            op: self.op,
+            value: ConditionValue::infer(self.value.value()),
+        })
+    }
+
+    fn cycle_op(&mut self, delta: isize) {
+        let index = ConditionOp::ALL
+            .iter()
+            .position(|op| *op == self.op)
+            .unwrap_or(0);
+        let len = ConditionOp::ALL.len() as isize;
+        self.op = ConditionOp::ALL[(index as isize + delta).rem_euclid(len) as usize];
+    }
+}
+
+/// Builds a query from attribute / operator / value conditions, all of which
+/// must match, for people who don't know the query syntax. The backend's
+/// query language writes the query text, which then runs like a typed one.
+pub(super) struct QueryBuilderPopup {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    rows: RefCell<Vec<ConditionRow>>,
+    focus: Cell<(usize, Field)>,
+    on_apply: Box<dyn Fn(String) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+impl QueryBuilderPopup {
+    /// `attribute` prefills the first condition, typically the partition key.
+    pub(super) fn new(
+        db: Arc<dyn Datastore>,
+        attribute: &str,
+        on_apply: impl Fn(String) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("tab/shift+tab"),
+                short: Cow::Borrowed("move"),
+                long: Cow::Borrowed("Next/previous field"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("←/→"),
+                short: Cow::Borrowed("operator"),
+                long: Cow::Borrowed("Change the operator"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("^n"),
+                short: Cow::Borrowed("add"),
+                long: Cow::Borrowed("Add a condition"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("^d"),
+                short: Cow::Borrowed("remove"),
+                long: Cow::Borrowed("Remove the condition"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("⏎"),
+                short: Cow::Borrowed("run"),
+                long: Cow::Borrowed("Run the query"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("cancel"),
+                long: Cow::Borrowed("Cancel"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        let popup = Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            rows: RefCell::new(vec![ConditionRow::new(attribute)]),
+            focus: Cell::new((0, Field::Attribute)),
+            on_apply: Box::new(on_apply),
+            help_entries,
+        };
+        // Start on the value when the attribute is already filled in.
+        let field = if attribute.is_empty() {
+            Field::Attribute
+        } else {
+            Field::Value
+        };
+        popup.set_focus(0, field);
+        popup
+    }
+
+    fn set_focus(&self, row: usize, field: Field) {
+        let mut rows = self.rows.borrow_mut();
+        for (idx, condition) in rows.iter_mut().enumerate() {
+            condition
+                .attribute
+                .set_active(idx == row && field == Field::Attribute);
+            condition
+                .value
+                .set_active(idx == row && field == Field::Value);
+        }
+        self.focus.set((row, field));
+    }
+
+    /// Step through the fields of every row, skipping values of operators
+    /// that take none.
+    fn step_focus(&self, delta: isize) {
+        let fields: Vec<(usize, Field)> = self
+            .rows
+            .borrow()
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, row)| {
+                let value = row.op.takes_value().then_some((idx, Field::Value));
+                [(idx, Field::Attribute), (idx, Field::Operator)]
+                    .into_iter()
+                    .chain(value)
+            })
+            .collect();
+        let position = fields
+            .iter()
+            .position(|focus| *focus == self.focus.get())
+            .unwrap_or(0);
+        let len = fields.len() as isize;
+        let (row, field) = fields[(position as isize + delta).rem_euclid(len) as usize];
+        self.set_focus(row, field);
+    }
+
+    fn add_row(&self) {
+        let row = {
+            let mut rows = self.rows.borrow_mut();
+            rows.push(ConditionRow::new(""));
+            rows.len() - 1
+        };
+        self.set_focus(row, Field::Attribute);
+    }
+
+    /// Remove the focused condition; the last one is cleared instead.
+    fn remove_row(&self) {
+        let (row, field) = self.focus.get();
+        let row = {
+            let mut rows = self.rows.borrow_mut();
+            if rows.len() > 1 {
+                rows.remove(row);
+                row.min(rows.len() - 1)
+            } else {
+                rows[0] = ConditionRow::new("");
+                0
+            }
+        };
+        self.set_focus(row, field);
+    }
+
+    fn query(&self) -> Result<String, String> {
+        let conditions: Vec<Condition> = self
+            .rows
+            .borrow()
+            .iter()
+            .filter_map(ConditionRow::condition)
+            .collect();
+        self.db.query_language().build_query(&conditions)
+    }
+
+    fn render_operator(
+        frame: &mut Frame,
+        area: Rect,
+        op: ConditionOp,
+        focused: bool,
+        theme: &Theme,
+    ) {
+        let border = if focused {
+            theme.accent()
+        } else {
+            theme.border()
+        };
+        let block = Block::bordered()
+            .title("Operator")
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()))
+            .border_style(Style::default().fg(border));
+        let label = if focused {
+            format!("‹ {} ›", op.label())
+        } else {
+            op.label().to_string()
+        };
+        frame.render_widget(Paragraph::new(label).centered().block(block), area);
+    }
+}
+
+impl crate::widgets::Widget for QueryBuilderPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Query builder", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ]);
+        let [rows_area, _, preview_area] = inner.layout(&layout);
+
+        let rows = self.rows.borrow();
+        let (focused_row, focused_field) = self.focus.get();
+        let first = (focused_row + 1).saturating_sub(VISIBLE_ROWS);
+        let row_areas =
+            Layout::vertical(vec![Constraint::Length(3); VISIBLE_ROWS]).split(rows_area);
+        for ((idx, row), row_area) in rows.iter().enumerate().skip(first).zip(row_areas.iter()) {
+            let columns = Layout::horizontal([
+                Constraint::Fill(1),
+                Constraint::Length(18),
+                Constraint::Fill(1),
+            ]);
+            let [attribute_area, op_area, value_area] = row_area.layout(&columns);
+            row.attribute.render(frame, attribute_area, theme);
+            let op_focused = idx == focused_row && focused_field == Field::Operator;
+            Self::render_operator(frame, op_area, row.op, op_focused, theme);
+            if row.op.takes_value() {
+                row.value.render(frame, value_area, theme);
+            }
+        }
+
+        let preview = match self.query() {
+            Ok(query) if query.is_empty() => Line::styled(
+                "No conditions: matches every item",
+                Style::default().fg(theme.text_muted()),
+            ),
+            Ok(query) => Line::from(vec![
+                Span::styled("Query: ", Style::default().fg(theme.text_muted())),
+                Span::styled(query, Style::default().fg(theme.text())),
+            ]),
+            Err(err) => Line::styled(err, Style::default().fg(theme.error())),
+        };
+        frame.render_widget(Paragraph::new(preview), preview_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        let (row, field) = self.focus.get();
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => {
+                if let Ok(query) = self.query() {
+                    (self.on_apply)(query);
+                    ctx.dismiss_popup();
+                }
+            }
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Char('n') if ctrl => self.add_row(),
+            KeyCode::Char('d') if ctrl => self.remove_row(),
+            KeyCode::Tab => self.step_focus(1),
+            KeyCode::BackTab => self.step_focus(-1),
+            KeyCode::Down => {
+                let last = self.rows.borrow().len() - 1;
+                self.set_focus((row + 1).min(last), field);
+            }
+            KeyCode::Up => self.set_focus(row.saturating_sub(1), field),
+            KeyCode::Left | KeyCode::Right | KeyCode::Char(' ') if field == Field::Operator => {
+                let delta = if key.code == KeyCode::Left { -1 } else { 1 };
+                self.rows.borrow_mut()[row].cycle_op(delta);
+            }
+            _ => {
+                let mut rows = self.rows.borrow_mut();
+                match field {
+                    Field::Attribute => rows[row].attribute.handle_event(event),
+                    Field::Value => rows[row].value.handle_event(event),
+                    Field::Operator => false,
+                };
+            }
+        }
+        // Operators without a value can't keep the value focused.
+        let (row, field) = self.focus.get();
+        if field == Field::Value && !self.rows.borrow()[row].op.takes_value() {
+            self.set_focus(row, Field::Operator);
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for QueryBuilderPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 84.min(area.width.saturating_sub(4));
+        // Three-row inputs per visible condition, the preview, and the borders.
+        let height = (VISIBLE_ROWS as u16 * 3 + 4).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConditionRow;
+    use dynamate::core::language::{ConditionOp, ConditionValue};
+
+    #[test]
+    fn rows_without_an_attribute_are_skipped() {
+        assert!(ConditionRow::new("  ").condition().is_none());
+        let mut row = ConditionRow::new(" status ");
+        row.cycle_op(-1);
+        let condition = row.condition().expect("condition");
+        assert_eq!(condition.attribute, "status");
+        assert_eq!(condition.op, ConditionOp::NotExists);
+        assert_eq!(condition.value, ConditionValue::String(String::new()));
+    }
+}
@@ -0,0 +1,530 @@
+//! Comparing the query results with a saved export or another table. `C` in
+//! the table view asks for the other side with [`DiffSourcePopup`]; the result
+//! opens full screen in [`ResultDiffWidget`], listing added, removed, and
+//! changed items with the changed attributes of the selected one below.
+
+use std::{borrow::Cow, cell::RefCell, path::PathBuf, time::Duration};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table,
+        TableState, Wrap,
+    },
+};
+
+use dynamate::core::diff::{AttributeChange, ItemDiff};
+use dynamate::core::json::{item_to_json, item_to_typed_json, value_to_json, value_to_typed_json};
+use dynamate::core::schema::KeySchema;
+use dynamate::core::value::{Item, Value};
+
+use super::export_popup::ExportPopup;
+use crate::{
+    env::{Toast, ToastAction, ToastKind},
+    help,
+    util::{abbreviate_home, fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, theme::Theme},
+};
+
+/// Asks what to compare the results with: the path of a saved export, or
+/// the name of another table.
+pub(super) struct DiffSourcePopup {
+    inner: WidgetInner,
+    state: RefCell<DiffSourceState>,
+    on_submit: Box<dyn Fn(String) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct DiffSourceState {
+    input: TextInput,
+    error: Option<String>,
+}
+
+impl DiffSourcePopup {
+    pub(super) fn new(
+        on_submit: impl Fn(String) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut input =
+            TextInput::new("Compare with", String::new()).with_placeholder("results.json");
+        input.set_active(true);
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            state: RefCell::new(DiffSourceState { input, error: None }),
+            on_submit: Box::new(on_submit),
+            help_entries: vec![
+                help_entry("⏎", "compare", "Compare the results"),
+                help_entry("esc", "cancel", "Cancel"),
+            ],
+        }
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let source = {
+            let mut state = self.state.borrow_mut();
+            let source = state.input.value().trim().to_string();
+            if source.is_empty() {
+                state.error = Some("Enter a saved export or a table name".to_string());
+                return;
+            }
+            source
+        };
+        ctx.dismiss_popup();
+        (self.on_submit)(source);
+    }
+}
+
+impl crate::widgets::Widget for DiffSourcePopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Compare results", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let state = self.state.borrow();
+        let [input_area, status_area] = inner.layout(&Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]));
+        state.input.render(frame, input_area, theme);
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (
+                "A saved export (JSON or NDJSON), or another table's name",
+                Style::default().fg(theme.text_muted()),
+            ),
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            _ => {
+                let mut state = self.state.borrow_mut();
+                if state.input.handle_event(event) {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for DiffSourcePopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        let height = 6.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// The differences between the query results and another set of items.
+pub(super) struct ResultDiffWidget {
+    inner: WidgetInner,
+    table_name: String,
+    /// What the results were compared with, e.g. a file name.
+    other: String,
+    diff: ItemDiff,
+    key: KeySchema,
+    rows: Vec<(RowKind, usize)>,
+    table_state: RefCell<TableState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+struct DiffExportRequest(PathBuf);
+
+impl ResultDiffWidget {
+    pub(super) fn new(
+        table_name: &str,
+        other: String,
+        diff: ItemDiff,
+        key: KeySchema,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let rows: Vec<(RowKind, usize)> = (0..diff.added.len())
+            .map(|idx| (RowKind::Added, idx))
+            .chain((0..diff.removed.len()).map(|idx| (RowKind::Removed, idx)))
+            .chain((0..diff.changed.len()).map(|idx| (RowKind::Changed, idx)))
+            .collect();
+        let mut table_state = TableState::default();
+        table_state.select((!rows.is_empty()).then_some(0));
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            table_name: table_name.to_string(),
+            other,
+            diff,
+            key,
+            rows,
+            table_state: RefCell::new(table_state),
+            help_entries: vec![
+                help_entry("j/k/↑/↓/PgUp/PgDn", "move", "Move selection"),
+                help_entry("x", "export", "Export the diff as JSON"),
+                help_entry("esc", "back", "Back"),
+            ],
+        }
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "+{} added · −{} removed · ~{} changed · {} unchanged",
+            self.diff.added.len(),
+            self.diff.removed.len(),
+            self.diff.changed.len(),
+            self.diff.unchanged
+        )
+    }
+
+    /// The item a row stands for: the newer version for changed items.
+    fn row_item(&self, (kind, idx): (RowKind, usize)) -> &Item {
+        match kind {
+            RowKind::Added => &self.diff.added[idx],
+            RowKind::Removed => &self.diff.removed[idx],
+            RowKind::Changed => &self.diff.changed[idx].after,
+        }
+    }
+
+    fn key_text(&self, item: &Item) -> String {
+        let key: Item = self
+            .key
+            .fields
+            .iter()
+            .filter_map(|field| {
+                item.get(&field.name)
+                    .map(|value| (field.name.clone(), value.clone()))
+            })
+            .collect();
+        compact_json(&key)
+    }
+
+    fn detail_lines(&self, theme: &Theme) -> Vec<Line<'static>> {
+        let Some(row) = self
+            .table_state
+            .borrow()
+            .selected()
+            .and_then(|idx| self.rows.get(idx).copied())
+        else {
+            return Vec::new();
+        };
+        match row.0 {
+            RowKind::Added => vec![Line::styled(
+                format!("Only in the results: {}", compact_json(self.row_item(row))),
+                Style::default().fg(theme.success()),
+            )],
+            RowKind::Removed => vec![Line::styled(
+                format!(
+                    "Only in {}: {}",
+                    self.other,
+                    compact_json(self.row_item(row))
+                ),
+                Style::default().fg(theme.error()),
+            )],
+            RowKind::Changed => self.diff.changed[row.1]
+                .changes
+                .iter()
+                .map(|change| change_line(change, theme))
+                .collect(),
+        }
+    }
+
+    fn select_by(&self, delta: isize) -> bool {
+        let len = self.rows.len();
+        if len == 0 {
+            return false;
+        }
+        let mut table_state = self.table_state.borrow_mut();
+        let current = table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        if table_state.selected() == Some(next) {
+            return false;
+        }
+        table_state.select(Some(next));
+        true
+    }
+
+    fn show_export(&self, ctx: &crate::env::WidgetCtx) {
+        let base = std::env::current_dir().unwrap_or_else(|_| std::env::temp_dir());
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let table = self
+            .table_name
+            .chars()
+            .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+            .collect::<String>();
+        let path = base.join(format!("dynamate-diff-{table}-{timestamp}.json"));
+        let ctx_for_confirm = ctx.clone();
+        ctx.set_popup(Box::new(ExportPopup::new(
+            path,
+            None,
+            false,
+            move |choice| ctx_for_confirm.emit_self(DiffExportRequest(choice.path)),
+            self.inner.id(),
+        )));
+    }
+
+    fn export(&self, ctx: &crate::env::WidgetCtx, path: &PathBuf) {
+        let result = serde_json::to_string_pretty(&self.diff.to_json())
+            .map_err(|err| err.to_string())
+            .and_then(|text| std::fs::write(path, text).map_err(|err| err.to_string()));
+        let toast = match result {
+            Ok(()) => Toast {
+                message: format!("Exported the diff to {}", abbreviate_home(path)),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(4),
+                action: Some(ToastAction::copy_path('c', path.display().to_string())),
+            },
+            Err(err) => Toast {
+                message: format!("Failed to export the diff: {err}"),
+                kind: ToastKind::Error,
+                duration: Duration::from_secs(4),
+                action: None,
+            },
+        };
+        ctx.show_toast(toast);
+    }
+}
+
+impl crate::widgets::Widget for ResultDiffWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some(format!("{} diff", self.table_name))
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            context: Some(self.table_name.clone()),
+            mode: Some("DIFF".to_string()),
+            stats: Some(self.summary()),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let heading = format!("Diff: {} vs {}", self.table_name, self.other);
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let block = Block::bordered()
+            .title_top(title)
+            .title_bottom(Line::styled(
+                pad(self.summary(), 1),
+                Style::default().fg(theme.text_muted()),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+        if self.diff.is_empty() {
+            let body = Paragraph::new("No differences")
+                .style(Style::default().fg(theme.success()))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+        frame.render_widget(block.clone(), area);
+        let [list_area, detail_area] = block.inner(area).layout(&Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(8),
+        ]));
+
+        let header = Row::new(vec!["", "Change", "Key", "Attributes"]).style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows: Vec<Row> = self
+            .rows
+            .iter()
+            .map(|&row| {
+                let (marker, label, color) = match row.0 {
+                    RowKind::Added => ("+", "added", theme.success()),
+                    RowKind::Removed => ("−", "removed", theme.error()),
+                    RowKind::Changed => ("~", "changed", theme.warning()),
+                };
+                let attributes = match row.0 {
+                    RowKind::Changed => self.diff.changed[row.1]
+                        .changes
+                        .iter()
+                        .map(|change| change.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    _ => String::new(),
+                };
+                Row::new(vec![
+                    Cell::from(marker).style(Style::default().fg(color)),
+                    Cell::from(label).style(Style::default().fg(color)),
+                    Cell::from(self.key_text(self.row_item(row))),
+                    Cell::from(attributes),
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(1),
+            Constraint::Length(8),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("❯ ")
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(
+            table,
+            list_area,
+            frame.buffer_mut(),
+            &mut self.table_state.borrow_mut(),
+        );
+
+        let detail = Paragraph::new(self.detail_lines(theme))
+            .wrap(Wrap { trim: false })
+            .block(Block::new().borders(Borders::TOP));
+        frame.render_widget(detail, detail_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let moved = match key.code {
+            KeyCode::Char('x') => {
+                self.show_export(&ctx);
+                return true;
+            }
+            KeyCode::Esc => {
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.select_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_by(-1),
+            KeyCode::PageDown => self.select_by(10),
+            KeyCode::PageUp => self.select_by(-10),
+            _ => return false,
+        };
+        if moved {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(DiffExportRequest(path)) = event.payload::<DiffExportRequest>() {
+            self.export(&ctx, path);
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+/// `name: before → after`, with a missing side shown as a dash.
+fn change_line(change: &AttributeChange, theme: &Theme) -> Line<'static> {
+    let side = |value: Option<&Value>| {
+        value.map_or_else(
+            || "—".to_string(),
+            |value| {
+                value_to_json(value)
+                    .unwrap_or_else(|_| value_to_typed_json(value))
+                    .to_string()
+            },
+        )
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("{}: ", change.name),
+            Style::default().fg(theme.text()),
+        ),
+        Span::styled(
+            side(change.before.as_ref()),
+            Style::default().fg(theme.error()),
+        ),
+        Span::styled(" → ", Style::default().fg(theme.text_muted())),
+        Span::styled(
+            side(change.after.as_ref()),
+            Style::default().fg(theme.success()),
+        ),
+    ])
+}
+
+/// An item as one line of plain JSON, falling back to DynamoDB JSON for
+/// types plain JSON can't hold.
+fn compact_json(item: &Item) -> String {
+    item_to_json(item)
+        .or_else(|_| item_to_typed_json(item))
+        .map_or_else(|err| err.to_string(), |json| json.to_string())
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
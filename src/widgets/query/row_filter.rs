@@ -0,0 +1,246 @@
+//! The `/` filter over loaded rows. It is a list of space-separated terms that
+//! must all match:
+//!
+//! - `text` appears in any attribute name or value, ignoring case.
+//! - `field:text` appears in that attribute's value (a nested path works too).
+//! - `/pattern/` is a regular expression, also after `field:`.
+//! - `!term` keeps the rows the term doesn't match.
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use regex::{Regex, RegexBuilder};
+
+use super::projection;
+use dynamate::core::language::{Condition, ConditionOp, ConditionValue};
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct RowFilter {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    /// As typed, to tell whether a later filter keeps this term.
+    raw: String,
+    negated: bool,
+    field: Option<String>,
+    pattern: Pattern,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    /// Lowercased.
+    Text(String),
+    Regex(Regex),
+}
+
+impl RowFilter {
+    pub(super) fn parse(text: &str) -> Result<Self, String> {
+        let terms = text
+            .split_whitespace()
+            .filter_map(|raw| Term::parse(raw).transpose())
+            .collect::<Result<_, _>>()?;
+        Ok(Self { terms })
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether `item` passes. `search_text` is the item's [`search_text`],
+    /// which callers keep to reuse across filters.
+    pub(super) fn matches(
+        &self,
+        item: &HashMap<String, AttributeValue>,
+        search_text: &str,
+    ) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.matches(item, search_text) != term.negated)
+    }
+
+    /// The filter as query conditions, when every term is a `field:text` one:
+    /// each becomes "`field` contains `text`". The server matches case as
+    /// typed, unlike the local filter.
+    pub(super) fn conditions(&self) -> Result<Vec<Condition>, String> {
+        self.terms.iter().map(Term::condition).collect()
+    }
+
+    /// Whether every row this filter passes, `previous` passed too, so the
+    /// rows `previous` kept are enough to filter again.
+    pub(super) fn narrows(&self, previous: &Self) -> bool {
+        !previous.is_empty()
+            && previous
+                .terms
+                .iter()
+                .all(|old| self.terms.iter().any(|new| new.is_stricter_than(old)))
+    }
+}
+
+impl Term {
+    /// A term, or `None` for one with nothing to match yet (a lone `!` or
+    /// `field:`).
+    fn parse(raw: &str) -> Result<Option<Self>, String> {
+        let (negated, rest) = match raw.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+        let (field, value) = match rest.split_once(':') {
+            Some((field, value)) if !field.is_empty() && !field.starts_with('/') => {
+                (Some(field.to_string()), value)
+            }
+            _ => (None, rest),
+        };
+        let pattern = match value
+            .strip_prefix('/')
+            .and_then(|value| value.strip_suffix('/'))
+        {
+            Some(pattern) if !pattern.is_empty() => Pattern::Regex(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|err| format!("bad pattern /{pattern}/: {err}"))?,
+            ),
+            _ if value.is_empty() => return Ok(None),
+            _ => Pattern::Text(value.to_lowercase()),
+        };
+        Ok(Some(Self {
+            raw: raw.to_string(),
+            negated,
+            field,
+            pattern,
+        }))
+    }
+
+    fn matches(&self, item: &HashMap<String, AttributeValue>, search_text: &str) -> bool {
+        match (&self.field, &self.pattern) {
+            (Some(field), pattern) => projection::attribute_at(item, field)
+                .is_some_and(|value| pattern.is_match(&value_text(value))),
+            (None, Pattern::Text(needle)) => search_text.contains(needle.as_str()),
+            (None, pattern @ Pattern::Regex(_)) => item.iter().any(|(name, value)| {
+                pattern.is_match(name) || pattern.is_match(&value_text(value))
+            }),
+        }
+    }
+
+    fn condition(&self) -> Result<Condition, String> {
+        match (&self.field, &self.pattern, self.negated) {
+            (Some(field), Pattern::Text(_), false) => {
+                let text = self.raw.split_once(':').map_or("", |(_, text)| text);
+                Ok(Condition {
+                    attribute: field.clone(),
+                    op: ConditionOp::Contains,
+                    value: ConditionValue::String(text.to_string()),
+                })
+            }
+            _ => Err(format!(
+                "{} can't be filtered on the server, only field:text terms can",
+                self.raw
+            )),
+        }
+    }
+
+    /// Whether this term passes only rows `other` passes: the same term, or
+    /// the same positive text term grown longer.
+    fn is_stricter_than(&self, other: &Self) -> bool {
+        if self.raw == other.raw {
+            return true;
+        }
+        match (&self.pattern, &other.pattern) {
+            (Pattern::Text(new), Pattern::Text(old)) => {
+                !self.negated
+                    && !other.negated
+                    && self.field == other.field
+                    && new.contains(old.as_str())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Pattern {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Text(needle) => text.to_lowercase().contains(needle.as_str()),
+            Self::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// The lowercased attribute names and values of `item`, each followed by a
+/// NUL so a match can't span two of them.
+pub(super) fn search_text(item: &HashMap<String, AttributeValue>) -> String {
+    let mut text = String::new();
+    for (key, value) in item {
+        text.push_str(&key.to_lowercase());
+        text.push('\0');
+        text.push_str(&value_text(value).to_lowercase());
+        text.push('\0');
+    }
+    text
+}
+
+/// The text a value is matched by: strings and numbers as they are, other
+/// types in their debug form.
+fn value_text(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::S(v) | AttributeValue::N(v) => v.clone(),
+        AttributeValue::Bool(v) => v.to_string(),
+        _ => format!("{value:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::RowFilter;
+    use dynamate::core::language::ConditionValue;
+
+    fn passes(filter: &str, item: &HashMap<String, AttributeValue>) -> bool {
+        RowFilter::parse(filter)
+            .unwrap()
+            .matches(item, &super::search_text(item))
+    }
+
+    #[test]
+    fn scopes_negates_and_matches_patterns() {
+        let item = HashMap::from([
+            (
+                "status".to_string(),
+                AttributeValue::S("Active".to_string()),
+            ),
+            (
+                "email".to_string(),
+                AttributeValue::S("ada@example.com".to_string()),
+            ),
+        ]);
+        assert!(passes("active", &item));
+        assert!(passes("status:act", &item));
+        assert!(!passes("email:act", &item));
+        assert!(passes("!status:closed", &item));
+        assert!(!passes("!active", &item));
+        assert!(passes("email:/^ada@.*\\.com$/", &item));
+        assert!(passes("/^STATUS$/ example", &item));
+        assert!(!passes("missing:x", &item));
+        assert!(passes("! status:", &item));
+        assert!(RowFilter::parse("/(/").is_err());
+
+        let parse = |text| RowFilter::parse(text).unwrap();
+        assert!(parse("status:act x").narrows(&parse("status:a")));
+        assert!(!parse("status:act").narrows(&parse("act")));
+        assert!(!parse("!ab").narrows(&parse("!a")));
+        assert!(!parse("a").narrows(&parse("")));
+
+        let conditions = parse("status:Open").conditions().unwrap();
+        assert_eq!(conditions[0].attribute, "status");
+        assert_eq!(
+            conditions[0].value,
+            ConditionValue::String("Open".to_string())
+        );
+        assert!(parse("status:open !x").conditions().is_err());
+    }
+}
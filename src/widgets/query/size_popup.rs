@@ -0,0 +1,236 @@
+use std::{borrow::Cow, cell::Cell};
+
+use crossterm::event::{Event, KeyCode};
+use humansize::{BINARY, format_size};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use dynamate::core::size::MAX_ITEM_SIZE_BYTES;
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Width of the bar that shows an attribute's share of the item.
+const BAR_WIDTH: usize = 12;
+
+/// Breaks an item's estimated size down by attribute, largest first, against
+/// DynamoDB's 400 KB item limit.
+pub(super) struct SizePopup {
+    inner: WidgetInner,
+    sizes: Vec<(String, usize)>,
+    total: usize,
+    scroll: Cell<usize>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+impl SizePopup {
+    /// `sizes` are the per-attribute sizes, largest first.
+    pub(super) fn new(sizes: Vec<(String, usize)>, parent: crate::env::WidgetId) -> Self {
+        let total = sizes.iter().map(|(_, size)| size).sum();
+        let help_entries = vec![
+            help::Entry {
+                keys: Cow::Borrowed("j/k/↑/↓"),
+                short: Cow::Borrowed("scroll"),
+                long: Cow::Borrowed("Scroll attributes"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+            help::Entry {
+                keys: Cow::Borrowed("esc"),
+                short: Cow::Borrowed("close"),
+                long: Cow::Borrowed("Close"),
+                ctrl: None,
+                shift: None,
+                alt: None,
+            },
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            sizes,
+            total,
+            scroll: Cell::new(0),
+            help_entries,
+        }
+    }
+
+    fn row(&self, name: &str, size: usize, name_width: usize, theme: &Theme) -> Line<'static> {
+        let share = if self.total == 0 {
+            0.0
+        } else {
+            size as f64 / self.total as f64
+        };
+        let filled = (share * BAR_WIDTH as f64).round() as usize;
+        let name: String = name.chars().take(name_width).collect();
+        Line::from(vec![
+            Span::styled(
+                format!("{name:<name_width$} "),
+                Style::default().fg(theme.text()),
+            ),
+            Span::styled(
+                format!("{:>10} ", format_size(size as u64, BINARY)),
+                Style::default().fg(theme.text()),
+            ),
+            Span::styled(
+                format!("{:>6} ", limit_percent(size)),
+                Style::default().fg(theme.text_muted()),
+            ),
+            Span::styled("█".repeat(filled), Style::default().fg(theme.accent())),
+            Span::styled(
+                "░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)),
+                Style::default().fg(theme.border()),
+            ),
+        ])
+    }
+}
+
+/// `size` as a percentage of the 400 KB item limit.
+fn limit_percent(size: usize) -> String {
+    let percent = size as f64 * 100.0 / MAX_ITEM_SIZE_BYTES as f64;
+    if percent > 0.0 && percent < 0.1 {
+        "<0.1%".to_string()
+    } else {
+        format!("{percent:.1}%")
+    }
+}
+
+impl crate::widgets::Widget for SizePopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Item size", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ]);
+        let [summary_area, body_area, footer_area] = inner.layout(&layout);
+
+        let over = self.total > MAX_ITEM_SIZE_BYTES;
+        let summary_style = if over {
+            Style::default().fg(theme.error())
+        } else {
+            Style::default().fg(theme.text())
+        };
+        frame.render_widget(
+            Paragraph::new(format!(
+                "~{} · {} of the 400 KB limit",
+                format_size(self.total as u64, BINARY),
+                limit_percent(self.total)
+            ))
+            .style(summary_style),
+            summary_area,
+        );
+
+        let visible = body_area.height as usize;
+        let max_scroll = self.sizes.len().saturating_sub(visible);
+        let scroll = self.scroll.get().min(max_scroll);
+        self.scroll.set(scroll);
+        let name_width = self
+            .sizes
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(
+                (body_area.width as usize)
+                    .saturating_sub(BAR_WIDTH + 19)
+                    .max(4),
+            );
+        let lines: Vec<Line> = self
+            .sizes
+            .iter()
+            .skip(scroll)
+            .take(visible)
+            .map(|(name, size)| self.row(name, *size, name_width, theme))
+            .collect();
+        frame.render_widget(Paragraph::new(lines), body_area);
+
+        let footer = if over {
+            "Over the limit: DynamoDB will reject writes of this item"
+        } else {
+            "Estimated with DynamoDB's item size rules"
+        };
+        frame.render_widget(
+            Paragraph::new(footer).style(Style::default().fg(theme.text_muted())),
+            footer_area,
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                let last = self.sizes.len().saturating_sub(1);
+                self.scroll.set((self.scroll.get() + 1).min(last));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.scroll.set(self.scroll.get().saturating_sub(1));
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => ctx.dismiss_popup(),
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for SizePopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        // One row per attribute, the summary, the footer, and the borders.
+        let wanted = (self.sizes.len() + 4) as u16;
+        let height = wanted.clamp(6, 24).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::limit_percent;
+
+    #[test]
+    fn limit_percent_is_relative_to_400_kb() {
+        assert_eq!(limit_percent(0), "0.0%");
+        assert_eq!(limit_percent(10), "<0.1%");
+        assert_eq!(limit_percent(204_800), "50.0%");
+        assert_eq!(limit_percent(409_600), "100.0%");
+    }
+}
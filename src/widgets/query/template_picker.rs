@@ -0,0 +1,385 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
+
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue};
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    prelude::StatefulWidget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, HighlightSpacing, List, ListItem, ListState},
+};
+
+use dynamate::core::schema::{CollectionSchema, ScalarType};
+
+use crate::{
+    help,
+    util::fill_bg,
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// How many of the most common item shapes are offered.
+const MAX_SHAPES: usize = 5;
+
+/// A starting point for a new item.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct ItemTemplate {
+    pub(super) name: String,
+    /// What the template holds, shown dimmed after the name.
+    pub(super) detail: String,
+    pub(super) item: HashMap<String, AttributeValue>,
+}
+
+/// The templates offered for a new item: an empty one, the table's key
+/// attributes, the templates saved for the table in the config file, and the
+/// most common shapes among the loaded items, with their values blanked.
+pub(super) fn item_templates<'a>(
+    schema: Option<&CollectionSchema>,
+    saved: Vec<(String, HashMap<String, AttributeValue>)>,
+    items: impl IntoIterator<Item = &'a HashMap<String, AttributeValue>>,
+) -> Vec<ItemTemplate> {
+    let mut templates = vec![ItemTemplate {
+        name: "Empty item".to_string(),
+        detail: "{}".to_string(),
+        item: HashMap::new(),
+    }];
+    if let Some(schema) = schema.filter(|schema| !schema.key.fields.is_empty()) {
+        let fields = &schema.key.fields;
+        templates.push(ItemTemplate {
+            name: "Key attributes".to_string(),
+            detail: fields
+                .iter()
+                .map(|field| format!("{} ({})", field.name, key_type(field.ty)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            item: fields
+                .iter()
+                .map(|field| (field.name.clone(), blank_key(field.ty)))
+                .collect(),
+        });
+    }
+    templates.extend(saved.into_iter().map(|(name, item)| ItemTemplate {
+        detail: format!("saved, {}", attribute_list(&item)),
+        name,
+        item,
+    }));
+
+    // Items with the same attribute names and types share a shape.
+    let mut shapes: BTreeMap<Vec<(&str, &str)>, (usize, &HashMap<String, AttributeValue>)> =
+        BTreeMap::new();
+    for item in items {
+        let mut shape: Vec<(&str, &str)> = item
+            .iter()
+            .map(|(name, value)| (name.as_str(), type_tag(value)))
+            .collect();
+        shape.sort_unstable();
+        shapes.entry(shape).or_insert((0, item)).0 += 1;
+    }
+    let mut shapes: Vec<_> = shapes.into_values().collect();
+    shapes.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+    templates.extend(shapes.into_iter().take(MAX_SHAPES).map(|(count, item)| {
+        ItemTemplate {
+            name: format!(
+                "Like {count} loaded {}",
+                if count == 1 { "item" } else { "items" }
+            ),
+            detail: attribute_list(item),
+            item: item
+                .iter()
+                .map(|(name, value)| (name.clone(), blank(value)))
+                .collect(),
+        }
+    }));
+    templates
+}
+
+fn attribute_list(item: &HashMap<String, AttributeValue>) -> String {
+    let mut names: Vec<&str> = item.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    names.join(", ")
+}
+
+fn key_type(ty: ScalarType) -> &'static str {
+    match ty {
+        ScalarType::String => "S",
+        ScalarType::Number => "N",
+        ScalarType::Binary => "B",
+    }
+}
+
+fn blank_key(ty: ScalarType) -> AttributeValue {
+    match ty {
+        ScalarType::String => AttributeValue::S(String::new()),
+        ScalarType::Number => AttributeValue::N("0".to_string()),
+        ScalarType::Binary => AttributeValue::B(Blob::new(Vec::new())),
+    }
+}
+
+fn type_tag(value: &AttributeValue) -> &'static str {
+    match value {
+        AttributeValue::S(_) => "S",
+        AttributeValue::N(_) => "N",
+        AttributeValue::B(_) => "B",
+        AttributeValue::Bool(_) => "BOOL",
+        AttributeValue::Null(_) => "NULL",
+        AttributeValue::L(_) => "L",
+        AttributeValue::M(_) => "M",
+        AttributeValue::Ss(_) => "SS",
+        AttributeValue::Ns(_) => "NS",
+        AttributeValue::Bs(_) => "BS",
+        _ => "?",
+    }
+}
+
+/// A value of the same type with nothing in it. Maps keep their attributes,
+/// and sets keep one blank member since they can't be empty.
+fn blank(value: &AttributeValue) -> AttributeValue {
+    match value {
+        AttributeValue::S(_) => AttributeValue::S(String::new()),
+        AttributeValue::N(_) => AttributeValue::N("0".to_string()),
+        AttributeValue::B(_) => AttributeValue::B(Blob::new(Vec::new())),
+        AttributeValue::Bool(_) => AttributeValue::Bool(false),
+        AttributeValue::L(_) => AttributeValue::L(Vec::new()),
+        AttributeValue::M(map) => AttributeValue::M(
+            map.iter()
+                .map(|(name, value)| (name.clone(), blank(value)))
+                .collect(),
+        ),
+        AttributeValue::Ss(_) => AttributeValue::Ss(vec![String::new()]),
+        AttributeValue::Ns(_) => AttributeValue::Ns(vec!["0".to_string()]),
+        AttributeValue::Bs(_) => AttributeValue::Bs(vec![Blob::new(Vec::new())]),
+        other => other.clone(),
+    }
+}
+
+/// Picks the template a new item starts from.
+pub(super) struct TemplatePicker {
+    inner: WidgetInner,
+    title: &'static str,
+    templates: Vec<ItemTemplate>,
+    state: RefCell<ListState>,
+    on_select: Box<dyn Fn(HashMap<String, AttributeValue>) + Send + 'static>,
+}
+
+impl TemplatePicker {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓/j/k"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("select"),
+            long: Cow::Borrowed("Edit a new item from the template"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close"),
+            long: Cow::Borrowed("Close picker"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    pub(super) fn new(
+        title: &'static str,
+        templates: Vec<ItemTemplate>,
+        on_select: impl Fn(HashMap<String, AttributeValue>) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut state = ListState::default();
+        state.select(Some(0));
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            title,
+            templates,
+            state: RefCell::new(state),
+            on_select: Box::new(on_select),
+        }
+    }
+}
+
+impl crate::widgets::Widget for TemplatePicker {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::styled(
+                format!(" {} ", self.title),
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+
+        let items: Vec<ListItem> = self
+            .templates
+            .iter()
+            .map(|template| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(template.name.as_str()),
+                    Span::styled(
+                        format!("  {}", template.detail),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("> ")
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(
+            list,
+            block.inner(area),
+            frame.buffer_mut(),
+            &mut self.state.borrow_mut(),
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Up | KeyCode::Char('k') => self.state.borrow_mut().select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.state.borrow_mut().select_next(),
+            KeyCode::Enter => {
+                let selected = self.state.borrow().selected();
+                if let Some(template) = selected.and_then(|row| self.templates.get(row)) {
+                    ctx.dismiss_popup();
+                    (self.on_select)(template.item.clone());
+                }
+            }
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for TemplatePicker {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        let rows = u16::try_from(self.templates.len()).unwrap_or(u16::MAX);
+        let height = rows.saturating_add(2).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+    use dynamate::core::schema::{CollectionSchema, KeyField, KeyRole, KeySchema, ScalarType};
+
+    use super::item_templates;
+
+    #[test]
+    fn offers_keys_saved_templates_and_common_shapes() {
+        let schema = CollectionSchema {
+            name: "orders".to_string(),
+            key: KeySchema {
+                fields: vec![
+                    KeyField {
+                        name: "pk".to_string(),
+                        role: KeyRole::Partition,
+                        ty: ScalarType::String,
+                    },
+                    KeyField {
+                        name: "sk".to_string(),
+                        role: KeyRole::Sort,
+                        ty: ScalarType::Number,
+                    },
+                ],
+            },
+            ..CollectionSchema::default()
+        };
+        let order = |pk: &str, total: &str| {
+            HashMap::from([
+                ("pk".to_string(), AttributeValue::S(pk.to_string())),
+                ("sk".to_string(), AttributeValue::N("1".to_string())),
+                ("total".to_string(), AttributeValue::N(total.to_string())),
+                (
+                    "tags".to_string(),
+                    AttributeValue::Ss(vec!["new".to_string()]),
+                ),
+            ])
+        };
+        let customer = HashMap::from([
+            ("pk".to_string(), AttributeValue::S("c#1".to_string())),
+            ("sk".to_string(), AttributeValue::N("0".to_string())),
+        ]);
+        let items = [order("o#1", "10"), customer, order("o#2", "25")];
+        let saved = vec![(
+            "open order".to_string(),
+            HashMap::from([("status".to_string(), AttributeValue::S("OPEN".to_string()))]),
+        )];
+        let templates = item_templates(Some(&schema), saved, &items);
+
+        let names: Vec<_> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Empty item",
+                "Key attributes",
+                "open order",
+                "Like 2 loaded items",
+                "Like 1 loaded item"
+            ]
+        );
+        assert_eq!(templates[1].detail, "pk (S), sk (N)");
+        assert_eq!(
+            templates[1].item.get("sk"),
+            Some(&AttributeValue::N("0".to_string()))
+        );
+        assert_eq!(templates[3].detail, "pk, sk, tags, total");
+        assert_eq!(
+            templates[3].item.get("pk"),
+            Some(&AttributeValue::S(String::new()))
+        );
+        assert_eq!(
+            templates[3].item.get("tags"),
+            Some(&AttributeValue::Ss(vec![String::new()]))
+        );
+
+        let templates = item_templates(None, Vec::new(), []);
+        assert_eq!(templates.len(), 1);
+    }
+}
@@ -0,0 +1,133 @@
+//! Showing timestamp columns readably: epoch seconds or milliseconds as UTC
+//! date-times, and ISO 8601 strings as a time relative to now. Chosen per
+//! column in the fields popup (`t`); the item's TTL uses the same format.
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TimeFormat {
+    EpochSeconds,
+    EpochMillis,
+    Relative,
+}
+
+impl TimeFormat {
+    /// The next format `t` picks: none, then seconds, millis, relative, and
+    /// back to none.
+    pub(super) fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::EpochSeconds),
+            Some(Self::EpochSeconds) => Some(Self::EpochMillis),
+            Some(Self::EpochMillis) => Some(Self::Relative),
+            Some(Self::Relative) => None,
+        }
+    }
+
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            Self::EpochSeconds => "epoch s",
+            Self::EpochMillis => "epoch ms",
+            Self::Relative => "relative",
+        }
+    }
+
+    /// `value` in this format, or `None` when it isn't a timestamp of this
+    /// kind (the cell then shows the raw value).
+    pub(super) fn apply(self, value: &AttributeValue, now: DateTime<Utc>) -> Option<String> {
+        match self {
+            Self::EpochSeconds => {
+                let secs = epoch_number(value)?;
+                DateTime::from_timestamp(secs, 0).map(format_utc)
+            }
+            Self::EpochMillis => {
+                let millis = epoch_number(value)?;
+                DateTime::from_timestamp_millis(millis).map(format_utc)
+            }
+            Self::Relative => {
+                let AttributeValue::S(text) = value else {
+                    return None;
+                };
+                parse_iso(text).map(|time| relative(time, now))
+            }
+        }
+    }
+}
+
+/// A whole number stored as a number or a numeric string.
+fn epoch_number(value: &AttributeValue) -> Option<i64> {
+    match value {
+        AttributeValue::N(text) | AttributeValue::S(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+pub(super) fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%SZ").to_string()
+}
+
+/// RFC 3339, or a date-time without an offset taken as UTC.
+pub(super) fn parse_iso(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                .map(|time| time.and_utc())
+        })
+}
+
+/// How long before or after `now` `time` is, in its largest unit.
+fn relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - time).num_seconds();
+    let magnitude = seconds.unsigned_abs();
+    let amount = match magnitude {
+        0..60 => return "just now".to_string(),
+        60..3_600 => format!("{}m", magnitude / 60),
+        3_600..86_400 => format!("{}h", magnitude / 3_600),
+        86_400..31_536_000 => format!("{}d", magnitude / 86_400),
+        _ => format!("{}y", magnitude / 31_536_000),
+    };
+    if seconds > 0 {
+        format!("{amount} ago")
+    } else {
+        format!("in {amount}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::types::AttributeValue;
+    use chrono::DateTime;
+
+    use super::TimeFormat;
+
+    #[test]
+    fn formats_epochs_and_relative_times() {
+        let now = DateTime::from_timestamp(1_767_225_600, 0).unwrap();
+        let number = |text: &str| AttributeValue::N(text.to_string());
+        let string = |text: &str| AttributeValue::S(text.to_string());
+        assert_eq!(
+            TimeFormat::EpochSeconds.apply(&number("1767225600"), now),
+            Some("2026-01-01 00:00:00Z".to_string())
+        );
+        assert_eq!(
+            TimeFormat::EpochMillis.apply(&string("1767225600000"), now),
+            Some("2026-01-01 00:00:00Z".to_string())
+        );
+        assert_eq!(
+            TimeFormat::Relative.apply(&string("2025-12-31T21:00:00Z"), now),
+            Some("3h ago".to_string())
+        );
+        assert_eq!(
+            TimeFormat::Relative.apply(&string("2026-01-03 00:00:00"), now),
+            Some("in 2d".to_string())
+        );
+        assert_eq!(TimeFormat::Relative.apply(&number("1767225600"), now), None);
+        assert_eq!(TimeFormat::EpochSeconds.apply(&string("soon"), now), None);
+        assert_eq!(TimeFormat::cycle(Some(TimeFormat::Relative)), None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use aws_sdk_dynamodb::types::AttributeValue;
 use ratatui::{
@@ -9,31 +9,231 @@ use serde_json::Value;
 
 use dynamate::dynamodb::json::{self, JsonConversionError};
 
-use crate::widgets::theme::Theme;
+use crate::widgets::{filter_input::FilterInput, theme::Theme};
+
+/// One line of an item's tree: a key (or list element) and its value.
+/// Non-empty maps and lists are followed by their children unless collapsed.
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    /// Document path of the value, e.g. `address.lines[0]`.
+    pub path: String,
+    depth: usize,
+    /// The map key, or `None` for a list element.
+    key: Option<String>,
+    pub value: Value,
+    collapsed: bool,
+}
+
+impl TreeRow {
+    fn is_container(&self) -> bool {
+        match &self.value {
+            Value::Object(map) => !map.is_empty(),
+            Value::Array(values) => !values.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Case-insensitive match of `needle` (already lowercased) against the
+    /// key and, for scalars, the value.
+    fn matches(&self, needle: &str) -> bool {
+        self.key
+            .as_ref()
+            .is_some_and(|key| key.to_lowercase().contains(needle))
+            || (is_scalar(&self.value) && scalar_text(&self.value).to_lowercase().contains(needle))
+    }
+
+    pub fn to_line(&self, theme: &Theme) -> Line<'static> {
+        let indent = self.depth * 2;
+        let mut spans = vec![indent_span(indent, theme)];
+        match &self.key {
+            Some(key) => spans.push(Span::styled(
+                key.clone(),
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            )),
+            None => spans.push(Span::styled("-", Style::default().fg(theme.text_muted()))),
+        }
+        let separator = match (&self.key, self.is_container() && !self.collapsed) {
+            (Some(_), true) => ":",
+            (Some(_), false) => ": ",
+            (None, true) => "",
+            (None, false) => " ",
+        };
+        spans.push(Span::styled(
+            separator,
+            Style::default().fg(theme.text_muted()),
+        ));
+        if !self.is_container() {
+            spans.push(scalar_span(&self.value, theme));
+        } else if self.collapsed {
+            let (marker, count, noun) = match &self.value {
+                Value::Object(map) => ("{…}", map.len(), "key"),
+                Value::Array(values) => ("[…]", values.len(), "element"),
+                _ => ("", 0, ""),
+            };
+            let plural = if count == 1 { "" } else { "s" };
+            spans.push(Span::styled(
+                format!("{marker} {count} {noun}{plural}"),
+                Style::default().fg(theme.text_muted()),
+            ));
+        }
+        Line::from(spans)
+    }
+}
+
+/// Cursor, folded paths, and search for the interactive item tree. Rows are
+/// rebuilt from the item on every use, so only paths are remembered here.
+#[derive(Debug, Default)]
+pub struct TreeState {
+    collapsed: HashSet<String>,
+    pub cursor: usize,
+    pub search: FilterInput,
+}
+
+impl TreeState {
+    pub fn reset(&mut self) {
+        self.collapsed.clear();
+        self.cursor = 0;
+        self.search.clear();
+        self.search.set_active(false);
+    }
+
+    pub fn collapsed(&self) -> &HashSet<String> {
+        &self.collapsed
+    }
+
+    /// Lowercased search text, or `None` when nothing was typed.
+    pub fn needle(&self) -> Option<String> {
+        let needle = self.search.value.trim().to_lowercase();
+        (!needle.is_empty()).then_some(needle)
+    }
+
+    /// Fold or unfold the container under the cursor.
+    pub fn toggle(&mut self, rows: &[TreeRow]) {
+        let Some(row) = rows.get(self.cursor).filter(|row| row.is_container()) else {
+            return;
+        };
+        if !self.collapsed.remove(&row.path) {
+            self.collapsed.insert(row.path.clone());
+        }
+    }
+
+    /// Unfold the container under the cursor, or step into it when it's
+    /// already open.
+    pub fn expand(&mut self, rows: &[TreeRow]) {
+        let Some(row) = rows.get(self.cursor).filter(|row| row.is_container()) else {
+            return;
+        };
+        if !self.collapsed.remove(&row.path) {
+            self.cursor += 1;
+        }
+    }
+
+    /// Fold the container under the cursor, or step out to its parent.
+    pub fn collapse(&mut self, rows: &[TreeRow]) {
+        let Some(row) = rows.get(self.cursor) else {
+            return;
+        };
+        if row.is_container() && !row.collapsed {
+            self.collapsed.insert(row.path.clone());
+            return;
+        }
+        if let Some(parent) = rows[..self.cursor]
+            .iter()
+            .rposition(|candidate| candidate.depth < row.depth)
+        {
+            self.cursor = parent;
+        }
+    }
+
+    /// The path of the next (or previous) row matching the search, among
+    /// `all_rows` (the unfolded tree), starting after `current`. Wraps around;
+    /// `include_current` lets the current row match, for search-as-you-type.
+    pub fn find(
+        &self,
+        all_rows: &[TreeRow],
+        current: Option<&str>,
+        forward: bool,
+        include_current: bool,
+    ) -> Option<String> {
+        let needle = self.needle()?;
+        let total = all_rows.len();
+        if total == 0 {
+            return None;
+        }
+        let start = current
+            .and_then(|path| all_rows.iter().position(|row| row.path == path))
+            .unwrap_or(0);
+        let skip = usize::from(!include_current);
+        (0..total)
+            .map(|step| {
+                let step = step + skip;
+                if forward {
+                    (start + step) % total
+                } else {
+                    (start + total * 2 - step) % total
+                }
+            })
+            .map(|idx| &all_rows[idx])
+            .find(|row| row.matches(&needle))
+            .map(|row| row.path.clone())
+    }
+
+    /// Unfold every ancestor of `path` so its row is visible.
+    pub fn reveal(&mut self, path: &str) {
+        self.collapsed.retain(|folded| !is_ancestor(folded, path));
+    }
+}
+
+fn is_ancestor(candidate: &str, path: &str) -> bool {
+    path.strip_prefix(candidate)
+        .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+}
+
+/// The item's rows, skipping the children of `collapsed` paths. Top-level
+/// attributes follow `key_order`; anything else is sorted by name.
+pub fn item_rows(
+    item: &HashMap<String, AttributeValue>,
+    key_order: Option<&[String]>,
+    collapsed: &HashSet<String>,
+) -> Result<Vec<TreeRow>, String> {
+    let value = item_to_json_value(item).map_err(|err| err.to_string())?;
+    let mut rows = Vec::new();
+    push_rows(&value, "", 0, collapsed, &mut rows, key_order);
+    Ok(rows)
+}
 
 pub fn item_to_lines(
-    item: &std::collections::HashMap<String, AttributeValue>,
+    item: &HashMap<String, AttributeValue>,
     theme: &Theme,
     key_order: Option<&[String]>,
 ) -> Vec<Line<'static>> {
-    let value = match item_to_json_value(item) {
-        Ok(value) => value,
+    let rows = match item_rows(item, key_order, &HashSet::new()) {
+        Ok(rows) => rows,
         Err(err) => {
             return vec![Line::from(format!("Failed to render item: {err}"))];
         }
     };
+    if rows.is_empty() {
+        return vec![Line::from("(empty item)")];
+    }
+    rows.iter().map(|row| row.to_line(theme)).collect()
+}
 
-    let mut lines = Vec::new();
-    render_value(&value, 0, theme, &mut lines, key_order);
-    if lines.is_empty() {
-        lines.push(Line::from("(empty item)"));
+/// The clipboard text for a row's value: scalars bare, anything else as
+/// pretty-printed JSON.
+pub fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Object(_) | Value::Array(_) => {
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+        }
+        _ => scalar_text(value),
     }
-    lines
 }
 
-fn item_to_json_value(
-    item: &std::collections::HashMap<String, AttributeValue>,
-) -> json::Result<Value> {
+fn item_to_json_value(item: &HashMap<String, AttributeValue>) -> json::Result<Value> {
     match json::to_json(item) {
         Ok(value) => Ok(value),
         Err(JsonConversionError::UnsupportedType { .. }) => json::to_dynamodb_json(item),
@@ -41,112 +241,84 @@ fn item_to_json_value(
     }
 }
 
-fn render_value(
+fn push_rows(
     value: &Value,
-    indent: usize,
-    theme: &Theme,
-    lines: &mut Vec<Line<'static>>,
+    parent: &str,
+    depth: usize,
+    collapsed: &HashSet<String>,
+    rows: &mut Vec<TreeRow>,
     key_order: Option<&[String]>,
 ) {
     match value {
         Value::Object(map) => {
-            if map.is_empty() {
-                lines.push(Line::from(vec![
-                    indent_span(indent, theme),
-                    Span::styled("{}", Style::default().fg(theme.text_muted())),
-                ]));
-                return;
-            }
-
-            let mut keys: Vec<&str> = Vec::new();
-            if let Some(order) = key_order {
-                let mut seen = HashSet::new();
-                for key in order {
-                    if map.contains_key(key) {
-                        keys.push(key.as_str());
-                        seen.insert(key.as_str());
-                    }
-                }
-                let mut remaining: Vec<&str> = map
-                    .keys()
-                    .map(std::string::String::as_str)
-                    .filter(|key| !seen.contains(key))
-                    .collect();
-                remaining.sort_unstable();
-                keys.extend(remaining);
-            } else {
-                let mut sorted: Vec<&str> = map.keys().map(std::string::String::as_str).collect();
-                sorted.sort_unstable();
-                keys = sorted;
-            }
-
-            for key in keys {
+            for key in ordered_keys(map, key_order) {
                 let Some(child) = map.get(key) else {
                     continue;
                 };
-                if is_scalar(child) {
-                    let line = Line::from(vec![
-                        indent_span(indent, theme),
-                        Span::styled(
-                            key.to_string(),
-                            Style::default()
-                                .fg(theme.accent())
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled(": ", Style::default().fg(theme.text_muted())),
-                        scalar_span(child, theme),
-                    ]);
-                    lines.push(line);
+                let path = if parent.is_empty() {
+                    key.to_string()
                 } else {
-                    let line = Line::from(vec![
-                        indent_span(indent, theme),
-                        Span::styled(
-                            key.to_string(),
-                            Style::default()
-                                .fg(theme.accent())
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled(":", Style::default().fg(theme.text_muted())),
-                    ]);
-                    lines.push(line);
-                    render_value(child, indent + 2, theme, lines, None);
-                }
+                    format!("{parent}.{key}")
+                };
+                push_row(child, path, Some(key.to_string()), depth, collapsed, rows);
             }
         }
         Value::Array(values) => {
-            if values.is_empty() {
-                lines.push(Line::from(vec![
-                    indent_span(indent, theme),
-                    Span::styled("[]", Style::default().fg(theme.text_muted())),
-                ]));
-                return;
+            for (idx, child) in values.iter().enumerate() {
+                let path = format!("{parent}[{idx}]");
+                push_row(child, path, None, depth, collapsed, rows);
             }
+        }
+        _ => {}
+    }
+}
 
-            for value in values {
-                if is_scalar(value) {
-                    let line = Line::from(vec![
-                        indent_span(indent, theme),
-                        Span::styled("- ", Style::default().fg(theme.text_muted())),
-                        scalar_span(value, theme),
-                    ]);
-                    lines.push(line);
-                } else {
-                    let line = Line::from(vec![
-                        indent_span(indent, theme),
-                        Span::styled("-", Style::default().fg(theme.text_muted())),
-                    ]);
-                    lines.push(line);
-                    render_value(value, indent + 2, theme, lines, None);
-                }
+fn push_row(
+    value: &Value,
+    path: String,
+    key: Option<String>,
+    depth: usize,
+    collapsed: &HashSet<String>,
+    rows: &mut Vec<TreeRow>,
+) {
+    let is_collapsed = collapsed.contains(&path);
+    rows.push(TreeRow {
+        path: path.clone(),
+        depth,
+        key,
+        value: value.clone(),
+        collapsed: is_collapsed,
+    });
+    if !is_collapsed {
+        push_rows(value, &path, depth + 1, collapsed, rows, None);
+    }
+}
+
+fn ordered_keys<'a>(
+    map: &'a serde_json::Map<String, Value>,
+    key_order: Option<&'a [String]>,
+) -> Vec<&'a str> {
+    let mut keys: Vec<&str> = Vec::new();
+    if let Some(order) = key_order {
+        let mut seen = HashSet::new();
+        for key in order {
+            if map.contains_key(key) {
+                keys.push(key.as_str());
+                seen.insert(key.as_str());
             }
         }
-        _ => {
-            lines.push(Line::from(vec![
-                indent_span(indent, theme),
-                scalar_span(value, theme),
-            ]));
-        }
+        let mut remaining: Vec<&str> = map
+            .keys()
+            .map(std::string::String::as_str)
+            .filter(|key| !seen.contains(key))
+            .collect();
+        remaining.sort_unstable();
+        keys.extend(remaining);
+    } else {
+        keys = map.keys().map(std::string::String::as_str).collect();
+        keys.sort_unstable();
     }
+    keys
 }
 
 fn is_scalar(value: &Value) -> bool {
@@ -162,7 +334,8 @@ fn scalar_text(value: &Value) -> String {
         Value::Number(number) => number.to_string(),
         Value::Bool(value) => value.to_string(),
         Value::Null => "null".to_string(),
-        _ => String::new(),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
     }
 }
 
@@ -179,7 +352,7 @@ fn scalar_span(value: &Value, theme: &Theme) -> Span<'static> {
             Span::styled(value.to_string(), Style::default().fg(theme.text_muted()))
         }
         Value::Null => Span::styled("null", Style::default().fg(theme.text_muted())),
-        _ => Span::styled(scalar_text(value), Style::default().fg(theme.text())),
+        _ => Span::styled(scalar_text(value), Style::default().fg(theme.text_muted())),
     }
 }
 
@@ -192,11 +365,11 @@ fn indent_span(indent: usize, theme: &Theme) -> Span<'static> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
 
     use aws_sdk_dynamodb::types::AttributeValue;
 
-    use super::item_to_lines;
+    use super::{TreeState, item_rows, item_to_lines, value_text};
     use crate::widgets::theme::Theme;
 
     #[test]
@@ -233,4 +406,120 @@ mod tests {
             ]
         );
     }
+
+    fn nested_item() -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("pk".to_string(), AttributeValue::S("user#1".to_string())),
+            (
+                "address".to_string(),
+                AttributeValue::M(HashMap::from([
+                    ("city".to_string(), AttributeValue::S("Lisbon".to_string())),
+                    (
+                        "lines".to_string(),
+                        AttributeValue::L(vec![AttributeValue::S("Rua 1".to_string())]),
+                    ),
+                ])),
+            ),
+        ])
+    }
+
+    fn paths(rows: &[super::TreeRow]) -> Vec<&str> {
+        rows.iter().map(|row| row.path.as_str()).collect()
+    }
+
+    #[test]
+    fn collapsed_paths_hide_their_children() {
+        let item = nested_item();
+        let key_order = ["pk".to_string(), "address".to_string()];
+        let rows = item_rows(&item, Some(&key_order), &HashSet::new()).unwrap();
+        assert_eq!(
+            paths(&rows),
+            vec![
+                "pk",
+                "address",
+                "address.city",
+                "address.lines",
+                "address.lines[0]"
+            ]
+        );
+
+        let mut tree = TreeState {
+            cursor: 1,
+            ..TreeState::default()
+        };
+        tree.toggle(&rows);
+        let rows = item_rows(&item, Some(&key_order), tree.collapsed()).unwrap();
+        assert_eq!(paths(&rows), vec!["pk", "address"]);
+        let line: String = rows[1]
+            .to_line(&Theme::dark())
+            .spans
+            .into_iter()
+            .map(|span| span.content.to_string())
+            .collect();
+        assert_eq!(line, "address: {…} 2 keys");
+    }
+
+    #[test]
+    fn paths_step_through_lists_of_maps() {
+        let line_item = AttributeValue::M(HashMap::from([(
+            "sku".to_string(),
+            AttributeValue::S("A-1".to_string()),
+        )]));
+        let order = AttributeValue::M(HashMap::from([(
+            "lineItems".to_string(),
+            AttributeValue::L(vec![line_item]),
+        )]));
+        let item = HashMap::from([("orders".to_string(), AttributeValue::L(vec![order]))]);
+        let rows = item_rows(&item, None, &HashSet::new()).unwrap();
+        assert_eq!(
+            paths(&rows),
+            vec![
+                "orders",
+                "orders[0]",
+                "orders[0].lineItems",
+                "orders[0].lineItems[0]",
+                "orders[0].lineItems[0].sku"
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_steps_in_and_collapse_steps_out() {
+        let item = nested_item();
+        let rows = item_rows(&item, None, &HashSet::new()).unwrap();
+        let mut tree = TreeState::default();
+        assert_eq!(rows[tree.cursor].path, "address");
+
+        tree.expand(&rows);
+        assert_eq!(rows[tree.cursor].path, "address.city");
+
+        tree.collapse(&rows);
+        assert_eq!(rows[tree.cursor].path, "address");
+        tree.collapse(&rows);
+        assert!(tree.collapsed().contains("address"));
+    }
+
+    #[test]
+    fn search_finds_folded_matches_and_reveals_them() {
+        let item = nested_item();
+        let mut tree = TreeState::default();
+        tree.search.value = "rua".to_string();
+        let all_rows = item_rows(&item, None, &HashSet::new()).unwrap();
+        let folded = item_rows(&item, None, &HashSet::from(["address".to_string()])).unwrap();
+        assert_eq!(paths(&folded), vec!["address", "pk"]);
+
+        let found = tree.find(&all_rows, Some("pk"), true, false);
+        assert_eq!(found.as_deref(), Some("address.lines[0]"));
+
+        tree.collapse(&all_rows);
+        tree.reveal("address.lines[0]");
+        assert!(tree.collapsed().is_empty());
+    }
+
+    #[test]
+    fn value_text_copies_scalars_bare() {
+        assert_eq!(value_text(&serde_json::json!("Lisbon")), "Lisbon");
+        assert_eq!(value_text(&serde_json::json!(3)), "3");
+        assert_eq!(value_text(&serde_json::json!(["a"])), "[\n  \"a\"\n]");
+    }
 }
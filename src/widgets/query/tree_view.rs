@@ -0,0 +1,168 @@
+//! Keys of the full-screen item tree: folding maps and lists, searching keys
+//! and values with `/`, and copying the value or path under the cursor.
+//!
+//! The folds, cursor, and search live in a [`TreeState`]; the rows are
+//! rebuilt from the selected item on every key. [`handle_key`] and
+//! [`handle_search_key`] take a key from there and tell the query widget what
+//! it has to do about it.
+
+use std::collections::{HashMap, HashSet};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use crossterm::event::{Event, KeyCode, KeyEvent};
+
+use crate::env::WidgetCtx;
+
+use super::{
+    clipboard,
+    tree::{self, TreeRow, TreeState},
+};
+
+/// The item the tree shows: the table's selected row.
+pub(super) struct TreeItem<'a> {
+    pub(super) item: Option<&'a HashMap<String, AttributeValue>>,
+    /// The order of the top-level attributes, as in the table.
+    pub(super) key_order: &'a [String],
+}
+
+/// What a key did to the tree, for the query widget to act on.
+pub(super) enum TreeStep {
+    /// Nothing more to do.
+    Handled,
+    /// The cursor or folds moved; this many rows show now.
+    Moved(usize),
+    /// ⏎ on this row: follow the item it refers to, or else [`fold`] it.
+    Open(TreeRow),
+    /// `T` on this row: offer its type conversions.
+    Convert(TreeRow),
+    Failed(String),
+}
+
+/// The item's rows, honoring the folds unless `unfolded`.
+pub(super) fn rows(
+    item: &TreeItem<'_>,
+    tree: &TreeState,
+    unfolded: bool,
+) -> Result<Vec<TreeRow>, String> {
+    let Some(attributes) = item.item else {
+        return Ok(Vec::new());
+    };
+    let no_folds = HashSet::new();
+    let collapsed = if unfolded {
+        &no_folds
+    } else {
+        tree.collapsed()
+    };
+    tree::item_rows(attributes, Some(item.key_order), collapsed)
+}
+
+/// The row under the cursor.
+fn cursor_row(item: &TreeItem<'_>, tree: &TreeState) -> Result<Option<TreeRow>, String> {
+    rows(item, tree, false).map(|rows| rows.get(tree.cursor).cloned())
+}
+
+/// Handle a key of the tree keymap. `None` for keys the main keymap handles.
+pub(super) fn handle_key(
+    item: &TreeItem<'_>,
+    tree: &mut TreeState,
+    key: KeyEvent,
+    ctx: &WidgetCtx,
+) -> Option<TreeStep> {
+    let searching = tree.needle().is_some();
+    Some(match key.code {
+        KeyCode::Enter => match cursor_row(item, tree) {
+            Ok(Some(row)) => TreeStep::Open(row),
+            _ => TreeStep::Handled,
+        },
+        KeyCode::Char(' ') => fold(item, tree, TreeState::toggle),
+        KeyCode::Right => fold(item, tree, TreeState::expand),
+        KeyCode::Left => fold(item, tree, TreeState::collapse),
+        KeyCode::Char('/') => {
+            tree.search.clear();
+            tree.search.set_active(true);
+            TreeStep::Handled
+        }
+        KeyCode::Char(c @ ('n' | 'N')) if searching => search(item, tree, c == 'n', false),
+        KeyCode::Esc if searching => {
+            tree.search.clear();
+            TreeStep::Handled
+        }
+        KeyCode::Char('Y') => match cursor_row(item, tree) {
+            Ok(Some(row)) => {
+                clipboard::copy(ctx, &row.path, &tree::value_text(&row.value));
+                TreeStep::Handled
+            }
+            Ok(None) => TreeStep::Failed("No value to copy".to_string()),
+            Err(err) => TreeStep::Failed(err),
+        },
+        // The document path, such as `orders[2].lineItems[0].sku`, for
+        // projections and update expressions.
+        KeyCode::Char('c') => match cursor_row(item, tree) {
+            Ok(Some(row)) => {
+                clipboard::copy(ctx, "path", &row.path);
+                TreeStep::Handled
+            }
+            Ok(None) => TreeStep::Failed("No path to copy".to_string()),
+            Err(err) => TreeStep::Failed(err),
+        },
+        KeyCode::Char('T') => match cursor_row(item, tree) {
+            Ok(Some(row)) => TreeStep::Convert(row),
+            Ok(None) => TreeStep::Failed("No attribute to convert".to_string()),
+            Err(err) => TreeStep::Failed(err),
+        },
+        _ => return None,
+    })
+}
+
+/// Handle a key while the search is being typed, jumping to the first match
+/// as the text changes. `None` for keys the search doesn't take.
+pub(super) fn handle_search_key(
+    item: &TreeItem<'_>,
+    tree: &mut TreeState,
+    event: &Event,
+) -> Option<TreeStep> {
+    let before = tree.search.value.clone();
+    if !tree.search.handle_event(event) {
+        return None;
+    }
+    if tree.search.value == before {
+        return Some(TreeStep::Handled);
+    }
+    Some(search(item, tree, true, true))
+}
+
+/// Apply a fold action to the row under the cursor.
+pub(super) fn fold(
+    item: &TreeItem<'_>,
+    tree: &mut TreeState,
+    fold: fn(&mut TreeState, &[TreeRow]),
+) -> TreeStep {
+    let Ok(rows) = rows(item, tree, false) else {
+        return TreeStep::Handled;
+    };
+    fold(tree, &rows);
+    TreeStep::Moved(self::rows(item, tree, false).map_or(0, |rows| rows.len()))
+}
+
+/// Move the cursor to the next (or previous) row matching the search,
+/// unfolding whatever hides it.
+fn search(
+    item: &TreeItem<'_>,
+    tree: &mut TreeState,
+    forward: bool,
+    include_current: bool,
+) -> TreeStep {
+    let (Ok(all_rows), Ok(rows)) = (rows(item, tree, true), rows(item, tree, false)) else {
+        return TreeStep::Handled;
+    };
+    let current = rows.get(tree.cursor).map(|row| row.path.as_str());
+    let Some(path) = tree.find(&all_rows, current, forward, include_current) else {
+        return TreeStep::Handled;
+    };
+    tree.reveal(&path);
+    let Ok(rows) = self::rows(item, tree, false) else {
+        return TreeStep::Handled;
+    };
+    tree.cursor = rows.iter().position(|row| row.path == path).unwrap_or(0);
+    TreeStep::Moved(rows.len())
+}
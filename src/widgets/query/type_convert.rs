@@ -0,0 +1,377 @@
+//! Changing an attribute's type from the item tree (`T`): a string to a
+//! number and back, a list of strings to a string set and back, and epoch
+//! numbers to ISO 8601 strings and back. Each conversion checks the value
+//! first, so a fix never needs hand-edited DynamoDB JSON type tags; the
+//! result is saved through the same review as an edit.
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, str::FromStr};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{DateTime, SecondsFormat};
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    prelude::StatefulWidget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, HighlightSpacing, List, ListItem, ListState},
+};
+
+use super::{projection::AttributePath, time_format};
+use crate::{
+    help,
+    util::fill_bg,
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Conversion {
+    StringToNumber,
+    NumberToString,
+    ListToStringSet,
+    StringSetToList,
+    EpochSecondsToIso,
+    EpochMillisToIso,
+    IsoToEpochSeconds,
+    IsoToEpochMillis,
+}
+
+impl Conversion {
+    /// The conversions that take a value of `value`'s type.
+    pub(super) fn available(value: &AttributeValue) -> &'static [Self] {
+        match value {
+            AttributeValue::S(_) => &[
+                Self::StringToNumber,
+                Self::IsoToEpochSeconds,
+                Self::IsoToEpochMillis,
+            ],
+            AttributeValue::N(_) => &[
+                Self::NumberToString,
+                Self::EpochSecondsToIso,
+                Self::EpochMillisToIso,
+            ],
+            AttributeValue::L(_) => &[Self::ListToStringSet],
+            AttributeValue::Ss(_) => &[Self::StringSetToList],
+            _ => &[],
+        }
+    }
+
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            Self::StringToNumber => "S → N",
+            Self::NumberToString => "N → S",
+            Self::ListToStringSet => "L → SS",
+            Self::StringSetToList => "SS → L",
+            Self::EpochSecondsToIso => "epoch seconds → ISO 8601",
+            Self::EpochMillisToIso => "epoch millis → ISO 8601",
+            Self::IsoToEpochSeconds => "ISO 8601 → epoch seconds",
+            Self::IsoToEpochMillis => "ISO 8601 → epoch millis",
+        }
+    }
+
+    /// `value` converted, or why it can't be.
+    pub(super) fn apply(self, value: &AttributeValue) -> Result<AttributeValue, String> {
+        match (self, value) {
+            (Self::StringToNumber, AttributeValue::S(text)) => {
+                let text = text.trim();
+                serde_json::Number::from_str(text)
+                    .map(|_| AttributeValue::N(text.to_string()))
+                    .map_err(|_| format!("{text:?} is not a number"))
+            }
+            (Self::NumberToString, AttributeValue::N(number)) => {
+                Ok(AttributeValue::S(number.clone()))
+            }
+            (Self::ListToStringSet, AttributeValue::L(values)) => string_set(values),
+            (Self::StringSetToList, AttributeValue::Ss(values)) => Ok(AttributeValue::L(
+                values.iter().cloned().map(AttributeValue::S).collect(),
+            )),
+            (Self::EpochSecondsToIso | Self::EpochMillisToIso, AttributeValue::N(number)) => {
+                let epoch: i64 = number
+                    .parse()
+                    .map_err(|_| format!("{number} is not a whole number"))?;
+                let (time, format) = if self == Self::EpochSecondsToIso {
+                    (DateTime::from_timestamp(epoch, 0), SecondsFormat::Secs)
+                } else {
+                    (
+                        DateTime::from_timestamp_millis(epoch),
+                        SecondsFormat::Millis,
+                    )
+                };
+                time.map(|time| AttributeValue::S(time.to_rfc3339_opts(format, true)))
+                    .ok_or_else(|| format!("{number} is out of range"))
+            }
+            (Self::IsoToEpochSeconds | Self::IsoToEpochMillis, AttributeValue::S(text)) => {
+                let time = time_format::parse_iso(text)
+                    .ok_or_else(|| format!("{text:?} is not an ISO 8601 date-time"))?;
+                let epoch = if self == Self::IsoToEpochSeconds {
+                    time.timestamp()
+                } else {
+                    time.timestamp_millis()
+                };
+                Ok(AttributeValue::N(epoch.to_string()))
+            }
+            _ => Err(format!("{} doesn't take this type", self.label())),
+        }
+    }
+}
+
+/// DynamoDB sets are non-empty and hold each string once.
+fn string_set(values: &[AttributeValue]) -> Result<AttributeValue, String> {
+    if values.is_empty() {
+        return Err("A set can't be empty".to_string());
+    }
+    let mut strings: Vec<String> = Vec::with_capacity(values.len());
+    for (index, value) in values.iter().enumerate() {
+        let AttributeValue::S(text) = value else {
+            return Err(format!("Element {index} is not a string"));
+        };
+        if strings.contains(text) {
+            return Err(format!("{text:?} appears more than once"));
+        }
+        strings.push(text.clone());
+    }
+    Ok(AttributeValue::Ss(strings))
+}
+
+/// `item` with the value at `path` converted.
+pub(super) fn convert_at(
+    item: &HashMap<String, AttributeValue>,
+    path: &str,
+    conversion: Conversion,
+) -> Result<HashMap<String, AttributeValue>, String> {
+    let mut converted = item.clone();
+    let value = if converted.contains_key(path) {
+        converted.get_mut(path)
+    } else {
+        AttributePath::parse(path)?.resolve_mut(&mut converted)
+    }
+    .ok_or_else(|| format!("The item has no {path}"))?;
+    *value = conversion.apply(value)?;
+    Ok(converted)
+}
+
+/// A short rendering of a converted value for the picker.
+fn preview(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::S(text) => format!("{text:?}"),
+        AttributeValue::N(number) => number.clone(),
+        AttributeValue::Ss(values) => format!("SS of {}", values.len()),
+        AttributeValue::L(values) => format!("L of {}", values.len()),
+        _ => String::new(),
+    }
+}
+
+/// Lists the conversions for one attribute, with what each would give.
+pub(super) struct ConvertPicker {
+    inner: WidgetInner,
+    path: String,
+    choices: Vec<(Conversion, Result<AttributeValue, String>)>,
+    state: RefCell<ListState>,
+    on_select: Box<dyn Fn(Conversion) + Send + 'static>,
+}
+
+impl ConvertPicker {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("↑/↓/j/k"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("convert"),
+            long: Cow::Borrowed("Convert the attribute, reviewing the change before saving"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("close"),
+            long: Cow::Borrowed("Close picker"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    pub(super) fn new(
+        path: String,
+        value: &AttributeValue,
+        on_select: impl Fn(Conversion) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let choices = Conversion::available(value)
+            .iter()
+            .map(|conversion| (*conversion, conversion.apply(value)))
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(0));
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            path,
+            choices,
+            state: RefCell::new(state),
+            on_select: Box::new(on_select),
+        }
+    }
+}
+
+impl crate::widgets::Widget for ConvertPicker {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::styled(
+                format!(" Convert {} ", self.path),
+                Style::default()
+                    .fg(theme.accent())
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+
+        let items: Vec<ListItem> = self
+            .choices
+            .iter()
+            .map(|(conversion, result)| {
+                let outcome = match result {
+                    Ok(value) => Span::styled(
+                        format!("  {}", preview(value)),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                    Err(err) => {
+                        Span::styled(format!("  {err}"), Style::default().fg(theme.error()))
+                    }
+                };
+                ListItem::new(Line::from(vec![Span::raw(conversion.label()), outcome]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("> ")
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(
+            list,
+            block.inner(area),
+            frame.buffer_mut(),
+            &mut self.state.borrow_mut(),
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Up | KeyCode::Char('k') => self.state.borrow_mut().select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.state.borrow_mut().select_next(),
+            KeyCode::Enter => {
+                let selected = self.state.borrow().selected();
+                // Conversions the value fails stay listed with the reason.
+                if let Some((conversion, Ok(_))) = selected.and_then(|row| self.choices.get(row)) {
+                    ctx.dismiss_popup();
+                    (self.on_select)(*conversion);
+                }
+            }
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for ConvertPicker {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 64.min(area.width.saturating_sub(4));
+        let rows = u16::try_from(self.choices.len()).unwrap_or(u16::MAX);
+        let height = rows.saturating_add(2).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::{Conversion, convert_at};
+
+    #[test]
+    fn converts_between_types_and_rejects_bad_values() {
+        let s = |text: &str| AttributeValue::S(text.to_string());
+        let n = |number: &str| AttributeValue::N(number.to_string());
+
+        assert_eq!(
+            Conversion::StringToNumber.apply(&s(" 42.5 ")),
+            Ok(n("42.5"))
+        );
+        assert!(Conversion::StringToNumber.apply(&s("42kg")).is_err());
+        assert_eq!(
+            Conversion::EpochSecondsToIso.apply(&n("1700000000")),
+            Ok(s("2023-11-14T22:13:20Z"))
+        );
+        assert_eq!(
+            Conversion::IsoToEpochMillis.apply(&s("2023-11-14T22:13:20.5Z")),
+            Ok(n("1700000000500"))
+        );
+        assert!(Conversion::EpochSecondsToIso.apply(&n("1.5")).is_err());
+        assert_eq!(
+            Conversion::ListToStringSet.apply(&AttributeValue::L(vec![s("a"), s("b")])),
+            Ok(AttributeValue::Ss(vec!["a".to_string(), "b".to_string()]))
+        );
+        assert!(
+            Conversion::ListToStringSet
+                .apply(&AttributeValue::L(vec![s("a"), s("a")]))
+                .is_err()
+        );
+        assert!(
+            Conversion::ListToStringSet
+                .apply(&AttributeValue::L(vec![]))
+                .is_err()
+        );
+
+        let item = HashMap::from([(
+            "orders".to_string(),
+            AttributeValue::L(vec![AttributeValue::M(HashMap::from([(
+                "qty".to_string(),
+                s("3"),
+            )]))]),
+        )]);
+        let converted = convert_at(&item, "orders[0].qty", Conversion::StringToNumber).unwrap();
+        let AttributeValue::L(orders) = &converted["orders"] else {
+            panic!("orders is a list");
+        };
+        let AttributeValue::M(order) = &orders[0] else {
+            panic!("the order is a map");
+        };
+        assert_eq!(order["qty"], n("3"));
+        assert!(convert_at(&item, "orders[1].qty", Conversion::StringToNumber).is_err());
+    }
+}
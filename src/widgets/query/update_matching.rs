@@ -0,0 +1,334 @@
+use std::{borrow::Cow, cell::RefCell, sync::Arc};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph, Wrap},
+};
+
+use dynamate::core::{bulk_update::UpdateSummary, datastore::Datastore, query::ItemUpdate};
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, create_table::TextInput, purge::format_elapsed, theme::Theme},
+};
+
+/// Asks for the update to apply to every item the current query matches.
+/// The backend's query language parses it; ⏎ previews the result on the
+/// first matches before anything is written.
+pub(super) struct UpdateMatchingPopup {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    query: String,
+    expression: RefCell<TextInput>,
+    on_preview: Box<dyn Fn(ItemUpdate) + Send + 'static>,
+}
+
+impl UpdateMatchingPopup {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("preview"),
+            long: Cow::Borrowed("Preview the update on the first matches"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("cancel"),
+            long: Cow::Borrowed("Cancel"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    /// `query` describes what matches.
+    pub(super) fn new(
+        db: Arc<dyn Datastore>,
+        query: impl Into<String>,
+        on_preview: impl Fn(ItemUpdate) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let placeholder = db.query_language().update_placeholder().unwrap_or_default();
+        let mut expression = TextInput::new("Update", "").with_placeholder(placeholder);
+        expression.set_active(true);
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            query: query.into(),
+            expression: RefCell::new(expression),
+            on_preview: Box::new(on_preview),
+        }
+    }
+
+    fn update(&self) -> Result<ItemUpdate, String> {
+        let text = self.expression.borrow().value().to_string();
+        if text.trim().is_empty() {
+            return Err("Type an update".to_string());
+        }
+        self.db.query_language().parse_update(&text)
+    }
+}
+
+/// "sets a, b · removes c" for the hint line.
+fn update_label(update: &ItemUpdate) -> String {
+    let mut parts = Vec::new();
+    if !update.set.is_empty() {
+        let names: Vec<&str> = update.set.keys().map(String::as_str).collect();
+        parts.push(format!("sets {}", names.join(", ")));
+    }
+    if !update.remove.is_empty() {
+        parts.push(format!("removes {}", update.remove.join(", ")));
+    }
+    parts.join(" · ")
+}
+
+impl crate::widgets::Widget for UpdateMatchingPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad("Update matching", 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ]);
+        let [query_area, _, input_area, hint_area] = inner.layout(&layout);
+
+        let query = Line::from(vec![
+            Span::styled(
+                "Updates every item matching ",
+                Style::default().fg(theme.text_muted()),
+            ),
+            Span::styled(self.query.clone(), Style::default().fg(theme.text())),
+        ]);
+        frame.render_widget(Paragraph::new(query).wrap(Wrap { trim: true }), query_area);
+        self.expression.borrow().render(frame, input_area, theme);
+        let hint = match self.update() {
+            Ok(update) => Line::styled(
+                update_label(&update),
+                Style::default().fg(theme.text_muted()),
+            ),
+            Err(err) => Line::styled(err, Style::default().fg(theme.error())),
+        };
+        frame.render_widget(Paragraph::new(hint), hint_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Enter => {
+                if let Ok(update) = self.update() {
+                    ctx.dismiss_popup();
+                    (self.on_preview)(update);
+                }
+            }
+            _ => {
+                self.expression.borrow_mut().handle_event(event);
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for UpdateMatchingPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        // The query, a gap, the input, the hint, and the borders.
+        let height = 9.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Reports how a bulk update ended: how many items it updated, how many
+/// failed and why, and how long it took.
+pub(super) struct UpdateSummaryPopup {
+    inner: WidgetInner,
+    table_name: String,
+    summary: UpdateSummary,
+}
+
+impl UpdateSummaryPopup {
+    const HELP: &'static [help::Entry<'static>] = &[help::Entry {
+        keys: Cow::Borrowed("esc/⏎"),
+        short: Cow::Borrowed("close"),
+        long: Cow::Borrowed("Close"),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }];
+
+    pub(super) fn new(
+        table_name: impl Into<String>,
+        summary: UpdateSummary,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            table_name: table_name.into(),
+            summary,
+        }
+    }
+
+    fn rows(&self) -> Vec<(&'static str, String)> {
+        let progress = self.summary.progress;
+        let status = if self.summary.cancelled {
+            "canceled"
+        } else if progress.failed > 0 {
+            "finished with errors"
+        } else {
+            "finished"
+        };
+        vec![
+            ("Table", self.table_name.clone()),
+            ("Matched", format!("{} items", progress.matched)),
+            ("Updated", format!("{} items", progress.updated)),
+            ("Failed", format!("{} items", progress.failed)),
+            ("Took", format_elapsed(self.summary.elapsed)),
+            ("Status", status.to_string()),
+        ]
+    }
+}
+
+impl crate::widgets::Widget for UpdateSummaryPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let failed = !self.summary.errors.is_empty();
+        let accent = if failed {
+            theme.error()
+        } else {
+            theme.accent()
+        };
+        let title = Line::styled(
+            pad("Update matching", 1),
+            Style::default().fg(accent).add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+
+        let mut lines: Vec<Line> = self
+            .rows()
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{label:<9}"),
+                        Style::default().fg(theme.text_muted()),
+                    ),
+                    Span::styled(value, Style::default().fg(theme.text())),
+                ])
+            })
+            .collect();
+        if failed {
+            lines.push(Line::default());
+            lines.extend(
+                self.summary
+                    .errors
+                    .iter()
+                    .map(|err| Line::styled(err.clone(), Style::default().fg(theme.error()))),
+            );
+        }
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+            ctx.dismiss_popup();
+        }
+        true
+    }
+}
+
+impl Popup for UpdateSummaryPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 72.min(area.width.saturating_sub(4));
+        // Six rows, a blank line and the errors when there are any, and the
+        // borders.
+        let errors = if self.summary.errors.is_empty() {
+            0
+        } else {
+            self.summary.errors.len() as u16 + 1
+        };
+        let height = (8 + errors).min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::{query::ItemUpdate, value::Value};
+
+    use super::update_label;
+
+    #[test]
+    fn update_label_names_set_and_removed_attributes() {
+        let mut update = ItemUpdate::default();
+        update.set.insert("status".to_string(), Value::from("done"));
+        update.set.insert("retries".to_string(), Value::from(0));
+        assert_eq!(update_label(&update), "sets status, retries");
+        update.remove.push("lock".to_string());
+        assert_eq!(update_label(&update), "sets status, retries · removes lock");
+    }
+}
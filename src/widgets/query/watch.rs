@@ -0,0 +1,233 @@
+//! Watch mode: the query view re-runs its query on an interval and marks the
+//! rows that changed since the previous refresh.
+//!
+//! A watch ticks every [`TICK`] to redraw its countdown. Each tick comes back
+//! as an event; [`handle`] takes it from there and tells the query widget
+//! when a refresh is due.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+
+use dynamate::core::audit;
+use dynamate::core::schema::CollectionSchema;
+
+use crate::env::{AppEvent, Toast, ToastKind, WidgetCtx};
+
+use super::selection::ItemKey;
+
+/// The interval `^w` starts watching with, unless `--watch` set another.
+pub(super) const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the countdown in the footer is redrawn.
+const TICK: Duration = Duration::from_secs(1);
+
+struct WatchTick {
+    generation: u64,
+}
+
+/// Where a watch got to on a tick, for the query widget to act on.
+pub(super) enum WatchStep {
+    /// Counting down; only the footer changes.
+    Waiting,
+    /// The interval is up: refresh, unless something's in the way.
+    Due(Instant),
+}
+
+pub(super) struct Watch {
+    pub(super) interval: Duration,
+    next_at: Instant,
+    /// Bumped on every start, so ticks from an earlier watch are ignored.
+    pub(super) generation: u64,
+    /// The rows loaded before the last refresh, by key.
+    previous: HashMap<ItemKey, HashMap<String, AttributeValue>>,
+    /// Whether a refresh has happened, so there is something to compare.
+    compared: bool,
+}
+
+impl Watch {
+    pub(super) fn new(interval: Duration, generation: u64) -> Self {
+        Self {
+            interval,
+            next_at: Instant::now() + interval,
+            generation,
+            previous: HashMap::new(),
+            compared: false,
+        }
+    }
+
+    /// Start watching, with the first tick on its way. `generation` tells
+    /// this watch's ticks apart from those of one stopped before.
+    pub(super) fn start(interval: Duration, generation: u64, ctx: &WidgetCtx) -> Self {
+        schedule_tick(ctx, generation);
+        ctx.show_toast(Toast {
+            message: format!("Refreshing every {}s (^w stops)", interval.as_secs()),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(3),
+            action: None,
+        });
+        Self::new(interval, generation)
+    }
+
+    pub(super) fn is_due(&self, now: Instant) -> bool {
+        now >= self.next_at
+    }
+
+    /// Whole seconds until the next refresh.
+    pub(super) fn remaining_secs(&self, now: Instant) -> u64 {
+        let remaining = self.next_at.saturating_duration_since(now);
+        remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+    }
+
+    /// The countdown shown in the footer.
+    pub(super) fn status(&self, now: Instant) -> String {
+        format!("watching, next in {}s", self.remaining_secs(now))
+    }
+
+    /// Remember `rows` as the state before a refresh and restart the countdown.
+    pub(super) fn refreshed(
+        &mut self,
+        rows: impl IntoIterator<Item = (ItemKey, HashMap<String, AttributeValue>)>,
+        now: Instant,
+    ) {
+        self.previous = rows.into_iter().collect();
+        self.compared = true;
+        self.next_at = now + self.interval;
+    }
+
+    /// Whether the row at `position` differs from the previous refresh: its
+    /// attributes changed, or it is new among the rows loaded back then. Rows
+    /// paged in beyond those are only compared when they were seen before.
+    pub(super) fn is_changed(
+        &self,
+        key: &ItemKey,
+        item: &HashMap<String, AttributeValue>,
+        position: usize,
+    ) -> bool {
+        if !self.compared {
+            return false;
+        }
+        match self.previous.get(key) {
+            Some(previous) => previous != item,
+            None => position < self.previous.len(),
+        }
+    }
+}
+
+/// Confirm that watching stopped.
+pub(super) fn stopped(ctx: &WidgetCtx) {
+    ctx.show_toast(Toast {
+        message: "Stopped watching".to_string(),
+        kind: ToastKind::Info,
+        duration: Duration::from_secs(2),
+        action: None,
+    });
+}
+
+/// Why `query` can't be watched: re-running a statement that writes would
+/// repeat the write.
+pub(super) fn blocker(query: &str, statement: bool) -> Option<&'static str> {
+    (statement && !audit::is_read_statement(query))
+        .then_some("Only reads can be watched; this statement writes")
+}
+
+/// Take the watch one tick further if `event` is one of its ticks. Ticks of
+/// a watch that was stopped since are dropped.
+pub(super) fn handle(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    watch: Option<&Watch>,
+) -> Option<WatchStep> {
+    let tick = event.payload::<WatchTick>()?;
+    let watch = watch.filter(|watch| watch.generation == tick.generation)?;
+    schedule_tick(ctx, watch.generation);
+    let now = Instant::now();
+    Some(if watch.is_due(now) {
+        WatchStep::Due(now)
+    } else {
+        WatchStep::Waiting
+    })
+}
+
+/// The loaded `items` by key, to compare the next refresh with.
+pub(super) fn keyed_rows<'a>(
+    items: impl IntoIterator<Item = &'a HashMap<String, AttributeValue>>,
+    schema: Option<&CollectionSchema>,
+) -> Vec<(ItemKey, HashMap<String, AttributeValue>)> {
+    let Some(schema) = schema else {
+        return Vec::new();
+    };
+    items
+        .into_iter()
+        .filter_map(|item| {
+            ItemKey::from_item(item, schema)
+                .ok()
+                .map(|key| (key, item.clone()))
+        })
+        .collect()
+}
+
+/// Whether `watch` marks `item`, shown at `position`, as changed.
+pub(super) fn row_changed(
+    watch: Option<&Watch>,
+    item: &HashMap<String, AttributeValue>,
+    schema: Option<&CollectionSchema>,
+    position: usize,
+) -> bool {
+    let (Some(watch), Some(schema)) = (watch, schema) else {
+        return false;
+    };
+    ItemKey::from_item(item, schema).is_ok_and(|key| watch.is_changed(&key, item, position))
+}
+
+fn schedule_tick(ctx: &WidgetCtx, generation: u64) {
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(TICK).await;
+        ctx.emit_self(WatchTick { generation });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use super::Watch;
+    use crate::widgets::query::selection::{ItemKey, KeyValue};
+
+    fn row(id: &str, status: &str) -> (ItemKey, HashMap<String, AttributeValue>) {
+        let key = ItemKey {
+            hash_key: "id".to_string(),
+            hash_value: KeyValue::String(id.to_string()),
+            range: None,
+        };
+        let item = HashMap::from([
+            ("id".to_string(), AttributeValue::S(id.to_string())),
+            ("status".to_string(), AttributeValue::S(status.to_string())),
+        ]);
+        (key, item)
+    }
+
+    #[test]
+    fn marks_rows_that_changed_since_the_last_refresh() {
+        let mut watch = Watch::new(Duration::from_secs(5), 0);
+        let start = Instant::now();
+        let (a, a_item) = row("a", "open");
+        assert!(!watch.is_changed(&a, &a_item, 0));
+        assert_eq!(watch.remaining_secs(start), 5);
+
+        watch.refreshed([row("a", "open"), row("b", "open")], start);
+        let (b, b_item) = row("b", "closed");
+        let (c, c_item) = row("c", "open");
+        assert!(!watch.is_changed(&a, &a_item, 0));
+        assert!(watch.is_changed(&b, &b_item, 1));
+        assert!(watch.is_changed(&c, &c_item, 1));
+        assert!(!watch.is_changed(&c, &c_item, 2));
+        assert!(!watch.is_due(start + Duration::from_secs(4)));
+        assert!(watch.is_due(start + Duration::from_secs(5)));
+    }
+}
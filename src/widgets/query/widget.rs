@@ -1,29 +1,27 @@
 use std::{
     borrow::Cow,
-    cell::{Cell, RefCell},
-    cmp::{max, min},
+    cell::{Cell, OnceCell, RefCell},
+    cmp::{self, max, min},
     collections::{HashMap, HashSet},
-    env,
-    fs::{self, File},
-    io::{BufWriter, Write},
+    env, fs,
+    io::Write,
     path::{Path, PathBuf},
-    process::Command,
-    sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
-    },
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use aws_sdk_dynamodb::types::AttributeValue;
 use crossterm::cursor::MoveTo;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers};
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton,
+    MouseEventKind,
+};
 use crossterm::terminal::{
     Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
 use ratatui::{
     Frame,
-    layout::{Constraint, Layout, Margin, Rect},
+    layout::{Constraint, Layout, Margin, Position, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
@@ -33,39 +31,86 @@ use ratatui::{
 };
 
 use super::{
+    aggregate::{AggregatePopup, ColumnStats},
+    bulk_update, clipboard,
+    column_widths::{self, ColumnWidths},
+    compare::{self, MAX_DIFF_ITEMS},
+    copy_items,
+    count_popup::CountSummaryPopup,
+    delete_matching,
+    diff_popup::DiffPopup,
+    entities::{self, Entities, EntityPicker, EntityScope, EntityView},
     export_popup::ExportPopup,
-    index_picker, input, item_keys, keys_widget,
+    export_writer::{ExportFile, ExportFormat, StreamedNdjsonWriter},
+    follow,
+    hydrate::{self, Hydration},
+    index_picker, index_scope, input, item_keys,
+    key_list_popup::KeyListPopup,
+    keys_widget,
+    limit::Limit,
+    options_popup::{OptionsPopup, QueryOptions},
+    pages::{self, PageJump, Pages},
+    parameters_popup::ParametersPopup,
+    pattern_picker::PatternPicker,
+    projection::{self, RowProjection},
+    projection_popup::ProjectionPopup,
+    query_builder::QueryBuilderPopup,
     reference_popup::ReferencePopup,
+    row_filter::{self, RowFilter},
     selection::{ItemKey, SelectionMode, SelectionSnapshot},
-    tree,
+    size_popup::SizePopup,
+    template_picker::{TemplatePicker, item_templates},
+    time_format::{self, TimeFormat},
+    tree, tree_view,
+    type_convert::{self, Conversion, ConvertPicker},
+    watch::{self, Watch},
 };
 use keys_widget::KeysWidget;
 
 use crate::{
+    access_patterns::{self, AccessPattern},
     env::{Toast, ToastAction, ToastKind},
     help,
-    util::{abbreviate_home, env_flag, fill_bg, pad},
+    jobs::{self, JobHandle},
+    safety::{self, Action, Confirmation},
+    util::{self, ClickTracker, Editor, abbreviate_home, fill_bg, mouse_capture, pad},
     widgets::{
         WidgetInner,
+        backups::NamePopup,
         confirm::{ConfirmAction, ConfirmPopup},
         error::ErrorPopup,
         filter_input::FilterInput,
+        profile_picker::ProfileSwitchedEvent,
+        text_editor::TextEditorPopup,
         theme::Theme,
+        transaction::{self, TransactionWidget},
+        undo::{self, ItemRestoredEvent},
     },
 };
 use chrono::{DateTime, Utc};
+use dynamate::core::capabilities::Capabilities;
+use dynamate::core::copy::check_key;
+use dynamate::core::count::{CountSummary, count_matches};
 use dynamate::core::datastore::Datastore;
 use dynamate::core::language::{
     CompletionRequest, QueryLanguage, QueryStatus, Suggestion, SuggestionKind, TokenSpan,
 };
-use dynamate::core::query::{Cursor, IndexHint, Key, Page, PlanKind, QueryPlan, QueryResult};
-use dynamate::core::schema::{CollectionSchema, IndexKind, IndexSchema, SchemaHints};
-use dynamate::core::value::Value;
+use dynamate::core::query::{
+    Cursor, IndexHint, ItemUpdate, Key, Page, PlanExplanation, PlanKind, QueryPlan, QueryResult,
+    TransactWrite,
+};
+use dynamate::core::scan_cost::ScanEstimate;
+use dynamate::core::schema::{CollectionSchema, IndexKind, IndexSchema, Projection, SchemaHints};
+use dynamate::core::value::{self, Value};
 use dynamate::dynamodb::convert::{
     attribute_map_from_item, attribute_value_to_value, item_from_attribute_map,
 };
 use dynamate::dynamodb::json;
-use dynamate::dynamodb::size::estimate_item_size_bytes;
+use dynamate::dynamodb::partiql;
+use dynamate::dynamodb::requests::{RequestHandle, RequestManager};
+use dynamate::dynamodb::size::{attribute_sizes, estimate_item_size_bytes};
+use dynamate::expr::{SortOrder, split_order_clause};
+use dynamate::s3::{self, S3Uri};
 use humansize::{BINARY, format_size};
 use tokio::sync::mpsc;
 use tokio_stream::{StreamExt, wrappers::ReceiverStream};
@@ -79,9 +124,19 @@ pub struct QueryWidget {
     state: RefCell<QueryState>,
     table_meta: RefCell<Option<TableMeta>>,
     meta_started: Cell<bool>,
-    request_seq: Cell<u64>,
-    export_seq: Cell<u64>,
+    /// The query or page being fetched.
+    requests: RequestManager,
+    /// The running export.
+    exports: RequestManager,
+    /// The running delete- or update-matching run (or dry run).
+    bulk: RequestManager,
     page_size: i32,
+    /// Most rows kept loaded (`DYNAMATE_MAX_LOADED_ITEMS`); past it the oldest
+    /// pages are unloaded and fetched again on the way back.
+    max_loaded_items: usize,
+    /// Segments for fetching a whole scan in parallel (`DYNAMATE_SCAN_SEGMENTS`);
+    /// 1 pages through it sequentially.
+    scan_segments: u32,
     /// Database-level free-form SQL mode: no single table; runs `raw_query`,
     /// uses the raw query language, and disables row edit/delete/index actions.
     raw_sql: bool,
@@ -90,6 +145,27 @@ pub struct QueryWidget {
     help_table: Vec<help::Entry<'static>>,
     help_filter_applied: Vec<help::Entry<'static>>,
     help_tree: Vec<help::Entry<'static>>,
+    help_query_edit: Vec<help::Entry<'static>>,
+    clicks: ClickTracker,
+    /// How often watch mode re-runs the query.
+    watch_interval: Duration,
+    /// Start watching as soon as the view opens (`--watch`).
+    watch_on_start: bool,
+    watch_seq: Cell<u64>,
+}
+
+/// The value widths of the rows last drawn.
+struct WidthCache {
+    version: u64,
+    rows: Vec<usize>,
+    columns: Vec<String>,
+    widths: Vec<usize>,
+}
+
+impl WidthCache {
+    fn matches(&self, version: u64, rows: &[usize], columns: &[String]) -> bool {
+        self.version == version && self.rows == rows && self.columns == columns
+    }
 }
 
 #[derive(Default)]
@@ -98,6 +174,11 @@ struct QueryState {
     /// rather than the loaded items' keys.
     raw: bool,
     raw_hints: SchemaHints,
+    /// The backend accepts statements (PartiQL) in this query box.
+    statements: bool,
+    /// PartiQL mode toggled on explicitly (^p); otherwise a statement is
+    /// recognized by its leading keyword.
+    partiql: bool,
     input: input::Input,
     filter: FilterInput,
     loading_state: LoadingState,
@@ -107,24 +188,131 @@ struct QueryState {
     item_keys: item_keys::ItemKeys,
     table_state: TableState,
     last_evaluated_key: Option<Cursor>,
+    /// Where each loaded page starts, and the cursors of the pages seen.
+    pages: Pages,
+    /// Counts the pages loaded against the options' result cap.
+    limit: Limit,
     last_query: String,
     active_query: ActiveQuery,
     is_loading_more: bool,
     show_tree: bool,
     reopen_tree: Option<usize>,
     scanned_total: i64,
+    /// Capacity units consumed by the pages of the current query.
+    consumed_capacity: f64,
     matched_total: i64,
     last_render_capacity: usize,
     is_prefetching: bool,
-    export_id: Option<u64>,
-    export_cancel: Option<Arc<AtomicBool>>,
+    /// Where the running export goes once written, if it is uploaded to S3.
+    pending_upload: Option<S3Uri>,
+    /// Whether the running export keeps only the visible columns.
+    export_visible_columns: bool,
     column_offset: usize,
+    /// Widths set with `+`/`-`, kept for the table across views.
+    column_widths: ColumnWidths,
     compact_columns: bool,
+    /// Show the selected row's tree next to the results, following the
+    /// selection (`P`).
+    split_preview: bool,
     tree_scroll_offset: usize,
     tree_render_capacity: usize,
     tree_line_count: usize,
+    /// Cursor, folds, and search of the full-screen item tree.
+    tree: tree::TreeState,
     selection: SelectionMode,
     completion: Completion,
+    /// Values for the `:name` parameters of the text query, as entered in the
+    /// parameters prompt. Reused when the query is re-run or paged.
+    parameters: Vec<(String, String)>,
+    /// Consistent read, projection, and order set in the options popup.
+    options: QueryOptions,
+    /// Reshapes each loaded row before it is shown or exported (`m`). The
+    /// loaded items themselves stay whole, for the tree view and edits.
+    projection: Option<RowProjection>,
+    /// Columns shown as timestamps (`t` in the fields popup). Only the cells
+    /// change; sorting, stats, and exports see the stored values.
+    time_formats: HashMap<String, TimeFormat>,
+    /// Bumped whenever the loaded rows or how they are shown change, so the
+    /// column widths of the rows on screen can be reused until then. Only
+    /// [`QueryState::rows_changed`] and [`QueryState::rows_mut`] touch it.
+    rows_version: u64,
+    width_cache: Option<WidthCache>,
+    /// The `/` filter the shown rows were last filtered by.
+    row_filter: RowFilter,
+    /// Why the `/` filter as typed can't be applied; the last one that could
+    /// stays in effect.
+    filter_error: Option<String>,
+    /// A scan keeps fetching pages on its own until the view is full; `p`
+    /// pauses that.
+    scan_paused: bool,
+    /// Order of the loaded rows, set by clicking a column header.
+    column_sort: Option<ColumnSort>,
+    /// Where the results table was last drawn, for mouse clicks.
+    table_area: Rect,
+    /// The screen columns each rendered header cell spans, and its attribute.
+    header_hits: Vec<(u16, u16, String)>,
+    /// Re-runs the query on an interval while set (`^w`).
+    watch: Option<Watch>,
+    /// The table's entities, from the `entities` rules in the config file.
+    entities: Entities,
+    /// The entity the shown rows are limited to, and whether they're grouped
+    /// by entity (`V`).
+    entity_view: EntityView,
+    /// Attributes of related items joined into extra columns (`H`).
+    hydration: Option<Hydration>,
+}
+
+/// Sorts the loaded rows by one attribute. Rows without it come last either
+/// way; ties keep the order the backend returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ColumnSort {
+    key: String,
+    descending: bool,
+}
+
+impl ColumnSort {
+    /// Ascending, then descending, then back to the backend's order.
+    fn cycle(current: Option<&Self>, key: &str) -> Option<Self> {
+        match current {
+            Some(sort) if sort.key == key && sort.descending => None,
+            Some(sort) if sort.key == key => Some(Self {
+                key: key.to_string(),
+                descending: true,
+            }),
+            _ => Some(Self {
+                key: key.to_string(),
+                descending: false,
+            }),
+        }
+    }
+
+    fn arrow(&self) -> &'static str {
+        if self.descending { "↓" } else { "↑" }
+    }
+
+    fn compare(&self, a: &Item, b: &Item) -> cmp::Ordering {
+        match (a.get(&self.key), b.get(&self.key)) {
+            (None, None) => cmp::Ordering::Equal,
+            (None, Some(_)) => cmp::Ordering::Greater,
+            (Some(_), None) => cmp::Ordering::Less,
+            (Some(left), Some(right)) => {
+                let numbers = left
+                    .as_n()
+                    .ok()
+                    .and_then(|n| n.parse::<f64>().ok())
+                    .zip(right.as_n().ok().and_then(|n| n.parse::<f64>().ok()));
+                let ordering = match numbers {
+                    Some((left, right)) => left.total_cmp(&right),
+                    None => a.value(&self.key).cmp(&b.value(&self.key)),
+                };
+                if self.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+        }
+    }
 }
 
 /// Autocompletion state for the query input. Suggestions are recomputed from the
@@ -157,7 +345,12 @@ const TABLE_MIN_COLUMN_WIDTH: usize = 1;
 const TABLE_MAX_COLUMN_WIDTH: usize = 48;
 const TABLE_MAX_COLUMN_WIDTH_COMPACT: usize = 20;
 const TABLE_MAX_RENDER_COLUMNS: usize = 24;
+const DEFAULT_MAX_LOADED_ITEMS: usize = 100_000;
 const MAX_DROPDOWN_ROWS: usize = 8;
+/// Narrowest results area that still gets the split preview pane.
+const MIN_SPLIT_WIDTH: u16 = 80;
+/// Read units from which a filtered scan is confirmed before it runs.
+const LARGE_SCAN_CAPACITY_UNITS: f64 = 10_000.0;
 
 struct QueryPageEvent {
     request_id: u64,
@@ -188,6 +381,57 @@ struct PutItemEvent {
     result: Result<(), String>,
 }
 
+#[derive(Clone)]
+struct SaveEditRequest {
+    original: HashMap<String, AttributeValue>,
+    item: HashMap<String, AttributeValue>,
+    active_query: ActiveQuery,
+    reopen_tree: Option<usize>,
+}
+
+/// Convert the type of the selected item's attribute at `path`.
+struct ConvertRequest {
+    path: String,
+    conversion: Conversion,
+}
+
+/// What text open in an editor is for, to carry on with once it's saved.
+#[derive(Clone)]
+enum EditTarget {
+    /// The query expression (`M-e`).
+    Query,
+    /// The selected item, opened as `initial`.
+    Item {
+        original: HashMap<String, AttributeValue>,
+        initial: String,
+        format: EditorFormat,
+        active_query: ActiveQuery,
+        reopen_tree: Option<usize>,
+    },
+    NewItem {
+        format: EditorFormat,
+        active_query: ActiveQuery,
+    },
+}
+
+/// Text saved in the built-in editor.
+struct EditedTextEvent {
+    target: EditTarget,
+    text: String,
+}
+
+/// The template picked for a new item.
+struct ItemTemplateEvent {
+    format: EditorFormat,
+    item: HashMap<String, AttributeValue>,
+}
+
+/// A new item to put, once the safety policy's confirmation (if any) is given.
+struct CreateItemRequest {
+    item: HashMap<String, AttributeValue>,
+    active_query: ActiveQuery,
+}
+
 struct DeleteItemRequest {
     key: HashMap<String, AttributeValue>,
 }
@@ -209,25 +453,109 @@ struct IndexQueryEvent {
     target: index_picker::IndexTarget,
 }
 
+struct KeyListEvent {
+    keys: Vec<Key>,
+}
+
+struct IndexScopeEvent {
+    index: Option<String>,
+}
+
+struct QueryBuilderEvent {
+    query: String,
+}
+
+/// The entity rows are shown for, picked with `V`.
+struct EntityViewEvent(EntityView);
+
+/// An access pattern picked to run.
+struct RunPatternRequest {
+    query: String,
+}
+
+/// Ask for a name to save the current query under as an access pattern.
+struct SavePatternRequest;
+
+/// The name the current query is saved under as an access pattern.
+struct SavePatternEvent {
+    name: String,
+}
+
+struct CountMatchesEvent {
+    run_id: u64,
+    query: String,
+    result: Result<CountSummary, String>,
+}
+
 struct KeyVisibilityEvent {
     name: String,
     hidden: bool,
 }
 
+struct PathColumnEvent {
+    path: String,
+    added: bool,
+}
+
+struct TimeFormatEvent {
+    name: String,
+    format: Option<TimeFormat>,
+}
+
 struct ExportRequest {
     mode: ExportKind,
     path: PathBuf,
     fetch_all: bool,
+    /// Export only the columns the fields popup leaves visible.
+    visible_columns: bool,
+    /// Upload the written file here afterwards.
+    upload: Option<S3Uri>,
     overwrite_confirmed: bool,
 }
 
+struct AggregateRequest {
+    column: String,
+}
+
+struct AggregateEvent {
+    column: String,
+    result: Result<ColumnStats, String>,
+}
+
+struct ProjectionEvent {
+    projection: Option<RowProjection>,
+}
+
+struct QueryOptionsEvent {
+    options: QueryOptions,
+}
+
+/// A query checked for what scanning the table with it would cost: `None`
+/// when it isn't a scan or is cheap enough to run straight away.
+struct ScanEstimateEvent {
+    query: String,
+    estimate: Option<ScanEstimate>,
+}
+
+/// Run a query whose scan cost was confirmed.
+struct RunQueryRequest {
+    query: String,
+}
+
+struct RunWithParametersRequest {
+    query: String,
+    parameters: Vec<(String, String)>,
+}
+
 struct ExportEvent {
+    /// The streamed export this ends; one-shot exports have none.
+    export_id: Option<u64>,
     result: Result<ExportOutcome, String>,
 }
 
-struct ExportProgressEvent {
-    export_id: u64,
-    count: usize,
+struct S3UploadEvent {
+    uri: S3Uri,
+    result: Result<(), String>,
 }
 
 struct ExportOutcome {
@@ -269,6 +597,8 @@ impl PutAction {
 enum ActiveQuery {
     Text(String),
     Index(index_picker::IndexTarget),
+    /// Items fetched by a list of primary keys, all in one go.
+    Keys(Arc<Vec<Key>>),
 }
 
 impl Default for ActiveQuery {
@@ -282,6 +612,7 @@ impl ActiveQuery {
         match self {
             ActiveQuery::Text(query) => Some(query.clone()),
             ActiveQuery::Index(target) => QueryWidget::format_index_query(target),
+            ActiveQuery::Keys(_) => None,
         }
     }
 }
@@ -291,15 +622,33 @@ struct DeleteTarget {
     summary: String,
 }
 
+/// A loaded row: its attributes, and their lowercased text for the `/`
+/// filter, worked out once when first filtered.
 #[derive(Debug, Clone)]
-struct Item(HashMap<String, AttributeValue>);
+struct Item(HashMap<String, AttributeValue>, OnceCell<String>);
 
 impl Item {
     const MAX_CELL_LEN: usize = 120;
 
+    fn new(attributes: HashMap<String, AttributeValue>) -> Self {
+        Self(attributes, OnceCell::new())
+    }
+
+    fn matches(&self, filter: &RowFilter) -> bool {
+        filter.is_empty()
+            || filter.matches(
+                &self.0,
+                self.1.get_or_init(|| row_filter::search_text(&self.0)),
+            )
+    }
+
+    /// The attribute for a column, following nested path columns.
+    fn get(&self, key: &str) -> Option<&AttributeValue> {
+        projection::attribute_at(&self.0, key)
+    }
+
     fn value(&self, key: &str) -> String {
         let value = self
-            .0
             .get(key)
             .map(|val| {
                 if let Ok(v) = val.as_s() {
@@ -331,7 +680,7 @@ impl Item {
     }
 
     fn value_size(&self, key: &str) -> usize {
-        let Some(val) = self.0.get(key) else {
+        let Some(val) = self.get(key) else {
             return 0;
         };
         let len = if let Ok(v) = val.as_s() {
@@ -428,6 +777,13 @@ impl QueryState {
         language: &dyn QueryLanguage,
         schema: Option<&CollectionSchema>,
     ) {
+        // Expression completions don't apply to PartiQL statements.
+        if self.partiql_active() {
+            self.completion.items.clear();
+            self.completion.visible = false;
+            self.completion.has_sentinel = false;
+            return;
+        }
         let value = self.input.value().to_string();
         let cursor = self.input.cursor_byte();
         // In raw-SQL mode, completion is driven by the database's table/column
@@ -465,6 +821,12 @@ impl QueryState {
         self.completion.visible = !self.completion.dismissed && !self.completion.items.is_empty();
     }
 
+    /// Whether the input text runs as a PartiQL statement rather than an
+    /// expression.
+    fn partiql_active(&self) -> bool {
+        self.statements && (self.partiql || partiql::is_statement(self.input.value()))
+    }
+
     /// Forget any dismissal so suggestions can show again (e.g. after the input
     /// is (re)activated or its text changes).
     fn reset_completion_dismissal(&mut self) {
@@ -504,28 +866,283 @@ impl QueryState {
         !self.filter.value.trim().is_empty()
     }
 
+    fn is_scan(&self) -> bool {
+        matches!(
+            self.query_output.as_ref().map(|output| &output.plan_kind),
+            Some(PlanKind::Scan)
+        )
+    }
+
+    /// Whether a scan is still working through the table: a page is on its
+    /// way, or the scan is paused with pages left.
+    fn scan_in_progress(&self) -> bool {
+        matches!(self.loading_state, LoadingState::Loading)
+            || (self.is_scan()
+                && (self.is_loading_more
+                    || (self.scan_paused && self.last_evaluated_key.is_some())))
+    }
+
+    /// Whether a scan should fetch its next page without being asked: a
+    /// selective filter can leave whole pages without a match.
+    fn scan_wants_more(&self) -> bool {
+        self.is_scan()
+            && !self.scan_paused
+            && !self.is_loading_more
+            && !self.limit.is_reached()
+            && self.last_evaluated_key.is_some()
+            && self.filtered_indices.len() < self.last_render_capacity.max(1)
+    }
+
+    /// Footer labels for how the rows are shown: compact columns, sort,
+    /// read options, projection, the capacity used so far, and where the
+    /// result cap stopped loading.
+    fn view_labels(&self) -> Vec<String> {
+        let mut labels = Vec::new();
+        if self.compact_columns {
+            labels.push("compact".to_string());
+        }
+        if let Some(sort) = self.column_sort.as_ref() {
+            labels.push(format!("sorted by {} {}", sort.key, sort.arrow()));
+        }
+        match self.entity_view.scope {
+            EntityScope::All => {}
+            EntityScope::Entity(index) => {
+                labels.push(format!("entity {}", self.entities.names()[index]));
+            }
+            EntityScope::Other => labels.push("no entity".to_string()),
+        }
+        if self.entity_view.grouped {
+            labels.push("grouped by entity".to_string());
+        }
+        labels.extend(self.options.summary());
+        if let Some(projection) = self.projection.as_ref() {
+            labels.push(format!("show {}", projection.text()));
+        }
+        labels.extend(capacity_label(self.consumed_capacity));
+        if self.limit.is_reached() && self.last_evaluated_key.is_some() {
+            labels.push(self.limit.prompt());
+        }
+        if let Some(err) = self.filter_error.as_ref() {
+            labels.push(format!("filter: {err}"));
+        } else if !self.raw
+            && self.last_evaluated_key.is_some()
+            && matches!(self.active_query, ActiveQuery::Text(_))
+            && !self.row_filter.is_empty()
+            && self.row_filter.conditions().is_ok()
+        {
+            labels.push("F filters on the server".to_string());
+        }
+        let first_page = self.pages.first_loaded();
+        if first_page > 1 {
+            labels.push(format!("pages before {first_page} unloaded"));
+        }
+        labels
+    }
+
+    /// The page the selected row came from.
+    fn current_page(&self) -> usize {
+        let row = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.filtered_indices.get(idx).copied())
+            .unwrap_or(0);
+        self.pages.page_of(row)
+    }
+
+    /// The results table's columns: the visible attributes, or the
+    /// projection's columns when one is set.
+    fn columns(&self) -> Vec<String> {
+        match self.projection.as_ref() {
+            Some(projection) => projection.columns(self.item_keys.visible()),
+            None => self.item_keys.visible().to_vec(),
+        }
+    }
+
+    /// The text of one cell, as a timestamp when its column has a time
+    /// format the value fits.
+    fn cell_text(&self, item: &Item, key: &str) -> String {
+        self.time_formats
+            .get(key)
+            .zip(item.get(key))
+            .and_then(|(format, value)| format.apply(value, Utc::now()))
+            .map_or_else(|| item.value(key), truncate_cell)
+    }
+
+    fn cell_width(&self, item: &Item, key: &str) -> usize {
+        if self.time_formats.contains_key(key) {
+            self.cell_text(item, key).chars().count()
+        } else {
+            item.value_size(key)
+        }
+    }
+
+    /// How wide each column wants to be for the rows on screen. A width set
+    /// by hand wins over the values' and may exceed the cap.
+    fn natural_widths(&mut self, columns: &[String], rows: &[usize]) -> Vec<usize> {
+        let max_column_width = if self.compact_columns {
+            TABLE_MAX_COLUMN_WIDTH_COMPACT
+        } else {
+            TABLE_MAX_COLUMN_WIDTH
+        };
+        let cached = self
+            .width_cache
+            .as_ref()
+            .filter(|cache| cache.matches(self.rows_version, rows, columns));
+        let value_widths = if let Some(cache) = cached {
+            cache.widths.clone()
+        } else {
+            let displayed: Vec<Cow<'_, Item>> = rows
+                .iter()
+                .filter_map(|idx| self.items.get(*idx))
+                .map(|item| self.displayed(item))
+                .collect();
+            let widths: Vec<usize> = columns
+                .iter()
+                .map(|key| {
+                    let max_value = displayed
+                        .iter()
+                        .map(|item| self.cell_width(item, key))
+                        .max()
+                        .unwrap_or(0);
+                    max(max_value, key.len() + 2)
+                })
+                .collect();
+            self.width_cache = Some(WidthCache {
+                version: self.rows_version,
+                rows: rows.to_vec(),
+                columns: columns.to_vec(),
+                widths: widths.clone(),
+            });
+            widths
+        };
+        columns
+            .iter()
+            .zip(value_widths)
+            .map(|(key, width)| {
+                self.column_widths
+                    .get(key)
+                    .unwrap_or_else(|| width.min(max_column_width))
+            })
+            .collect()
+    }
+
+    /// A loaded row as the results table shows it.
+    /// The projection exports apply: the results' projection, cut down to
+    /// the visible columns when the export asked for that.
+    fn export_projection(&self) -> Option<RowProjection> {
+        if self.export_visible_columns {
+            Some(RowProjection::only_columns(
+                self.projection.as_ref(),
+                self.columns(),
+            ))
+        } else {
+            self.projection.clone()
+        }
+    }
+
+    fn displayed<'a>(&self, item: &'a Item) -> Cow<'a, Item> {
+        let shown = match self.projection.as_ref() {
+            Some(projection) => Cow::Owned(Item::new(projection.apply(&item.0))),
+            None => Cow::Borrowed(item),
+        };
+        match self.hydration.as_ref() {
+            Some(hydration) => {
+                let mut attributes = shown.into_owned().0;
+                hydration.merge(&item.0, &mut attributes);
+                Cow::Owned(Item::new(attributes))
+            }
+            None => shown,
+        }
+    }
+
+    /// Note that the loaded rows, or how they're shown, changed, dropping the
+    /// cached column widths.
+    fn rows_changed(&mut self) {
+        self.rows_version += 1;
+    }
+
+    /// The loaded rows, to change them. Every change to `items` goes through
+    /// here so the cached column widths never outlive the rows they measured.
+    fn rows_mut(&mut self) -> &mut Vec<Item> {
+        self.rows_changed();
+        &mut self.items
+    }
+
+    /// Show `hydration`'s columns in place of the ones joined before.
+    fn set_hydration(&mut self, hydration: Option<Hydration>) {
+        if let Some(previous) = self.hydration.take() {
+            for column in previous.columns() {
+                self.item_keys.remove_path(column);
+            }
+        }
+        if let Some(hydration) = hydration.as_ref() {
+            for column in hydration.columns() {
+                self.item_keys.add_path(column);
+            }
+        }
+        self.hydration = hydration;
+        self.rows_changed();
+    }
+
     fn apply_filter(&mut self) {
-        let needle = self.filter.value.trim().to_lowercase();
+        self.filter_rows(false);
+    }
+
+    /// Re-filter after the filter text changed. Text that only grew narrows
+    /// the rows already shown instead of scanning every loaded row.
+    fn refine_filter(&mut self) {
+        self.filter_rows(true);
+    }
+
+    fn filter_rows(&mut self, refine: bool) {
+        let filter = match RowFilter::parse(&self.filter.value) {
+            Ok(filter) => {
+                self.filter_error = None;
+                filter
+            }
+            Err(err) => {
+                self.filter_error = Some(err);
+                if refine {
+                    return;
+                }
+                self.row_filter.clone()
+            }
+        };
+        self.rows_changed();
         let current_item = self
             .table_state
             .selected()
             .and_then(|idx| self.filtered_indices.get(idx).copied());
 
-        if needle.is_empty() {
-            self.filtered_indices = (0..self.items.len()).collect();
+        if refine && filter.narrows(&self.row_filter) {
+            let items = &self.items;
+            self.filtered_indices
+                .retain(|idx| items[*idx].matches(&filter));
         } else {
             self.filtered_indices = self
                 .items
                 .iter()
                 .enumerate()
-                .filter(|(_, item)| item_matches_filter(&item.0, &needle))
+                .filter(|(_, item)| item.matches(&filter) && self.in_entity_scope(item))
                 .map(|(idx, _)| idx)
                 .collect();
+            if let Some(sort) = self.column_sort.as_ref() {
+                let items: Vec<Cow<'_, Item>> =
+                    self.items.iter().map(|item| self.displayed(item)).collect();
+                self.filtered_indices
+                    .sort_by(|a, b| sort.compare(&items[*a], &items[*b]));
+            }
+            if self.entity_view.grouped {
+                let (items, entities) = (&self.items, &self.entities);
+                self.filtered_indices
+                    .sort_by_key(|idx| entities.classify(&items[*idx].0).unwrap_or(usize::MAX));
+            }
         }
+        self.row_filter = filter;
 
         if self.filtered_indices.is_empty() {
             self.table_state.select(None);
-            self.reset_tree_scroll();
+            self.reset_tree_view();
             return;
         }
 
@@ -542,7 +1159,51 @@ impl QueryState {
 
         self.table_state.select(Some(0));
         self.clamp_table_offset();
-        self.reset_tree_scroll();
+        self.reset_tree_view();
+    }
+
+    /// Filter the rows loaded from `first_new` on into the view, leaving the
+    /// rows before them alone. A sorted view is sorted again in full.
+    fn filter_appended(&mut self, first_new: usize) {
+        if self.column_sort.is_some()
+            || self.entity_view.grouped
+            || self.filtered_indices.is_empty()
+        {
+            self.apply_filter();
+            return;
+        }
+        self.rows_changed();
+        let appended: Vec<usize> = (first_new..self.items.len())
+            .filter(|idx| {
+                let item = &self.items[*idx];
+                item.matches(&self.row_filter) && self.in_entity_scope(item)
+            })
+            .collect();
+        self.filtered_indices.extend(appended);
+    }
+
+    fn in_entity_scope(&self, item: &Item) -> bool {
+        self.entity_view.scope == EntityScope::All
+            || self
+                .entity_view
+                .scope
+                .includes(self.entities.classify(&item.0))
+    }
+
+    /// Unload the first `count` rows, keeping the selected row selected when
+    /// it stays loaded.
+    fn drop_front_rows(&mut self, count: usize) {
+        let selected = self
+            .table_state
+            .selected()
+            .and_then(|pos| self.filtered_indices.get(pos))
+            .and_then(|idx| idx.checked_sub(count));
+        self.rows_mut().drain(..count);
+        // Unfiltered, a row's position is its index, which is how
+        // `apply_filter` finds the selected row again.
+        self.filtered_indices = (0..self.items.len()).collect();
+        self.table_state.select(selected);
+        self.apply_filter();
     }
 
     fn clamp_table_offset(&mut self) {
@@ -577,8 +1238,31 @@ impl QueryState {
         }
     }
 
-    fn reset_tree_scroll(&mut self) {
+    /// The selected item for the tree, and the tree's folds and cursor.
+    fn tree_view(&mut self) -> (tree_view::TreeItem<'_>, &mut tree::TreeState) {
+        let selected = self.table_state.selected().unwrap_or(0);
+        let item = self
+            .filtered_indices
+            .get(selected)
+            .and_then(|idx| self.items.get(*idx))
+            .map(|item| &item.0);
+        let view = tree_view::TreeItem {
+            item,
+            key_order: self.item_keys.sorted(),
+        };
+        (view, &mut self.tree)
+    }
+
+    fn tree_item(&self) -> tree_view::TreeItem<'_> {
+        tree_view::TreeItem {
+            item: selected_tree_item(self).map(|item| &item.0),
+            key_order: self.item_keys.sorted(),
+        }
+    }
+
+    fn reset_tree_view(&mut self) {
         self.tree_scroll_offset = 0;
+        self.tree.reset();
     }
 
     fn clamp_tree_offset(&mut self) {
@@ -587,15 +1271,26 @@ impl QueryState {
         self.tree_scroll_offset = self.tree_scroll_offset.min(max_offset);
     }
 
-    fn scroll_tree_down(&mut self) {
-        self.clamp_tree_offset();
+    /// Keep the tree cursor on a row and scroll it into view.
+    fn follow_tree_cursor(&mut self) {
         let viewport = self.tree_render_capacity.max(1);
-        let max_offset = self.tree_line_count.saturating_sub(viewport);
-        self.tree_scroll_offset = (self.tree_scroll_offset + 1).min(max_offset);
+        self.tree.cursor = self.tree.cursor.min(self.tree_line_count.saturating_sub(1));
+        if self.tree.cursor < self.tree_scroll_offset {
+            self.tree_scroll_offset = self.tree.cursor;
+        } else if self.tree.cursor >= self.tree_scroll_offset + viewport {
+            self.tree_scroll_offset = self.tree.cursor + 1 - viewport;
+        }
+        self.clamp_tree_offset();
+    }
+
+    fn scroll_tree_down(&mut self) {
+        self.tree.cursor = self.tree.cursor.saturating_add(1);
+        self.follow_tree_cursor();
     }
 
     fn scroll_tree_up(&mut self) {
-        self.tree_scroll_offset = self.tree_scroll_offset.saturating_sub(1);
+        self.tree.cursor = self.tree.cursor.saturating_sub(1);
+        self.follow_tree_cursor();
     }
 
     fn page_tree_down(&mut self) {
@@ -606,11 +1301,15 @@ impl QueryState {
             .tree_scroll_offset
             .saturating_add(viewport)
             .min(max_offset);
+        self.tree.cursor = self.tree.cursor.saturating_add(viewport);
+        self.follow_tree_cursor();
     }
 
     fn page_tree_up(&mut self) {
         let viewport = self.tree_render_capacity.max(1);
         self.tree_scroll_offset = self.tree_scroll_offset.saturating_sub(viewport);
+        self.tree.cursor = self.tree.cursor.saturating_sub(viewport);
+        self.follow_tree_cursor();
     }
 }
 
@@ -646,9 +1345,15 @@ impl crate::widgets::Widget for QueryWidget {
 
         // Title-bar context: table name · region · approximate item count.
         let mut context_parts = vec![self.table_name.clone()];
-        if let Some(region) = env::var("AWS_REGION")
-            .or_else(|_| env::var("AWS_DEFAULT_REGION"))
-            .ok()
+        if let Some(region) = self
+            .db
+            .active_profile()
+            .and_then(|selection| selection.region)
+            .or_else(|| {
+                env::var("AWS_REGION")
+                    .or_else(|_| env::var("AWS_DEFAULT_REGION"))
+                    .ok()
+            })
             .filter(|r| !r.is_empty())
         {
             context_parts.push(region);
@@ -666,8 +1371,12 @@ impl crate::widgets::Widget for QueryWidget {
         // Mode chip reflects the dominant interaction state.
         let mode = if matches!(state.loading_state, LoadingState::Error(_)) {
             "ERROR"
+        } else if state.show_tree && state.tree.search.is_active() {
+            "SEARCH"
         } else if state.show_tree {
             "ITEM"
+        } else if state.input.is_active() && state.partiql_active() {
+            "PARTIQL"
         } else if state.input.is_active() {
             "QUERY"
         } else if state.filter.is_active() {
@@ -688,6 +1397,9 @@ impl crate::widgets::Widget for QueryWidget {
             if let Some(selection) = self.selection_status(&state) {
                 parts.push(selection);
             }
+            if transaction::is_staging() {
+                parts.push(format!("staging {} writes", transaction::staged_count()));
+            }
             Some(parts.join(" · "))
         };
 
@@ -707,6 +1419,9 @@ impl crate::widgets::Widget for QueryWidget {
     }
 
     fn start(&self, ctx: crate::env::WidgetCtx) {
+        if self.watch_on_start {
+            self.start_watch(&ctx);
+        }
         if self.raw_sql {
             self.fetch_schema_hints(ctx.clone());
             // If launched with a query (e.g. from the table picker's SQL bar),
@@ -789,7 +1504,8 @@ impl crate::widgets::Widget for QueryWidget {
                     LoadingState::Error(message) => Some(message.as_str()),
                     _ => None,
                 };
-                let hint = self.query_hint_line(state.input.value(), error, theme);
+                let hint =
+                    self.query_hint_line(state.input.value(), state.partiql_active(), error, theme);
                 frame.render_widget(Paragraph::new(hint), sub[1]);
                 if completion_visible && actual_dropdown > 0 {
                     self.render_completion(frame, sub[2], theme, &state.completion);
@@ -801,7 +1517,15 @@ impl crate::widgets::Widget for QueryWidget {
                 state.filter.render(frame, filter_area, theme);
                 idx += 1;
             }
-            let results_area = areas[idx];
+            let mut results_area = areas[idx];
+            if state.split_preview && results_area.width >= MIN_SPLIT_WIDTH {
+                let [table_area, preview_area] = results_area.layout(&Layout::horizontal([
+                    Constraint::Percentage(60),
+                    Constraint::Percentage(40),
+                ]));
+                self.render_preview(frame, preview_area, theme, &state);
+                results_area = table_area;
+            }
             self.render_table(
                 frame,
                 results_area,
@@ -823,13 +1547,19 @@ impl crate::widgets::Widget for QueryWidget {
         if filter_active && self.handle_filter_key(event) {
             return true;
         }
+        if self.state.borrow().tree.search.is_active() && self.handle_tree_search_key(&ctx, event) {
+            return true;
+        }
         if let Some(key) = event.as_key_press_event() {
             return self.handle_browse_key(&ctx, key, input_is_active, filter_active);
         }
         if let Some(mouse) = event.as_mouse_event() {
             match mouse.kind {
-                crossterm::event::MouseEventKind::ScrollUp => self.scroll_up(),
-                crossterm::event::MouseEventKind::ScrollDown => self.scroll_down(ctx.clone()),
+                MouseEventKind::ScrollUp => self.scroll_up(),
+                MouseEventKind::ScrollDown => self.scroll_down(ctx.clone()),
+                MouseEventKind::Down(MouseButton::Left) if !input_is_active && !filter_active => {
+                    return self.handle_click(&ctx, mouse.column, mouse.row);
+                }
                 _ => {}
             }
         }
@@ -837,15 +1567,22 @@ impl crate::widgets::Widget for QueryWidget {
     }
 
     fn help(&self) -> Option<&[help::Entry<'_>]> {
-        let show_tree = self.state.borrow().show_tree;
+        let (show_tree, tree_search) = {
+            let state = self.state.borrow();
+            (state.show_tree, state.tree.search.is_active())
+        };
+        if show_tree && tree_search {
+            return Some(Self::HELP_FILTER_EDIT);
+        }
         if show_tree {
             return Some(&self.help_tree);
         }
         let state = self.state.borrow();
         if state.input.is_active() {
-            return Some(Self::HELP_QUERY_EDIT);
+            return Some(&self.help_query_edit);
         }
-        if matches!(state.loading_state, LoadingState::Loading) && !state.filter.is_active() {
+        let scanning = state.scan_in_progress() && !state.scan_paused;
+        if scanning && !state.filter.is_active() {
             return Some(Self::HELP_LOADING);
         }
         if state.filter.is_active() {
@@ -861,11 +1598,31 @@ impl crate::widgets::Widget for QueryWidget {
 
     fn suppress_global_help(&self) -> bool {
         let state = self.state.borrow();
-        state.filter.is_active() || state.input.is_active()
+        state.filter.is_active() || state.input.is_active() || state.tree.search.is_active()
+    }
+
+    fn on_app_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        // The shared datastore now talks to another account/region: drop the
+        // cached table metadata and rerun the current query against it.
+        if event.payload::<ProfileSwitchedEvent>().is_some() {
+            self.table_meta.borrow_mut().take();
+            self.meta_started.set(false);
+            let active_query = self.state.borrow().active_query.clone();
+            self.restart_query(active_query, ctx, None);
+            return;
+        }
+        if let Some(restored) = event.payload::<ItemRestoredEvent>()
+            && restored.result.is_ok()
+            && restored.table == self.table_name
+        {
+            let active_query = self.state.borrow().active_query.clone();
+            self.restart_query(active_query, ctx, None);
+        }
     }
 
     #[expect(
         clippy::cognitive_complexity,
+        clippy::too_many_lines,
         reason = "flat if-let dispatch over self-event payload variants"
     )]
     fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
@@ -893,6 +1650,7 @@ impl crate::widgets::Widget for QueryWidget {
                         matched = output.count,
                         "query_page"
                     );
+                    self.record_limit(&output, &ctx);
                     self.process_query_output(output, page_event.append);
                     if !page_event.append {
                         self.set_loading_state(LoadingState::Loaded);
@@ -901,6 +1659,9 @@ impl crate::widgets::Widget for QueryWidget {
                         let mut state = self.state.borrow_mut();
                         state.is_prefetching = false;
                     }
+                    if self.state.borrow().scan_wants_more() {
+                        self.load_more(ctx.clone());
+                    }
                     ctx.invalidate();
                     let _ = (scanned_total, matched_total);
                 }
@@ -957,64 +1718,199 @@ impl crate::widgets::Widget for QueryWidget {
             return;
         }
 
-        if let Some(export_request) = event.payload::<ExportRequest>() {
-            if !export_request.overwrite_confirmed && export_request.path.exists() {
-                let filename = export_request.path.file_name().map_or_else(
-                    || export_request.path.display().to_string(),
-                    |name| name.to_string_lossy().to_string(),
-                );
-                let message = format!("{filename} already exists");
-                let ctx_for_confirm = ctx.clone();
-                let confirm_action = ConfirmAction::new(
-                    KeyCode::Char('o'),
-                    KeyModifiers::CONTROL,
-                    "^o",
-                    "overwrite",
-                    "Overwrite file",
-                );
-                let mode = export_request.mode;
-                let fetch_all = export_request.fetch_all;
-                let path = export_request.path.clone();
-                let popup = Box::new(ConfirmPopup::new_with_action(
-                    "Overwrite?",
-                    message,
-                    "Overwrite",
-                    "cancel",
-                    confirm_action,
-                    move || {
-                        ctx_for_confirm.emit_self(ExportRequest {
-                            mode,
-                            path: path.clone(),
-                            fetch_all,
-                            overwrite_confirmed: true,
-                        });
-                    },
-                    self.inner.id(),
-                ));
-                ctx.set_popup(popup);
-                return;
+        if let Some(column) = event.payload::<TimeFormatEvent>() {
+            let mut state = self.state.borrow_mut();
+            state.rows_changed();
+            match column.format {
+                Some(format) => state.time_formats.insert(column.name.clone(), format),
+                None => state.time_formats.remove(&column.name),
+            };
+            ctx.invalidate();
+            return;
+        }
+
+        if let Some(column) = event.payload::<PathColumnEvent>() {
+            let mut state = self.state.borrow_mut();
+            if column.added {
+                state.item_keys.add_path(&column.path);
+            } else {
+                state.item_keys.remove_path(&column.path);
             }
-            self.start_export(
-                export_request.mode,
-                export_request.path.clone(),
-                export_request.fetch_all,
-                ctx,
-            );
+            ctx.invalidate();
             return;
         }
 
-        if let Some(export_event) = event.payload::<ExportEvent>() {
-            {
-                let mut state = self.state.borrow_mut();
-                state.is_prefetching = false;
-                state.export_id = None;
-                state.export_cancel = None;
+        if let Some(export_request) = event.payload::<ExportRequest>() {
+            self.handle_export_request(export_request, ctx);
+            return;
+        }
+
+        let step = watch::handle(event, &ctx, self.state.borrow().watch.as_ref());
+        if let Some(step) = step {
+            self.on_watch_step(step, &ctx);
+            return;
+        }
+
+        if let Some(step) = compare::handle(event, &ctx, &self.table_name, self.inner.id()) {
+            self.on_compare_step(step, &ctx);
+            return;
+        }
+
+        if let Some(request) = event.payload::<AggregateRequest>() {
+            self.aggregate_all(request.column.clone(), ctx);
+            return;
+        }
+
+        if let Some(aggregate) = event.payload::<AggregateEvent>() {
+            match &aggregate.result {
+                Ok(stats) => self.show_aggregate(&aggregate.column, stats.clone(), false, &ctx),
+                Err(err) => self.show_error(ctx, err),
             }
-            match export_event.result.as_ref() {
-                Ok(outcome) => {
-                    let display_path = abbreviate_home(&outcome.path);
-                    let message = match outcome.mode {
-                        ExportKind::Item => format!("Exported to {display_path}"),
+            return;
+        }
+
+        if let Some(projection_event) = event.payload::<ProjectionEvent>() {
+            let mut state = self.state.borrow_mut();
+            state.projection.clone_from(&projection_event.projection);
+            state.rows_changed();
+            state.column_offset = 0;
+            state.column_sort = None;
+            state.apply_filter();
+            ctx.invalidate();
+            return;
+        }
+
+        if let Some(options_event) = event.payload::<QueryOptionsEvent>() {
+            let active_query = {
+                let mut state = self.state.borrow_mut();
+                state.options = options_event.options.clone();
+                state.active_query.clone()
+            };
+            self.restart_query(active_query, ctx.clone(), None);
+            return;
+        }
+
+        if let Some(scope) = event.payload::<IndexScopeEvent>() {
+            let query = {
+                let mut state = self.state.borrow_mut();
+                state.options.index.clone_from(&scope.index);
+                // A key lookup from the index picker targets a fixed index;
+                // switching scope starts over with a scan of the new one.
+                match &state.active_query {
+                    ActiveQuery::Text(query) => query.clone(),
+                    ActiveQuery::Index(_) | ActiveQuery::Keys(_) => String::new(),
+                }
+            };
+            self.start_query(Some(&query), ctx.clone());
+            return;
+        }
+
+        if let Some(built) = event.payload::<QueryBuilderEvent>() {
+            self.start_query(Some(&built.query), ctx.clone());
+            return;
+        }
+
+        if let Some(step) = hydrate::handle(event, &ctx, || self.join_source()) {
+            match step {
+                hydrate::HydrateStep::Continuing => {}
+                hydrate::HydrateStep::Joined(hydration) => {
+                    self.state.borrow_mut().set_hydration(hydration);
+                }
+                hydrate::HydrateStep::Failed(err) => self.show_error(ctx.clone(), &err),
+            }
+            ctx.invalidate();
+            return;
+        }
+
+        if let Some(EntityViewEvent(view)) = event.payload::<EntityViewEvent>() {
+            let mut state = self.state.borrow_mut();
+            state.entity_view = *view;
+            state.apply_filter();
+            ctx.invalidate();
+            return;
+        }
+
+        if let Some(request) = event.payload::<RunPatternRequest>() {
+            self.submit_query(request.query.clone(), ctx.clone());
+            return;
+        }
+
+        if event.payload::<SavePatternRequest>().is_some() {
+            self.name_access_pattern(ctx.clone());
+            return;
+        }
+
+        if let Some(save) = event.payload::<SavePatternEvent>() {
+            self.save_access_pattern(&save.name, &ctx);
+            return;
+        }
+
+        if self.handle_count_matches_event(&ctx, event) {
+            return;
+        }
+
+        if let Some(step) = delete_matching::handle(event, &ctx, || self.delete_source()) {
+            match step {
+                delete_matching::DeleteStep::Continuing => {}
+                delete_matching::DeleteStep::Failed(err) => self.show_error(ctx.clone(), &err),
+                delete_matching::DeleteStep::Deleted(deleted) => {
+                    if deleted > 0 {
+                        let active_query = self.state.borrow().active_query.clone();
+                        self.restart_query(active_query, ctx.clone(), None);
+                    }
+                }
+            }
+            return;
+        }
+
+        if let Some(step) = bulk_update::handle(event, &ctx, || self.update_source()) {
+            self.on_update_step(step, &ctx);
+            return;
+        }
+
+        if let Some(request) = event.payload::<RunWithParametersRequest>() {
+            self.state
+                .borrow_mut()
+                .parameters
+                .clone_from(&request.parameters);
+            self.check_scan_cost(request.query.clone(), ctx.clone());
+            return;
+        }
+        if let Some(checked) = event.payload::<ScanEstimateEvent>() {
+            match checked.estimate.as_ref() {
+                Some(estimate) => self.confirm_scan(&checked.query, estimate, ctx),
+                None => self.start_query(Some(&checked.query), ctx.clone()),
+            }
+            return;
+        }
+        if let Some(request) = event.payload::<RunQueryRequest>() {
+            self.start_query(Some(&request.query), ctx.clone());
+            return;
+        }
+        if let Some(page) = pages::handle(event) {
+            self.go_to_page(page, ctx);
+            return;
+        }
+
+        if let Some(export_event) = event.payload::<ExportEvent>() {
+            self.state.borrow_mut().is_prefetching = false;
+            if let Some(export_id) = export_event.export_id {
+                self.exports.finish(export_id);
+            }
+            let upload = self.state.borrow_mut().pending_upload.take();
+            match (export_event.result.as_ref(), upload) {
+                (Ok(outcome), Some(upload)) => {
+                    let file_name = outcome
+                        .path
+                        .file_name()
+                        .map_or_else(|| "export.json".into(), |name| name.to_string_lossy());
+                    let uri = upload.object_for(&file_name);
+                    self.start_upload(outcome.path.clone(), uri, outcome.count, ctx);
+                }
+                (Ok(outcome), None) => {
+                    let display_path = abbreviate_home(&outcome.path);
+                    let message = match outcome.mode {
+                        ExportKind::Item => format!("Exported to {display_path}"),
                         ExportKind::Selection => {
                             format!(
                                 "Exported {} selected items to {}",
@@ -1035,7 +1931,7 @@ impl crate::widgets::Widget for QueryWidget {
                         )),
                     });
                 }
-                Err(err) => {
+                (Err(err), _) => {
                     if err == "Export canceled" {
                         ctx.show_toast(Toast {
                             message: "Export canceled".to_string(),
@@ -1052,17 +1948,21 @@ impl crate::widgets::Widget for QueryWidget {
             return;
         }
 
-        if let Some(progress) = event.payload::<ExportProgressEvent>() {
-            let should_update = {
-                let state = self.state.borrow();
-                state.export_id == Some(progress.export_id)
-                    && !state
-                        .export_cancel
-                        .as_ref()
-                        .is_some_and(|flag| flag.load(Ordering::Relaxed))
-            };
-            if should_update {
-                self.show_export_progress_toast(ctx, progress.count);
+        if let Some(upload) = event.payload::<S3UploadEvent>() {
+            match &upload.result {
+                Ok(()) => ctx.show_toast(Toast {
+                    message: format!("Uploaded to {}", upload.uri),
+                    kind: ToastKind::Info,
+                    duration: Duration::from_secs(4),
+                    action: Some(ToastAction::copy_path('c', upload.uri.to_string())),
+                }),
+                Err(err) => {
+                    self.show_error(
+                        ctx.clone(),
+                        &format!("Upload to {} failed: {err}", upload.uri),
+                    );
+                    ctx.invalidate();
+                }
             }
             return;
         }
@@ -1091,6 +1991,41 @@ impl crate::widgets::Widget for QueryWidget {
             }
         }
 
+        if let Some(Err(err)) = clipboard::handle(event, &ctx, &self.table_name) {
+            self.show_error(ctx.clone(), &err);
+        }
+
+        if let Some(request) = event.payload::<ConvertRequest>() {
+            self.convert_attribute(request, ctx);
+            return;
+        }
+
+        if let Some(save_event) = event.payload::<SaveEditRequest>() {
+            self.save_edit(save_event, ctx);
+            return;
+        }
+
+        if let Some(edited) = event.payload::<EditedTextEvent>() {
+            self.finish_edit(edited.target.clone(), &edited.text, ctx);
+            return;
+        }
+
+        if let Some(template) = event.payload::<ItemTemplateEvent>() {
+            self.create_item(template.format, &template.item, ctx);
+            return;
+        }
+
+        if let Some(create) = event.payload::<CreateItemRequest>() {
+            self.put_item(
+                create.item.clone(),
+                create.active_query.clone(),
+                PutAction::Create,
+                ctx,
+                None,
+            );
+            return;
+        }
+
         if let Some(delete_event) = event.payload::<DeleteItemRequest>() {
             self.delete_item(delete_event.key.clone(), ctx);
             return;
@@ -1105,13 +2040,17 @@ impl crate::widgets::Widget for QueryWidget {
             match delete_event.result.as_ref() {
                 Ok(()) => {
                     self.set_loading_state(LoadingState::Loaded);
-                    self.remove_item_by_key(&delete_event.key);
+                    let removed = self.remove_item_by_key(&delete_event.key);
                     self.remove_selection_key(&delete_event.key);
+                    let action = removed.map(|item| {
+                        undo::record(&self.table_name, item_from_attribute_map(&item.0));
+                        ToastAction::restore_deleted()
+                    });
                     ctx.show_toast(Toast {
                         message: "Item deleted".to_string(),
                         kind: ToastKind::Info,
-                        duration: Duration::from_secs(3),
-                        action: None,
+                        duration: Duration::from_secs(5),
+                        action,
                     });
                     ctx.invalidate();
                 }
@@ -1147,6 +2086,22 @@ impl crate::widgets::Widget for QueryWidget {
             return;
         }
 
+        if let Some(step) = copy_items::handle(event, &ctx, || self.copy_source()) {
+            self.on_copy_step(step, ctx);
+            return;
+        }
+
+        if let Some(key_list) = event.payload::<KeyListEvent>() {
+            let widget = Box::new(QueryWidget::new_with_query(
+                self.db.clone(),
+                &self.table_name,
+                self.inner.id(),
+                Some(ActiveQuery::Keys(Arc::new(key_list.keys.clone()))),
+            ));
+            ctx.push_widget(widget);
+            return;
+        }
+
         if let Some(index_event) = event.payload::<IndexQueryEvent>() {
             let widget = Box::new(QueryWidget::new_with_query(
                 self.db.clone(),
@@ -1190,6 +2145,21 @@ impl QueryWidget {
                     self.open_reference_popup(ctx.clone());
                     return true;
                 }
+                KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::ALT) => {
+                    self.edit_query_in_editor(ctx);
+                    return true;
+                }
+                KeyCode::Char('p')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.state.borrow().statements =>
+                {
+                    let lang = self.input_language();
+                    let schema = self.schema_snapshot();
+                    let mut state = self.state.borrow_mut();
+                    state.partiql = !state.partiql;
+                    state.refresh_completion(lang, schema.as_ref());
+                    return true;
+                }
                 KeyCode::Up if dropdown_visible => {
                     self.state.borrow_mut().completion.select_prev();
                     return true;
@@ -1240,19 +2210,125 @@ impl QueryWidget {
         false
     }
 
+    /// `M-e` in the query input: edit the expression in an editor, for long
+    /// ones. Line breaks become spaces, since the input is a single line.
+    fn edit_query_in_editor(&self, ctx: &crate::env::WidgetCtx) {
+        let current = self.state.borrow().input.value().to_string();
+        self.edit_text(&format!("{current}\n"), EditTarget::Query, ctx.clone());
+    }
+
+    fn set_query_from_editor(&self, edited: &str) {
+        let lang = self.input_language();
+        let schema = self.schema_snapshot();
+        let mut state = self.state.borrow_mut();
+        state.input.set_value(single_line(edited));
+        state.reset_completion_dismissal();
+        state.refresh_completion(lang, schema.as_ref());
+    }
+
     /// Handle a key while the results filter is active. Returns `true` when the
     /// filter consumed it (and the visible rows were re-filtered).
     fn handle_filter_key(&self, event: &Event) -> bool {
         let mut state = self.state.borrow_mut();
         if state.filter.handle_event(event) {
-            state.apply_filter();
+            state.refine_filter();
             return true;
         }
         false
     }
 
+    fn handle_tree_search_key(&self, ctx: &crate::env::WidgetCtx, event: &Event) -> bool {
+        let step = {
+            let mut state = self.state.borrow_mut();
+            let (item, tree) = state.tree_view();
+            tree_view::handle_search_key(&item, tree, event)
+        };
+        let Some(step) = step else {
+            return false;
+        };
+        self.on_tree_step(step, ctx);
+        true
+    }
+
+    /// Keys specific to the item tree. Returns `false` for keys the main
+    /// keymap handles.
+    fn handle_tree_key(
+        &self,
+        ctx: &crate::env::WidgetCtx,
+        key: crossterm::event::KeyEvent,
+    ) -> bool {
+        let step = {
+            let mut state = self.state.borrow_mut();
+            let (item, tree) = state.tree_view();
+            tree_view::handle_key(&item, tree, key, ctx)
+        };
+        let Some(step) = step else {
+            return false;
+        };
+        self.on_tree_step(step, ctx);
+        true
+    }
+
+    fn on_tree_step(&self, step: tree_view::TreeStep, ctx: &crate::env::WidgetCtx) {
+        match step {
+            tree_view::TreeStep::Handled => {}
+            tree_view::TreeStep::Moved(rows) => {
+                let mut state = self.state.borrow_mut();
+                state.tree_line_count = rows;
+                state.follow_tree_cursor();
+            }
+            tree_view::TreeStep::Open(row) => {
+                if !self.follow_reference(ctx, &row) {
+                    let step = {
+                        let mut state = self.state.borrow_mut();
+                        let (item, tree) = state.tree_view();
+                        tree_view::fold(&item, tree, tree::TreeState::toggle)
+                    };
+                    self.on_tree_step(step, ctx);
+                }
+            }
+            tree_view::TreeStep::Convert(row) => self.show_convert_picker(ctx, row),
+            tree_view::TreeStep::Failed(err) => self.show_error(ctx.clone(), &err),
+        }
+    }
+
     /// Handle a key in browse/tree mode (the main keymap). Returns `true` when
     /// the key was handled, `false` for unrecognized keys.
+    /// Esc while browsing backs out one step: the tree view, a running export
+    /// or request, the filter, the selection, and finally the widget itself.
+    fn handle_escape(&self, ctx: &crate::env::WidgetCtx) {
+        let mut state = self.state.borrow_mut();
+        if state.show_tree {
+            state.show_tree = false;
+        } else if state.is_prefetching {
+            drop(state);
+            self.request_export_cancel(ctx.clone(), true);
+        } else if self.bulk.is_busy() {
+            drop(state);
+            if self.bulk.cancel() {
+                ctx.show_toast(Toast {
+                    message: "Canceling...".to_string(),
+                    kind: ToastKind::Info,
+                    duration: Duration::from_secs(2),
+                    action: None,
+                });
+            }
+        } else if matches!(state.loading_state, LoadingState::Loading)
+            || (state.is_loading_more && state.is_scan())
+        {
+            drop(state);
+            self.cancel_active_request();
+        } else if state.filter_applied() {
+            state.filter.clear();
+            state.apply_filter();
+        } else if state.selection.is_active() {
+            state.selection.clear();
+        } else {
+            drop(state);
+            ctx.pop_widget();
+        }
+    }
+
     fn handle_browse_key(
         &self,
         ctx: &crate::env::WidgetCtx,
@@ -1260,6 +2336,9 @@ impl QueryWidget {
         input_is_active: bool,
         filter_active: bool,
     ) -> bool {
+        if self.state.borrow().show_tree && !input_is_active && self.handle_tree_key(ctx, key) {
+            return true;
+        }
         match key.code {
             KeyCode::Tab | KeyCode::BackTab => self.state.borrow_mut().input.toggle_active(),
             KeyCode::Esc if input_is_active => {
@@ -1272,26 +2351,7 @@ impl QueryWidget {
                 state.filter.set_active(false);
                 state.apply_filter();
             }
-            KeyCode::Esc => {
-                let mut state = self.state.borrow_mut();
-                if state.show_tree {
-                    state.show_tree = false;
-                } else if state.is_prefetching {
-                    drop(state);
-                    self.request_export_cancel(ctx.clone(), true);
-                } else if matches!(state.loading_state, LoadingState::Loading) {
-                    drop(state);
-                    self.cancel_active_request();
-                } else if state.filter_applied() {
-                    state.filter.clear();
-                    state.apply_filter();
-                } else if state.selection.is_active() {
-                    state.selection.clear();
-                } else {
-                    drop(state);
-                    ctx.pop_widget();
-                }
-            }
+            KeyCode::Esc => self.handle_escape(ctx),
             KeyCode::Enter if input_is_active => {
                 let query = {
                     let mut state = self.state.borrow_mut();
@@ -1300,13 +2360,13 @@ impl QueryWidget {
                     state.completion.visible = false;
                     value
                 };
-                self.start_query(Some(&query), ctx.clone());
+                self.submit_query(query, ctx.clone());
             }
             KeyCode::Enter => {
                 let mut state = self.state.borrow_mut();
                 if !state.show_tree {
                     state.show_tree = true;
-                    state.reset_tree_scroll();
+                    state.reset_tree_view();
                 }
             }
             KeyCode::Char('/') if !input_is_active && !filter_active => {
@@ -1332,19 +2392,11 @@ impl QueryWidget {
             KeyCode::PageDown => self.page_down(ctx.clone()),
             KeyCode::PageUp => self.page_up(),
             KeyCode::Left
-                if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
-            {
-                self.scroll_columns_left();
-            }
-            KeyCode::Right
-                if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
-            {
-                self.scroll_columns_right();
-            }
-            KeyCode::Char('z')
-                if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
-            {
-                self.toggle_compact_columns();
+            | KeyCode::Right
+            | KeyCode::Char(
+                'z' | 'P' | '<' | '>' | 'g' | 'c' | '+' | '-' | '=' | 'm' | 'F' | 'V' | 'H',
+            ) if !input_is_active && !filter_active && !self.state.borrow().show_tree => {
+                self.handle_layout_key(key.code, ctx);
             }
             KeyCode::Char('f') => {
                 let state = self.state.borrow();
@@ -1355,6 +2407,8 @@ impl QueryWidget {
                     .map(|k| keys_widget::Key {
                         name: k.clone(),
                         hidden: state.item_keys.is_hidden(k),
+                        path: state.item_keys.is_path(k),
+                        time: state.time_formats.get(k).copied(),
                     })
                     .collect::<Vec<_>>();
                 let ctx_for_keys = ctx.clone();
@@ -1370,19 +2424,25 @@ impl QueryWidget {
                                 hidden: false,
                             });
                         }
+                        keys_widget::Event::PathAdded(path) => {
+                            ctx_for_keys.emit_self(PathColumnEvent { path, added: true });
+                        }
+                        keys_widget::Event::PathRemoved(path) => {
+                            ctx_for_keys.emit_self(PathColumnEvent { path, added: false });
+                        }
+                        keys_widget::Event::TimeFormat(name, format) => {
+                            ctx_for_keys.emit_self(TimeFormatEvent { name, format });
+                        }
                     },
                     self.inner.id(),
                 ));
                 ctx.set_popup(popup);
             }
             KeyCode::Char('x') if !input_is_active && !filter_active => {
-                if self.state.borrow().show_tree {
-                    self.show_export_popup(ExportKind::Item, ctx.clone());
-                } else if self.selection_active() {
-                    self.show_export_popup(ExportKind::Selection, ctx.clone());
-                } else {
-                    self.show_export_popup(ExportKind::Results, ctx.clone());
-                }
+                self.export_current_view(ctx.clone());
+            }
+            KeyCode::Char('C') if !input_is_active && !filter_active => {
+                self.show_diff_source(ctx.clone());
             }
             KeyCode::Char(' ')
                 if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
@@ -1408,7 +2468,7 @@ impl QueryWidget {
                 let mut state = self.state.borrow_mut();
                 state.show_tree = !state.show_tree;
                 if state.show_tree {
-                    state.reset_tree_scroll();
+                    state.reset_tree_view();
                 }
             }
             KeyCode::Char('i')
@@ -1416,6 +2476,25 @@ impl QueryWidget {
             {
                 self.show_index_picker(ctx.clone());
             }
+            KeyCode::Char('I')
+                if !input_is_active && !filter_active && self.db.capabilities().index_query =>
+            {
+                self.show_index_scope_picker(ctx.clone());
+            }
+            KeyCode::Char('p') if !input_is_active && !filter_active => {
+                self.toggle_scan_pause(ctx.clone());
+            }
+            KeyCode::Char(key @ ('b' | 'G' | 'A' | '#')) if !input_is_active && !filter_active => {
+                self.handle_query_source_key(key, ctx.clone());
+            }
+            KeyCode::Char(c @ ('w' | 'W')) if !input_is_active && !filter_active => {
+                self.handle_w_key(c, key.modifiers, ctx);
+            }
+            KeyCode::Char(key @ ('o' | 'O'))
+                if !input_is_active && !filter_active && self.db.capabilities().read_options =>
+            {
+                self.handle_options_key(key, ctx.clone());
+            }
             KeyCode::Char('e')
                 if !input_is_active
                     && key
@@ -1436,7 +2515,7 @@ impl QueryWidget {
                         .modifiers
                         .contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                self.create_item(EditorFormat::DynamoDb, ctx.clone());
+                self.pick_item_template(EditorFormat::DynamoDb, ctx.clone());
             }
             KeyCode::Char('d')
                 if !input_is_active
@@ -1461,11 +2540,56 @@ impl QueryWidget {
                     self.confirm_delete(ctx.clone());
                 }
             }
+            KeyCode::Char('D')
+                if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
+            {
+                self.confirm_delete_matching(ctx.clone());
+            }
+            KeyCode::Char('M')
+                if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
+            {
+                self.start_copy_to_table(ctx.clone());
+            }
+            KeyCode::Char(key @ ('u' | 'U')) if !input_is_active && !filter_active => {
+                self.handle_undo_key(key, ctx.clone());
+            }
+            KeyCode::Char('y')
+                if !input_is_active
+                    && !filter_active
+                    && key
+                        .modifiers
+                        .contains(crossterm::event::KeyModifiers::CONTROL) =>
+            {
+                self.copy_query(ctx);
+            }
+            KeyCode::Char('y')
+                if !input_is_active
+                    && !filter_active
+                    && key.modifiers.contains(crossterm::event::KeyModifiers::ALT) =>
+            {
+                self.copy_table_arn(ctx);
+            }
+            KeyCode::Char('y') if !input_is_active && !filter_active => {
+                self.copy_selected_item(ctx);
+            }
+            KeyCode::Char('Y')
+                if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
+            {
+                self.copy_selected_cell(ctx);
+            }
+            KeyCode::Char('s')
+                if !input_is_active && !filter_active && !self.state.borrow().show_tree =>
+            {
+                self.show_column_stats(ctx);
+            }
+            KeyCode::Char('S') if !input_is_active && !filter_active => {
+                self.show_size_breakdown(ctx);
+            }
             KeyCode::Char('n') => {
-                self.create_item(EditorFormat::Plain, ctx.clone());
+                self.pick_item_template(EditorFormat::Plain, ctx.clone());
             }
             KeyCode::Char('N') => {
-                self.create_item(EditorFormat::DynamoDb, ctx.clone());
+                self.pick_item_template(EditorFormat::DynamoDb, ctx.clone());
             }
             _ => {
                 return false; // not handled
@@ -1508,51 +2632,227 @@ impl QueryWidget {
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("z"),
-            short: Cow::Borrowed("compact"),
-            long: Cow::Borrowed("Toggle compact columns"),
+            keys: Cow::Borrowed("</>"),
+            short: Cow::Borrowed("pages"),
+            long: Cow::Borrowed("Previous/next page of results"),
             ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("space/a"),
-            short: Cow::Borrowed("select"),
-            long: Cow::Borrowed("Toggle row/select all query matches"),
+            keys: Cow::Borrowed("g"),
+            short: Cow::Borrowed("go to page"),
+            long: Cow::Borrowed("Jump to a page of results"),
             ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("x"),
-            short: Cow::Borrowed("export"),
-            long: Cow::Borrowed("Export results/selection"),
+            keys: Cow::Borrowed("c"),
+            short: Cow::Borrowed("continue"),
+            long: Cow::Borrowed("Keep loading past the result limit"),
             ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("⏎"),
-            short: Cow::Borrowed("view"),
-            long: Cow::Borrowed("View selected item"),
+            keys: Cow::Borrowed("+/-"),
+            short: Cow::Borrowed("width"),
+            long: Cow::Borrowed("Widen/narrow the first visible column (= resets it)"),
             ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("i"),
-            short: Cow::Borrowed("indexes"),
-            long: Cow::Borrowed("Query by index PK"),
+            keys: Cow::Borrowed("z"),
+            short: Cow::Borrowed("compact"),
+            long: Cow::Borrowed("Toggle compact columns"),
             ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("e"),
-            short: Cow::Borrowed("edit"),
-            long: Cow::Borrowed("Edit item (JSON)"),
-            ctrl: Some(help::Variant {
-                keys: Some(Cow::Borrowed("^e")),
+            keys: Cow::Borrowed("P"),
+            short: Cow::Borrowed("preview"),
+            long: Cow::Borrowed("Toggle the selected item's tree beside the results"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("m"),
+            short: Cow::Borrowed("project"),
+            long: Cow::Borrowed("Reshape rows with attribute paths (e.g. address.city)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("V"),
+            short: Cow::Borrowed("entities"),
+            long: Cow::Borrowed("Show one entity's rows, or group rows by entity"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("H"),
+            short: Cow::Borrowed("join"),
+            long: Cow::Borrowed("Show attributes of the items the rows refer to"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("s"),
+            short: Cow::Borrowed("stats"),
+            long: Cow::Borrowed("Stats and value counts of first visible column"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("space/a"),
+            short: Cow::Borrowed("select"),
+            long: Cow::Borrowed("Toggle row/select all query matches"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("S"),
+            short: Cow::Borrowed("size"),
+            long: Cow::Borrowed("Item size by attribute, against the 400 KB limit"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("x"),
+            short: Cow::Borrowed("export"),
+            long: Cow::Borrowed("Export results/selection"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("C"),
+            short: Cow::Borrowed("compare"),
+            long: Cow::Borrowed("Compare results with an export or another table"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("y"),
+            short: Cow::Borrowed("copy"),
+            long: Cow::Borrowed("Copy item (JSON)"),
+            ctrl: Some(help::Variant {
+                keys: Some(Cow::Borrowed("^y")),
+                short: Some(Cow::Borrowed("copy query")),
+                long: Some(Cow::Borrowed("Copy current query")),
+            }),
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("Y")),
+                short: Some(Cow::Borrowed("copy cell")),
+                long: Some(Cow::Borrowed("Copy value in the first visible column")),
+            }),
+            alt: Some(help::Variant {
+                keys: Some(Cow::Borrowed("M-y")),
+                short: Some(Cow::Borrowed("copy ARN")),
+                long: Some(Cow::Borrowed("Copy table ARN")),
+            }),
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("view"),
+            long: Cow::Borrowed("View selected item"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("i"),
+            short: Cow::Borrowed("indexes"),
+            long: Cow::Borrowed("Query by index PK"),
+            ctrl: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("I")),
+                short: Some(Cow::Borrowed("browse index")),
+                long: Some(Cow::Borrowed("Browse the table or a secondary index")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("b"),
+            short: Cow::Borrowed("builder"),
+            long: Cow::Borrowed("Build a query from conditions"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("A"),
+            short: Cow::Borrowed("patterns"),
+            long: Cow::Borrowed("Run or save the table's access patterns"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("#"),
+            short: Cow::Borrowed("count"),
+            long: Cow::Borrowed("Count the query's matches without fetching them"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("G"),
+            short: Cow::Borrowed("get keys"),
+            long: Cow::Borrowed("Fetch items for a list of keys (file or inline)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("w"),
+            short: Cow::Borrowed("stage"),
+            long: Cow::Borrowed("Stage edits and deletes for a transaction (on/off)"),
+            ctrl: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("W")),
+                short: Some(Cow::Borrowed("transaction")),
+                long: Some(Cow::Borrowed("Review and commit the staged writes")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("^w"),
+            short: Cow::Borrowed("watch"),
+            long: Cow::Borrowed("Re-run the query on an interval, marking changed rows (on/off)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("o"),
+            short: Cow::Borrowed("options"),
+            long: Cow::Borrowed("Consistent read, projection, and order"),
+            ctrl: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("O")),
+                short: Some(Cow::Borrowed("flip order")),
+                long: Some(Cow::Borrowed("Reverse sort-key order and re-run")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("e"),
+            short: Cow::Borrowed("edit"),
+            long: Cow::Borrowed("Edit item (JSON)"),
+            ctrl: Some(help::Variant {
+                keys: Some(Cow::Borrowed("^e")),
                 short: Some(Cow::Borrowed("edit (Dynamo JSON)")),
                 long: Some(Cow::Borrowed("Edit item (Dynamo JSON)")),
             }),
@@ -1562,11 +2862,11 @@ impl QueryWidget {
         help::Entry {
             keys: Cow::Borrowed("n"),
             short: Cow::Borrowed("new"),
-            long: Cow::Borrowed("New item"),
+            long: Cow::Borrowed("New item from a template"),
             ctrl: Some(help::Variant {
                 keys: Some(Cow::Borrowed("^n")),
                 short: Some(Cow::Borrowed("new (Dynamo JSON)")),
-                long: Some(Cow::Borrowed("New item (Dynamo JSON)")),
+                long: Some(Cow::Borrowed("New item from a template (Dynamo JSON)")),
             }),
             shift: None,
             alt: None,
@@ -1580,6 +2880,34 @@ impl QueryWidget {
                 short: Some(Cow::Borrowed("delete")),
                 long: Some(Cow::Borrowed("Delete item/selection")),
             }),
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("D")),
+                short: Some(Cow::Borrowed("delete matching")),
+                long: Some(Cow::Borrowed("Delete every item the query matches")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("u"),
+            short: Cow::Borrowed("undo delete"),
+            long: Cow::Borrowed("Restore the most recently deleted item"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("U"),
+            short: Cow::Borrowed("update matching"),
+            long: Cow::Borrowed("Update every item the query matches"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("M"),
+            short: Cow::Borrowed("copy to table"),
+            long: Cow::Borrowed("Copy or move the item/selection to another table"),
+            ctrl: None,
             shift: None,
             alt: None,
         },
@@ -1645,6 +2973,14 @@ impl QueryWidget {
             shift: None,
             alt: None,
         },
+        help::Entry {
+            keys: Cow::Borrowed("M"),
+            short: Cow::Borrowed("copy to table"),
+            long: Cow::Borrowed("Copy or move the selection to another table"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
     ];
     const HELP_FILTER_EDIT: &'static [help::Entry<'static>] = &[
         help::Entry {
@@ -1705,6 +3041,22 @@ impl QueryWidget {
             shift: None,
             alt: None,
         },
+        help::Entry {
+            keys: Cow::Borrowed("^p"),
+            short: Cow::Borrowed("partiql"),
+            long: Cow::Borrowed("Toggle PartiQL statement mode"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("M-e"),
+            short: Cow::Borrowed("editor"),
+            long: Cow::Borrowed("Edit the query in $EDITOR"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
     ];
     const HELP_FILTER_APPLIED: &'static [help::Entry<'static>] = &[
         help::Entry {
@@ -1731,6 +3083,14 @@ impl QueryWidget {
             shift: None,
             alt: None,
         },
+        help::Entry {
+            keys: Cow::Borrowed("F"),
+            short: Cow::Borrowed("server filter"),
+            long: Cow::Borrowed("Rerun the query with the field:text filter terms in it"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
         help::Entry {
             keys: Cow::Borrowed("f"),
             short: Cow::Borrowed("fields"),
@@ -1747,6 +3107,38 @@ impl QueryWidget {
             shift: None,
             alt: None,
         },
+        help::Entry {
+            keys: Cow::Borrowed("</>"),
+            short: Cow::Borrowed("pages"),
+            long: Cow::Borrowed("Previous/next page of results"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("g"),
+            short: Cow::Borrowed("go to page"),
+            long: Cow::Borrowed("Jump to a page of results"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("c"),
+            short: Cow::Borrowed("continue"),
+            long: Cow::Borrowed("Keep loading past the result limit"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("+/-"),
+            short: Cow::Borrowed("width"),
+            long: Cow::Borrowed("Widen/narrow the first visible column (= resets it)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
         help::Entry {
             keys: Cow::Borrowed("z"),
             short: Cow::Borrowed("compact"),
@@ -1755,6 +3147,22 @@ impl QueryWidget {
             shift: None,
             alt: None,
         },
+        help::Entry {
+            keys: Cow::Borrowed("P"),
+            short: Cow::Borrowed("preview"),
+            long: Cow::Borrowed("Toggle the selected item's tree beside the results"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("s"),
+            short: Cow::Borrowed("stats"),
+            long: Cow::Borrowed("Stats and value counts of first visible column"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
         help::Entry {
             keys: Cow::Borrowed("space/a"),
             short: Cow::Borrowed("select"),
@@ -1771,6 +3179,14 @@ impl QueryWidget {
             shift: None,
             alt: None,
         },
+        help::Entry {
+            keys: Cow::Borrowed("C"),
+            short: Cow::Borrowed("compare"),
+            long: Cow::Borrowed("Compare results with an export or another table"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
         help::Entry {
             keys: Cow::Borrowed("⏎"),
             short: Cow::Borrowed("view"),
@@ -1784,85 +3200,75 @@ impl QueryWidget {
             short: Cow::Borrowed("indexes"),
             long: Cow::Borrowed("Query by index PK"),
             ctrl: None,
-            shift: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("I")),
+                short: Some(Cow::Borrowed("browse index")),
+                long: Some(Cow::Borrowed("Browse the table or a secondary index")),
+            }),
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("e"),
-            short: Cow::Borrowed("edit"),
-            long: Cow::Borrowed("Edit item (JSON)"),
-            ctrl: Some(help::Variant {
-                keys: Some(Cow::Borrowed("^e")),
-                short: Some(Cow::Borrowed("edit (Dynamo JSON)")),
-                long: Some(Cow::Borrowed("Edit item (Dynamo JSON)")),
-            }),
+            keys: Cow::Borrowed("b"),
+            short: Cow::Borrowed("builder"),
+            long: Cow::Borrowed("Build a query from conditions"),
+            ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("n"),
-            short: Cow::Borrowed("new"),
-            long: Cow::Borrowed("New item"),
-            ctrl: Some(help::Variant {
-                keys: Some(Cow::Borrowed("^n")),
-                short: Some(Cow::Borrowed("new (Dynamo JSON)")),
-                long: Some(Cow::Borrowed("New item (Dynamo JSON)")),
-            }),
+            keys: Cow::Borrowed("A"),
+            short: Cow::Borrowed("patterns"),
+            long: Cow::Borrowed("Run or save the table's access patterns"),
+            ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("^d"),
-            short: Cow::Borrowed("delete"),
-            long: Cow::Borrowed("Delete item/selection"),
+            keys: Cow::Borrowed("#"),
+            short: Cow::Borrowed("count"),
+            long: Cow::Borrowed("Count the query's matches without fetching them"),
             ctrl: None,
             shift: None,
             alt: None,
         },
-    ];
-    const HELP_LOADING: &'static [help::Entry<'static>] = &[help::Entry {
-        keys: Cow::Borrowed("esc"),
-        short: Cow::Borrowed("cancel"),
-        long: Cow::Borrowed("Cancel request"),
-        ctrl: None,
-        shift: None,
-        alt: None,
-    }];
-    const HELP_TREE: &'static [help::Entry<'static>] = &[
         help::Entry {
-            keys: Cow::Borrowed("j/k/↑/↓"),
-            short: Cow::Borrowed("scroll"),
-            long: Cow::Borrowed("Scroll item"),
+            keys: Cow::Borrowed("G"),
+            short: Cow::Borrowed("get keys"),
+            long: Cow::Borrowed("Fetch items for a list of keys (file or inline)"),
             ctrl: None,
-            shift: Some(help::Variant {
-                keys: Some(Cow::Borrowed("J/K")),
-                short: Some(Cow::Borrowed("next/prev")),
-                long: Some(Cow::Borrowed("Next/previous item")),
-            }),
+            shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("PgUp/PgDn"),
-            short: Cow::Borrowed("page"),
-            long: Cow::Borrowed("Page through item"),
+            keys: Cow::Borrowed("w"),
+            short: Cow::Borrowed("stage"),
+            long: Cow::Borrowed("Stage edits and deletes for a transaction (on/off)"),
             ctrl: None,
-            shift: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("W")),
+                short: Some(Cow::Borrowed("transaction")),
+                long: Some(Cow::Borrowed("Review and commit the staged writes")),
+            }),
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("x"),
-            short: Cow::Borrowed("export"),
-            long: Cow::Borrowed("Export"),
+            keys: Cow::Borrowed("^w"),
+            short: Cow::Borrowed("watch"),
+            long: Cow::Borrowed("Re-run the query on an interval, marking changed rows (on/off)"),
             ctrl: None,
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("i"),
-            short: Cow::Borrowed("indexes"),
-            long: Cow::Borrowed("Query by index PK"),
+            keys: Cow::Borrowed("o"),
+            short: Cow::Borrowed("options"),
+            long: Cow::Borrowed("Consistent read, projection, and order"),
             ctrl: None,
-            shift: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("O")),
+                short: Some(Cow::Borrowed("flip order")),
+                long: Some(Cow::Borrowed("Reverse sort-key order and re-run")),
+            }),
             alt: None,
         },
         help::Entry {
@@ -1878,21 +3284,245 @@ impl QueryWidget {
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed(""),
-            short: Cow::Borrowed(""),
-            long: Cow::Borrowed(""),
+            keys: Cow::Borrowed("n"),
+            short: Cow::Borrowed("new"),
+            long: Cow::Borrowed("New item from a template"),
             ctrl: Some(help::Variant {
-                keys: Some(Cow::Borrowed("^d")),
-                short: Some(Cow::Borrowed("delete")),
-                long: Some(Cow::Borrowed("Delete item")),
+                keys: Some(Cow::Borrowed("^n")),
+                short: Some(Cow::Borrowed("new (Dynamo JSON)")),
+                long: Some(Cow::Borrowed("New item from a template (Dynamo JSON)")),
             }),
             shift: None,
             alt: None,
         },
         help::Entry {
-            keys: Cow::Borrowed("esc"),
-            short: Cow::Borrowed("back"),
-            long: Cow::Borrowed("Back to results"),
+            keys: Cow::Borrowed("^d"),
+            short: Cow::Borrowed("delete"),
+            long: Cow::Borrowed("Delete item/selection"),
+            ctrl: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("D")),
+                short: Some(Cow::Borrowed("delete matching")),
+                long: Some(Cow::Borrowed("Delete every item the query matches")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("u"),
+            short: Cow::Borrowed("undo delete"),
+            long: Cow::Borrowed("Restore the most recently deleted item"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("U"),
+            short: Cow::Borrowed("update matching"),
+            long: Cow::Borrowed("Update every item the query matches"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("M"),
+            short: Cow::Borrowed("copy to table"),
+            long: Cow::Borrowed("Copy or move the item/selection to another table"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+    const HELP_LOADING: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("p"),
+            short: Cow::Borrowed("pause"),
+            long: Cow::Borrowed("Pause or resume the scan"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("cancel"),
+            long: Cow::Borrowed("Cancel request"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+    const HELP_TREE: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("j/k/↑/↓"),
+            short: Cow::Borrowed("move"),
+            long: Cow::Borrowed("Move through the item"),
+            ctrl: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("J/K")),
+                short: Some(Cow::Borrowed("next/prev")),
+                long: Some(Cow::Borrowed("Next/previous item")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("PgUp/PgDn"),
+            short: Cow::Borrowed("page"),
+            long: Cow::Borrowed("Page through item"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("←/→/⏎"),
+            short: Cow::Borrowed("fold"),
+            long: Cow::Borrowed("Collapse/expand/toggle a map or list"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("follow"),
+            long: Cow::Borrowed("Open the item a reference attribute names (follow rules)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("/"),
+            short: Cow::Borrowed("search"),
+            long: Cow::Borrowed("Search keys and values (n/N: next/previous match)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("S"),
+            short: Cow::Borrowed("size"),
+            long: Cow::Borrowed("Item size by attribute, against the 400 KB limit"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("x"),
+            short: Cow::Borrowed("export"),
+            long: Cow::Borrowed("Export"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("y"),
+            short: Cow::Borrowed("copy"),
+            long: Cow::Borrowed("Copy item (JSON)"),
+            ctrl: Some(help::Variant {
+                keys: Some(Cow::Borrowed("^y")),
+                short: Some(Cow::Borrowed("copy query")),
+                long: Some(Cow::Borrowed("Copy current query")),
+            }),
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("Y")),
+                short: Some(Cow::Borrowed("copy value")),
+                long: Some(Cow::Borrowed("Copy the value under the cursor")),
+            }),
+            alt: Some(help::Variant {
+                keys: Some(Cow::Borrowed("M-y")),
+                short: Some(Cow::Borrowed("copy ARN")),
+                long: Some(Cow::Borrowed("Copy table ARN")),
+            }),
+        },
+        help::Entry {
+            keys: Cow::Borrowed("T"),
+            short: Cow::Borrowed("convert"),
+            long: Cow::Borrowed("Convert the attribute's type (S↔N, L↔SS, epoch↔ISO)"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("c"),
+            short: Cow::Borrowed("copy path"),
+            long: Cow::Borrowed("Copy the document path under the cursor, e.g. orders[2].sku"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("i"),
+            short: Cow::Borrowed("indexes"),
+            long: Cow::Borrowed("Query by index PK"),
+            ctrl: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("I")),
+                short: Some(Cow::Borrowed("browse index")),
+                long: Some(Cow::Borrowed("Browse the table or a secondary index")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("b"),
+            short: Cow::Borrowed("builder"),
+            long: Cow::Borrowed("Build a query from conditions"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("A"),
+            short: Cow::Borrowed("patterns"),
+            long: Cow::Borrowed("Run or save the table's access patterns"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("#"),
+            short: Cow::Borrowed("count"),
+            long: Cow::Borrowed("Count the query's matches without fetching them"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("o"),
+            short: Cow::Borrowed("options"),
+            long: Cow::Borrowed("Consistent read, projection, and order"),
+            ctrl: None,
+            shift: Some(help::Variant {
+                keys: Some(Cow::Borrowed("O")),
+                short: Some(Cow::Borrowed("flip order")),
+                long: Some(Cow::Borrowed("Reverse sort-key order and re-run")),
+            }),
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("e"),
+            short: Cow::Borrowed("edit"),
+            long: Cow::Borrowed("Edit item (JSON)"),
+            ctrl: Some(help::Variant {
+                keys: Some(Cow::Borrowed("^e")),
+                short: Some(Cow::Borrowed("edit (Dynamo JSON)")),
+                long: Some(Cow::Borrowed("Edit item (Dynamo JSON)")),
+            }),
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed(""),
+            short: Cow::Borrowed(""),
+            long: Cow::Borrowed(""),
+            ctrl: Some(help::Variant {
+                keys: Some(Cow::Borrowed("^d")),
+                short: Some(Cow::Borrowed("delete")),
+                long: Some(Cow::Borrowed("Delete item")),
+            }),
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("back"),
+            long: Cow::Borrowed("Back to results"),
             ctrl: None,
             shift: None,
             alt: None,
@@ -1921,6 +3551,16 @@ impl QueryWidget {
         let mut widget = Self::new_with_query(db, "SQL", parent, None);
         widget.raw_sql = true;
         widget.state.get_mut().raw = true;
+        widget.state.get_mut().statements = false;
+        widget.help_query_edit = query_edit_help(Self::HELP_QUERY_EDIT, false);
+        for help in [&mut widget.help_table, &mut widget.help_filter_applied] {
+            help.retain(|entry| {
+                !matches!(
+                    entry.short.as_ref(),
+                    "builder" | "update matching" | "get keys" | "stage" | "server filter"
+                )
+            });
+        }
         widget
     }
 
@@ -1934,25 +3574,58 @@ impl QueryWidget {
             .and_then(|value| i32::try_from(value).ok())
             .filter(|value| *value > 0)
             .unwrap_or(100);
-        let index_query = db.capabilities().index_query;
+        let max_loaded_items = env_u64("DYNAMATE_MAX_LOADED_ITEMS")
+            .and_then(|value| usize::try_from(value).ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(DEFAULT_MAX_LOADED_ITEMS);
+        let scan_segments = crate::util::scan_segments();
+        let (max_items, max_pages) = crate::util::query_limit();
+        let capabilities = db.capabilities().clone();
+        let statements = capabilities.statements;
         Self {
             inner: WidgetInner::new::<Self>(parent),
             db,
             table_name: table_name.to_string(),
             initial_query,
-            state: RefCell::new(QueryState::default()),
+            state: RefCell::new(QueryState {
+                statements,
+                options: QueryOptions {
+                    max_items,
+                    max_pages,
+                    ..QueryOptions::default()
+                },
+                column_widths: ColumnWidths::for_table(table_name),
+                entities: Entities::for_table(table_name),
+                ..QueryState::default()
+            }),
             table_meta: RefCell::new(None),
             meta_started: Cell::new(false),
-            request_seq: Cell::new(0),
-            export_seq: Cell::new(0),
+            requests: RequestManager::default(),
+            exports: RequestManager::default(),
+            bulk: RequestManager::default(),
             page_size,
+            max_loaded_items,
+            scan_segments,
             raw_sql: false,
-            help_table: browse_help(Self::HELP_TABLE, index_query),
-            help_filter_applied: browse_help(Self::HELP_FILTER_APPLIED, index_query),
-            help_tree: browse_help(Self::HELP_TREE, index_query),
+            help_table: browse_help(Self::HELP_TABLE, &capabilities),
+            help_filter_applied: browse_help(Self::HELP_FILTER_APPLIED, &capabilities),
+            help_tree: browse_help(Self::HELP_TREE, &capabilities),
+            help_query_edit: query_edit_help(Self::HELP_QUERY_EDIT, statements),
+            clicks: ClickTracker::default(),
+            watch_interval: watch::DEFAULT_INTERVAL,
+            watch_on_start: false,
+            watch_seq: Cell::new(0),
         }
     }
 
+    /// Re-run the query every `interval` from the start.
+    #[must_use]
+    pub fn with_watch(mut self, interval: Duration) -> Self {
+        self.watch_interval = interval;
+        self.watch_on_start = true;
+        self
+    }
+
     /// The query language for the active input — the backend's free-form SQL
     /// language in raw mode, else its per-collection filter language.
     fn input_language(&self) -> &dyn QueryLanguage {
@@ -2103,6 +3776,7 @@ impl QueryWidget {
             SelectionMode::Explicit(keys) => Some(format!("selected {}", keys.len())),
             SelectionMode::Query { excluded } => {
                 if state.last_evaluated_key.is_none()
+                    && state.pages.first_loaded() == 1
                     && matches!(
                         state.loading_state,
                         LoadingState::Idle | LoadingState::Loaded
@@ -2216,13 +3890,22 @@ impl QueryWidget {
     /// One-line feedback shown under the query box while editing: a placeholder
     /// when empty, otherwise whether the query is valid and how it will run —
     /// all delegated to the backend's query language.
-    fn query_hint_line(&self, value: &str, error: Option<&str>, theme: &Theme) -> Line<'static> {
+    fn query_hint_line(
+        &self,
+        value: &str,
+        partiql: bool,
+        error: Option<&str>,
+        theme: &Theme,
+    ) -> Line<'static> {
         if let Some(error) = error {
             return Line::from(Span::styled(
                 format!("  ✖ {}", error.replace('\n', " ")),
                 Style::default().fg(theme.error()),
             ));
         }
+        if partiql {
+            return partiql_hint_line(value, theme);
+        }
         let language = self.input_language();
         let meta = self.table_meta.borrow();
         // The per-table view waits for table metadata; the raw SQL view has no
@@ -2278,6 +3961,29 @@ impl QueryWidget {
                     Span::styled(label, Style::default().fg(theme.success())),
                 ])
             }
+            QueryStatus::Valid {
+                plan_kind: PlanKind::Lookup,
+            } => Line::from(vec![
+                Span::styled("  ✓ ".to_string(), Style::default().fg(theme.success())),
+                Span::styled("GetItem".to_string(), Style::default().fg(theme.success())),
+                Span::styled(
+                    " — fetches a single item by its key".to_string(),
+                    Style::default().fg(theme.text_muted()),
+                ),
+            ]),
+            // Key lists come from the get-by-keys popup, never typed text.
+            QueryStatus::Valid {
+                plan_kind: PlanKind::BatchLookup,
+            } => Line::from(vec![
+                Span::styled("  ✓ ".to_string(), Style::default().fg(theme.success())),
+                Span::styled(
+                    "BatchGetItem".to_string(),
+                    Style::default().fg(theme.success()),
+                ),
+            ]),
+            QueryStatus::Valid {
+                plan_kind: PlanKind::Statement,
+            } => partiql_hint_line(value, theme),
         }
     }
 
@@ -2357,6 +4063,40 @@ impl QueryWidget {
         }
     }
 
+    /// Rerun the query with the `/` filter in it (`F`), so the pages still to
+    /// come are filtered by the server rather than fetched whole.
+    fn filter_on_server(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let (query, conditions) = {
+            let state = self.state.borrow();
+            let ActiveQuery::Text(query) = &state.active_query else {
+                return;
+            };
+            if state.row_filter.is_empty() {
+                return;
+            }
+            (query.clone(), state.row_filter.conditions())
+        };
+        let narrowed = conditions
+            .and_then(|conditions| self.input_language().narrow_query(&query, &conditions));
+        let query = match narrowed {
+            Ok(query) => query,
+            Err(err) => {
+                self.show_error(ctx, &err);
+                return;
+            }
+        };
+        {
+            let mut state = self.state.borrow_mut();
+            state.filter.clear();
+            state.filter.set_active(false);
+            state.apply_filter();
+        }
+        self.start_query(Some(&query), ctx);
+    }
+
     fn show_error(&self, ctx: crate::env::WidgetCtx, message: &str) {
         let is_empty = self.state.borrow().items.is_empty();
         if is_empty {
@@ -2389,7 +4129,7 @@ impl QueryWidget {
         let message = target.summary;
         let key = target.key;
         let ctx_for_delete = ctx.clone();
-        let popup = Box::new(ConfirmPopup::new(
+        ConfirmPopup::new(
             "Delete item",
             message,
             "Delete",
@@ -2398,8 +4138,8 @@ impl QueryWidget {
                 ctx_for_delete.emit_self(DeleteItemRequest { key: key.clone() });
             },
             self.inner.id(),
-        ));
-        ctx.set_popup(popup);
+        )
+        .show(&ctx, Action::DeleteItems, &self.table_name);
     }
 
     fn confirm_delete_selection(&self, ctx: crate::env::WidgetCtx) {
@@ -2416,7 +4156,7 @@ impl QueryWidget {
         };
         let message = self.selection_summary(&selection);
         let ctx_for_delete = ctx.clone();
-        let popup = Box::new(ConfirmPopup::new_with_action(
+        ConfirmPopup::new_with_action(
             "Delete selection",
             message,
             "Delete",
@@ -2434,83 +4174,893 @@ impl QueryWidget {
                 });
             },
             self.inner.id(),
-        ));
-        ctx.set_popup(popup);
+        )
+        .show(&ctx, Action::DeleteItems, &self.table_name);
     }
 
-    fn show_index_picker(&self, ctx: crate::env::WidgetCtx) {
+    /// `D` asks to delete every item the active query matches, or to count
+    /// them in a dry run.
+    fn confirm_delete_matching(&self, ctx: crate::env::WidgetCtx) {
         if self.raw_sql {
             return;
         }
-        let targets = match self.index_targets() {
-            Ok(targets) if targets.is_empty() => {
-                ctx.show_toast(Toast {
-                    message: "No indexes available for this item".to_string(),
-                    kind: ToastKind::Info,
-                    duration: Duration::from_secs(3),
-                    action: None,
-                });
-                return;
-            }
-            Ok(targets) => targets,
-            Err(err) => {
-                self.show_error(ctx.clone(), &err);
-                return;
-            }
-        };
-        let ctx_for_select = ctx.clone();
-        let popup = Box::new(index_picker::IndexPicker::new(
-            targets,
-            move |target| {
-                ctx_for_select.emit_self(IndexQueryEvent { target });
-            },
-            self.inner.id(),
-        ));
-        ctx.set_popup(popup);
-    }
-
-    fn show_export_popup(&self, mode: ExportKind, ctx: crate::env::WidgetCtx) {
-        if matches!(mode, ExportKind::Item) && self.selected_item().is_err() {
-            self.show_error(ctx.clone(), "No item selected");
+        if !self.db.capabilities().purge {
+            self.show_error(
+                ctx.clone(),
+                "Deleting matching items isn't supported by this backend",
+            );
             return;
         }
-        if matches!(mode, ExportKind::Selection) && !self.selection_active() {
-            self.show_error(ctx.clone(), "No items selected");
+        let Some(query) = self.bulk_query_label(&ctx) else {
             return;
+        };
+        delete_matching::prompt(query, self.inner.id(), &ctx);
+    }
+
+    fn delete_source(&self) -> delete_matching::DeleteSource<'_> {
+        delete_matching::DeleteSource {
+            db: self.db.clone(),
+            table_name: self.table_name.clone(),
+            plan: self.active_plan(),
+            segments: self.parallel_segments().unwrap_or(1),
+            bulk: &self.bulk,
+            parent: self.inner.id(),
         }
-        let path = self.export_path(mode);
-        let option_label = matches!(mode, ExportKind::Results)
-            .then_some(Cow::Borrowed("Fetch all results before exporting"));
-        let ctx_for_confirm = ctx.clone();
-        let popup = Box::new(ExportPopup::new(
-            path,
-            option_label,
-            false,
-            move |path, fetch_all| {
-                ctx_for_confirm.emit_self(ExportRequest {
-                    mode,
-                    path,
-                    fetch_all,
-                    overwrite_confirmed: false,
-                });
-            },
-            self.inner.id(),
-        ));
-        ctx.set_popup(popup);
     }
 
-    fn show_export_progress_toast(&self, ctx: crate::env::WidgetCtx, count: usize) {
-        let message = format!(
-            "Exporting... {} item{}",
-            count,
-            if count == 1 { "" } else { "s" }
-        );
-        ctx.show_toast(Toast {
-            message,
+    /// What a bulk action on the active query would cover, for its prompt.
+    /// `None`, after telling the user why, when another bulk action is still
+    /// running or the query is a statement.
+    fn bulk_query_label(&self, ctx: &crate::env::WidgetCtx) -> Option<String> {
+        let state = self.state.borrow();
+        if self.bulk.is_busy() {
+            drop(state);
+            self.show_error(ctx.clone(), "Another bulk change is still running");
+            return None;
+        }
+        if let ActiveQuery::Text(query) = &state.active_query
+            && state.statements
+            && (state.partiql || partiql::is_statement(query))
+        {
+            drop(state);
+            self.show_error(ctx.clone(), "Bulk changes work on queries, not statements");
+            return None;
+        }
+        if matches!(state.active_query, ActiveQuery::Keys(_)) {
+            drop(state);
+            self.show_error(
+                ctx.clone(),
+                "Bulk changes work on queries; select the fetched items instead",
+            );
+            return None;
+        }
+        Some(
+            state
+                .active_query
+                .input_value()
+                .filter(|query| !query.trim().is_empty())
+                .unwrap_or_else(|| "every item (full scan)".to_string()),
+        )
+    }
+
+    /// The active query as a plan, with its parameters and options.
+    fn active_plan(&self) -> QueryPlan {
+        let state = self.state.borrow();
+        plan_for_active_query(&state.active_query, &state.parameters, &self.read_options())
+    }
+
+    /// `#` counts what the active query matches across every page, reading
+    /// only the counts, and reports it in a popup.
+    fn count_active_matches(&self, ctx: crate::env::WidgetCtx) {
+        let query = {
+            let state = self.state.borrow();
+            let statement = matches!(&state.active_query, ActiveQuery::Text(query)
+                if state.statements && (state.partiql || partiql::is_statement(query)));
+            let error = if self.raw_sql || statement {
+                Some("Counting works on queries, not statements")
+            } else if matches!(state.active_query, ActiveQuery::Keys(_)) {
+                Some("The fetched keys are all loaded; there is nothing to count")
+            } else if self.bulk.is_busy() {
+                Some("Another bulk operation is still running")
+            } else {
+                None
+            };
+            if let Some(error) = error {
+                drop(state);
+                self.show_error(ctx, error);
+                return;
+            }
+            state
+                .active_query
+                .input_value()
+                .filter(|query| !query.trim().is_empty())
+                .unwrap_or_else(|| "every item (full scan)".to_string())
+        };
+        let plan = self.active_plan();
+        let run = self.bulk.start();
+        let job = jobs::track(
+            format!("Count matches in {}", self.table_name),
+            run.shared_flag(),
+        );
+        job.progress(count_matches_progress(CountSummary::default()));
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        tokio::spawn(async move {
+            let on_progress = |summary: CountSummary| {
+                job.progress(count_matches_progress(summary));
+            };
+            let result = count_matches(db.as_ref(), &table_name, &plan, run.flag(), &on_progress)
+                .await
+                .map_err(|err| err.to_string());
+            job.finish_quietly(
+                result
+                    .as_ref()
+                    .map(|summary| format!("{} items match", summary.matched))
+                    .map_err(Clone::clone),
+            );
+            ctx.emit_self(CountMatchesEvent {
+                run_id: run.id(),
+                query,
+                result,
+            });
+        });
+    }
+
+    /// The outcome of a count-only run. Returns `true` when `event` was it.
+    fn handle_count_matches_event(
+        &self,
+        ctx: &crate::env::WidgetCtx,
+        event: &crate::env::AppEvent,
+    ) -> bool {
+        let Some(finished) = event.payload::<CountMatchesEvent>() else {
+            return false;
+        };
+        self.bulk.finish(finished.run_id);
+        match &finished.result {
+            Ok(summary) => ctx.set_popup(Box::new(CountSummaryPopup::new(
+                finished.query.clone(),
+                *summary,
+                self.inner.id(),
+            ))),
+            Err(err) => self.show_error(ctx.clone(), err),
+        }
+        true
+    }
+
+    /// `U` asks for an update to apply to every item the active query
+    /// matches.
+    fn prompt_update_matching(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        if !self.db.capabilities().partial_update
+            || self.db.query_language().update_placeholder().is_none()
+        {
+            self.show_error(
+                ctx.clone(),
+                "Updating matching items isn't supported by this backend",
+            );
+            return;
+        }
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let Some(query) = self.bulk_query_label(&ctx) else {
+            return;
+        };
+        bulk_update::prompt(self.db.clone(), query, self.inner.id(), &ctx);
+    }
+
+    fn update_source(&self) -> bulk_update::UpdateSource<'_> {
+        bulk_update::UpdateSource {
+            db: self.db.clone(),
+            table_name: self.table_name.clone(),
+            plan: self.active_plan(),
+            bulk: &self.bulk,
+            parent: self.inner.id(),
+        }
+    }
+
+    fn on_update_step(&self, step: bulk_update::UpdateStep, ctx: &crate::env::WidgetCtx) {
+        match step {
+            bulk_update::UpdateStep::Continuing => {}
+            bulk_update::UpdateStep::Failed(err) => self.show_error(ctx.clone(), &err),
+            bulk_update::UpdateStep::Updated(updated) => {
+                if updated > 0 {
+                    let active_query = self.state.borrow().active_query.clone();
+                    self.restart_query(active_query, ctx.clone(), None);
+                }
+            }
+        }
+    }
+
+    /// `o` edits the query options; `O` flips the sort order.
+    fn handle_options_key(&self, key: char, ctx: crate::env::WidgetCtx) {
+        if key == 'O' {
+            self.flip_sort_order(ctx);
+        } else {
+            self.show_options_popup(ctx);
+        }
+    }
+
+    fn show_options_popup(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let options = self.state.borrow().options.clone();
+        let ctx_for_apply = ctx.clone();
+        let popup = Box::new(OptionsPopup::new(
+            &options,
+            move |options| {
+                ctx_for_apply.emit_self(QueryOptionsEvent { options });
+            },
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    /// `u` puts back the most recently deleted item; `U` updates every item
+    /// the query matches.
+    fn handle_undo_key(&self, key: char, ctx: crate::env::WidgetCtx) {
+        if key == 'U' {
+            if !self.state.borrow().show_tree {
+                self.prompt_update_matching(ctx);
+            }
+            return;
+        }
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let ctx_for_done = ctx.clone();
+        let started = undo::restore_last(self.db.clone(), move |restored| {
+            ctx_for_done.show_toast(restored.toast());
+            ctx_for_done.broadcast_event(restored);
+        });
+        if !started {
+            ctx.show_toast(Toast {
+                message: "No deleted item to restore".to_string(),
+                kind: ToastKind::Warning,
+                duration: Duration::from_secs(3),
+                action: None,
+            });
+        }
+    }
+
+    /// `w` turns staging writes for a transaction on or off; `W` opens the
+    /// staged transaction for review.
+    /// `^w` toggles watch mode; `w` and `W` work the transaction.
+    fn handle_w_key(&self, key: char, modifiers: KeyModifiers, ctx: &crate::env::WidgetCtx) {
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            self.toggle_watch(ctx);
+        } else {
+            self.handle_transaction_key(key, ctx.clone());
+        }
+    }
+
+    fn handle_transaction_key(&self, key: char, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql || !self.db.capabilities().transactions {
+            return;
+        }
+        if key == 'W' {
+            ctx.push_widget(Box::new(TransactionWidget::new(
+                self.db.clone(),
+                self.inner.id(),
+            )));
+            return;
+        }
+        let message = if transaction::toggle_staging() {
+            "Staging writes: edits, new items, and deletes join the transaction (W reviews)"
+        } else {
+            "Writing directly; staged writes wait in the transaction (W reviews)"
+        };
+        ctx.show_toast(Toast {
+            message: message.to_string(),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(3),
+            action: None,
+        });
+    }
+
+    /// Hold `write` in the transaction instead of applying it. `key_source`
+    /// is the item, or at least its key attributes.
+    fn stage_write(
+        &self,
+        key_source: &HashMap<String, AttributeValue>,
+        write: TransactWrite,
+        ctx: &crate::env::WidgetCtx,
+    ) {
+        let label = write.label();
+        let staged = self
+            .schema()
+            .and_then(|schema| ItemKey::from_item(key_source, &schema))
+            .and_then(|key| transaction::stage(write, key.summary_line()));
+        match staged {
+            Ok(count) => ctx.show_toast(Toast {
+                message: format!("Staged {label} · {count} in the transaction"),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(2),
+                action: None,
+            }),
+            Err(err) => self.show_error(ctx.clone(), &err),
+        }
+    }
+
+    /// `b` builds a query from conditions; `G` fetches items by a key list,
+    /// where the backend can.
+    fn handle_query_source_key(&self, key: char, ctx: crate::env::WidgetCtx) {
+        if key == 'b' {
+            self.show_query_builder(ctx);
+        } else if key == 'A' {
+            self.show_access_patterns(ctx);
+        } else if key == '#' {
+            self.count_active_matches(ctx);
+        } else if self.db.capabilities().batch_get {
+            self.show_key_list_popup(ctx);
+        }
+    }
+
+    fn show_key_list_popup(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let schema = match self.schema() {
+            Ok(schema) => schema,
+            Err(err) => {
+                self.show_error(ctx, &err);
+                return;
+            }
+        };
+        let ctx_for_submit = ctx.clone();
+        let popup = Box::new(KeyListPopup::new(
+            schema.key,
+            move |keys| {
+                ctx_for_submit.emit_self(KeyListEvent { keys });
+            },
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    fn show_projection_popup(&self, ctx: crate::env::WidgetCtx) {
+        let current = self.state.borrow().projection.clone();
+        let ctx_for_apply = ctx.clone();
+        let popup = Box::new(ProjectionPopup::new(
+            current.as_ref(),
+            move |projection| {
+                ctx_for_apply.emit_self(ProjectionEvent { projection });
+            },
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    /// Reverse the sort-key order of the active key query and run it again. An
+    /// `ORDER` clause in the query text is rewritten; otherwise the order
+    /// option is toggled.
+    fn flip_sort_order(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let (active_query, is_key_query) = {
+            let state = self.state.borrow();
+            let is_key_query = matches!(
+                state.query_output.as_ref().map(|output| &output.plan_kind),
+                Some(PlanKind::IndexedQuery { .. })
+            );
+            (state.active_query.clone(), is_key_query)
+        };
+        if !is_key_query {
+            ctx.show_toast(Toast {
+                message: "Only key queries have a sort order".to_string(),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(3),
+                action: None,
+            });
+            return;
+        }
+        let (active_query, descending) = match active_query {
+            ActiveQuery::Text(query) => match split_order_clause(&query) {
+                (base, Some(order)) => {
+                    let flipped = match order {
+                        SortOrder::Ascending => SortOrder::Descending,
+                        SortOrder::Descending => SortOrder::Ascending,
+                    };
+                    let query = format!("{base} ORDER {}", flipped.keyword());
+                    (ActiveQuery::Text(query), flipped == SortOrder::Descending)
+                }
+                (_, None) => (ActiveQuery::Text(query), self.toggle_descending()),
+            },
+            other @ (ActiveQuery::Index(_) | ActiveQuery::Keys(_)) => {
+                (other, self.toggle_descending())
+            }
+        };
+        let message = if descending {
+            "Sort order: descending"
+        } else {
+            "Sort order: ascending"
+        };
+        ctx.show_toast(Toast {
+            message: message.to_string(),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(2),
+            action: None,
+        });
+        self.restart_query(active_query, ctx, None);
+    }
+
+    fn toggle_descending(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        state.options.descending = !state.options.descending;
+        state.options.descending
+    }
+
+    /// How many segments to refetch the whole result set with, when that beats
+    /// continuing page by page: the results come from a scan, the backend can
+    /// split it, and more than one segment is configured.
+    fn parallel_segments(&self) -> Option<u32> {
+        let state = self.state.borrow();
+        let is_scan = matches!(
+            state.query_output.as_ref().map(|output| &output.plan_kind),
+            Some(PlanKind::Scan)
+        );
+        (is_scan
+            && self.scan_segments > 1
+            && self.db.capabilities().parallel_scan
+            && matches!(state.active_query, ActiveQuery::Text(_)))
+        .then_some(self.scan_segments)
+    }
+
+    /// The options for the next request. A projection always includes the
+    /// table's key attributes so items can still be selected, edited, and
+    /// deleted.
+    fn read_options(&self) -> QueryOptions {
+        let mut options = self.state.borrow().options.clone();
+        if !options.projection.is_empty()
+            && let Some(schema) = self.schema_snapshot()
+        {
+            for field in schema.key.fields.iter().rev() {
+                if !options.projection.contains(&field.name) {
+                    options.projection.insert(0, field.name.clone());
+                }
+            }
+        }
+        options
+    }
+
+    fn show_index_picker(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let targets = match self.index_targets() {
+            Ok(targets) if targets.is_empty() => {
+                ctx.show_toast(Toast {
+                    message: "No indexes available for this item".to_string(),
+                    kind: ToastKind::Info,
+                    duration: Duration::from_secs(3),
+                    action: None,
+                });
+                return;
+            }
+            Ok(targets) => targets,
+            Err(err) => {
+                self.show_error(ctx.clone(), &err);
+                return;
+            }
+        };
+        let ctx_for_select = ctx.clone();
+        let popup = Box::new(index_picker::IndexPicker::new(
+            targets,
+            move |target| {
+                ctx_for_select.emit_self(IndexQueryEvent { target });
+            },
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    fn show_index_scope_picker(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let indexes = self
+            .schema_snapshot()
+            .map(|schema| schema.indexes)
+            .unwrap_or_default();
+        if indexes.is_empty() {
+            ctx.show_toast(Toast {
+                message: "This table has no secondary indexes".to_string(),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(3),
+                action: None,
+            });
+            return;
+        }
+        let current = self.state.borrow().options.index.clone();
+        let ctx_for_select = ctx.clone();
+        let popup = Box::new(index_scope::IndexScopePicker::new(
+            indexes,
+            current.as_deref(),
+            move |index| {
+                ctx_for_select.emit_self(IndexScopeEvent { index });
+            },
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    fn show_query_builder(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        // Start from the key of whatever the view browses.
+        let attribute = self
+            .scoped_index()
+            .map(|index| index.key)
+            .or_else(|| self.schema_snapshot().map(|schema| schema.key))
+            .and_then(|key| key.partition_key().map(str::to_owned))
+            .unwrap_or_default();
+        let ctx_for_apply = ctx.clone();
+        let popup = Box::new(QueryBuilderPopup::new(
+            self.db.clone(),
+            &attribute,
+            move |query| {
+                ctx_for_apply.emit_self(QueryBuilderEvent { query });
+            },
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    /// List the table's saved access patterns to run one.
+    fn show_access_patterns(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let ctx_for_run = ctx.clone();
+        let ctx_for_save = ctx.clone();
+        let popup = Box::new(PatternPicker::new(
+            self.table_name.clone(),
+            access_patterns::list(&self.table_name),
+            move |query| ctx_for_run.emit_self(RunPatternRequest { query }),
+            move || ctx_for_save.emit_self(SavePatternRequest),
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    fn name_access_pattern(&self, ctx: crate::env::WidgetCtx) {
+        if self.state.borrow().input.value().trim().is_empty() {
+            ctx.show_toast(Toast {
+                message: "Type a query to save as an access pattern".to_string(),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(3),
+                action: None,
+            });
+            return;
+        }
+        let emitter = ctx.clone();
+        let popup = NamePopup::new(
+            "Save access pattern".to_string(),
+            "Name",
+            "",
+            "e.g. orders by customer; a saved name is replaced",
+            move |name| emitter.emit_self(SavePatternEvent { name }),
+            self.inner.id(),
+        )
+        .with_validation(access_patterns::validate_name);
+        ctx.set_popup(Box::new(popup));
+    }
+
+    fn save_access_pattern(&self, name: &str, ctx: &crate::env::WidgetCtx) {
+        let query = self.state.borrow().input.value().trim().to_string();
+        access_patterns::save(
+            &self.table_name,
+            &AccessPattern {
+                name: name.to_string(),
+                query,
+            },
+        );
+        ctx.show_toast(Toast {
+            message: format!("Saved access pattern {name}"),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(2),
+            action: None,
+        });
+    }
+
+    /// The secondary index the view browses, if any.
+    fn scoped_index(&self) -> Option<IndexSchema> {
+        let index = self.state.borrow().options.index.clone()?;
+        self.schema_snapshot()?
+            .indexes
+            .into_iter()
+            .find(|schema| schema.name == index)
+    }
+
+    fn handle_export_request(&self, request: &ExportRequest, ctx: crate::env::WidgetCtx) {
+        if !request.overwrite_confirmed && request.path.exists() {
+            let filename = request.path.file_name().map_or_else(
+                || request.path.display().to_string(),
+                |name| name.to_string_lossy().to_string(),
+            );
+            let message = format!("{filename} already exists");
+            let ctx_for_confirm = ctx.clone();
+            let confirm_action = ConfirmAction::new(
+                KeyCode::Char('o'),
+                KeyModifiers::CONTROL,
+                "^o",
+                "overwrite",
+                "Overwrite file",
+            );
+            let mode = request.mode;
+            let fetch_all = request.fetch_all;
+            let visible_columns = request.visible_columns;
+            let path = request.path.clone();
+            let upload = request.upload.clone();
+            ConfirmPopup::new_with_action(
+                "Overwrite?",
+                message,
+                "Overwrite",
+                "cancel",
+                confirm_action,
+                move || {
+                    ctx_for_confirm.emit_self(ExportRequest {
+                        mode,
+                        path: path.clone(),
+                        fetch_all,
+                        visible_columns,
+                        upload: upload.clone(),
+                        overwrite_confirmed: true,
+                    });
+                },
+                self.inner.id(),
+            )
+            .show(&ctx, Action::Export, &filename);
+            return;
+        }
+        {
+            let mut state = self.state.borrow_mut();
+            state.pending_upload.clone_from(&request.upload);
+            state.export_visible_columns = request.visible_columns;
+        }
+        self.start_export(request.mode, request.path.clone(), request.fetch_all, ctx);
+    }
+
+    /// `x` exports what's in view: the open item, the selection, or every
+    /// result.
+    fn export_current_view(&self, ctx: crate::env::WidgetCtx) {
+        let kind = if self.state.borrow().show_tree {
+            ExportKind::Item
+        } else if self.selection_active() {
+            ExportKind::Selection
+        } else {
+            ExportKind::Results
+        };
+        self.show_export_popup(kind, ctx);
+    }
+
+    fn toggle_watch(&self, ctx: &crate::env::WidgetCtx) {
+        if self.state.borrow_mut().watch.take().is_some() {
+            watch::stopped(ctx);
+            ctx.invalidate();
+            return;
+        }
+        if let Some(err) = self.watch_blocker() {
+            self.show_error(ctx.clone(), err);
+            return;
+        }
+        self.start_watch(ctx);
+    }
+
+    fn watch_blocker(&self) -> Option<&'static str> {
+        let state = self.state.borrow();
+        let ActiveQuery::Text(query) = &state.active_query else {
+            return None;
+        };
+        let statement =
+            self.raw_sql || (state.statements && (state.partiql || partiql::is_statement(query)));
+        watch::blocker(query, statement)
+    }
+
+    fn start_watch(&self, ctx: &crate::env::WidgetCtx) {
+        let generation = self.watch_seq.get() + 1;
+        self.watch_seq.set(generation);
+        self.state.borrow_mut().watch = Some(Watch::start(self.watch_interval, generation, ctx));
+        ctx.invalidate();
+    }
+
+    fn on_watch_step(&self, step: watch::WatchStep, ctx: &crate::env::WidgetCtx) {
+        if let watch::WatchStep::Due(now) = step
+            && self.watch_can_refresh()
+        {
+            if self.watch_blocker().is_some() {
+                // A statement that writes was run since watching started.
+                self.toggle_watch(ctx);
+                return;
+            }
+            self.refresh_watched(ctx.clone(), now);
+        }
+        ctx.invalidate();
+    }
+
+    /// A refresh waits while a page is loading, something runs over the
+    /// results, or resetting the view would throw away what's being done.
+    fn watch_can_refresh(&self) -> bool {
+        let state = self.state.borrow();
+        !matches!(state.loading_state, LoadingState::Loading)
+            && !state.is_loading_more
+            && !state.is_prefetching
+            && !self.exports.is_busy()
+            && !self.bulk.is_busy()
+            && !state.input.is_active()
+            && !state.filter.is_active()
+            && !state.show_tree
+            && !state.selection.is_active()
+    }
+
+    fn refresh_watched(&self, ctx: crate::env::WidgetCtx, now: Instant) {
+        let schema = self.schema().ok();
+        let active_query = {
+            let mut state = self.state.borrow_mut();
+            let rows = watch::keyed_rows(state.items.iter().map(|item| &item.0), schema.as_ref());
+            if let Some(watch) = state.watch.as_mut() {
+                watch.refreshed(rows, now);
+            }
+            state.active_query.clone()
+        };
+        self.restart_query(active_query, ctx, None);
+    }
+
+    fn show_diff_source(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        compare::prompt(self.inner.id(), &ctx);
+    }
+
+    /// Compare every result of the active query with a saved export or the
+    /// same query against another table.
+    fn start_diff(&self, source: &str, ctx: crate::env::WidgetCtx) {
+        let busy = {
+            let state = self.state.borrow();
+            matches!(state.loading_state, LoadingState::Loading) || state.is_loading_more
+        };
+        if busy {
+            self.show_error(
+                ctx,
+                "Query is still loading; wait for it to finish before comparing.",
+            );
+            return;
+        }
+        let schema = match self.schema() {
+            Ok(schema) => schema,
+            Err(err) => {
+                self.show_error(ctx, &err);
+                return;
+            }
+        };
+        let segments = self.parallel_segments();
+        let (loaded, request, statement) = {
+            let state = self.state.borrow();
+            // A parallel scan refetches the loaded pages too.
+            let loaded: Vec<value::Item> = if segments.is_some() {
+                Vec::new()
+            } else {
+                state
+                    .items
+                    .iter()
+                    .map(|item| item_from_attribute_map(&item.0))
+                    .collect()
+            };
+            let statement = matches!(&state.active_query, ActiveQuery::Text(query)
+                if state.statements && (state.partiql || partiql::is_statement(query)));
+            let request =
+                state
+                    .last_evaluated_key
+                    .clone()
+                    .filter(|_| !statement)
+                    .map(|start_key| BatchActionStreamRequest {
+                        scope: BatchActionScope::Results { filter: None },
+                        start_key,
+                        segments,
+                        active_query: state.active_query.clone(),
+                        parameters: state.parameters.clone(),
+                        options: self.read_options(),
+                        db: self.db.clone(),
+                        table_name: self.table_name.clone(),
+                        cancel: None,
+                    });
+            (loaded, request, statement)
+        };
+        let keys = match &self.state.borrow().active_query {
+            ActiveQuery::Keys(keys) => Some(keys.as_ref().clone()),
+            _ => None,
+        };
+        let comparison = compare::Comparison {
+            db: self.db.clone(),
+            source: source.to_string(),
+            key: schema.key,
+            plan: self.active_plan(),
+            keys,
+            statement,
+        };
+        self.set_loading_state(LoadingState::Loading);
+        compare::start(
+            comparison,
+            Box::pin(collect_all_results(loaded, request)),
+            &ctx,
+        );
+        ctx.invalidate();
+    }
+
+    fn on_compare_step(&self, step: compare::CompareStep, ctx: &crate::env::WidgetCtx) {
+        match step {
+            compare::CompareStep::Requested(source) => self.start_diff(&source, ctx.clone()),
+            compare::CompareStep::Finished(result) => {
+                self.set_loading_state(LoadingState::Loaded);
+                if let Err(err) = result {
+                    self.show_error(ctx.clone(), &err);
+                }
+                ctx.invalidate();
+            }
+        }
+    }
+
+    fn show_export_popup(&self, mode: ExportKind, ctx: crate::env::WidgetCtx) {
+        if matches!(mode, ExportKind::Item) && self.selected_item().is_err() {
+            self.show_error(ctx.clone(), "No item selected");
+            return;
+        }
+        if matches!(mode, ExportKind::Selection) && !self.selection_active() {
+            self.show_error(ctx.clone(), "No items selected");
+            return;
+        }
+        let path = self.export_path(mode);
+        let option_label = matches!(mode, ExportKind::Results)
+            .then_some(Cow::Borrowed("Fetch all results before exporting"));
+        let ctx_for_confirm = ctx.clone();
+        let popup = Box::new(
+            ExportPopup::new(
+                path,
+                option_label,
+                false,
+                move |choice| {
+                    ctx_for_confirm.emit_self(ExportRequest {
+                        mode,
+                        path: choice.path,
+                        fetch_all: choice.option,
+                        visible_columns: choice.visible_columns,
+                        upload: choice.upload,
+                        overwrite_confirmed: false,
+                    });
+                },
+                self.inner.id(),
+            )
+            .with_s3_upload()
+            .with_columns_option()
+            .with_compression(),
+        );
+        ctx.set_popup(popup);
+    }
+
+    /// Upload a finished export to S3. The local file is kept.
+    fn start_upload(&self, path: PathBuf, uri: S3Uri, count: usize, ctx: crate::env::WidgetCtx) {
+        ctx.show_toast(Toast {
+            message: format!(
+                "Uploading {count} item{} to {uri}...",
+                if count == 1 { "" } else { "s" }
+            ),
             kind: ToastKind::Info,
             duration: Duration::from_hours(1),
             action: None,
         });
+        let selection = self.db.active_profile().unwrap_or_default();
+        tokio::spawn(async move {
+            let result = s3::upload_file(&selection, &path, &uri).await;
+            ctx.emit_self(S3UploadEvent { uri, result });
+        });
     }
 
     fn start_export(
@@ -2541,6 +5091,10 @@ impl QueryWidget {
                         return;
                     }
                 };
+                let item = match self.state.borrow().export_projection() {
+                    Some(projection) => projection.apply(&item),
+                    None => item,
+                };
                 self.spawn_export_task(mode, path, ctx, move |path| {
                     export_item_to_path(&item, &path)
                 });
@@ -2551,12 +5105,13 @@ impl QueryWidget {
             ExportKind::Results => {
                 let items = {
                     let state = self.state.borrow();
-                    state
+                    let items = state
                         .filtered_indices
                         .iter()
                         .filter_map(|idx| state.items.get(*idx))
                         .map(|item| item.0.clone())
-                        .collect::<Vec<_>>()
+                        .collect();
+                    project_items(state.export_projection().as_ref(), items)
                 };
                 if !fetch_all {
                     self.spawn_export_task(mode, path, ctx, move |path| {
@@ -2566,22 +5121,14 @@ impl QueryWidget {
                 }
                 let (active_query, start_key, filter, items) = {
                     let state = self.state.borrow();
-                    let filter_value = state.filter.value.trim().to_lowercase();
-                    let filter = if filter_value.is_empty() {
-                        None
-                    } else {
-                        Some(filter_value)
-                    };
-                    let items = if let Some(needle) = filter.as_deref() {
-                        state
-                            .items
-                            .iter()
-                            .filter(|item| item_matches_filter(&item.0, needle))
-                            .map(|item| item.0.clone())
-                            .collect::<Vec<_>>()
-                    } else {
-                        state.items.iter().map(|item| item.0.clone()).collect()
-                    };
+                    let filter = (!state.row_filter.is_empty()).then(|| state.row_filter.clone());
+                    let items = state
+                        .items
+                        .iter()
+                        .filter(|item| item.matches(&state.row_filter))
+                        .map(|item| item.0.clone())
+                        .collect();
+                    let items = project_items(state.export_projection().as_ref(), items);
                     (
                         state.active_query.clone(),
                         state.last_evaluated_key.clone(),
@@ -2595,16 +5142,26 @@ impl QueryWidget {
                     });
                     return;
                 };
-                let cancel = Arc::new(AtomicBool::new(false));
+                // A parallel scan refetches the loaded pages too.
+                let segments = self.parallel_segments();
+                let items = if segments.is_some() {
+                    Vec::new()
+                } else {
+                    items
+                };
+                let export = self.exports.start();
                 let request = BatchActionStreamRequest {
                     scope: BatchActionScope::Results { filter },
                     start_key,
+                    segments,
                     active_query,
+                    parameters: self.state.borrow().parameters.clone(),
+                    options: self.read_options(),
                     db: self.db.clone(),
                     table_name: self.table_name.clone(),
-                    cancel: Some(cancel.clone()),
+                    cancel: Some(export.clone()),
                 };
-                self.spawn_stream_export(mode, path, items, request, cancel, ctx);
+                self.spawn_stream_export(mode, path, items, request, export.id(), ctx);
             }
         }
     }
@@ -2615,30 +5172,35 @@ impl QueryWidget {
         path: PathBuf,
         items: Vec<HashMap<String, AttributeValue>>,
         request: BatchActionStreamRequest,
-        cancel: Arc<AtomicBool>,
+        export_id: u64,
         ctx: crate::env::WidgetCtx,
     ) {
-        let initial_count = items.len();
-        let export_id = self.next_export_id();
-        {
-            let mut state = self.state.borrow_mut();
-            state.is_prefetching = true;
-            state.export_id = Some(export_id);
-            state.export_cancel = Some(cancel);
-        }
-        self.show_export_progress_toast(ctx.clone(), initial_count);
+        let projection = self.state.borrow().export_projection();
+        self.state.borrow_mut().is_prefetching = true;
+        let job = jobs::track(
+            format!("Export {}", abbreviate_home(&path)),
+            request
+                .cancel
+                .as_ref()
+                .map(RequestHandle::shared_flag)
+                .unwrap_or_default(),
+        );
+        job.progress(export_progress(items.len()));
         let ctx_for_export = ctx.clone();
         tokio::spawn(async move {
-            let result = export_batch_to_path(
-                path.clone(),
-                items,
-                Some(request),
-                ctx_for_export.clone(),
-                export_id,
-            )
-            .await
-            .map(|count| ExportOutcome { mode, path, count });
-            ctx_for_export.emit_self(ExportEvent { result });
+            let result = export_batch_to_path(path.clone(), items, Some(request), projection, &job)
+                .await
+                .map(|count| ExportOutcome { mode, path, count });
+            job.finish_quietly(
+                result
+                    .as_ref()
+                    .map(|outcome| format!("Exported {} items", outcome.count))
+                    .map_err(Clone::clone),
+            );
+            ctx_for_export.emit_self(ExportEvent {
+                export_id: Some(export_id),
+                result,
+            });
         });
     }
 
@@ -2654,7 +5216,10 @@ impl QueryWidget {
         let ctx_for_export = ctx.clone();
         tokio::spawn(async move {
             let result = task(path.clone()).map(|count| ExportOutcome { mode, path, count });
-            ctx_for_export.emit_self(ExportEvent { result });
+            ctx_for_export.emit_self(ExportEvent {
+                export_id: None,
+                result,
+            });
         });
     }
 
@@ -2670,7 +5235,10 @@ impl QueryWidget {
                 return;
             }
         };
-        let items = self.selected_loaded_items(&selection, &schema);
+        let items = project_items(
+            self.state.borrow().export_projection().as_ref(),
+            self.selected_loaded_items(&selection, &schema),
+        );
         let start_key = {
             let state = self.state.borrow();
             match &selection {
@@ -2684,19 +5252,29 @@ impl QueryWidget {
             });
             return;
         };
-        let cancel = Arc::new(AtomicBool::new(false));
+        let export = self.exports.start();
         let request = BatchActionStreamRequest {
             scope: BatchActionScope::Selection {
                 selection,
                 schema: Box::new(schema),
             },
             start_key,
+            segments: None,
             active_query: self.state.borrow().active_query.clone(),
+            parameters: self.state.borrow().parameters.clone(),
+            options: self.read_options(),
             db: self.db.clone(),
             table_name: self.table_name.clone(),
-            cancel: Some(cancel.clone()),
+            cancel: Some(export.clone()),
         };
-        self.spawn_stream_export(ExportKind::Selection, path, items, request, cancel, ctx);
+        self.spawn_stream_export(
+            ExportKind::Selection,
+            path,
+            items,
+            request,
+            export.id(),
+            ctx,
+        );
     }
 
     fn delete_selection(&self, selection: SelectionSnapshot, ctx: crate::env::WidgetCtx) {
@@ -2704,6 +5282,10 @@ impl QueryWidget {
             show_readonly_toast(&ctx);
             return;
         }
+        if transaction::is_staging() {
+            self.stage_selection_deletes(&selection, &ctx);
+            return;
+        }
         self.set_loading_state(LoadingState::Loading);
         ctx.invalidate();
         let schema = match self.schema() {
@@ -2724,6 +5306,8 @@ impl QueryWidget {
             }
         };
         let loaded_keys = self.selected_loaded_keys(&selection, &schema);
+        let parameters = self.state.borrow().parameters.clone();
+        let options = self.read_options();
         let db = self.db.clone();
         let table_name = self.table_name.clone();
         tokio::spawn(async move {
@@ -2733,6 +5317,8 @@ impl QueryWidget {
                 schema,
                 start_key,
                 active_query,
+                parameters,
+                options,
                 db,
                 table_name,
             };
@@ -2741,6 +5327,120 @@ impl QueryWidget {
         });
     }
 
+    /// Stage a delete for each explicitly selected row. A selection of every
+    /// query match may run past what a transaction holds, so it isn't staged.
+    fn stage_selection_deletes(&self, selection: &SelectionSnapshot, ctx: &crate::env::WidgetCtx) {
+        let SelectionSnapshot::Explicit(keys) = selection else {
+            self.show_error(
+                ctx.clone(),
+                "Only selected rows can be staged, not every query match",
+            );
+            return;
+        };
+        for key in keys {
+            let key_map = key.to_key_map();
+            let write = TransactWrite::Delete {
+                collection: self.table_name.clone(),
+                key: Key(item_from_attribute_map(&key_map)),
+            };
+            if let Err(err) = transaction::stage(write, key.summary_line()) {
+                self.show_error(ctx.clone(), &err);
+                return;
+            }
+        }
+        self.state.borrow_mut().selection.clear();
+        ctx.show_toast(Toast {
+            message: format!(
+                "Staged {} deletes · {} in the transaction",
+                keys.len(),
+                transaction::staged_count()
+            ),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(2),
+            action: None,
+        });
+    }
+
+    /// `M` copies the selected rows, or the focused one without a selection,
+    /// to a table picked from the list.
+    fn start_copy_to_table(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let items = match self.items_to_copy() {
+            Ok(items) => items,
+            Err(err) => {
+                self.show_error(ctx, &err);
+                return;
+            }
+        };
+        copy_items::start(self.db.clone(), items, ctx);
+    }
+
+    fn copy_source(&self) -> copy_items::CopySource {
+        copy_items::CopySource {
+            db: self.db.clone(),
+            table_name: self.table_name.clone(),
+            key_fields: self.schema().map(|schema| {
+                schema
+                    .key
+                    .fields
+                    .into_iter()
+                    .map(|field| field.name)
+                    .collect()
+            }),
+            parent: self.inner.id(),
+        }
+    }
+
+    fn on_copy_step(&self, step: copy_items::CopyStep, ctx: crate::env::WidgetCtx) {
+        match step {
+            copy_items::CopyStep::Continuing => {}
+            copy_items::CopyStep::Failed(err) => self.show_error(ctx, &err),
+            copy_items::CopyStep::Writing => {
+                self.set_loading_state(LoadingState::Loading);
+                ctx.invalidate();
+            }
+            copy_items::CopyStep::Written(result) => {
+                self.set_loading_state(LoadingState::Loaded);
+                match result {
+                    Ok(true) => {
+                        self.clear_selection();
+                        let active_query = self.state.borrow().active_query.clone();
+                        self.restart_query(active_query, ctx.clone(), None);
+                    }
+                    Ok(false) => {}
+                    Err(err) => self.show_error(ctx.clone(), &err),
+                }
+                ctx.invalidate();
+            }
+        }
+    }
+
+    /// The loaded rows the selection holds, or the focused row without one.
+    fn items_to_copy(&self) -> Result<Vec<value::Item>, String> {
+        let Some(selection) = self.selection_snapshot() else {
+            return Ok(vec![item_from_attribute_map(&self.selected_item()?)]);
+        };
+        if let SelectionSnapshot::Query { .. } = selection {
+            return Err("Only selected rows can be copied, not every query match".to_string());
+        }
+        let schema = self.schema()?;
+        let state = self.state.borrow();
+        Ok(state
+            .items
+            .iter()
+            .filter(|item| {
+                ItemKey::from_item(&item.0, &schema).is_ok_and(|key| selection.is_selected(&key))
+            })
+            .map(|item| item_from_attribute_map(&item.0))
+            .collect())
+    }
+
     fn selected_item(&self) -> Result<HashMap<String, AttributeValue>, String> {
         let state = self.state.borrow();
         let selected = state.table_state.selected();
@@ -2767,7 +5467,9 @@ impl QueryWidget {
                 }
                 base.join(export_file_name(&self.table_name, mode, timestamp))
             }
-            ExportKind::Selection => base.join(export_file_name(&self.table_name, mode, timestamp)),
+            ExportKind::Selection => base
+                .join(export_file_name(&self.table_name, mode, timestamp))
+                .with_extension(ExportFormat::preferred().extension()),
             ExportKind::Results => {
                 let schema = self
                     .table_meta
@@ -2783,6 +5485,7 @@ impl QueryWidget {
                     query.as_deref(),
                     timestamp,
                 ))
+                .with_extension(ExportFormat::preferred().extension())
             }
         }
     }
@@ -2903,6 +5606,14 @@ impl QueryWidget {
             show_readonly_toast(&ctx);
             return;
         }
+        if transaction::is_staging() {
+            let write = TransactWrite::Delete {
+                collection: self.table_name.clone(),
+                key: Key(item_from_attribute_map(&key)),
+            };
+            self.stage_write(&key, write, &ctx);
+            return;
+        }
         self.set_loading_state(LoadingState::Loading);
         ctx.invalidate();
         let db = self.db.clone();
@@ -2920,17 +5631,13 @@ impl QueryWidget {
         });
     }
 
-    fn remove_item_by_key(&self, key: &HashMap<String, AttributeValue>) {
+    /// Drop the loaded item with `key` from the results, returning it.
+    fn remove_item_by_key(&self, key: &HashMap<String, AttributeValue>) -> Option<Item> {
         let (hash_key, range_key) = {
             let meta = self.table_meta.borrow();
-            let Some(meta) = meta.as_ref() else {
-                return;
-            };
-            extract_hash_range(&meta.schema)
-        };
-        let Some(hash_key) = hash_key else {
-            return;
+            extract_hash_range(&meta.as_ref()?.schema)
         };
+        let hash_key = hash_key?;
         let mut state = self.state.borrow_mut();
         if let Some(index) = state.items.iter().position(|item| {
             let hash_matches = item.0.get(&hash_key) == key.get(&hash_key);
@@ -2940,9 +5647,50 @@ impl QueryWidget {
                 hash_matches
             }
         }) {
-            state.items.remove(index);
+            let removed = state.rows_mut().remove(index);
+            state.apply_filter();
+            return Some(removed);
+        }
+        None
+    }
+
+    /// A click on a header cell cycles its sort; on a row, selects it, and a
+    /// double click opens the item.
+    fn handle_click(&self, ctx: &crate::env::WidgetCtx, column: u16, row: u16) -> bool {
+        let mut state = self.state.borrow_mut();
+        let area = state.table_area;
+        if state.show_tree || !area.contains(Position::new(column, row)) {
+            return false;
+        }
+        if row == area.y + 1 {
+            let Some(key) = state
+                .header_hits
+                .iter()
+                .find(|(start, end, _)| (*start..*end).contains(&column))
+                .map(|(_, _, key)| key.clone())
+            else {
+                return false;
+            };
+            state.column_sort = ColumnSort::cycle(state.column_sort.as_ref(), &key);
             state.apply_filter();
+            ctx.invalidate();
+            return true;
+        }
+        let Some(index) = row
+            .checked_sub(area.y + 2)
+            .map(|visible| state.table_state.offset() + visible as usize)
+            .filter(|index| *index < state.filtered_indices.len())
+        else {
+            return false;
+        };
+        state.table_state.select(Some(index));
+        state.clamp_table_offset();
+        if self.clicks.click(index) {
+            state.show_tree = true;
+            state.reset_tree_view();
         }
+        ctx.invalidate();
+        true
     }
 
     fn scroll_down(&self, ctx: crate::env::WidgetCtx) {
@@ -3055,7 +5803,7 @@ impl QueryWidget {
             }
             state.table_state.scroll_down_by(1);
             state.clamp_table_offset();
-            state.reset_tree_scroll();
+            state.reset_tree_view();
             self.should_load_more(&state)
         };
 
@@ -3071,7 +5819,7 @@ impl QueryWidget {
         }
         state.table_state.scroll_up_by(1);
         state.clamp_table_offset();
-        state.reset_tree_scroll();
+        state.reset_tree_view();
     }
 
     fn scroll_columns_left(&self) {
@@ -3087,7 +5835,7 @@ impl QueryWidget {
         if state.show_tree {
             return;
         }
-        let total_columns = state.item_keys.visible().len();
+        let total_columns = state.columns().len();
         if total_columns == 0 {
             state.column_offset = 0;
             return;
@@ -3095,6 +5843,124 @@ impl QueryWidget {
         state.column_offset = (state.column_offset + 1).min(total_columns.saturating_sub(1));
     }
 
+    /// Keys that change how the results table is laid out or what it holds.
+    fn handle_layout_key(&self, code: KeyCode, ctx: &crate::env::WidgetCtx) {
+        match code {
+            KeyCode::Left => self.scroll_columns_left(),
+            KeyCode::Right => self.scroll_columns_right(),
+            KeyCode::Char('z') => self.toggle_compact_columns(),
+            KeyCode::Char('P') => self.toggle_split_preview(),
+            KeyCode::Char(key @ ('<' | '>' | 'g' | 'c')) => self.handle_page_key(key, ctx),
+            KeyCode::Char(key @ ('+' | '-' | '=')) => self.resize_column(key),
+            KeyCode::Char('m') => self.show_projection_popup(ctx.clone()),
+            KeyCode::Char('F') => self.filter_on_server(ctx.clone()),
+            KeyCode::Char('V') => self.show_entity_picker(ctx.clone()),
+            KeyCode::Char('H') => self.show_hydrate_popup(ctx.clone()),
+            _ => {}
+        }
+    }
+
+    /// Widen (`+`), narrow (`-`), or reset (`=`) the first scrolled-to
+    /// column.
+    fn resize_column(&self, key: char) {
+        let mut state = self.state.borrow_mut();
+        let Some(focused) = state.columns().get(state.column_offset).cloned() else {
+            return;
+        };
+        let Some((start, end, column)) = state
+            .header_hits
+            .iter()
+            .find(|(_, _, column)| *column == focused)
+            .cloned()
+        else {
+            return;
+        };
+        if key == '=' {
+            state.column_widths.reset(&column);
+        } else {
+            let current = usize::from(end.saturating_sub(start));
+            state.column_widths.resize(&column, current, key == '+');
+        }
+    }
+
+    /// Pick a reference whose related items to join into extra columns.
+    fn show_hydrate_popup(&self, ctx: crate::env::WidgetCtx) {
+        if self.raw_sql {
+            return;
+        }
+        let attributes = self
+            .state
+            .borrow()
+            .hydration
+            .as_ref()
+            .map(Hydration::attributes)
+            .unwrap_or_default();
+        let prompted = hydrate::prompt(
+            self.db.as_ref(),
+            &self.table_name,
+            &attributes,
+            self.inner.id(),
+            &ctx,
+        );
+        if let Err(err) = prompted {
+            self.show_error(ctx, &err);
+        }
+    }
+
+    fn join_source(&self) -> hydrate::JoinSource {
+        let state = self.state.borrow();
+        hydrate::JoinSource {
+            db: self.db.clone(),
+            rows: state
+                .filtered_indices
+                .iter()
+                .filter_map(|idx| state.items.get(*idx))
+                .map(|item| item.0.clone())
+                .collect(),
+        }
+    }
+
+    /// Pick the entity whose rows are shown, and whether rows are grouped by
+    /// entity.
+    fn show_entity_picker(&self, ctx: crate::env::WidgetCtx) {
+        let state = self.state.borrow();
+        if state.entities.is_empty() {
+            ctx.show_toast(Toast {
+                message: format!(
+                    "No entities for {}: add prefix rules under \"entities\" in config.json",
+                    self.table_name
+                ),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(4),
+                action: None,
+            });
+            return;
+        }
+        let names = state.entities.names();
+        let mut counts = vec![0; names.len() + 1];
+        for item in &state.items {
+            let entity = state.entities.classify(&item.0).unwrap_or(names.len());
+            counts[entity] += 1;
+        }
+        let emitter = ctx.clone();
+        let popup = EntityPicker::new(
+            names,
+            &counts,
+            state.entity_view,
+            move |view| emitter.emit_self(EntityViewEvent(view)),
+            self.inner.id(),
+        );
+        ctx.set_popup(Box::new(popup));
+    }
+
+    fn toggle_split_preview(&self) {
+        let mut state = self.state.borrow_mut();
+        if state.show_tree {
+            return;
+        }
+        state.split_preview = !state.split_preview;
+    }
+
     fn toggle_compact_columns(&self) {
         let mut state = self.state.borrow_mut();
         if state.show_tree {
@@ -3104,7 +5970,7 @@ impl QueryWidget {
     }
 
     fn should_load_more(&self, state: &QueryState) -> bool {
-        if state.is_loading_more || state.last_evaluated_key.is_none() {
+        if state.is_loading_more || state.last_evaluated_key.is_none() || state.limit.is_reached() {
             return false;
         }
         let visible_len = state.filtered_indices.len();
@@ -3115,10 +5981,129 @@ impl QueryWidget {
         selected + 1 >= visible_len
     }
 
+    /// Stop a scan from fetching further pages on its own, or let it carry on.
+    /// The page already on its way still lands.
+    fn toggle_scan_pause(&self, ctx: crate::env::WidgetCtx) {
+        let resume = {
+            let mut state = self.state.borrow_mut();
+            if !state.scan_in_progress() {
+                return;
+            }
+            state.scan_paused = !state.scan_paused;
+            state.scan_wants_more()
+        };
+        if resume {
+            self.load_more(ctx);
+        }
+    }
+
+    /// `<`/`>` step through the pages of results; `g` asks for one, and `c`
+    /// continues loading past the result cap.
+    fn handle_page_key(&self, key: char, ctx: &crate::env::WidgetCtx) {
+        let current = self.state.borrow().current_page();
+        match key {
+            '<' => self.go_to_page(current.saturating_sub(1).max(1), ctx.clone()),
+            '>' => self.go_to_page(current + 1, ctx.clone()),
+            'c' => self.continue_loading(ctx.clone()),
+            _ => self.show_go_to_page(ctx),
+        }
+    }
+
+    /// Allow another run of pages up to the result cap, starting with the
+    /// next one.
+    fn continue_loading(&self, ctx: crate::env::WidgetCtx) {
+        {
+            let mut state = self.state.borrow_mut();
+            if !state.limit.is_reached() {
+                return;
+            }
+            state.limit.resume();
+        }
+        self.load_more(ctx);
+    }
+
+    /// Count a loaded page against the result cap, and say so when it stops
+    /// the loading.
+    fn record_limit(&self, output: &QueryResult, ctx: &crate::env::WidgetCtx) {
+        let reached = {
+            let mut state = self.state.borrow_mut();
+            let state = &mut *state;
+            state.limit.record(output.items.len(), &state.options)
+        };
+        if reached && output.next.is_some() {
+            ctx.show_toast(Toast {
+                message: format!("Loading {}", self.state.borrow().limit.prompt()),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(4),
+                action: None,
+            });
+        }
+    }
+
+    fn show_go_to_page(&self, ctx: &crate::env::WidgetCtx) {
+        let state = self.state.borrow();
+        pages::prompt(&state.pages, state.current_page(), self.inner.id(), ctx);
+    }
+
+    /// Select the first row of `page` when it is loaded; otherwise fetch it
+    /// from its cursor, replacing the loaded rows.
+    fn go_to_page(&self, page: usize, ctx: crate::env::WidgetCtx) {
+        let jump = {
+            let state = self.state.borrow();
+            pages::go_to(&state.pages, page, state.items.len(), &ctx)
+        };
+        let cursor = match jump {
+            Some(PageJump::Loaded(rows)) => {
+                let mut state = self.state.borrow_mut();
+                let position = state
+                    .filtered_indices
+                    .iter()
+                    .position(|idx| rows.contains(idx));
+                if let Some(position) = position {
+                    state.table_state.select(Some(position));
+                    *state.table_state.offset_mut() = position;
+                    state.clamp_table_offset();
+                }
+                ctx.invalidate();
+                return;
+            }
+            Some(PageJump::Fetch(cursor)) => cursor,
+            None => return,
+        };
+        let active_query = {
+            let mut state = self.state.borrow_mut();
+            if matches!(state.active_query, ActiveQuery::Keys(_)) {
+                return;
+            }
+            state.pages.begin_jump(page);
+            state.limit.resume();
+            state.rows_mut().clear();
+            state.filtered_indices.clear();
+            state.table_state = TableState::default();
+            state.last_evaluated_key = None;
+            state.is_loading_more = false;
+            state.loading_state = LoadingState::Loading;
+            state.scan_paused = false;
+            state.selection.clear();
+            state.active_query.clone()
+        };
+        let request_id = self.bump_request_id();
+        ctx.invalidate();
+        match active_query {
+            ActiveQuery::Text(query) => {
+                self.start_query_page(query, cursor, false, ctx, request_id);
+            }
+            ActiveQuery::Index(target) => {
+                self.start_index_query_page(target, cursor, false, ctx, request_id);
+            }
+            ActiveQuery::Keys(_) => {}
+        }
+    }
+
     fn load_more(&self, ctx: crate::env::WidgetCtx) {
         let (active_query, start_key) = {
             let mut state = self.state.borrow_mut();
-            if state.is_loading_more {
+            if state.is_loading_more || state.limit.is_reached() {
                 return;
             }
             let Some(start_key) = state.last_evaluated_key.clone() else {
@@ -3136,11 +6121,136 @@ impl QueryWidget {
             ActiveQuery::Index(target) => {
                 self.start_index_query_page(target, Some(start_key), true, ctx, request_id);
             }
+            // Key lists are fetched whole and never leave a cursor.
+            ActiveQuery::Keys(_) => {}
+        }
+    }
+
+    fn start_query(&self, query: Option<&str>, ctx: crate::env::WidgetCtx) {
+        self.start_query_with_reopen(query, ctx, None);
+    }
+
+    /// Run a query typed into the input, first asking for the values of any
+    /// `:name` parameters it uses.
+    fn submit_query(&self, query: String, ctx: crate::env::WidgetCtx) {
+        let statement = {
+            let state = self.state.borrow();
+            state.statements && (state.partiql || partiql::is_statement(&query))
+        };
+        let names = if statement {
+            Vec::new()
+        } else {
+            self.input_language().parameters(&query)
+        };
+        if names.is_empty() {
+            self.check_scan_cost(query, ctx);
+            return;
+        }
+        let previous = self.state.borrow().parameters.clone();
+        let emitter = ctx.clone();
+        let popup = ParametersPopup::new(
+            names,
+            &previous,
+            move |parameters| {
+                emitter.emit_self(RunWithParametersRequest {
+                    query: query.clone(),
+                    parameters,
+                });
+            },
+            self.inner.id(),
+        );
+        ctx.set_popup(Box::new(popup));
+    }
+
+    /// Run a typed query, first confirming what it costs when it filters a
+    /// scan of a large table: a selective filter can read the whole table
+    /// before a screenful of matches turns up.
+    fn check_scan_cost(&self, query: String, ctx: crate::env::WidgetCtx) {
+        let (statement, parameters) = {
+            let state = self.state.borrow();
+            (
+                state.statements && (state.partiql || partiql::is_statement(&query)),
+                state.parameters.clone(),
+            )
+        };
+        if query.trim().is_empty()
+            || statement
+            || self.raw_sql
+            || safety::confirmation(Action::Scan) == Confirmation::None
+        {
+            self.start_query(Some(&query), ctx);
+            return;
         }
+        let options = self.read_options();
+        let consistent_read = options.consistent_read;
+        let known = self
+            .table_meta
+            .borrow()
+            .as_ref()
+            .map(|meta| meta.schema.clone());
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        let page_size = u32::try_from(self.page_size).unwrap_or(1);
+        tokio::spawn(async move {
+            let mut plan = text_query_plan(&query, &parameters);
+            options.apply(&mut plan);
+            let scan = matches!(
+                db.explain(&table_name, &plan).await,
+                PlanExplanation::Predicted(PlanKind::Scan)
+            );
+            let schema = match known {
+                Some(schema) => Some(schema),
+                None if scan => db.describe_collection(&table_name).await.ok(),
+                None => None,
+            };
+            let estimate = schema
+                .filter(|_| scan)
+                .and_then(|schema| {
+                    Some(ScanEstimate::new(
+                        u64::try_from(schema.item_count?).ok()?,
+                        u64::try_from(schema.size_bytes?).ok()?,
+                        page_size,
+                        consistent_read,
+                        dynamate::throttle::budget(),
+                    ))
+                })
+                .filter(|estimate| estimate.capacity_units >= LARGE_SCAN_CAPACITY_UNITS);
+            ctx.emit_self(ScanEstimateEvent { query, estimate });
+        });
     }
 
-    fn start_query(&self, query: Option<&str>, ctx: crate::env::WidgetCtx) {
-        self.start_query_with_reopen(query, ctx, None);
+    fn confirm_scan(&self, query: &str, estimate: &ScanEstimate, ctx: crate::env::WidgetCtx) {
+        let message = format!(
+            "Table={}\n\nThis filter is applied while scanning, so finding matches can read all ~{} items ({}). That is roughly {:.0} RCU and {} at {} items a page.\n\nContinue?",
+            self.table_name,
+            estimate.items,
+            format_size(estimate.bytes, BINARY),
+            estimate.capacity_units,
+            approximate_duration(estimate.duration),
+            self.page_size,
+        );
+        let emitter = ctx.clone();
+        let query = query.to_string();
+        let popup = ConfirmPopup::new_with_action(
+            "Scan large table",
+            message,
+            "Scan",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Char('s'),
+                KeyModifiers::CONTROL,
+                "^s",
+                "scan",
+                "Run the scan",
+            ),
+            move || {
+                emitter.emit_self(RunQueryRequest {
+                    query: query.clone(),
+                });
+            },
+            self.inner.id(),
+        );
+        popup.show(&ctx, Action::Scan, &self.table_name);
     }
 
     fn restart_query(
@@ -3156,6 +6266,9 @@ impl QueryWidget {
             ActiveQuery::Index(target) => {
                 self.start_index_query(target, ctx, reopen_tree);
             }
+            ActiveQuery::Keys(keys) => {
+                self.start_key_list_query(keys, ctx, reopen_tree);
+            }
         }
     }
 
@@ -3175,36 +6288,44 @@ impl QueryWidget {
             query = %query,
             "start_query"
         );
-        {
-            let mut state = self.state.borrow_mut();
-            state.items.clear();
-            state.filtered_indices.clear();
-            state.item_keys.clear();
-            state.table_state = TableState::default();
-            state.query_output = None;
-            state.last_evaluated_key = None;
-            state.is_loading_more = false;
-            state.last_query = active_query.input_value().unwrap_or_default();
-            state.active_query = active_query.clone();
-            if let Some(value) = active_query.input_value() {
-                state.input.set_value(value);
-            }
-            state.loading_state = LoadingState::Loading;
-            state.show_tree = false;
-            state.reopen_tree = reopen_tree;
-            state.scanned_total = 0;
-            state.matched_total = 0;
-            state.is_prefetching = false;
-            state.column_offset = 0;
-            state.reset_tree_scroll();
-            state.tree_line_count = 0;
-            state.tree_render_capacity = 0;
-            state.selection.clear();
-        }
+        self.reset_for_query(active_query, reopen_tree);
         ctx.invalidate();
         self.start_query_page(query, None, false, ctx, request_id);
     }
 
+    /// Clear the loaded results ahead of the first page of `active_query`.
+    fn reset_for_query(&self, active_query: ActiveQuery, reopen_tree: Option<usize>) {
+        let mut state = self.state.borrow_mut();
+        state.rows_mut().clear();
+        state.filtered_indices.clear();
+        state.item_keys.clear();
+        state.hydration = None;
+        state.table_state = TableState::default();
+        state.query_output = None;
+        state.last_evaluated_key = None;
+        state.pages = Pages::default();
+        state.limit.resume();
+        state.is_loading_more = false;
+        state.last_query = active_query.input_value().unwrap_or_default();
+        if let Some(value) = active_query.input_value() {
+            state.input.set_value(value);
+        }
+        state.active_query = active_query;
+        state.loading_state = LoadingState::Loading;
+        state.show_tree = false;
+        state.reopen_tree = reopen_tree;
+        state.scanned_total = 0;
+        state.consumed_capacity = 0.0;
+        state.matched_total = 0;
+        state.is_prefetching = false;
+        state.scan_paused = false;
+        state.column_offset = 0;
+        state.reset_tree_view();
+        state.tree_line_count = 0;
+        state.tree_render_capacity = 0;
+        state.selection.clear();
+    }
+
     fn start_query_page(
         &self,
         query: String,
@@ -3217,11 +6338,20 @@ impl QueryWidget {
         let table_name = self.table_name.clone();
         let page_size = self.page_size;
         let raw_sql = self.raw_sql;
+        let (statement, parameters) = {
+            let state = self.state.borrow();
+            (
+                state.statements && (state.partiql || partiql::is_statement(&query)),
+                state.parameters.clone(),
+            )
+        };
+        let options = self.read_options();
         let ctx = ctx.clone();
         tokio::spawn(async move {
             // The raw query text travels to the backend, which parses and
             // compiles it in its own query language.
-            let plan = text_query_plan(&query);
+            let mut plan = text_query_plan(&query, &parameters);
+            options.apply(&mut plan);
             let start_key_present = start_key.is_some();
             tracing::trace!(
                 table = %table_name,
@@ -3234,12 +6364,18 @@ impl QueryWidget {
                 cursor: start_key,
                 limit: Some(page_size as u32),
             };
+            dynamate::throttle::acquire().await;
             let result = if raw_sql {
                 db.raw_query(&query, page).await
+            } else if statement {
+                db.execute_statement(&query, page).await
             } else {
                 db.query(&table_name, &plan, page).await
+            };
+            if let Ok(output) = result.as_ref() {
+                dynamate::throttle::charge(output);
             }
-            .map_err(|err| err.to_string());
+            let result = result.map_err(|err| err.to_string());
             ctx.emit_self(QueryPageEvent {
                 request_id,
                 append,
@@ -3264,32 +6400,7 @@ impl QueryWidget {
             index = %target.name,
             "start_index_query"
         );
-        {
-            let mut state = self.state.borrow_mut();
-            state.items.clear();
-            state.filtered_indices.clear();
-            state.item_keys.clear();
-            state.table_state = TableState::default();
-            state.query_output = None;
-            state.last_evaluated_key = None;
-            state.is_loading_more = false;
-            state.last_query = active_query.input_value().unwrap_or_default();
-            state.active_query = active_query.clone();
-            if let Some(value) = active_query.input_value() {
-                state.input.set_value(value);
-            }
-            state.loading_state = LoadingState::Loading;
-            state.show_tree = false;
-            state.reopen_tree = reopen_tree;
-            state.scanned_total = 0;
-            state.matched_total = 0;
-            state.is_prefetching = false;
-            state.column_offset = 0;
-            state.reset_tree_scroll();
-            state.tree_line_count = 0;
-            state.tree_render_capacity = 0;
-            state.selection.clear();
-        }
+        self.reset_for_query(active_query, reopen_tree);
         ctx.invalidate();
         self.start_index_query_page(target, None, false, ctx, request_id);
     }
@@ -3305,9 +6416,11 @@ impl QueryWidget {
         let db = self.db.clone();
         let table_name = self.table_name.clone();
         let page_size = self.page_size;
+        let options = self.read_options();
         let ctx = ctx.clone();
         tokio::spawn(async move {
-            let plan = plan_for_index_target(&target);
+            let mut plan = plan_for_index_target(&target);
+            options.apply(&mut plan);
             let start_key_present = start_key.is_some();
             tracing::trace!(
                 table = %table_name,
@@ -3316,6 +6429,7 @@ impl QueryWidget {
                 start_key_present,
                 "execute_page_start"
             );
+            dynamate::throttle::acquire().await;
             let result = db
                 .query(
                     &table_name,
@@ -3325,8 +6439,11 @@ impl QueryWidget {
                         limit: Some(page_size as u32),
                     },
                 )
-                .await
-                .map_err(|err| err.to_string());
+                .await;
+            if let Ok(output) = result.as_ref() {
+                dynamate::throttle::charge(output);
+            }
+            let result = result.map_err(|err| err.to_string());
             ctx.emit_self(QueryPageEvent {
                 request_id,
                 append,
@@ -3336,6 +6453,52 @@ impl QueryWidget {
         });
     }
 
+    /// Fetch the items for a list of keys with one batch lookup, delivered as
+    /// a single page.
+    fn start_key_list_query(
+        &self,
+        keys: Arc<Vec<Key>>,
+        ctx: crate::env::WidgetCtx,
+        reopen_tree: Option<usize>,
+    ) {
+        self.maybe_start_meta_fetch(ctx.clone());
+        let request_id = self.bump_request_id();
+        tracing::debug!(
+            table = %self.table_name,
+            request_id,
+            keys = keys.len(),
+            "start_key_list_query"
+        );
+        self.reset_for_query(ActiveQuery::Keys(keys.clone()), reopen_tree);
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        let consistent_read = self.read_options().consistent_read;
+        tokio::spawn(async move {
+            dynamate::throttle::acquire().await;
+            let result = db
+                .batch_get(&table_name, keys.as_ref().clone(), consistent_read)
+                .await
+                .map(|items| QueryResult {
+                    count: items.len() as u64,
+                    items,
+                    scanned_count: None,
+                    next: None,
+                    plan_kind: PlanKind::BatchLookup,
+                    cost: None,
+                });
+            if let Ok(output) = result.as_ref() {
+                dynamate::throttle::charge(output);
+            }
+            ctx.emit_self(QueryPageEvent {
+                request_id,
+                append: false,
+                start_key_present: false,
+                result: result.map_err(|err| err.to_string()),
+            });
+        });
+    }
+
     fn format_query_value(value: &Value) -> Option<String> {
         match value {
             Value::Str(text) => serde_json::to_string(text).ok(),
@@ -3352,27 +6515,24 @@ impl QueryWidget {
     }
 
     fn bump_request_id(&self) -> u64 {
-        let next = self.request_seq.get() + 1;
-        self.request_seq.set(next);
-        next
-    }
-
-    fn next_export_id(&self) -> u64 {
-        let next = self.export_seq.get().saturating_add(1);
-        self.export_seq.set(next);
-        next
+        self.requests.start().id()
     }
 
+    /// The request a further page belongs to: the running one, or a new one
+    /// after it was cancelled.
     fn active_request_id(&self) -> u64 {
-        self.request_seq.get()
+        self.requests
+            .current()
+            .unwrap_or_else(|| self.requests.start())
+            .id()
     }
 
     fn is_request_active(&self, request_id: u64) -> bool {
-        self.active_request_id() == request_id
+        self.requests.is_current(request_id)
     }
 
     fn cancel_active_request(&self) {
-        self.bump_request_id();
+        self.requests.cancel();
         let mut state = self.state.borrow_mut();
         state.is_loading_more = false;
         state.is_prefetching = false;
@@ -3382,14 +6542,7 @@ impl QueryWidget {
     }
 
     fn request_export_cancel(&self, ctx: crate::env::WidgetCtx, show_toast: bool) {
-        let cancel = {
-            let state = self.state.borrow();
-            state.export_cancel.clone()
-        };
-        let Some(cancel) = cancel else {
-            return;
-        };
-        if !cancel.swap(true, Ordering::Relaxed) && show_toast {
+        if self.exports.cancel() && show_toast {
             ctx.show_toast(Toast {
                 message: "Canceling export...".to_string(),
                 kind: ToastKind::Info,
@@ -3416,9 +6569,31 @@ impl QueryWidget {
         });
     }
 
+    /// How far the current scan has got, measured against the table's
+    /// approximate item count when the backend reports scanned counts.
+    fn scan_progress(&self, state: &QueryState) -> String {
+        let item_count = self
+            .table_meta
+            .borrow()
+            .as_ref()
+            .and_then(|meta| meta.schema.item_count)
+            .filter(|_| self.db.capabilities().scanned_count && state.is_scan());
+        scan_progress_label(
+            state.scanned_total,
+            state.matched_total,
+            item_count,
+            state.scan_paused,
+        )
+    }
+
     fn record_query_progress(&self, output: &QueryResult) -> (i64, i64) {
         let mut state = self.state.borrow_mut();
         state.scanned_total += output.scanned_count.unwrap_or(0) as i64;
+        state.consumed_capacity += output
+            .cost
+            .as_ref()
+            .and_then(|cost| cost.capacity_units)
+            .unwrap_or(0.0);
         state.matched_total += output.count as i64;
         (state.scanned_total, state.matched_total)
     }
@@ -3432,7 +6607,7 @@ impl QueryWidget {
             .map(|item| {
                 let map = attribute_map_from_item(item);
                 item_keys.extend(map.keys().cloned());
-                Item(map)
+                Item::new(map)
             })
             .collect();
 
@@ -3445,9 +6620,11 @@ impl QueryWidget {
 
         let mut state = self.state.borrow_mut();
         if !append {
-            state.items.clear();
+            state.rows_mut().clear();
         }
-        state.items.extend(new_items);
+        let first_row = state.items.len();
+        state.pages.loaded(append, first_row, output.next.as_ref());
+        state.rows_mut().extend(new_items);
         state.last_evaluated_key.clone_from(&output.next);
         state.is_loading_more = false;
 
@@ -3457,7 +6634,20 @@ impl QueryWidget {
             state.item_keys.extend_unordered(keys_for_update);
         }
         state.query_output = Some(output);
-        state.apply_filter();
+        // Key lists have no pages to fetch again, so they are never unloaded.
+        let evicted = if matches!(state.active_query, ActiveQuery::Keys(_)) {
+            0
+        } else {
+            let loaded = state.items.len();
+            state.pages.evict(loaded, self.max_loaded_items)
+        };
+        if evicted > 0 {
+            state.drop_front_rows(evicted);
+        } else if append {
+            state.filter_appended(first_row);
+        } else {
+            state.apply_filter();
+        }
         if !append && let Some(index) = state.reopen_tree.take() {
             if state.filtered_indices.is_empty() {
                 state.show_tree = false;
@@ -3465,7 +6655,7 @@ impl QueryWidget {
             } else if let Some(pos) = state.filtered_indices.iter().position(|idx| *idx == index) {
                 state.table_state.select(Some(pos));
                 state.show_tree = true;
-                state.reset_tree_scroll();
+                state.reset_tree_view();
             } else {
                 state.show_tree = false;
                 state.table_state.select(None);
@@ -3475,6 +6665,58 @@ impl QueryWidget {
         drop(state);
     }
 
+    /// What the results footer shows after the counts: the table's size, the
+    /// query plan, the columns scrolled to (offset, end, and count), and the
+    /// view's labels.
+    fn footer_suffix(
+        &self,
+        state: &QueryState,
+        schema: Option<&CollectionSchema>,
+        columns: (usize, usize, usize),
+    ) -> String {
+        let approx_total = self
+            .table_meta
+            .borrow()
+            .as_ref()
+            .and_then(|meta| meta.schema.item_count)
+            .map(|count| format!("~{count} items"));
+        let mut footer_suffix = String::new();
+        if let Some(value) = approx_total.as_ref() {
+            footer_suffix.push_str(&format!(" · {value}"));
+        }
+        if let Some(value) = query_footer_label(
+            state.query_output.as_ref(),
+            &state.active_query,
+            schema,
+            self.input_language(),
+        ) {
+            footer_suffix.push_str(&format!(" · {value}"));
+        }
+        let (column_offset, column_end, column_count) = columns;
+        if column_count > 0 && (column_offset > 0 || column_end < column_count) {
+            footer_suffix.push_str(&format!(
+                " · cols {}-{column_end}/{column_count}",
+                column_offset + 1
+            ));
+        }
+        for label in state.view_labels() {
+            footer_suffix.push_str(&format!(" · {label}"));
+        }
+        if let Some(selection_status) = self.selection_status(state) {
+            footer_suffix.push_str(&format!(" · {selection_status}"));
+        }
+        if let Some(watch) = state.watch.as_ref() {
+            footer_suffix.push_str(&format!(" · {}", watch.status(Instant::now())));
+        }
+        if state.pages.known() > 1 {
+            let label = state
+                .pages
+                .label(state.current_page(), state.last_evaluated_key.is_some());
+            footer_suffix.push_str(&format!(" · {label}"));
+        }
+        footer_suffix
+    }
+
     fn render_table(
         &self,
         frame: &mut Frame,
@@ -3497,33 +6739,16 @@ impl QueryWidget {
             (first_item, last_item)
         };
 
-        let all_keys: Vec<String> = state.item_keys.visible().to_vec();
-        let visible_indices = if total == 0 {
-            &[][..]
+        let all_keys: Vec<String> = state.columns();
+        let visible_indices: Vec<usize> = if total == 0 {
+            Vec::new()
         } else {
             let start = state.table_state.offset();
             let end = start.saturating_add(max_rows).min(total);
-            &state.filtered_indices[start..end]
+            state.filtered_indices[start..end].to_vec()
         };
 
-        let natural_widths: Vec<usize> = all_keys
-            .iter()
-            .map(|key| {
-                let max_value = visible_indices
-                    .iter()
-                    .filter_map(|idx| state.items.get(*idx))
-                    .map(|item| item.value_size(key))
-                    .max()
-                    .unwrap_or(0);
-                let key_size = key.len() + 2;
-                max(max_value, key_size)
-            })
-            .collect();
-        let max_column_width = if state.compact_columns {
-            TABLE_MAX_COLUMN_WIDTH_COMPACT
-        } else {
-            TABLE_MAX_COLUMN_WIDTH
-        };
+        let natural_widths = state.natural_widths(&all_keys, &visible_indices);
         // The selection gutter only exists while a selection is active, so
         // the data columns reclaim its width when nothing is selected.
         let selection_active = state.selection.is_active();
@@ -3532,26 +6757,80 @@ impl QueryWidget {
         } else {
             0
         };
-        let (column_offset, fitted_widths) = fit_table_column_widths(
-            &natural_widths,
-            area.width.saturating_sub(selection_budget),
-            state.column_offset,
-            max_column_width,
-        );
+        // Tables with entity rules show each row's entity in a column of its
+        // own, ahead of the attributes.
+        let entity_width = (!state.entities.is_empty())
+            .then(|| u16::try_from(state.entities.column_width()).unwrap_or(u16::MAX));
+        let entity_budget =
+            entity_width.map_or(0, |width| width.saturating_add(TABLE_COLUMN_SPACING as u16));
+        // Key columns scrolled past stay pinned on the left, so each row can
+        // still be told apart.
+        let key_names: Vec<String> = self
+            .schema_snapshot()
+            .map(|schema| {
+                schema
+                    .key
+                    .fields
+                    .into_iter()
+                    .map(|field| field.name)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let column_offset = state.column_offset.min(all_keys.len().saturating_sub(1));
         state.column_offset = column_offset;
-        let rendered_columns = fitted_widths.len();
-        let column_end = column_offset
-            .saturating_add(rendered_columns)
-            .min(all_keys.len());
-        let keys = &all_keys[column_offset..column_end];
+        let mut shown = shown_columns(&all_keys, &key_names, column_offset);
+        let shown_widths: Vec<usize> = shown.iter().map(|idx| natural_widths[*idx]).collect();
+        let (_, fitted_widths) = fit_table_column_widths(
+            &shown_widths,
+            area.width.saturating_sub(selection_budget + entity_budget),
+            0,
+            column_widths::MAX_WIDTH,
+        );
+        shown.truncate(fitted_widths.len());
+        let column_end = shown.last().map_or(0, |idx| idx + 1);
+        let keys: Vec<String> = shown.iter().map(|idx| all_keys[*idx].clone()).collect();
         let mut widths = Vec::with_capacity(fitted_widths.len() + 1);
         let mut header_cells = Vec::with_capacity(keys.len() + 1);
         if selection_active {
             widths.push(Constraint::Length(SELECTION_GUTTER_WIDTH));
             header_cells.push(Line::from(""));
         }
+        if let Some(width) = entity_width {
+            widths.push(Constraint::Length(width));
+            header_cells.push(Line::from(entities::ENTITY_COLUMN));
+        }
+        // Cells start after the border and the highlight symbol, one column
+        // apart.
+        let mut x = area.x + 1 + 2 + entity_budget;
+        if selection_active {
+            x += SELECTION_GUTTER_WIDTH + TABLE_COLUMN_SPACING as u16;
+        }
+        state.table_area = area;
+        state.header_hits = keys
+            .iter()
+            .zip(&fitted_widths)
+            .map(|(key, width)| {
+                let start = x;
+                x = x.saturating_add(width + TABLE_COLUMN_SPACING as u16);
+                (start, start.saturating_add(*width), key.clone())
+            })
+            .collect();
         widths.extend(fitted_widths.into_iter().map(Constraint::Length));
-        header_cells.extend(keys.iter().map(|key| Line::from(key.clone())));
+        let column_sort = state.column_sort.clone();
+        let focused = (column_offset > 0).then(|| all_keys[column_offset].as_str());
+        header_cells.extend(keys.iter().map(|key| {
+            let line = match column_sort.as_ref() {
+                Some(sort) if sort.key == *key => Line::from(format!("{key} {}", sort.arrow())),
+                _ => Line::from(key.clone()),
+            };
+            // With key columns pinned in front, underline the column that
+            // copy, stats, and resizing act on.
+            if focused == Some(key.as_str()) {
+                line.patch_style(Modifier::UNDERLINED)
+            } else {
+                line
+            }
+        }));
         let header = Row::new(header_cells)
             .style(Style::new().bold().bg(theme.header_bg()).fg(theme.text()));
 
@@ -3561,52 +6840,38 @@ impl QueryWidget {
         } else {
             "end"
         };
-        let approx_total = self
-            .table_meta
-            .borrow()
-            .as_ref()
-            .and_then(|meta| meta.schema.item_count)
-            .map(|count| format!("~{count} items"));
-        let mut footer_suffix = String::new();
-        if let Some(value) = approx_total.as_ref() {
-            footer_suffix.push_str(&format!(" · {value}"));
-        }
         let schema = self
             .table_meta
             .borrow()
             .as_ref()
             .map(|meta| meta.schema.clone());
-        if let Some(value) = query_footer_label(
-            state.query_output.as_ref(),
-            &state.active_query,
+        let footer_suffix = self.footer_suffix(
+            state,
             schema.as_ref(),
-            self.input_language(),
-        ) {
-            footer_suffix.push_str(&format!(" · {value}"));
-        }
-        let has_hidden_columns =
-            !all_keys.is_empty() && (column_offset > 0 || column_end < all_keys.len());
-        if has_hidden_columns {
-            footer_suffix.push_str(&format!(
-                " · cols {}-{column_end}/{}",
-                column_offset + 1,
-                all_keys.len()
-            ));
-        }
-        if state.compact_columns {
-            footer_suffix.push_str(" · compact");
-        }
-        if let Some(selection_status) = self.selection_status(state) {
-            footer_suffix.push_str(&format!(" · {selection_status}"));
-        }
-        // Per-table browse shows the table name; the free-form SQL view, which
-        // has no single table, shows "Results".
+            (column_offset, column_end, all_keys.len()),
+        );
+        // Per-table browse shows the table name (and the index being
+        // browsed); the free-form SQL view, which has no single table, shows
+        // "Results".
         let result_label = if self.raw_sql {
-            "Results"
+            "Results".to_string()
+        } else if let Some(index) = self.scoped_index() {
+            format!(
+                "{} › {} ({})",
+                self.table_name,
+                index.name,
+                index_scope::projection_label(&index.projection)
+            )
         } else {
-            self.table_name.as_str()
+            self.table_name.clone()
         };
+        let progress = self.scan_progress(state);
         let (title, title_bottom, title_style) = match &state.loading_state {
+            LoadingState::Idle | LoadingState::Loaded if state.scan_in_progress() => (
+                format!("{result_label}{}", output_info(state.query_output.as_ref())),
+                pad(format!("{total} results · {progress}{footer_suffix}"), 2),
+                Style::default().fg(theme.text()),
+            ),
             LoadingState::Idle | LoadingState::Loaded => (
                 format!("{result_label}{}", output_info(state.query_output.as_ref())),
                 pad(
@@ -3624,13 +6889,7 @@ impl QueryWidget {
             ),
             LoadingState::Loading => (
                 "Loading".to_string(),
-                pad(
-                    format!(
-                        "scanned {} · matched {} · {}{}",
-                        state.scanned_total, state.matched_total, more_marker, footer_suffix
-                    ),
-                    2,
-                ),
+                pad(format!("{progress} · {more_marker}{footer_suffix}"), 2),
                 Style::default().fg(theme.warning()),
             ),
             LoadingState::Error(_) => (
@@ -3666,6 +6925,14 @@ impl QueryWidget {
             .enumerate()
             .map(|(row_pos, item)| {
                 let selected = self.item_is_selected(item, schema.as_ref(), selection.as_ref());
+                let changed = watch::row_changed(
+                    state.watch.as_ref(),
+                    &item.0,
+                    schema.as_ref(),
+                    row_offset + row_pos,
+                );
+                let entity = entity_width.and_then(|_| state.entities.classify(&item.0));
+                let item = state.displayed(item);
                 let mut cells: Vec<Line> = Vec::with_capacity(keys.len() + 1);
                 if selection_active {
                     cells.push(if selected {
@@ -3677,16 +6944,28 @@ impl QueryWidget {
                         Line::from(" ")
                     });
                 }
-                cells.extend(keys.iter().map(|key| Line::from(item.value(key))));
+                if entity_width.is_some() {
+                    cells.push(entity.map_or_else(Line::default, |index| {
+                        Line::from(Span::styled(
+                            state.entities.names()[index].clone(),
+                            Style::default().fg(entities::color(theme, index)),
+                        ))
+                    }));
+                }
+                cells.extend(
+                    keys.iter()
+                        .map(|key| Line::from(state.cell_text(&item, key))),
+                );
                 // Zebra striping keyed on the absolute row index so the bands
                 // stay stable while scrolling. Even rows keep the block bg
                 // (panel_bg_alt); odd rows get the subtle stripe.
-                let row = Row::new(cells);
-                if (row_offset + row_pos) % 2 == 1 {
-                    row.style(Style::default().bg(theme.row_stripe()))
-                } else {
-                    row
-                }
+                // Rows that changed since the last watch refresh stand out.
+                let stripe = (row_offset + row_pos) % 2 == 1;
+                Row::new(cells).style(Style {
+                    fg: changed.then(|| theme.warning()),
+                    bg: stripe.then(|| theme.row_stripe()),
+                    ..Style::default()
+                })
             })
             .collect();
         let visible_len = rows.len();
@@ -3768,8 +7047,12 @@ impl QueryWidget {
                 "Loading".to_string(),
                 pad(
                     format!(
-                        "scanned {} · matched {} · {}",
-                        state.scanned_total, state.matched_total, more_marker
+                        "{} · {}{}",
+                        self.scan_progress(state),
+                        more_marker,
+                        capacity_label(state.consumed_capacity)
+                            .map(|capacity| format!(" · {capacity}"))
+                            .unwrap_or_default()
                     ),
                     2,
                 ),
@@ -3782,37 +7065,194 @@ impl QueryWidget {
             ),
         };
 
-        let title_line = self.title_line(title, title_style, theme, back_title);
-        let border = match &state.loading_state {
-            LoadingState::Error(_) => Style::default().fg(theme.error()),
-            _ => Style::default().fg(theme.border()),
+        let title_line = self.title_line(title, title_style, theme, back_title);
+        let border = match &state.loading_state {
+            LoadingState::Error(_) => Style::default().fg(theme.error()),
+            _ => Style::default().fg(theme.border()),
+        };
+        let block = Block::bordered()
+            .title_top(title_line)
+            .title_bottom(Line::styled(
+                title_bottom,
+                Style::default().fg(theme.text_muted()),
+            ))
+            .border_style(border)
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        let mut area = area;
+        if state.tree.search.is_active() {
+            let [search_area, tree_area] = area.layout(&Layout::vertical([
+                Constraint::Length(3),
+                Constraint::Fill(1),
+            ]));
+            state
+                .tree
+                .search
+                .render_with_title(frame, search_area, theme, "Search");
+            area = tree_area;
+        }
+
+        let rows = tree_view::rows(&state.tree_item(), &state.tree, false);
+        let content = match &rows {
+            _ if selected_tree_item(state).is_none() => vec![Line::from("No item selected")],
+            Err(err) => vec![Line::from(format!("Failed to render item: {err}"))],
+            Ok(rows) if rows.is_empty() => vec![Line::from("(empty item)")],
+            Ok(rows) => rows
+                .iter()
+                .enumerate()
+                .map(|(idx, row)| {
+                    let line = row.to_line(theme);
+                    if idx == state.tree.cursor {
+                        line.style(
+                            Style::default()
+                                .bg(theme.selection_bg())
+                                .fg(theme.selection_fg()),
+                        )
+                    } else {
+                        line
+                    }
+                })
+                .collect(),
+        };
+        let inner_area = block.inner(area);
+        state.tree_render_capacity = inner_area.height as usize;
+        state.tree_line_count = rows.as_ref().map_or(0, Vec::len);
+        state.follow_tree_cursor();
+        let paragraph = Paragraph::new(content)
+            .block(block)
+            .scroll((state.tree_scroll_offset.min(u16::MAX as usize) as u16, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    /// Open the item the attribute in the tree `row` refers to, by the
+    /// `follow` rules in the config file, in a query view of its table.
+    /// Returns `false` when no rule covers the attribute.
+    fn follow_reference(&self, ctx: &crate::env::WidgetCtx, row: &tree::TreeRow) -> bool {
+        if self.raw_sql {
+            return false;
+        }
+        let path = row.path.clone();
+        let Some(item) = selected_tree_item(&self.state.borrow()).map(|item| item.0.clone()) else {
+            return false;
+        };
+        let Some(rule) = util::follow_rule(&self.table_name, &path) else {
+            return false;
+        };
+        let query = follow::key_conditions(&rule.key, &path, &item)
+            .and_then(|conditions| self.input_language().build_query(&conditions));
+        match query {
+            Ok(query) => ctx.push_widget(Box::new(QueryWidget::new_with_text_query(
+                self.db.clone(),
+                &rule.target,
+                &query,
+                self.inner.id(),
+            ))),
+            Err(err) => self.show_error(ctx.clone(), &format!("Can't follow {path}: {err}")),
+        }
+        true
+    }
+
+    /// Offer the type conversions for the attribute in the tree `row`.
+    fn show_convert_picker(&self, ctx: &crate::env::WidgetCtx, row: tree::TreeRow) {
+        if !self.check_editable(ctx) {
+            return;
+        }
+        let value = {
+            let state = self.state.borrow();
+            selected_tree_item(&state)
+                .and_then(|item| projection::attribute_at(&item.0, &row.path))
+                .cloned()
+        };
+        let Some(value) = value.filter(|value| !Conversion::available(value).is_empty()) else {
+            ctx.show_toast(Toast {
+                message: format!("No type conversions for {}", row.path),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(2),
+                action: None,
+            });
+            return;
+        };
+        let ctx_for_select = ctx.clone();
+        let path = row.path.clone();
+        let popup = ConvertPicker::new(
+            row.path,
+            &value,
+            move |conversion| {
+                ctx_for_select.emit_self(ConvertRequest {
+                    path: path.clone(),
+                    conversion,
+                });
+            },
+            self.inner.id(),
+        );
+        ctx.set_popup(Box::new(popup));
+    }
+
+    /// Review saving the selected item with the attribute at `path`
+    /// converted, as Dynamo JSON so the type change shows in the diff.
+    fn convert_attribute(&self, request: &ConvertRequest, ctx: crate::env::WidgetCtx) {
+        let (original, item_index, active_query) = {
+            let state = self.state.borrow();
+            let item_index = state
+                .filtered_indices
+                .get(state.table_state.selected().unwrap_or(0))
+                .copied();
+            let original = item_index
+                .and_then(|index| state.items.get(index))
+                .map(|item| item.0.clone());
+            (original, item_index, state.active_query.clone())
+        };
+        let Some(original) = original else {
+            return self.show_error(ctx, "No item selected");
         };
+        let converted = type_convert::convert_at(&original, &request.path, request.conversion)
+            .and_then(|converted| {
+                let before = json::to_dynamodb_json_string(&original);
+                let after = json::to_dynamodb_json_string(&converted);
+                before
+                    .and_then(|before| after.map(|after| (converted, before, after)))
+                    .map_err(|err| err.to_string())
+            });
+        match converted {
+            Ok((item, before, after)) => self.review_edit(
+                &before,
+                &after,
+                SaveEditRequest {
+                    original,
+                    item,
+                    active_query,
+                    reopen_tree: item_index,
+                },
+                ctx,
+            ),
+            Err(err) => self.show_error(ctx, &format!("Can't convert {}: {err}", request.path)),
+        }
+    }
+
+    /// The split-layout pane: the selected row as a tree, from the top.
+    fn render_preview(&self, frame: &mut Frame, area: Rect, theme: &Theme, state: &QueryState) {
         let block = Block::bordered()
-            .title_top(title_line)
-            .title_bottom(Line::styled(
-                title_bottom,
+            .title_top(Line::styled(
+                pad("Preview", 1),
                 Style::default().fg(theme.text_muted()),
             ))
-            .border_style(border)
+            .border_style(Style::default().fg(theme.border()))
             .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
-
-        let selected = state.table_state.selected().unwrap_or(0);
         let content = state
-            .filtered_indices
-            .get(selected)
+            .table_state
+            .selected()
+            .and_then(|selected| state.filtered_indices.get(selected))
             .and_then(|idx| state.items.get(*idx))
             .map_or_else(
-                || vec![Line::from("No item selected")],
+                || {
+                    vec![Line::styled(
+                        "No item selected",
+                        Style::default().fg(theme.text_muted()),
+                    )]
+                },
                 |item| tree::item_to_lines(&item.0, theme, Some(state.item_keys.sorted())),
             );
-        let inner_area = block.inner(area);
-        state.tree_render_capacity = inner_area.height as usize;
-        state.tree_line_count = content.len();
-        state.clamp_tree_offset();
-        let paragraph = Paragraph::new(content)
-            .block(block)
-            .scroll((state.tree_scroll_offset.min(u16::MAX as usize) as u16, 0));
-        frame.render_widget(paragraph, area);
+        frame.render_widget(Paragraph::new(content).block(block), area);
     }
 
     fn item_view_title(&self, state: &QueryState) -> String {
@@ -3937,12 +7377,225 @@ impl QueryWidget {
         }
     }
 
-    fn edit_selected(&self, format: EditorFormat, ctx: crate::env::WidgetCtx) {
+    fn copy_selected_item(&self, ctx: &crate::env::WidgetCtx) {
+        let item = match self.selected_item() {
+            Ok(item) => item,
+            Err(err) => {
+                self.show_error(ctx.clone(), &err);
+                return;
+            }
+        };
+        if let Err(err) = clipboard::copy_item(ctx, &item) {
+            self.show_error(ctx.clone(), &err);
+        }
+    }
+
+    /// Copy the selected row's value in the first visible column (the one
+    /// ←/→ scroll to).
+    fn copy_selected_cell(&self, ctx: &crate::env::WidgetCtx) {
+        let item = match self.selected_item() {
+            Ok(item) => item,
+            Err(err) => {
+                self.show_error(ctx.clone(), &err);
+                return;
+            }
+        };
+        let (column, item) = {
+            let state = self.state.borrow();
+            let item = match state.projection.as_ref() {
+                Some(projection) => projection.apply(&item),
+                None => item,
+            };
+            (state.columns().get(state.column_offset).cloned(), item)
+        };
+        let Some(column) = column else {
+            self.show_error(ctx.clone(), "No column to copy");
+            return;
+        };
+        let Some(value) = projection::attribute_at(&item, &column) else {
+            self.show_error(ctx.clone(), &format!("Selected item has no {column}"));
+            return;
+        };
+        if let Err(err) = clipboard::copy_cell(ctx, &column, value) {
+            self.show_error(ctx.clone(), &err);
+        }
+    }
+
+    /// The selected item's size, attribute by attribute.
+    fn show_size_breakdown(&self, ctx: &crate::env::WidgetCtx) {
+        match self.selected_item() {
+            Ok(item) => ctx.set_popup(Box::new(SizePopup::new(
+                attribute_sizes(&item),
+                self.inner.id(),
+            ))),
+            Err(err) => self.show_error(ctx.clone(), &err),
+        }
+    }
+
+    /// Stats for the first visible column over the loaded rows that match the
+    /// filter.
+    fn show_column_stats(&self, ctx: &crate::env::WidgetCtx) {
+        let (column, stats, partial) = {
+            let state = self.state.borrow();
+            let Some(column) = state.columns().get(state.column_offset).cloned() else {
+                drop(state);
+                self.show_error(ctx.clone(), "No column to aggregate");
+                return;
+            };
+            let mut stats = ColumnStats::default();
+            stats.add_items(
+                &column,
+                state
+                    .filtered_indices
+                    .iter()
+                    .filter_map(|index| state.items.get(*index))
+                    .map(|item| state.displayed(item).into_owned().0)
+                    .collect::<Vec<_>>()
+                    .iter(),
+            );
+            (column, stats, state.last_evaluated_key.is_some())
+        };
+        self.show_aggregate(&column, stats, partial, ctx);
+    }
+
+    fn show_aggregate(
+        &self,
+        column: &str,
+        stats: ColumnStats,
+        partial: bool,
+        ctx: &crate::env::WidgetCtx,
+    ) {
+        let ctx_for_fetch = ctx.clone();
+        let column_for_fetch = column.to_string();
+        ctx.set_popup(Box::new(AggregatePopup::new(
+            column,
+            stats,
+            partial,
+            move || {
+                ctx_for_fetch.emit_self(AggregateRequest {
+                    column: column_for_fetch.clone(),
+                });
+            },
+            self.inner.id(),
+        )));
+    }
+
+    /// Recompute the stats of `column` over every item the query matches,
+    /// fetching the pages that aren't loaded yet.
+    fn aggregate_all(&self, column: String, ctx: crate::env::WidgetCtx) {
+        let segments = self.parallel_segments();
+        let (request, mut stats, projection) = {
+            let state = self.state.borrow();
+            let Some(start_key) = state.last_evaluated_key.clone() else {
+                return;
+            };
+            let mut stats = ColumnStats::default();
+            // A parallel scan refetches the loaded pages too.
+            if segments.is_none() {
+                stats.add_items(
+                    &column,
+                    state
+                        .filtered_indices
+                        .iter()
+                        .filter_map(|index| state.items.get(*index))
+                        .map(|item| state.displayed(item).into_owned().0)
+                        .collect::<Vec<_>>()
+                        .iter(),
+                );
+            }
+            let request = BatchActionStreamRequest {
+                scope: BatchActionScope::Results {
+                    filter: (!state.row_filter.is_empty()).then(|| state.row_filter.clone()),
+                },
+                start_key,
+                segments,
+                active_query: state.active_query.clone(),
+                parameters: state.parameters.clone(),
+                options: self.read_options(),
+                db: self.db.clone(),
+                table_name: self.table_name.clone(),
+                cancel: None,
+            };
+            (request, stats, state.projection.clone())
+        };
+        ctx.show_toast(Toast {
+            message: format!("Computing {column} over all items…"),
+            kind: ToastKind::Info,
+            duration: Duration::from_secs(3),
+            action: None,
+        });
+        tokio::spawn(async move {
+            let mut stream = batch_action_stream(request);
+            let mut result = Ok(());
+            while let Some(batch) = stream.next().await {
+                match batch {
+                    Ok(items) => {
+                        stats.add_items(&column, &project_items(projection.as_ref(), items));
+                    }
+                    Err(err) => {
+                        result = Err(err);
+                        break;
+                    }
+                }
+            }
+            ctx.emit_self(AggregateEvent {
+                column,
+                result: result.map(|()| stats),
+            });
+        });
+    }
+
+    fn copy_query(&self, ctx: &crate::env::WidgetCtx) {
+        let query = self
+            .state
+            .borrow()
+            .active_query
+            .input_value()
+            .unwrap_or_default();
+        clipboard::copy_query(ctx, &query);
+    }
+
+    fn copy_table_arn(&self, ctx: &crate::env::WidgetCtx) {
         if self.raw_sql {
             return;
         }
+        clipboard::copy_table_arn(self.db.clone(), self.table_name.clone(), ctx);
+    }
+
+    /// Whether the loaded items can be saved back whole, else why not.
+    fn check_editable(&self, ctx: &crate::env::WidgetCtx) -> bool {
+        if self.raw_sql {
+            return false;
+        }
         if self.db.is_read_only() {
-            show_readonly_toast(&ctx);
+            show_readonly_toast(ctx);
+            return false;
+        }
+        if !self.state.borrow().options.projection.is_empty() {
+            // Saving a partial item could drop the attributes that weren't fetched.
+            self.show_error(
+                ctx.clone(),
+                "Items only have the projected attributes; clear the projection (o) to edit",
+            );
+            return false;
+        }
+        if let Some(index) = self.scoped_index()
+            && index.projection != Projection::All
+        {
+            self.show_error(
+                ctx.clone(),
+                &format!(
+                    "Items only have the attributes projected into {}; browse the table (I) to edit",
+                    index.name
+                ),
+            );
+            return false;
+        }
+        true
+    }
+
+    fn edit_selected(&self, format: EditorFormat, ctx: crate::env::WidgetCtx) {
+        if !self.check_editable(&ctx) {
             return;
         }
         let (item, active_query, reopen_tree) = {
@@ -3997,33 +7650,28 @@ impl QueryWidget {
             });
         }
 
-        let edited = match self.open_editor(&initial, ctx.clone()) {
-            Ok(value) => value,
-            Err(err) => {
-                self.set_loading_state(LoadingState::Error(err.clone()));
-                self.show_error(ctx.clone(), &err);
-                ctx.invalidate();
-                return;
-            }
-        };
-        ctx.invalidate();
-
-        let updated = match actual_format {
-            EditorFormat::Plain => json::from_json_string(&edited),
-            EditorFormat::DynamoDb => json::from_dynamodb_json_string(&edited),
-        };
-        let updated = match updated {
-            Ok(value) => value,
-            Err(err) => {
-                let message = err.to_string();
-                self.set_loading_state(LoadingState::Error(message.clone()));
-                self.show_error(ctx.clone(), &message);
-                ctx.invalidate();
-                return;
-            }
-        };
+        self.edit_text(
+            &initial.clone(),
+            EditTarget::Item {
+                original: item,
+                initial,
+                format: actual_format,
+                active_query,
+                reopen_tree,
+            },
+            ctx,
+        );
+    }
 
-        if updated == item {
+    /// Offer to save an edited item, showing what changed.
+    fn review_edit(
+        &self,
+        initial: &str,
+        edited: &str,
+        request: SaveEditRequest,
+        ctx: crate::env::WidgetCtx,
+    ) {
+        if request.item == request.original {
             ctx.show_toast(Toast {
                 message: "Item unchanged".to_string(),
                 kind: ToastKind::Info,
@@ -4032,11 +7680,90 @@ impl QueryWidget {
             });
             return;
         }
+        if !self.check_item_key(&request.item, &ctx) {
+            return;
+        }
+
+        let ctx_for_save = ctx.clone();
+        let popup = Box::new(DiffPopup::new(
+            "Save changes?",
+            initial,
+            edited,
+            move || ctx_for_save.emit_self(request.clone()),
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    /// Whether `item` holds the table's key attributes with their types, on
+    /// backends that require it. Otherwise the error names the attribute, so
+    /// the write isn't left to fail with the backend's validation message.
+    fn check_item_key(
+        &self,
+        item: &HashMap<String, AttributeValue>,
+        ctx: &crate::env::WidgetCtx,
+    ) -> bool {
+        if !self.db.capabilities().typed_keys {
+            return true;
+        }
+        let Some(schema) = self.schema_snapshot() else {
+            return true;
+        };
+        match check_key(&item_from_attribute_map(item), &schema) {
+            Ok(()) => true,
+            Err(message) => {
+                self.show_error(ctx.clone(), &message);
+                ctx.invalidate();
+                false
+            }
+        }
+    }
 
-        self.put_item(updated, active_query, PutAction::Update, ctx, reopen_tree);
+    /// Save an edited item. Backends that can update attributes in place only
+    /// get the changed attributes, so concurrent writes to other attributes
+    /// (or ones the query projected out) survive; otherwise, or when the edit
+    /// changed the key, the whole item is put.
+    fn save_edit(&self, request: &SaveEditRequest, ctx: crate::env::WidgetCtx) {
+        let partial = self
+            .schema_snapshot()
+            .filter(|_| self.db.capabilities().partial_update)
+            .and_then(|schema| {
+                let original = item_from_attribute_map(&request.original);
+                let edited = item_from_attribute_map(&request.item);
+                let update = ItemUpdate::between(&original, &edited, &schema.key)?;
+                let key: value::Item = schema
+                    .key
+                    .fields
+                    .iter()
+                    .filter_map(|field| {
+                        original
+                            .get(&field.name)
+                            .map(|value| (field.name.clone(), value.clone()))
+                    })
+                    .collect();
+                (!key.is_empty()).then_some((Key(key), update))
+            });
+        match partial {
+            Some((key, update)) => self.update_item(
+                key,
+                update,
+                request.active_query.clone(),
+                ctx,
+                request.reopen_tree,
+            ),
+            None => self.put_item(
+                request.item.clone(),
+                request.active_query.clone(),
+                PutAction::Update,
+                ctx,
+                request.reopen_tree,
+            ),
+        }
     }
 
-    fn create_item(&self, format: EditorFormat, ctx: crate::env::WidgetCtx) {
+    /// Offer the templates a new item can start from, or go straight to the
+    /// editor when there's only the empty one.
+    fn pick_item_template(&self, format: EditorFormat, ctx: crate::env::WidgetCtx) {
         if self.raw_sql {
             return;
         }
@@ -4044,55 +7771,230 @@ impl QueryWidget {
             show_readonly_toast(&ctx);
             return;
         }
+        let templates = {
+            let schema = self.schema_snapshot();
+            let saved = util::item_templates(&self.table_name)
+                .map(|template| (template.name.clone(), template.item.clone()))
+                .collect();
+            let state = self.state.borrow();
+            item_templates(
+                schema.as_ref(),
+                saved,
+                state.items.iter().map(|item| &item.0),
+            )
+        };
+        if templates.len() == 1 {
+            self.create_item(format, &HashMap::new(), ctx);
+            return;
+        }
+        let title = match format {
+            EditorFormat::Plain => "New item from",
+            EditorFormat::DynamoDb => "New item (Dynamo JSON) from",
+        };
+        let ctx_for_select = ctx.clone();
+        let popup = Box::new(TemplatePicker::new(
+            title,
+            templates,
+            move |item| ctx_for_select.emit_self(ItemTemplateEvent { format, item }),
+            self.inner.id(),
+        ));
+        ctx.set_popup(popup);
+    }
+
+    fn create_item(
+        &self,
+        format: EditorFormat,
+        template: &HashMap<String, AttributeValue>,
+        ctx: crate::env::WidgetCtx,
+    ) {
         let active_query = self.state.borrow().active_query.clone();
+        // Plain JSON can't hold every type; such templates open as Dynamo JSON.
         let initial = match format {
-            EditorFormat::Plain => "{}\n".to_string(),
-            EditorFormat::DynamoDb => "{}\n".to_string(),
+            EditorFormat::Plain => json::to_json_string(template)
+                .map(|value| (value, EditorFormat::Plain))
+                .or_else(|_| {
+                    json::to_dynamodb_json_string(template)
+                        .map(|value| (value, EditorFormat::DynamoDb))
+                }),
+            EditorFormat::DynamoDb => {
+                json::to_dynamodb_json_string(template).map(|value| (value, EditorFormat::DynamoDb))
+            }
+        };
+        let (initial, format) = match initial {
+            Ok((value, format)) => (format!("{value}\n"), format),
+            Err(err) => {
+                let message = err.to_string();
+                self.set_loading_state(LoadingState::Error(message.clone()));
+                self.show_error(ctx.clone(), &message);
+                ctx.invalidate();
+                return;
+            }
+        };
+
+        self.edit_text(
+            &initial,
+            EditTarget::NewItem {
+                format,
+                active_query,
+            },
+            ctx,
+        );
+    }
+
+    /// Ask to create an item written in the editor.
+    fn confirm_create(
+        &self,
+        updated: HashMap<String, AttributeValue>,
+        active_query: ActiveQuery,
+        ctx: crate::env::WidgetCtx,
+    ) {
+        if !self.check_item_key(&updated, &ctx) {
+            return;
+        }
+
+        let ctx_for_create = ctx.clone();
+        ConfirmPopup::new_with_action(
+            "Create item",
+            format!("Table={}", self.table_name),
+            "Create",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Char('s'),
+                KeyModifiers::CONTROL,
+                "^s",
+                "create",
+                "Create the item",
+            ),
+            move || {
+                ctx_for_create.emit_self(CreateItemRequest {
+                    item: updated.clone(),
+                    active_query: active_query.clone(),
+                });
+            },
+            self.inner.id(),
+        )
+        .show(&ctx, Action::Write, &self.table_name);
+    }
+
+    /// Open `text` in the editor. An external one suspends the TUI until
+    /// it's closed; the built-in one is a popup that reports back with an
+    /// [`EditedTextEvent`]. Either way [`Self::finish_edit`] carries on.
+    fn edit_text(&self, text: &str, target: EditTarget, ctx: crate::env::WidgetCtx) {
+        let command = match util::editor() {
+            Editor::Command(command) => command,
+            Editor::Builtin => {
+                let title = match &target {
+                    EditTarget::Query => "Edit query",
+                    EditTarget::Item { .. } => "Edit item",
+                    EditTarget::NewItem { .. } => "New item",
+                };
+                let json = !matches!(target, EditTarget::Query);
+                let ctx_for_save = ctx.clone();
+                let popup = Box::new(TextEditorPopup::new(
+                    title,
+                    text,
+                    json,
+                    move |text| {
+                        ctx_for_save.emit_self(EditedTextEvent {
+                            target: target.clone(),
+                            text,
+                        });
+                    },
+                    self.inner.id(),
+                ));
+                ctx.set_popup(popup);
+                return;
+            }
         };
-
-        let edited = match self.open_editor(&initial, ctx.clone()) {
-            Ok(value) => value,
+        let extension = match target {
+            EditTarget::Query => "txt",
+            EditTarget::Item { .. } | EditTarget::NewItem { .. } => "json",
+        };
+        let edited = self.open_editor(&command, text, extension, ctx.clone());
+        ctx.invalidate();
+        match edited {
+            Ok(edited) => self.finish_edit(target, &edited, ctx),
             Err(err) => {
-                self.set_loading_state(LoadingState::Error(err.clone()));
+                if !matches!(target, EditTarget::Query) {
+                    self.set_loading_state(LoadingState::Error(err.clone()));
+                }
                 self.show_error(ctx.clone(), &err);
                 ctx.invalidate();
+            }
+        }
+    }
+
+    /// Carry on with text saved in the editor. When an item's JSON doesn't
+    /// parse, the editor reopens on it under a comment naming the error, so
+    /// the edit isn't lost; saving it with nothing but the comment gives up.
+    fn finish_edit(&self, target: EditTarget, saved: &str, ctx: crate::env::WidgetCtx) {
+        let format = match &target {
+            EditTarget::Query => {
+                self.set_query_from_editor(saved);
                 return;
             }
+            EditTarget::Item { format, .. } | EditTarget::NewItem { format, .. } => *format,
         };
-        ctx.invalidate();
-
-        let updated = match format {
-            EditorFormat::Plain => json::from_json_string(&edited),
-            EditorFormat::DynamoDb => json::from_dynamodb_json_string(&edited),
+        let edited = strip_error_comment(saved);
+        let parsed = match format {
+            EditorFormat::Plain => json::from_json_string(edited),
+            EditorFormat::DynamoDb => json::from_dynamodb_json_string(edited),
         };
-        let updated = match updated {
-            Ok(value) => value,
-            Err(err) => {
+        let updated = match parsed {
+            Ok(item) => item,
+            Err(err) if edited.trim().is_empty() => {
                 let message = err.to_string();
                 self.set_loading_state(LoadingState::Error(message.clone()));
                 self.show_error(ctx.clone(), &message);
                 ctx.invalidate();
                 return;
             }
+            Err(err) => {
+                self.edit_text(&with_error_comment(&err.to_string(), edited), target, ctx);
+                return;
+            }
         };
-
-        self.put_item(updated, active_query, PutAction::Create, ctx, None);
+        match target {
+            EditTarget::Item {
+                original,
+                initial,
+                active_query,
+                reopen_tree,
+                ..
+            } => self.review_edit(
+                &initial,
+                edited,
+                SaveEditRequest {
+                    original,
+                    item: updated,
+                    active_query,
+                    reopen_tree,
+                },
+                ctx,
+            ),
+            EditTarget::NewItem { active_query, .. } => {
+                self.confirm_create(updated, active_query, ctx);
+            }
+            EditTarget::Query => {}
+        }
     }
 
-    fn open_editor(&self, initial: &str, ctx: crate::env::WidgetCtx) -> Result<String, String> {
-        let editor = env::var("EDITOR").map_err(|_| "EDITOR is not set".to_string())?;
-        let temp_path = self.temp_path();
+    fn open_editor(
+        &self,
+        editor: &str,
+        initial: &str,
+        extension: &str,
+        ctx: crate::env::WidgetCtx,
+    ) -> Result<String, String> {
+        let temp_path = self.temp_path(extension);
         fs::write(&temp_path, initial).map_err(|err| err.to_string())?;
-        let restore_mouse_capture = env_flag("DYNAMATE_MOUSE_CAPTURE");
+        let restore_mouse_capture = mouse_capture();
 
         disable_raw_mode().map_err(|err| err.to_string())?;
         crossterm::execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
             .map_err(|err| err.to_string())?;
 
-        let command = format!("{editor} \"{}\"", temp_path.display());
-        let status = Command::new("sh")
-            .arg("-c")
-            .arg(command)
+        let status = util::editor_command(editor, &temp_path)
             .status()
             .map_err(|err| err.to_string())?;
 
@@ -4119,13 +8021,13 @@ impl QueryWidget {
         Ok(contents)
     }
 
-    fn temp_path(&self) -> std::path::PathBuf {
+    fn temp_path(&self, extension: &str) -> std::path::PathBuf {
         let mut path = env::temp_dir();
         let nanos = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|duration| duration.as_nanos())
             .unwrap_or_default();
-        path.push(format!("dynamate-edit-{nanos}.json"));
+        path.push(format!("dynamate-edit-{nanos}.{extension}"));
         path
     }
 
@@ -4141,6 +8043,14 @@ impl QueryWidget {
             show_readonly_toast(&ctx);
             return;
         }
+        if transaction::is_staging() {
+            let write = TransactWrite::Put {
+                collection: self.table_name.clone(),
+                item: item_from_attribute_map(&item),
+            };
+            self.stage_write(&item, write, &ctx);
+            return;
+        }
         self.set_loading_state(LoadingState::Loading);
         ctx.invalidate();
         let db = self.db.clone();
@@ -4159,22 +8069,159 @@ impl QueryWidget {
             });
         });
     }
+
+    fn update_item(
+        &self,
+        key: Key,
+        update: ItemUpdate,
+        active_query: ActiveQuery,
+        ctx: crate::env::WidgetCtx,
+        reopen_tree: Option<usize>,
+    ) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        if transaction::is_staging() {
+            let key_source = attribute_map_from_item(&key.0);
+            let write = TransactWrite::Update {
+                collection: self.table_name.clone(),
+                key,
+                update,
+            };
+            self.stage_write(&key_source, write, &ctx);
+            return;
+        }
+        self.set_loading_state(LoadingState::Loading);
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        tokio::spawn(async move {
+            let event_result = db
+                .update_item(&table_name, key, &update)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(PutItemEvent {
+                active_query,
+                reopen_tree,
+                action: PutAction::Update,
+                result: event_result,
+            });
+        });
+    }
+}
+
+/// The row the tree view shows: the table's selected row.
+fn selected_tree_item(state: &QueryState) -> Option<&Item> {
+    let selected = state.table_state.selected().unwrap_or(0);
+    state
+        .filtered_indices
+        .get(selected)
+        .and_then(|idx| state.items.get(*idx))
 }
 
 /// A browse-view help line tuned to the backend: drops the index-picker entry
-/// for backends that don't support index queries (e.g. SQL).
+/// for backends that don't support index queries (e.g. SQL), and likewise
+/// the options, bulk-update, key-list, and transaction entries.
 fn browse_help(
     entries: &'static [help::Entry<'static>],
-    index_query: bool,
+    capabilities: &Capabilities,
+) -> Vec<help::Entry<'static>> {
+    entries
+        .iter()
+        .filter(|entry| capabilities.index_query || entry.short.as_ref() != "indexes")
+        .filter(|entry| capabilities.read_options || entry.short.as_ref() != "options")
+        .filter(|entry| capabilities.partial_update || entry.short.as_ref() != "update matching")
+        .filter(|entry| capabilities.batch_get || entry.short.as_ref() != "get keys")
+        .filter(|entry| capabilities.transactions || entry.short.as_ref() != "stage")
+        .cloned()
+        .collect()
+}
+
+/// The hint under the query input in PartiQL mode. The statement is validated
+/// by the service, so this only flags statements that write.
+fn partiql_hint_line(value: &str, theme: &Theme) -> Line<'static> {
+    if value.trim().is_empty() {
+        return Line::from(Span::styled(
+            "  PartiQL: SELECT * FROM \"table\" WHERE …".to_string(),
+            Style::default().fg(theme.text_muted()),
+        ));
+    }
+    if partiql::is_mutating(value) {
+        return Line::from(vec![
+            Span::styled("  ⚠ ".to_string(), Style::default().fg(theme.warning())),
+            Span::styled("PartiQL".to_string(), Style::default().fg(theme.warning())),
+            Span::styled(
+                " — this statement modifies items".to_string(),
+                Style::default().fg(theme.text_muted()),
+            ),
+        ]);
+    }
+    Line::from(vec![
+        Span::styled("  ✓ ".to_string(), Style::default().fg(theme.success())),
+        Span::styled("PartiQL".to_string(), Style::default().fg(theme.success())),
+    ])
+}
+
+/// The query-input help, without the PartiQL toggle when the backend has no
+/// statement language.
+fn query_edit_help(
+    entries: &'static [help::Entry<'static>],
+    statements: bool,
 ) -> Vec<help::Entry<'static>> {
     entries
         .iter()
-        .filter(|entry| index_query || entry.short.as_ref() != "indexes")
+        .filter(|entry| statements || entry.short.as_ref() != "partiql")
         .cloned()
         .collect()
 }
 
-fn show_readonly_toast(ctx: &crate::env::WidgetCtx) {
+/// The note put above JSON that didn't parse, for the next round in the
+/// editor. The line the parser points at is moved down past the note.
+fn with_error_comment(error: &str, text: &str) -> String {
+    const NOTE_LINES: usize = 3;
+    let error = match error.rsplit_once(" at line ") {
+        Some((message, position)) => {
+            let digits = position
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(position.len());
+            match position[..digits].parse::<usize>() {
+                Ok(line) => format!(
+                    "{message} at line {}{}",
+                    line + NOTE_LINES,
+                    &position[digits..]
+                ),
+                Err(_) => error.to_string(),
+            }
+        }
+        None => error.to_string(),
+    };
+    format!(
+        "// This doesn't parse: {error}\n\
+         // Fix it and save, or delete everything and save to give up.\n\
+         // Lines starting with // at the top are ignored.\n{text}"
+    )
+}
+
+/// `text` without the comment lines at its top.
+fn strip_error_comment(text: &str) -> &str {
+    let mut rest = text;
+    while rest.trim_start_matches([' ', '\t']).starts_with("//") {
+        rest = rest.split_once('\n').map_or("", |(_, rest)| rest);
+    }
+    rest
+}
+
+/// `text` with its lines joined by spaces and the trailing newline dropped.
+fn single_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub(super) fn show_readonly_toast(ctx: &crate::env::WidgetCtx) {
     ctx.show_toast(Toast {
         message: dynamate::readonly::REJECT_MESSAGE.to_string(),
         kind: ToastKind::Warning,
@@ -4194,13 +8241,25 @@ fn plan_for_index_target(target: &index_picker::IndexTarget) -> QueryPlan {
 }
 
 /// Wrap raw query text into a plan; an empty query scans. The backend parses the
-/// text in its own query language.
-fn text_query_plan(query: &str) -> QueryPlan {
+/// text in its own query language and binds `parameters` to its placeholders.
+/// A duration as loosely as an estimate deserves.
+fn approximate_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs().div_ceil(60);
+    match minutes {
+        0 | 1 => "about a minute".to_string(),
+        2..=90 => format!("about {minutes} minutes"),
+        _ => format!("about {} hours", minutes.div_ceil(60)),
+    }
+}
+
+fn text_query_plan(query: &str, parameters: &[(String, String)]) -> QueryPlan {
     let query = query.trim();
     if query.is_empty() {
         QueryPlan::default()
     } else {
-        QueryPlan::new(Some(query.to_string()), None)
+        let mut plan = QueryPlan::new(Some(query.to_string()), None);
+        plan.parameters = parameters.to_vec();
+        plan
     }
 }
 
@@ -4245,24 +8304,6 @@ fn item_has_index_keys(item: &Item, index: &IndexSchema) -> bool {
         .all(|field| item.0.contains_key(&field.name))
 }
 
-fn item_matches_filter(item: &HashMap<String, AttributeValue>, needle: &str) -> bool {
-    for (key, value) in item {
-        if key.to_lowercase().contains(needle) {
-            return true;
-        }
-        let value = match value {
-            AttributeValue::S(v) => v.clone(),
-            AttributeValue::N(v) => v.clone(),
-            AttributeValue::Bool(v) => v.to_string(),
-            _ => format!("{value:?}"),
-        };
-        if value.to_lowercase().contains(needle) {
-            return true;
-        }
-    }
-    false
-}
-
 fn format_ttl_value(value: &AttributeValue) -> Option<String> {
     let text = match value {
         AttributeValue::N(num) => num,
@@ -4273,16 +8314,14 @@ fn format_ttl_value(value: &AttributeValue) -> Option<String> {
     if ts <= 0 {
         return None;
     }
-    let time = UNIX_EPOCH + Duration::from_secs(ts as u64);
-    let dt: DateTime<Utc> = time.into();
-    Some(dt.format("%Y-%m-%d %H:%M:%SZ").to_string())
+    DateTime::from_timestamp(ts, 0).map(time_format::format_utc)
 }
 
 const BATCH_ACTION_CANCELED: &str = "Batch action canceled";
 
 enum BatchActionScope {
     Results {
-        filter: Option<String>,
+        filter: Option<RowFilter>,
     },
     Selection {
         selection: SelectionSnapshot,
@@ -4300,8 +8339,8 @@ impl BatchActionScope {
                 .iter()
                 .filter(|item| {
                     filter
-                        .as_deref()
-                        .is_none_or(|needle| item_matches_filter(item, needle))
+                        .as_ref()
+                        .is_none_or(|filter| filter.matches(item, &row_filter::search_text(item)))
                 })
                 .cloned()
                 .collect()),
@@ -4322,10 +8361,15 @@ impl BatchActionScope {
 struct BatchActionStreamRequest {
     scope: BatchActionScope,
     start_key: Cursor,
+    /// Refetch the whole scan in this many parallel segments instead of
+    /// continuing from `start_key`.
+    segments: Option<u32>,
     active_query: ActiveQuery,
+    parameters: Vec<(String, String)>,
+    options: QueryOptions,
     db: Arc<dyn Datastore>,
     table_name: String,
-    cancel: Option<Arc<AtomicBool>>,
+    cancel: Option<RequestHandle>,
 }
 
 struct DeleteSelectionJob {
@@ -4334,19 +8378,29 @@ struct DeleteSelectionJob {
     schema: CollectionSchema,
     start_key: Option<Cursor>,
     active_query: ActiveQuery,
+    parameters: Vec<(String, String)>,
+    options: QueryOptions,
     db: Arc<dyn Datastore>,
     table_name: String,
 }
 
-fn plan_for_active_query(active_query: &ActiveQuery) -> QueryPlan {
-    match active_query {
-        ActiveQuery::Text(query) => text_query_plan(query),
+fn plan_for_active_query(
+    active_query: &ActiveQuery,
+    parameters: &[(String, String)],
+    options: &QueryOptions,
+) -> QueryPlan {
+    let mut plan = match active_query {
+        ActiveQuery::Text(query) => text_query_plan(query, parameters),
         ActiveQuery::Index(target) => plan_for_index_target(target),
-    }
+        // Never paged or bulk-changed; `bulk_query_label` turns key lists away.
+        ActiveQuery::Keys(_) => QueryPlan::new(None, None),
+    };
+    options.apply(&mut plan);
+    plan
 }
 
-fn batch_action_was_canceled(cancel: Option<&Arc<AtomicBool>>) -> bool {
-    cancel.is_some_and(|flag| flag.load(Ordering::Relaxed))
+fn batch_action_was_canceled(cancel: Option<&RequestHandle>) -> bool {
+    cancel.is_some_and(RequestHandle::is_cancelled)
 }
 
 fn batch_action_stream(
@@ -4368,7 +8422,10 @@ async fn stream_batch_action_pages(
     let BatchActionStreamRequest {
         scope,
         start_key,
+        segments,
         active_query,
+        parameters,
+        options,
         db,
         table_name,
         cancel,
@@ -4378,12 +8435,44 @@ async fn stream_batch_action_pages(
         return Err(BATCH_ACTION_CANCELED.to_string());
     }
 
-    let plan = plan_for_active_query(&active_query);
+    let plan = plan_for_active_query(&active_query, &parameters, &options);
+    if let Some(total_segments) = segments {
+        let scope = Arc::new(scope);
+        let plan = Arc::new(plan);
+        let tasks: Vec<_> = (0..total_segments)
+            .map(|segment| {
+                tokio::spawn(stream_scan_segment(
+                    ScanSegmentJob {
+                        scope: scope.clone(),
+                        plan: plan.clone(),
+                        db: db.clone(),
+                        table_name: table_name.clone(),
+                        cancel: cancel.clone(),
+                        segment: (segment, total_segments),
+                    },
+                    tx.clone(),
+                ))
+            })
+            .collect();
+        let mut result = Ok(());
+        for task in tasks {
+            if result.is_err() {
+                task.abort();
+                continue;
+            }
+            result = task
+                .await
+                .map_err(|err| err.to_string())
+                .and_then(|done| done);
+        }
+        return result;
+    }
     let mut next_key = Some(start_key);
     while let Some(cursor) = next_key {
         if batch_action_was_canceled(cancel.as_ref()) {
             return Err(BATCH_ACTION_CANCELED.to_string());
         }
+        dynamate::throttle::acquire().await;
         let output = db
             .query(
                 &table_name,
@@ -4395,6 +8484,7 @@ async fn stream_batch_action_pages(
             )
             .await
             .map_err(|err| err.to_string())?;
+        dynamate::throttle::charge(&output);
         let page_items: Vec<HashMap<String, AttributeValue>> =
             output.items.iter().map(attribute_map_from_item).collect();
         let items = scope.collect_page(&page_items)?;
@@ -4410,12 +8500,76 @@ async fn stream_batch_action_pages(
     Ok(())
 }
 
+struct ScanSegmentJob {
+    scope: Arc<BatchActionScope>,
+    plan: Arc<QueryPlan>,
+    db: Arc<dyn Datastore>,
+    table_name: String,
+    cancel: Option<RequestHandle>,
+    segment: (u32, u32),
+}
+
+/// Page through one segment of a parallel scan, sending each page's matches.
+async fn stream_scan_segment(
+    job: ScanSegmentJob,
+    tx: mpsc::Sender<Result<Vec<HashMap<String, AttributeValue>>, String>>,
+) -> Result<(), String> {
+    let (segment, total_segments) = job.segment;
+    let mut cursor = None;
+    loop {
+        if batch_action_was_canceled(job.cancel.as_ref()) {
+            return Err(BATCH_ACTION_CANCELED.to_string());
+        }
+        dynamate::throttle::acquire().await;
+        let output = job
+            .db
+            .scan_segment(
+                &job.table_name,
+                &job.plan,
+                segment,
+                total_segments,
+                Page {
+                    cursor,
+                    limit: None,
+                },
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        dynamate::throttle::charge(&output);
+        let page_items: Vec<HashMap<String, AttributeValue>> =
+            output.items.iter().map(attribute_map_from_item).collect();
+        let items = job.scope.collect_page(&page_items)?;
+        if !items.is_empty() && tx.send(Ok(items)).await.is_err() {
+            return Ok(());
+        }
+        cursor = output.next;
+        if cursor.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+fn export_progress(count: usize) -> String {
+    format!(
+        "Exporting... {} item{}",
+        count,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+fn count_matches_progress(summary: CountSummary) -> String {
+    format!(
+        "Counting matches... {} of {} scanned items match (esc cancels)",
+        summary.matched, summary.scanned
+    )
+}
+
 async fn export_batch_to_path(
     path: PathBuf,
     items: Vec<HashMap<String, AttributeValue>>,
     stream_request: Option<BatchActionStreamRequest>,
-    ctx: crate::env::WidgetCtx,
-    export_id: u64,
+    projection: Option<RowProjection>,
+    job: &JobHandle,
 ) -> Result<usize, String> {
     let cancel = stream_request
         .as_ref()
@@ -4424,7 +8578,7 @@ async fn export_batch_to_path(
         return Err("Export canceled".to_string());
     }
 
-    let mut writer = StreamedJsonArrayWriter::create(&path)?;
+    let mut writer = ExportWriter::create(&path)?;
     writer.write_items(&items)?;
     let mut count = items.len();
     if let Some(request) = stream_request {
@@ -4437,9 +8591,10 @@ async fn export_batch_to_path(
                     err
                 }
             })?;
+            let items = project_items(projection.as_ref(), items);
             writer.write_items(&items)?;
             count = count.saturating_add(items.len());
-            ctx.emit_self(ExportProgressEvent { export_id, count });
+            job.progress(export_progress(count));
         }
     }
     if batch_action_was_canceled(cancel.as_ref()) {
@@ -4455,6 +8610,8 @@ async fn delete_selection_full(request: DeleteSelectionJob) -> Result<usize, Str
         schema,
         start_key,
         active_query,
+        parameters,
+        options,
         db,
         table_name,
     } = request;
@@ -4472,7 +8629,10 @@ async fn delete_selection_full(request: DeleteSelectionJob) -> Result<usize, Str
                 schema: Box::new(schema.clone()),
             },
             start_key,
+            segments: None,
             active_query,
+            parameters,
+            options,
             db: db.clone(),
             table_name: table_name.clone(),
             cancel: None,
@@ -4491,6 +8651,26 @@ async fn delete_selection_full(request: DeleteSelectionJob) -> Result<usize, Str
     Ok(deleted)
 }
 
+/// The loaded results plus every page after them.
+async fn collect_all_results(
+    mut items: Vec<value::Item>,
+    request: Option<BatchActionStreamRequest>,
+) -> Result<Vec<value::Item>, String> {
+    let Some(request) = request else {
+        return Ok(items);
+    };
+    let mut stream = batch_action_stream(request);
+    while let Some(batch) = stream.next().await {
+        items.extend(batch?.iter().map(item_from_attribute_map));
+        if items.len() > MAX_DIFF_ITEMS {
+            return Err(format!(
+                "The query matches more than {MAX_DIFF_ITEMS} items"
+            ));
+        }
+    }
+    Ok(items)
+}
+
 async fn batch_delete_keys(
     db: &Arc<dyn Datastore>,
     table_name: &str,
@@ -4510,10 +8690,39 @@ async fn batch_delete_keys(
     Ok(outcome.deleted as usize)
 }
 
+/// Streams pages of items to disk in the format chosen by the file extension.
+enum ExportWriter {
+    Json(StreamedJsonArrayWriter),
+    Ndjson(StreamedNdjsonWriter),
+}
+
+impl ExportWriter {
+    fn create(path: &Path) -> Result<Self, String> {
+        match ExportFormat::from_path(path) {
+            ExportFormat::Json => StreamedJsonArrayWriter::create(path).map(Self::Json),
+            ExportFormat::Ndjson => StreamedNdjsonWriter::create(path).map(Self::Ndjson),
+        }
+    }
+
+    fn write_items(&mut self, items: &[HashMap<String, AttributeValue>]) -> Result<(), String> {
+        match self {
+            Self::Json(writer) => writer.write_items(items),
+            Self::Ndjson(writer) => writer.write_items(items),
+        }
+    }
+
+    fn finish(self) -> Result<usize, String> {
+        match self {
+            Self::Json(writer) => writer.finish(),
+            Self::Ndjson(writer) => writer.finish(),
+        }
+    }
+}
+
 struct StreamedJsonArrayWriter {
     path: PathBuf,
     temp_path: PathBuf,
-    writer: Option<BufWriter<File>>,
+    writer: Option<ExportFile>,
     count: usize,
 }
 
@@ -4521,16 +8730,16 @@ impl StreamedJsonArrayWriter {
     fn create(path: &Path) -> Result<Self, String> {
         ensure_export_parent(path)?;
         let temp_path = export_temp_path(path);
-        let file = File::create(&temp_path).map_err(|err| err.to_string())?;
+        let writer = ExportFile::create(&temp_path, path)?;
         Ok(Self {
             path: path.to_path_buf(),
             temp_path,
-            writer: Some(BufWriter::new(file)),
+            writer: Some(writer),
             count: 0,
         })
     }
 
-    fn writer(&mut self) -> Result<&mut BufWriter<File>, String> {
+    fn writer(&mut self) -> Result<&mut ExportFile, String> {
         self.writer
             .as_mut()
             .ok_or_else(|| "Export writer is closed".to_string())
@@ -4566,8 +8775,7 @@ impl StreamedJsonArrayWriter {
         } else {
             writer.write_all(b"\n]").map_err(|err| err.to_string())?;
         }
-        writer.flush().map_err(|err| err.to_string())?;
-        drop(writer);
+        writer.finish()?;
         #[cfg(windows)]
         if self.path.exists() {
             fs::remove_file(&self.path).map_err(|err| err.to_string())?;
@@ -4586,6 +8794,18 @@ impl Drop for StreamedJsonArrayWriter {
     }
 }
 
+/// Apply the results projection, if any, to items about to be exported or
+/// aggregated.
+fn project_items(
+    projection: Option<&RowProjection>,
+    items: Vec<HashMap<String, AttributeValue>>,
+) -> Vec<HashMap<String, AttributeValue>> {
+    match projection {
+        Some(projection) => items.iter().map(|item| projection.apply(item)).collect(),
+        None => items,
+    }
+}
+
 fn export_item_to_path(
     item: &HashMap<String, AttributeValue>,
     path: &Path,
@@ -4599,6 +8819,11 @@ fn export_results_to_path(
     items: &[HashMap<String, AttributeValue>],
     path: &Path,
 ) -> Result<usize, String> {
+    if ExportFormat::from_path(path) == ExportFormat::Ndjson {
+        let mut writer = StreamedNdjsonWriter::create(path)?;
+        writer.write_items(items)?;
+        return writer.finish();
+    }
     let values = items_to_json_values(items)?;
     write_json_to_path(path, &serde_json::Value::Array(values))?;
     Ok(items.len())
@@ -4633,7 +8858,7 @@ where
     Ok(())
 }
 
-fn ensure_export_parent(path: &Path) -> Result<(), String> {
+pub(super) fn ensure_export_parent(path: &Path) -> Result<(), String> {
     if let Some(parent) = path.parent()
         && !parent.as_os_str().is_empty()
     {
@@ -4659,8 +8884,10 @@ fn export_temp_path(path: &Path) -> PathBuf {
 fn write_json_to_path(path: &Path, value: &serde_json::Value) -> Result<(), String> {
     ensure_export_parent(path)?;
     let payload = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
-    fs::write(path, payload).map_err(|err| err.to_string())?;
-    Ok(())
+    let mut file = ExportFile::create(path, path)?;
+    file.write_all(payload.as_bytes())
+        .map_err(|err| err.to_string())?;
+    file.finish()
 }
 
 fn export_base_dir() -> PathBuf {
@@ -4773,11 +9000,57 @@ fn sanitize_query_component(raw: &str) -> Option<String> {
     }
 }
 
+/// The capacity consumed by the current query so far, with the throttle
+/// budget when one is set. `None` when the backend reports no capacity.
+fn capacity_label(consumed: f64) -> Option<String> {
+    let budget = dynamate::throttle::budget();
+    if consumed <= 0.0 && budget.is_none() {
+        return None;
+    }
+    let consumed = format!("{consumed:.1}");
+    let consumed = consumed.strip_suffix(".0").unwrap_or(&consumed);
+    Some(match budget {
+        Some(budget) => format!("consumed {consumed} RCU (max {budget})"),
+        None => format!("consumed {consumed} RCU"),
+    })
+}
+
+/// Scanned and matched counts, led by a gauge of the scanned share of
+/// `item_count` when it is known. The count is an estimate DynamoDB refreshes
+/// every few hours, so the gauge stops short of full until the scan ends.
+fn scan_progress_label(
+    scanned: i64,
+    matched: i64,
+    item_count: Option<i64>,
+    paused: bool,
+) -> String {
+    const GAUGE_WIDTH: i64 = 16;
+    let mut label = match item_count {
+        Some(count) if count > 0 => {
+            let percent = (scanned.max(0) * 100 / count).min(99);
+            let filled = percent * GAUGE_WIDTH / 100;
+            format!(
+                "{}{} {percent}% · scanned {scanned} · matched {matched}",
+                "█".repeat(filled as usize),
+                "░".repeat((GAUGE_WIDTH - filled) as usize)
+            )
+        }
+        _ => format!("scanned {scanned} · matched {matched}"),
+    };
+    if paused {
+        label.push_str(" · paused (p resumes)");
+    }
+    label
+}
+
 fn output_info(output: Option<&QueryResult>) -> String {
     match output.map(|result| &result.plan_kind) {
         Some(PlanKind::Scan) => " (Scan)".to_string(),
         Some(PlanKind::IndexedQuery { index: None }) => " (Query)".to_string(),
         Some(PlanKind::IndexedQuery { index: Some(name) }) => format!(" (Query: {name})"),
+        Some(PlanKind::Lookup) => " (GetItem)".to_string(),
+        Some(PlanKind::BatchLookup) => " (BatchGetItem)".to_string(),
+        Some(PlanKind::Statement) => " (PartiQL)".to_string(),
         None => String::new(),
     }
 }
@@ -4792,6 +9065,20 @@ fn query_footer_label(
         Some(PlanKind::Scan) => "scan".to_string(),
         Some(PlanKind::IndexedQuery { index: None }) => "query".to_string(),
         Some(PlanKind::IndexedQuery { index: Some(name) }) => format!("query@{name}"),
+        Some(PlanKind::Lookup) => "get".to_string(),
+        Some(PlanKind::BatchLookup) => {
+            return Some(match active_query {
+                ActiveQuery::Keys(keys) => format!("get {} keys", keys.len()),
+                _ => "get".to_string(),
+            });
+        }
+        // Statements aren't in the expression language; show them verbatim.
+        Some(PlanKind::Statement) => {
+            return Some(match active_query.input_value() {
+                Some(text) if !text.trim().is_empty() => format!("partiql {}", text.trim()),
+                _ => "partiql".to_string(),
+            });
+        }
         None => return None,
     };
     match normalized_query(active_query, schema, language) {
@@ -4825,6 +9112,17 @@ fn collect_attribute_values(items: &[Item], attr: &str) -> Vec<String> {
     out
 }
 
+/// The columns the table shows, as indices into `columns`: those from
+/// `offset` on, after any key columns scrolled past.
+fn shown_columns(columns: &[String], key_names: &[String], offset: usize) -> Vec<usize> {
+    let pinned = columns[..offset]
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| key_names.contains(column))
+        .map(|(idx, _)| idx);
+    pinned.chain(offset..columns.len()).collect()
+}
+
 fn fit_table_column_widths(
     natural_widths: &[usize],
     area_width: u16,
@@ -4913,6 +9211,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn error_comment_round_trips_and_points_past_itself() {
+        let text = with_error_comment(
+            "failed to parse JSON value: trailing comma at line 3 column 1",
+            "{\n  \"a\": 1,\n}\n",
+        );
+        assert!(text.starts_with(
+            "// This doesn't parse: failed to parse JSON value: trailing comma at line 6 column 1\n"
+        ));
+        assert_eq!(text.lines().nth(5), Some("}"));
+        assert_eq!(strip_error_comment(&text), "{\n  \"a\": 1,\n}\n");
+        assert_eq!(
+            with_error_comment("invalid number: x", "{}").lines().next(),
+            Some("// This doesn't parse: invalid number: x")
+        );
+        assert_eq!(
+            strip_error_comment("{}\n// not a note\n"),
+            "{}\n// not a note\n"
+        );
+    }
+
+    #[test]
+    fn single_line_joins_an_edited_query() {
+        assert_eq!(
+            single_line("PK = \"USER#1\"\n  AND status = \"OPEN\"  \n\n"),
+            "PK = \"USER#1\"   AND status = \"OPEN\""
+        );
+        assert_eq!(single_line("\n"), "");
+    }
+
     #[test]
     fn sanitize_export_component_rewrites_invalid_chars() {
         assert_eq!(sanitize_export_component("My Table"), "my_table");
@@ -5155,6 +9483,16 @@ mod tests {
         assert_eq!(fitted, vec![8]);
     }
 
+    #[test]
+    fn shown_columns_pin_scrolled_past_keys() {
+        let columns: Vec<String> = ["pk", "sk", "a", "b", "c"].map(String::from).to_vec();
+        let keys = ["pk".to_string(), "sk".to_string()];
+        assert_eq!(shown_columns(&columns, &keys, 0), vec![0, 1, 2, 3, 4]);
+        assert_eq!(shown_columns(&columns, &keys, 1), vec![0, 1, 2, 3, 4]);
+        assert_eq!(shown_columns(&columns, &keys, 3), vec![0, 1, 3, 4]);
+        assert_eq!(shown_columns(&columns, &[], 3), vec![3, 4]);
+    }
+
     #[test]
     fn fit_table_column_widths_compact_mode_reduces_column_width() {
         let widths = vec![80];
@@ -5162,6 +9500,76 @@ mod tests {
         assert_eq!(fitted, vec![TABLE_MAX_COLUMN_WIDTH_COMPACT as u16]);
     }
 
+    #[test]
+    fn filter_narrows_as_the_text_grows() {
+        let row = |name: &str| {
+            Item::new(HashMap::from([(
+                "name".to_string(),
+                AttributeValue::S(name.to_string()),
+            )]))
+        };
+        let mut state = QueryState {
+            items: vec![row("Ada"), row("Adele"), row("Bob")],
+            ..QueryState::default()
+        };
+        state.filter.value = "ad".to_string();
+        state.refine_filter();
+        assert_eq!(state.filtered_indices, vec![0, 1]);
+        state.filter.value = "ade".to_string();
+        state.refine_filter();
+        assert_eq!(state.filtered_indices, vec![1]);
+        state.filter.value = "b".to_string();
+        state.refine_filter();
+        assert_eq!(state.filtered_indices, vec![2]);
+        // A match can't run from a name into its value.
+        state.filter.value = "eb".to_string();
+        state.refine_filter();
+        assert!(state.filtered_indices.is_empty());
+    }
+
+    #[test]
+    fn changed_rows_are_measured_again() {
+        let row = |name: &str| {
+            Item::new(HashMap::from([(
+                "name".to_string(),
+                AttributeValue::S(name.to_string()),
+            )]))
+        };
+        let mut state = QueryState {
+            items: vec![row("ab"), row("cd")],
+            ..QueryState::default()
+        };
+        let columns = ["name".to_string()];
+        let before = state.natural_widths(&columns, &[0, 1]);
+        state.rows_mut()[1] = row("a much longer name");
+        let after = state.natural_widths(&columns, &[0, 1]);
+        assert!(after[0] > before[0], "{before:?} -> {after:?}");
+    }
+
+    #[test]
+    fn dropping_front_rows_keeps_the_selected_row() {
+        let row = |id: usize| {
+            Item::new(HashMap::from([(
+                "id".to_string(),
+                AttributeValue::N(id.to_string()),
+            )]))
+        };
+        let mut state = QueryState {
+            items: (0..6).map(row).collect(),
+            ..QueryState::default()
+        };
+        state.apply_filter();
+        state.rows_mut().extend((6..8).map(row));
+        state.filter_appended(6);
+        assert_eq!(state.filtered_indices, (0..8).collect::<Vec<_>>());
+        state.table_state.select(Some(5));
+
+        state.drop_front_rows(4);
+        assert_eq!(state.items.len(), 4);
+        assert_eq!(state.table_state.selected(), Some(1));
+        assert_eq!(state.items[1].0, row(5).0);
+    }
+
     #[test]
     fn clamp_tree_offset_limits_scroll_to_last_visible_page() {
         let mut state = QueryState {
@@ -5194,4 +9602,49 @@ mod tests {
         state.page_tree_up();
         assert_eq!(state.tree_scroll_offset, 3);
     }
+
+    #[test]
+    fn scan_progress_gauges_scanned_share_of_item_count() {
+        assert_eq!(
+            scan_progress_label(250, 3, Some(1000), false),
+            "████░░░░░░░░░░░░ 25% · scanned 250 · matched 3"
+        );
+        assert_eq!(
+            scan_progress_label(1200, 9, Some(1000), true),
+            "███████████████░ 99% · scanned 1200 · matched 9 · paused (p resumes)"
+        );
+        assert_eq!(
+            scan_progress_label(40, 2, None, false),
+            "scanned 40 · matched 2"
+        );
+    }
+
+    #[test]
+    fn capacity_label_rounds_consumed_units() {
+        assert_eq!(capacity_label(0.0), None);
+        assert_eq!(capacity_label(12.0).as_deref(), Some("consumed 12 RCU"));
+        assert_eq!(capacity_label(2.54).as_deref(), Some("consumed 2.5 RCU"));
+    }
+
+    #[test]
+    fn column_sort_cycles_and_orders_numbers_numerically() {
+        let sort = ColumnSort::cycle(None, "n").unwrap();
+        assert!(!sort.descending);
+        let descending = ColumnSort::cycle(Some(&sort), "n").unwrap();
+        assert!(descending.descending);
+        assert_eq!(ColumnSort::cycle(Some(&descending), "n"), None);
+        assert_eq!(ColumnSort::cycle(Some(&descending), "m").unwrap().key, "m");
+
+        let item = |n: Option<&str>| {
+            let mut map = HashMap::new();
+            if let Some(n) = n {
+                map.insert("n".to_string(), AttributeValue::N(n.to_string()));
+            }
+            Item::new(map)
+        };
+        let mut items = [item(Some("10")), item(None), item(Some("9"))];
+        items.sort_by(|a, b| sort.compare(a, b));
+        let values: Vec<_> = items.iter().map(|item| item.value("n")).collect();
+        assert_eq!(values, ["9", "10", ""]);
+    }
 }
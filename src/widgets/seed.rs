@@ -0,0 +1,239 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph, Wrap},
+};
+
+use dynamate::core::{
+    schema::KeySchema,
+    seed::{DEFAULT_TEMPLATES, SeedSpec, parse_templates},
+};
+
+use super::create_table::TextInput;
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Most items one seeding run writes from the TUI.
+const MAX_COUNT: u64 = 1_000_000;
+
+/// Asks how many items to generate for a table and what their attributes
+/// look like.
+pub struct SeedPopup {
+    inner: WidgetInner,
+    table_name: String,
+    key: KeySchema,
+    state: RefCell<SeedState>,
+    on_submit: Box<dyn Fn(SeedSpec, u64) + Send + 'static>,
+}
+
+struct SeedState {
+    count: TextInput,
+    templates: TextInput,
+    /// Whether the templates input has focus rather than the count.
+    templates_focused: bool,
+    error: Option<String>,
+}
+
+impl SeedPopup {
+    const HELP: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("seed"),
+            long: Cow::Borrowed("Write the generated items"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⇥"),
+            short: Cow::Borrowed("next field"),
+            long: Cow::Borrowed("Switch between count and attributes"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("cancel"),
+            long: Cow::Borrowed("Cancel"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    pub fn new(
+        table_name: &str,
+        key: KeySchema,
+        on_submit: impl Fn(SeedSpec, u64) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let mut count = TextInput::new("Items", "100");
+        count.set_active(true);
+        let templates = TextInput::new("Attributes (name=template, …)", DEFAULT_TEMPLATES);
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            table_name: table_name.to_string(),
+            key,
+            state: RefCell::new(SeedState {
+                count,
+                templates,
+                templates_focused: false,
+                error: None,
+            }),
+            on_submit: Box::new(on_submit),
+        }
+    }
+
+    fn submit(&self, ctx: &crate::env::WidgetCtx) {
+        let parsed = {
+            let state = self.state.borrow();
+            parse_seed(&self.key, state.count.value(), state.templates.value())
+        };
+        match parsed {
+            Ok((spec, count)) => {
+                ctx.dismiss_popup();
+                (self.on_submit)(spec, count);
+            }
+            Err(err) => self.state.borrow_mut().error = Some(err),
+        }
+    }
+}
+
+fn parse_seed(key: &KeySchema, count: &str, templates: &str) -> Result<(SeedSpec, u64), String> {
+    let count = count
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|count| (1..=MAX_COUNT).contains(count))
+        .ok_or_else(|| format!("Items must be a number from 1 to {MAX_COUNT}"))?;
+    let spec = SeedSpec::new(key, parse_templates(templates)?)?;
+    Ok((spec, count))
+}
+
+impl crate::widgets::Widget for SeedPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(Self::HELP)
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad(format!("Seed {}", self.table_name), 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let inner = block.inner(area).inner(Margin::new(1, 0));
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(2),
+        ]);
+        let [count_area, templates_area, status_area] = inner.layout(&layout);
+
+        let state = self.state.borrow();
+        state.count.render(frame, count_area, theme);
+        state.templates.render(frame, templates_area, theme);
+        let (status, style) = match state.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(theme.error())),
+            None => (
+                "Placeholders: {uuid} {seq} {int:1..100} {float} {lorem:3} {bool} {pick:a|b} {timestamp}",
+                Style::default().fg(theme.text_muted()),
+            ),
+        };
+        frame.render_widget(
+            Paragraph::new(status)
+                .style(style)
+                .wrap(Wrap { trim: true }),
+            status_area,
+        );
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        match key.code {
+            KeyCode::Enter => self.submit(&ctx),
+            KeyCode::Esc => ctx.dismiss_popup(),
+            KeyCode::Tab | KeyCode::BackTab => {
+                let mut state = self.state.borrow_mut();
+                let focused = !state.templates_focused;
+                state.templates_focused = focused;
+                state.count.set_active(!focused);
+                state.templates.set_active(focused);
+            }
+            _ => {
+                let mut state = self.state.borrow_mut();
+                let changed = if state.templates_focused {
+                    state.templates.handle_event(event)
+                } else {
+                    state.count.handle_event(event)
+                };
+                if changed {
+                    state.error = None;
+                }
+            }
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for SeedPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = 88.min(area.width.saturating_sub(4));
+        // Two inputs, the status lines, and the borders.
+        let height = 10.min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::schema::{KeyField, KeyRole, KeySchema, ScalarType};
+
+    use super::parse_seed;
+
+    #[test]
+    fn count_and_templates_are_validated() {
+        let key = KeySchema {
+            fields: vec![KeyField {
+                name: "id".to_string(),
+                role: KeyRole::Partition,
+                ty: ScalarType::Number,
+            }],
+        };
+        assert_eq!(parse_seed(&key, " 20 ", "n={int}").unwrap().1, 20);
+        assert!(parse_seed(&key, "0", "n={int}").is_err());
+        assert!(parse_seed(&key, "ten", "n={int}").is_err());
+        assert!(parse_seed(&key, "10", "id=x{uuid}").is_err());
+    }
+}
@@ -0,0 +1,883 @@
+//! A full-screen view of one table's description: key schema, indexes with
+//! their projections and status, capacity, streams, TTL, and backups. Opened
+//! with `i` from the table picker; `^r` refreshes. On backends that manage
+//! indexes, GSIs can be added (`^n`) and dropped (`^d`) here; `c` edits the
+//! billing mode and capacity, and `b` opens the table's backups. Global
+//! tables list their replica regions; `g` adds one and `^d` removes the
+//! selected one. The view polls while a table, index, or replica change is in
+//! progress.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    sync::Arc,
+    time::Duration,
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+};
+
+use dynamate::core::capabilities::Capabilities;
+use dynamate::core::datastore::Datastore;
+use dynamate::core::describe::{self, LABEL_WIDTH, Section, SectionTarget};
+use dynamate::core::schema::{CollectionDetails, IndexKind};
+
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    safety::Action,
+    util::{self, pad},
+    widgets::{
+        WidgetInner,
+        backups::{BackupsWidget, NamePopup},
+        capacity::{CapacityChangedEvent, CapacityPopup},
+        confirm::{ConfirmAction, ConfirmPopup},
+        create_index::{CreateIndexPopup, IndexChangedEvent},
+        theme::Theme,
+    },
+};
+
+/// How often to re-describe the table while an index or table change is in
+/// progress.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct TableDetailsWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    table_name: String,
+    state: RefCell<DetailsState>,
+    scroll: Cell<usize>,
+    /// Visible content rows and total rows, recorded on render for clamping.
+    viewport: Cell<usize>,
+    content_len: Cell<usize>,
+    /// Set when the selection moved, so the next render scrolls it into view.
+    follow_selection: Cell<bool>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Debug, Default)]
+struct DetailsState {
+    loading: bool,
+    details: Option<CollectionDetails>,
+    error: Option<String>,
+    /// The GSI or replica that `^d` removes, by name so it survives reloads.
+    selected: Option<SectionTarget>,
+    /// Whether a poll is scheduled; keeps manual refreshes from stacking
+    /// extra polls.
+    poll_pending: bool,
+}
+
+struct DetailsLoadedEvent {
+    result: Result<CollectionDetails, String>,
+}
+
+struct PollTick;
+
+struct DropIndexRequest {
+    index_name: String,
+}
+
+struct IndexDroppedEvent {
+    index_name: String,
+    result: Result<(), String>,
+}
+
+struct ReplicaRequest {
+    region: String,
+    add: bool,
+}
+
+struct ReplicaUpdatedEvent {
+    region: String,
+    add: bool,
+    result: Result<(), String>,
+}
+
+impl TableDetailsWidget {
+    pub fn new(db: Arc<dyn Datastore>, table_name: &str, parent: crate::env::WidgetId) -> Self {
+        let help_entries = build_help(db.capabilities());
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            table_name: table_name.to_string(),
+            state: RefCell::new(DetailsState::default()),
+            scroll: Cell::new(0),
+            viewport: Cell::new(0),
+            content_len: Cell::new(0),
+            follow_selection: Cell::new(false),
+            help_entries,
+        }
+    }
+
+    fn reload(&self, ctx: crate::env::WidgetCtx) {
+        self.state.borrow_mut().loading = true;
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        tokio::spawn(async move {
+            let result = db
+                .describe_details(&table_name)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(DetailsLoadedEvent { result });
+        });
+    }
+
+    /// Re-describe the table after `POLL_INTERVAL` if something is still
+    /// changing and no poll is already scheduled.
+    fn schedule_poll(&self, ctx: crate::env::WidgetCtx) {
+        let mut state = self.state.borrow_mut();
+        let changing = state.details.as_ref().is_some_and(changes_pending);
+        if !changing || state.poll_pending {
+            return;
+        }
+        state.poll_pending = true;
+        tokio::spawn(async move {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            ctx.emit_self(PollTick);
+        });
+    }
+
+    fn manages_indexes(&self) -> bool {
+        self.db.capabilities().index_management
+    }
+
+    fn manages_replicas(&self) -> bool {
+        self.db.capabilities().replicas
+    }
+
+    /// What tab cycles through: the GSIs and replicas this backend can
+    /// remove.
+    fn targets(&self, details: Option<&CollectionDetails>) -> Vec<SectionTarget> {
+        let mut targets = Vec::new();
+        if self.manages_indexes() {
+            targets.extend(gsi_names(details).into_iter().map(SectionTarget::Index));
+        }
+        if self.manages_replicas() {
+            targets.extend(
+                replica_regions(details)
+                    .into_iter()
+                    .map(SectionTarget::Replica),
+            );
+        }
+        targets
+    }
+
+    /// Move the selection forward or backward, wrapping around.
+    fn select_target(&self, forward: bool) -> bool {
+        let mut state = self.state.borrow_mut();
+        let targets = self.targets(state.details.as_ref());
+        if targets.is_empty() {
+            return false;
+        }
+        let current = state
+            .selected
+            .as_ref()
+            .and_then(|selected| targets.iter().position(|target| target == selected));
+        let next = match current {
+            Some(idx) if forward => (idx + 1) % targets.len(),
+            Some(idx) => (idx + targets.len() - 1) % targets.len(),
+            None if forward => 0,
+            None => targets.len() - 1,
+        };
+        state.selected = Some(targets[next].clone());
+        self.follow_selection.set(true);
+        true
+    }
+
+    fn show_create_index(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let index_id = gsi_names(self.state.borrow().details.as_ref()).len();
+        ctx.set_popup(Box::new(CreateIndexPopup::new(
+            self.db.clone(),
+            &self.table_name,
+            index_id,
+            self.inner.id(),
+        )));
+    }
+
+    fn show_capacity(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        ctx.set_popup(Box::new(CapacityPopup::new(
+            self.db.clone(),
+            &self.table_name,
+            self.inner.id(),
+        )));
+    }
+
+    /// Ask for a region to replicate the table to, suggesting a configured
+    /// one it isn't in yet.
+    fn show_add_replica(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let existing = replica_regions(self.state.borrow().details.as_ref());
+        let suggested = util::regions()
+            .iter()
+            .find(|region| !existing.contains(region))
+            .cloned()
+            .unwrap_or_default();
+        let ctx_for_submit = ctx.clone();
+        ctx.set_popup(Box::new(NamePopup::new(
+            format!("Replicate {}", self.table_name),
+            "Region",
+            &suggested,
+            "The region to add a replica in, e.g. eu-west-1",
+            move |region| ctx_for_submit.emit_self(ReplicaRequest { region, add: true }),
+            self.inner.id(),
+        )));
+    }
+
+    /// `^d`: drop the selected GSI or remove the selected replica.
+    fn confirm_remove(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let selected = self.state.borrow().selected.clone();
+        let index_name = match selected {
+            Some(SectionTarget::Index(index_name)) => index_name,
+            Some(SectionTarget::Replica(region)) => {
+                self.confirm_remove_replica(ctx, region);
+                return;
+            }
+            None => {
+                let what = match (self.manages_indexes(), self.manages_replicas()) {
+                    (true, true) => "a GSI or replica",
+                    (false, true) => "a replica",
+                    _ => "a GSI",
+                };
+                ctx.show_toast(Toast {
+                    message: format!("Select {what} with tab first"),
+                    kind: ToastKind::Warning,
+                    duration: Duration::from_secs(3),
+                    action: None,
+                });
+                return;
+            }
+        };
+        let message = format!("Table={}\nIndex={index_name}", self.table_name);
+        let subject = index_name.clone();
+        let ctx_for_action = ctx.clone();
+        ConfirmPopup::new_with_action(
+            "Drop index",
+            message,
+            "Drop",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Char('d'),
+                KeyModifiers::CONTROL,
+                "^d",
+                "drop",
+                "Drop index",
+            ),
+            move || {
+                ctx_for_action.emit_self(DropIndexRequest {
+                    index_name: index_name.clone(),
+                });
+            },
+            self.inner.id(),
+        )
+        .show(&ctx, Action::DropIndex, &subject);
+    }
+
+    fn confirm_remove_replica(&self, ctx: crate::env::WidgetCtx, region: String) {
+        let message = format!(
+            "Table={}\nReplica={region}\nThe table and its items in {region} are deleted.",
+            self.table_name
+        );
+        let subject = region.clone();
+        let ctx_for_action = ctx.clone();
+        ConfirmPopup::new_with_action(
+            "Remove replica",
+            message,
+            "Remove",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Char('d'),
+                KeyModifiers::CONTROL,
+                "^d",
+                "remove",
+                "Remove replica",
+            ),
+            move || {
+                ctx_for_action.emit_self(ReplicaRequest {
+                    region: region.clone(),
+                    add: false,
+                });
+            },
+            self.inner.id(),
+        )
+        .show(&ctx, Action::RemoveReplica, &subject);
+    }
+
+    fn update_replica(&self, ctx: crate::env::WidgetCtx, request: &ReplicaRequest) {
+        self.state.borrow_mut().loading = true;
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        let (region, add) = (request.region.clone(), request.add);
+        tokio::spawn(async move {
+            let result = if add {
+                db.add_replica(&table_name, &region).await
+            } else {
+                db.remove_replica(&table_name, &region).await
+            }
+            .map_err(|err| err.to_string());
+            ctx.emit_self(ReplicaUpdatedEvent {
+                region,
+                add,
+                result,
+            });
+        });
+    }
+
+    fn on_replica_updated(&self, ctx: crate::env::WidgetCtx, updated: &ReplicaUpdatedEvent) {
+        match updated.result.as_ref() {
+            Ok(()) => {
+                let verb = if updated.add { "Adding" } else { "Removing" };
+                ctx.show_toast(Toast {
+                    message: format!("{verb} replica {} of {}", updated.region, self.table_name),
+                    kind: ToastKind::Info,
+                    duration: Duration::from_secs(3),
+                    action: None,
+                });
+                self.reload(ctx);
+            }
+            Err(err) => {
+                self.state.borrow_mut().loading = false;
+                let verb = if updated.add { "add" } else { "remove" };
+                ctx.show_toast(Toast {
+                    message: format!("Failed to {verb} replica {}: {err}", updated.region),
+                    kind: ToastKind::Error,
+                    duration: Duration::from_secs(4),
+                    action: None,
+                });
+                ctx.invalidate();
+            }
+        }
+    }
+
+    fn drop_index(&self, ctx: crate::env::WidgetCtx, index_name: String) {
+        self.state.borrow_mut().loading = true;
+        ctx.invalidate();
+        let db = self.db.clone();
+        let table_name = self.table_name.clone();
+        tokio::spawn(async move {
+            let result = db
+                .drop_index(&table_name, &index_name)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(IndexDroppedEvent { index_name, result });
+        });
+    }
+
+    fn scroll_by(&self, delta: isize) -> bool {
+        let max = self.content_len.get().saturating_sub(self.viewport.get());
+        let current = self.scroll.get() as isize;
+        let next = (current + delta).clamp(0, max as isize);
+        if next == current {
+            return false;
+        }
+        self.scroll.set(next as usize);
+        true
+    }
+}
+
+impl crate::widgets::Widget for TableDetailsWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.reload(ctx);
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some(format!("{} details", self.table_name))
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            context: Some(self.table_name.clone()),
+            mode: Some("DETAILS".to_string()),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().loading
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let state = self.state.borrow();
+        let mut heading = format!("Details: {}", self.table_name);
+        if state.poll_pending {
+            heading.push_str(" · changes in progress, refreshing");
+        }
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        let (lines, selected_line) = match (&state.details, &state.error) {
+            (_, Some(err)) => (
+                vec![Line::styled(
+                    err.clone(),
+                    Style::default().fg(theme.error()),
+                )],
+                None,
+            ),
+            (Some(details), None) => {
+                section_lines(&describe::sections(details), state.selected.as_ref(), theme)
+            }
+            (None, None) => (
+                vec![Line::styled(
+                    "Loading table details...",
+                    Style::default().fg(theme.warning()),
+                )],
+                None,
+            ),
+        };
+        drop(state);
+
+        let inner = block.inner(area);
+        let height = inner.height as usize;
+        self.content_len.set(lines.len());
+        self.viewport.set(height);
+        if self.follow_selection.replace(false)
+            && let Some(line) = selected_line
+        {
+            let scroll = self.scroll.get();
+            if line < scroll || line >= scroll + height {
+                self.scroll.set(line);
+            }
+        }
+        let max = lines.len().saturating_sub(height);
+        if self.scroll.get() > max {
+            self.scroll.set(max);
+        }
+        let body = Paragraph::new(lines)
+            .scroll((self.scroll.get() as u16, 0))
+            .block(block);
+        frame.render_widget(body, area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let page = self.viewport.get().max(1) as isize;
+        let scrolled = match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.reload(ctx);
+                return true;
+            }
+            KeyCode::Esc => {
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('n')
+                if key.modifiers.contains(KeyModifiers::CONTROL) && self.manages_indexes() =>
+            {
+                self.show_create_index(ctx);
+                return true;
+            }
+            KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.manages_indexes() || self.manages_replicas()) =>
+            {
+                self.confirm_remove(ctx);
+                return true;
+            }
+            KeyCode::Char('g') if key.modifiers.is_empty() && self.manages_replicas() => {
+                self.show_add_replica(ctx);
+                return true;
+            }
+            KeyCode::Char('b') if key.modifiers.is_empty() && self.db.capabilities().backups => {
+                ctx.push_widget(Box::new(BackupsWidget::new(
+                    self.db.clone(),
+                    &self.table_name,
+                    self.inner.id(),
+                )));
+                return true;
+            }
+            KeyCode::Char('c') if key.modifiers.is_empty() && self.db.capabilities().capacity => {
+                self.show_capacity(ctx);
+                return true;
+            }
+            KeyCode::Tab => self.select_target(true),
+            KeyCode::BackTab => self.select_target(false),
+            KeyCode::Char('j') | KeyCode::Down => self.scroll_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll_by(-1),
+            KeyCode::PageDown => self.scroll_by(page),
+            KeyCode::PageUp => self.scroll_by(-page),
+            _ => return false,
+        };
+        if scrolled {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(loaded) = event.payload::<DetailsLoadedEvent>() {
+            {
+                let mut state = self.state.borrow_mut();
+                state.loading = false;
+                match loaded.result.as_ref() {
+                    Ok(details) => {
+                        let targets = self.targets(Some(details));
+                        if state
+                            .selected
+                            .as_ref()
+                            .is_some_and(|selected| !targets.contains(selected))
+                        {
+                            state.selected = None;
+                        }
+                        state.details = Some(details.clone());
+                        state.error = None;
+                    }
+                    Err(err) => state.error = Some(format!("Failed to describe table: {err}")),
+                }
+            }
+            self.schedule_poll(ctx.clone());
+            ctx.invalidate();
+            return;
+        }
+        if event.payload::<PollTick>().is_some() {
+            self.state.borrow_mut().poll_pending = false;
+            self.reload(ctx);
+            return;
+        }
+        if let Some(request) = event.payload::<DropIndexRequest>() {
+            self.drop_index(ctx, request.index_name.clone());
+            return;
+        }
+        if let Some(request) = event.payload::<ReplicaRequest>() {
+            self.update_replica(ctx, request);
+            return;
+        }
+        if let Some(updated) = event.payload::<ReplicaUpdatedEvent>() {
+            self.on_replica_updated(ctx, updated);
+            return;
+        }
+        if let Some(dropped) = event.payload::<IndexDroppedEvent>() {
+            match dropped.result.as_ref() {
+                Ok(()) => {
+                    ctx.show_toast(Toast {
+                        message: format!(
+                            "Dropping index {} on {}",
+                            dropped.index_name, self.table_name
+                        ),
+                        kind: ToastKind::Info,
+                        duration: Duration::from_secs(3),
+                        action: None,
+                    });
+                    ctx.broadcast_event(IndexChangedEvent {
+                        table_name: self.table_name.clone(),
+                    });
+                }
+                Err(err) => {
+                    self.state.borrow_mut().loading = false;
+                    ctx.show_toast(Toast {
+                        message: format!("Failed to drop index: {err}"),
+                        kind: ToastKind::Error,
+                        duration: Duration::from_secs(4),
+                        action: None,
+                    });
+                    ctx.invalidate();
+                }
+            }
+        }
+    }
+
+    fn on_app_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        let table_name = event
+            .payload::<IndexChangedEvent>()
+            .map(|changed| &changed.table_name)
+            .or_else(|| {
+                event
+                    .payload::<CapacityChangedEvent>()
+                    .map(|changed| &changed.table_name)
+            });
+        if table_name == Some(&self.table_name) {
+            self.reload(ctx);
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+/// The view's help line, tuned to the backend's capabilities.
+fn build_help(caps: &Capabilities) -> Vec<help::Entry<'static>> {
+    let mut entries = vec![
+        help_entry("j/k/↑/↓/PgUp/PgDn", "scroll", "Scroll details"),
+        help_ctrl("^r", "refresh", "Refresh details"),
+    ];
+    if caps.capacity {
+        entries.push(help_entry("c", "capacity", "Edit billing mode/capacity"));
+    }
+    if caps.backups {
+        entries.push(help_entry("b", "backups", "Manage backups"));
+    }
+    match (caps.index_management, caps.replicas) {
+        (true, false) => entries.extend([
+            help_entry("tab/shift+tab", "select index", "Select next/previous GSI"),
+            help_ctrl("^n", "add gsi", "Add a GSI"),
+            help_ctrl("^d", "drop gsi", "Drop the selected GSI"),
+        ]),
+        (true, true) => entries.extend([
+            help_entry(
+                "tab/shift+tab",
+                "select",
+                "Select next/previous GSI or replica",
+            ),
+            help_ctrl("^n", "add gsi", "Add a GSI"),
+            help_ctrl("^d", "remove", "Drop the selected GSI or replica"),
+        ]),
+        (false, true) => entries.extend([
+            help_entry(
+                "tab/shift+tab",
+                "select replica",
+                "Select next/previous replica",
+            ),
+            help_ctrl("^d", "remove replica", "Remove the selected replica"),
+        ]),
+        (false, false) => {}
+    }
+    if caps.replicas {
+        entries.push(help_entry(
+            "g",
+            "add replica",
+            "Replicate to another region",
+        ));
+    }
+    entries.push(help_entry("esc", "back", "Back to tables"));
+    entries
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+fn help_ctrl(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(""),
+        short: Cow::Borrowed(""),
+        long: Cow::Borrowed(""),
+        ctrl: Some(help::Variant {
+            keys: Some(Cow::Borrowed(keys)),
+            short: Some(Cow::Borrowed(short)),
+            long: Some(Cow::Borrowed(long)),
+        }),
+        shift: None,
+        alt: None,
+    }
+}
+
+/// Render the sections, highlighting the selected index or replica's title.
+/// Also returns the line that title landed on, for scrolling it into view.
+fn section_lines(
+    sections: &[Section],
+    selected: Option<&SectionTarget>,
+    theme: &Theme,
+) -> (Vec<Line<'static>>, Option<usize>) {
+    let mut lines = Vec::new();
+    let mut selected_line = None;
+    for (idx, section) in sections.iter().enumerate() {
+        if idx > 0 {
+            lines.push(Line::raw(""));
+        }
+        let selected = section.target.is_some() && section.target.as_ref() == selected;
+        let title_style = if selected {
+            selected_line = Some(lines.len());
+            Style::default()
+                .bg(theme.selection_bg())
+                .fg(theme.selection_fg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD)
+        };
+        lines.push(Line::styled(pad(&section.title, 1), title_style));
+        for (label, value) in &section.rows {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("   {label:<LABEL_WIDTH$}"),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(value.clone(), Style::default().fg(theme.text())),
+            ]));
+        }
+    }
+    (lines, selected_line)
+}
+
+/// Names of the GSIs, in display order — the ones that can be selected and
+/// dropped.
+fn gsi_names(details: Option<&CollectionDetails>) -> Vec<String> {
+    details
+        .map(|details| {
+            details
+                .indexes
+                .iter()
+                .filter(|index| index.schema.kind == IndexKind::GlobalSecondary)
+                .map(|index| index.schema.name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Regions of the replicas, in display order.
+fn replica_regions(details: Option<&CollectionDetails>) -> Vec<String> {
+    details
+        .map(|details| {
+            details
+                .replicas
+                .iter()
+                .map(|replica| replica.region.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether the table, one of its indexes, or a replica is mid-change
+/// (creating, updating, deleting, or backfilling), so the view should keep
+/// polling.
+fn changes_pending(details: &CollectionDetails) -> bool {
+    let busy = |status: Option<&String>| status.is_some_and(|status| status != "ACTIVE");
+    busy(details.schema.status.as_ref())
+        || details
+            .indexes
+            .iter()
+            .any(|index| index.backfilling || busy(index.status.as_ref()))
+        || details
+            .replicas
+            .iter()
+            .any(|replica| busy(replica.status.as_ref()))
+}
+
+fn show_readonly_toast(ctx: &crate::env::WidgetCtx) {
+    ctx.show_toast(Toast {
+        message: dynamate::readonly::REJECT_MESSAGE.to_string(),
+        kind: ToastKind::Warning,
+        duration: dynamate::readonly::TOAST_DURATION,
+        action: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::describe::{self, SectionTarget};
+    use dynamate::core::schema::{
+        CollectionDetails, CollectionSchema, IndexKind, IndexSchema, KeyField, KeyRole, KeySchema,
+        Projection, ReplicaDetails, ScalarType,
+    };
+
+    use super::{changes_pending, gsi_names};
+
+    fn key(name: &str, role: KeyRole) -> KeyField {
+        KeyField {
+            name: name.to_string(),
+            role,
+            ty: ScalarType::String,
+        }
+    }
+
+    #[test]
+    fn polls_while_an_index_is_changing() {
+        let schema = CollectionSchema {
+            indexes: vec![IndexSchema {
+                name: "byStatus".to_string(),
+                kind: IndexKind::GlobalSecondary,
+                key: KeySchema {
+                    fields: vec![key("status", KeyRole::Partition)],
+                },
+                projection: Projection::All,
+            }],
+            status: Some("ACTIVE".to_string()),
+            ..CollectionSchema::default()
+        };
+        let mut details = CollectionDetails::from(schema);
+        details.indexes[0].status = Some("ACTIVE".to_string());
+        assert!(!changes_pending(&details));
+        assert_eq!(gsi_names(Some(&details)), vec!["byStatus".to_string()]);
+
+        details.indexes[0].status = Some("CREATING".to_string());
+        details.indexes[0].backfilling = true;
+        assert!(changes_pending(&details));
+    }
+
+    #[test]
+    fn replicas_get_a_section_and_poll_until_active() {
+        let mut details = CollectionDetails::from(CollectionSchema {
+            status: Some("ACTIVE".to_string()),
+            ..CollectionSchema::default()
+        });
+        details.replicas = vec![ReplicaDetails {
+            region: "eu-west-1".to_string(),
+            status: Some("CREATING".to_string()),
+            progress: Some("40%".to_string()),
+        }];
+        assert!(changes_pending(&details));
+        let sections = describe::sections(&details);
+        let replica = sections.last().unwrap();
+        assert_eq!(replica.title, "Replica eu-west-1");
+        assert_eq!(
+            replica.target,
+            Some(SectionTarget::Replica("eu-west-1".to_string()))
+        );
+        assert_eq!(
+            replica.rows,
+            vec![("Status".to_string(), "CREATING (40%)".to_string())]
+        );
+
+        details.replicas[0].status = Some("ACTIVE".to_string());
+        assert!(!changes_pending(&details));
+    }
+}
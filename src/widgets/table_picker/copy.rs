@@ -0,0 +1,341 @@
+//! Copying the selected table (`^t`): a destination is created with the same
+//! definition and the items are streamed into it with a parallel scan and
+//! batched writes.
+//!
+//! The copy runs as a job and saves a checkpoint as it goes, so a failed or
+//! interrupted one can be carried on with. Each step comes back as one of the
+//! events here; [`handle`] takes it from there and tells the table picker
+//! what it has to do about it.
+
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use dynamate::core::copy::{CopyCheckpoint, copy_items, copy_spec, wait_until_active};
+use dynamate::core::datastore::Datastore;
+use dynamate::dynamodb::requests::{RequestHandle, RequestManager};
+
+use crate::{
+    env::{AppEvent, WidgetCtx, WidgetId},
+    jobs::{self, JobHandle},
+    resume::{self, Checkpoint},
+    safety::Action,
+    widgets::{
+        confirm::{ConfirmAction, ConfirmPopup},
+        copy_table::CopyTablePopup,
+    },
+};
+
+use super::{TablePickerState, show_readonly_toast};
+
+struct CopyTableRequest {
+    source: String,
+    destination: String,
+    /// Whether the copy was confirmed, here or as a resumed job.
+    confirmed: bool,
+}
+
+struct CopyTableEvent {
+    run_id: u64,
+    destination: String,
+    result: Result<u64, String>,
+    /// Where the copy stopped when it failed after creating the destination.
+    checkpoint: Option<CopyCheckpoint>,
+}
+
+/// What a copy needs from the table picker.
+pub(super) struct CopySource<'a> {
+    /// The client for the selected table.
+    pub(super) db: Arc<dyn Datastore>,
+    pub(super) copies: &'a RequestManager,
+    /// Holds the running copy and the last failed one.
+    pub(super) state: &'a RefCell<TablePickerState>,
+    pub(super) parent: WidgetId,
+}
+
+/// Where a copy got to, for the table picker to act on.
+pub(super) enum CopyFlow {
+    /// The next step is under way; nothing to do.
+    Continuing,
+    /// The copy finished, was canceled, or failed; the tables changed either
+    /// way.
+    Finished(Result<(), String>),
+}
+
+/// Ask for a destination name for the selected table, offering to resume
+/// the last failed copy of it.
+pub(super) fn prompt(source: &CopySource<'_>, ctx: &WidgetCtx) -> Result<(), String> {
+    let (table, destination) = {
+        let state = source.state.borrow();
+        if state.copy_job.is_some() {
+            return Err("A table copy is already running".to_string());
+        }
+        let Some(table) = state.selected_table_name().map(str::to_string) else {
+            return Ok(());
+        };
+        let destination = state
+            .copy_resume
+            .as_ref()
+            .filter(|checkpoint| checkpoint.source == table)
+            .map_or_else(
+                || format!("{table}-copy"),
+                |checkpoint| checkpoint.destination.clone(),
+            );
+        (table, destination)
+    };
+    let ctx_for_submit = ctx.clone();
+    let table_for_submit = table.clone();
+    ctx.set_popup(Box::new(CopyTablePopup::new(
+        &table,
+        &destination,
+        move |destination| {
+            ctx_for_submit.emit_self(CopyTableRequest {
+                source: table_for_submit.clone(),
+                destination,
+                confirmed: false,
+            });
+        },
+        source.parent.clone(),
+    )));
+    Ok(())
+}
+
+/// Remember a copy a previous session didn't finish, and carry on with it if
+/// `start`.
+pub(super) fn resume(
+    source: CopySource<'_>,
+    checkpoint: &CopyCheckpoint,
+    start: bool,
+    ctx: &WidgetCtx,
+) {
+    source.state.borrow_mut().copy_resume = Some(checkpoint.clone());
+    if start {
+        let request = CopyTableRequest {
+            source: checkpoint.source.clone(),
+            destination: checkpoint.destination.clone(),
+            confirmed: true,
+        };
+        run(source, &request, ctx);
+    }
+}
+
+/// Take the copy one step further if `event` is one of its own. `source` is
+/// only built then.
+pub(super) fn handle<'a>(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    source: impl FnOnce() -> CopySource<'a>,
+) -> Option<CopyFlow> {
+    if let Some(request) = event.payload::<CopyTableRequest>() {
+        run(source(), request, ctx);
+        return Some(CopyFlow::Continuing);
+    }
+    let finished = event.payload::<CopyTableEvent>()?;
+    finish(&source(), finished).map(CopyFlow::Finished)
+}
+
+/// Confirm the copy, then start it, or carry on with the failed one it
+/// matches.
+fn run(source: CopySource<'_>, request: &CopyTableRequest, ctx: &WidgetCtx) {
+    if source.db.is_read_only() {
+        show_readonly_toast(ctx);
+        return;
+    }
+    if !request.confirmed {
+        confirm(&source, request, ctx);
+        return;
+    }
+    let run = source.copies.start();
+    let job = jobs::track(
+        format!("Copy {} to {}", request.source, request.destination),
+        run.shared_flag(),
+    );
+    let (resume, estimate) = {
+        let mut state = source.state.borrow_mut();
+        state.copy_job = Some(job.clone());
+        let resume = state.copy_resume.take().filter(|checkpoint| {
+            checkpoint.source == request.source && checkpoint.destination == request.destination
+        });
+        let estimate = state
+            .tables
+            .iter()
+            .find(|entry| entry.name == request.source)
+            .and_then(|entry| entry.meta.item_count);
+        (resume, estimate)
+    };
+    let segments = if source.db.capabilities().parallel_scan {
+        crate::util::scan_segments()
+    } else {
+        1
+    };
+    job.progress(format!(
+        "Copying {} to {}...",
+        request.source, request.destination
+    ));
+    let db = source.db;
+    let copy = CopyJob {
+        connection: resume::connection(db.as_ref()),
+        db,
+        source: request.source.clone(),
+        destination: request.destination.clone(),
+        resume,
+        segments,
+        estimate,
+        run,
+        job,
+    };
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let (run_id, destination) = (copy.run.id(), copy.destination.clone());
+        let (result, checkpoint) = match run_copy(copy).await {
+            Ok(copied) => (Ok(copied), None),
+            Err((err, checkpoint)) => (Err(err), checkpoint),
+        };
+        ctx.emit_self(CopyTableEvent {
+            run_id,
+            destination,
+            result,
+            checkpoint,
+        });
+    });
+}
+
+fn confirm(source: &CopySource<'_>, request: &CopyTableRequest, ctx: &WidgetCtx) {
+    let ctx_for_action = ctx.clone();
+    let (table, destination) = (request.source.clone(), request.destination.clone());
+    ConfirmPopup::new_with_action(
+        "Copy table",
+        format!("Table={table}\nCopy={destination}"),
+        "Copy",
+        "cancel",
+        ConfirmAction::new(
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL,
+            "^t",
+            "copy",
+            "Copy the table",
+        ),
+        move || {
+            ctx_for_action.emit_self(CopyTableRequest {
+                source: table.clone(),
+                destination: destination.clone(),
+                confirmed: true,
+            });
+        },
+        source.parent.clone(),
+    )
+    .show(ctx, Action::CopyTable, &request.destination);
+}
+
+/// Finish the copy's job and remember where a failed one stopped. `None`
+/// when the copy was already forgotten.
+fn finish(source: &CopySource<'_>, event: &CopyTableEvent) -> Option<Result<(), String>> {
+    source.copies.finish(event.run_id);
+    let job = {
+        let mut state = source.state.borrow_mut();
+        state.copy_resume.clone_from(&event.checkpoint);
+        state.copy_job.take()
+    }?;
+    Some(match event.result.as_ref() {
+        Ok(copied) => {
+            job.finish(Ok(format!(
+                "Copied {copied} items to {}",
+                event.destination
+            )));
+            Ok(())
+        }
+        Err(_) if job.is_cancelled() => {
+            let copied = event
+                .checkpoint
+                .as_ref()
+                .map_or(0, |checkpoint| checkpoint.copied);
+            job.finish(Ok(format!(
+                "Copy canceled after {copied} items. Press ^t to resume."
+            )));
+            Ok(())
+        }
+        Err(err) => {
+            job.finish_quietly(Err(err.clone()));
+            let mut message = format!("Failed to copy table: {err}");
+            if let Some(checkpoint) = event.checkpoint.as_ref() {
+                message.push_str(&format!(
+                    "\n\n{} items were copied. Press ^t on {} to resume.",
+                    checkpoint.copied, checkpoint.source
+                ));
+            }
+            Err(message)
+        }
+    })
+}
+
+struct CopyJob {
+    db: Arc<dyn Datastore>,
+    /// What the checkpoint is saved under.
+    connection: String,
+    source: String,
+    destination: String,
+    /// Carry on from here rather than creating the destination.
+    resume: Option<CopyCheckpoint>,
+    segments: u32,
+    estimate: Option<i64>,
+    /// Quiets the progress once the copy is canceled.
+    run: RequestHandle,
+    job: JobHandle,
+}
+
+/// Create the destination (unless resuming) and copy the items, reporting
+/// progress to the job and saving checkpoints to resume from after a
+/// restart. On failure or cancellation, returns where the copy stopped once
+/// the destination exists.
+async fn run_copy(job: CopyJob) -> Result<u64, (String, Option<CopyCheckpoint>)> {
+    let db = job.db.as_ref();
+    let checkpoint = if let Some(checkpoint) = job.resume {
+        checkpoint
+    } else {
+        let schema = db
+            .describe_collection(&job.source)
+            .await
+            .map_err(|err| (err.to_string(), None))?;
+        db.create_collection(&copy_spec(&schema, &job.destination))
+            .await
+            .map_err(|err| (err.to_string(), None))?;
+        CopyCheckpoint::new(&job.source, &job.destination, job.segments)
+    };
+    resume::save(&job.connection, &Checkpoint::Copy(checkpoint.clone()));
+    let latest = Mutex::new(checkpoint.clone());
+    if let Err(err) = wait_until_active(db, &job.destination).await {
+        return Err((err.to_string(), Some(checkpoint)));
+    }
+    let estimate = job
+        .estimate
+        .map(|count| format!(" of ~{count}"))
+        .unwrap_or_default();
+    let report = job
+        .run
+        .progress(|message: String| job.job.progress(message));
+    let on_progress = |checkpoint: &CopyCheckpoint| {
+        latest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone_from(checkpoint);
+        resume::save(&job.connection, &Checkpoint::Copy(checkpoint.clone()));
+        report(format!(
+            "Copying {} to {}... {}{estimate} items",
+            checkpoint.source, checkpoint.destination, checkpoint.copied
+        ));
+    };
+    match copy_items(db, checkpoint, job.run.flag(), &on_progress).await {
+        Ok(checkpoint) if checkpoint.is_complete() => {
+            resume::clear(&job.connection, resume::Kind::Copy);
+            Ok(checkpoint.copied)
+        }
+        Ok(checkpoint) => Err(("Copy canceled".to_string(), Some(checkpoint))),
+        Err(err) => Err((
+            err.to_string(),
+            Some(latest.into_inner().unwrap_or_else(PoisonError::into_inner)),
+        )),
+    }
+}
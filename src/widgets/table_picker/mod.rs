@@ -0,0 +1,2433 @@
+mod copy;
+mod rename;
+mod seed;
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    cmp,
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use futures::StreamExt;
+use humansize::{BINARY, format_size};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::Style,
+    text::{Line, Span, Text},
+    widgets::{Block, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState},
+};
+use throbber_widgets_tui::symbols::throbber::BRAILLE_SIX;
+use unicode_width::UnicodeWidthStr;
+
+use dynamate::core::copy::CopyCheckpoint;
+use dynamate::core::datastore::Datastore;
+use dynamate::core::purge::{PurgeOptions, PurgeProgress, PurgeSummary, purge_items};
+use dynamate::core::query::{CollectionPage, QueryPlan};
+use dynamate::core::rename::RenameJob;
+use dynamate::core::schema::CollectionSchema;
+use dynamate::core::table_spec::{self, TableSpec};
+use dynamate::dynamodb::requests::RequestManager;
+
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    jobs::{self, JobHandle},
+    resume::{self, Checkpoint},
+    safety::Action,
+    util::{self, ClickTracker, pad},
+    widgets::{
+        QueryWidget, WidgetInner,
+        analyze::AnalyzeWidget,
+        assume_role::AssumeRolePopup,
+        audit_log::AuditLogWidget,
+        backups::BackupsWidget,
+        capacity::CapacityPopup,
+        confirm::{ConfirmAction, ConfirmPopup},
+        create_table::{CreateTablePopup, TableCreatedEvent},
+        error::ErrorPopup,
+        filter_input::FilterInput,
+        imports::ImportsWidget,
+        metrics::MetricsWidget,
+        profile_picker::{ProfilePickerWidget, ProfileSwitchedEvent},
+        purge::PurgeSummaryPopup,
+        schema_popup::{SchemaNavEvent, SchemaPopup},
+        table_details::TableDetailsWidget,
+        text_editor::TextEditorPopup,
+        theme::Theme,
+    },
+};
+
+/// Tables described at once.
+const DESCRIBE_CONCURRENCY: usize = 8;
+
+/// Tables whose metadata is kept. Past this, the ones described longest ago
+/// are forgotten, and described again when they next come into view.
+const MAX_DESCRIBED: usize = 500;
+
+/// Rows described before the list has been drawn and its height is known.
+const DEFAULT_VISIBLE_ROWS: usize = 50;
+
+/// How long each frame of a row's spinner shows.
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+pub struct TablePickerWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    state: RefCell<TablePickerState>,
+    /// Help lines, tuned to the backend's capabilities (computed once).
+    help_base: Vec<help::Entry<'static>>,
+    help_filter_applied: Vec<help::Entry<'static>>,
+    clicks: ClickTracker,
+    /// When the widget was created, to step the row spinners.
+    created: Instant,
+    /// Clients for the configured regions, once listed across them.
+    region_dbs: RefCell<HashMap<String, Arc<dyn Datastore>>>,
+    purges: RequestManager,
+    copies: RequestManager,
+}
+
+#[derive(Debug, Clone)]
+struct TableMeta {
+    status: String,
+    item_count: Option<i64>,
+    size_bytes: Option<i64>,
+    gsi_count: usize,
+    lsi_count: usize,
+}
+
+impl TableMeta {
+    fn placeholder() -> Self {
+        Self {
+            status: "unknown".to_string(),
+            item_count: None,
+            size_bytes: None,
+            gsi_count: 0,
+            lsi_count: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TableEntry {
+    name: String,
+    /// Set when tables are listed across regions.
+    region: Option<String>,
+    meta: TableMeta,
+    schema: CollectionSchema,
+    describe: Describe,
+}
+
+/// Where a table's metadata stands. Tables are described once they scroll
+/// into view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Describe {
+    Wanted,
+    InFlight,
+    /// Described; the number orders describes, oldest first.
+    Done(u64),
+    Failed,
+}
+
+impl TableEntry {
+    fn new(name: String, schema: CollectionSchema) -> Self {
+        Self {
+            name,
+            region: None,
+            meta: table_meta_from(&schema),
+            schema,
+            describe: Describe::Done(0),
+        }
+    }
+
+    /// An entry whose schema isn't known yet.
+    fn placeholder(name: String) -> Self {
+        let schema = CollectionSchema {
+            name: name.clone(),
+            ..CollectionSchema::default()
+        };
+        Self {
+            name,
+            region: None,
+            meta: TableMeta::placeholder(),
+            schema,
+            describe: Describe::Wanted,
+        }
+    }
+
+    fn with_region(mut self, region: Option<String>) -> Self {
+        self.region = region;
+        self
+    }
+
+    fn is_describing(&self) -> bool {
+        matches!(self.describe, Describe::Wanted | Describe::InFlight)
+    }
+}
+
+/// The column the table list is ordered by (`S` cycles it).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum SortColumn {
+    #[default]
+    Name,
+    Items,
+    Size,
+}
+
+impl SortColumn {
+    fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Items,
+            Self::Items => Self::Size,
+            Self::Size => Self::Name,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Items => "item count",
+            Self::Size => "size",
+        }
+    }
+
+    /// Names sort A to Z; counts and sizes largest first, with tables that
+    /// have none last. Ties fall back to the name, then the region.
+    fn compare(self, a: &TableEntry, b: &TableEntry) -> cmp::Ordering {
+        let metric = |entry: &TableEntry| match self {
+            Self::Name => None,
+            Self::Items => entry.meta.item_count,
+            Self::Size => entry.meta.size_bytes,
+        };
+        let by_name = a.name.cmp(&b.name).then_with(|| a.region.cmp(&b.region));
+        match (metric(a), metric(b)) {
+            (Some(left), Some(right)) => right.cmp(&left).then(by_name),
+            (Some(_), None) => cmp::Ordering::Less,
+            (None, Some(_)) => cmp::Ordering::Greater,
+            (None, None) => by_name,
+        }
+    }
+
+    /// A column header, with an arrow on the sorted column.
+    fn header(self, column: Self, title: &str) -> String {
+        match (self == column, column) {
+            (false, _) => title.to_string(),
+            (true, Self::Name) => format!("{title} ↑"),
+            (true, _) => format!("{title} ↓"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum LoadingState {
+    #[default]
+    Idle,
+    Loading,
+    Loaded,
+    Busy(String),
+    Error(String),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TableAction {
+    Delete,
+    Purge,
+}
+
+#[derive(Debug, Default)]
+struct TablePickerState {
+    loading_state: LoadingState,
+    tables: Vec<TableEntry>,
+    filtered_indices: Vec<usize>,
+    table_state: TableState,
+    filter: FilterInput,
+    last_render_capacity: usize,
+    /// The table copy running in the background.
+    copy_job: Option<JobHandle>,
+    /// Where the last failed copy stopped, so it can be resumed.
+    copy_resume: Option<CopyCheckpoint>,
+    /// The table rename running in the background.
+    rename_job: Option<JobHandle>,
+    /// Where the last failed rename stopped, so it can be resumed.
+    rename_resume: Option<RenameJob>,
+    /// The purge running in the background.
+    purge_job: Option<JobHandle>,
+    /// The job writing generated items.
+    seed_job: Option<JobHandle>,
+    /// Where the table rows were last drawn, below the header, for clicks.
+    rows_area: Rect,
+    sort: SortColumn,
+    /// Bumped on every reload, so pages and describes from an earlier one are
+    /// ignored.
+    reload_seq: u64,
+    /// Where the next page of names starts, while there are more to list.
+    next_page: Option<String>,
+    /// Whether a page of names is being listed.
+    listing: bool,
+    /// Tables being described, and how many of the finished ones failed.
+    describe_pending: usize,
+    describe_failures: usize,
+    describe_seq: u64,
+    /// Whether tables are listed across the configured regions (`R`).
+    multi_region: bool,
+}
+
+/// A page of table names; their metadata follows in `TableDescribedEvent`s
+/// as they come into view.
+struct TablePageEvent {
+    reload: u64,
+    append: bool,
+    result: Result<CollectionPage, String>,
+}
+
+/// A region's client and table names, or why they couldn't be listed.
+type RegionListing = Result<(Arc<dyn Datastore>, Vec<String>), String>;
+
+/// The tables of every configured region, listed in one go.
+struct RegionListEvent {
+    reload: u64,
+    results: Vec<(String, RegionListing)>,
+}
+
+struct TableDescribedEvent {
+    reload: u64,
+    name: String,
+    region: Option<String>,
+    result: Result<CollectionSchema, String>,
+}
+
+struct DeleteTableRequest {
+    table_name: String,
+}
+
+struct DeleteTableEvent {
+    table_name: String,
+    result: Result<(), String>,
+}
+
+struct PurgeTableRequest {
+    table_name: String,
+}
+
+struct PurgeTableEvent {
+    run_id: u64,
+    table_name: String,
+    result: Result<PurgeSummary, String>,
+}
+
+/// What to do with the jobs a previous session didn't finish.
+struct ResumeJobsRequest {
+    checkpoints: Vec<Checkpoint>,
+    /// Start them, rather than forget them.
+    start: bool,
+}
+
+/// The definition of the table a new one is created like, to edit first.
+struct CreateLikeEvent {
+    source: String,
+    result: Result<TableSpec, String>,
+}
+
+/// The edited definition to create a table from.
+struct CreateLikeRequest {
+    text: String,
+}
+
+struct CreateLikeFinishedEvent {
+    table_name: String,
+    result: Result<(), String>,
+}
+
+struct ProfileListEvent {
+    result: Result<Vec<dynamate::core::connect::ProfileInfo>, String>,
+}
+
+impl TablePickerState {
+    fn apply_filter(&mut self) {
+        let filter = self.filter.value.trim().to_lowercase();
+        let current = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.filtered_indices.get(idx).copied());
+
+        if filter.is_empty() {
+            self.filtered_indices = (0..self.tables.len()).collect();
+        } else {
+            self.filtered_indices = self
+                .tables
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| {
+                    entry.name.to_lowercase().contains(&filter)
+                        || entry
+                            .region
+                            .as_ref()
+                            .is_some_and(|region| region.contains(&filter))
+                })
+                .map(|(idx, _)| idx)
+                .collect();
+        }
+        let (tables, sort) = (&self.tables, self.sort);
+        self.filtered_indices
+            .sort_by(|a, b| sort.compare(&tables[*a], &tables[*b]));
+
+        if self.filtered_indices.is_empty() {
+            self.table_state.select(None);
+            *self.table_state.offset_mut() = 0;
+            return;
+        }
+
+        if let Some(current) = current
+            && let Some(index) = self.filtered_indices.iter().position(|idx| *idx == current)
+        {
+            self.table_state.select(Some(index));
+            self.clamp_offset();
+            return;
+        }
+
+        self.table_state.select(Some(0));
+        self.clamp_offset();
+    }
+
+    fn selected_table_name(&self) -> Option<&str> {
+        self.selected_table().map(|entry| entry.name.as_str())
+    }
+
+    fn selected_table(&self) -> Option<&TableEntry> {
+        self.table_state
+            .selected()
+            .and_then(|idx| self.filtered_indices.get(idx).copied())
+            .and_then(|idx| self.tables.get(idx))
+    }
+
+    fn visible_rows(&self) -> usize {
+        if self.last_render_capacity == 0 {
+            DEFAULT_VISIBLE_ROWS
+        } else {
+            self.last_render_capacity
+        }
+    }
+
+    /// Forget the metadata described longest ago past [`MAX_DESCRIBED`].
+    fn evict_described(&mut self) {
+        let mut described: Vec<(u64, usize)> = self
+            .tables
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| match entry.describe {
+                Describe::Done(seq) => Some((seq, idx)),
+                _ => None,
+            })
+            .collect();
+        if described.len() <= MAX_DESCRIBED {
+            return;
+        }
+        described.sort_unstable();
+        let excess = described.len() - MAX_DESCRIBED;
+        for (_, idx) in &described[..excess] {
+            let entry = &self.tables[*idx];
+            let region = entry.region.clone();
+            self.tables[*idx] = TableEntry::placeholder(entry.name.clone()).with_region(region);
+        }
+    }
+
+    fn clamp_offset(&mut self) {
+        let total = self.filtered_indices.len();
+        let max_rows = self.last_render_capacity.max(1);
+        if total == 0 {
+            self.table_state.select(None);
+            *self.table_state.offset_mut() = 0;
+            return;
+        }
+        let selected = match self.table_state.selected() {
+            Some(selected) if selected < total => selected,
+            Some(_) | None => {
+                let last = total.saturating_sub(1);
+                self.table_state.select(Some(last));
+                last
+            }
+        };
+        if total <= max_rows {
+            *self.table_state.offset_mut() = 0;
+            return;
+        }
+        let offset = self.table_state.offset();
+        if selected < offset {
+            *self.table_state.offset_mut() = selected;
+            return;
+        }
+        let end = offset.saturating_add(max_rows);
+        if selected >= end {
+            let new_offset = selected + 1 - max_rows;
+            *self.table_state.offset_mut() = new_offset.min(total.saturating_sub(1));
+        }
+    }
+}
+
+impl TablePickerWidget {
+    const HELP_FILTER_EDIT: &'static [help::Entry<'static>] = &[
+        help::Entry {
+            keys: Cow::Borrowed("esc"),
+            short: Cow::Borrowed("clear"),
+            long: Cow::Borrowed("Clear filter"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+        help::Entry {
+            keys: Cow::Borrowed("⏎"),
+            short: Cow::Borrowed("apply"),
+            long: Cow::Borrowed("Apply filter"),
+            ctrl: None,
+            shift: None,
+            alt: None,
+        },
+    ];
+
+    pub fn new(db: Arc<dyn Datastore>, parent: crate::env::WidgetId) -> Self {
+        let caps = db.capabilities();
+        let help_base = build_help(caps, false);
+        let help_filter_applied = build_help(caps, true);
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            state: RefCell::new(TablePickerState::default()),
+            help_base,
+            help_filter_applied,
+            clicks: ClickTracker::default(),
+            created: Instant::now(),
+            region_dbs: RefCell::new(HashMap::new()),
+            purges: RequestManager::default(),
+            copies: RequestManager::default(),
+        }
+    }
+
+    /// The client for the selected table: its region's when listed across
+    /// regions.
+    fn table_db(&self) -> Arc<dyn Datastore> {
+        let state = self.state.borrow();
+        state
+            .selected_table()
+            .and_then(|entry| entry.region.as_ref())
+            .and_then(|region| self.region_dbs.borrow().get(region).cloned())
+            .unwrap_or_else(|| self.db.clone())
+    }
+
+    fn region_db(&self, region: Option<&String>) -> Arc<dyn Datastore> {
+        region
+            .and_then(|region| self.region_dbs.borrow().get(region).cloned())
+            .unwrap_or_else(|| self.db.clone())
+    }
+
+    /// List one page of table names, starting after `start`.
+    fn fetch_page(&self, reload: u64, start: Option<String>, ctx: crate::env::WidgetCtx) {
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let result = db
+                .list_collections_page(start.as_deref())
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(TablePageEvent {
+                reload,
+                append: start.is_some(),
+                result,
+            });
+        });
+    }
+
+    /// List the next page of names once the selection nears the end of the
+    /// loaded ones (or a filter needs them all), and describe the tables in
+    /// view that aren't yet.
+    fn load_visible(&self, ctx: &crate::env::WidgetCtx) {
+        let (reload, next_page, wanted) = {
+            let mut state = self.state.borrow_mut();
+            let rows = state.visible_rows();
+            let selected = state.table_state.selected().unwrap_or(0);
+            let wants_more = selected + rows >= state.filtered_indices.len()
+                || !state.filter.value.trim().is_empty();
+            let next_page = if wants_more && !state.listing {
+                state.next_page.clone()
+            } else {
+                None
+            };
+            state.listing |= next_page.is_some();
+            let visible: Vec<usize> = state
+                .filtered_indices
+                .iter()
+                .skip(state.table_state.offset())
+                .take(rows)
+                .copied()
+                .collect();
+            let mut wanted = Vec::new();
+            for idx in visible {
+                let entry = &mut state.tables[idx];
+                if entry.describe == Describe::Wanted {
+                    entry.describe = Describe::InFlight;
+                    wanted.push((entry.region.clone(), entry.name.clone()));
+                }
+            }
+            if state.describe_pending == 0 {
+                state.describe_failures = 0;
+            }
+            state.describe_pending += wanted.len();
+            (state.reload_seq, next_page, wanted)
+        };
+        if let Some(start) = next_page {
+            self.fetch_page(reload, Some(start), ctx.clone());
+        }
+        if !wanted.is_empty() {
+            let wanted = wanted
+                .into_iter()
+                .map(|(region, name)| DescribeJob {
+                    db: self.region_db(region.as_ref()),
+                    region,
+                    name,
+                })
+                .collect();
+            tokio::spawn(describe_tables(wanted, reload, ctx.clone()));
+        }
+    }
+
+    fn select_next(&self) {
+        let mut state = self.state.borrow_mut();
+        let len = state.filtered_indices.len();
+        if len == 0 {
+            return;
+        }
+        let next = match state.table_state.selected() {
+            Some(index) => (index + 1).min(len - 1),
+            None => 0,
+        };
+        state.table_state.select(Some(next));
+        state.clamp_offset();
+    }
+
+    fn select_previous(&self) {
+        let mut state = self.state.borrow_mut();
+        let len = state.filtered_indices.len();
+        if len == 0 {
+            return;
+        }
+        let next = match state.table_state.selected() {
+            Some(index) => index.saturating_sub(1),
+            None => 0,
+        };
+        state.table_state.select(Some(next));
+        state.clamp_offset();
+    }
+
+    fn page_down(&self) {
+        let mut state = self.state.borrow_mut();
+        let total = state.filtered_indices.len();
+        if total == 0 {
+            return;
+        }
+        let page = state.last_render_capacity.max(1);
+        let offset = state.table_state.offset();
+        let selected = state
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .min(total.saturating_sub(1));
+        let rel = selected.saturating_sub(offset).min(page.saturating_sub(1));
+        let max_offset = total.saturating_sub(page);
+        let new_offset = offset.saturating_add(page).min(max_offset);
+        let mut new_selected = new_offset.saturating_add(rel);
+        if new_selected >= total {
+            new_selected = total.saturating_sub(1);
+        }
+        *state.table_state.offset_mut() = new_offset;
+        state.table_state.select(Some(new_selected));
+    }
+
+    fn page_up(&self) {
+        let mut state = self.state.borrow_mut();
+        let total = state.filtered_indices.len();
+        if total == 0 {
+            return;
+        }
+        let page = state.last_render_capacity.max(1);
+        let offset = state.table_state.offset();
+        let selected = state
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .min(total.saturating_sub(1));
+        let rel = selected.saturating_sub(offset).min(page.saturating_sub(1));
+        let new_offset = offset.saturating_sub(page);
+        let mut new_selected = new_offset.saturating_add(rel);
+        if new_selected >= total {
+            new_selected = total.saturating_sub(1);
+        }
+        *state.table_state.offset_mut() = new_offset;
+        state.table_state.select(Some(new_selected));
+    }
+
+    /// The wheel moves the selection; a click selects a table and a double
+    /// click opens it.
+    fn handle_mouse(&self, ctx: crate::env::WidgetCtx, mouse: MouseEvent) -> bool {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.select_next(),
+            MouseEventKind::ScrollUp => self.select_previous(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let index = {
+                    let mut state = self.state.borrow_mut();
+                    let area = state.rows_area;
+                    if !area.contains(Position::new(mouse.column, mouse.row)) {
+                        return false;
+                    }
+                    let index = state.table_state.offset() + (mouse.row - area.y) as usize;
+                    if index >= state.filtered_indices.len() {
+                        return false;
+                    }
+                    state.table_state.select(Some(index));
+                    state.clamp_offset();
+                    index
+                };
+                if self.clicks.click(index) {
+                    return self.handle_selection(ctx);
+                }
+            }
+            _ => return false,
+        }
+        ctx.invalidate();
+        true
+    }
+
+    fn handle_selection(&self, ctx: crate::env::WidgetCtx) -> bool {
+        let selected = {
+            self.state
+                .borrow()
+                .selected_table_name()
+                .map(str::to_string)
+        };
+        if let Some(table_name) = selected {
+            let widget = Box::new(QueryWidget::new(
+                self.table_db(),
+                &table_name,
+                self.inner.id(),
+            ));
+            ctx.push_widget(widget);
+            return true;
+        }
+        false
+    }
+
+    /// Open the selected table in a tab of its own, leaving this list where
+    /// it is.
+    fn open_in_new_tab(&self, ctx: &crate::env::WidgetCtx) {
+        let selected = {
+            self.state
+                .borrow()
+                .selected_table_name()
+                .map(str::to_string)
+        };
+        if let Some(table_name) = selected {
+            let widget = Box::new(QueryWidget::new(
+                self.table_db(),
+                &table_name,
+                crate::env::WidgetId::app(),
+            ));
+            ctx.open_tab(widget);
+        }
+    }
+
+    fn reload_tables(&self, ctx: crate::env::WidgetCtx) {
+        let (reload, multi_region) = {
+            let mut state = self.state.borrow_mut();
+            state.loading_state = LoadingState::Loading;
+            state.listing = true;
+            state.next_page = None;
+            state.describe_pending = 0;
+            state.reload_seq += 1;
+            (state.reload_seq, state.multi_region)
+        };
+        ctx.invalidate();
+        if multi_region {
+            self.fetch_regions(reload, ctx);
+        } else {
+            self.fetch_page(reload, None, ctx);
+        }
+    }
+
+    /// List every table of each configured region. Regions aren't paged:
+    /// the names of all of them are needed to merge the list.
+    fn fetch_regions(&self, reload: u64, ctx: crate::env::WidgetCtx) {
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let listed = util::regions().iter().map(|region| {
+                let db = db.clone();
+                async move {
+                    let result = async {
+                        let regional = db.for_region(region).await?;
+                        let names = regional.list_collections().await?;
+                        Ok::<_, dynamate::core::error::DbError>((regional, names))
+                    }
+                    .await
+                    .map_err(|err| err.to_string());
+                    (region.clone(), result)
+                }
+            });
+            let results = futures::future::join_all(listed).await;
+            ctx.emit_self(RegionListEvent { reload, results });
+        });
+    }
+
+    /// Toggle listing tables across the configured regions.
+    fn toggle_regions(&self, ctx: crate::env::WidgetCtx) {
+        let multi_region = {
+            let mut state = self.state.borrow_mut();
+            state.multi_region = !state.multi_region;
+            state.tables.clear();
+            state.apply_filter();
+            state.multi_region
+        };
+        if !multi_region {
+            self.region_dbs.borrow_mut().clear();
+        }
+        self.reload_tables(ctx);
+    }
+
+    /// Replace the list with the tables of every region that could be
+    /// listed, warning about the ones that couldn't.
+    fn on_region_list(&self, event: &RegionListEvent, ctx: &crate::env::WidgetCtx) {
+        let mut failed = Vec::new();
+        let mut entries = Vec::new();
+        {
+            let mut region_dbs = self.region_dbs.borrow_mut();
+            region_dbs.clear();
+            for (region, result) in &event.results {
+                match result {
+                    Ok((db, names)) => {
+                        entries.extend(names.iter().map(|name| {
+                            TableEntry::placeholder(name.clone()).with_region(Some(region.clone()))
+                        }));
+                        region_dbs.insert(region.clone(), db.clone());
+                    }
+                    Err(err) => {
+                        tracing::warn!(region = %region, error = %err, "list_region_failed");
+                        failed.push(region.as_str());
+                    }
+                }
+            }
+        }
+        {
+            let mut state = self.state.borrow_mut();
+            state.tables = entries;
+            state.describe_failures = 0;
+            state.next_page = None;
+            state.listing = false;
+            state.apply_filter();
+            state.loading_state = LoadingState::Loaded;
+        }
+        if !failed.is_empty() {
+            ctx.show_toast(Toast {
+                message: format!("Could not list tables in {}", failed.join(", ")),
+                kind: ToastKind::Warning,
+                duration: Duration::from_secs(4),
+                action: None,
+            });
+        }
+        self.load_visible(ctx);
+        ctx.invalidate();
+    }
+
+    /// Add a page of names to the list. The first page of a reload replaces
+    /// it, keeping what is known about tables already shown until they are
+    /// described again.
+    fn on_table_page(&self, names: &[String], next: Option<String>, append: bool) {
+        let mut state = self.state.borrow_mut();
+        let entries: Vec<TableEntry> = if append {
+            names.iter().cloned().map(TableEntry::placeholder).collect()
+        } else {
+            let mut previous: HashMap<String, TableEntry> = state
+                .tables
+                .drain(..)
+                .map(|entry| (entry.name.clone(), entry))
+                .collect();
+            state.describe_failures = 0;
+            names
+                .iter()
+                .map(|name| match previous.remove(name) {
+                    Some(mut entry) => {
+                        entry.describe = Describe::Wanted;
+                        entry
+                    }
+                    None => TableEntry::placeholder(name.clone()),
+                })
+                .collect()
+        };
+        state.tables.extend(entries);
+        state.next_page = next;
+        state.apply_filter();
+        state.loading_state = LoadingState::Loaded;
+    }
+
+    fn on_table_described(&self, described: &TableDescribedEvent, ctx: &crate::env::WidgetCtx) {
+        let mut state = self.state.borrow_mut();
+        if described.reload != state.reload_seq {
+            return;
+        }
+        state.describe_pending = state.describe_pending.saturating_sub(1);
+        state.describe_seq += 1;
+        let seq = state.describe_seq;
+        if let Some(entry) = state
+            .tables
+            .iter_mut()
+            .find(|entry| entry.name == described.name && entry.region == described.region)
+        {
+            match &described.result {
+                Ok(schema) => {
+                    *entry = TableEntry::new(described.name.clone(), schema.clone())
+                        .with_region(described.region.clone());
+                    entry.describe = Describe::Done(seq);
+                }
+                Err(err) => {
+                    tracing::warn!(table = %described.name, error = %err, "describe_failed");
+                    entry.describe = Describe::Failed;
+                }
+            }
+        }
+        if described.result.is_err() {
+            state.describe_failures += 1;
+        }
+        if state.describe_pending == 0 && state.describe_failures > 0 {
+            ctx.show_toast(Toast {
+                message: format!(
+                    "{} missing metadata",
+                    format_table_count(state.describe_failures)
+                ),
+                kind: ToastKind::Warning,
+                duration: Duration::from_secs(4),
+                action: None,
+            });
+        }
+        state.evict_described();
+        state.apply_filter();
+        drop(state);
+        // A sort by size or item count may have moved other tables into view.
+        self.load_visible(ctx);
+        ctx.invalidate();
+    }
+
+    fn cycle_sort(&self, ctx: &crate::env::WidgetCtx) {
+        let mut state = self.state.borrow_mut();
+        state.sort = state.sort.next();
+        state.apply_filter();
+        ctx.invalidate();
+    }
+
+    /// The spinner frame shown on rows still being described.
+    fn spinner(&self) -> &'static str {
+        let symbols = BRAILLE_SIX.symbols;
+        let step = self.created.elapsed().as_millis() / SPINNER_FRAME.as_millis();
+        symbols[usize::try_from(step).unwrap_or_default() % symbols.len()]
+    }
+
+    fn show_error(&self, ctx: crate::env::WidgetCtx, message: &str) {
+        let is_empty = self.state.borrow().tables.is_empty();
+        if is_empty {
+            ctx.set_popup(Box::new(ErrorPopup::new("Error", message, self.inner.id())));
+        } else {
+            ctx.show_toast(Toast {
+                message: message.to_string(),
+                kind: ToastKind::Error,
+                duration: Duration::from_secs(4),
+                action: None,
+            });
+        }
+    }
+
+    fn confirm_table_action(&self, ctx: crate::env::WidgetCtx, action: TableAction) {
+        let selected = { self.state.borrow().selected_table().cloned() };
+        let Some(entry) = selected else {
+            self.show_error(ctx, "No table selected");
+            return;
+        };
+
+        let mut lines = vec![format!("Table={}", entry.name)];
+        if let Some(count) = entry.meta.item_count {
+            lines.push(format!("Items=~{count}"));
+        }
+        let message = lines.join("\n");
+
+        let (title, confirm_label, confirm_key) = match action {
+            TableAction::Delete => (
+                "Delete table",
+                "Delete",
+                ConfirmAction::new(
+                    KeyCode::Char('d'),
+                    KeyModifiers::CONTROL,
+                    "^d",
+                    "delete",
+                    "Delete table",
+                ),
+            ),
+            TableAction::Purge => (
+                "Purge table",
+                "Purge",
+                ConfirmAction::new(
+                    KeyCode::Char('p'),
+                    KeyModifiers::CONTROL,
+                    "^p",
+                    "purge",
+                    "Purge table",
+                ),
+            ),
+        };
+
+        let table_name = entry.name.clone();
+        let ctx_for_action = ctx.clone();
+        let popup = ConfirmPopup::new_with_action(
+            title,
+            message,
+            confirm_label,
+            "cancel",
+            confirm_key,
+            move || match action {
+                TableAction::Delete => {
+                    ctx_for_action.emit_self(DeleteTableRequest {
+                        table_name: table_name.clone(),
+                    });
+                }
+                TableAction::Purge => {
+                    ctx_for_action.emit_self(PurgeTableRequest {
+                        table_name: table_name.clone(),
+                    });
+                }
+            },
+            self.inner.id(),
+        );
+        let safety_action = match action {
+            TableAction::Delete => Action::DeleteTable,
+            TableAction::Purge => Action::PurgeTable,
+        };
+        popup.show(&ctx, safety_action, &entry.name);
+    }
+
+    fn delete_table(&self, table_name: String, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        {
+            let mut state = self.state.borrow_mut();
+            state.loading_state = LoadingState::Busy(format!("Deleting {table_name}..."));
+        }
+        ctx.invalidate();
+        let db = self.table_db();
+        let ctx_clone = ctx.clone();
+        tokio::spawn(async move {
+            let event_result = db
+                .drop_collection(&table_name)
+                .await
+                .map_err(|err| err.to_string());
+            ctx_clone.emit_self(DeleteTableEvent {
+                table_name,
+                result: event_result,
+            });
+        });
+    }
+
+    /// Delete every item of a table in the background, reporting progress in
+    /// a toast and the outcome in a summary popup.
+    fn purge_table(&self, table_name: String, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let run = self.purges.start();
+        let job = jobs::track(format!("Purge {table_name}"), run.shared_flag());
+        let estimate = {
+            let mut state = self.state.borrow_mut();
+            state.purge_job = Some(job.clone());
+            state
+                .tables
+                .iter()
+                .find(|entry| entry.name == table_name)
+                .and_then(|entry| entry.meta.item_count)
+        };
+        let segments = if self.db.capabilities().parallel_scan {
+            crate::util::scan_segments()
+        } else {
+            1
+        };
+        job.progress(format!("Purging {table_name}... (esc cancels)"));
+        let db = self.table_db();
+        let connection = resume::connection(db.as_ref());
+        tokio::spawn(async move {
+            let estimate = estimate
+                .map(|count| format!(" of ~{count}"))
+                .unwrap_or_default();
+            let report = run.progress(|message: String| job.progress(message));
+            let on_progress = |progress: PurgeProgress| {
+                resume::save(
+                    &connection,
+                    &Checkpoint::Purge {
+                        table: table_name.clone(),
+                        deleted: progress.deleted,
+                    },
+                );
+                report(format!(
+                    "Purging {table_name}... {}{estimate} items deleted (esc cancels)",
+                    progress.deleted
+                ));
+            };
+            on_progress(PurgeProgress::default());
+            let result = purge_items(
+                db.as_ref(),
+                &table_name,
+                &QueryPlan::default(),
+                PurgeOptions {
+                    segments,
+                    dry_run: false,
+                },
+                run.flag(),
+                &on_progress,
+            )
+            .await
+            .map_err(|err| err.to_string());
+            if result
+                .as_ref()
+                .is_ok_and(|summary| !summary.cancelled && summary.errors.is_empty())
+            {
+                resume::clear(&connection, resume::Kind::Purge);
+            }
+            ctx.emit_self(PurgeTableEvent {
+                run_id: run.id(),
+                table_name,
+                result,
+            });
+        });
+    }
+
+    fn cancel_purge(&self) {
+        if let Some(job) = self.state.borrow().purge_job.as_ref() {
+            job.cancel();
+        }
+    }
+
+    fn cancel_seed(&self) {
+        if let Some(job) = self.state.borrow().seed_job.as_ref() {
+            job.cancel();
+        }
+    }
+
+    /// Offer to carry on with the jobs a previous session on this connection
+    /// didn't finish. Declining forgets them, though `^t` and `m` still resume
+    /// a copy or rename for the rest of the session.
+    fn offer_resume(&self, ctx: &crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            return;
+        }
+        let checkpoints = resume::unoffered(&resume::connection(self.db.as_ref()));
+        if checkpoints.is_empty() {
+            return;
+        }
+        let lines: Vec<String> = checkpoints
+            .iter()
+            .map(|checkpoint| format!("• {}", checkpoint.describe()))
+            .collect();
+        let message = format!(
+            "dynamate stopped before these finished:\n\n{}\n\nResume them now?",
+            lines.join("\n")
+        );
+        let ctx_for_confirm = ctx.clone();
+        let ctx_for_cancel = ctx.clone();
+        let for_confirm = checkpoints.clone();
+        let popup = ConfirmPopup::new_with_action(
+            "Resume jobs",
+            message,
+            "Resume",
+            "Not now",
+            ConfirmAction::new(
+                KeyCode::Char('r'),
+                KeyModifiers::CONTROL,
+                "^r",
+                "resume",
+                "Resume the interrupted jobs",
+            ),
+            move || {
+                ctx_for_confirm.emit_self(ResumeJobsRequest {
+                    checkpoints: for_confirm.clone(),
+                    start: true,
+                });
+            },
+            self.inner.id(),
+        )
+        .on_cancel(move || {
+            ctx_for_cancel.emit_self(ResumeJobsRequest {
+                checkpoints: checkpoints.clone(),
+                start: false,
+            });
+        });
+        popup.show(ctx, Action::ResumeJobs, "resume");
+    }
+
+    fn resume_jobs(&self, request: &ResumeJobsRequest, ctx: &crate::env::WidgetCtx) {
+        let connection = resume::connection(self.db.as_ref());
+        for checkpoint in &request.checkpoints {
+            if !request.start {
+                resume::clear(&connection, checkpoint.kind());
+            }
+            match checkpoint {
+                Checkpoint::Copy(checkpoint) => {
+                    copy::resume(self.copy_source(), checkpoint, request.start, ctx);
+                }
+                Checkpoint::Rename(job) => {
+                    rename::resume(self.rename_source(), job, request.start, ctx);
+                }
+                Checkpoint::Purge { table, .. } if request.start => {
+                    self.purge_table(table.clone(), ctx.clone());
+                }
+                Checkpoint::Purge { .. } => {}
+            }
+        }
+    }
+
+    fn copy_source(&self) -> copy::CopySource<'_> {
+        copy::CopySource {
+            db: self.table_db(),
+            copies: &self.copies,
+            state: &self.state,
+            parent: self.inner.id(),
+        }
+    }
+
+    fn seed_source(&self) -> seed::SeedSource<'_> {
+        seed::SeedSource {
+            db: self.table_db(),
+            state: &self.state,
+            parent: self.inner.id(),
+        }
+    }
+
+    fn rename_source(&self) -> rename::RenameSource<'_> {
+        rename::RenameSource {
+            db: self.table_db(),
+            state: &self.state,
+            parent: self.inner.id(),
+        }
+    }
+
+    fn show_create_table(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let popup = Box::new(CreateTablePopup::new(self.db.clone(), self.inner.id()));
+        ctx.set_popup(popup);
+    }
+
+    /// Describe the selected table and open its definition, renamed, in the
+    /// editor; saving creates a table from it.
+    fn show_create_like(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let Some(source) = self
+            .state
+            .borrow()
+            .selected_table_name()
+            .map(str::to_string)
+        else {
+            return;
+        };
+        let db = self.table_db();
+        tokio::spawn(async move {
+            let result = db
+                .describe_details(&source)
+                .await
+                .map(|details| TableSpec::from_details(&details))
+                .map_err(|err| err.to_string());
+            ctx.emit_self(CreateLikeEvent { source, result });
+        });
+    }
+
+    fn edit_create_like(&self, event: &CreateLikeEvent, ctx: &crate::env::WidgetCtx) {
+        let spec = match event.result.as_ref() {
+            Ok(spec) => spec.clone().renamed(&format!("{}-copy", event.source)),
+            Err(err) => {
+                self.show_error(
+                    ctx.clone(),
+                    &format!("Failed to describe {}: {err}", event.source),
+                );
+                return;
+            }
+        };
+        let text = serde_json::to_string_pretty(&spec.to_json()).unwrap_or_default();
+        let ctx_for_save = ctx.clone();
+        ctx.set_popup(Box::new(TextEditorPopup::new(
+            format!("New table like {}", event.source),
+            &text,
+            true,
+            move |text| ctx_for_save.emit_self(CreateLikeRequest { text }),
+            self.inner.id(),
+        )));
+    }
+
+    fn create_like(&self, request: &CreateLikeRequest, ctx: crate::env::WidgetCtx) {
+        let spec = serde_json::from_str(&request.text)
+            .map_err(|err| err.to_string())
+            .and_then(|value| TableSpec::from_json(&value));
+        let spec = match spec {
+            Ok(spec) => spec,
+            Err(err) => {
+                self.show_error(ctx, &format!("Invalid table definition: {err}"));
+                return;
+            }
+        };
+        ctx.show_toast(Toast {
+            message: format!("Creating {}...", spec.collection.name),
+            kind: ToastKind::Info,
+            duration: Duration::from_hours(1),
+            action: None,
+        });
+        let db = self.table_db();
+        tokio::spawn(async move {
+            let result = table_spec::create(db.as_ref(), &spec)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(CreateLikeFinishedEvent {
+                table_name: spec.collection.name,
+                result,
+            });
+        });
+    }
+
+    fn on_create_like_finished(&self, event: &CreateLikeFinishedEvent, ctx: crate::env::WidgetCtx) {
+        match event.result.as_ref() {
+            Ok(()) => ctx.show_toast(Toast {
+                message: format!("Table {} created", event.table_name),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(3),
+                action: None,
+            }),
+            Err(err) => self.show_error(
+                ctx.clone(),
+                &format!("Failed to create {}: {err}", event.table_name),
+            ),
+        }
+        self.reload_tables(ctx);
+    }
+
+    /// Open the full details view for the selected table.
+    fn show_table_details(&self, ctx: crate::env::WidgetCtx) {
+        let selected = {
+            self.state
+                .borrow()
+                .selected_table_name()
+                .map(str::to_string)
+        };
+        if let Some(table_name) = selected {
+            let widget = Box::new(TableDetailsWidget::new(
+                self.table_db(),
+                &table_name,
+                self.inner.id(),
+            ));
+            ctx.push_widget(widget);
+        }
+    }
+
+    /// Open the analysis view for the selected table.
+    fn show_analyze(&self, ctx: crate::env::WidgetCtx) {
+        let selected = {
+            self.state
+                .borrow()
+                .selected_table_name()
+                .map(str::to_string)
+        };
+        if let Some(table_name) = selected {
+            let widget = Box::new(AnalyzeWidget::new(
+                self.table_db(),
+                &table_name,
+                self.inner.id(),
+            ));
+            ctx.push_widget(widget);
+        }
+    }
+
+    /// Open the backups view for the selected table.
+    fn show_backups(&self, ctx: crate::env::WidgetCtx) {
+        let selected = {
+            self.state
+                .borrow()
+                .selected_table_name()
+                .map(str::to_string)
+        };
+        if let Some(table_name) = selected {
+            let widget = Box::new(BackupsWidget::new(
+                self.table_db(),
+                &table_name,
+                self.inner.id(),
+            ));
+            ctx.push_widget(widget);
+        }
+    }
+
+    /// Open the CloudWatch metrics view for the selected table.
+    fn show_metrics(&self, ctx: crate::env::WidgetCtx) {
+        let selected = {
+            self.state
+                .borrow()
+                .selected_table_name()
+                .map(str::to_string)
+        };
+        if let Some(table_name) = selected {
+            let widget = Box::new(MetricsWidget::new(
+                self.table_db(),
+                &table_name,
+                self.inner.id(),
+            ));
+            ctx.push_widget(widget);
+        }
+    }
+
+    /// Open the list of imports from S3.
+    fn show_imports(&self, ctx: &crate::env::WidgetCtx) {
+        ctx.push_widget(Box::new(ImportsWidget::new(
+            self.db.clone(),
+            self.inner.id(),
+        )));
+    }
+
+    /// Open the billing mode/capacity editor for the selected table.
+    fn show_capacity(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            show_readonly_toast(&ctx);
+            return;
+        }
+        let selected = {
+            self.state
+                .borrow()
+                .selected_table_name()
+                .map(str::to_string)
+        };
+        if let Some(table_name) = selected {
+            let popup = Box::new(CapacityPopup::new(
+                self.table_db(),
+                &table_name,
+                self.inner.id(),
+            ));
+            ctx.set_popup(popup);
+        }
+    }
+
+    /// Whether this backend offers a free-form database-level query (SQL).
+    fn is_sql(&self) -> bool {
+        self.db.capabilities().raw_query
+    }
+
+    /// Open the schema popup for the current selection; up/down navigates the
+    /// other tables (in the current filtered order) without leaving it.
+    fn show_schema_popup(&self, ctx: crate::env::WidgetCtx) {
+        let (schemas, index) = {
+            let state = self.state.borrow();
+            let schemas: Vec<CollectionSchema> = state
+                .filtered_indices
+                .iter()
+                .filter_map(|idx| state.tables.get(*idx))
+                .map(|entry| entry.schema.clone())
+                .collect();
+            (schemas, state.table_state.selected().unwrap_or(0))
+        };
+        if schemas.is_empty() {
+            return;
+        }
+        ctx.set_popup(Box::new(SchemaPopup::new(schemas, index, self.inner.id())));
+    }
+
+    /// Load the available profiles, then open the profile/region picker.
+    fn show_profile_picker(&self, ctx: crate::env::WidgetCtx) {
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let result = db.list_profiles().await.map_err(|err| err.to_string());
+            ctx.emit_self(ProfileListEvent { result });
+        });
+    }
+
+    /// Open the free-form SQL query view (a dynamic action; the view focuses its
+    /// input and autocompletes table/column names).
+    fn open_sql_query(&self, ctx: crate::env::WidgetCtx) {
+        let widget = Box::new(QueryWidget::new_raw_sql(self.db.clone(), self.inner.id()));
+        ctx.push_widget(widget);
+    }
+
+    fn handle_input(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let (filter_active, filter_applied, busy) = {
+            let state = self.state.borrow();
+            (
+                state.filter.is_active(),
+                !state.filter.value.is_empty(),
+                matches!(state.loading_state, LoadingState::Busy(_)),
+            )
+        };
+        if busy {
+            return true;
+        }
+        if let Some(key) = event.as_key_press_event()
+            && key.code == KeyCode::Char('r')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            self.reload_tables(ctx);
+            return true;
+        }
+        if let Some(mouse) = event.as_mouse_event() {
+            return self.handle_mouse(ctx, mouse);
+        }
+        if filter_active {
+            let mut state = self.state.borrow_mut();
+            if state.filter.handle_event(event) {
+                state.apply_filter();
+                return true;
+            }
+        }
+
+        if let Some(key) = event.as_key_press_event() {
+            match key.code {
+                KeyCode::Char('/') => {
+                    let mut state = self.state.borrow_mut();
+                    state.filter.set_active(true);
+                    return true;
+                }
+                KeyCode::Char('q') if !filter_active && self.is_sql() => {
+                    self.open_sql_query(ctx);
+                    return true;
+                }
+                KeyCode::Tab if !filter_active => {
+                    self.show_schema_popup(ctx);
+                    return true;
+                }
+                KeyCode::Enter if !filter_active => {
+                    return self.handle_selection(ctx);
+                }
+                KeyCode::Char('o') if !filter_active && key.modifiers.is_empty() => {
+                    self.open_in_new_tab(&ctx);
+                    return true;
+                }
+                KeyCode::Char('i') if !filter_active && key.modifiers.is_empty() => {
+                    self.show_table_details(ctx);
+                    return true;
+                }
+                KeyCode::Char('a') if !filter_active && key.modifiers.is_empty() => {
+                    self.show_analyze(ctx);
+                    return true;
+                }
+                KeyCode::Char('b')
+                    if !filter_active
+                        && key.modifiers.is_empty()
+                        && self.db.capabilities().backups =>
+                {
+                    self.show_backups(ctx);
+                    return true;
+                }
+                KeyCode::Char('M') if !filter_active && self.db.capabilities().metrics => {
+                    self.show_metrics(ctx);
+                    return true;
+                }
+                KeyCode::Char('s') if !filter_active && key.modifiers.is_empty() => {
+                    if self.db.is_read_only() {
+                        show_readonly_toast(&ctx);
+                    } else if let Err(err) = seed::prompt(&self.seed_source(), &ctx) {
+                        self.show_error(ctx, &err);
+                    }
+                    return true;
+                }
+                KeyCode::Char('S') if !filter_active && !self.is_sql() => {
+                    self.cycle_sort(&ctx);
+                    return true;
+                }
+                KeyCode::Char('I') if !filter_active && self.db.capabilities().imports => {
+                    self.show_imports(&ctx);
+                    return true;
+                }
+                KeyCode::Char('R')
+                    if !filter_active
+                        && self.db.capabilities().regions
+                        && !util::regions().is_empty() =>
+                {
+                    self.toggle_regions(ctx);
+                    return true;
+                }
+                KeyCode::Char('l') if !filter_active && key.modifiers.is_empty() => {
+                    ctx.push_widget(Box::new(AuditLogWidget::new(
+                        self.db.clone(),
+                        self.inner.id(),
+                    )));
+                    return true;
+                }
+                KeyCode::Char('c')
+                    if !filter_active
+                        && key.modifiers.is_empty()
+                        && self.db.capabilities().capacity =>
+                {
+                    self.show_capacity(ctx);
+                    return true;
+                }
+                KeyCode::Esc if !filter_active && self.state.borrow().purge_job.is_some() => {
+                    self.cancel_purge();
+                    return true;
+                }
+                KeyCode::Esc if !filter_active && self.state.borrow().seed_job.is_some() => {
+                    self.cancel_seed();
+                    return true;
+                }
+                KeyCode::Esc if !filter_active && filter_applied => {
+                    let mut state = self.state.borrow_mut();
+                    state.filter.clear();
+                    state.apply_filter();
+                    return true;
+                }
+                KeyCode::Esc if !filter_active => {
+                    ctx.pop_widget();
+                    return true;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.select_next();
+                    return true;
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.select_previous();
+                    return true;
+                }
+                KeyCode::PageDown => {
+                    self.page_down();
+                    return true;
+                }
+                KeyCode::PageUp => {
+                    self.page_up();
+                    return true;
+                }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if self.db.is_read_only() {
+                        show_readonly_toast(&ctx);
+                    } else {
+                        self.confirm_table_action(ctx, TableAction::Delete);
+                    }
+                    return true;
+                }
+                KeyCode::Char('p')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.db.capabilities().purge =>
+                {
+                    if self.db.is_read_only() {
+                        show_readonly_toast(&ctx);
+                    } else if self.state.borrow().purge_job.is_some() {
+                        self.show_error(ctx, "A purge is already running");
+                    } else {
+                        self.confirm_table_action(ctx, TableAction::Purge);
+                    }
+                    return true;
+                }
+                KeyCode::Char('p')
+                    if key.modifiers.is_empty() && self.db.capabilities().profiles =>
+                {
+                    self.show_profile_picker(ctx);
+                    return true;
+                }
+                KeyCode::Char('P') if self.db.capabilities().profiles => {
+                    ctx.set_popup(Box::new(AssumeRolePopup::new(
+                        self.db.clone(),
+                        self.inner.id(),
+                    )));
+                    return true;
+                }
+                KeyCode::Char('n')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.db.capabilities().create_collection =>
+                {
+                    self.show_create_table(ctx);
+                    return true;
+                }
+                KeyCode::Char('N')
+                    if !filter_active && self.db.capabilities().create_collection =>
+                {
+                    self.show_create_like(ctx);
+                    return true;
+                }
+                KeyCode::Char('t')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.db.capabilities().create_collection =>
+                {
+                    if self.db.is_read_only() {
+                        show_readonly_toast(&ctx);
+                    } else if let Err(err) = copy::prompt(&self.copy_source(), &ctx) {
+                        self.show_error(ctx, &err);
+                    }
+                    return true;
+                }
+                KeyCode::Char('m')
+                    if !filter_active
+                        && key.modifiers.is_empty()
+                        && self.db.capabilities().create_collection =>
+                {
+                    if self.db.is_read_only() {
+                        show_readonly_toast(&ctx);
+                    } else if let Err(err) = rename::prompt(&self.rename_source(), &ctx) {
+                        self.show_error(ctx, &err);
+                    }
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+fn show_readonly_toast(ctx: &crate::env::WidgetCtx) {
+    ctx.show_toast(Toast {
+        message: dynamate::readonly::REJECT_MESSAGE.to_string(),
+        kind: ToastKind::Warning,
+        duration: dynamate::readonly::TOAST_DURATION,
+        action: None,
+    });
+}
+
+impl crate::widgets::Widget for TablePickerWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some("tables".to_string())
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            context: self.db.active_profile().map(|selection| selection.label()),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        let state = self.state.borrow();
+        matches!(
+            state.loading_state,
+            LoadingState::Loading | LoadingState::Busy(_)
+        ) || state.listing
+            || state.describe_pending > 0
+    }
+
+    fn start(&self, ctx: crate::env::WidgetCtx) {
+        self.offer_resume(&ctx);
+        self.reload_tables(ctx);
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let sql = self.is_sql();
+        let filter_active = state.filter.is_active();
+        let list_area = if filter_active {
+            let layout = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]);
+            let [filter_area, list_area] = area.layout(&layout);
+            state.filter.render(frame, filter_area, theme);
+            list_area
+        } else {
+            area
+        };
+
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled("Tables", Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled("Tables", Style::default().fg(theme.text()))
+        };
+
+        let total_tables = state.tables.len();
+        let filtered_tables = state.filtered_indices.len();
+        let mut count_label = format_table_count_label(total_tables, filtered_tables);
+        if state.next_page.is_some() {
+            count_label.push_str(" · more as you scroll");
+        }
+        if state.sort != SortColumn::Name {
+            count_label.push_str(&format!(" · sorted by {}", state.sort.label()));
+        }
+
+        let block = Block::bordered()
+            .title_top(title)
+            .title_bottom(Line::styled(
+                pad(count_label, 2),
+                Style::default().fg(theme.text_muted()),
+            ))
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+
+        match &state.loading_state {
+            LoadingState::Loading => {
+                let text = Paragraph::new("Loading tables...")
+                    .style(Style::default().fg(theme.warning()))
+                    .block(block);
+                frame.render_widget(text, list_area);
+            }
+            LoadingState::Busy(message) => {
+                let text = Paragraph::new(message.as_str())
+                    .style(Style::default().fg(theme.warning()))
+                    .block(block);
+                frame.render_widget(text, list_area);
+            }
+            LoadingState::Error(_) => {
+                let text = Paragraph::new("Error")
+                    .style(Style::default().fg(theme.error()))
+                    .block(block);
+                frame.render_widget(text, list_area);
+            }
+            LoadingState::Idle | LoadingState::Loaded => {
+                if state.filtered_indices.is_empty() {
+                    let empty = Paragraph::new("").block(block);
+                    frame.render_widget(empty, list_area);
+                } else {
+                    let header_style = Style::default()
+                        .fg(theme.text_muted())
+                        .add_modifier(ratatui::style::Modifier::BOLD);
+                    let header = if sql {
+                        Row::new(vec![
+                            Cell::from("Table"),
+                            Cell::from(Text::from("Columns").alignment(Alignment::Right)),
+                            Cell::from("Primary key"),
+                            Cell::from(Text::from("Indexes").alignment(Alignment::Right)),
+                        ])
+                        .style(header_style)
+                    } else {
+                        let sort = state.sort;
+                        let mut cells = vec![
+                            Cell::from(sort.header(SortColumn::Name, "Table")),
+                            Cell::from("Status"),
+                            Cell::from(
+                                Text::from(sort.header(SortColumn::Items, "Items"))
+                                    .alignment(Alignment::Right),
+                            ),
+                            Cell::from(
+                                Text::from(sort.header(SortColumn::Size, "Size"))
+                                    .alignment(Alignment::Right),
+                            ),
+                            Cell::from(Text::from("Indexes").alignment(Alignment::Right)),
+                        ];
+                        if state.multi_region {
+                            cells.insert(1, Cell::from("Region"));
+                        }
+                        Row::new(cells).style(header_style)
+                    };
+
+                    let spinner = self.spinner();
+                    let rows: Vec<Row> = state
+                        .filtered_indices
+                        .iter()
+                        .filter_map(|idx| state.tables.get(*idx))
+                        .map(|entry| {
+                            if sql {
+                                let columns = if entry.is_describing() {
+                                    spinner.to_string()
+                                } else {
+                                    entry.schema.columns.len().to_string()
+                                };
+                                let pk = sql_primary_key(&entry.schema);
+                                let indexes = entry.schema.indexes.len().to_string();
+                                Row::new(vec![
+                                    Cell::from(entry.name.clone()),
+                                    Cell::from(Text::from(columns).alignment(Alignment::Right)),
+                                    Cell::from(pk),
+                                    Cell::from(Text::from(indexes).alignment(Alignment::Right)),
+                                ])
+                            } else {
+                                let (status, status_style) = if entry.is_describing() {
+                                    (spinner.to_string(), Style::default().fg(theme.warning()))
+                                } else {
+                                    (
+                                        entry.meta.status.clone(),
+                                        status_style(&entry.meta.status, theme),
+                                    )
+                                };
+                                let items = format_count(entry.meta.item_count);
+                                let size = format_size_bytes(entry.meta.size_bytes);
+                                let idx_label =
+                                    format!("G{}/L{}", entry.meta.gsi_count, entry.meta.lsi_count);
+                                let mut cells = vec![
+                                    Cell::from(entry.name.clone()),
+                                    Cell::from(status).style(status_style),
+                                    Cell::from(Text::from(items).alignment(Alignment::Right)),
+                                    Cell::from(Text::from(size).alignment(Alignment::Right)),
+                                    Cell::from(Text::from(idx_label).alignment(Alignment::Right)),
+                                ];
+                                if let Some(region) = &entry.region {
+                                    cells.insert(1, Cell::from(region.clone()));
+                                }
+                                Row::new(cells)
+                            }
+                        })
+                        .collect();
+
+                    let inner = block.inner(list_area);
+                    state.rows_area = Rect {
+                        y: inner.y.saturating_add(1),
+                        height: inner.height.saturating_sub(1),
+                        ..inner
+                    };
+                    let mut widths = if sql {
+                        vec![
+                            Constraint::Fill(1),
+                            Constraint::Length(9),
+                            Constraint::Length(24),
+                            Constraint::Length(9),
+                        ]
+                    } else {
+                        vec![
+                            Constraint::Fill(1),
+                            Constraint::Length(10),
+                            Constraint::Length(9),
+                            Constraint::Length(10),
+                            Constraint::Length(8),
+                        ]
+                    };
+                    if state.multi_region {
+                        widths.insert(1, Constraint::Length(16));
+                    }
+                    let table = Table::new(rows, widths)
+                        .block(block)
+                        .header(header)
+                        .highlight_spacing(HighlightSpacing::Always)
+                        .highlight_symbol("❯ ")
+                        .row_highlight_style(
+                            Style::default()
+                                .bg(theme.selection_bg())
+                                .fg(theme.selection_fg()),
+                        );
+
+                    let visible_rows = inner.height.saturating_sub(1) as usize;
+                    state.last_render_capacity = visible_rows;
+                    state.clamp_offset();
+
+                    StatefulWidget::render(
+                        table,
+                        list_area,
+                        frame.buffer_mut(),
+                        &mut state.table_state,
+                    );
+                }
+            }
+        }
+
+        let value = state.filter.value.as_str();
+        if !value.is_empty() {
+            let title = format!("</{value}>");
+            let width = title.width() as u16;
+            if list_area.width > 2 && width < list_area.width - 2 {
+                let start = list_area.x + (list_area.width - width) / 2;
+                let y = list_area.y;
+                let buf = frame.buffer_mut();
+                buf.set_stringn(
+                    start,
+                    y,
+                    title,
+                    width as usize,
+                    Style::default().fg(theme.accent()),
+                );
+            }
+        }
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(page_event) = event.payload::<TablePageEvent>() {
+            if page_event.reload != self.state.borrow().reload_seq {
+                return;
+            }
+            self.state.borrow_mut().listing = false;
+            match page_event.result.as_ref() {
+                Ok(page) => {
+                    self.on_table_page(&page.names, page.next.clone(), page_event.append);
+                    self.load_visible(&ctx);
+                }
+                Err(err) => {
+                    let mut state = self.state.borrow_mut();
+                    // A later page failing keeps the tables listed so far;
+                    // scrolling on tries it again.
+                    if !page_event.append {
+                        state.loading_state = LoadingState::Error(err.clone());
+                    }
+                    let is_empty = state.tables.is_empty();
+                    drop(state);
+                    if is_empty {
+                        ctx.set_popup(Box::new(ErrorPopup::new(
+                            "Error",
+                            err.clone(),
+                            self.inner.id(),
+                        )));
+                    } else {
+                        ctx.show_toast(Toast {
+                            message: err.clone(),
+                            kind: ToastKind::Error,
+                            duration: Duration::from_secs(4),
+                            action: None,
+                        });
+                    }
+                }
+            }
+            ctx.invalidate();
+            return;
+        }
+
+        if let Some(listed) = event.payload::<RegionListEvent>() {
+            if listed.reload == self.state.borrow().reload_seq {
+                self.on_region_list(listed, &ctx);
+            }
+            return;
+        }
+
+        if let Some(described) = event.payload::<TableDescribedEvent>() {
+            self.on_table_described(described, &ctx);
+            return;
+        }
+
+        if let Some(profiles) = event.payload::<ProfileListEvent>() {
+            match profiles.result.as_ref() {
+                Ok(profiles) => ctx.set_popup(Box::new(ProfilePickerWidget::new(
+                    self.db.clone(),
+                    profiles.clone(),
+                    self.inner.id(),
+                ))),
+                Err(err) => self.show_error(ctx, &format!("Failed to list profiles: {err}")),
+            }
+            return;
+        }
+
+        if let Some(request) = event.payload::<DeleteTableRequest>() {
+            self.delete_table(request.table_name.clone(), ctx);
+            return;
+        }
+
+        if let Some(request) = event.payload::<PurgeTableRequest>() {
+            self.purge_table(request.table_name.clone(), ctx);
+            return;
+        }
+
+        if let Some(flow) = seed::handle(event, &ctx, || self.seed_source()) {
+            match flow {
+                seed::SeedFlow::Continuing => {}
+                seed::SeedFlow::Seeded => self.reload_tables(ctx),
+                seed::SeedFlow::Failed(err) => self.show_error(ctx, &err),
+            }
+            return;
+        }
+
+        if let Some(flow) = copy::handle(event, &ctx, || self.copy_source()) {
+            if let copy::CopyFlow::Finished(result) = flow {
+                if let Err(err) = result {
+                    self.show_error(ctx.clone(), &err);
+                }
+                self.reload_tables(ctx);
+            }
+            return;
+        }
+
+        if let Some(flow) = rename::handle(event, &ctx, || self.rename_source()) {
+            if let rename::RenameFlow::Finished(result) = flow {
+                if let Err(err) = result {
+                    self.show_error(ctx.clone(), &err);
+                }
+                self.reload_tables(ctx);
+            }
+            return;
+        }
+        if let Some(request) = event.payload::<ResumeJobsRequest>() {
+            self.resume_jobs(request, &ctx);
+            return;
+        }
+
+        if let Some(described) = event.payload::<CreateLikeEvent>() {
+            self.edit_create_like(described, &ctx);
+            return;
+        }
+        if let Some(request) = event.payload::<CreateLikeRequest>() {
+            self.create_like(request, ctx);
+            return;
+        }
+        if let Some(finished) = event.payload::<CreateLikeFinishedEvent>() {
+            self.on_create_like_finished(finished, ctx);
+            return;
+        }
+
+        if let Some(result) = event.payload::<DeleteTableEvent>() {
+            match result.result.as_ref() {
+                Ok(()) => {
+                    ctx.show_toast(Toast {
+                        message: format!("Table {} deleted", result.table_name),
+                        kind: ToastKind::Info,
+                        duration: Duration::from_secs(3),
+                        action: None,
+                    });
+                    self.reload_tables(ctx);
+                }
+                Err(err) => {
+                    let message = format!("Failed to delete table: {err}");
+                    {
+                        let mut state = self.state.borrow_mut();
+                        state.loading_state = LoadingState::Loaded;
+                    }
+                    self.show_error(ctx.clone(), &message);
+                    ctx.invalidate();
+                }
+            }
+            return;
+        }
+
+        if let Some(purge) = event.payload::<PurgeTableEvent>() {
+            self.purges.finish(purge.run_id);
+            let Some(job) = self.state.borrow_mut().purge_job.take() else {
+                return;
+            };
+            match purge.result.as_ref() {
+                Ok(summary) => {
+                    job.finish(Ok(format!("Purged {} items", summary.progress.deleted)));
+                    ctx.set_popup(Box::new(PurgeSummaryPopup::new(
+                        "Purge",
+                        purge.table_name.clone(),
+                        summary.clone(),
+                        self.inner.id(),
+                    )));
+                    self.reload_tables(ctx);
+                }
+                Err(err) => {
+                    job.finish_quietly(Err(err.clone()));
+                    self.show_error(ctx.clone(), &format!("Failed to purge table: {err}"));
+                    ctx.invalidate();
+                }
+            }
+        }
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let handled = self.handle_input(ctx.clone(), event);
+        if handled {
+            self.load_visible(&ctx);
+        }
+        handled
+    }
+
+    fn on_app_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if let Some(created) = event.payload::<TableCreatedEvent>() {
+            tracing::debug!(table = %created.table_name, "table_created");
+            self.reload_tables(ctx);
+            return;
+        }
+        if let Some(switched) = event.payload::<ProfileSwitchedEvent>() {
+            tracing::debug!(profile = %switched.selection.label(), "profile_switched");
+            {
+                let mut state = self.state.borrow_mut();
+                state.tables.clear();
+                state.filtered_indices.clear();
+                state.table_state = TableState::default();
+            }
+            self.offer_resume(&ctx);
+            self.reload_tables(ctx);
+            return;
+        }
+        // Keep the list selection in sync as the schema popup pages through tables.
+        if let Some(nav) = event.payload::<SchemaNavEvent>() {
+            let mut state = self.state.borrow_mut();
+            if let Some(pos) = state
+                .filtered_indices
+                .iter()
+                .position(|idx| state.tables.get(*idx).is_some_and(|e| e.name == nav.table))
+            {
+                state.table_state.select(Some(pos));
+                state.clamp_offset();
+                drop(state);
+                ctx.invalidate();
+            }
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        let state = self.state.borrow();
+        if state.filter.is_active() {
+            Some(Self::HELP_FILTER_EDIT)
+        } else if !state.filter.value.is_empty() {
+            Some(&self.help_filter_applied)
+        } else {
+            Some(&self.help_base)
+        }
+    }
+
+    fn suppress_global_help(&self) -> bool {
+        self.state.borrow().filter.is_active()
+    }
+}
+
+/// A plain (no-modifier) help entry.
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+/// A Ctrl-modified help entry.
+fn help_ctrl(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(""),
+        short: Cow::Borrowed(""),
+        long: Cow::Borrowed(""),
+        ctrl: Some(help::Variant {
+            keys: Some(Cow::Borrowed(keys)),
+            short: Some(Cow::Borrowed(short)),
+            long: Some(Cow::Borrowed(long)),
+        }),
+        shift: None,
+        alt: None,
+    }
+}
+
+/// The picker's help line, tuned to the backend's capabilities. `applied` is the
+/// variant shown when a filter is already in effect.
+fn build_help(
+    caps: &dynamate::core::capabilities::Capabilities,
+    applied: bool,
+) -> Vec<help::Entry<'static>> {
+    let mut entries = Vec::new();
+    if applied {
+        entries.push(help_entry("/", "filter", "Edit filter"));
+        entries.push(help_entry("esc", "clear filter", "Clear filter"));
+    } else {
+        entries.push(help_entry("/", "filter", "Filter tables"));
+    }
+    entries.push(help_entry("⏎", "select", "Open table"));
+    entries.push(help_entry("o", "new tab", "Open table in a new tab"));
+    entries.push(help_entry("j/k/↑/↓/PgUp/PgDn", "move", "Move selection"));
+    entries.push(help_entry("⇥", "schema", "View schema"));
+    entries.push(help_entry("i", "details", "View table details"));
+    entries.push(help_entry("a", "analyze", "Profile the table's items"));
+    entries.push(help_entry("s", "seed", "Generate sample items"));
+    if !caps.raw_query {
+        entries.push(help_entry("S", "sort", "Sort by name, item count, or size"));
+    }
+    if caps.regions && !util::regions().is_empty() {
+        entries.push(help_entry(
+            "R",
+            "regions",
+            "List tables across the configured regions",
+        ));
+    }
+    entries.push(help_entry("l", "audit log", "Browse the write audit log"));
+    if caps.capacity {
+        entries.push(help_entry("c", "capacity", "Edit billing mode/capacity"));
+    }
+    if caps.backups {
+        entries.push(help_entry("b", "backups", "Manage backups"));
+    }
+    if caps.metrics {
+        entries.push(help_entry("M", "metrics", "Chart capacity and latency"));
+    }
+    if caps.imports {
+        entries.push(help_entry("I", "imports", "Import tables from S3"));
+    }
+    if caps.raw_query {
+        entries.push(help_entry("q", "query", "Run SQL query"));
+    }
+    if caps.profiles {
+        entries.push(help_entry("p", "profile", "Switch AWS profile/region"));
+        entries.push(help_entry("P", "role", "Assume an IAM role"));
+    }
+    if caps.create_collection {
+        entries.push(help_ctrl("^n", "new", "Create table"));
+        entries.push(help_entry(
+            "N",
+            "create like",
+            "Create a table from the selected one's definition",
+        ));
+        entries.push(help_ctrl("^t", "copy", "Copy table"));
+        entries.push(help_entry(
+            "m",
+            "rename",
+            "Rename table: create, copy, verify, and delete the old one",
+        ));
+    }
+    entries.push(help_ctrl("^r", "refresh", "Refresh tables"));
+    entries.push(help_ctrl("^d", "delete", "Delete table"));
+    if caps.purge {
+        entries.push(help_ctrl("^p", "purge", "Purge table"));
+    }
+    entries
+}
+
+/// A table to describe, with the client for its region.
+struct DescribeJob {
+    db: Arc<dyn Datastore>,
+    region: Option<String>,
+    name: String,
+}
+
+/// Describe `jobs` [`DESCRIBE_CONCURRENCY`] at a time, reporting each one as
+/// it finishes.
+async fn describe_tables(jobs: Vec<DescribeJob>, reload: u64, ctx: crate::env::WidgetCtx) {
+    let mut described = futures::stream::iter(jobs)
+        .map(|job| async move {
+            let result = job
+                .db
+                .describe_collection(&job.name)
+                .await
+                .map_err(|err| err.to_string());
+            (job.region, job.name, result)
+        })
+        .buffer_unordered(DESCRIBE_CONCURRENCY);
+    while let Some((region, name, result)) = described.next().await {
+        ctx.emit_self(TableDescribedEvent {
+            reload,
+            name,
+            region,
+            result,
+        });
+    }
+}
+
+fn table_meta_from(schema: &dynamate::core::schema::CollectionSchema) -> TableMeta {
+    TableMeta {
+        status: schema
+            .status
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+        item_count: schema.item_count,
+        size_bytes: schema.size_bytes,
+        gsi_count: schema.global_secondary_index_count(),
+        lsi_count: schema.local_secondary_index_count(),
+    }
+}
+
+fn format_count(count: Option<i64>) -> String {
+    count.map_or_else(|| "—".to_string(), |value| value.to_string())
+}
+
+/// The comma-joined primary-key column names of a SQL table (or `—` if none).
+fn sql_primary_key(schema: &CollectionSchema) -> String {
+    if schema.key.fields.is_empty() {
+        "—".to_string()
+    } else {
+        schema
+            .key
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn format_table_count(count: usize) -> String {
+    match count {
+        0 => "no tables".to_string(),
+        1 => "1 table".to_string(),
+        _ => format!("{count} tables"),
+    }
+}
+
+fn format_table_count_label(total: usize, filtered: usize) -> String {
+    if total == filtered {
+        format_table_count(total)
+    } else {
+        let total_label = if total == 1 { "table" } else { "tables" };
+        format!("{filtered} of {total} {total_label}")
+    }
+}
+
+fn format_size_bytes(size: Option<i64>) -> String {
+    size.and_then(|value| u64::try_from(value).ok())
+        .map_or_else(|| "—".to_string(), |value| format_size(value, BINARY))
+}
+
+fn status_style(status: &str, theme: &Theme) -> Style {
+    let lower = status.to_ascii_lowercase();
+    if lower == "active" {
+        Style::default().fg(theme.success())
+    } else if lower.contains("create") || lower.contains("update") || lower.contains("delete") {
+        Style::default().fg(theme.warning())
+    } else if lower.contains("error") {
+        Style::default().fg(theme.error())
+    } else {
+        Style::default().fg(theme.text_muted())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::schema::CollectionSchema;
+
+    use super::{Describe, MAX_DESCRIBED, SortColumn, TableEntry, TablePickerState};
+
+    fn entry(name: &str, item_count: Option<i64>) -> TableEntry {
+        TableEntry::new(
+            name.to_string(),
+            CollectionSchema {
+                name: name.to_string(),
+                item_count,
+                ..CollectionSchema::default()
+            },
+        )
+    }
+
+    #[test]
+    fn item_counts_sort_largest_first_with_unknown_last() {
+        let mut tables = [
+            entry("b", None),
+            entry("c", Some(5)),
+            entry("a", Some(5)),
+            entry("d", Some(40)),
+        ];
+        tables.sort_by(|a, b| SortColumn::Items.compare(a, b));
+        let names: Vec<&str> = tables.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["d", "a", "c", "b"]);
+        tables.sort_by(|a, b| SortColumn::Name.compare(a, b));
+        assert_eq!(tables[0].name, "a");
+    }
+
+    #[test]
+    fn forgets_the_oldest_metadata_past_the_limit() {
+        let mut state = TablePickerState::default();
+        for seq in 0..=MAX_DESCRIBED as u64 {
+            let mut table = entry(&format!("t{seq:04}"), Some(1));
+            table.describe = Describe::Done(seq + 1);
+            state.tables.push(table);
+        }
+        state.evict_described();
+        assert_eq!(state.tables[0].describe, Describe::Wanted);
+        assert_eq!(state.tables[0].meta.item_count, None);
+        assert_eq!(state.tables[1].describe, Describe::Done(2));
+    }
+
+    #[test]
+    fn filters_and_orders_tables_by_region() {
+        let mut state = TablePickerState::default();
+        for region in ["us-west-2", "eu-west-1"] {
+            state
+                .tables
+                .push(entry("orders", None).with_region(Some(region.to_string())));
+        }
+        state.apply_filter();
+        assert_eq!(state.filtered_indices, [1, 0]);
+        state.filter.value = "west-2".to_string();
+        state.apply_filter();
+        assert_eq!(state.filtered_indices, [0]);
+    }
+}
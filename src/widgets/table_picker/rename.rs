@@ -0,0 +1,287 @@
+//! Renaming the selected table (`m`). DynamoDB has no rename, so the new
+//! table is created from the old one's definition, the items are copied and
+//! counted, and the old table is deleted if asked for.
+//!
+//! The rename runs as a job and saves a checkpoint at every step, so a failed
+//! or interrupted one can be carried on with. Each step comes back as one of
+//! the events here; [`handle`] takes it from there and tells the table picker
+//! what it has to do about it.
+
+use std::{cell::RefCell, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::rename::{self, RenameJob, RenameStep};
+
+use crate::{
+    env::{AppEvent, WidgetCtx, WidgetId},
+    jobs,
+    resume::{self, Checkpoint},
+    safety::Action,
+    widgets::{
+        confirm::{ConfirmAction, ConfirmPopup},
+        copy_table::CopyTablePopup,
+    },
+};
+
+use super::{TablePickerState, show_readonly_toast};
+
+struct RenameTableRequest {
+    source: String,
+    destination: String,
+    drop_source: bool,
+    /// Whether deleting the source has been confirmed.
+    confirmed: bool,
+}
+
+struct RenameTableEvent {
+    job: RenameJob,
+    result: Result<(), String>,
+}
+
+/// What a rename needs from the table picker.
+pub(super) struct RenameSource<'a> {
+    /// The client for the selected table.
+    pub(super) db: Arc<dyn Datastore>,
+    /// Holds the running rename and the last failed one.
+    pub(super) state: &'a RefCell<TablePickerState>,
+    pub(super) parent: WidgetId,
+}
+
+/// Where a rename got to, for the table picker to act on.
+pub(super) enum RenameFlow {
+    /// The next step is under way; nothing to do.
+    Continuing,
+    /// The rename finished, was canceled, or failed; the tables changed
+    /// either way.
+    Finished(Result<(), String>),
+}
+
+/// Ask for the new name of the selected table, offering to resume the last
+/// failed rename of it.
+pub(super) fn prompt(source: &RenameSource<'_>, ctx: &WidgetCtx) -> Result<(), String> {
+    let (table, destination, drop_source) = {
+        let state = source.state.borrow();
+        if state.rename_job.is_some() {
+            return Err("A table rename is already running".to_string());
+        }
+        let Some(table) = state.selected_table_name().map(str::to_string) else {
+            return Ok(());
+        };
+        match state
+            .rename_resume
+            .as_ref()
+            .filter(|job| job.source == table)
+        {
+            Some(job) => (table, job.destination.clone(), job.drop_source),
+            None => (table.clone(), table, false),
+        }
+    };
+    let ctx_for_submit = ctx.clone();
+    let table_for_submit = table.clone();
+    ctx.set_popup(Box::new(CopyTablePopup::rename(
+        &table,
+        &destination,
+        drop_source,
+        move |destination, drop_source| {
+            ctx_for_submit.emit_self(RenameTableRequest {
+                source: table_for_submit.clone(),
+                destination,
+                drop_source,
+                confirmed: false,
+            });
+        },
+        source.parent.clone(),
+    )));
+    Ok(())
+}
+
+/// Remember a rename a previous session didn't finish, and carry on with it
+/// if `start`. Deleting the source was confirmed when it was first started.
+pub(super) fn resume(source: RenameSource<'_>, job: &RenameJob, start: bool, ctx: &WidgetCtx) {
+    source.state.borrow_mut().rename_resume = Some(job.clone());
+    if start {
+        let request = RenameTableRequest {
+            source: job.source.clone(),
+            destination: job.destination.clone(),
+            drop_source: job.drop_source,
+            confirmed: true,
+        };
+        run(source, &request, ctx);
+    }
+}
+
+/// Take the rename one step further if `event` is one of its own. `source`
+/// is only built then.
+pub(super) fn handle<'a>(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    source: impl FnOnce() -> RenameSource<'a>,
+) -> Option<RenameFlow> {
+    if let Some(request) = event.payload::<RenameTableRequest>() {
+        run(source(), request, ctx);
+        return Some(RenameFlow::Continuing);
+    }
+    let finished = event.payload::<RenameTableEvent>()?;
+    finish(&source(), finished).map(RenameFlow::Finished)
+}
+
+/// Confirm deleting the source like any table delete, then start the rename,
+/// or carry on with the failed one it matches.
+fn run(source: RenameSource<'_>, request: &RenameTableRequest, ctx: &WidgetCtx) {
+    if source.db.is_read_only() {
+        show_readonly_toast(ctx);
+        return;
+    }
+    if request.drop_source && !request.confirmed {
+        confirm(&source, request, ctx);
+        return;
+    }
+    let segments = if source.db.capabilities().parallel_scan {
+        crate::util::scan_segments()
+    } else {
+        1
+    };
+    let handle = jobs::start(format!(
+        "Rename {} to {}",
+        request.source, request.destination
+    ));
+    let (mut job, estimate) = {
+        let mut state = source.state.borrow_mut();
+        state.rename_job = Some(handle.clone());
+        let job = state
+            .rename_resume
+            .take()
+            .filter(|job| job.source == request.source && job.destination == request.destination)
+            .unwrap_or_else(|| {
+                RenameJob::new(&request.source, &request.destination, false, segments)
+            });
+        let estimate = state
+            .tables
+            .iter()
+            .find(|entry| entry.name == request.source)
+            .and_then(|entry| entry.meta.item_count);
+        (job, estimate)
+    };
+    job.drop_source = request.drop_source;
+    let db = source.db;
+    let connection = resume::connection(db.as_ref());
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let on_progress = |step: &RenameStep| {
+            // Until the new table exists there is nothing to resume.
+            if !matches!(step, RenameStep::Create | RenameStep::Done) {
+                let reached = RenameJob {
+                    step: step.clone(),
+                    ..job.clone()
+                };
+                resume::save(&connection, &Checkpoint::Rename(reached));
+            }
+            handle.progress(progress_message(&job, step, estimate));
+        };
+        let mut running = job.clone();
+        let result = rename::run(db.as_ref(), &mut running, handle.flag(), &on_progress)
+            .await
+            .map_err(|err| err.to_string());
+        if result.is_ok() {
+            resume::clear(&connection, resume::Kind::Rename);
+        }
+        ctx.emit_self(RenameTableEvent {
+            job: running,
+            result,
+        });
+    });
+}
+
+fn confirm(source: &RenameSource<'_>, request: &RenameTableRequest, ctx: &WidgetCtx) {
+    let ctx_for_action = ctx.clone();
+    let (table, destination) = (request.source.clone(), request.destination.clone());
+    let popup = ConfirmPopup::new_with_action(
+        "Rename table",
+        format!(
+            "Table={table}\nNewName={destination}\n\n{table} is deleted once its items are copied and counted."
+        ),
+        "Rename",
+        "cancel",
+        ConfirmAction::new(
+            KeyCode::Char('d'),
+            KeyModifiers::CONTROL,
+            "^d",
+            "rename",
+            "Rename and delete the old table",
+        ),
+        move || {
+            ctx_for_action.emit_self(RenameTableRequest {
+                source: table.clone(),
+                destination: destination.clone(),
+                drop_source: true,
+                confirmed: true,
+            });
+        },
+        source.parent.clone(),
+    );
+    popup.show(ctx, Action::DeleteTable, &request.source);
+}
+
+/// Finish the rename's job and remember where a failed one stopped. `None`
+/// when the rename was already forgotten.
+fn finish(source: &RenameSource<'_>, event: &RenameTableEvent) -> Option<Result<(), String>> {
+    let job = &event.job;
+    let handle = {
+        let mut state = source.state.borrow_mut();
+        state.rename_resume =
+            (event.result.is_err() && job.step != RenameStep::Create).then(|| job.clone());
+        state.rename_job.take()
+    }?;
+    Some(match event.result.as_ref() {
+        Ok(()) => {
+            let mut message = format!(
+                "Renamed {} to {} ({} items)",
+                job.source,
+                job.destination,
+                job.items.unwrap_or_default()
+            );
+            if !job.drop_source {
+                message.push_str(&format!("; {} was kept", job.source));
+            }
+            handle.finish(Ok(message));
+            Ok(())
+        }
+        Err(_) if handle.is_cancelled() => {
+            handle.finish(Ok(format!(
+                "Rename canceled while {}. Press m on {} to resume.",
+                job.step.label(),
+                job.source
+            )));
+            Ok(())
+        }
+        Err(err) => {
+            handle.finish_quietly(Err(err.clone()));
+            let mut message = format!("Failed to rename table: {err}");
+            if job.step != RenameStep::Create {
+                message.push_str(&format!(
+                    "\n\nStopped while {}. Press m on {} to resume.",
+                    job.step.label(),
+                    job.source
+                ));
+            }
+            Err(message)
+        }
+    })
+}
+
+/// What a rename's job reports as it reaches `step`.
+fn progress_message(job: &RenameJob, step: &RenameStep, estimate: Option<i64>) -> String {
+    let detail = match step {
+        RenameStep::Copy(checkpoint) => {
+            let estimate = estimate
+                .map(|count| format!(" of ~{count}"))
+                .unwrap_or_default();
+            format!("copied {}{estimate} items", checkpoint.copied)
+        }
+        RenameStep::Verify => "counting items...".to_string(),
+        step => format!("{}...", step.label()),
+    };
+    format!("Renaming {} to {}: {detail}", job.source, job.destination)
+}
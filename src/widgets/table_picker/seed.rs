@@ -0,0 +1,225 @@
+//! Seeding the selected table (`s`) with generated items, for trying out
+//! queries and indexes on something that isn't empty.
+//!
+//! The items are written as a job that `esc` cancels. Each step comes back as
+//! one of the events here; [`handle`] takes it from there and tells the table
+//! picker what it has to do about it.
+
+use std::{cell::RefCell, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::seed::{SeedOptions, SeedProgress, SeedSpec, SeedSummary, seed_items};
+
+use crate::{
+    env::{AppEvent, WidgetCtx, WidgetId},
+    jobs,
+    safety::Action,
+    widgets::{
+        confirm::{ConfirmAction, ConfirmPopup},
+        error::ErrorPopup,
+        seed::SeedPopup,
+    },
+};
+
+use super::{TablePickerState, show_readonly_toast};
+
+/// Batches written at once while seeding.
+const SEED_CONCURRENCY: usize = 4;
+
+struct SeedTableRequest {
+    table_name: String,
+    spec: SeedSpec,
+    count: u64,
+    confirmed: bool,
+}
+
+struct SeedTableEvent {
+    table_name: String,
+    result: Result<SeedSummary, String>,
+}
+
+/// What seeding needs from the table picker.
+pub(super) struct SeedSource<'a> {
+    /// The client for the selected table.
+    pub(super) db: Arc<dyn Datastore>,
+    /// Holds the running seed job.
+    pub(super) state: &'a RefCell<TablePickerState>,
+    pub(super) parent: WidgetId,
+}
+
+/// Where seeding got to, for the table picker to act on.
+pub(super) enum SeedFlow {
+    /// The next step is under way; nothing to do.
+    Continuing,
+    /// Items were written, even if some failed or it was canceled.
+    Seeded,
+    Failed(String),
+}
+
+/// Ask how many items to generate for the selected table and what they look
+/// like.
+pub(super) fn prompt(source: &SeedSource<'_>, ctx: &WidgetCtx) -> Result<(), String> {
+    let entry = {
+        let state = source.state.borrow();
+        if state.seed_job.is_some() {
+            return Err("Seeding is already running".to_string());
+        }
+        state
+            .selected_table()
+            .cloned()
+            .ok_or_else(|| "No table selected".to_string())?
+    };
+    if entry.schema.key.fields.is_empty() {
+        return Err(format!("The keys of {} are unknown", entry.name));
+    }
+    let table_name = entry.name.clone();
+    let ctx_for_submit = ctx.clone();
+    ctx.set_popup(Box::new(SeedPopup::new(
+        &entry.name,
+        entry.schema.key.clone(),
+        move |spec, count| {
+            ctx_for_submit.emit_self(SeedTableRequest {
+                table_name: table_name.clone(),
+                spec,
+                count,
+                confirmed: false,
+            });
+        },
+        source.parent.clone(),
+    )));
+    Ok(())
+}
+
+/// Take seeding one step further if `event` is one of its own. `source` is
+/// only built then.
+pub(super) fn handle<'a>(
+    event: &AppEvent,
+    ctx: &WidgetCtx,
+    source: impl FnOnce() -> SeedSource<'a>,
+) -> Option<SeedFlow> {
+    if let Some(request) = event.payload::<SeedTableRequest>() {
+        run(source(), request, ctx);
+        return Some(SeedFlow::Continuing);
+    }
+    let finished = event.payload::<SeedTableEvent>()?;
+    finish(&source(), finished, ctx)
+}
+
+/// Confirm, then write generated items in the background, reporting progress
+/// to the job.
+fn run(source: SeedSource<'_>, request: &SeedTableRequest, ctx: &WidgetCtx) {
+    if source.db.is_read_only() {
+        show_readonly_toast(ctx);
+        return;
+    }
+    if !request.confirmed {
+        confirm(&source, request, ctx);
+        return;
+    }
+    let table_name = request.table_name.clone();
+    let job = jobs::start(format!("Seed {table_name}"));
+    source.state.borrow_mut().seed_job = Some(job.clone());
+    let spec = request.spec.clone();
+    let count = request.count;
+    job.progress(format!("Seeding {table_name}... (esc cancels)"));
+    let db = source.db;
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let on_progress = |progress: SeedProgress| {
+            job.progress(format!(
+                "Seeding {table_name}... {} of {count} items written (esc cancels)",
+                progress.written
+            ));
+        };
+        let options = SeedOptions {
+            count,
+            concurrency: SEED_CONCURRENCY,
+            seed: None,
+        };
+        let result = seed_items(
+            db.as_ref(),
+            &table_name,
+            &spec,
+            options,
+            job.flag(),
+            &on_progress,
+        )
+        .await
+        .map_err(|err| err.to_string());
+        ctx.emit_self(SeedTableEvent { table_name, result });
+    });
+}
+
+fn confirm(source: &SeedSource<'_>, request: &SeedTableRequest, ctx: &WidgetCtx) {
+    let ctx_for_action = ctx.clone();
+    let (table_name, spec, count) = (
+        request.table_name.clone(),
+        request.spec.clone(),
+        request.count,
+    );
+    ConfirmPopup::new_with_action(
+        "Seed table",
+        format!(
+            "Table={table_name}\nItems={count}\n\nGenerated items replace any with the same key."
+        ),
+        "Seed",
+        "cancel",
+        ConfirmAction::new(
+            KeyCode::Char('s'),
+            KeyModifiers::CONTROL,
+            "^s",
+            "seed",
+            "Write the generated items",
+        ),
+        move || {
+            ctx_for_action.emit_self(SeedTableRequest {
+                table_name: table_name.clone(),
+                spec: spec.clone(),
+                count,
+                confirmed: true,
+            });
+        },
+        source.parent.clone(),
+    )
+    .show(ctx, Action::Seed, &request.table_name);
+}
+
+/// Finish the seed job, listing the writes that failed. `None` when the job
+/// was already forgotten.
+fn finish(source: &SeedSource<'_>, seed: &SeedTableEvent, ctx: &WidgetCtx) -> Option<SeedFlow> {
+    let job = source.state.borrow_mut().seed_job.take()?;
+    Some(match seed.result.as_ref() {
+        Ok(summary) if summary.errors.is_empty() && summary.progress.failed == 0 => {
+            let written = summary.progress.written;
+            job.finish(Ok(if summary.cancelled {
+                format!("Seeding canceled after {written} items")
+            } else {
+                format!("Seeded {written} items into {}", seed.table_name)
+            }));
+            SeedFlow::Seeded
+        }
+        Ok(summary) => {
+            let mut lines = vec![format!(
+                "{} items written, {} failed",
+                summary.progress.written, summary.progress.failed
+            )];
+            lines.extend(summary.errors.iter().cloned());
+            job.finish(Err(format!(
+                "Seeding {} finished with errors",
+                seed.table_name
+            )));
+            ctx.set_popup(Box::new(ErrorPopup::new(
+                "Seeding failed",
+                lines.join("\n"),
+                source.parent.clone(),
+            )));
+            SeedFlow::Seeded
+        }
+        Err(err) => {
+            job.finish_quietly(Err(err.clone()));
+            SeedFlow::Failed(format!("Failed to seed table: {err}"))
+        }
+    })
+}
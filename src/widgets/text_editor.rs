@@ -0,0 +1,521 @@
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Paragraph},
+};
+
+use crate::{
+    help,
+    util::{fill_bg, pad},
+    widgets::{Popup, WidgetInner, theme::Theme},
+};
+
+/// Spaces a tab inserts, and an opened bracket indents by.
+const INDENT: &str = "  ";
+
+/// A multi-line text editor inside the TUI, for when there's no `$EDITOR`
+/// (or `editor` is `builtin` in the config file). JSON gets syntax
+/// highlighting and the bracket under the cursor is matched.
+pub struct TextEditorPopup {
+    inner: WidgetInner,
+    title: String,
+    json: bool,
+    buffer: RefCell<TextBuffer>,
+    /// The first line and column shown.
+    scroll: Cell<(usize, usize)>,
+    /// Lines shown at once, for page up and down.
+    page: Cell<usize>,
+    /// Esc was pressed on unsaved changes; another one discards them.
+    discard_armed: Cell<bool>,
+    on_save: Box<dyn Fn(String) + Send + 'static>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+impl TextEditorPopup {
+    pub fn new(
+        title: impl Into<String>,
+        text: &str,
+        json: bool,
+        on_save: impl Fn(String) + Send + 'static,
+        parent: crate::env::WidgetId,
+    ) -> Self {
+        let help_entries = vec![
+            help_entry("^s", "save", "Save and close the editor"),
+            help_entry("esc", "cancel", "Close the editor without saving"),
+            help_entry("tab", "indent", "Insert two spaces"),
+        ];
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            title: title.into(),
+            json,
+            buffer: RefCell::new(TextBuffer::new(text)),
+            scroll: Cell::new((0, 0)),
+            page: Cell::new(0),
+            discard_armed: Cell::new(false),
+            on_save: Box::new(on_save),
+            help_entries,
+        }
+    }
+
+    /// Scroll so the cursor stays inside a text area of `width` by `height`.
+    fn scroll_to_cursor(&self, width: usize, height: usize) -> (usize, usize) {
+        let (row, col) = self.buffer.borrow().cursor;
+        let (mut top, mut left) = self.scroll.get();
+        if row < top {
+            top = row;
+        } else if height > 0 && row >= top + height {
+            top = row + 1 - height;
+        }
+        if col < left {
+            left = col;
+        } else if width > 0 && col >= left + width {
+            left = col + 1 - width;
+        }
+        self.scroll.set((top, left));
+        (top, left)
+    }
+}
+
+impl crate::widgets::Widget for TextEditorPopup {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        fill_bg(frame.buffer_mut(), area, theme.panel_bg());
+        let title = Line::styled(
+            pad(&self.title, 1),
+            Style::default()
+                .fg(theme.accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .centered();
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg()).fg(theme.text()));
+        frame.render_widget(block.clone(), area);
+        let [text_area, status_area] = block.inner(area).layout(&Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ]));
+
+        let buffer = self.buffer.borrow();
+        let gutter = buffer.lines.len().to_string().len() + 1;
+        let width = usize::from(text_area.width).saturating_sub(gutter);
+        let height = usize::from(text_area.height);
+        drop(buffer);
+        self.page.set(height);
+        let (top, left) = self.scroll_to_cursor(width, height);
+        let buffer = self.buffer.borrow();
+        let matched =
+            matching_bracket(&buffer.lines, buffer.cursor).map(|other| [buffer.cursor, other]);
+
+        let lines: Vec<Line> = buffer
+            .lines
+            .iter()
+            .enumerate()
+            .skip(top)
+            .take(height)
+            .map(|(row, line)| {
+                let mut spans = vec![Span::styled(
+                    format!("{:>width$} ", row + 1, width = gutter - 1),
+                    Style::default().fg(theme.text_muted()),
+                )];
+                let styles = if self.json {
+                    json_styles(line, theme)
+                } else {
+                    vec![Style::default().fg(theme.text()); line.len()]
+                };
+                spans.extend(line.iter().zip(styles).enumerate().skip(left).map(
+                    |(col, (ch, style))| {
+                        let style = if matched.is_some_and(|matched| matched.contains(&(row, col)))
+                        {
+                            style
+                                .bg(theme.selection_bg())
+                                .fg(theme.selection_fg())
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            style
+                        };
+                        Span::styled(ch.to_string(), style)
+                    },
+                ));
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), text_area);
+
+        let (row, col) = buffer.cursor;
+        frame.set_cursor_position(Position::new(
+            text_area.x + u16::try_from(gutter + col - left).unwrap_or(u16::MAX),
+            text_area.y + u16::try_from(row - top).unwrap_or(u16::MAX),
+        ));
+
+        let (status, style) = if self.discard_armed.get() {
+            (
+                "Unsaved changes: esc again to discard them".to_string(),
+                Style::default().fg(theme.warning()),
+            )
+        } else {
+            (
+                format!("Ln {}, Col {}  ^s save · esc cancel", row + 1, col + 1),
+                Style::default().fg(theme.text_muted()),
+            )
+        };
+        frame.render_widget(Paragraph::new(status).style(style), status_area);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return true;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if key.code == KeyCode::Esc {
+            if self.buffer.borrow().modified && !self.discard_armed.get() {
+                self.discard_armed.set(true);
+            } else {
+                ctx.dismiss_popup();
+            }
+            ctx.invalidate();
+            return true;
+        }
+        self.discard_armed.set(false);
+        if ctrl && key.code == KeyCode::Char('s') {
+            let text = self.buffer.borrow().text();
+            ctx.dismiss_popup();
+            (self.on_save)(text);
+            ctx.invalidate();
+            return true;
+        }
+        let page = self.page.get().max(1);
+        let mut buffer = self.buffer.borrow_mut();
+        match key.code {
+            KeyCode::Char(ch) if !ctrl => buffer.insert(&ch.to_string()),
+            KeyCode::Tab => buffer.insert(INDENT),
+            KeyCode::Enter => buffer.newline(),
+            KeyCode::Backspace => buffer.backspace(),
+            KeyCode::Delete => buffer.delete(),
+            KeyCode::Left => buffer.move_left(),
+            KeyCode::Right => buffer.move_right(),
+            KeyCode::Up => buffer.move_up(1),
+            KeyCode::Down => buffer.move_down(1),
+            KeyCode::PageUp => buffer.move_up(page),
+            KeyCode::PageDown => buffer.move_down(page),
+            KeyCode::Home => buffer.move_home(),
+            KeyCode::End => buffer.move_end(),
+            _ => {}
+        }
+        ctx.invalidate();
+        true
+    }
+}
+
+impl Popup for TextEditorPopup {
+    fn rect(&self, area: Rect) -> Rect {
+        let width = (area.width * 4 / 5)
+            .max(60)
+            .min(area.width.saturating_sub(4));
+        let height = (area.height * 4 / 5)
+            .max(12)
+            .min(area.height.saturating_sub(2));
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// The text being edited, as lines of characters, and the cursor as a line
+/// and a character in it.
+struct TextBuffer {
+    lines: Vec<Vec<char>>,
+    cursor: (usize, usize),
+    modified: bool,
+}
+
+impl TextBuffer {
+    fn new(text: &str) -> Self {
+        let mut lines: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+        if lines.is_empty() {
+            lines.push(Vec::new());
+        }
+        Self {
+            lines,
+            cursor: (0, 0),
+            modified: false,
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut text = self
+            .lines
+            .iter()
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        text.push('\n');
+        text
+    }
+
+    fn insert(&mut self, text: &str) {
+        let (row, col) = self.cursor;
+        let line = &mut self.lines[row];
+        let chars: Vec<char> = text.chars().collect();
+        line.splice(col..col, chars.iter().copied());
+        self.cursor.1 += chars.len();
+        self.modified = true;
+    }
+
+    /// Break the line at the cursor, keeping its indentation, and indenting
+    /// one step more after an opening bracket.
+    fn newline(&mut self) {
+        let (row, col) = self.cursor;
+        let line = &mut self.lines[row];
+        let rest = line.split_off(col);
+        let mut indent: Vec<char> = line
+            .iter()
+            .take_while(|ch| **ch == ' ' || **ch == '\t')
+            .copied()
+            .collect();
+        if matches!(line.last(), Some('{' | '[')) {
+            indent.extend(INDENT.chars());
+        }
+        let col = indent.len();
+        indent.extend(rest);
+        self.lines.insert(row + 1, indent);
+        self.cursor = (row + 1, col);
+        self.modified = true;
+    }
+
+    fn backspace(&mut self) {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            self.lines[row].remove(col - 1);
+            self.cursor.1 -= 1;
+        } else if row > 0 {
+            let line = self.lines.remove(row);
+            let previous = &mut self.lines[row - 1];
+            self.cursor = (row - 1, previous.len());
+            previous.extend(line);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    fn delete(&mut self) {
+        let (row, col) = self.cursor;
+        if col < self.lines[row].len() {
+            self.lines[row].remove(col);
+        } else if row + 1 < self.lines.len() {
+            let next = self.lines.remove(row + 1);
+            self.lines[row].extend(next);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    fn move_left(&mut self) {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            self.cursor.1 -= 1;
+        } else if row > 0 {
+            self.cursor = (row - 1, self.lines[row - 1].len());
+        }
+    }
+
+    fn move_right(&mut self) {
+        let (row, col) = self.cursor;
+        if col < self.lines[row].len() {
+            self.cursor.1 += 1;
+        } else if row + 1 < self.lines.len() {
+            self.cursor = (row + 1, 0);
+        }
+    }
+
+    fn move_home(&mut self) {
+        self.cursor.1 = 0;
+    }
+
+    fn move_end(&mut self) {
+        self.cursor.1 = self.lines[self.cursor.0].len();
+    }
+
+    fn move_up(&mut self, lines: usize) {
+        let row = self.cursor.0.saturating_sub(lines);
+        self.cursor = (row, self.cursor.1.min(self.lines[row].len()));
+    }
+
+    fn move_down(&mut self, lines: usize) {
+        let row = (self.cursor.0 + lines).min(self.lines.len() - 1);
+        self.cursor = (row, self.cursor.1.min(self.lines[row].len()));
+    }
+}
+
+/// Where the bracket matching the one at `cursor` (or, failing that, the one
+/// just before it) is. Brackets inside strings don't count.
+fn matching_bracket(lines: &[Vec<char>], cursor: (usize, usize)) -> Option<(usize, usize)> {
+    let mut brackets = Vec::new();
+    for (row, line) in lines.iter().enumerate() {
+        let mut in_string = false;
+        let mut escaped = false;
+        for (col, ch) in line.iter().enumerate() {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                '{' | '[' | '}' | ']' if !in_string => brackets.push((row, col, *ch)),
+                _ => {}
+            }
+        }
+    }
+    let (row, col) = cursor;
+    let at = brackets
+        .iter()
+        .position(|(r, c, _)| (*r, *c) == (row, col))
+        .or_else(|| {
+            let before = col.checked_sub(1)?;
+            brackets
+                .iter()
+                .position(|(r, c, _)| (*r, *c) == (row, before))
+        })?;
+    let (_, _, bracket) = brackets[at];
+    let (open, close, forward) = match bracket {
+        '{' => ('{', '}', true),
+        '[' => ('[', ']', true),
+        '}' => ('{', '}', false),
+        _ => ('[', ']', false),
+    };
+    let mut depth = 0_usize;
+    let candidates: Box<dyn Iterator<Item = &(usize, usize, char)>> = if forward {
+        Box::new(brackets[at..].iter())
+    } else {
+        Box::new(brackets[..=at].iter().rev())
+    };
+    for (r, c, ch) in candidates {
+        let (opens, closes) = if forward {
+            (open, close)
+        } else {
+            (close, open)
+        };
+        if *ch == opens {
+            depth += 1;
+        } else if *ch == closes {
+            depth -= 1;
+            if depth == 0 {
+                return Some((*r, *c));
+            }
+        }
+    }
+    None
+}
+
+/// A style for each character of a line of JSON: object keys, strings,
+/// numbers, and keywords are told apart, and `//` comment lines stand out.
+fn json_styles(line: &[char], theme: &Theme) -> Vec<Style> {
+    let text = Style::default().fg(theme.text());
+    if line
+        .iter()
+        .collect::<String>()
+        .trim_start()
+        .starts_with("//")
+    {
+        return vec![Style::default().fg(theme.error()); line.len()];
+    }
+    let mut styles = vec![text; line.len()];
+    let mut col = 0;
+    while col < line.len() {
+        let ch = line[col];
+        if ch == '"' {
+            let start = col;
+            col += 1;
+            while col < line.len() && line[col] != '"' {
+                col += if line[col] == '\\' { 2 } else { 1 };
+            }
+            col = (col + 1).min(line.len());
+            let is_key = line[col..]
+                .iter()
+                .find(|ch| !ch.is_whitespace())
+                .is_some_and(|ch| *ch == ':');
+            let style = if is_key {
+                Style::default().fg(theme.accent())
+            } else {
+                Style::default().fg(theme.accent_alt())
+            };
+            styles[start..col].fill(style);
+        } else if ch == '-' || ch.is_ascii_digit() {
+            let start = col;
+            while col < line.len()
+                && (line[col].is_ascii_digit() || matches!(line[col], '-' | '+' | '.' | 'e' | 'E'))
+            {
+                col += 1;
+            }
+            styles[start..col].fill(Style::default().fg(theme.warning()));
+        } else if ch.is_ascii_alphabetic() {
+            let start = col;
+            while col < line.len() && line[col].is_ascii_alphabetic() {
+                col += 1;
+            }
+            styles[start..col].fill(Style::default().fg(theme.text_muted()));
+        } else {
+            col += 1;
+        }
+    }
+    styles
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextBuffer, matching_bracket};
+
+    #[test]
+    fn edits_lines_and_matches_brackets() {
+        let mut buffer = TextBuffer::new("{\"a\": [1, \"]\"]}");
+        buffer.cursor = (0, 1);
+        buffer.newline();
+        assert_eq!(buffer.text(), "{\n  \"a\": [1, \"]\"]}\n");
+        assert_eq!(buffer.cursor, (1, 2));
+        buffer.backspace();
+        buffer.backspace();
+        buffer.backspace();
+        assert_eq!(buffer.text(), "{\"a\": [1, \"]\"]}\n");
+        assert!(buffer.modified);
+
+        let lines = &buffer.lines;
+        // The `]` inside the string is skipped.
+        assert_eq!(matching_bracket(lines, (0, 6)), Some((0, 13)));
+        assert_eq!(matching_bracket(lines, (0, 14)), Some((0, 0)));
+        assert_eq!(matching_bracket(lines, (0, 15)), Some((0, 0)));
+        assert_eq!(matching_bracket(lines, (0, 3)), None);
+    }
+}
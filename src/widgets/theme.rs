@@ -1,4 +1,15 @@
-use std::{env, sync::OnceLock, time::Duration};
+//! Colors for every widget. A theme is picked once at startup, from `--theme`,
+//! the config file's `theme`, or `DYNAMATE_THEME`, in that order: a built-in
+//! name, `auto` (dark or light, following the terminal background), or the
+//! path of a JSON palette file such as
+//!
+//! ```json
+//! { "base": "solarized", "accent": "#d33682", "error": "red" }
+//! ```
+//!
+//! where `base` names the built-in theme the other colors override.
+
+use std::{env, path::Path, str::FromStr, time::Duration};
 
 use ratatui::style::Color;
 
@@ -8,7 +19,46 @@ const LUMA_THRESHOLD: f32 = 0.6;
 const LUMA_SAMPLES: usize = 5;
 const LUMA_SAMPLE_DELAY: Duration = Duration::from_millis(20);
 
-#[derive(Clone, Copy)]
+const THEME_ENV: &str = "DYNAMATE_THEME";
+
+/// The built-in themes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeName {
+    Dark,
+    Light,
+    Solarized,
+    HighContrast,
+}
+
+impl ThemeName {
+    pub const ALL: [Self; 4] = [Self::Dark, Self::Light, Self::Solarized, Self::HighContrast];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::Solarized => "solarized",
+            Self::HighContrast => "high-contrast",
+        }
+    }
+
+    /// Case-insensitive; `_` works in place of `-`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase().replace('_', "-");
+        Self::ALL.into_iter().find(|name| name.as_str() == value)
+    }
+
+    pub fn theme(self) -> Theme {
+        match self {
+            Self::Dark => Theme::dark(),
+            Self::Light => Theme::light(),
+            Self::Solarized => Theme::solarized(),
+            Self::HighContrast => Theme::high_contrast(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Theme {
     bg: Color,
     panel_bg: Color,
@@ -29,25 +79,89 @@ pub struct Theme {
 }
 
 impl Theme {
-    pub fn default() -> Self {
-        static THEME: OnceLock<Theme> = OnceLock::new();
-        *THEME.get_or_init(|| {
-            if let Ok(value) = env::var("DYNAMATE_THEME") {
-                if value.eq_ignore_ascii_case("light") {
-                    return Self::light();
-                }
-                if value.eq_ignore_ascii_case("dark") {
-                    return Self::dark();
-                }
-            }
-
-            if let Some(luma) = detect_terminal_luma()
-                && luma > LUMA_THRESHOLD
-            {
-                return Self::light();
-            }
-
-            Self::dark()
+    /// The theme for `choice` (from `--theme` or the config file), falling
+    /// back to `DYNAMATE_THEME` and then to `auto`.
+    pub fn resolve(choice: Option<&str>) -> Result<Self, String> {
+        let choice = choice
+            .map(str::to_string)
+            .or_else(|| env::var(THEME_ENV).ok());
+        let choice = choice.as_deref().map(str::trim).unwrap_or_default();
+        if choice.is_empty() || choice.eq_ignore_ascii_case("auto") {
+            return Ok(Self::detect());
+        }
+        match ThemeName::parse(choice) {
+            Some(name) => Ok(name.theme()),
+            None => Self::from_file(Path::new(choice)),
+        }
+    }
+
+    /// Dark or light, following the terminal background.
+    pub fn detect() -> Self {
+        if let Some(luma) = detect_terminal_luma()
+            && luma > LUMA_THRESHOLD
+        {
+            return Self::light();
+        }
+        Self::dark()
+    }
+
+    /// Load a palette file.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let names: Vec<&str> = ThemeName::ALL.iter().map(|name| name.as_str()).collect();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            format!(
+                "{} is neither a theme ({}, auto) nor a readable palette file: {err}",
+                path.display(),
+                names.join(", ")
+            )
+        })?;
+        Self::from_palette(&text)
+            .map_err(|err| format!("Invalid palette {}: {err}", path.display()))
+    }
+
+    fn from_palette(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| "expected a JSON object".to_string())?;
+        let mut theme = match object.get("base") {
+            None => Self::dark(),
+            Some(serde_json::Value::String(base)) => ThemeName::parse(base)
+                .ok_or_else(|| format!("unknown base theme {base}"))?
+                .theme(),
+            Some(_) => return Err("base must be a theme name".to_string()),
+        };
+        for (key, value) in object.iter().filter(|(key, _)| *key != "base") {
+            let color = value
+                .as_str()
+                .and_then(|color| Color::from_str(color).ok())
+                .ok_or_else(|| format!("{key} must be a color like \"#5ccfe6\" or \"cyan\""))?;
+            *theme
+                .slot(key)
+                .ok_or_else(|| format!("unknown color {key}"))? = color;
+        }
+        Ok(theme)
+    }
+
+    fn slot(&mut self, key: &str) -> Option<&mut Color> {
+        Some(match key {
+            "bg" => &mut self.bg,
+            "panel_bg" => &mut self.panel_bg,
+            "panel_bg_alt" => &mut self.panel_bg_alt,
+            "text" => &mut self.text,
+            "text_muted" => &mut self.text_muted,
+            "accent" => &mut self.accent,
+            "accent_alt" => &mut self.accent_alt,
+            "border" => &mut self.border,
+            "header_bg" => &mut self.header_bg,
+            "row_stripe" => &mut self.row_stripe,
+            "scrollbar" => &mut self.scrollbar,
+            "selection_bg" => &mut self.selection_bg,
+            "selection_fg" => &mut self.selection_fg,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            _ => return None,
         })
     }
 
@@ -93,6 +207,49 @@ impl Theme {
         }
     }
 
+    pub fn solarized() -> Self {
+        Self {
+            bg: Color::Rgb(0, 43, 54),
+            panel_bg: Color::Rgb(3, 49, 61),
+            panel_bg_alt: Color::Rgb(7, 54, 66),
+            text: Color::Rgb(147, 161, 161),
+            text_muted: Color::Rgb(101, 123, 131),
+            accent: Color::Rgb(38, 139, 210),
+            accent_alt: Color::Rgb(203, 75, 22),
+            border: Color::Rgb(88, 110, 117),
+            header_bg: Color::Rgb(7, 54, 66),
+            row_stripe: Color::Rgb(5, 51, 63),
+            scrollbar: Color::Rgb(88, 110, 117),
+            selection_bg: Color::Rgb(17, 78, 96),
+            selection_fg: Color::Rgb(253, 246, 227),
+            success: Color::Rgb(133, 153, 0),
+            warning: Color::Rgb(181, 137, 0),
+            error: Color::Rgb(220, 50, 47),
+        }
+    }
+
+    /// Pure black and white with saturated accents.
+    pub fn high_contrast() -> Self {
+        Self {
+            bg: Color::Rgb(0, 0, 0),
+            panel_bg: Color::Rgb(0, 0, 0),
+            panel_bg_alt: Color::Rgb(24, 24, 24),
+            text: Color::Rgb(255, 255, 255),
+            text_muted: Color::Rgb(210, 210, 210),
+            accent: Color::Rgb(0, 255, 255),
+            accent_alt: Color::Rgb(255, 215, 0),
+            border: Color::Rgb(255, 255, 255),
+            header_bg: Color::Rgb(40, 40, 40),
+            row_stripe: Color::Rgb(20, 20, 20),
+            scrollbar: Color::Rgb(255, 255, 255),
+            selection_bg: Color::Rgb(255, 215, 0),
+            selection_fg: Color::Rgb(0, 0, 0),
+            success: Color::Rgb(0, 255, 0),
+            warning: Color::Rgb(255, 215, 0),
+            error: Color::Rgb(255, 85, 85),
+        }
+    }
+
     pub fn bg(&self) -> Color {
         self.bg
     }
@@ -190,7 +347,35 @@ fn median_luma(samples: &mut [f32]) -> f32 {
 
 #[cfg(test)]
 mod tests {
-    use super::median_luma;
+    use ratatui::style::Color;
+
+    use super::{Theme, ThemeName, median_luma};
+
+    #[test]
+    fn theme_names_parse_loosely() {
+        assert_eq!(ThemeName::parse("Solarized"), Some(ThemeName::Solarized));
+        assert_eq!(
+            ThemeName::parse("high_contrast"),
+            Some(ThemeName::HighContrast)
+        );
+        assert_eq!(ThemeName::parse("sepia"), None);
+        assert_eq!(Theme::resolve(Some("light")), Ok(Theme::light()));
+    }
+
+    #[test]
+    fn palettes_override_a_base_theme() {
+        let theme =
+            Theme::from_palette(r##"{ "base": "light", "accent": "#102030", "error": "red" }"##)
+                .unwrap();
+        assert_eq!(theme.accent(), Color::Rgb(16, 32, 48));
+        assert_eq!(theme.error(), Color::Red);
+        assert_eq!(theme.text(), Theme::light().text());
+        assert_eq!(Theme::from_palette("{}"), Ok(Theme::dark()));
+
+        assert!(Theme::from_palette(r#"{ "base": "sepia" }"#).is_err());
+        assert!(Theme::from_palette(r#"{ "accnet": "red" }"#).is_err());
+        assert!(Theme::from_palette(r#"{ "accent": "not a color" }"#).is_err());
+    }
 
     #[test]
     fn median_luma_odd() {
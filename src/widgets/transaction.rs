@@ -0,0 +1,525 @@
+//! Writes staged for one all-or-nothing transaction. While staging is on
+//! (`w` in a table view), edits, new items, and deletes are held here instead
+//! of being written; `W` opens the review, which lists every staged write and
+//! commits them together with `^s`. If the transaction is rejected, each
+//! write shows why.
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table, TableState},
+};
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::query::{TransactOutcome, TransactWrite};
+use dynamate::dynamodb::transact::MAX_TRANSACT_WRITES;
+
+use crate::{
+    env::{Toast, ToastKind},
+    help,
+    safety::Action,
+    util::pad,
+    widgets::{
+        WidgetInner,
+        confirm::{ConfirmAction, ConfirmPopup},
+        theme::Theme,
+    },
+};
+
+/// Shared by every table view, so one transaction can span tables.
+static STAGED: Mutex<Vec<StagedWrite>> = Mutex::new(Vec::new());
+/// Whether table views stage their writes rather than applying them.
+static STAGING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone)]
+pub struct StagedWrite {
+    pub write: TransactWrite,
+    /// The item's key as shown to the user, e.g. `pk=a, sk=1`. Also what
+    /// tells two writes to the same item apart.
+    pub key: String,
+}
+
+/// Add a write to the transaction, returning how many are staged.
+pub fn stage(write: TransactWrite, key: String) -> Result<usize, String> {
+    let mut staged = STAGED.lock().unwrap_or_else(PoisonError::into_inner);
+    // DynamoDB rejects a transaction that touches one item twice.
+    if staged
+        .iter()
+        .any(|other| other.write.collection() == write.collection() && other.key == key)
+    {
+        return Err(format!(
+            "{key} in {} already has a staged write",
+            write.collection()
+        ));
+    }
+    if staged.len() >= MAX_TRANSACT_WRITES {
+        return Err(format!(
+            "A transaction holds at most {MAX_TRANSACT_WRITES} writes"
+        ));
+    }
+    staged.push(StagedWrite { write, key });
+    Ok(staged.len())
+}
+
+pub fn is_staging() -> bool {
+    STAGING.load(Ordering::Relaxed)
+}
+
+/// Turn staging on or off, returning the new setting.
+pub fn toggle_staging() -> bool {
+    !STAGING.fetch_xor(true, Ordering::Relaxed)
+}
+
+pub fn staged_count() -> usize {
+    STAGED.lock().unwrap_or_else(PoisonError::into_inner).len()
+}
+
+/// Drop every staged write, e.g. when the connection changes under them.
+pub fn clear() {
+    STAGED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+fn staged() -> Vec<StagedWrite> {
+    STAGED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+fn unstage(index: usize) {
+    let mut staged = STAGED.lock().unwrap_or_else(PoisonError::into_inner);
+    if index < staged.len() {
+        staged.remove(index);
+    }
+}
+
+pub struct TransactionWidget {
+    inner: WidgetInner,
+    db: Arc<dyn Datastore>,
+    state: RefCell<TransactionState>,
+    help_entries: Vec<help::Entry<'static>>,
+}
+
+#[derive(Default)]
+struct TransactionState {
+    committing: bool,
+    table_state: TableState,
+    /// Why each write was rejected by the last commit, lined up with the
+    /// staged list. Cleared when the list changes.
+    reasons: Option<Vec<Option<String>>>,
+}
+
+struct CommitRequest;
+
+struct CommitEvent {
+    result: Result<TransactOutcome, String>,
+}
+
+impl TransactionWidget {
+    pub fn new(db: Arc<dyn Datastore>, parent: crate::env::WidgetId) -> Self {
+        Self {
+            inner: WidgetInner::new::<Self>(parent),
+            db,
+            state: RefCell::new(TransactionState::default()),
+            help_entries: build_help(),
+        }
+    }
+
+    fn select_by(&self, delta: isize) -> bool {
+        let len = staged_count();
+        let mut state = self.state.borrow_mut();
+        if len == 0 {
+            return false;
+        }
+        let current = state.table_state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        if state.table_state.selected() == Some(next) {
+            return false;
+        }
+        state.table_state.select(Some(next));
+        true
+    }
+
+    fn unstage_selected(&self) {
+        let mut state = self.state.borrow_mut();
+        let Some(selected) = state.table_state.selected() else {
+            return;
+        };
+        unstage(selected);
+        state.reasons = None;
+        let len = staged_count();
+        state
+            .table_state
+            .select((len > 0).then(|| selected.min(len - 1)));
+    }
+
+    fn confirm_commit(&self, ctx: crate::env::WidgetCtx) {
+        if self.db.is_read_only() {
+            ctx.show_toast(Toast {
+                message: dynamate::readonly::REJECT_MESSAGE.to_string(),
+                kind: ToastKind::Warning,
+                duration: dynamate::readonly::TOAST_DURATION,
+                action: None,
+            });
+            return;
+        }
+        let writes = staged();
+        if writes.is_empty() {
+            ctx.show_toast(Toast {
+                message: "Nothing staged; press w in a table view to stage writes".to_string(),
+                kind: ToastKind::Warning,
+                duration: Duration::from_secs(3),
+                action: None,
+            });
+            return;
+        }
+        let message = commit_summary(&writes);
+        let ctx_for_commit = ctx.clone();
+        ConfirmPopup::new_with_action(
+            "Commit transaction",
+            message,
+            "Commit",
+            "cancel",
+            ConfirmAction::new(
+                KeyCode::Char('s'),
+                KeyModifiers::CONTROL,
+                "^s",
+                "commit",
+                "Commit every staged write",
+            ),
+            move || ctx_for_commit.emit_self(CommitRequest),
+            self.inner.id(),
+        )
+        .show(&ctx, Action::Transact, "commit");
+    }
+
+    fn commit(&self, ctx: crate::env::WidgetCtx) {
+        let writes: Vec<TransactWrite> = staged().into_iter().map(|staged| staged.write).collect();
+        self.state.borrow_mut().committing = true;
+        ctx.invalidate();
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let result = db
+                .transact_write(&writes)
+                .await
+                .map_err(|err| err.to_string());
+            ctx.emit_self(CommitEvent { result });
+        });
+    }
+
+    fn on_committed(&self, ctx: &crate::env::WidgetCtx, result: &Result<TransactOutcome, String>) {
+        let mut state = self.state.borrow_mut();
+        state.committing = false;
+        match result {
+            Ok(TransactOutcome::Committed) => {
+                let count = staged_count();
+                clear();
+                state.reasons = None;
+                state.table_state.select(None);
+                ctx.show_toast(Toast {
+                    message: format!("Committed {count} writes"),
+                    kind: ToastKind::Info,
+                    duration: Duration::from_secs(3),
+                    action: None,
+                });
+            }
+            Ok(TransactOutcome::Canceled { reasons }) => {
+                state.reasons = Some(reasons.clone());
+                ctx.show_toast(Toast {
+                    message: "Transaction canceled; nothing was written".to_string(),
+                    kind: ToastKind::Error,
+                    duration: Duration::from_secs(4),
+                    action: None,
+                });
+            }
+            Err(err) => {
+                ctx.show_toast(Toast {
+                    message: format!("Failed to commit: {err}"),
+                    kind: ToastKind::Error,
+                    duration: Duration::from_secs(4),
+                    action: None,
+                });
+            }
+        }
+    }
+}
+
+/// The confirmation text: how many writes of each kind, over which tables.
+fn commit_summary(writes: &[StagedWrite]) -> String {
+    let count = |label: &str| {
+        writes
+            .iter()
+            .filter(|staged| staged.write.label() == label)
+            .count()
+    };
+    let mut tables: Vec<&str> = writes
+        .iter()
+        .map(|staged| staged.write.collection())
+        .collect();
+    tables.sort_unstable();
+    tables.dedup();
+    format!(
+        "{} puts, {} updates, {} deletes\nTables: {}\nAll are applied, or none.",
+        count("put"),
+        count("update"),
+        count("delete"),
+        tables.join(", ")
+    )
+}
+
+impl crate::widgets::Widget for TransactionWidget {
+    fn inner(&self) -> &WidgetInner {
+        &self.inner
+    }
+
+    fn start(&self, _ctx: crate::env::WidgetCtx) {
+        if staged_count() > 0 {
+            self.state.borrow_mut().table_state.select(Some(0));
+        }
+    }
+
+    fn navigation_title(&self) -> Option<String> {
+        Some("transaction".to_string())
+    }
+
+    fn status(&self) -> crate::widgets::StatusInfo {
+        crate::widgets::StatusInfo {
+            context: Some(format!("{} staged", staged_count())),
+            mode: Some("TRANSACTION".to_string()),
+            ..crate::widgets::StatusInfo::default()
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().committing
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_nav(frame, area, theme, &crate::widgets::NavContext::default());
+    }
+
+    fn render_with_nav(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        nav: &crate::widgets::NavContext,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let writes = staged();
+        let heading = format!("Transaction: {} staged writes", writes.len());
+        let title = if let Some(back_title) = nav.back_title.as_ref() {
+            Line::from(vec![
+                Span::styled(
+                    format!("← {back_title} "),
+                    Style::default().fg(theme.text_muted()),
+                ),
+                Span::styled(heading, Style::default().fg(theme.text())),
+            ])
+        } else {
+            Line::styled(heading, Style::default().fg(theme.text()))
+        };
+        let mut block = Block::bordered()
+            .title_top(title)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.panel_bg_alt()).fg(theme.text()));
+        if state.reasons.is_some() {
+            block = block.title_bottom(Line::styled(
+                pad("Canceled: fix or unstage the writes marked below", 1),
+                Style::default().fg(theme.error()),
+            ));
+        }
+
+        if writes.is_empty() {
+            let body = Paragraph::new("Nothing staged. Press w in a table view to stage writes.")
+                .style(Style::default().fg(theme.warning()))
+                .block(block);
+            frame.render_widget(body, area);
+            return;
+        }
+
+        let header = Row::new(vec!["Write", "Table", "Key", "Status"]).style(
+            Style::default()
+                .fg(theme.text_muted())
+                .add_modifier(Modifier::BOLD),
+        );
+        let rows: Vec<Row> = writes
+            .iter()
+            .enumerate()
+            .map(|(index, staged)| {
+                let reason = state
+                    .reasons
+                    .as_ref()
+                    .map(|reasons| reasons.get(index).cloned().flatten());
+                let status = match reason {
+                    None => Cell::from("staged").style(Style::default().fg(theme.text_muted())),
+                    Some(None) => Cell::from("ok").style(Style::default().fg(theme.success())),
+                    Some(Some(reason)) => {
+                        Cell::from(reason).style(Style::default().fg(theme.error()))
+                    }
+                };
+                Row::new(vec![
+                    Cell::from(staged.write.label()),
+                    Cell::from(staged.write.collection().to_string()),
+                    Cell::from(staged.key.clone()),
+                    status,
+                ])
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(7),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+        ];
+        let table = Table::new(rows, widths)
+            .block(block)
+            .header(header)
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_symbol("❯ ")
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme.selection_bg())
+                    .fg(theme.selection_fg()),
+            );
+        StatefulWidget::render(table, area, frame.buffer_mut(), &mut state.table_state);
+    }
+
+    fn handle_event(&self, ctx: crate::env::WidgetCtx, event: &Event) -> bool {
+        let Some(key) = event.as_key_press_event() else {
+            return false;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let changed = match key.code {
+            KeyCode::Char('s') if ctrl => {
+                if !self.state.borrow().committing {
+                    self.confirm_commit(ctx);
+                }
+                return true;
+            }
+            KeyCode::Char('d') if key.modifiers.is_empty() => {
+                self.unstage_selected();
+                true
+            }
+            KeyCode::Esc => {
+                ctx.pop_widget();
+                return true;
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.select_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.select_by(-1),
+            _ => return false,
+        };
+        if changed {
+            ctx.invalidate();
+        }
+        true
+    }
+
+    fn on_self_event(&self, ctx: crate::env::WidgetCtx, event: &crate::env::AppEvent) {
+        if event.payload::<CommitRequest>().is_some() {
+            self.commit(ctx);
+            return;
+        }
+        if let Some(committed) = event.payload::<CommitEvent>() {
+            self.on_committed(&ctx, &committed.result);
+            ctx.invalidate();
+        }
+    }
+
+    fn help(&self) -> Option<&[help::Entry<'_>]> {
+        Some(self.help_entries.as_slice())
+    }
+}
+
+fn build_help() -> Vec<help::Entry<'static>> {
+    vec![
+        help_entry("j/k/↑/↓", "move", "Move selection"),
+        help_entry(
+            "d",
+            "unstage",
+            "Drop the selected write from the transaction",
+        ),
+        help_ctrl("^s", "commit", "Commit every staged write, all or nothing"),
+        help_entry("esc", "back", "Back"),
+    ]
+}
+
+fn help_entry(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(keys),
+        short: Cow::Borrowed(short),
+        long: Cow::Borrowed(long),
+        ctrl: None,
+        shift: None,
+        alt: None,
+    }
+}
+
+fn help_ctrl(keys: &'static str, short: &'static str, long: &'static str) -> help::Entry<'static> {
+    help::Entry {
+        keys: Cow::Borrowed(""),
+        short: Cow::Borrowed(""),
+        long: Cow::Borrowed(""),
+        ctrl: Some(help::Variant {
+            keys: Some(Cow::Borrowed(keys)),
+            short: Some(Cow::Borrowed(short)),
+            long: Some(Cow::Borrowed(long)),
+        }),
+        shift: None,
+        alt: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::query::{Key, TransactWrite};
+    use dynamate::core::value::{Item, Value};
+
+    use super::StagedWrite;
+    use super::commit_summary;
+
+    fn delete(table: &str, pk: &str) -> StagedWrite {
+        StagedWrite {
+            write: TransactWrite::Delete {
+                collection: table.to_string(),
+                key: Key(Item::from([("pk".to_string(), Value::from(pk))])),
+            },
+            key: format!("pk={pk}"),
+        }
+    }
+
+    #[test]
+    fn summary_counts_writes_and_lists_tables_once() {
+        let writes = vec![
+            delete("orders", "a"),
+            delete("users", "b"),
+            StagedWrite {
+                write: TransactWrite::Put {
+                    collection: "orders".to_string(),
+                    item: Item::new(),
+                },
+                key: "pk=c".to_string(),
+            },
+        ];
+        assert_eq!(
+            commit_summary(&writes),
+            "1 puts, 0 updates, 2 deletes\nTables: orders, users\nAll are applied, or none."
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Items removed by single-item deletes in this session, so a fat-fingered
+//! delete can be put back. `u` in a table view (or the `undo` action on the
+//! delete toast) restores the most recent one with a put. Only the last
+//! [`MAX_DELETED`] are kept, and a profile switch drops them all.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use dynamate::core::datastore::Datastore;
+use dynamate::core::value::Item;
+
+use crate::env::{Toast, ToastKind};
+
+pub const MAX_DELETED: usize = 20;
+
+static DELETED: Mutex<VecDeque<DeletedItem>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug, Clone)]
+pub struct DeletedItem {
+    pub table: String,
+    pub item: Item,
+}
+
+/// Broadcast once a restore finishes, so views of the table can reload.
+pub struct ItemRestoredEvent {
+    pub table: String,
+    pub result: Result<(), String>,
+}
+
+impl ItemRestoredEvent {
+    pub fn toast(&self) -> Toast {
+        match &self.result {
+            Ok(()) => Toast {
+                message: format!("Restored the deleted item in {}", self.table),
+                kind: ToastKind::Info,
+                duration: Duration::from_secs(3),
+                action: None,
+            },
+            Err(err) => Toast {
+                message: format!("Failed to restore the deleted item: {err}"),
+                kind: ToastKind::Error,
+                duration: Duration::from_secs(4),
+                action: None,
+            },
+        }
+    }
+}
+
+/// Remember a deleted item, dropping the oldest past [`MAX_DELETED`].
+pub fn record(table: &str, item: Item) {
+    let mut deleted = DELETED.lock().unwrap_or_else(PoisonError::into_inner);
+    deleted.push_back(DeletedItem {
+        table: table.to_string(),
+        item,
+    });
+    while deleted.len() > MAX_DELETED {
+        deleted.pop_front();
+    }
+}
+
+pub fn clear() {
+    DELETED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+fn pop() -> Option<DeletedItem> {
+    DELETED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .pop_back()
+}
+
+/// Put back the most recently deleted item, calling `done` when the write
+/// finishes. A failed restore keeps the item for another try. Returns `false`
+/// when there is nothing to restore.
+pub fn restore_last(
+    db: Arc<dyn Datastore>,
+    done: impl FnOnce(ItemRestoredEvent) + Send + 'static,
+) -> bool {
+    let Some(deleted) = pop() else {
+        return false;
+    };
+    tokio::spawn(async move {
+        let result = db
+            .put_item(&deleted.table, deleted.item.clone())
+            .await
+            .map_err(|err| err.to_string());
+        if result.is_err() {
+            DELETED
+                .lock()
+                .expect("deleted items lock")
+                .push_back(deleted.clone());
+        }
+        done(ItemRestoredEvent {
+            table: deleted.table,
+            result,
+        });
+    });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use dynamate::core::value::{Item, Value};
+
+    use super::{MAX_DELETED, pop, record};
+
+    #[test]
+    fn keeps_the_newest_deletes() {
+        for id in 0..=MAX_DELETED {
+            record(
+                "users",
+                Item::from([("id".to_string(), Value::from(id.to_string()))]),
+            );
+        }
+        let newest = pop().unwrap();
+        assert_eq!(
+            newest.item.get("id"),
+            Some(&Value::from(MAX_DELETED.to_string()))
+        );
+        let mut remaining = 0;
+        while let Some(deleted) = pop() {
+            assert_ne!(deleted.item.get("id"), Some(&Value::from("0")));
+            remaining += 1;
+        }
+        assert_eq!(remaining, MAX_DELETED - 1);
+    }
+}
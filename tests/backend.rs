@@ -14,9 +14,11 @@ use testcontainers::{
 };
 
 use dynamate::core::datastore::Datastore;
-use dynamate::core::query::{CreateCollectionSpec, IndexHint, Key, Page, PlanKind, QueryPlan};
+use dynamate::core::query::{
+    CapacitySpec, CreateCollectionSpec, IndexHint, ItemUpdate, Key, Page, PlanKind, QueryPlan,
+};
 use dynamate::core::schema::{
-    IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, Projection, ScalarType,
+    IndexKind, IndexSchema, KeyField, KeyRole, KeySchema, Projection, ScalarType, Throughput,
 };
 use dynamate::core::value::{Item, Number, Value};
 use dynamate::dynamodb::DynamoBackend;
@@ -186,6 +188,53 @@ async fn datastore_round_trips_through_neutral_values() {
         Some(&Value::StringSet(vec!["a".to_string(), "b".to_string()]))
     );
 
+    // The full primary key fetches the item with GetItem.
+    let mut plan = QueryPlan::new(
+        Some("PK = \"user#1\" AND SK = \"profile\"".to_string()),
+        None,
+    );
+    plan.consistent_read = true;
+    let lookup = backend.query("demo", &plan, Page::default()).await.unwrap();
+    assert_eq!(lookup.plan_kind, PlanKind::Lookup);
+    assert_eq!(lookup.items, vec![stored.clone()]);
+    assert!(lookup.next.is_none());
+    let missing = backend
+        .query(
+            "demo",
+            &QueryPlan::new(
+                Some("PK = \"user#9\" AND SK = \"profile\"".to_string()),
+                None,
+            ),
+            Page::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(missing.count, 0);
+
+    // A key list comes back in key order, without the keys that have no item.
+    let key = |pk: &str| {
+        Key(item(vec![
+            ("PK", Value::Str(pk.to_string())),
+            ("SK", Value::Str("profile".to_string())),
+        ]))
+    };
+    let fetched = backend
+        .batch_get(
+            "demo",
+            vec![key("user#2"), key("user#9"), key("user#1")],
+            false,
+        )
+        .await
+        .unwrap();
+    let fetched_pks: Vec<_> = fetched.iter().map(|item| item.get("PK").cloned()).collect();
+    assert_eq!(
+        fetched_pks,
+        vec![
+            Some(Value::Str("user#2".to_string())),
+            Some(Value::Str("user#1".to_string())),
+        ]
+    );
+
     // Query the GSI via an explicit index hint.
     let gsi_result = backend
         .query(
@@ -390,6 +439,572 @@ async fn pagination_sort_key_conditions_and_filters() {
     assert_eq!(scanned.plan_kind, PlanKind::Scan);
 }
 
+#[tokio::test]
+async fn read_options_project_and_reverse_results() {
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+
+    for (sk, amount) in [("A", 1), ("B", 2), ("C", 3)] {
+        backend
+            .put_item(
+                "demo",
+                item(vec![
+                    ("PK", Value::Str("u".to_string())),
+                    ("SK", Value::Str(sk.to_string())),
+                    ("amount", Value::Num(Number::from(amount))),
+                    ("note", Value::Str("unused".to_string())),
+                ]),
+            )
+            .await
+            .unwrap();
+    }
+
+    let mut plan = QueryPlan::new(Some("PK = \"u\"".to_string()), None);
+    plan.consistent_read = true;
+    plan.descending = true;
+    plan.projection = vec!["PK".to_string(), "SK".to_string(), "amount".to_string()];
+    let items = query_all(&backend, "demo", &plan).await;
+    let sort_keys: Vec<_> = items.iter().map(|item| item["SK"].clone()).collect();
+    assert_eq!(
+        sort_keys,
+        vec![
+            Value::Str("C".to_string()),
+            Value::Str("B".to_string()),
+            Value::Str("A".to_string()),
+        ]
+    );
+    assert!(items.iter().all(|item| !item.contains_key("note")));
+
+    // An ORDER clause in the query text wins over the plan's order.
+    let mut plan = QueryPlan::new(Some("PK = \"u\" ORDER ASC".to_string()), None);
+    plan.descending = true;
+    let items = query_all(&backend, "demo", &plan).await;
+    assert_eq!(items[0]["SK"], Value::Str("A".to_string()));
+
+    // A projected scan ignores the order but still trims attributes.
+    let scan = QueryPlan {
+        projection: vec!["SK".to_string()],
+        ..QueryPlan::default()
+    };
+    let scanned = query_all(&backend, "demo", &scan).await;
+    assert_eq!(scanned.len(), 3);
+    assert!(scanned.iter().all(|item| item.len() == 1));
+}
+
+#[tokio::test]
+async fn named_index_scans_read_only_indexed_items() {
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+
+    for n in 0..6 {
+        let mut entries = vec![
+            ("PK", Value::Str(format!("p{n}"))),
+            ("SK", Value::Str("x".to_string())),
+            ("amount", Value::Num(Number::from(n))),
+        ];
+        // Only even items land in the sparse GSI.
+        if n % 2 == 0 {
+            entries.push(("GSI1PK", Value::Str("even".to_string())));
+        }
+        backend.put_item("demo", item(entries)).await.unwrap();
+    }
+
+    let index = Some(IndexHint::Named("GSI1".to_string()));
+    let scan = QueryPlan::new(None, index.clone());
+    assert_eq!(query_all(&backend, "demo", &scan).await.len(), 3);
+
+    // A filter that isn't a key condition on the index scans the index.
+    let filtered = QueryPlan::new(Some("amount >= 2".to_string()), index.clone());
+    let items = query_all(&backend, "demo", &filtered).await;
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| item.contains_key("GSI1PK")));
+
+    let segment = backend
+        .scan_segment("demo", &scan, 0, 1, Page::default())
+        .await
+        .unwrap();
+    assert_eq!(segment.items.len(), 3);
+}
+
+#[tokio::test]
+async fn parallel_scan_segments_cover_the_table_once() {
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+
+    for n in 0..12 {
+        backend
+            .put_item(
+                "demo",
+                item(vec![
+                    ("PK", Value::Str(format!("p{n}"))),
+                    ("SK", Value::Str("x".to_string())),
+                    ("amount", Value::Num(Number::from(n))),
+                ]),
+            )
+            .await
+            .unwrap();
+    }
+
+    let plan = QueryPlan::new(Some("amount >= 6".to_string()), None);
+    let mut seen = Vec::new();
+    for segment in 0..3 {
+        let mut cursor = None;
+        loop {
+            let page = backend
+                .scan_segment(
+                    "demo",
+                    &plan,
+                    segment,
+                    3,
+                    Page {
+                        cursor,
+                        limit: Some(2),
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(page.plan_kind, PlanKind::Scan);
+            seen.extend(page.items);
+            cursor = page.next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+    }
+    let mut keys: Vec<String> = seen
+        .iter()
+        .filter_map(|item| item.get("PK").and_then(Value::as_str).map(str::to_string))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    assert_eq!(seen.len(), 6);
+    assert_eq!(keys.len(), 6);
+}
+
+#[tokio::test]
+async fn copy_creates_destination_and_resumes_from_a_checkpoint() {
+    use std::sync::atomic::AtomicBool;
+
+    use dynamate::core::copy::{CopyCheckpoint, copy_items, copy_spec, wait_until_active};
+
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+    for n in 0..10 {
+        backend
+            .put_item(
+                "demo",
+                item(vec![
+                    ("PK", Value::Str(format!("p{n}"))),
+                    ("SK", Value::Str("x".to_string())),
+                ]),
+            )
+            .await
+            .unwrap();
+    }
+
+    let schema = backend.describe_collection("demo").await.unwrap();
+    create_with_retry(&backend, &copy_spec(&schema, "demo-copy")).await;
+    wait_until_active(&backend, "demo-copy").await.unwrap();
+    let copied = backend.describe_collection("demo-copy").await.unwrap();
+    assert_eq!(copied.key, schema.key);
+    assert_eq!(copied.indexes.len(), 1);
+
+    // Segment 0 already finished; only segment 1 is copied.
+    let mut checkpoint = CopyCheckpoint::new("demo", "demo-copy", 2);
+    checkpoint.segments[0] = dynamate::core::copy::SegmentState::Done;
+    let done = copy_items(&backend, checkpoint, &AtomicBool::new(false), &|_| {})
+        .await
+        .unwrap();
+    assert!(done.is_complete());
+    let partial = query_all(&backend, "demo-copy", &QueryPlan::default()).await;
+    assert_eq!(partial.len() as u64, done.copied);
+
+    let done = copy_items(
+        &backend,
+        CopyCheckpoint::new("demo", "demo-copy", 2),
+        &AtomicBool::new(false),
+        &|_| {},
+    )
+    .await
+    .unwrap();
+    assert_eq!(done.copied, 10);
+    let all = query_all(&backend, "demo-copy", &QueryPlan::default()).await;
+    assert_eq!(all.len(), 10);
+}
+
+#[tokio::test]
+async fn purge_deletes_matching_items_across_segments() {
+    use std::sync::atomic::AtomicBool;
+
+    use dynamate::core::purge::{PurgeOptions, purge_items};
+
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+    for n in 0..40 {
+        backend
+            .put_item(
+                "demo",
+                item(vec![
+                    ("PK", Value::Str(format!("p{n}"))),
+                    ("SK", Value::Str("x".to_string())),
+                    ("amount", Value::Num(Number::from(n))),
+                ]),
+            )
+            .await
+            .unwrap();
+    }
+
+    let cancel = AtomicBool::new(false);
+    let plan = QueryPlan::new(Some("amount >= 30".to_string()), None);
+    let options = PurgeOptions {
+        segments: 3,
+        dry_run: true,
+    };
+    let summary = purge_items(&backend, "demo", &plan, options, &cancel, &|_| {})
+        .await
+        .unwrap();
+    assert_eq!(summary.progress.deleted, 10);
+    assert_eq!(
+        query_all(&backend, "demo", &QueryPlan::default())
+            .await
+            .len(),
+        40
+    );
+
+    let options = PurgeOptions {
+        dry_run: false,
+        ..options
+    };
+    let summary = purge_items(&backend, "demo", &plan, options, &cancel, &|_| {})
+        .await
+        .unwrap();
+    assert!(summary.errors.is_empty());
+    assert!(!summary.cancelled);
+    assert_eq!(summary.progress.deleted, 10);
+    assert_eq!(summary.progress.scanned, 40);
+    assert_eq!(
+        query_all(&backend, "demo", &QueryPlan::default())
+            .await
+            .len(),
+        30
+    );
+
+    let options = PurgeOptions {
+        segments: 1,
+        dry_run: false,
+    };
+    let summary = purge_items(
+        &backend,
+        "demo",
+        &QueryPlan::default(),
+        options,
+        &cancel,
+        &|_| {},
+    )
+    .await
+    .unwrap();
+    assert_eq!(summary.progress.deleted, 30);
+    assert!(
+        query_all(&backend, "demo", &QueryPlan::default())
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn bulk_update_applies_to_matching_items_only() {
+    use std::sync::atomic::AtomicBool;
+
+    use dynamate::core::bulk_update::{preview_update, update_items};
+
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+    for n in 0..20 {
+        backend
+            .put_item(
+                "demo",
+                item(vec![
+                    ("PK", Value::Str(format!("p{n}"))),
+                    ("SK", Value::Str("x".to_string())),
+                    ("amount", Value::Num(Number::from(n))),
+                    ("lock", Value::Bool(true)),
+                ]),
+            )
+            .await
+            .unwrap();
+    }
+
+    let update = backend
+        .query_language()
+        .parse_update("SET status = \"done\" REMOVE lock")
+        .unwrap();
+    let plan = QueryPlan::new(Some("amount >= 15".to_string()), None);
+    let preview = preview_update(&backend, "demo", &plan, &update, 3)
+        .await
+        .unwrap();
+    assert_eq!(preview.len(), 3);
+    for (before, after) in &preview {
+        assert_eq!(before.get("lock"), Some(&Value::Bool(true)));
+        assert_eq!(after.get("status"), Some(&Value::from("done")));
+        assert!(!after.contains_key("lock"));
+    }
+    let done = QueryPlan::new(Some("status = \"done\"".to_string()), None);
+    assert!(query_all(&backend, "demo", &done).await.is_empty());
+
+    let cancel = AtomicBool::new(false);
+    let summary = update_items(&backend, "demo", &plan, &update, 4, &cancel, &|_| {})
+        .await
+        .unwrap();
+    assert!(summary.errors.is_empty());
+    assert_eq!(summary.progress.matched, 5);
+    assert_eq!(summary.progress.updated, 5);
+    assert_eq!(summary.progress.failed, 0);
+    let updated = query_all(&backend, "demo", &done).await;
+    assert_eq!(updated.len(), 5);
+    assert!(updated.iter().all(|item| !item.contains_key("lock")));
+
+    let mut key_change = update.clone();
+    key_change
+        .set
+        .insert("SK".to_string(), Value::Str("y".to_string()));
+    assert!(
+        update_items(&backend, "demo", &plan, &key_change, 4, &cancel, &|_| {})
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn transactions_apply_every_write_or_none() {
+    use dynamate::core::query::{TransactOutcome, TransactWrite};
+
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+    let key = |pk: &str| {
+        Key(item(vec![
+            ("PK", Value::Str(pk.to_string())),
+            ("SK", Value::Str("x".to_string())),
+        ]))
+    };
+    backend.put_item("demo", key("old").0).await.unwrap();
+
+    let update = ItemUpdate {
+        set: item(vec![("status", Value::from("done"))]),
+        remove: Vec::new(),
+        expression: None,
+    };
+    let writes = vec![
+        TransactWrite::Put {
+            collection: "demo".to_string(),
+            item: key("new").0,
+        },
+        TransactWrite::Update {
+            collection: "demo".to_string(),
+            key: key("old"),
+            update: update.clone(),
+        },
+    ];
+    let outcome = backend.transact_write(&writes).await.unwrap();
+    assert_eq!(outcome, TransactOutcome::Committed);
+    let all = QueryPlan::new(None, None);
+    assert_eq!(query_all(&backend, "demo", &all).await.len(), 2);
+
+    // Updating a missing item fails its condition, so the delete beside it
+    // is rolled back too.
+    let writes = vec![
+        TransactWrite::Delete {
+            collection: "demo".to_string(),
+            key: key("new"),
+        },
+        TransactWrite::Update {
+            collection: "demo".to_string(),
+            key: key("missing"),
+            update,
+        },
+    ];
+    let TransactOutcome::Canceled { reasons } = backend.transact_write(&writes).await.unwrap()
+    else {
+        panic!("expected the transaction to be canceled");
+    };
+    assert_eq!(reasons.len(), 2);
+    assert!(reasons[0].is_none());
+    assert!(
+        reasons[1]
+            .as_deref()
+            .is_some_and(|reason| reason.starts_with("ConditionalCheckFailed"))
+    );
+    assert_eq!(query_all(&backend, "demo", &all).await.len(), 2);
+}
+
+#[tokio::test]
+async fn seed_writes_generated_items_with_valid_keys() {
+    use std::sync::atomic::AtomicBool;
+
+    use dynamate::core::seed::{SeedOptions, SeedSpec, parse_templates, seed_items};
+
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+
+    let schema = backend.describe_collection("demo").await.unwrap();
+    let templates = parse_templates("PK=user#{seq}, score={int:1..10}").unwrap();
+    let spec = SeedSpec::new(&schema.key, templates).unwrap();
+    let options = SeedOptions {
+        count: 60,
+        concurrency: 3,
+        seed: Some(42),
+    };
+    let summary = seed_items(
+        &backend,
+        "demo",
+        &spec,
+        options,
+        &AtomicBool::new(false),
+        &|_| {},
+    )
+    .await
+    .unwrap();
+    assert!(summary.errors.is_empty());
+    assert_eq!(summary.progress.written, 60);
+
+    let items = query_all(&backend, "demo", &QueryPlan::default()).await;
+    assert_eq!(items.len(), 60);
+    assert!(items.iter().all(|item| {
+        matches!(item.get("PK"), Some(Value::Str(pk)) if pk.starts_with("user#"))
+            && matches!(item.get("score"), Some(Value::Num(_)))
+    }));
+}
+
+#[tokio::test]
+async fn create_and_drop_gsi_on_existing_table() {
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+
+    let index = IndexSchema {
+        name: "byStatus".to_string(),
+        kind: IndexKind::GlobalSecondary,
+        key: KeySchema {
+            fields: vec![KeyField {
+                name: "status".to_string(),
+                role: KeyRole::Partition,
+                ty: ScalarType::String,
+            }],
+        },
+        projection: Projection::KeysOnly,
+    };
+    backend.create_index("demo", &index).await.unwrap();
+    let details = backend.describe_details("demo").await.unwrap();
+    let names: Vec<&str> = details
+        .indexes
+        .iter()
+        .map(|index| index.schema.name.as_str())
+        .collect();
+    assert!(names.contains(&"byStatus"), "indexes: {names:?}");
+
+    let lsi = IndexSchema {
+        kind: IndexKind::LocalSecondary,
+        ..index.clone()
+    };
+    assert!(backend.create_index("demo", &lsi).await.is_err());
+
+    backend.drop_index("demo", "byStatus").await.unwrap();
+    let read_only = new_backend(&env.endpoint_url, true).await;
+    assert!(read_only.drop_index("demo", "GSI1").await.is_err());
+}
+
+#[tokio::test]
+async fn switch_billing_mode_and_capacity() {
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+
+    let spec = CapacitySpec::Provisioned {
+        table: Throughput { read: 7, write: 3 },
+        indexes: vec![("GSI1".to_string(), Throughput { read: 2, write: 2 })],
+    };
+    backend.update_capacity("demo", &spec).await.unwrap();
+    let details = backend.describe_details("demo").await.unwrap();
+    assert_eq!(details.billing_mode.as_deref(), Some("PROVISIONED"));
+    assert_eq!(details.throughput, Some(Throughput { read: 7, write: 3 }));
+
+    // Re-applying the same capacity is a no-op rather than an error.
+    backend.update_capacity("demo", &spec).await.unwrap();
+}
+
+#[tokio::test]
+async fn update_item_keeps_untouched_attributes() {
+    let env = new_dynamodb_env().await.unwrap();
+    let backend = new_backend(&env.endpoint_url, false).await;
+    create_with_retry(&backend, &demo_spec()).await;
+    wait_until_listed(&backend, "demo").await;
+
+    let read = item(vec![
+        ("PK", Value::Str("user#1".to_string())),
+        ("SK", Value::Str("profile".to_string())),
+        ("name", Value::Str("Ada".to_string())),
+        ("nickname", Value::Str("ada".to_string())),
+    ]);
+    backend.put_item("demo", read.clone()).await.unwrap();
+    // Written after `read` was loaded; the edit below must not drop it.
+    let mut concurrent = read.clone();
+    concurrent.insert("age".to_string(), Value::Num(Number::new("36")));
+    backend.put_item("demo", concurrent).await.unwrap();
+
+    let mut edited = read.clone();
+    edited.insert("name".to_string(), Value::Str("Ada L.".to_string()));
+    edited.shift_remove("nickname");
+    let key = demo_spec().key;
+    let update = ItemUpdate::between(&read, &edited, &key).unwrap();
+    let item_key = Key(item(vec![
+        ("PK", Value::Str("user#1".to_string())),
+        ("SK", Value::Str("profile".to_string())),
+    ]));
+    backend
+        .update_item("demo", item_key.clone(), &update)
+        .await
+        .unwrap();
+
+    let result = backend
+        .query(
+            "demo",
+            &QueryPlan::new(Some("PK = \"user#1\"".to_string()), None),
+            Page::default(),
+        )
+        .await
+        .unwrap();
+    let stored = &result.items[0];
+    assert_eq!(stored.get("name"), Some(&Value::Str("Ada L.".to_string())));
+    assert_eq!(stored.get("nickname"), None);
+    assert_eq!(stored.get("age"), Some(&Value::Num(Number::new("36"))));
+
+    // Updating an item that no longer exists fails instead of recreating it.
+    backend.delete_item("demo", item_key.clone()).await.unwrap();
+    assert!(
+        backend
+            .update_item("demo", item_key, &update)
+            .await
+            .is_err()
+    );
+}
+
 fn lsi_spec() -> CreateCollectionSpec {
     CreateCollectionSpec {
         name: "lsi_demo".to_string(),